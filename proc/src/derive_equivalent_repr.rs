@@ -0,0 +1,45 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+
+use crate::bound;
+use crate::internals::ctxt;
+
+const EQUIVALENT_REPR: &str = "equivalent_repr";
+
+pub fn impl_derive(input: syn::DeriveInput) -> Result<TokenStream, Vec<syn::Error>> {
+    let cx = ctxt::Ctxt::new();
+
+    // Parse `#[equivalent_repr(Foo, Bar<'_>)]`
+    let mut types = Vec::new();
+    for attr in &input.attrs {
+        if !attr.path().is_ident(EQUIVALENT_REPR) {
+            continue;
+        }
+        match attr.parse_args_with(Punctuated::<syn::Type, syn::Token![,]>::parse_terminated) {
+            Ok(items) => types.extend(items),
+            Err(e) => cx.syn_error(e),
+        }
+    }
+    if types.is_empty() {
+        cx.error_spanned_by(
+            &input.ident,
+            "expected at least one type in `#[equivalent_repr(...)]`",
+        );
+    }
+    cx.check()?;
+
+    let ident = &input.ident;
+    let generics = bound::without_default(&input.generics);
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let impls = types.iter().map(|ty| {
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::everscale_types::cell::EquivalentRepr<#ty>
+                for #ident #ty_generics #where_clause {}
+        }
+    });
+
+    Ok(quote!(#(#impls)*))
+}
@@ -2,6 +2,7 @@ use proc_macro::TokenStream;
 use quote::quote;
 
 mod bound;
+mod derive_equivalent_repr;
 mod derive_load;
 mod derive_store;
 mod internals;
@@ -29,6 +30,16 @@ pub fn derive_store(input: TokenStream) -> TokenStream {
         .into()
 }
 
+/// Implements `EquivalentRepr` for the type and all types
+/// from the `#[equivalent_repr(...)]` attribute.
+#[proc_macro_derive(EquivalentRepr, attributes(equivalent_repr))]
+pub fn derive_equivalent_repr(input: TokenStream) -> TokenStream {
+    let input = syn::parse_macro_input!(input as syn::DeriveInput);
+    derive_equivalent_repr::impl_derive(input)
+        .unwrap_or_else(to_compile_errors)
+        .into()
+}
+
 fn to_compile_errors(errors: Vec<syn::Error>) -> proc_macro2::TokenStream {
     let compile_errors = errors.iter().map(syn::Error::to_compile_error);
     quote!(#(#compile_errors)*)
@@ -15,13 +15,13 @@ use crate::cell::{
 };
 use crate::dict::RawDict;
 use crate::models::{
-    ExtInMsgInfo, IntAddr, MsgInfo, OwnedMessage, OwnedRelaxedMessage, RelaxedIntMsgInfo,
-    RelaxedMsgInfo, StateInit, StdAddr,
+    Account, AccountState, CurrencyCollection, ExtInMsgInfo, IntAddr, IntMsgInfo, MsgInfo,
+    OwnedMessage, OwnedRelaxedMessage, RelaxedIntMsgInfo, RelaxedMsgInfo, StateInit, StdAddr,
 };
 use crate::num::Tokens;
 use crate::prelude::Dict;
 
-use super::error::AbiError;
+use super::error::{AbiArgError, AbiError};
 use super::{AbiHeaderType, AbiType, AbiValue, AbiVersion, NamedAbiType, NamedAbiValue};
 
 /// Contract ABI definition.
@@ -222,6 +222,115 @@ impl Contract {
         ok!(AbiValue::check_remaining(slice, allow_partial));
         Ok(res)
     }
+
+    /// Tries to parse storage fields of this contract from an account state.
+    ///
+    /// Fails if the account is not active or has no data.
+    pub fn decode_account_fields(&self, account: &Account) -> Result<Vec<NamedAbiValue>> {
+        let data = match &account.state {
+            AccountState::Active(StateInit {
+                data: Some(data), ..
+            }) => data,
+            _ => anyhow::bail!(AbiError::AccountDataNotFound),
+        };
+        self.decode_fields(data.as_slice()?)
+    }
+
+    /// Returns whether this contract has all functions and events of the interface.
+    pub fn implements(&self, interface: &Interface) -> bool {
+        let has_functions = interface.functions.values().all(|function| {
+            matches!(
+                self.functions.get(&function.name),
+                Some(item) if item.input_id == function.input_id
+                    && item.output_id == function.output_id
+            )
+        });
+        let has_events = interface
+            .events
+            .values()
+            .all(|event| matches!(self.events.get(&event.name), Some(item) if item.id == event.id));
+        has_functions && has_events
+    }
+
+    /// Adds all functions and events of the interface to this contract.
+    ///
+    /// Fails if the interface has a different ABI version or headers,
+    /// or if any of its items conflicts with the existing ones.
+    /// The contract is left untouched on error.
+    pub fn add_interface(&mut self, interface: &Interface) -> Result<()> {
+        ok!(check_compatibility(
+            (self.abi_version, &self.headers),
+            (interface.abi_version, &interface.headers)
+        ));
+
+        let mut functions = self.functions.clone();
+        for function in interface.functions.values() {
+            ok!(insert_function(&mut functions, function.clone()));
+        }
+
+        let mut events = self.events.clone();
+        for event in interface.events.values() {
+            ok!(insert_event(&mut events, event.clone()));
+        }
+
+        self.functions = functions;
+        self.events = events;
+        Ok(())
+    }
+
+    /// Merges another contract ABI into this one.
+    ///
+    /// Functions, events and init data are combined. Storage fields are
+    /// taken from the other contract only if this contract has none.
+    /// Identical items are deduplicated, all other conflicts result in an error.
+    /// The contract is left untouched on error.
+    pub fn merge(&mut self, other: Contract) -> Result<()> {
+        ok!(check_compatibility(
+            (self.abi_version, &self.headers),
+            (other.abi_version, &other.headers)
+        ));
+
+        let mut functions = self.functions.clone();
+        for function in other.functions.into_values() {
+            ok!(insert_function(&mut functions, function));
+        }
+
+        let mut events = self.events.clone();
+        for event in other.events.into_values() {
+            ok!(insert_event(&mut events, event));
+        }
+
+        let mut init_data = self.init_data.clone();
+        for (name, (key, ty)) in other.init_data {
+            let is_duplicate = init_data.iter().any(|(existing_name, (existing_key, _))| {
+                existing_name != &name && *existing_key == key
+            });
+            match init_data.get(&name) {
+                _ if is_duplicate => anyhow::bail!(AbiError::ConflictingDefinition(name)),
+                Some(existing) if existing.0 != key || existing.1 != ty => {
+                    anyhow::bail!(AbiError::ConflictingDefinition(name))
+                }
+                Some(_) => {}
+                None => {
+                    init_data.insert(name, (key, ty));
+                }
+            }
+        }
+
+        let fields = if self.fields.is_empty() {
+            other.fields
+        } else if other.fields.is_empty() || self.fields == other.fields {
+            self.fields.clone()
+        } else {
+            anyhow::bail!(AbiError::ConflictingDefinition(Arc::from("fields")));
+        };
+
+        self.functions = functions;
+        self.events = events;
+        self.init_data = init_data;
+        self.fields = fields;
+        Ok(())
+    }
 }
 
 impl<'de> Deserialize<'de> for Contract {
@@ -382,6 +491,179 @@ impl<'de> Deserialize<'de> for Contract {
     }
 }
 
+/// A subset of contract functions and events.
+///
+/// Can be used to compose a contract ABI from several interfaces
+/// (e.g. TIP-6) or to check whether a contract implements it.
+#[derive(Debug, Clone)]
+pub struct Interface {
+    /// ABI version.
+    pub abi_version: AbiVersion,
+
+    /// List of headers for external messages.
+    ///
+    /// NOTE: header order matters.
+    pub headers: Arc<[AbiHeaderType]>,
+
+    /// A mapping with all interface methods by name.
+    pub functions: HashMap<Arc<str>, Function>,
+
+    /// A mapping with all interface events by name.
+    pub events: HashMap<Arc<str>, Event>,
+}
+
+impl Interface {
+    /// Creates an empty interface.
+    pub fn new<I: IntoIterator<Item = AbiHeaderType>>(abi_version: AbiVersion, headers: I) -> Self {
+        Self {
+            abi_version,
+            headers: headers.into_iter().collect(),
+            functions: HashMap::default(),
+            events: HashMap::default(),
+        }
+    }
+
+    /// Creates an interface from the specified subset of contract functions and events.
+    pub fn from_contract(contract: &Contract, functions: &[&str], events: &[&str]) -> Result<Self> {
+        let mut result = Self::new(contract.abi_version, contract.headers.iter().copied());
+
+        for name in functions {
+            let Some(function) = contract.functions.get(*name) else {
+                anyhow::bail!(AbiError::UnknownItem(Arc::from(*name)));
+            };
+            ok!(result.add_function(function.clone()));
+        }
+
+        for name in events {
+            let Some(event) = contract.events.get(*name) else {
+                anyhow::bail!(AbiError::UnknownItem(Arc::from(*name)));
+            };
+            ok!(result.add_event(event.clone()));
+        }
+
+        Ok(result)
+    }
+
+    /// Adds a function to the interface.
+    ///
+    /// Fails if the function has a different ABI version or headers,
+    /// or if it conflicts with the existing functions by name or id.
+    pub fn add_function(&mut self, function: Function) -> Result<()> {
+        ok!(check_compatibility(
+            (self.abi_version, &self.headers),
+            (function.abi_version, &function.headers)
+        ));
+        insert_function(&mut self.functions, function)
+    }
+
+    /// Adds an event to the interface.
+    ///
+    /// Fails if the event has a different ABI version,
+    /// or if it conflicts with the existing events by name or id.
+    pub fn add_event(&mut self, event: Event) -> Result<()> {
+        anyhow::ensure!(
+            self.abi_version == event.abi_version,
+            AbiError::AbiVersionMismatch {
+                expected: self.abi_version,
+                version: event.abi_version,
+            }
+        );
+        insert_event(&mut self.events, event)
+    }
+
+    /// Computes an interface id as a XOR of all function input ids.
+    pub fn compute_id(&self) -> u32 {
+        self.functions
+            .values()
+            .fold(0, |id, function| id ^ function.input_id)
+    }
+}
+
+impl From<Contract> for Interface {
+    fn from(contract: Contract) -> Self {
+        Self {
+            abi_version: contract.abi_version,
+            headers: contract.headers,
+            functions: contract.functions,
+            events: contract.events,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Interface {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        Contract::deserialize(deserializer).map(Self::from)
+    }
+}
+
+fn check_compatibility(
+    (abi_version, headers): (AbiVersion, &[AbiHeaderType]),
+    (other_abi_version, other_headers): (AbiVersion, &[AbiHeaderType]),
+) -> Result<()> {
+    anyhow::ensure!(
+        abi_version == other_abi_version,
+        AbiError::AbiVersionMismatch {
+            expected: abi_version,
+            version: other_abi_version,
+        }
+    );
+    anyhow::ensure!(headers == other_headers, AbiError::HeadersMismatch);
+    Ok(())
+}
+
+fn insert_function(functions: &mut HashMap<Arc<str>, Function>, function: Function) -> Result<()> {
+    if let Some(existing) = functions.get(&function.name) {
+        anyhow::ensure!(
+            existing.input_id == function.input_id && existing.output_id == function.output_id,
+            AbiError::ConflictingDefinition(function.name.clone())
+        );
+        return Ok(());
+    }
+
+    for existing in functions.values() {
+        let id = if existing.input_id == function.input_id {
+            function.input_id
+        } else if existing.output_id == function.output_id {
+            function.output_id
+        } else {
+            continue;
+        };
+
+        anyhow::bail!(AbiError::IdCollision {
+            id,
+            name: function.name.clone(),
+            other: existing.name.clone(),
+        });
+    }
+
+    functions.insert(function.name.clone(), function);
+    Ok(())
+}
+
+fn insert_event(events: &mut HashMap<Arc<str>, Event>, event: Event) -> Result<()> {
+    if let Some(existing) = events.get(&event.name) {
+        anyhow::ensure!(
+            existing.id == event.id,
+            AbiError::ConflictingDefinition(event.name.clone())
+        );
+        return Ok(());
+    }
+
+    if let Some(existing) = events.values().find(|item| item.id == event.id) {
+        anyhow::bail!(AbiError::IdCollision {
+            id: event.id,
+            name: event.name.clone(),
+            other: existing.name.clone(),
+        });
+    }
+
+    events.insert(event.name.clone(), event);
+    Ok(())
+}
+
 /// Contract method ABI definition.
 #[derive(Debug, Clone)]
 pub struct Function {
@@ -522,12 +804,78 @@ impl Function {
         Ok(res)
     }
 
+    /// Ensures that the arguments match the method inputs by names, order and types.
+    ///
+    /// Unlike the type check performed during encoding, reports
+    /// the exact argument which is missing, unexpected or has a wrong type.
+    pub fn validate_args(&self, args: &[NamedAbiValue]) -> Result<(), AbiArgError> {
+        if let Some(arg) = args
+            .iter()
+            .find(|arg| !self.inputs.iter().any(|input| input.name == arg.name))
+        {
+            return Err(AbiArgError::Unexpected {
+                name: arg.name.clone(),
+            });
+        }
+
+        for (index, input) in self.inputs.iter().enumerate() {
+            match args.get(index) {
+                Some(arg) if arg.name == input.name => {
+                    if !arg.value.has_type(&input.ty) {
+                        return Err(AbiArgError::TypeMismatch {
+                            name: arg.name.clone(),
+                            expected: input.ty.to_string().into(),
+                            ty: arg.value.display_type().to_string().into(),
+                        });
+                    }
+                }
+                _ => {
+                    return Err(match args.iter().position(|arg| arg.name == input.name) {
+                        Some(position) => AbiArgError::Misplaced {
+                            name: input.name.clone(),
+                            expected: index,
+                            position,
+                        },
+                        None => AbiArgError::Missing {
+                            name: input.name.clone(),
+                            expected: input.ty.to_string().into(),
+                        },
+                    })
+                }
+            }
+        }
+
+        if let Some(arg) = args.get(self.inputs.len()) {
+            return Err(AbiArgError::Unexpected {
+                name: arg.name.clone(),
+            });
+        }
+
+        Ok(())
+    }
+
     /// Encodes a message body with invocation of this method as an internal message.
     pub fn encode_internal_input(&self, tokens: &[NamedAbiValue]) -> Result<CellBuilder> {
         ok!(NamedAbiValue::check_types(tokens, &self.inputs));
         Self::encode_internal_msg_body(self.abi_version, self.input_id, tokens)
     }
 
+    /// Returns a builder for an internal message with invocation of this method.
+    pub fn encode_internal<'f, 'a: 'f>(
+        &'f self,
+        tokens: &'a [NamedAbiValue],
+        dst: &'a IntAddr,
+    ) -> InternalInput<'f, 'a> {
+        InternalInput {
+            function: self,
+            tokens,
+            dst,
+            value: CurrencyCollection::ZERO,
+            bounce: false,
+            state_init: None,
+        }
+    }
+
     /// Encodes an internal message with invocation of this method.
     pub fn encode_internal_message(
         &self,
@@ -840,6 +1188,99 @@ impl<'f, 'a> ExternalInput<'f, 'a> {
     }
 }
 
+/// Internal input builder.
+#[derive(Clone)]
+pub struct InternalInput<'f, 'a> {
+    function: &'f Function,
+    tokens: &'a [NamedAbiValue],
+    dst: &'a IntAddr,
+    value: CurrencyCollection,
+    bounce: bool,
+    state_init: Option<&'a StateInit>,
+}
+
+impl<'f, 'a> InternalInput<'f, 'a> {
+    /// Builds an internal message from the specified address.
+    pub fn build_message(&self, src: &IntAddr) -> Result<OwnedMessage> {
+        let body = ok!(self.build_input());
+        let range = CellSliceRange::full(body.as_ref());
+        Ok(OwnedMessage {
+            info: MsgInfo::Int(IntMsgInfo {
+                src: src.clone(),
+                dst: self.dst.clone(),
+                value: self.value.clone(),
+                bounce: self.bounce,
+                ..Default::default()
+            }),
+            body: (body, range),
+            init: self.state_init.cloned(),
+            layout: None,
+        })
+    }
+
+    /// Builds an internal message without source address (e.g. for wallet actions).
+    pub fn build_relaxed_message(&self) -> Result<OwnedRelaxedMessage> {
+        let body = ok!(self.build_input());
+        let range = CellSliceRange::full(body.as_ref());
+        Ok(OwnedRelaxedMessage {
+            info: RelaxedMsgInfo::Int(RelaxedIntMsgInfo {
+                dst: self.dst.clone(),
+                value: self.value.clone(),
+                bounce: self.bounce,
+                ..Default::default()
+            }),
+            body: (body, range),
+            init: self.state_init.cloned(),
+            layout: None,
+        })
+    }
+
+    /// Builds an internal message body.
+    pub fn build_input(&self) -> Result<Cell> {
+        let body = ok!(self.function.encode_internal_input(self.tokens));
+        body.build().map_err(From::from)
+    }
+
+    /// Attach the specified amount to the message.
+    #[inline]
+    pub fn set_value<T: Into<CurrencyCollection>>(&mut self, value: T) {
+        self.value = value.into();
+    }
+
+    /// Attach the specified amount to the message.
+    #[inline]
+    pub fn with_value<T: Into<CurrencyCollection>>(mut self, value: T) -> Self {
+        self.set_value(value);
+        self
+    }
+
+    /// Whether to bounce the message back if the destination transaction fails.
+    #[inline]
+    pub fn set_bounce(&mut self, bounce: bool) {
+        self.bounce = bounce;
+    }
+
+    /// Whether to bounce the message back if the destination transaction fails.
+    #[inline]
+    pub fn with_bounce(mut self, bounce: bool) -> Self {
+        self.set_bounce(bounce);
+        self
+    }
+
+    /// Attach the specified state init to the message.
+    #[inline]
+    pub fn set_state_init(&mut self, state_init: &'a StateInit) {
+        self.state_init = Some(state_init);
+    }
+
+    /// Attach the specified state init to the message.
+    #[inline]
+    pub fn with_state_init(mut self, state_init: &'a StateInit) -> Self {
+        self.set_state_init(state_init);
+        self
+    }
+}
+
 /// Method ABI declaration builder.
 #[derive(Debug, Clone)]
 pub struct FunctionBuilder {
@@ -1236,7 +1677,7 @@ impl UnsignedBody {
             match signature {
                 Some(signature) => {
                     builder.store_bit_one()?;
-                    builder.store_raw(signature, 512)?;
+                    builder.store_array(signature)?;
                 }
                 None => builder.store_bit_zero()?,
             }
@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+use num_bigint::BigUint;
+
+use crate::abi::error::{AbiError, ParseDecimalError};
+use crate::abi::{AbiType, AbiValue};
+use crate::num::Tokens;
+
+/// Unsigned fixed-point number with the specified number of fractional digits.
+///
+/// Stores an integer amount of minimal units (e.g. `1.5` with 9 decimals is `1500000000`).
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Decimal {
+    value: BigUint,
+    decimals: u8,
+}
+
+impl Decimal {
+    /// Creates a decimal from an integer amount of minimal units.
+    pub const fn new(value: BigUint, decimals: u8) -> Self {
+        Self { value, decimals }
+    }
+
+    /// Parses a human-readable decimal string (e.g. `123.45`).
+    ///
+    /// Fails if the string has more fractional digits than `decimals`.
+    pub fn from_str_with_decimals(s: &str, decimals: u8) -> Result<Self, ParseDecimalError> {
+        let (int, frac) = match s.split_once('.') {
+            Some((int, frac)) if !frac.is_empty() => (int, frac),
+            Some(_) => return Err(ParseDecimalError::InvalidFormat),
+            None => (s, ""),
+        };
+
+        let is_digits = |s: &str| s.bytes().all(|b| b.is_ascii_digit());
+        if int.is_empty() || !is_digits(int) || !is_digits(frac) {
+            return Err(ParseDecimalError::InvalidFormat);
+        }
+        if frac.len() > decimals as usize {
+            return Err(ParseDecimalError::TooManyDecimals(decimals));
+        }
+
+        let mut digits = String::with_capacity(int.len() + decimals as usize);
+        digits.push_str(int);
+        digits.push_str(frac);
+        digits.extend(std::iter::repeat('0').take(decimals as usize - frac.len()));
+
+        match BigUint::from_str(&digits) {
+            Ok(value) => Ok(Self { value, decimals }),
+            Err(_) => Err(ParseDecimalError::InvalidFormat),
+        }
+    }
+
+    /// Creates a decimal from an ABI integer value (`uintN`, `varuintN` or `gram`).
+    pub fn from_abi(value: &AbiValue, decimals: u8) -> Result<Self> {
+        let value = match value {
+            AbiValue::Uint(_, value) | AbiValue::VarUint(_, value) => value.clone(),
+            AbiValue::Token(tokens) => BigUint::from(tokens.into_inner()),
+            _ => anyhow::bail!(AbiError::TypeMismatch {
+                expected: "uint".into(),
+                ty: value.display_type().to_string().into(),
+            }),
+        };
+        Ok(Self { value, decimals })
+    }
+
+    /// Converts this decimal into an ABI value of the specified integer type.
+    ///
+    /// See [`AbiValue::coerce_to`] for the supported types.
+    pub fn to_abi(&self, ty: &AbiType) -> Result<AbiValue> {
+        let bits = u16::try_from(self.value.bits()).unwrap_or(u16::MAX);
+        AbiValue::Uint(bits, self.value.clone()).coerce_to(ty)
+    }
+
+    /// Converts this decimal into [`Tokens`].
+    pub fn to_tokens(&self) -> Result<Tokens> {
+        match ok!(self.to_abi(&AbiType::Token)) {
+            AbiValue::Token(tokens) => Ok(tokens),
+            _ => unreachable!(),
+        }
+    }
+
+    /// Returns an integer amount of minimal units.
+    #[inline]
+    pub fn value(&self) -> &BigUint {
+        &self.value
+    }
+
+    /// Returns the number of fractional digits.
+    #[inline]
+    pub fn decimals(&self) -> u8 {
+        self.decimals
+    }
+}
+
+impl std::fmt::Display for Decimal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let digits = self.value.to_string();
+        let decimals = self.decimals as usize;
+        if decimals == 0 {
+            return f.write_str(&digits);
+        }
+
+        let digits = if digits.len() <= decimals {
+            format!("{digits:0>width$}", width = decimals + 1)
+        } else {
+            digits
+        };
+
+        let (int, frac) = digits.split_at(digits.len() - decimals);
+        let frac = frac.trim_end_matches('0');
+        if frac.is_empty() {
+            f.write_str(int)
+        } else {
+            write!(f, "{int}.{frac}")
+        }
+    }
+}
@@ -13,6 +13,17 @@ pub enum ParseAbiVersionError {
     InvalidComponent(#[source] std::num::ParseIntError),
 }
 
+/// Error type for decimal number parsing related errors.
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum ParseDecimalError {
+    /// Expected digits with an optional fractional part.
+    #[error("invalid decimal format")]
+    InvalidFormat,
+    /// Fractional part is longer than the number of decimals.
+    #[error("too many decimal places, expected at most {0}")]
+    TooManyDecimals(u8),
+}
+
 /// Error type for ABI type parsing related errors.
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum ParseAbiTypeError {
@@ -67,6 +78,7 @@ pub enum ParseNamedAbiTypeError {
 
 /// Error type for ABI values unpacking related errors.
 #[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+#[non_exhaustive]
 pub enum AbiError {
     /// Expected a different value type.
     #[error("expected ABI type `{expected}`, got `{ty}`")]
@@ -88,6 +100,9 @@ pub enum AbiError {
     /// All cells for `bytes` or `fixedbytes` must have data multiple of 8.
     #[error("number of bits in a cell is not a multiple of 8")]
     ExpectedCellWithBytes,
+    /// Data is not encoded the same way as the decoded values.
+    #[error("non-canonical encoding")]
+    NonCanonicalEncoding,
     /// Expected a valid utf8-encoded string.
     #[error("invalid string")]
     InvalidString(#[from] std::str::Utf8Error),
@@ -107,6 +122,63 @@ pub enum AbiError {
         /// Length of the parsed bytes.
         len: usize,
     },
+    /// Integer value does not fit into the target type.
+    #[error("integer value does not fit into `{ty}`")]
+    IntOutOfRange {
+        /// A full signature of the target type.
+        ty: Box<str>,
+    },
+    /// Invalid syntax of a nested value path.
+    #[error("invalid value path `{0}`")]
+    InvalidPath(Box<str>),
+    /// There is no value at the specified path.
+    #[error("value not found at path `{0}`")]
+    PathNotFound(Box<str>),
+    /// Expected a different ABI version.
+    #[error("expected ABI version {expected}, got {version}")]
+    AbiVersionMismatch {
+        /// Expected ABI version.
+        expected: crate::abi::AbiVersion,
+        /// Received ABI version.
+        version: crate::abi::AbiVersion,
+    },
+    /// Expected the same list of external message headers.
+    #[error("external message headers mismatch")]
+    HeadersMismatch,
+    /// Item with the same name but a different signature already exists.
+    #[error("conflicting definitions of `{0}`")]
+    ConflictingDefinition(Arc<str>),
+    /// Item with the same id but a different name already exists.
+    #[error("id 0x{id:08x} of `{name}` collides with `{other}`")]
+    IdCollision {
+        /// Colliding id.
+        id: u32,
+        /// Name of the new item.
+        name: Arc<str>,
+        /// Name of the existing item.
+        other: Arc<str>,
+    },
+    /// Function or event with the specified name was not found.
+    #[error("unknown function or event `{0}`")]
+    UnknownItem(Arc<str>),
+    /// Value of the type can't fit into a single cell.
+    #[error("type `{ty}` does not fit into a cell")]
+    TypeTooLarge {
+        /// A full signature of the type.
+        ty: Box<str>,
+    },
+    /// Account is not active or has no data.
+    #[error("account data not found")]
+    AccountDataNotFound,
+    /// Decoded value differs from the encoded one.
+    #[cfg(feature = "test-utils")]
+    #[error("{form} roundtrip mismatch for `{name}`")]
+    RoundtripMismatch {
+        /// Encoding form (`internal` or `external`).
+        form: &'static str,
+        /// Argument name.
+        name: Arc<str>,
+    },
     /// Address is required for signature for some ABI versions and it was not provided.
     #[error("an address was expected for signing but was not provided")]
     AddressNotProvided,
@@ -127,3 +199,42 @@ pub enum AbiError {
         id: u32,
     },
 }
+
+/// Error type for function arguments validation.
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum AbiArgError {
+    /// Required argument was not provided.
+    #[error("missing argument `{name}` of type `{expected}`")]
+    Missing {
+        /// Argument name.
+        name: Arc<str>,
+        /// A full signature of the expected type.
+        expected: Box<str>,
+    },
+    /// Argument is not a part of the function inputs.
+    #[error("unexpected argument `{name}`")]
+    Unexpected {
+        /// Argument name.
+        name: Arc<str>,
+    },
+    /// Argument was provided at the wrong position.
+    #[error("argument `{name}` is expected at position {expected}, got {position}")]
+    Misplaced {
+        /// Argument name.
+        name: Arc<str>,
+        /// Position in the function inputs.
+        expected: usize,
+        /// Position in the provided arguments.
+        position: usize,
+    },
+    /// Argument has a different type.
+    #[error("argument `{name}` expected type `{expected}`, got `{ty}`")]
+    TypeMismatch {
+        /// Argument name.
+        name: Arc<str>,
+        /// A full signature of the expected type.
+        expected: Box<str>,
+        /// A full signature of the received type.
+        ty: Box<str>,
+    },
+}
@@ -4,20 +4,24 @@ use std::hash::{BuildHasher, Hash};
 use std::str::FromStr;
 
 pub use self::contract::{
-    Contract, Event, EventBuilder, ExternalInput, Function, FunctionBuilder, UnsignedBody,
-    UnsignedExternalMessage,
+    Contract, Event, EventBuilder, ExternalInput, Function, FunctionBuilder, Interface,
+    InternalInput, UnsignedBody, UnsignedExternalMessage,
 };
+pub use self::decimal::Decimal;
 pub use self::signature::{extend_signature_with_id, sign_with_signature_id};
 pub use self::traits::{
     FromAbi, FromAbiIter, FromPlainAbi, IgnoreName, IntoAbi, IntoPlainAbi, WithAbiType,
     WithPlainAbiType,
 };
 pub use self::ty::{AbiHeaderType, AbiType, NamedAbiType, PlainAbiType};
-pub use self::value::{AbiHeader, AbiValue, NamedAbiValue, PlainAbiValue};
+pub use self::value::{AbiBytesLayout, AbiHeader, AbiValue, NamedAbiValue, PlainAbiValue};
 
 pub mod error;
+#[cfg(feature = "test-utils")]
+pub mod test_utils;
 
 mod contract;
+mod decimal;
 mod signature;
 mod traits;
 mod ty;
@@ -0,0 +1,54 @@
+//! ABI testing utilities.
+
+use anyhow::Result;
+
+use crate::abi::error::AbiError;
+use crate::abi::{Function, NamedAbiValue};
+use crate::models::StdAddr;
+
+/// Encodes the specified arguments as internal and external inputs
+/// of the function, decodes them back and compares with the original values.
+///
+/// External input is built with fixed headers and a fake signature,
+/// so the result doesn't depend on the current time or keys.
+pub fn roundtrip(function: &Function, values: &[NamedAbiValue]) -> Result<()> {
+    // Internal
+    let body = ok!(function.encode_internal_input(values)).build()?;
+    let decoded = ok!(function.decode_internal_input(body.as_slice()?));
+    ok!(compare("internal", values, &decoded));
+
+    // External
+    let address = StdAddr::default();
+    let body = ok!(function
+        .encode_external(values)
+        .with_time(1)
+        .with_expire_at(u32::MAX)
+        .with_address(&address)
+        .build_input())
+    .with_fake_signature()?;
+    let decoded = ok!(function.decode_external_input(body.as_slice()?));
+    compare("external", values, &decoded)
+}
+
+fn compare(
+    form: &'static str,
+    expected: &[NamedAbiValue],
+    decoded: &[NamedAbiValue],
+) -> Result<()> {
+    anyhow::ensure!(
+        decoded.len() == expected.len(),
+        "{form} roundtrip mismatch: expected {} values, got {}",
+        expected.len(),
+        decoded.len()
+    );
+    for (expected, decoded) in expected.iter().zip(decoded) {
+        anyhow::ensure!(
+            decoded == expected,
+            AbiError::RoundtripMismatch {
+                form,
+                name: expected.name.clone(),
+            }
+        );
+    }
+    Ok(())
+}
@@ -1,7 +1,9 @@
 use bytes::Bytes;
 
+use crate::abi::error::AbiError;
 use crate::abi::*;
 use crate::models::StdAddr;
+use crate::num::Tokens;
 use crate::prelude::{Cell, CellBuilder, CellFamily, HashBytes, RawDict, Store};
 
 const DEPOOL_ABI: &str = include_str!("depool.abi.json");
@@ -260,3 +262,688 @@ fn encode_empty_init_data() {
 
     assert_eq!(init_data, expected);
 }
+
+#[test]
+fn coerce_values() {
+    fn error(value: AbiValue, ty: &AbiType) -> AbiError {
+        value
+            .coerce_to(ty)
+            .unwrap_err()
+            .downcast::<AbiError>()
+            .unwrap()
+    }
+
+    // Integers
+    assert_eq!(
+        AbiValue::uint(8, 200u32)
+            .coerce_to(&AbiType::Uint(64))
+            .unwrap(),
+        AbiValue::uint(64, 200u32)
+    );
+    assert_eq!(
+        AbiValue::uint(32, 100u32)
+            .coerce_to(&AbiType::Int(8))
+            .unwrap(),
+        AbiValue::int(8, 100)
+    );
+    assert_eq!(
+        AbiValue::int(32, -128).coerce_to(&AbiType::Int(8)).unwrap(),
+        AbiValue::int(8, -128)
+    );
+    assert_eq!(
+        AbiValue::uint(128, 1000u32)
+            .coerce_to(&AbiType::Token)
+            .unwrap(),
+        AbiValue::Token(Tokens::new(1000))
+    );
+    assert_eq!(
+        AbiValue::Token(Tokens::new(1000))
+            .coerce_to(&AbiType::varuint(16))
+            .unwrap(),
+        AbiValue::varuint(16, 1000u32)
+    );
+    assert_eq!(
+        error(AbiValue::uint(32, 200u32), &AbiType::Int(8)),
+        AbiError::IntOutOfRange { ty: "int8".into() }
+    );
+    assert_eq!(
+        error(AbiValue::int(32, -1), &AbiType::Uint(256)),
+        AbiError::IntOutOfRange {
+            ty: "uint256".into()
+        }
+    );
+
+    // Bytes
+    assert_eq!(
+        AbiValue::bytes(vec![1u8; 4])
+            .coerce_to(&AbiType::FixedBytes(4))
+            .unwrap(),
+        AbiValue::fixedbytes(vec![1u8; 4])
+    );
+    assert_eq!(
+        error(AbiValue::bytes(vec![1u8; 3]), &AbiType::FixedBytes(4)),
+        AbiError::BytesSizeMismatch {
+            expected: 4,
+            len: 3
+        }
+    );
+
+    // Wrappers
+    assert_eq!(
+        AbiValue::uint(8, 1u32)
+            .coerce_to(&AbiType::optional(AbiType::Uint(32)))
+            .unwrap(),
+        AbiValue::optional(Some(1u32))
+    );
+    assert_eq!(
+        AbiValue::unnamed_tuple([AbiValue::Bool(true)])
+            .coerce_to(&AbiType::Bool)
+            .unwrap(),
+        AbiValue::Bool(true)
+    );
+    assert_eq!(
+        AbiValue::array([1u8, 2, 3])
+            .coerce_to(&AbiType::array(AbiType::Uint(32)))
+            .unwrap(),
+        AbiValue::array([1u32, 2, 3])
+    );
+    assert_eq!(
+        error(
+            AbiValue::array([1u8, 2, 3]),
+            &AbiType::fixedarray(AbiType::Uint(32), 2)
+        ),
+        AbiError::ArraySizeMismatch {
+            expected: 2,
+            len: 3
+        }
+    );
+    assert_eq!(
+        AbiValue::map([(1u8, 2u8)])
+            .coerce_to(&AbiType::map(PlainAbiType::Uint(32), AbiType::Uint(64)))
+            .unwrap(),
+        AbiValue::map([(1u32, 2u64)])
+    );
+
+    assert!(matches!(
+        error(AbiValue::Bool(true), &AbiType::Cell),
+        AbiError::TypeMismatch { .. }
+    ));
+}
+
+#[test]
+fn value_paths() {
+    let mut value = AbiValue::tuple([
+        ("a", AbiValue::uint(32, 1u32)),
+        (
+            "b",
+            AbiValue::Array(
+                std::sync::Arc::new(AbiType::tuple([("c", AbiType::Bool)])),
+                vec![
+                    AbiValue::tuple([("c", AbiValue::Bool(false))]),
+                    AbiValue::tuple([("c", AbiValue::Bool(true))]),
+                ],
+            ),
+        ),
+        ("m", AbiValue::map([(10u32, 20u64)])),
+        (
+            "o",
+            AbiValue::Optional(
+                std::sync::Arc::new(AbiType::reference(AbiType::tuple([(
+                    "x",
+                    AbiType::Uint(8),
+                )]))),
+                Some(Box::new(AbiValue::reference(AbiValue::tuple([(
+                    "x",
+                    AbiValue::uint(8, 5u32),
+                )])))),
+            ),
+        ),
+    ])
+    .named("root");
+
+    assert_eq!(value.get_path(""), Some(&value.value));
+    assert_eq!(value.get_path("a"), Some(&AbiValue::uint(32, 1u32)));
+    assert_eq!(value.get_path("b[1].c"), Some(&AbiValue::Bool(true)));
+    assert_eq!(value.get_path("m[10]"), Some(&AbiValue::uint(64, 20u32)));
+    assert_eq!(value.get_path("o.x"), Some(&AbiValue::uint(8, 5u32)));
+    assert_eq!(value.get_path("b[2].c"), None);
+    assert_eq!(value.get_path("m[11]"), None);
+    assert_eq!(value.get_path("a.x"), None);
+    assert_eq!(value.get_path("b[1"), None);
+    assert_eq!(value.get_path("b..c"), None);
+
+    value.set_path("b[0].c", AbiValue::Bool(true)).unwrap();
+    assert_eq!(value.get_path("b[0].c"), Some(&AbiValue::Bool(true)));
+
+    // Values are coerced to the existing type
+    value.set_path("a", AbiValue::uint(8, 2u32)).unwrap();
+    assert_eq!(value.get_path("a"), Some(&AbiValue::uint(32, 2u32)));
+
+    // New map entries are inserted
+    value.set_path("m[11]", AbiValue::uint(8, 3u32)).unwrap();
+    assert_eq!(value.get_path("m[11]"), Some(&AbiValue::uint(64, 3u32)));
+
+    // Signed map keys must fit into the key type
+    let mut signed_map = AbiValue::Map(
+        PlainAbiType::Int(8),
+        std::sync::Arc::new(AbiType::Bool),
+        Default::default(),
+    );
+    for key in ["127", "-128", "0", "-1"] {
+        signed_map
+            .set_path(&format!("[{key}]"), AbiValue::Bool(true))
+            .unwrap();
+        assert_eq!(
+            signed_map.get_path(&format!("[{key}]")),
+            Some(&AbiValue::Bool(true))
+        );
+    }
+    for key in ["128", "-129", "255", "-256"] {
+        assert!(signed_map
+            .set_path(&format!("[{key}]"), AbiValue::Bool(true))
+            .is_err());
+        assert_eq!(signed_map.get_path(&format!("[{key}]")), None);
+    }
+
+    assert_eq!(
+        value
+            .set_path("x", AbiValue::Bool(true))
+            .unwrap_err()
+            .downcast::<AbiError>()
+            .unwrap(),
+        AbiError::PathNotFound("x".into())
+    );
+    assert_eq!(
+        value
+            .set_path("b[", AbiValue::Bool(true))
+            .unwrap_err()
+            .downcast::<AbiError>()
+            .unwrap(),
+        AbiError::InvalidPath("b[".into())
+    );
+}
+
+#[test]
+fn compose_interfaces() {
+    const EXTRA_ABI: &str = r#"{
+        "ABI version": 2,
+        "header": ["time", "expire"],
+        "functions": [
+            { "name": "supportsInterface", "inputs": [{ "name": "id", "type": "uint32" }], "outputs": [{ "name": "value", "type": "bool" }] },
+            { "name": "withdrawAll", "inputs": [], "outputs": [] }
+        ],
+        "events": [
+            { "name": "Extra", "inputs": [{ "name": "value", "type": "uint32" }] }
+        ]
+    }"#;
+
+    let contract = serde_json::from_str::<Contract>(DEPOOL_ABI).unwrap();
+
+    let interface =
+        Interface::from_contract(&contract, &["withdrawAll", "withdrawPart"], &[]).unwrap();
+    assert!(contract.implements(&interface));
+    assert_eq!(
+        interface.compute_id(),
+        contract.functions["withdrawAll"].input_id ^ contract.functions["withdrawPart"].input_id
+    );
+    assert!(matches!(
+        Interface::from_contract(&contract, &["unknown"], &[])
+            .unwrap_err()
+            .downcast::<AbiError>()
+            .unwrap(),
+        AbiError::UnknownItem(name) if name.as_ref() == "unknown"
+    ));
+
+    // Merge contracts
+    let mut merged = serde_json::from_str::<Contract>(DEPOOL_ABI).unwrap();
+    let extra = serde_json::from_str::<Contract>(EXTRA_ABI).unwrap();
+    let extra_interface = serde_json::from_str::<Interface>(EXTRA_ABI).unwrap();
+    assert!(!merged.implements(&extra_interface));
+
+    merged.merge(extra).unwrap();
+    assert!(merged.implements(&extra_interface));
+    assert!(merged.implements(&interface));
+    assert_eq!(merged.functions.len(), contract.functions.len() + 1);
+    assert_eq!(merged.events.len(), contract.events.len() + 1);
+
+    // Interfaces with the same items are deduplicated
+    merged.add_interface(&extra_interface).unwrap();
+    assert_eq!(merged.functions.len(), contract.functions.len() + 1);
+
+    // Id collisions are detected
+    let function = contract.functions["withdrawAll"].clone();
+    let mut interface = Interface::new(contract.abi_version, contract.headers.iter().copied());
+    interface.add_function(function.clone()).unwrap();
+
+    let colliding = Function::builder(contract.abi_version, "otherWithdrawAll")
+        .with_headers(contract.headers.iter().copied())
+        .with_id(function.input_id)
+        .build();
+    assert_eq!(
+        interface
+            .add_function(colliding)
+            .unwrap_err()
+            .downcast::<AbiError>()
+            .unwrap(),
+        AbiError::IdCollision {
+            id: function.input_id,
+            name: "otherWithdrawAll".into(),
+            other: "withdrawAll".into(),
+        }
+    );
+
+    let mut colliding = Function::builder(contract.abi_version, "otherWithdrawAll")
+        .with_headers(contract.headers.iter().copied())
+        .build();
+    colliding.output_id = function.output_id;
+    assert_eq!(
+        interface
+            .add_function(colliding)
+            .unwrap_err()
+            .downcast::<AbiError>()
+            .unwrap(),
+        AbiError::IdCollision {
+            id: function.output_id,
+            name: "otherWithdrawAll".into(),
+            other: "withdrawAll".into(),
+        }
+    );
+
+    // Incompatible interfaces are rejected
+    let other_headers = Interface::new(contract.abi_version, []);
+    assert_eq!(
+        merged
+            .add_interface(&other_headers)
+            .unwrap_err()
+            .downcast::<AbiError>()
+            .unwrap(),
+        AbiError::HeadersMismatch
+    );
+}
+
+#[test]
+fn encode_internal_message() {
+    use crate::models::{IntAddr, MsgInfo, RelaxedMsgInfo, StateInit};
+
+    let contract = serde_json::from_str::<Contract>(DEPOOL_ABI).unwrap();
+    let function = contract.functions.get("addOrdinaryStake").unwrap();
+
+    let tokens = [123u64.into_abi().named("stake")];
+    let src = IntAddr::Std(StdAddr::new(0, HashBytes([0x11; 32])));
+    let dst = IntAddr::Std(StdAddr::new(0, HashBytes([0x22; 32])));
+    let state_init = StateInit::default();
+
+    let input = function
+        .encode_internal(&tokens, &dst)
+        .with_value(Tokens::new(1_000_000_000))
+        .with_bounce(true)
+        .with_state_init(&state_init);
+
+    let body = input.build_input().unwrap();
+    assert_eq!(
+        body,
+        function
+            .encode_internal_input(&tokens)
+            .unwrap()
+            .build()
+            .unwrap()
+    );
+
+    let message = input.build_message(&src).unwrap();
+    let MsgInfo::Int(info) = &message.info else {
+        panic!("expected an internal message");
+    };
+    assert_eq!(info.src, src);
+    assert_eq!(info.dst, dst);
+    assert_eq!(info.value.tokens, Tokens::new(1_000_000_000));
+    assert!(info.bounce);
+    assert_eq!(message.init.as_ref(), Some(&state_init));
+    assert_eq!(message.body.0, body);
+
+    let relaxed = input.build_relaxed_message().unwrap();
+    let RelaxedMsgInfo::Int(info) = &relaxed.info else {
+        panic!("expected an internal message");
+    };
+    assert_eq!(info.src, None);
+    assert_eq!(info.dst, dst);
+    assert!(info.bounce);
+
+    let legacy = function
+        .encode_internal_message(
+            &tokens,
+            dst.clone(),
+            Tokens::new(1_000_000_000),
+            true,
+            Some(&state_init),
+        )
+        .unwrap();
+    assert_eq!(
+        CellBuilder::build_from(&relaxed).unwrap(),
+        CellBuilder::build_from(legacy.as_ref()).unwrap()
+    );
+}
+
+#[test]
+fn decimal_amounts() {
+    use crate::abi::error::ParseDecimalError;
+
+    let amount = Decimal::from_str_with_decimals("1.5", 9).unwrap();
+    assert_eq!(amount.value(), &1_500_000_000u64.into());
+    assert_eq!(amount.to_string(), "1.5");
+    assert_eq!(amount.to_tokens().unwrap(), Tokens::new(1_500_000_000));
+    assert_eq!(
+        amount.to_abi(&AbiType::Uint(128)).unwrap(),
+        AbiValue::uint(128, 1_500_000_000u64)
+    );
+
+    let parsed = Decimal::from_abi(&AbiValue::Token(Tokens::new(1_500_000_000)), 9).unwrap();
+    assert_eq!(parsed, amount);
+
+    for (s, decimals) in [("0", 9), ("123", 0), ("0.000000001", 9), ("10.01", 2)] {
+        let amount = Decimal::from_str_with_decimals(s, decimals).unwrap();
+        assert_eq!(amount.to_string(), s);
+    }
+    assert_eq!(
+        Decimal::from_str_with_decimals("007.50", 3)
+            .unwrap()
+            .to_string(),
+        "7.5"
+    );
+
+    assert_eq!(
+        Decimal::from_str_with_decimals("1.0001", 3),
+        Err(ParseDecimalError::TooManyDecimals(3))
+    );
+    for s in ["", ".5", "1.", "1.2.3", "-1", "1e9", "1,5"] {
+        assert_eq!(
+            Decimal::from_str_with_decimals(s, 9),
+            Err(ParseDecimalError::InvalidFormat)
+        );
+    }
+
+    let large = Decimal::from_str_with_decimals("1000", 9).unwrap();
+    assert!(large.to_abi(&AbiType::Uint(32)).is_err());
+    assert!(Decimal::from_abi(&AbiValue::Bool(true), 9).is_err());
+}
+
+#[test]
+fn abi_type_sizes() {
+    use crate::cell::{CellSliceSize, CellTreeStats};
+
+    // Exact sizes
+    assert_eq!(
+        AbiType::Uint(32).exact_size(AbiVersion::V2_0),
+        Some(CellTreeStats {
+            bit_count: 32,
+            cell_count: 0
+        })
+    );
+    assert_eq!(AbiType::Address.exact_size(AbiVersion::V2_0), None);
+    assert_eq!(
+        AbiType::Address.exact_size(AbiVersion::V2_2),
+        Some(AbiType::Address.max_size())
+    );
+    assert_eq!(
+        AbiType::tuple([("a", AbiType::Bool), ("b", AbiType::Cell)]).exact_size(AbiVersion::V2_1),
+        Some(CellTreeStats {
+            bit_count: 1,
+            cell_count: 1
+        })
+    );
+    assert_eq!(
+        AbiType::tuple([("a", AbiType::Bool), ("b", AbiType::Token)]).exact_size(AbiVersion::V2_1),
+        None
+    );
+    let map = AbiType::map(PlainAbiType::Uint(32), AbiType::Bool);
+    assert_eq!(map.exact_size(AbiVersion::V2_1), None);
+    assert_eq!(
+        AbiType::FixedArray(std::sync::Arc::new(AbiType::Bool), 2).exact_size(AbiVersion::V2_1),
+        None
+    );
+    assert_eq!(
+        AbiType::tuple([("a", AbiType::Bool), ("b", map.clone())]).exact_size(AbiVersion::V2_1),
+        None
+    );
+    assert_eq!(map.exact_size(AbiVersion::V2_2), Some(map.max_size()));
+
+    // Encodable types
+    assert!(AbiType::Uint(256).check_encodable().is_ok());
+    assert!(AbiType::array(AbiType::tuple([
+        ("a", AbiType::Uint(256)),
+        ("b", AbiType::Uint(256)),
+        ("c", AbiType::Uint(256)),
+        ("d", AbiType::Uint(256)),
+    ]))
+    .check_encodable()
+    .is_ok());
+    assert_eq!(
+        AbiType::optional(AbiType::Uint(1024)).check_encodable(),
+        Err(AbiError::TypeTooLarge {
+            ty: "uint1024".into()
+        })
+    );
+
+    // Layout
+    let items = [
+        AbiType::Uint(256).named("a"),
+        AbiType::Uint(256).named("b"),
+        AbiType::Uint(256).named("c"),
+        AbiType::Uint(256).named("d"),
+        AbiType::Cell.named("e"),
+        AbiType::Bool.named("f"),
+    ];
+    let layout = NamedAbiType::compute_tuple_layout(&items, AbiVersion::V2_2);
+    assert_eq!(
+        layout,
+        [
+            CellSliceSize { bits: 768, refs: 0 },
+            CellSliceSize { bits: 257, refs: 1 },
+        ]
+    );
+
+    let values = items
+        .iter()
+        .map(NamedAbiType::make_default_value)
+        .collect::<Vec<_>>();
+    let cell = NamedAbiValue::tuple_to_cell(&values, AbiVersion::V2_2).unwrap();
+    assert_eq!(cell.bit_len(), layout[0].bits);
+    let child = cell.reference(cell.reference_count() - 1).unwrap();
+    assert_eq!(child.bit_len(), layout[1].bits);
+    assert_eq!(child.reference_count(), layout[1].refs);
+}
+
+#[test]
+fn default_values() {
+    let types = [
+        AbiType::Uint(32).named("uint"),
+        AbiType::Int(8).named("int"),
+        AbiType::varuint(16).named("varuint"),
+        AbiType::Bool.named("bool"),
+        AbiType::Cell.named("cell"),
+        AbiType::Address.named("address"),
+        AbiType::Bytes.named("bytes"),
+        AbiType::FixedBytes(4).named("fixedbytes"),
+        AbiType::String.named("string"),
+        AbiType::Token.named("token"),
+        AbiType::array(AbiType::Uint(8)).named("array"),
+        AbiType::fixedarray(AbiType::Bool, 3).named("fixedarray"),
+        AbiType::map(PlainAbiType::Address, AbiType::Uint(16)).named("map"),
+        AbiType::optional(AbiType::Cell).named("optional"),
+        AbiType::reference(AbiType::tuple([("a", AbiType::Uint(8))])).named("ref"),
+    ];
+
+    let values = NamedAbiType::make_default_tuple(&types);
+    NamedAbiValue::check_types(&values, &types).unwrap();
+
+    for version in [AbiVersion::V2_0, AbiVersion::V2_2] {
+        let cell = NamedAbiValue::tuple_to_cell(&values, version).unwrap();
+        let decoded =
+            NamedAbiValue::load_tuple(&types, version, &mut cell.as_slice().unwrap()).unwrap();
+        assert_eq!(decoded, values);
+    }
+
+    for ty in [
+        PlainAbiType::Uint(32),
+        PlainAbiType::Int(8),
+        PlainAbiType::Bool,
+        PlainAbiType::Address,
+    ] {
+        assert!(ty.make_default_value().has_type(&ty));
+    }
+}
+
+#[test]
+fn decode_storage_fields() {
+    use crate::models::{Account, AccountState, CurrencyCollection, StateInit, StorageInfo};
+
+    const ABI: &str = r#"{
+        "ABI version": 2,
+        "version": "2.3",
+        "header": ["time", "expire"],
+        "functions": [],
+        "fields": [
+            { "name": "_pubkey", "type": "uint256" },
+            { "name": "_timestamp", "type": "uint64" },
+            { "name": "_constructorFlag", "type": "bool" },
+            { "name": "owner", "type": "address" },
+            { "name": "counter", "type": "uint32" }
+        ]
+    }"#;
+
+    let contract = serde_json::from_str::<Contract>(ABI).unwrap();
+    assert_eq!(contract.fields.len(), 5);
+
+    let values = [
+        HashBytes([0x11; 32]).into_abi().named("_pubkey"),
+        123u64.into_abi().named("_timestamp"),
+        true.into_abi().named("_constructorFlag"),
+        StdAddr::new(0, HashBytes([0x22; 32]))
+            .into_abi()
+            .named("owner"),
+        42u32.into_abi().named("counter"),
+    ];
+
+    let data = contract.encode_fields(&values).unwrap().build().unwrap();
+    assert_eq!(
+        contract.decode_fields(data.as_slice().unwrap()).unwrap(),
+        values
+    );
+
+    let mut account = Account {
+        address: StdAddr::new(0, HashBytes::ZERO).into(),
+        storage_stat: StorageInfo::default(),
+        last_trans_lt: 0,
+        balance: CurrencyCollection::ZERO,
+        state: AccountState::Uninit,
+        init_code_hash: None,
+    };
+    assert_eq!(
+        contract
+            .decode_account_fields(&account)
+            .unwrap_err()
+            .downcast::<AbiError>()
+            .unwrap(),
+        AbiError::AccountDataNotFound
+    );
+
+    account.state = AccountState::Active(StateInit {
+        data: Some(data),
+        ..Default::default()
+    });
+    assert_eq!(contract.decode_account_fields(&account).unwrap(), values);
+
+    // Wrong types are not accepted
+    assert!(contract.encode_fields(&values[..4]).is_err());
+}
+
+#[test]
+fn validate_function_args() {
+    use crate::abi::error::AbiArgError;
+
+    let contract = serde_json::from_str::<Contract>(DEPOOL_ABI).unwrap();
+    let function = contract.functions.get("transferStake").unwrap();
+
+    let dest = StdAddr::new(0, HashBytes::ZERO).into_abi().named("dest");
+    let amount = 100u64.into_abi().named("amount");
+
+    function
+        .validate_args(&[dest.clone(), amount.clone()])
+        .unwrap();
+
+    assert_eq!(
+        function.validate_args(std::slice::from_ref(&dest)),
+        Err(AbiArgError::Missing {
+            name: "amount".into(),
+            expected: "uint64".into(),
+        })
+    );
+    assert_eq!(
+        function.validate_args(&[amount.clone(), dest.clone()]),
+        Err(AbiArgError::Misplaced {
+            name: "dest".into(),
+            expected: 0,
+            position: 1,
+        })
+    );
+    assert_eq!(
+        function.validate_args(&[dest.clone(), amount.clone(), true.into_abi().named("extra")]),
+        Err(AbiArgError::Unexpected {
+            name: "extra".into()
+        })
+    );
+    assert_eq!(
+        function.validate_args(&[dest.clone(), amount.clone(), amount.clone()]),
+        Err(AbiArgError::Unexpected {
+            name: "amount".into()
+        })
+    );
+    assert_eq!(
+        function.validate_args(&[dest, 100u32.into_abi().named("amount")]),
+        Err(AbiArgError::TypeMismatch {
+            name: "amount".into(),
+            expected: "uint64".into(),
+            ty: "uint32".into(),
+        })
+    );
+}
+
+#[cfg(feature = "test-utils")]
+#[test]
+fn roundtrip_function_inputs() {
+    use crate::abi::test_utils::roundtrip;
+
+    let contract = serde_json::from_str::<Contract>(DEPOOL_ABI).unwrap();
+    let function = contract.functions.get("transferStake").unwrap();
+
+    let dest = StdAddr::new(0, HashBytes([0x11; 32]))
+        .into_abi()
+        .named("dest");
+    let amount = 100u64.into_abi().named("amount");
+    roundtrip(function, &[dest, amount]).unwrap();
+
+    for abi_version in [AbiVersion::V2_0, AbiVersion::V2_3] {
+        let function = Function::builder(abi_version, "test")
+            .with_headers([AbiHeaderType::Time, AbiHeaderType::PublicKey])
+            .with_inputs([
+                AbiType::Bytes.named("data"),
+                AbiType::array(AbiType::Uint(32)).named("items"),
+                AbiType::tuple([("flag", AbiType::Bool), ("value", AbiType::varuint(16))])
+                    .named("pair"),
+            ])
+            .build();
+
+        let values = [
+            AbiValue::Bytes(Bytes::from_static(b"hello")).named("data"),
+            vec![1u32, 2, 3].into_abi().named("items"),
+            AbiValue::tuple([
+                true.into_abi().named("flag"),
+                AbiValue::varuint(16, 123u32).named("value"),
+            ])
+            .named("pair"),
+        ];
+        roundtrip(&function, &values).unwrap();
+
+        let err = roundtrip(&function, &values[..1]).unwrap_err();
+        assert!(err.downcast_ref::<AbiError>().is_some());
+    }
+}
@@ -6,8 +6,8 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 
-use super::error::{ParseAbiTypeError, ParseNamedAbiTypeError};
-use crate::abi::WithoutName;
+use super::error::{AbiError, ParseAbiTypeError, ParseNamedAbiTypeError};
+use crate::abi::{AbiVersion, WithoutName};
 use crate::cell::{CellTreeStats, MAX_BIT_LEN, MAX_REF_COUNT};
 use crate::models::{IntAddr, StdAddr};
 use crate::num::Tokens;
@@ -362,6 +362,76 @@ impl AbiType {
         }
     }
 
+    /// Returns the number of bits and refs reserved for this type during serialization
+    /// if it doesn't depend on the value.
+    ///
+    /// Starting from ABI 2.2 all types have a fixed size equal to [`AbiType::max_size`].
+    pub fn exact_size(&self, version: AbiVersion) -> Option<CellTreeStats> {
+        if version.use_max_size() {
+            return Some(self.max_size());
+        }
+
+        match self {
+            Self::Uint(_)
+            | Self::Int(_)
+            | Self::Bool
+            | Self::Cell
+            | Self::Bytes
+            | Self::FixedBytes(_)
+            | Self::String
+            | Self::Ref(_) => Some(self.max_size()),
+            Self::Tuple(items) => {
+                let mut size = CellTreeStats::ZERO;
+                for item in items.iter() {
+                    size += item.ty.exact_size(version)?;
+                }
+                Some(size)
+            }
+            Self::VarUint(_)
+            | Self::VarInt(_)
+            | Self::Address
+            | Self::Token
+            | Self::Array(_)
+            | Self::FixedArray(..)
+            | Self::Map(..)
+            | Self::Optional(_) => None,
+        }
+    }
+
+    /// Ensures that any value of this type can be serialized.
+    ///
+    /// Fails if some nested non-tuple type doesn't fit into a single cell.
+    pub fn check_encodable(&self) -> Result<(), AbiError> {
+        match self {
+            Self::Tuple(items) => {
+                for item in items.iter() {
+                    ok!(item.ty.check_encodable());
+                }
+                return Ok(());
+            }
+            Self::Array(ty) | Self::FixedArray(ty, _) | Self::Optional(ty) | Self::Ref(ty) => {
+                ok!(ty.check_encodable())
+            }
+            Self::Map(key_ty, value_ty) => {
+                if key_ty.key_bits() > MAX_BIT_LEN {
+                    return Err(AbiError::TypeTooLarge {
+                        ty: self.to_string().into(),
+                    });
+                }
+                ok!(value_ty.check_encodable())
+            }
+            _ => {}
+        }
+
+        let size = self.max_size();
+        if size.bit_count > MAX_BIT_LEN as u64 || size.cell_count > MAX_REF_COUNT as u64 {
+            return Err(AbiError::TypeTooLarge {
+                ty: self.to_string().into(),
+            });
+        }
+        Ok(())
+    }
+
     /// Returns the maximum number of bits that this type can occupy.
     pub fn max_bits(&self) -> usize {
         self.max_size().bit_count as usize
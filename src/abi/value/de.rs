@@ -7,10 +7,10 @@ use num_bigint::{BigInt, BigUint};
 
 use crate::abi::error::AbiError;
 use crate::abi::{
-    AbiHeader, AbiHeaderType, AbiType, AbiValue, AbiVersion, NamedAbiType, NamedAbiValue,
-    PlainAbiType, PlainAbiValue,
+    AbiBytesLayout, AbiHeader, AbiHeaderType, AbiType, AbiValue, AbiVersion, NamedAbiType,
+    NamedAbiValue, PlainAbiType, PlainAbiValue,
 };
-use crate::cell::{Cell, CellSlice, Load, MAX_BIT_LEN, MAX_REF_COUNT};
+use crate::cell::{Cell, CellBuilder, CellSlice, Load, MAX_BIT_LEN, MAX_REF_COUNT};
 use crate::dict::{self, RawDict};
 use crate::error::Error;
 use crate::models::IntAddr;
@@ -30,6 +30,24 @@ impl NamedAbiValue {
         Ok(result)
     }
 
+    /// Loads exactly one tuple from the specified slice requiring it to be fully consumed
+    /// and encoded canonically.
+    ///
+    /// Unlike [`NamedAbiValue::load_tuple`], checks that encoding the loaded values
+    /// with the specified bytes layout produces the same data.
+    pub fn load_tuple_strict(
+        items: &[NamedAbiType],
+        version: AbiVersion,
+        bytes_layout: AbiBytesLayout,
+        slice: &mut CellSlice,
+    ) -> Result<Vec<Self>> {
+        let original = *slice;
+        let result = ok!(Self::load_tuple(items, version, slice));
+        let encoded = Self::tuple_to_builder_ext(&result, version, bytes_layout)?;
+        ok!(check_canonical(&original, &encoded));
+        Ok(result)
+    }
+
     /// Loads a tuple from the specified slice.
     pub fn load_tuple_partial(
         items: &[NamedAbiType],
@@ -128,6 +146,24 @@ impl AbiValue {
         Ok(res)
     }
 
+    /// Loads exactly one unnamed tuple from the specified slice requiring it to be fully
+    /// consumed and encoded canonically.
+    ///
+    /// Unlike [`AbiValue::load_tuple`], checks that encoding the loaded values
+    /// with the specified bytes layout produces the same data.
+    pub fn load_tuple_strict(
+        types: &[AbiType],
+        version: AbiVersion,
+        bytes_layout: AbiBytesLayout,
+        slice: &mut CellSlice,
+    ) -> Result<Vec<Self>> {
+        let original = *slice;
+        let result = ok!(Self::load_tuple(types, version, slice));
+        let encoded = Self::tuple_to_builder_ext(&result, version, bytes_layout)?;
+        ok!(check_canonical(&original, &encoded));
+        Ok(result)
+    }
+
     /// Loads an unnamed tuple from the specified slice.
     pub fn load_tuple_partial(
         types: &[AbiType],
@@ -310,6 +346,14 @@ impl AbiHeader {
     }
 }
 
+fn check_canonical(original: &CellSlice, encoded: &CellBuilder) -> Result<()> {
+    anyhow::ensure!(
+        original.cmp_by_content_only(&encoded.as_full_slice())? == std::cmp::Ordering::Equal,
+        AbiError::NonCanonicalEncoding
+    );
+    Ok(())
+}
+
 fn preload_bits(bits: u16, slice: &mut CellSlice) -> Result<()> {
     if bits == 0 {
         return Ok(());
@@ -344,18 +388,7 @@ fn load_uint_plain(bits: u16, slice: &mut CellSlice) -> Result<BigUint, Error> {
     match bits {
         0 => Ok(BigUint::default()),
         1..=64 => slice.load_uint(bits).map(BigUint::from),
-        _ => {
-            let rem = bits % 8;
-            let mut buffer = vec![0u8; ((bits + 7) / 8) as usize];
-            slice.load_raw(&mut buffer, bits)?;
-
-            buffer.reverse();
-            let mut int = BigUint::from_bytes_le(&buffer);
-            if rem != 0 {
-                int >>= 8 - rem;
-            }
-            Ok(int)
-        }
+        _ => slice.load_biguint(bits),
     }
 }
 
@@ -369,18 +402,7 @@ fn load_int_plain(bits: u16, slice: &mut CellSlice) -> Result<BigInt, Error> {
             }
             BigInt::from(int as i64)
         }),
-        _ => {
-            let rem = bits % 8;
-            let mut buffer = vec![0u8; ((bits + 7) / 8) as usize];
-            slice.load_raw(&mut buffer, bits)?;
-
-            buffer.reverse();
-            let mut int = BigInt::from_signed_bytes_le(&buffer);
-            if rem != 0 {
-                int >>= 8 - rem;
-            }
-            Ok(int)
-        }
+        _ => slice.load_bigint(bits, true),
     }
 }
 
@@ -894,6 +916,46 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn decode_bytes_strict() -> Result<()> {
+        let values = [
+            AbiValue::uint(32, 123u32),
+            AbiValue::Bytes(Bytes::from(vec![0x55; 300])),
+        ];
+        let types = values.iter().map(AbiValue::get_type).collect::<Vec<_>>();
+
+        for v in VX_X {
+            println!("ABIv{v}");
+            let default_layout = AbiBytesLayout::for_version(v);
+            let custom_layout = AbiBytesLayout {
+                chunk_size: 100,
+                remainder_first: !default_layout.remainder_first,
+            };
+
+            let cell = AbiValue::tuple_to_cell(&values, v)?;
+            let parsed =
+                AbiValue::load_tuple_strict(&types, v, default_layout, &mut cell.as_slice()?)?;
+            assert_eq!(parsed, values);
+
+            let cell = AbiValue::tuple_to_builder_ext(&values, v, custom_layout)?.build()?;
+
+            // Non-strict decoding accepts any layout
+            let parsed = AbiValue::load_tuple(&types, v, &mut cell.as_slice()?)?;
+            assert_eq!(parsed, values);
+
+            let parsed =
+                AbiValue::load_tuple_strict(&types, v, custom_layout, &mut cell.as_slice()?)?;
+            assert_eq!(parsed, values);
+
+            assert_abi_err!(
+                AbiValue::load_tuple_strict(&types, v, default_layout, &mut cell.as_slice()?),
+                AbiError::NonCanonicalEncoding
+            );
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn decode_string() -> Result<()> {
         for v in VX_X {
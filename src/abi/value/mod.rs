@@ -4,15 +4,16 @@ use std::sync::Arc;
 
 use anyhow::Result;
 use bytes::Bytes;
-use num_bigint::{BigInt, BigUint};
+use num_bigint::{BigInt, BigUint, Sign};
 
-use super::{ty::*, IntoAbi, IntoPlainAbi, WithAbiType, WithPlainAbiType, WithoutName};
+use super::{ty::*, AbiVersion, IntoAbi, IntoPlainAbi, WithAbiType, WithPlainAbiType, WithoutName};
 use crate::abi::error::AbiError;
 use crate::cell::{Cell, CellFamily};
 use crate::models::IntAddr;
 use crate::num::Tokens;
 
 mod de;
+mod path;
 pub(crate) mod ser;
 
 /// ABI value with name.
@@ -103,6 +104,11 @@ impl NamedAbiType {
             value: self.ty.make_default_value(),
         }
     }
+
+    /// Returns default values for all of the specified types.
+    pub fn make_default_tuple(items: &[Self]) -> Vec<NamedAbiValue> {
+        items.iter().map(Self::make_default_value).collect()
+    }
 }
 
 impl PartialEq for WithoutName<NamedAbiValue> {
@@ -249,6 +255,124 @@ impl AbiValue {
         DisplayValueType(self)
     }
 
+    /// Converts this value into the value of the specified type.
+    ///
+    /// Only lossless conversions are performed:
+    /// - integers are converted between any integer types if the value fits;
+    /// - `bytes` and `fixedbytesN` are converted if the length matches;
+    /// - arrays are converted element-wise (fixed arrays require a matching length);
+    /// - tuples and maps are converted item-wise;
+    /// - values are wrapped into `optional(T)` and `ref(T)` when needed;
+    /// - a tuple of one item is unwrapped when a non-tuple type is expected.
+    pub fn coerce_to(self, ty: &AbiType) -> Result<Self> {
+        if self.has_type(ty) {
+            return Ok(self);
+        }
+
+        Ok(match (self, ty) {
+            (
+                value @ (Self::Uint(..)
+                | Self::Int(..)
+                | Self::VarUint(..)
+                | Self::VarInt(..)
+                | Self::Token(_)),
+                AbiType::Uint(_)
+                | AbiType::Int(_)
+                | AbiType::VarUint(_)
+                | AbiType::VarInt(_)
+                | AbiType::Token,
+            ) => {
+                let int = match value {
+                    Self::Uint(_, v) | Self::VarUint(_, v) => BigInt::from(v),
+                    Self::Int(_, v) | Self::VarInt(_, v) => v,
+                    Self::Token(v) => BigInt::from(v.into_inner()),
+                    _ => unreachable!(),
+                };
+                return coerce_int(int, ty);
+            }
+            (Self::Bytes(bytes), AbiType::FixedBytes(len)) => {
+                anyhow::ensure!(
+                    bytes.len() == *len,
+                    AbiError::BytesSizeMismatch {
+                        expected: *len,
+                        len: bytes.len()
+                    }
+                );
+                Self::FixedBytes(bytes)
+            }
+            (Self::FixedBytes(bytes), AbiType::Bytes) => Self::Bytes(bytes),
+            (Self::Tuple(items), AbiType::Tuple(types)) if items.len() == types.len() => {
+                let mut result = Vec::with_capacity(items.len());
+                for (item, ty) in items.into_iter().zip(types.iter()) {
+                    result.push(NamedAbiValue {
+                        name: ty.name.clone(),
+                        value: ok!(item.value.coerce_to(&ty.ty)),
+                    });
+                }
+                Self::Tuple(result)
+            }
+            (Self::Array(_, items) | Self::FixedArray(_, items), AbiType::Array(item_ty)) => {
+                Self::Array(item_ty.clone(), ok!(coerce_items(items, item_ty)))
+            }
+            (
+                Self::Array(_, items) | Self::FixedArray(_, items),
+                AbiType::FixedArray(item_ty, len),
+            ) => {
+                anyhow::ensure!(
+                    items.len() == *len,
+                    AbiError::ArraySizeMismatch {
+                        expected: *len,
+                        len: items.len()
+                    }
+                );
+                Self::FixedArray(item_ty.clone(), ok!(coerce_items(items, item_ty)))
+            }
+            (Self::Map(_, _, entries), AbiType::Map(key_ty, value_ty)) => {
+                let abi_key_ty = AbiType::from(*key_ty);
+                let mut result = BTreeMap::new();
+                for (key, value) in entries {
+                    let key = match ok!(AbiValue::from(key).coerce_to(&abi_key_ty)) {
+                        Self::Uint(n, v) => PlainAbiValue::Uint(n, v),
+                        Self::Int(n, v) => PlainAbiValue::Int(n, v),
+                        Self::Bool(v) => PlainAbiValue::Bool(v),
+                        Self::Address(v) => PlainAbiValue::Address(v),
+                        _ => unreachable!(),
+                    };
+                    result.insert(key, ok!(value.coerce_to(value_ty)));
+                }
+                Self::Map(*key_ty, value_ty.clone(), result)
+            }
+            (Self::Optional(_, value), AbiType::Optional(inner_ty)) => {
+                let value = match value {
+                    Some(value) => Some(Box::new(ok!(value.coerce_to(inner_ty)))),
+                    None => None,
+                };
+                Self::Optional(inner_ty.clone(), value)
+            }
+            (value, AbiType::Optional(inner_ty)) => {
+                let value = ok!(value.coerce_to(inner_ty));
+                Self::Optional(inner_ty.clone(), Some(Box::new(value)))
+            }
+            (Self::Ref(value), AbiType::Ref(inner_ty)) => {
+                Self::Ref(Box::new(ok!(value.coerce_to(inner_ty))))
+            }
+            (value, AbiType::Ref(inner_ty)) => Self::Ref(Box::new(ok!(value.coerce_to(inner_ty)))),
+            (Self::Tuple(mut items), ty)
+                if items.len() == 1 && !matches!(ty, AbiType::Tuple(_)) =>
+            {
+                let item = items.pop().unwrap();
+                return item.value.coerce_to(ty);
+            }
+            (value, ty) => {
+                return Err(AbiError::TypeMismatch {
+                    expected: ty.to_string().into(),
+                    ty: value.display_type().to_string().into(),
+                }
+                .into())
+            }
+        })
+    }
+
     /// Simple `uintN` constructor.
     #[inline]
     pub fn uint<T>(bits: u16, value: T) -> Self
@@ -403,6 +527,55 @@ impl AbiValue {
     }
 }
 
+fn coerce_items(items: Vec<AbiValue>, ty: &AbiType) -> Result<Vec<AbiValue>> {
+    let mut result = Vec::with_capacity(items.len());
+    for item in items {
+        result.push(ok!(item.coerce_to(ty)));
+    }
+    Ok(result)
+}
+
+fn coerce_int(value: BigInt, ty: &AbiType) -> Result<AbiValue> {
+    // Number of bits required to store the value as a signed integer
+    fn signed_bits(value: &BigInt) -> u64 {
+        let magnitude = match value.sign() {
+            Sign::Minus => value.magnitude() - 1u32,
+            _ => value.magnitude().clone(),
+        };
+        magnitude.bits() + 1
+    }
+
+    let is_negative = value.sign() == Sign::Minus;
+    let value = match ty {
+        AbiType::Uint(bits) if !is_negative && value.bits() <= *bits as u64 => {
+            Some(AbiValue::Uint(*bits, value.into_parts().1))
+        }
+        AbiType::Int(bits) if signed_bits(&value) <= *bits as u64 => {
+            Some(AbiValue::Int(*bits, value))
+        }
+        AbiType::VarUint(size) if !is_negative && value.bits() <= (size.get() as u64 - 1) * 8 => {
+            Some(AbiValue::VarUint(*size, value.into_parts().1))
+        }
+        AbiType::VarInt(size) if signed_bits(&value) <= (size.get() as u64 - 1) * 8 => {
+            Some(AbiValue::VarInt(*size, value))
+        }
+        AbiType::Token if !is_negative => u128::try_from(value.magnitude())
+            .ok()
+            .map(Tokens::new)
+            .filter(Tokens::is_valid)
+            .map(AbiValue::Token),
+        _ => None,
+    };
+
+    match value {
+        Some(value) => Ok(value),
+        None => Err(AbiError::IntOutOfRange {
+            ty: ty.to_string().into(),
+        }
+        .into()),
+    }
+}
+
 impl AbiType {
     /// Returns a default value corresponding to the this type.
     pub fn make_default_value(&self) -> AbiValue {
@@ -418,13 +591,7 @@ impl AbiType {
             AbiType::FixedBytes(len) => AbiValue::FixedBytes(Bytes::from(vec![0u8; *len])),
             AbiType::String => AbiValue::String(String::default()),
             AbiType::Token => AbiValue::Token(Tokens::ZERO),
-            AbiType::Tuple(items) => {
-                let mut tuple = Vec::with_capacity(items.len());
-                for item in items.as_ref() {
-                    tuple.push(item.make_default_value());
-                }
-                AbiValue::Tuple(tuple)
-            }
+            AbiType::Tuple(items) => AbiValue::Tuple(NamedAbiType::make_default_tuple(items)),
             AbiType::Array(ty) => AbiValue::Array(ty.clone(), Vec::new()),
             AbiType::FixedArray(ty, items) => {
                 AbiValue::FixedArray(ty.clone(), vec![ty.make_default_value(); *items])
@@ -514,6 +681,18 @@ impl PlainAbiValue {
     }
 }
 
+impl PlainAbiType {
+    /// Returns a default value corresponding to the this type.
+    pub fn make_default_value(&self) -> PlainAbiValue {
+        match self {
+            Self::Uint(bits) => PlainAbiValue::Uint(*bits, BigUint::default()),
+            Self::Int(bits) => PlainAbiValue::Int(*bits, BigInt::default()),
+            Self::Bool => PlainAbiValue::Bool(false),
+            Self::Address => PlainAbiValue::Address(Box::default()),
+        }
+    }
+}
+
 impl From<PlainAbiValue> for AbiValue {
     fn from(value: PlainAbiValue) -> Self {
         match value {
@@ -525,6 +704,32 @@ impl From<PlainAbiValue> for AbiValue {
     }
 }
 
+/// Layout of `bytes`, `fixedbytes` and `string` values in a chain of cells.
+///
+/// Each cell of the chain contains a chunk of bytes and a reference
+/// to the cell with the next chunk.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct AbiBytesLayout {
+    /// Max number of bytes in each cell of the chain (`1..=127`).
+    pub chunk_size: u8,
+    /// Whether the incomplete chunk is stored in the first cell of the chain.
+    /// Otherwise it is stored in the last cell.
+    pub remainder_first: bool,
+}
+
+impl AbiBytesLayout {
+    /// Max number of bytes in a cell.
+    pub const MAX_CHUNK_SIZE: u8 = (crate::cell::MAX_BIT_LEN / 8) as u8;
+
+    /// Returns the layout which is used by the specified ABI version.
+    pub const fn for_version(version: AbiVersion) -> Self {
+        Self {
+            chunk_size: Self::MAX_CHUNK_SIZE,
+            remainder_first: version.major == 1,
+        }
+    }
+}
+
 /// Contract header value.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum AbiHeader {
@@ -0,0 +1,232 @@
+use std::str::FromStr;
+
+use anyhow::Result;
+use num_bigint::{BigInt, BigUint, Sign};
+
+use crate::abi::error::AbiError;
+use crate::abi::{AbiValue, NamedAbiValue, PlainAbiType, PlainAbiValue};
+use crate::models::IntAddr;
+
+impl NamedAbiValue {
+    /// Returns a nested value at the specified path.
+    ///
+    /// Path is relative to this value and consists of tuple field names
+    /// separated by dots and array indices or map keys in square brackets
+    /// (e.g. `a.b[2].c`). Optional values and references are traversed implicitly.
+    ///
+    /// Returns `None` if the path is invalid or there is no value at it.
+    pub fn get_path(&self, path: &str) -> Option<&AbiValue> {
+        self.value.get_path(path)
+    }
+
+    /// Returns a mutable reference to a nested value at the specified path.
+    ///
+    /// See [`NamedAbiValue::get_path`] for the path syntax.
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut AbiValue> {
+        self.value.get_path_mut(path)
+    }
+
+    /// Replaces a nested value at the specified path.
+    ///
+    /// See [`NamedAbiValue::get_path`] for the path syntax.
+    pub fn set_path(&mut self, path: &str, value: AbiValue) -> Result<()> {
+        self.value.set_path(path, value)
+    }
+}
+
+impl AbiValue {
+    /// Returns a nested value at the specified path.
+    ///
+    /// See [`NamedAbiValue::get_path`] for the path syntax.
+    pub fn get_path(&self, path: &str) -> Option<&Self> {
+        let mut value = self;
+        for segment in parse_path(path)? {
+            value = value.get_child(segment)?;
+        }
+        Some(value)
+    }
+
+    /// Returns a mutable reference to a nested value at the specified path.
+    ///
+    /// See [`NamedAbiValue::get_path`] for the path syntax.
+    pub fn get_path_mut(&mut self, path: &str) -> Option<&mut Self> {
+        let mut value = self;
+        for segment in parse_path(path)? {
+            value = value.get_child_mut(segment)?;
+        }
+        Some(value)
+    }
+
+    /// Replaces a nested value at the specified path.
+    ///
+    /// The new value is coerced to the type of the replaced one (see [`AbiValue::coerce_to`]).
+    /// Map entries which don't exist yet are inserted.
+    ///
+    /// See [`NamedAbiValue::get_path`] for the path syntax.
+    pub fn set_path(&mut self, path: &str, value: AbiValue) -> Result<()> {
+        let Some(mut segments) = parse_path(path) else {
+            anyhow::bail!(AbiError::InvalidPath(path.into()));
+        };
+        let not_found = || AbiError::PathNotFound(path.into());
+
+        let last = segments.pop();
+        let mut target = self;
+        for segment in segments {
+            target = match target.get_child_mut(segment) {
+                Some(target) => target,
+                None => anyhow::bail!(not_found()),
+            };
+        }
+
+        let target = match last {
+            None => target,
+            Some(segment @ PathSegment::Field(_)) => match target.get_child_mut(segment) {
+                Some(target) => target,
+                None => anyhow::bail!(not_found()),
+            },
+            Some(PathSegment::Index(index)) => match target.unwrap_implicit_mut() {
+                Self::Array(_, items) | Self::FixedArray(_, items) => {
+                    match index.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+                        Some(target) => target,
+                        None => anyhow::bail!(not_found()),
+                    }
+                }
+                Self::Map(key_ty, value_ty, entries) => {
+                    let Some(key) = parse_map_key(*key_ty, index) else {
+                        anyhow::bail!(not_found());
+                    };
+                    let value = ok!(value.coerce_to(value_ty));
+                    entries.insert(key, value);
+                    return Ok(());
+                }
+                _ => anyhow::bail!(not_found()),
+            },
+        };
+
+        *target = ok!(value.coerce_to(&target.get_type()));
+        Ok(())
+    }
+
+    fn get_child(&self, segment: PathSegment<'_>) -> Option<&Self> {
+        match (self.unwrap_implicit(), segment) {
+            (Self::Tuple(items), PathSegment::Field(name)) => items
+                .iter()
+                .find(|item| item.name.as_ref() == name)
+                .map(|item| &item.value),
+            (Self::Array(_, items) | Self::FixedArray(_, items), PathSegment::Index(index)) => {
+                items.get(index.parse::<usize>().ok()?)
+            }
+            (Self::Map(key_ty, _, entries), PathSegment::Index(key)) => {
+                entries.get(&parse_map_key(*key_ty, key)?)
+            }
+            _ => None,
+        }
+    }
+
+    fn get_child_mut(&mut self, segment: PathSegment<'_>) -> Option<&mut Self> {
+        match (self.unwrap_implicit_mut(), segment) {
+            (Self::Tuple(items), PathSegment::Field(name)) => items
+                .iter_mut()
+                .find(|item| item.name.as_ref() == name)
+                .map(|item| &mut item.value),
+            (Self::Array(_, items) | Self::FixedArray(_, items), PathSegment::Index(index)) => {
+                items.get_mut(index.parse::<usize>().ok()?)
+            }
+            (Self::Map(key_ty, _, entries), PathSegment::Index(key)) => {
+                entries.get_mut(&parse_map_key(*key_ty, key)?)
+            }
+            _ => None,
+        }
+    }
+
+    fn unwrap_implicit(&self) -> &Self {
+        let mut value = self;
+        loop {
+            value = match value {
+                Self::Ref(value) | Self::Optional(_, Some(value)) => value,
+                _ => return value,
+            };
+        }
+    }
+
+    fn unwrap_implicit_mut(&mut self) -> &mut Self {
+        let mut value = self;
+        loop {
+            value = match value {
+                Self::Ref(value) | Self::Optional(_, Some(value)) => value,
+                _ => return value,
+            };
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum PathSegment<'a> {
+    Field(&'a str),
+    Index(&'a str),
+}
+
+fn parse_path(path: &str) -> Option<Vec<PathSegment<'_>>> {
+    let mut segments = Vec::new();
+    if path.is_empty() {
+        return Some(segments);
+    }
+
+    for (i, part) in path.split('.').enumerate() {
+        let (name, mut rest) = match part.find('[') {
+            Some(pos) => part.split_at(pos),
+            None => (part, ""),
+        };
+
+        // Only the first part can start with an index
+        if !name.is_empty() {
+            segments.push(PathSegment::Field(name));
+        } else if i > 0 || rest.is_empty() {
+            return None;
+        }
+
+        while !rest.is_empty() {
+            let (index, tail) = rest.strip_prefix('[')?.split_once(']')?;
+            if index.is_empty() {
+                return None;
+            }
+            segments.push(PathSegment::Index(index));
+            rest = tail;
+        }
+    }
+
+    Some(segments)
+}
+
+fn parse_map_key(ty: PlainAbiType, key: &str) -> Option<PlainAbiValue> {
+    let value = match ty {
+        PlainAbiType::Uint(bits) => {
+            let value = BigUint::from_str(key).ok()?;
+            if value.bits() > bits as u64 {
+                return None;
+            }
+            PlainAbiValue::Uint(bits, value)
+        }
+        PlainAbiType::Int(bits) => {
+            let value = BigInt::from_str(key).ok()?;
+            let magnitude = value.magnitude();
+            let fits = match bits.checked_sub(1) {
+                Some(value_bits) => {
+                    magnitude.bits() <= value_bits as u64
+                        // Min value for the specified number of bits
+                        || value.sign() == Sign::Minus
+                            && magnitude.bits() == bits as u64
+                            && magnitude.trailing_zeros() == Some(value_bits as u64)
+                }
+                None => magnitude.bits() == 0,
+            };
+            if !fits {
+                return None;
+            }
+            PlainAbiValue::Int(bits, value)
+        }
+        PlainAbiType::Bool => PlainAbiValue::Bool(key.parse().ok()?),
+        PlainAbiType::Address => PlainAbiValue::Address(Box::new(IntAddr::from_str(key).ok()?)),
+    };
+    Some(value)
+}
@@ -1,11 +1,11 @@
 use std::collections::BTreeMap;
 use std::num::NonZeroU8;
 
-use num_bigint::{BigUint, Sign};
+use num_bigint::{BigInt, BigUint};
 
 use crate::abi::{
-    AbiHeader, AbiHeaderType, AbiType, AbiValue, AbiVersion, NamedAbiValue, PlainAbiType,
-    PlainAbiValue,
+    AbiBytesLayout, AbiHeader, AbiHeaderType, AbiType, AbiValue, AbiVersion, NamedAbiType,
+    NamedAbiValue, PlainAbiType, PlainAbiValue,
 };
 use crate::cell::{
     Cell, CellBuilder, CellContext, CellSlice, CellSliceSize, CellTreeStats, Store, MAX_BIT_LEN,
@@ -20,8 +20,18 @@ use crate::prelude::CellFamily;
 impl NamedAbiValue {
     /// Tries to store multiple values into a new builder according to the specified ABI version.
     pub fn tuple_to_builder(items: &[Self], version: AbiVersion) -> Result<CellBuilder, Error> {
+        Self::tuple_to_builder_ext(items, version, AbiBytesLayout::for_version(version))
+    }
+
+    /// Tries to store multiple values into a new builder according to the specified ABI version
+    /// using the specified layout of bytes.
+    pub fn tuple_to_builder_ext(
+        items: &[Self],
+        version: AbiVersion,
+        bytes_layout: AbiBytesLayout,
+    ) -> Result<CellBuilder, Error> {
         let context = &mut Cell::empty_context();
-        let mut serializer = AbiSerializer::new(version);
+        let mut serializer = AbiSerializer::new(version).with_bytes_layout(bytes_layout);
         for item in items {
             serializer.reserve_value(&item.value);
         }
@@ -50,8 +60,18 @@ impl NamedAbiValue {
 impl AbiValue {
     /// Tries to store multiple values into a new builder according to the specified ABI version.
     pub fn tuple_to_builder(values: &[Self], version: AbiVersion) -> Result<CellBuilder, Error> {
+        Self::tuple_to_builder_ext(values, version, AbiBytesLayout::for_version(version))
+    }
+
+    /// Tries to store multiple values into a new builder according to the specified ABI version
+    /// using the specified layout of bytes.
+    pub fn tuple_to_builder_ext(
+        values: &[Self],
+        version: AbiVersion,
+        bytes_layout: AbiBytesLayout,
+    ) -> Result<CellBuilder, Error> {
         let context = &mut Cell::empty_context();
-        let mut serializer = AbiSerializer::new(version);
+        let mut serializer = AbiSerializer::new(version).with_bytes_layout(bytes_layout);
         for value in values {
             serializer.reserve_value(value);
         }
@@ -68,8 +88,18 @@ impl AbiValue {
 
     /// Tries to store this value into a new builder according to the specified ABI version.
     pub fn make_builder(&self, version: AbiVersion) -> Result<CellBuilder, Error> {
+        self.make_builder_ext(version, AbiBytesLayout::for_version(version))
+    }
+
+    /// Tries to store this value into a new builder according to the specified ABI version
+    /// using the specified layout of bytes.
+    pub fn make_builder_ext(
+        &self,
+        version: AbiVersion,
+        bytes_layout: AbiBytesLayout,
+    ) -> Result<CellBuilder, Error> {
         let context = &mut Cell::empty_context();
-        let mut serializer = AbiSerializer::new(version);
+        let mut serializer = AbiSerializer::new(version).with_bytes_layout(bytes_layout);
         serializer.reserve_value(self);
         ok!(serializer.write_value(self, context));
         serializer.finalize(context)
@@ -223,8 +253,53 @@ impl AbiValue {
     }
 }
 
+impl AbiBytesLayout {
+    /// Builds a chain of cells with the specified bytes.
+    pub fn build_cell(
+        &self,
+        mut data: &[u8],
+        context: &mut dyn CellContext,
+    ) -> Result<Cell, Error> {
+        if self.chunk_size == 0 || self.chunk_size > Self::MAX_CHUNK_SIZE {
+            return Err(Error::InvalidData);
+        }
+
+        let chunk_size = self.chunk_size as usize;
+        let mut len = data.len();
+
+        // NOTE: Chunks are stored starting from the end of the data
+        let mut bytes_per_builder = if self.remainder_first {
+            std::cmp::min(chunk_size, len)
+        } else {
+            match len % chunk_size {
+                0 => chunk_size,
+                x => x,
+            }
+        };
+
+        let mut result = CellBuilder::new();
+        while len > 0 {
+            len -= bytes_per_builder;
+            let (head, tail) = data.split_at(len);
+            data = head;
+
+            if result.bit_len() > 0 {
+                let child = ok!(std::mem::take(&mut result).build_ext(context));
+                ok!(result.store_reference(child));
+            }
+
+            ok!(result.store_raw(tail, (bytes_per_builder * 8) as u16));
+
+            bytes_per_builder = std::cmp::min(chunk_size, len);
+        }
+
+        result.build_ext(context)
+    }
+}
+
 pub(crate) struct AbiSerializer {
     version: AbiVersion,
+    bytes_layout: AbiBytesLayout,
     current: CellSliceSize,
     remaining_total: CellTreeStats,
     stack: Vec<CellBuilder>,
@@ -234,12 +309,18 @@ impl AbiSerializer {
     pub fn new(version: AbiVersion) -> Self {
         Self {
             version,
+            bytes_layout: AbiBytesLayout::for_version(version),
             current: CellSliceSize::ZERO,
             remaining_total: CellTreeStats::ZERO,
             stack: Vec::new(),
         }
     }
 
+    pub fn with_bytes_layout(mut self, bytes_layout: AbiBytesLayout) -> Self {
+        self.bytes_layout = bytes_layout;
+        self
+    }
+
     pub fn add_offset(&mut self, offset: CellSliceSize) {
         self.current += offset;
     }
@@ -290,7 +371,7 @@ impl AbiSerializer {
     }
 
     fn begin_child(&self) -> Self {
-        Self::new(self.version)
+        Self::new(self.version).with_bytes_layout(self.bytes_layout)
     }
 
     fn require_builder(&mut self, value_size: CellSliceSize) -> &mut CellBuilder {
@@ -300,19 +381,12 @@ impl AbiSerializer {
 
         self.remaining_total -= value_size;
 
-        let remaining = CellSliceSize::MAX - self.current;
-
-        let store_inline = if value_size.bits > remaining.bits || value_size.refs > remaining.refs {
-            false
-        } else if value_size.refs > 0 && value_size.refs == remaining.refs {
-            self.version.major != 1
-                && self.remaining_total.cell_count == 0
-                && self.remaining_total.bit_count + value_size.bits as u64 <= remaining.bits as u64
-        } else {
-            true
-        };
-
-        if !store_inline {
+        if !store_inline(
+            self.version,
+            self.current,
+            &self.remaining_total,
+            value_size,
+        ) {
             self.current = CellSliceSize::ZERO;
             self.stack.push(CellBuilder::new());
         }
@@ -357,10 +431,10 @@ impl AbiSerializer {
         c: &mut dyn CellContext,
     ) -> Result<(), Error> {
         match value {
-            AbiValue::Uint(n, value) => self.write_int(*n, Sign::Plus, value),
-            AbiValue::Int(n, value) => self.write_int(*n, value.sign(), value.magnitude()),
-            AbiValue::VarUint(n, value) => self.write_varint(*n, Sign::Plus, value),
-            AbiValue::VarInt(n, value) => self.write_varint(*n, value.sign(), value.magnitude()),
+            AbiValue::Uint(n, value) => self.write_uint(*n, value),
+            AbiValue::Int(n, value) => self.write_int(*n, value),
+            AbiValue::VarUint(n, value) => self.write_varint(*n, &value.to_bytes_be()),
+            AbiValue::VarInt(n, value) => self.write_varint(*n, &value.to_signed_bytes_be()),
             AbiValue::Bool(value) => self.write_bool(*value),
             AbiValue::Cell(value) => self.write_cell(value),
             AbiValue::Address(value) => self.write_address(value),
@@ -387,15 +461,17 @@ impl AbiSerializer {
         Ok(())
     }
 
-    fn write_int(&mut self, bits: u16, sign: Sign, value: &BigUint) -> Result<(), Error> {
+    fn write_uint(&mut self, bits: u16, value: &BigUint) -> Result<(), Error> {
         let target = self.require_builder(CellSliceSize { bits, refs: 0 });
-        write_int(bits, sign, value, target)
+        target.store_biguint(value, bits)
     }
 
-    fn write_varint(&mut self, size: NonZeroU8, sign: Sign, value: &BigUint) -> Result<(), Error> {
-        let is_negative = sign == Sign::Minus;
-        let bytes = to_signed_bytes_be(is_negative, value);
+    fn write_int(&mut self, bits: u16, value: &BigInt) -> Result<(), Error> {
+        let target = self.require_builder(CellSliceSize { bits, refs: 0 });
+        target.store_bigint(value, bits, true)
+    }
 
+    fn write_varint(&mut self, size: NonZeroU8, bytes: &[u8]) -> Result<(), Error> {
         let max_value_size = size.get() - 1;
         if bytes.len() > max_value_size as usize {
             return Err(Error::IntOverflow);
@@ -414,7 +490,7 @@ impl AbiSerializer {
         });
 
         ok!(target.store_small_uint(bytes.len() as u8, len_bits));
-        target.store_raw(&bytes, value_bits)
+        target.store_raw(bytes, value_bits)
     }
 
     fn write_bool(&mut self, value: bool) -> Result<(), Error> {
@@ -451,37 +527,10 @@ impl AbiSerializer {
         tokens.store_into(target, &mut Cell::empty_context())
     }
 
-    fn write_bytes(&mut self, mut data: &[u8], context: &mut dyn CellContext) -> Result<(), Error> {
-        const MAX_BYTES_PER_BUILDER: usize = (MAX_BIT_LEN / 8) as usize;
-        let mut len = data.len();
-
-        let mut bytes_per_builder = if self.version.major > 1 {
-            match len % MAX_BYTES_PER_BUILDER {
-                0 => MAX_BYTES_PER_BUILDER,
-                x => x,
-            }
-        } else {
-            std::cmp::min(MAX_BYTES_PER_BUILDER, len)
-        };
-
-        let mut result = CellBuilder::new();
-        while len > 0 {
-            len -= bytes_per_builder;
-            let (head, tail) = data.split_at(len);
-            data = head;
-
-            if result.bit_len() > 0 {
-                let child = ok!(std::mem::take(&mut result).build_ext(context));
-                ok!(result.store_reference(child));
-            }
-
-            ok!(result.store_raw(tail, (bytes_per_builder * 8) as u16));
-
-            bytes_per_builder = std::cmp::min(MAX_BYTES_PER_BUILDER, len);
-        }
-
+    fn write_bytes(&mut self, data: &[u8], context: &mut dyn CellContext) -> Result<(), Error> {
+        let cell = ok!(self.bytes_layout.build_cell(data, context));
         let target = self.require_builder(CellSliceSize { bits: 0, refs: 1 });
-        target.store_reference(ok!(result.build()))
+        target.store_reference(cell)
     }
 
     fn write_array(
@@ -643,41 +692,73 @@ impl AbiSerializer {
     }
 }
 
-fn to_signed_bytes_be(is_negative: bool, value: &BigUint) -> Vec<u8> {
-    #[inline]
-    fn is_zero(value: &u8) -> bool {
-        *value == 0
-    }
+impl NamedAbiType {
+    /// Computes the worst-case layout of a serialized tuple of the specified types.
+    ///
+    /// Returns sizes reserved in each cell of the chain
+    /// (excluding the reference to the next cell).
+    ///
+    /// NOTE: The layout is exact starting from ABI 2.2 where all values have a fixed size.
+    pub fn compute_tuple_layout(items: &[Self], version: AbiVersion) -> Vec<CellSliceSize> {
+        fn visit(
+            ty: &AbiType,
+            version: AbiVersion,
+            remaining_total: &mut CellTreeStats,
+            layout: &mut Vec<CellSliceSize>,
+        ) {
+            let value_size = match ty {
+                AbiType::Tuple(items) => {
+                    for item in items.iter() {
+                        visit(&item.ty, version, remaining_total, layout);
+                    }
+                    return;
+                }
+                // Same as reserved in `write_array`
+                AbiType::FixedArray(..) => CellSliceSize { bits: 2, refs: 1 },
+                ty => {
+                    let size = ty.max_size();
+                    CellSliceSize {
+                        bits: size.bit_count as u16,
+                        refs: size.cell_count as u8,
+                    }
+                }
+            };
 
-    #[inline]
-    pub fn twos_complement_le(digits: &mut [u8]) {
-        let mut carry = true;
-        for digit in digits {
-            *digit = !*digit;
-            if carry {
-                let (d, c) = digit.overflowing_add(1);
-                *digit = d;
-                carry = c;
+            *remaining_total -= value_size;
+
+            match layout.last_mut() {
+                Some(current) if store_inline(version, *current, remaining_total, value_size) => {
+                    *current += value_size;
+                }
+                _ => layout.push(value_size),
             }
         }
-    }
 
-    fn negative_to_signed_bytes_be(value: &BigUint) -> Vec<u8> {
-        let mut bytes = value.to_bytes_le();
-        let last_byte = bytes.last().cloned().unwrap_or(0);
-        if last_byte > 0x7f && !(last_byte == 0x80 && bytes.iter().rev().skip(1).all(is_zero)) {
-            // msb used by magnitude, extend by 1 byte
-            bytes.push(0);
+        let mut remaining_total = items.iter().map(|item| item.ty.max_size()).sum();
+        let mut layout = Vec::new();
+        for item in items {
+            visit(&item.ty, version, &mut remaining_total, &mut layout);
         }
-        twos_complement_le(&mut bytes);
-        bytes.reverse();
-        bytes
+        layout
     }
+}
 
-    if is_negative {
-        negative_to_signed_bytes_be(value)
+fn store_inline(
+    version: AbiVersion,
+    current: CellSliceSize,
+    remaining_total: &CellTreeStats,
+    value_size: CellSliceSize,
+) -> bool {
+    let remaining = CellSliceSize::MAX - current;
+
+    if value_size.bits > remaining.bits || value_size.refs > remaining.refs {
+        false
+    } else if value_size.refs > 0 && value_size.refs == remaining.refs {
+        version.major != 1
+            && remaining_total.cell_count == 0
+            && remaining_total.bit_count + value_size.bits as u64 <= remaining.bits as u64
     } else {
-        value.to_bytes_be()
+        true
     }
 }
 
@@ -706,8 +787,8 @@ impl Store for InlineOrRef<'_> {
 impl Store for PlainAbiValue {
     fn store_into(&self, builder: &mut CellBuilder, f: &mut dyn CellContext) -> Result<(), Error> {
         match self {
-            Self::Uint(bits, value) => write_int(*bits, Sign::Plus, value, builder),
-            Self::Int(bits, value) => write_int(*bits, value.sign(), value.magnitude(), builder),
+            Self::Uint(bits, value) => builder.store_biguint(value, *bits),
+            Self::Int(bits, value) => builder.store_bigint(value, *bits, true),
             Self::Bool(bit) => builder.store_bit(*bit),
             Self::Address(address) => address.store_into(builder, f),
         }
@@ -715,51 +796,11 @@ impl Store for PlainAbiValue {
 }
 
 impl AbiVersion {
-    fn use_max_size(&self) -> bool {
+    pub(crate) fn use_max_size(&self) -> bool {
         self >= &Self::V2_2
     }
 }
 
-fn write_int(
-    bits: u16,
-    sign: Sign,
-    value: &BigUint,
-    target: &mut CellBuilder,
-) -> Result<(), Error> {
-    if value.bits() > bits as u64 {
-        return Err(Error::IntOverflow);
-    }
-
-    let is_negative = sign == Sign::Minus;
-    let bytes = to_signed_bytes_be(is_negative, value);
-    let value_bits = (bytes.len() * 8) as u16;
-
-    if bytes.is_empty() {
-        target.store_zeros(bits)
-    } else if bits > value_bits {
-        let diff = bits - value_bits;
-        ok!(if is_negative {
-            target.store_ones(diff)
-        } else {
-            target.store_zeros(diff)
-        });
-        target.store_raw(&bytes, value_bits)
-    } else {
-        let bits_offset = value_bits - bits;
-        let bytes_offset = (bits_offset / 8) as usize;
-        let rem = bits_offset % 8;
-
-        let (left, right) = bytes.split_at(bytes_offset + 1);
-        if let Some(left) = left.last() {
-            ok!(target.store_small_uint(*left << rem, 8 - rem));
-        }
-        if !right.is_empty() {
-            ok!(target.store_raw(right, (right.len() * 8) as u16));
-        }
-        Ok(())
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
@@ -805,6 +846,20 @@ mod tests {
             AbiValue::Uint(16, BigUint::from(u16::MAX as u32 + 1)).make_cell(AbiVersion::V2_2),
             Err(Error::IntOverflow)
         );
+
+        // Signed values must fit into the two's complement range
+        assert_eq!(
+            AbiValue::Int(8, BigInt::from(i8::MAX as i32 + 1)).make_cell(AbiVersion::V2_2),
+            Err(Error::IntOverflow)
+        );
+        assert_eq!(
+            AbiValue::Int(8, BigInt::from(i8::MIN as i32 - 1)).make_cell(AbiVersion::V2_2),
+            Err(Error::IntOverflow)
+        );
+        assert_eq!(
+            AbiValue::Int(8, BigInt::from(u8::MAX)).make_cell(AbiVersion::V2_2),
+            Err(Error::IntOverflow)
+        );
     }
 
     #[test]
@@ -858,6 +913,12 @@ mod tests {
 
             check_encoding(v, AbiValue::varuint(128, 0u8));
             check_encoding(v, AbiValue::varuint(128, BigUint::from(1u8) << (126 * 8)));
+
+            check_encoding(v, AbiValue::varint(4, 0));
+            check_encoding(v, AbiValue::varint(4, 0x80));
+            check_encoding(v, AbiValue::varint(4, -0x80));
+            check_encoding(v, AbiValue::varint(4, i32::MAX >> 8));
+            check_encoding(v, AbiValue::varint(4, i32::MIN >> 8));
         }
     }
 
@@ -1031,6 +1092,48 @@ mod tests {
         }
     }
 
+    #[test]
+    fn encode_bytes_with_layout() {
+        fn chunks(cell: Cell) -> Vec<u16> {
+            let mut result = Vec::new();
+            let mut cell = cell.reference(0).unwrap();
+            loop {
+                result.push(cell.bit_len() / 8);
+                match cell.reference(0) {
+                    Some(child) => cell = child,
+                    None => break result,
+                }
+            }
+        }
+
+        let bytes = AbiValue::Bytes(Bytes::from(vec![0xaa; 10]));
+        for v in VX_X {
+            // Default layout
+            let layout = AbiBytesLayout::for_version(v);
+            assert_eq!(
+                bytes.make_builder_ext(v, layout).unwrap().build().unwrap(),
+                bytes.make_cell(v).unwrap()
+            );
+
+            for (remainder_first, expected) in [(false, [4, 4, 2]), (true, [2, 4, 4])] {
+                let layout = AbiBytesLayout {
+                    chunk_size: 4,
+                    remainder_first,
+                };
+                let cell = bytes.make_builder_ext(v, layout).unwrap().build().unwrap();
+                assert_eq!(chunks(cell), expected);
+            }
+
+            for chunk_size in [0, AbiBytesLayout::MAX_CHUNK_SIZE + 1] {
+                let layout = AbiBytesLayout {
+                    chunk_size,
+                    remainder_first: false,
+                };
+                assert_eq!(bytes.make_builder_ext(v, layout), Err(Error::InvalidData));
+            }
+        }
+    }
+
     #[test]
     fn encode_string() {
         for v in VX_X {
@@ -3,7 +3,9 @@ use std::ops::Deref;
 use smallvec::SmallVec;
 
 use super::BocTag;
-use crate::cell::{Cell, CellContext, CellDescriptor, CellParts, LevelMask, MAX_REF_COUNT};
+use crate::cell::{
+    AbsentCell, Cell, CellContext, CellDescriptor, CellParts, HashBytes, LevelMask, MAX_REF_COUNT,
+};
 use crate::util::{read_be_u32_fast, read_be_u64_fast, unlikely, ArrayVec};
 
 #[cfg(feature = "stats")]
@@ -28,17 +30,45 @@ impl Options {
     }
 }
 
-/// Parsed BOC header.
-pub struct BocHeader<'a> {
+/// Resolver for cells which are not included into the BOC.
+pub trait AbsentCellResolver {
+    /// Returns a full cell with the specified representation hash,
+    /// or `None` to keep an [`AbsentCell`] placeholder.
+    fn resolve(&mut self, repr_hash: &HashBytes, repr_depth: u16) -> Option<Cell>;
+}
+
+impl<F> AbsentCellResolver for F
+where
+    F: FnMut(&HashBytes, u16) -> Option<Cell>,
+{
+    #[inline]
+    fn resolve(&mut self, repr_hash: &HashBytes, repr_depth: u16) -> Option<Cell> {
+        self(repr_hash, repr_depth)
+    }
+}
+
+/// BOC header reader.
+///
+/// Parses and validates the header and root indices, and gives access
+/// to the raw cell records without assembling them into a tree.
+pub struct BocReader<'a> {
+    data: &'a [u8],
     ref_size: usize,
-    cells: SmallVec<[&'a [u8]; CELLS_ON_STACK]>,
+    cell_count: usize,
+    absent_count: usize,
+    allow_absent: bool,
     roots: SmallVec<[u32; ROOTS_ON_STACK]>,
+    cells_offset: usize,
+    total_cells_size: u64,
 }
 
-impl<'a> BocHeader<'a> {
-    /// Decodes boc info from the specified bytes.
-    pub fn decode(data: &'a [u8], options: &Options) -> Result<Self, Error> {
-        let mut reader = BocReader::new(data.len());
+impl<'a> BocReader<'a> {
+    /// Parses the BOC header from the specified bytes.
+    pub fn new(data: &'a [u8], options: &Options) -> Result<Self, Error> {
+        inc_counter!("everscale_types_boc_decode_total");
+        inc_counter!("everscale_types_boc_decode_bytes"; data.len());
+
+        let mut reader = Cursor::new(data.len());
 
         // 4 bytes - tag
         // 1 byte - flags
@@ -129,9 +159,6 @@ impl<'a> BocHeader<'a> {
         if unlikely(root_count.saturating_add(absent_count) > cell_count) {
             return Err(Error::TooManyRootCells);
         }
-        if unlikely(absent_count > 0) {
-            return Err(Error::AbsentCellsNotSupported);
-        }
         if let Some(min_roots) = options.min_roots {
             if unlikely(root_count < min_roots) {
                 return Err(Error::TooFewRootCells);
@@ -140,7 +167,7 @@ impl<'a> BocHeader<'a> {
         if unlikely(root_count > options.max_roots.unwrap_or(MAX_ROOTS)) {
             return Err(Error::TooManyRootCells);
         }
-        debug_assert!(absent_count == 0 && (1..=MAX_ROOTS).contains(&root_count));
+        debug_assert!((1..=MAX_ROOTS).contains(&root_count));
 
         // SAFETY: we have already requested at least {ref_size}*3+{offset_size}
         // and {ref_size} is in range 1..=8
@@ -199,36 +226,12 @@ impl<'a> BocHeader<'a> {
             reader.advance(cell_count * offset_size);
         }
 
-        #[cfg(not(fuzzing))]
-        let cells_start_offset = reader.offset;
-
-        let mut cells = SmallVec::with_capacity(cell_count);
-
-        let data_ptr = data.as_ptr();
-        for _ in 0..cell_count {
-            // SAFETY: there are manual bounds checks for bytes offset
-            let start_ptr = unsafe { data_ptr.add(reader.offset) };
-            let total_len = ok!(CellParts::read_raw_cell_from_ptr(
-                start_ptr,
-                reader.len - reader.offset,
-                ref_size
-            ));
-            reader.advance(total_len);
-
-            // SAFETY: We have already requested {total_len} bytes
-            let cell = unsafe { std::slice::from_raw_parts(start_ptr, total_len) };
-            cells.push(cell);
-        }
-
-        // Check that `total_cells_size` is correct
-        #[cfg(not(fuzzing))]
-        if (cells_start_offset as u64).saturating_add(total_cells_size) != reader.offset as u64 {
-            return Err(Error::InvalidTotalSize);
-        }
+        let cells_offset = reader.offset;
 
         // Verify checksum if specified
         #[cfg(not(fuzzing))]
         if has_crc {
+            reader.advance(total_cells_size as usize);
             if unlikely(!reader.require(4)) {
                 return Err(Error::UnexpectedEof);
             }
@@ -241,14 +244,170 @@ impl<'a> BocHeader<'a> {
         }
 
         Ok(Self {
+            data,
             ref_size,
-            cells,
+            cell_count,
+            absent_count,
+            allow_absent: false,
             roots,
+            cells_offset,
+            total_cells_size,
+        })
+    }
+
+    /// Sets whether to accept cells which are stored externally.
+    ///
+    /// Absent cells are rejected by default.
+    pub fn allow_absent(mut self, allow: bool) -> Self {
+        self.allow_absent = allow;
+        self
+    }
+
+    /// Cell index size in bytes. Guaranteed to be 4 at max.
+    pub fn ref_size(&self) -> usize {
+        self.ref_size
+    }
+
+    /// The number of cells in the BOC.
+    pub fn cell_count(&self) -> usize {
+        self.cell_count
+    }
+
+    /// The number of absent cells in the BOC.
+    pub fn absent_count(&self) -> usize {
+        self.absent_count
+    }
+
+    /// Root indices.
+    pub fn roots(&self) -> &[u32] {
+        &self.roots
+    }
+
+    /// Returns an iterator over the raw cell records.
+    ///
+    /// Cells are ordered so that children always follow their parents.
+    pub fn cells(&self) -> RawCells<'a> {
+        RawCells {
+            data: &self.data[self.cells_offset..],
+            ref_size: self.ref_size,
+            remaining: self.cell_count,
+            total_cells_size: self.total_cells_size,
+        }
+    }
+}
+
+/// An iterator over the raw cell records of the BOC.
+///
+/// This struct is created by the [`cells`] method on [`BocReader`].
+///
+/// [`cells`]: BocReader::cells
+pub struct RawCells<'a> {
+    data: &'a [u8],
+    ref_size: usize,
+    remaining: usize,
+    total_cells_size: u64,
+}
+
+impl<'a> Iterator for RawCells<'a> {
+    type Item = Result<&'a [u8], Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+
+        let cell = match CellParts::read_raw_cell(&mut self.data, self.ref_size) {
+            Ok(cell) => cell,
+            Err(e) => {
+                self.remaining = 0;
+                return Some(Err(e));
+            }
+        };
+        self.remaining -= 1;
+
+        // Check that `total_cells_size` is correct
+        #[cfg(not(fuzzing))]
+        {
+            let Some(total_cells_size) = self.total_cells_size.checked_sub(cell.len() as u64)
+            else {
+                self.remaining = 0;
+                return Some(Err(Error::InvalidTotalSize));
+            };
+            self.total_cells_size = total_cells_size;
+
+            if unlikely(self.remaining == 0 && total_cells_size != 0) {
+                return Some(Err(Error::InvalidTotalSize));
+            }
+        }
+
+        Some(Ok(cell))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining))
+    }
+}
+
+/// Parsed BOC header.
+pub struct BocHeader<'a> {
+    ref_size: usize,
+    cells: SmallVec<[&'a [u8]; CELLS_ON_STACK]>,
+    roots: SmallVec<[u32; ROOTS_ON_STACK]>,
+}
+
+impl<'a> BocHeader<'a> {
+    /// Decodes boc info from the specified bytes.
+    pub fn decode(data: &'a [u8], options: &Options) -> Result<Self, Error> {
+        let reader = ok!(BocReader::new(data, options));
+        Self::from_reader(reader)
+    }
+
+    /// Reads all raw cells from the parsed header.
+    pub fn from_reader(reader: BocReader<'a>) -> Result<Self, Error> {
+        if unlikely(reader.absent_count > 0 && !reader.allow_absent) {
+            return Err(Error::AbsentCellsNotSupported);
+        }
+
+        let mut cells = SmallVec::with_capacity(reader.cell_count);
+        let mut absent_found = 0;
+        for cell in reader.cells() {
+            let cell = ok!(cell);
+            absent_found += CellDescriptor::new([cell[0], cell[1]]).is_absent_raw() as usize;
+            cells.push(cell);
+        }
+
+        if unlikely(absent_found != reader.absent_count) {
+            return Err(if reader.allow_absent {
+                Error::AbsentCountMismatch
+            } else {
+                Error::AbsentCellsNotSupported
+            });
+        }
+
+        Ok(Self {
+            ref_size: reader.ref_size,
+            cells,
+            roots: reader.roots,
         })
     }
 
     /// Assembles cell tree from slices using the specified cell context.
+    ///
+    /// Absent cells are kept as [`AbsentCell`] placeholders.
     pub fn finalize(&self, context: &mut dyn CellContext) -> Result<ProcessedCells, Error> {
+        self.finalize_with_resolver(context, &mut |_: &HashBytes, _: u16| None)
+    }
+
+    /// Assembles cell tree from slices using the specified cell context.
+    ///
+    /// Absent cells are replaced with cells from the resolver
+    /// or kept as [`AbsentCell`] placeholders.
+    pub fn finalize_with_resolver(
+        &self,
+        context: &mut dyn CellContext,
+        resolver: &mut dyn AbsentCellResolver,
+    ) -> Result<ProcessedCells, Error> {
         let ref_size = self.ref_size;
         let cell_count = self.cells.len() as u32;
 
@@ -259,6 +418,12 @@ impl<'a> BocHeader<'a> {
         }
 
         for raw_cell in self.cells().iter().rev() {
+            if unlikely(CellDescriptor::new([raw_cell[0], raw_cell[1]]).is_absent_raw()) {
+                let cell = ok!(resolve_absent_cell(raw_cell, resolver));
+                res.push(cell);
+                continue;
+            }
+
             // SAFETY: it is safe to construct `CellParts` from a `read_raw_cell_from_ptr` output
             let ctx = unsafe {
                 ok!(CellParts::from_raw_cell(
@@ -292,6 +457,24 @@ impl<'a> BocHeader<'a> {
     }
 }
 
+fn resolve_absent_cell(
+    raw_cell: &[u8],
+    resolver: &mut dyn AbsentCellResolver,
+) -> Result<Cell, Error> {
+    let Ok(data) = <[u8; 34]>::try_from(&raw_cell[2..]) else {
+        return Err(Error::InvalidCell);
+    };
+    let placeholder = AbsentCell::from_data(data).into_cell();
+
+    let repr_hash = placeholder.repr_hash();
+    let repr_depth = placeholder.repr_depth();
+    match resolver.resolve(repr_hash, repr_depth) {
+        Some(cell) if cell.repr_hash() == repr_hash && cell.repr_depth() == repr_depth => Ok(cell),
+        Some(_) => Err(Error::InvalidCell),
+        None => Ok(placeholder),
+    }
+}
+
 /// Array of processed cells.
 pub struct ProcessedCells(SmallVec<[Cell; CELLS_ON_STACK]>);
 
@@ -304,12 +487,12 @@ impl ProcessedCells {
 
 /// Wrapper around indexed bytes slice access
 /// to eliminate bounds check.
-struct BocReader {
+struct Cursor {
     len: usize,
     offset: usize,
 }
 
-impl BocReader {
+impl Cursor {
     #[inline(always)]
     const fn new(len: usize) -> Self {
         Self { len, offset: 0 }
@@ -367,7 +550,7 @@ impl BocReader {
     }
 }
 
-impl Deref for BocReader {
+impl Deref for Cursor {
     type Target = usize;
 
     #[inline(always)]
@@ -391,9 +574,37 @@ impl<'a> CellParts<'a> {
         cell_count: u32,
         ref_size: usize,
     ) -> Result<Self, Error> {
+        Self::from_raw_cell_ext(raw_cell, ref_size, |child_index| {
+            if child_index >= cell_count {
+                return Err(Error::InvalidRef);
+            }
+            match cells.get((cell_count - child_index - 1) as usize) {
+                Some(child) => Ok(child.clone()),
+                None => Err(Error::InvalidRefOrder),
+            }
+        })
+    }
+
+    /// Reads cell parts from the raw cell slice, resolving children
+    /// by their indices with the specified function.
+    ///
+    /// # Safety
+    ///
+    /// The same as for [`CellParts::from_raw_cell`].
+    pub(crate) unsafe fn from_raw_cell_ext<F>(
+        raw_cell: &'a [u8],
+        ref_size: usize,
+        mut get_child: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut(u32) -> Result<Cell, Error>,
+    {
         let raw_cell_ptr = raw_cell.as_ptr();
 
         let descriptor = CellDescriptor::new(*(raw_cell_ptr as *const [u8; 2]));
+        if unlikely(descriptor.is_absent_raw()) {
+            return Err(Error::AbsentCellsNotSupported);
+        }
         let data_len = descriptor.byte_len() as usize;
 
         let mut data_ptr = raw_cell_ptr.add(2);
@@ -425,14 +636,7 @@ impl<'a> CellParts<'a> {
 
         for _ in 0..descriptor.reference_count() {
             let child_index = read_be_u32_fast(data_ptr, ref_size);
-            if child_index >= cell_count {
-                return Err(Error::InvalidRef);
-            }
-
-            let child = match cells.get((cell_count - child_index - 1) as usize) {
-                Some(child) => child.clone(),
-                None => return Err(Error::InvalidRefOrder),
-            };
+            let child = ok!(get_child(child_index));
 
             {
                 let child = child.as_ref();
@@ -486,8 +690,35 @@ impl<'a> CellParts<'a> {
         // and the `CellDescriptor` layout is fixed by `repr(C)`
         let descriptor = CellDescriptor::new(unsafe { *(bytes_ptr as *const [u8; 2]) });
 
-        if unlikely(descriptor.is_absent()) {
-            return Err(Error::AbsentCellsNotSupported);
+        let total_len = ok!(Self::raw_cell_len(descriptor, ref_size));
+        if unlikely(bytes_len < total_len) {
+            return Err(Error::UnexpectedEof);
+        }
+
+        if !descriptor.is_absent_raw() && descriptor.byte_len() > 0 && !descriptor.is_aligned() {
+            let refs_len = descriptor.reference_count() as usize * ref_size;
+            // SAFETY: we have already requested {total_len} bytes,
+            // data is followed by the references
+            let byte_with_tag = unsafe { *bytes_ptr.add(total_len - refs_len - 1) };
+            if unlikely(byte_with_tag & 0x7f == 0) {
+                return Err(Error::UnnormalizedCell);
+            }
+        }
+
+        Ok(total_len)
+    }
+
+    /// Returns the total length of the raw cell with the specified descriptor.
+    pub(crate) fn raw_cell_len(
+        descriptor: CellDescriptor,
+        ref_size: usize,
+    ) -> Result<usize, Error> {
+        if unlikely(descriptor.is_absent_raw()) {
+            // Absent cells contain only the representation hash and depth
+            if unlikely(descriptor.d2 != AbsentCell::DESCRIPTOR.d2) {
+                return Err(Error::InvalidCell);
+            }
+            return Ok(2 + descriptor.byte_len() as usize);
         }
 
         // 0b11111111 -> 0b01111111 + 1 = 0b10000000 = byte len 128, max bit len = 1023
@@ -508,30 +739,18 @@ impl<'a> CellParts<'a> {
             data_offset = (32 + 2) * (level as usize + 1);
         }
 
-        let total_len = 2 + data_offset + data_len + ref_count * ref_size;
-        if unlikely(bytes_len < total_len) {
-            return Err(Error::UnexpectedEof);
-        }
-
-        if data_len > 0 && !descriptor.is_aligned() {
-            // SAFETY: we have already requested 2+{data_len} bytes
-            let byte_with_tag = unsafe { *bytes_ptr.add(2 + data_offset + data_len - 1) };
-            if unlikely(byte_with_tag & 0x7f == 0) {
-                return Err(Error::UnnormalizedCell);
-            }
-        }
-
-        Ok(total_len)
+        Ok(2 + data_offset + data_len + ref_count * ref_size)
     }
 }
 
 const CELLS_ON_STACK: usize = 16;
 const ROOTS_ON_STACK: usize = 2;
 
-const MAX_ROOTS: usize = 32;
+pub(super) const MAX_ROOTS: usize = 32;
 
 /// Error type for BOC decoding related errors.
 #[derive(Debug, Copy, Clone, thiserror::Error)]
+#[non_exhaustive]
 pub enum Error {
     /// EOF encountered during another operation.
     #[error("unexpected EOF")]
@@ -557,9 +776,12 @@ pub enum Error {
     /// The number of roots in BOC is greater than expected.
     #[error("too many root cells")]
     TooManyRootCells,
-    /// Absent cells are legacy therefore not supported.
+    /// Absent cells are not allowed by the decoder options.
     #[error("absent cells are not supported")]
     AbsentCellsNotSupported,
+    /// The number of absent cells differs from the header.
+    #[error("absent cell count mismatch")]
+    AbsentCountMismatch,
     /// The number of roots in BOC is less than expected.
     #[error("too few root cells")]
     TooFewRootCells,
@@ -584,4 +806,16 @@ pub enum Error {
     /// Crc mismatch.
     #[error("invalid checksum")]
     InvalidChecksum,
+    /// Failed to read data from the source.
+    #[error("io error: {0}")]
+    Io(std::io::ErrorKind),
+}
+
+impl From<std::io::Error> for Error {
+    fn from(e: std::io::Error) -> Self {
+        match e.kind() {
+            std::io::ErrorKind::UnexpectedEof => Self::UnexpectedEof,
+            kind => Self::Io(kind),
+        }
+    }
 }
@@ -1,11 +1,15 @@
 //! BOC (Bag Of Cells) implementation.
 
-use crate::cell::{Cell, CellBuilder, CellContext, CellFamily, DynCell, Load, Store};
+use sha2::Digest;
+
+use crate::cell::{Cell, CellBuilder, CellContext, CellFamily, DynCell, HashBytes, Load, Store};
 
 /// BOC decoder implementation.
 pub mod de;
 /// BOC encoder implementation.
 pub mod ser;
+/// Streaming BOC decoder implementation.
+pub mod stream;
 
 /// BOC file magic number.
 #[derive(Default, Copy, Clone, Eq, PartialEq)]
@@ -106,6 +110,30 @@ impl Boc {
         encode_impl(cell.as_ref())
     }
 
+    /// Encodes the specified cell tree as BOC and
+    /// computes the file hash of the encoded bytes.
+    ///
+    /// See [`compute_file_hash`] for details.
+    ///
+    /// [`compute_file_hash`]: Boc::compute_file_hash
+    pub fn encode_with_file_hash<T>(cell: T) -> (Vec<u8>, HashBytes)
+    where
+        T: AsRef<DynCell>,
+    {
+        let data = Self::encode(cell);
+        let file_hash = Self::compute_file_hash(&data);
+        (data, file_hash)
+    }
+
+    /// Computes the file hash of the encoded BOC (sha256 of its bytes).
+    ///
+    /// NOTE: Unlike the root hash, the file hash depends on the BOC
+    /// serialization flags, so it must be computed from the exact bytes
+    /// which are stored or sent over the network.
+    pub fn compute_file_hash<T: AsRef<[u8]>>(data: T) -> HashBytes {
+        HashBytes(sha2::Sha256::digest(data.as_ref()).into())
+    }
+
     /// Encodes a pair of cell trees as BOC.
     pub fn encode_pair<T1, T2>((cell1, cell2): (T1, T2)) -> Vec<u8>
     where
@@ -182,6 +210,74 @@ impl Boc {
         Err(de::Error::RootCellNotFound)
     }
 
+    /// Decodes a cell tree from the reader using an empty cell context.
+    ///
+    /// Unlike [`Boc::decode`], the BOC is read incrementally and is never
+    /// buffered as a whole. See [`StreamDecoder`] for details.
+    ///
+    /// [`StreamDecoder`]: stream::StreamDecoder
+    pub fn decode_streaming<R: std::io::Read>(reader: R) -> Result<Cell, de::Error> {
+        Self::decode_streaming_ext(reader, &mut Cell::empty_context())
+    }
+
+    /// Decodes a cell tree from the reader using the specified cell context.
+    pub fn decode_streaming_ext<R: std::io::Read>(
+        reader: R,
+        context: &mut dyn CellContext,
+    ) -> Result<Cell, de::Error> {
+        let decoder = ok!(stream::StreamDecoder::new(
+            reader,
+            &de::Options::exact(1),
+            context
+        ));
+        match ok!(decoder.finish()).into_iter().next() {
+            Some(root) => Ok(root),
+            None => Err(de::Error::RootCellNotFound),
+        }
+    }
+
+    /// Encodes the specified cell tree as BOC into the async writer.
+    #[cfg(feature = "async")]
+    pub async fn encode_async<T, W>(cell: T, writer: &mut W) -> std::io::Result<()>
+    where
+        T: AsRef<DynCell>,
+        W: tokio::io::AsyncWrite + Unpin + ?Sized,
+    {
+        ser::BocHeader::<ahash::RandomState>::new(cell.as_ref())
+            .encode_async(writer)
+            .await
+    }
+
+    /// Decodes a cell tree from the async reader using an empty cell context.
+    ///
+    /// See [`AsyncStreamDecoder`] for details.
+    ///
+    /// [`AsyncStreamDecoder`]: stream::AsyncStreamDecoder
+    #[cfg(feature = "async")]
+    pub async fn decode_async<R>(reader: R) -> Result<Cell, de::Error>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        Self::decode_async_ext(reader, &mut Cell::empty_context()).await
+    }
+
+    /// Decodes a cell tree from the async reader using the specified cell context.
+    #[cfg(feature = "async")]
+    pub async fn decode_async_ext<R>(
+        reader: R,
+        context: &mut (dyn CellContext + Send),
+    ) -> Result<Cell, de::Error>
+    where
+        R: tokio::io::AsyncRead + Unpin,
+    {
+        let decoder =
+            ok!(stream::AsyncStreamDecoder::new(reader, &de::Options::exact(1), context).await);
+        match ok!(decoder.finish().await).into_iter().next() {
+            Some(root) => Ok(root),
+            None => Err(de::Error::RootCellNotFound),
+        }
+    }
+
     /// Decodes a pair of cell trees using the specified cell context.
     pub fn decode_pair_ext(
         data: &[u8],
@@ -208,6 +304,33 @@ impl Boc {
         Err(de::Error::RootCellNotFound)
     }
 
+    /// Decodes a cell tree which may contain absent cells
+    /// using the specified cell context.
+    ///
+    /// Absent cells are replaced with cells from the resolver
+    /// or kept as [`AbsentCell`] placeholders.
+    ///
+    /// [`AbsentCell`]: crate::cell::AbsentCell
+    pub fn decode_with_resolver(
+        data: &[u8],
+        context: &mut dyn CellContext,
+        resolver: &mut dyn de::AbsentCellResolver,
+    ) -> Result<Cell, de::Error> {
+        use self::de::*;
+
+        let reader = ok!(de::BocReader::new(data, &Options::exact(1)));
+        let header = ok!(de::BocHeader::from_reader(reader.allow_absent(true)));
+
+        if let Some(&root) = header.roots().first() {
+            let cells = ok!(header.finalize_with_resolver(context, resolver));
+            if let Some(root) = cells.get(root) {
+                return Ok(root);
+            }
+        }
+
+        Err(de::Error::RootCellNotFound)
+    }
+
     /// Serializes cell into an encoded BOC (as base64 for human readable serializers).
     #[cfg(feature = "serde")]
     pub fn serialize<S, T>(cell: &T, serializer: S) -> Result<S::Ok, S::Error>
@@ -492,6 +615,309 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn boc_file_hash() {
+        assert_eq!(
+            Boc::compute_file_hash([]),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+                .parse::<HashBytes>()
+                .unwrap()
+        );
+
+        let cell = CellBuilder::build_from((0xdeadbeafu32, Cell::empty_cell())).unwrap();
+        let (data, file_hash) = Boc::encode_with_file_hash(&cell);
+        assert_eq!(data, Boc::encode(&cell));
+        assert_eq!(file_hash, Boc::compute_file_hash(&data));
+        assert_ne!(&file_hash, cell.repr_hash());
+
+        // File hash depends on the serialization flags
+        let mut data_with_crc = Vec::new();
+        ser::BocHeader::<ahash::RandomState>::new(cell.as_ref())
+            .with_crc(true)
+            .encode(&mut data_with_crc);
+        assert_ne!(Boc::compute_file_hash(data_with_crc), file_hash);
+    }
+
+    #[test]
+    fn boc_decoding_steps() {
+        let child = CellBuilder::build_from(0xdeadbeafu32).unwrap();
+        let root = CellBuilder::build_from((child.clone(), child, 123u8)).unwrap();
+        let boc = Boc::encode(&root);
+
+        let reader = de::BocReader::new(&boc, &de::Options::exact(1)).unwrap();
+        assert_eq!(reader.cell_count(), 2);
+        assert_eq!(reader.absent_count(), 0);
+        assert_eq!(reader.roots(), &[0]);
+        let ref_size = reader.ref_size();
+
+        // Raw cells are ordered from parents to children
+        let raw_cells = reader.cells().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(raw_cells.len(), 2);
+        assert_eq!(raw_cells[1], &[0x00, 0x08, 0xde, 0xad, 0xbe, 0xaf]);
+
+        let header = de::BocHeader::from_reader(reader).unwrap();
+        assert_eq!(header.cells(), raw_cells.as_slice());
+
+        let cells = header.finalize(&mut Cell::empty_context()).unwrap();
+        assert_eq!(cells.get(0).unwrap().as_ref(), root.as_ref());
+
+        // Invalid total cells size
+        let mut invalid = boc.clone();
+        invalid.push(0);
+        invalid[4 + 2 + 3 * ref_size] += 1;
+        let reader = de::BocReader::new(&invalid, &de::Options::exact(1)).unwrap();
+        assert!(matches!(
+            reader.cells().last(),
+            Some(Err(de::Error::InvalidTotalSize))
+        ));
+    }
+
+    #[test]
+    fn boc_with_absent_cells() {
+        use crate::cell::{AbsentCell, CellDescriptor, HashBytes};
+        use crate::error::Error;
+
+        let child = CellBuilder::build_from((0xdeadbeafu32, Cell::empty_cell())).unwrap();
+        let root = CellBuilder::build_from((child.clone(), 123u8)).unwrap();
+
+        // Replace the child with a placeholder
+        let absent = AbsentCell::new(child.repr_hash(), child.repr_depth()).into_cell();
+        assert_eq!(absent.repr_hash(), child.repr_hash());
+        assert!(matches!(absent.as_slice(), Err(Error::AbsentCellAccess)));
+        assert_eq!(absent.descriptor().d1, CellDescriptor::IS_EXOTIC_MASK);
+        assert_eq!(absent.reference_count(), 0);
+        assert!(absent.references().next().is_none());
+        assert!(!absent.descriptor().is_merkle());
+
+        let partial = CellBuilder::build_from((absent, 123u8)).unwrap();
+        assert_eq!(partial.repr_hash(), root.repr_hash());
+
+        let boc = Boc::encode(&partial);
+        let reader = de::BocReader::new(&boc, &de::Options::exact(1)).unwrap();
+        assert_eq!(reader.absent_count(), 1);
+        // Only the serialized descriptor has the reference count `7`
+        let raw_absent = reader.cells().map(Result::unwrap).last().unwrap();
+        assert_eq!(&raw_absent[..2], &[0x0f, AbsentCell::DESCRIPTOR.d2]);
+        assert!(matches!(
+            de::BocHeader::from_reader(reader),
+            Err(de::Error::AbsentCellsNotSupported)
+        ));
+
+        assert!(matches!(
+            Boc::decode(&boc),
+            Err(de::Error::AbsentCellsNotSupported)
+        ));
+
+        // Keep placeholders
+        let decoded = Boc::decode_with_resolver(
+            &boc,
+            &mut Cell::empty_context(),
+            &mut |_: &HashBytes, _: u16| None,
+        )
+        .unwrap();
+        assert_eq!(decoded.repr_hash(), root.repr_hash());
+        assert!(decoded.reference(0).unwrap().descriptor().is_absent());
+        assert_eq!(Boc::encode(&decoded), boc);
+
+        // Streaming decoder rejects absent cells unless allowed
+        let mut context = Cell::empty_context();
+        let decoder =
+            stream::StreamDecoder::new(boc.as_slice(), &de::Options::exact(1), &mut context)
+                .unwrap();
+        assert!(matches!(
+            decoder.finish(),
+            Err(de::Error::AbsentCellsNotSupported)
+        ));
+
+        let decoder =
+            stream::StreamDecoder::new(boc.as_slice(), &de::Options::exact(1), &mut context)
+                .unwrap()
+                .allow_absent(true);
+        let roots = decoder.finish().unwrap();
+        assert_eq!(roots[0].repr_hash(), root.repr_hash());
+
+        // Resolve placeholders
+        let decoded = Boc::decode_with_resolver(
+            &boc,
+            &mut Cell::empty_context(),
+            &mut |hash: &HashBytes, _: u16| (hash == child.repr_hash()).then(|| child.clone()),
+        )
+        .unwrap();
+        assert_eq!(decoded.as_ref(), root.as_ref());
+        assert_eq!(Boc::encode(&decoded), Boc::encode(&root));
+
+        // Resolved cell must have the same hash
+        assert!(matches!(
+            Boc::decode_with_resolver(
+                &boc,
+                &mut Cell::empty_context(),
+                &mut |_: &HashBytes, _: u16| Some(Cell::empty_cell()),
+            ),
+            Err(de::Error::InvalidCell)
+        ));
+    }
+
+    #[test]
+    fn boc_streaming() {
+        // Tree with shared cells
+        let mut cells = (0..32u32)
+            .map(|i| CellBuilder::build_from(i).unwrap())
+            .collect::<Vec<_>>();
+        while cells.len() > 1 {
+            cells = cells
+                .windows(3)
+                .step_by(2)
+                .map(|chunk| {
+                    let mut builder = CellBuilder::new();
+                    for child in chunk {
+                        builder.store_reference(child.clone()).unwrap();
+                    }
+                    builder.build().unwrap()
+                })
+                .collect();
+        }
+        let root = cells.pop().unwrap();
+
+        let boc = Boc::encode(&root);
+        let decoded = Boc::decode_streaming(boc.as_slice()).unwrap();
+        assert_eq!(decoded.as_ref(), root.as_ref());
+
+        let mut context = Cell::empty_context();
+        let decoder =
+            stream::StreamDecoder::new(boc.as_slice(), &de::Options::exact(1), &mut context)
+                .unwrap();
+        let cell_count = decoder.cell_count();
+        assert_eq!(decoder.roots(), &[0]);
+
+        // All cells are yielded exactly once, children before parents
+        let mut indices = decoder.map(|item| item.unwrap().0).collect::<Vec<_>>();
+        assert_eq!(indices.len(), cell_count);
+        assert_eq!(indices.last(), Some(&0));
+        indices.sort_unstable();
+        indices.dedup();
+        assert_eq!(indices.len(), cell_count);
+
+        // With checksum
+        let mut boc_with_crc = Vec::new();
+        ser::BocHeader::<ahash::RandomState>::new(root.as_ref())
+            .with_crc(true)
+            .encode(&mut boc_with_crc);
+        let decoded = Boc::decode_streaming(boc_with_crc.as_slice()).unwrap();
+        assert_eq!(decoded.as_ref(), root.as_ref());
+
+        let last_byte = boc_with_crc.last_mut().unwrap();
+        *last_byte = !*last_byte;
+        assert!(matches!(
+            Boc::decode_streaming(boc_with_crc.as_slice()),
+            Err(de::Error::InvalidChecksum)
+        ));
+
+        // Truncated data
+        assert!(matches!(
+            Boc::decode_streaming(&boc[..boc.len() - 1]),
+            Err(de::Error::UnexpectedEof)
+        ));
+
+        // Multiple roots
+        let other = CellBuilder::build_from(0xdeadbeafu32).unwrap();
+        let boc = Boc::encode_pair((&root, &other));
+        let mut context = Cell::empty_context();
+        let roots =
+            stream::StreamDecoder::new(boc.as_slice(), &de::Options::exact(2), &mut context)
+                .unwrap()
+                .finish()
+                .unwrap();
+        assert_eq!(roots.len(), 2);
+        assert_eq!(roots[0].as_ref(), root.as_ref());
+        assert_eq!(roots[1].as_ref(), other.as_ref());
+    }
+
+    #[cfg(feature = "async")]
+    #[tokio::test]
+    async fn boc_async() {
+        // Large enough to be written in several chunks
+        let mut cells = (0..1024u32)
+            .map(|i| {
+                let mut builder = CellBuilder::new();
+                builder.store_u32(i).unwrap();
+                builder.store_raw(&[0xaa; 123], 123 * 8).unwrap();
+                builder.build().unwrap()
+            })
+            .collect::<Vec<_>>();
+        while cells.len() > 1 {
+            cells = cells
+                .chunks(4)
+                .map(|chunk| {
+                    let mut builder = CellBuilder::new();
+                    for child in chunk {
+                        builder.store_reference(child.clone()).unwrap();
+                    }
+                    builder.build().unwrap()
+                })
+                .collect();
+        }
+        let root = cells.pop().unwrap();
+
+        let mut boc = Vec::new();
+        Boc::encode_async(&root, &mut boc).await.unwrap();
+        assert_eq!(boc, Boc::encode(&root));
+
+        let decoded = Boc::decode_async(boc.as_slice()).await.unwrap();
+        assert_eq!(decoded.as_ref(), root.as_ref());
+
+        // With checksum
+        let mut expected = Vec::new();
+        ser::BocHeader::<ahash::RandomState>::new(root.as_ref())
+            .with_crc(true)
+            .encode(&mut expected);
+
+        let mut boc_with_crc = Vec::new();
+        ser::BocHeader::<ahash::RandomState>::new(root.as_ref())
+            .with_crc(true)
+            .encode_async(&mut boc_with_crc)
+            .await
+            .unwrap();
+        assert_eq!(boc_with_crc, expected);
+
+        let decoded = Boc::decode_async(boc_with_crc.as_slice()).await.unwrap();
+        assert_eq!(decoded.as_ref(), root.as_ref());
+
+        let last_byte = boc_with_crc.last_mut().unwrap();
+        *last_byte = !*last_byte;
+        assert!(matches!(
+            Boc::decode_async(boc_with_crc.as_slice()).await,
+            Err(de::Error::InvalidChecksum)
+        ));
+
+        // Truncated data
+        assert!(matches!(
+            Boc::decode_async(&boc[..boc.len() / 2]).await,
+            Err(de::Error::UnexpectedEof)
+        ));
+    }
+
+    #[cfg(all(feature = "sync", feature = "async"))]
+    #[test]
+    fn boc_async_is_send() {
+        fn assert_send<T: Send>(_: T) {}
+
+        let boc = Boc::encode(Cell::empty_cell());
+        assert_send(Boc::decode_async(boc.as_slice()));
+
+        let mut context = Cell::empty_context();
+        assert_send(Boc::decode_async_ext(boc.as_slice(), &mut context));
+    }
+
+    #[cfg(feature = "models")]
+    #[test]
+    fn boc_streaming_state() {
+        let boc = include_bytes!("../models/shard/tests/new_zerostate.boc");
+        let expected = Boc::decode(boc).unwrap();
+        let decoded = Boc::decode_streaming(&boc[..]).unwrap();
+        assert_eq!(decoded.repr_hash(), expected.repr_hash());
+        assert_eq!(Boc::encode(&decoded), Boc::encode(&expected));
+    }
+
     #[cfg(feature = "serde")]
     #[derive(::serde::Serialize)]
     struct SerdeWithCellRef<'a> {
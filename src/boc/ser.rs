@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::hash::BuildHasher;
 
 use super::BocTag;
-use crate::cell::{CellDescriptor, DynCell, HashBytes};
+use crate::cell::{AbsentCell, CellDescriptor, DynCell, HashBytes};
 
 /// Intermediate BOC serializer state.
 pub struct BocHeader<'a, S = ahash::RandomState> {
@@ -12,6 +12,7 @@ pub struct BocHeader<'a, S = ahash::RandomState> {
     total_data_size: u64,
     reference_count: u64,
     cell_count: u32,
+    absent_count: u32,
     without_hashes: bool,
     include_crc: bool,
 }
@@ -29,6 +30,7 @@ where
             total_data_size: 0,
             reference_count: 0,
             cell_count: 0,
+            absent_count: 0,
             without_hashes: false,
             include_crc: false,
         };
@@ -65,6 +67,74 @@ where
 
     /// Encodes cell trees into bytes.
     pub fn encode(self, target: &mut Vec<u8>) {
+        let layout = self.compute_layout();
+        target.reserve(layout.total_size as usize);
+
+        let target_len_before = target.len();
+
+        self.encode_header(&layout, target);
+        for cell in self.rev_cells.iter().rev() {
+            self.encode_cell(*cell, layout.ref_size, target);
+        }
+
+        if self.include_crc {
+            let target_len_after = target.len();
+            debug_assert!(target_len_before < target_len_after);
+
+            let crc = crc32c::crc32c(&target[target_len_before..target_len_after]);
+            target.extend_from_slice(&crc.to_le_bytes());
+        }
+
+        debug_assert_eq!(
+            target.len() as u64,
+            target_len_before as u64 + layout.total_size
+        );
+
+        inc_counter!("everscale_types_boc_encode_total");
+        inc_counter!("everscale_types_boc_encode_bytes"; layout.total_size);
+    }
+
+    /// Encodes cell trees into the async writer.
+    ///
+    /// Cells are written in chunks, so the encoded BOC
+    /// is never buffered as a whole.
+    #[cfg(feature = "async")]
+    pub async fn encode_async<W>(self, writer: &mut W) -> std::io::Result<()>
+    where
+        W: tokio::io::AsyncWrite + Unpin + ?Sized,
+    {
+        use tokio::io::AsyncWriteExt;
+
+        const CHUNK_SIZE: usize = 64 * 1024;
+
+        let layout = self.compute_layout();
+
+        let mut crc = 0;
+        let mut buffer = Vec::with_capacity(CHUNK_SIZE * 2);
+
+        self.encode_header(&layout, &mut buffer);
+        for cell in self.rev_cells.iter().rev() {
+            self.encode_cell(*cell, layout.ref_size, &mut buffer);
+            if buffer.len() >= CHUNK_SIZE {
+                crc = crc32c::crc32c_append(crc, &buffer);
+                ok!(writer.write_all(&buffer).await);
+                buffer.clear();
+            }
+        }
+
+        if self.include_crc {
+            crc = crc32c::crc32c_append(crc, &buffer);
+            buffer.extend_from_slice(&crc.to_le_bytes());
+        }
+        ok!(writer.write_all(&buffer).await);
+        ok!(writer.flush().await);
+
+        inc_counter!("everscale_types_boc_encode_total");
+        inc_counter!("everscale_types_boc_encode_bytes"; layout.total_size);
+        Ok(())
+    }
+
+    fn compute_layout(&self) -> EncodingLayout {
         let root_count = self.root_rev_indices.len();
 
         let ref_size = number_of_bytes_to_fit(self.cell_count as u64);
@@ -81,8 +151,6 @@ where
         // is at least 1, and `total_cells_size` is `u64`
         debug_assert!((1..=8).contains(&offset_size));
 
-        let flags = (ref_size as u8) | (u8::from(self.include_crc) * 0b0100_0000);
-
         // 4 bytes - BOC tag
         // 1 byte - flags
         // 1 byte - offset size
@@ -99,55 +167,60 @@ where
             + (offset_size as u64)
             + total_cells_size
             + u64::from(self.include_crc) * 4;
-        target.reserve(total_size as usize);
 
-        let target_len_before = target.len();
+        EncodingLayout {
+            ref_size,
+            offset_size,
+            total_cells_size,
+            total_size,
+        }
+    }
+
+    fn encode_header(&self, layout: &EncodingLayout, target: &mut Vec<u8>) {
+        let ref_size = layout.ref_size;
+        let offset_size = layout.offset_size;
+        let root_count = self.root_rev_indices.len();
+
+        let flags = (ref_size as u8) | (u8::from(self.include_crc) * 0b0100_0000);
 
         target.extend_from_slice(&BocTag::GENERIC);
         target.extend_from_slice(&[flags, offset_size as u8]);
         target.extend_from_slice(&self.cell_count.to_be_bytes()[4 - ref_size..]);
         target.extend_from_slice(&(root_count as u32).to_be_bytes()[4 - ref_size..]);
-        target.extend_from_slice(&[0; 4][4 - ref_size..]);
-        target.extend_from_slice(&total_cells_size.to_be_bytes()[8 - offset_size..]);
+        target.extend_from_slice(&self.absent_count.to_be_bytes()[4 - ref_size..]);
+        target.extend_from_slice(&layout.total_cells_size.to_be_bytes()[8 - offset_size..]);
 
-        for rev_index in self.root_rev_indices {
+        for rev_index in &self.root_rev_indices {
             let root_index = self.cell_count - rev_index - 1;
             target.extend_from_slice(&root_index.to_be_bytes()[4 - ref_size..]);
         }
+    }
 
-        for cell in self.rev_cells.into_iter().rev() {
-            let mut descriptor = cell.descriptor();
-            descriptor.d1 &= !(u8::from(self.without_hashes) * CellDescriptor::STORE_HASHES_MASK);
-            target.extend_from_slice(&[descriptor.d1, descriptor.d2]);
-            if descriptor.store_hashes() {
-                let level_mask = descriptor.level_mask();
-                for level in level_mask {
-                    target.extend_from_slice(cell.hash(level).as_ref());
-                }
-                for level in level_mask {
-                    target.extend_from_slice(&cell.depth(level).to_be_bytes());
-                }
+    fn encode_cell(&self, cell: &DynCell, ref_size: usize, target: &mut Vec<u8>) {
+        let mut descriptor = cell.descriptor();
+        descriptor.d1 &= !(u8::from(self.without_hashes) * CellDescriptor::STORE_HASHES_MASK);
+        if descriptor.is_absent() {
+            descriptor.d1 = AbsentCell::RAW_D1;
+        }
+        target.extend_from_slice(&[descriptor.d1, descriptor.d2]);
+        if descriptor.store_hashes() {
+            let level_mask = descriptor.level_mask();
+            for level in level_mask {
+                target.extend_from_slice(cell.hash(level).as_ref());
             }
-            target.extend_from_slice(cell.data());
-            for child in cell.references() {
-                if let Some(rev_index) = self.rev_indices.get(child.repr_hash()) {
-                    let rev_index = self.cell_count - *rev_index - 1;
-                    target.extend_from_slice(&rev_index.to_be_bytes()[4 - ref_size..]);
-                } else {
-                    debug_assert!(false, "child not found");
-                }
+            for level in level_mask {
+                target.extend_from_slice(&cell.depth(level).to_be_bytes());
             }
         }
-
-        if self.include_crc {
-            let target_len_after = target.len();
-            debug_assert!(target_len_before < target_len_after);
-
-            let crc = crc32c::crc32c(&target[target_len_before..target_len_after]);
-            target.extend_from_slice(&crc.to_le_bytes());
+        target.extend_from_slice(cell.data());
+        for child in cell.references() {
+            if let Some(rev_index) = self.rev_indices.get(child.repr_hash()) {
+                let rev_index = self.cell_count - *rev_index - 1;
+                target.extend_from_slice(&rev_index.to_be_bytes()[4 - ref_size..]);
+            } else {
+                debug_assert!(false, "child not found");
+            }
         }
-
-        debug_assert_eq!(target.len() as u64, target_len_before as u64 + total_size);
     }
 
     fn fill(&mut self, root: &'a DynCell) -> u32 {
@@ -175,13 +248,7 @@ where
             }
         }
 
-        self.rev_indices.insert(cell.repr_hash(), self.cell_count);
-        self.rev_cells.push(cell);
-
-        let descriptor = cell.descriptor();
-        self.total_data_size += descriptor.byte_len_full(self.without_hashes);
-        self.reference_count += descriptor.reference_count() as u64;
-        self.cell_count += 1;
+        self.add_cell(cell);
     }
 
     #[cold]
@@ -197,20 +264,22 @@ where
                     stack.push(cell.references());
                 }
             } else {
-                let cell = children.cell();
-
-                self.rev_indices.insert(cell.repr_hash(), self.cell_count);
-                self.rev_cells.push(cell);
-
-                let descriptor = cell.descriptor();
-                self.total_data_size += descriptor.byte_len_full(self.without_hashes);
-                self.reference_count += descriptor.reference_count() as u64;
-                self.cell_count += 1;
-
+                self.add_cell(children.cell());
                 stack.pop();
             }
         }
     }
+
+    fn add_cell(&mut self, cell: &'a DynCell) {
+        self.rev_indices.insert(cell.repr_hash(), self.cell_count);
+        self.rev_cells.push(cell);
+
+        let descriptor = cell.descriptor();
+        self.total_data_size += descriptor.byte_len_full(self.without_hashes);
+        self.reference_count += descriptor.reference_count() as u64;
+        self.absent_count += descriptor.is_absent() as u32;
+        self.cell_count += 1;
+    }
 }
 
 impl CellDescriptor {
@@ -226,3 +295,10 @@ impl CellDescriptor {
 fn number_of_bytes_to_fit(l: u64) -> usize {
     (8 - l.leading_zeros() / 8) as usize
 }
+
+struct EncodingLayout {
+    ref_size: usize,
+    offset_size: usize,
+    total_cells_size: u64,
+    total_size: u64,
+}
@@ -0,0 +1,676 @@
+use std::collections::VecDeque;
+use std::io::Read;
+
+use smallvec::SmallVec;
+
+use super::de::{Error, Options, MAX_ROOTS};
+use super::BocTag;
+use crate::cell::{AbsentCell, Cell, CellContext, CellDescriptor, CellParts, MAX_REF_COUNT};
+use crate::util::{read_be_u32_fast, read_be_u64_fast, unlikely};
+
+#[cfg(all(feature = "sync", feature = "async"))]
+#[doc(hidden)]
+mod __checks {
+    use super::*;
+
+    assert_impl_all!(AsyncStreamDecoder<'static, &'static [u8]>: Send);
+}
+
+/// Incremental BOC decoder over an arbitrary [`Read`] source.
+///
+/// Unlike [`BocHeader`], the whole BOC is never buffered. Cells are read
+/// one by one and finalized as soon as all their children are available,
+/// so only the cells with unresolved references (and finalized cells which
+/// are still referenced by them) are kept in memory.
+///
+/// The decoder is an iterator over the finalized cells with their indices,
+/// in the order of finalization. Use [`StreamDecoder::finish`] to get
+/// the root cells.
+///
+/// [`BocHeader`]: super::de::BocHeader
+pub struct StreamDecoder<'c, R> {
+    reader: R,
+    state: DecoderState<'c, dyn CellContext + 'c>,
+    buffer: Vec<u8>,
+}
+
+impl<'c, R: Read> StreamDecoder<'c, R> {
+    /// Reads and validates the BOC header from the source.
+    ///
+    /// NOTE: The checksum (if present) is verified only
+    /// after all cells are read.
+    pub fn new(
+        reader: R,
+        options: &Options,
+        context: &'c mut dyn CellContext,
+    ) -> Result<Self, Error> {
+        inc_counter!("everscale_types_boc_decode_total");
+
+        let mut decoder = Self {
+            reader,
+            state: DecoderState::new(options, context),
+            buffer: Vec::new(),
+        };
+        while decoder.state.is_header() {
+            ok!(decoder.step());
+        }
+        Ok(decoder)
+    }
+
+    /// Sets whether to accept cells which are stored externally.
+    ///
+    /// Absent cells are rejected by default.
+    pub fn allow_absent(mut self, allow: bool) -> Self {
+        self.state.allow_absent = allow;
+        self
+    }
+
+    /// The number of cells in the BOC.
+    pub fn cell_count(&self) -> usize {
+        self.state.cell_count as usize
+    }
+
+    /// Root indices.
+    pub fn roots(&self) -> &[u32] {
+        &self.state.roots
+    }
+
+    /// Reads the remaining cells and returns the root cells
+    /// in the order of root indices.
+    pub fn finish(mut self) -> Result<Vec<Cell>, Error> {
+        for item in &mut self {
+            ok!(item);
+        }
+        self.state.root_cells()
+    }
+
+    fn step(&mut self) -> Result<(), Error> {
+        self.buffer.resize(self.state.wanted(), 0);
+        if let Err(e) = self.reader.read_exact(&mut self.buffer) {
+            return Err(Error::from(e));
+        }
+        self.state.feed(&self.buffer)
+    }
+}
+
+impl<R: Read> Iterator for StreamDecoder<'_, R> {
+    type Item = Result<(u32, Cell), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(item) = self.state.ready.pop_front() {
+                return Some(Ok(item));
+            }
+            if self.state.is_done() {
+                return None;
+            }
+            if let Err(e) = self.step() {
+                self.state.fail();
+                return Some(Err(e));
+            }
+        }
+    }
+}
+
+/// Incremental BOC decoder over an [`AsyncRead`] source.
+///
+/// See [`StreamDecoder`] for details.
+///
+/// [`AsyncRead`]: tokio::io::AsyncRead
+#[cfg(feature = "async")]
+pub struct AsyncStreamDecoder<'c, R> {
+    reader: R,
+    state: DecoderState<'c, dyn CellContext + Send + 'c>,
+    buffer: Vec<u8>,
+}
+
+#[cfg(feature = "async")]
+impl<'c, R> AsyncStreamDecoder<'c, R>
+where
+    R: tokio::io::AsyncRead + Unpin,
+{
+    /// Reads and validates the BOC header from the source.
+    ///
+    /// NOTE: The checksum (if present) is verified only
+    /// after all cells are read.
+    pub async fn new(
+        reader: R,
+        options: &Options,
+        context: &'c mut (dyn CellContext + Send),
+    ) -> Result<AsyncStreamDecoder<'c, R>, Error> {
+        inc_counter!("everscale_types_boc_decode_total");
+
+        let mut decoder = Self {
+            reader,
+            state: DecoderState::new(options, context),
+            buffer: Vec::new(),
+        };
+        while decoder.state.is_header() {
+            ok!(decoder.step().await);
+        }
+        Ok(decoder)
+    }
+
+    /// Sets whether to accept cells which are stored externally.
+    ///
+    /// Absent cells are rejected by default.
+    pub fn allow_absent(mut self, allow: bool) -> Self {
+        self.state.allow_absent = allow;
+        self
+    }
+
+    /// The number of cells in the BOC.
+    pub fn cell_count(&self) -> usize {
+        self.state.cell_count as usize
+    }
+
+    /// Root indices.
+    pub fn roots(&self) -> &[u32] {
+        &self.state.roots
+    }
+
+    /// Returns the next finalized cell with its index,
+    /// or `None` if all cells were read.
+    pub async fn next_cell(&mut self) -> Option<Result<(u32, Cell), Error>> {
+        loop {
+            if let Some(item) = self.state.ready.pop_front() {
+                return Some(Ok(item));
+            }
+            if self.state.is_done() {
+                return None;
+            }
+            if let Err(e) = self.step().await {
+                self.state.fail();
+                return Some(Err(e));
+            }
+        }
+    }
+
+    /// Reads the remaining cells and returns the root cells
+    /// in the order of root indices.
+    pub async fn finish(mut self) -> Result<Vec<Cell>, Error> {
+        while let Some(item) = self.next_cell().await {
+            ok!(item);
+        }
+        self.state.root_cells()
+    }
+
+    async fn step(&mut self) -> Result<(), Error> {
+        use tokio::io::AsyncReadExt;
+
+        self.buffer.resize(self.state.wanted(), 0);
+        if let Err(e) = self.reader.read_exact(&mut self.buffer).await {
+            return Err(Error::from(e));
+        }
+        self.state.feed(&self.buffer)
+    }
+}
+
+/// IO-agnostic decoder state which consumes the BOC in chunks
+/// of the requested size.
+struct DecoderState<'c, C: ?Sized> {
+    context: &'c mut C,
+    phase: Phase,
+    crc: u32,
+    min_roots: Option<usize>,
+    max_roots: Option<usize>,
+    allow_absent: bool,
+    has_index: bool,
+    has_crc: bool,
+    supports_multiple_roots: bool,
+    ref_size: usize,
+    offset_size: usize,
+    cell_count: u32,
+    root_count: u32,
+    absent_count: u32,
+    roots: SmallVec<[u32; 2]>,
+    next_index: u32,
+    remaining_size: u64,
+    absent_found: u32,
+    /// Cells which wait for their children.
+    pending: ahash::HashMap<u32, PendingCell>,
+    /// Parents (one for each reference) which wait for the cell.
+    waiters: ahash::HashMap<u32, SmallVec<[u32; 2]>>,
+    /// Finalized cells with the number of remaining uses.
+    finalized: ahash::HashMap<u32, (Cell, usize)>,
+    ready: VecDeque<(u32, Cell)>,
+}
+
+#[derive(Clone, Copy)]
+enum Phase {
+    /// BOC tag, flags and offset size.
+    Tag,
+    /// Cell count, root count, absent count and total cells size.
+    Sizes,
+    /// Root indices.
+    Roots,
+    /// Remaining bytes of the cells index.
+    Index(u64),
+    /// Descriptor bytes of the next cell.
+    Descriptor,
+    /// The rest of the cell with the specified descriptor.
+    Cell(CellDescriptor, usize),
+    /// Checksum bytes.
+    Crc,
+    Done,
+}
+
+impl<'c, C: CellContext + ?Sized> DecoderState<'c, C> {
+    const INDEX_CHUNK_SIZE: u64 = 64 * 1024;
+
+    fn new(options: &Options, context: &'c mut C) -> Self {
+        Self {
+            context,
+            phase: Phase::Tag,
+            crc: 0,
+            min_roots: options.min_roots,
+            max_roots: options.max_roots,
+            allow_absent: false,
+            has_index: false,
+            has_crc: false,
+            supports_multiple_roots: false,
+            ref_size: 0,
+            offset_size: 0,
+            cell_count: 0,
+            root_count: 0,
+            absent_count: 0,
+            roots: SmallVec::new(),
+            next_index: 0,
+            remaining_size: 0,
+            absent_found: 0,
+            pending: Default::default(),
+            waiters: Default::default(),
+            finalized: Default::default(),
+            ready: VecDeque::new(),
+        }
+    }
+
+    fn is_header(&self) -> bool {
+        matches!(
+            self.phase,
+            Phase::Tag | Phase::Sizes | Phase::Roots | Phase::Index(_)
+        )
+    }
+
+    fn is_done(&self) -> bool {
+        matches!(self.phase, Phase::Done)
+    }
+
+    fn fail(&mut self) {
+        self.phase = Phase::Done;
+        self.ready.clear();
+        self.finalized.clear();
+    }
+
+    /// Returns the size of the next chunk for [`DecoderState::feed`].
+    fn wanted(&self) -> usize {
+        match self.phase {
+            // 4 bytes - tag
+            // 1 byte - flags
+            // 1 byte - offset size
+            Phase::Tag => 6,
+            // {ref_size} bytes - cell count
+            // {ref_size} bytes - root count
+            // {ref_size} bytes - absent cell count
+            // {offset_size} bytes - total cells size
+            Phase::Sizes => 3 * self.ref_size + self.offset_size,
+            Phase::Roots => self.root_count as usize * self.ref_size,
+            Phase::Index(remaining) => std::cmp::min(remaining, Self::INDEX_CHUNK_SIZE) as usize,
+            Phase::Descriptor => 2,
+            Phase::Cell(_, len) => len,
+            Phase::Crc => 4,
+            Phase::Done => 0,
+        }
+    }
+
+    fn feed(&mut self, data: &[u8]) -> Result<(), Error> {
+        debug_assert_eq!(data.len(), self.wanted());
+
+        if !matches!(self.phase, Phase::Crc) {
+            self.crc = crc32c::crc32c_append(self.crc, data);
+        }
+
+        match self.phase {
+            Phase::Tag => self.read_tag(data),
+            Phase::Sizes => self.read_sizes(data),
+            Phase::Roots => {
+                for chunk in data.chunks_exact(self.ref_size) {
+                    // SAFETY: chunk contains exactly {ref_size} bytes
+                    let root_index = unsafe { read_be_u32_fast(chunk.as_ptr(), self.ref_size) };
+                    if unlikely(root_index >= self.cell_count) {
+                        return Err(Error::RootOutOfBounds);
+                    }
+                    self.roots.push(root_index);
+                }
+                self.begin_cells();
+                Ok(())
+            }
+            Phase::Index(remaining) => {
+                let remaining = remaining - data.len() as u64;
+                self.phase = if remaining > 0 {
+                    Phase::Index(remaining)
+                } else {
+                    Phase::Descriptor
+                };
+                Ok(())
+            }
+            Phase::Descriptor => {
+                let descriptor = CellDescriptor::new([data[0], data[1]]);
+                let total_len = ok!(CellParts::raw_cell_len(descriptor, self.ref_size));
+                if total_len > 2 {
+                    self.phase = Phase::Cell(descriptor, total_len - 2);
+                    Ok(())
+                } else {
+                    self.read_cell(descriptor, &[])
+                }
+            }
+            Phase::Cell(descriptor, _) => self.read_cell(descriptor, data),
+            Phase::Crc => {
+                if u32::from_le_bytes([data[0], data[1], data[2], data[3]]) != self.crc {
+                    return Err(Error::InvalidChecksum);
+                }
+                self.phase = Phase::Done;
+                Ok(())
+            }
+            Phase::Done => Ok(()),
+        }
+    }
+
+    fn read_tag(&mut self, data: &[u8]) -> Result<(), Error> {
+        let flags = data[4];
+        let offset_size = data[5] as usize;
+
+        let has_cache_bits;
+        let ref_size;
+
+        match BocTag::from_bytes([data[0], data[1], data[2], data[3]]) {
+            Some(BocTag::Indexed) => {
+                self.has_index = true;
+                self.has_crc = false;
+                has_cache_bits = false;
+                ref_size = flags as usize;
+                self.supports_multiple_roots = false;
+            }
+            Some(BocTag::IndexedCrc32) => {
+                self.has_index = true;
+                self.has_crc = true;
+                has_cache_bits = false;
+                ref_size = flags as usize;
+                self.supports_multiple_roots = false;
+            }
+            Some(BocTag::Generic) => {
+                self.has_index = flags & 0b1000_0000 != 0;
+                self.has_crc = flags & 0b0100_0000 != 0;
+                has_cache_bits = flags & 0b0010_0000 != 0;
+                ref_size = (flags & 0b0000_0111) as usize;
+                self.supports_multiple_roots = true;
+            }
+            None => return Err(Error::UnknownBocTag),
+        }
+
+        if unlikely(has_cache_bits && !self.has_index) {
+            return Err(Error::InvalidHeader);
+        }
+        if unlikely(ref_size == 0 || ref_size > std::mem::size_of::<u32>()) {
+            return Err(Error::InvalidRefSize);
+        }
+        if unlikely(offset_size == 0 || offset_size > std::mem::size_of::<usize>()) {
+            return Err(Error::InvalidOffsetSize);
+        }
+
+        self.ref_size = ref_size;
+        self.offset_size = offset_size;
+        self.phase = Phase::Sizes;
+        Ok(())
+    }
+
+    fn read_sizes(&mut self, data: &[u8]) -> Result<(), Error> {
+        let ref_size = self.ref_size;
+
+        // SAFETY: data contains {3 * ref_size + offset_size} bytes
+        let (cell_count, root_count, absent_count, total_cells_size) = unsafe {
+            let ptr = data.as_ptr();
+            (
+                read_be_u32_fast(ptr, ref_size) as usize,
+                read_be_u32_fast(ptr.add(ref_size), ref_size) as usize,
+                read_be_u32_fast(ptr.add(ref_size * 2), ref_size) as usize,
+                read_be_u64_fast(ptr.add(ref_size * 3), self.offset_size),
+            )
+        };
+
+        // Validate root or absent cells
+        if unlikely(root_count == 0) {
+            return Err(Error::RootCellNotFound);
+        }
+        if unlikely(!self.supports_multiple_roots && root_count > 1) {
+            return Err(Error::UnexpectedMultipleRoots);
+        }
+        if unlikely(root_count.saturating_add(absent_count) > cell_count) {
+            return Err(Error::TooManyRootCells);
+        }
+        if let Some(min_roots) = self.min_roots {
+            if unlikely(root_count < min_roots) {
+                return Err(Error::TooFewRootCells);
+            }
+        }
+        if unlikely(root_count > self.max_roots.unwrap_or(MAX_ROOTS)) {
+            return Err(Error::TooManyRootCells);
+        }
+
+        const MIN_CELL_SIZE: u64 = 2; // [d1, d2]
+
+        let min_total_cell_size = (cell_count as u64) * (MIN_CELL_SIZE + ref_size as u64)
+            - (root_count * ref_size) as u64;
+        let max_cell_size = 2 + 4 * (2 + 32) + 128 + (MAX_REF_COUNT as u64) * ref_size as u64;
+        if unlikely(
+            total_cells_size < min_total_cell_size
+                || total_cells_size > (cell_count as u64) * max_cell_size,
+        ) {
+            return Err(Error::InvalidTotalSize);
+        }
+
+        self.cell_count = cell_count as u32;
+        self.root_count = root_count as u32;
+        self.absent_count = absent_count as u32;
+        self.remaining_size = total_cells_size;
+
+        if self.supports_multiple_roots {
+            self.phase = Phase::Roots;
+        } else {
+            self.roots.push(0);
+            self.begin_cells();
+        }
+        Ok(())
+    }
+
+    fn begin_cells(&mut self) {
+        let index_size = self.cell_count as u64 * self.offset_size as u64;
+        self.phase = if self.has_index && index_size > 0 {
+            Phase::Index(index_size)
+        } else {
+            Phase::Descriptor
+        };
+    }
+
+    fn read_cell(&mut self, descriptor: CellDescriptor, rest: &[u8]) -> Result<(), Error> {
+        let index = self.next_index;
+        self.next_index += 1;
+
+        let total_len = 2 + rest.len();
+        let mut raw = Vec::with_capacity(total_len);
+        raw.extend_from_slice(&[descriptor.d1, descriptor.d2]);
+        raw.extend_from_slice(rest);
+
+        // Validate the raw cell
+        ok!(CellParts::read_raw_cell(&mut raw.as_slice(), self.ref_size));
+
+        // Check that `total_cells_size` is correct
+        match self.remaining_size.checked_sub(total_len as u64) {
+            Some(remaining) => self.remaining_size = remaining,
+            None => return Err(Error::InvalidTotalSize),
+        }
+
+        ok!(self.process_cell(index, descriptor, raw));
+
+        if self.next_index < self.cell_count {
+            self.phase = Phase::Descriptor;
+            return Ok(());
+        }
+
+        ok!(self.finish_checks());
+        self.phase = if self.has_crc {
+            Phase::Crc
+        } else {
+            Phase::Done
+        };
+        Ok(())
+    }
+
+    fn process_cell(
+        &mut self,
+        index: u32,
+        descriptor: CellDescriptor,
+        raw: Vec<u8>,
+    ) -> Result<(), Error> {
+        if unlikely(descriptor.is_absent_raw()) {
+            self.absent_found += 1;
+            if unlikely(!self.allow_absent || self.absent_found > self.absent_count) {
+                return Err(if self.allow_absent {
+                    Error::AbsentCountMismatch
+                } else {
+                    Error::AbsentCellsNotSupported
+                });
+            }
+
+            let Ok(data) = <[u8; 34]>::try_from(&raw[2..]) else {
+                return Err(Error::InvalidCell);
+            };
+            return self.on_finalized(index, AbsentCell::from_data(data).into_cell());
+        }
+
+        let ref_count = descriptor.reference_count() as usize;
+        if ref_count == 0 {
+            let cell = ok!(self.finalize_raw(&raw));
+            return self.on_finalized(index, cell);
+        }
+
+        // Children always follow their parents
+        let refs_offset = raw.len() - ref_count * self.ref_size;
+        for i in 0..ref_count {
+            // SAFETY: `raw` contains {ref_count} references of {ref_size} bytes
+            let child_index = unsafe {
+                read_be_u32_fast(
+                    raw.as_ptr().add(refs_offset + i * self.ref_size),
+                    self.ref_size,
+                )
+            };
+            if unlikely(child_index >= self.cell_count) {
+                return Err(Error::InvalidRef);
+            }
+            if unlikely(child_index <= index) {
+                return Err(Error::InvalidRefOrder);
+            }
+            self.waiters.entry(child_index).or_default().push(index);
+        }
+
+        self.pending.insert(
+            index,
+            PendingCell {
+                raw: raw.into_boxed_slice(),
+                missing: ref_count as u8,
+            },
+        );
+        Ok(())
+    }
+
+    fn on_finalized(&mut self, index: u32, cell: Cell) -> Result<(), Error> {
+        let mut stack = vec![(index, cell)];
+        while let Some((index, cell)) = stack.pop() {
+            self.ready.push_back((index, cell.clone()));
+
+            // NOTE: All parents are already known since they precede the cell
+            let parents = self.waiters.remove(&index).unwrap_or_default();
+            let uses = parents.len() + self.roots.contains(&index) as usize;
+            if uses > 0 {
+                self.finalized.insert(index, (cell, uses));
+            }
+
+            for parent in parents {
+                let Some(pending) = self.pending.get_mut(&parent) else {
+                    continue;
+                };
+                pending.missing -= 1;
+                if pending.missing > 0 {
+                    continue;
+                }
+
+                let pending = self.pending.remove(&parent).unwrap();
+                let cell = ok!(self.finalize_raw(&pending.raw));
+                stack.push((parent, cell));
+            }
+        }
+        Ok(())
+    }
+
+    fn finalize_raw(&mut self, raw: &[u8]) -> Result<Cell, Error> {
+        let finalized = &mut self.finalized;
+
+        // SAFETY: `raw` was validated by `CellParts::read_raw_cell`
+        let ctx = unsafe {
+            ok!(CellParts::from_raw_cell_ext(
+                raw,
+                self.ref_size,
+                |child_index| {
+                    let Some((child, uses)) = finalized.get_mut(&child_index) else {
+                        return Err(Error::InvalidRef);
+                    };
+                    let child = child.clone();
+                    // NOTE: Roots have an extra use, so they are never removed
+                    *uses -= 1;
+                    if *uses == 0 {
+                        finalized.remove(&child_index);
+                    }
+                    Ok(child)
+                }
+            ))
+        };
+
+        match self.context.finalize_cell(ctx) {
+            Ok(cell) => Ok(cell),
+            Err(_) => Err(Error::InvalidCell),
+        }
+    }
+
+    fn finish_checks(&mut self) -> Result<(), Error> {
+        if unlikely(self.remaining_size != 0) {
+            return Err(Error::InvalidTotalSize);
+        }
+        if unlikely(self.absent_found != self.absent_count) {
+            return Err(if self.allow_absent {
+                Error::AbsentCountMismatch
+            } else {
+                Error::AbsentCellsNotSupported
+            });
+        }
+        if unlikely(!self.pending.is_empty()) {
+            return Err(Error::InvalidRef);
+        }
+        Ok(())
+    }
+
+    fn root_cells(&mut self) -> Result<Vec<Cell>, Error> {
+        let mut result = Vec::with_capacity(self.roots.len());
+        for root in &self.roots {
+            match self.finalized.get(root) {
+                Some((cell, _)) => result.push(cell.clone()),
+                None => return Err(Error::RootCellNotFound),
+            }
+        }
+        Ok(result)
+    }
+}
+
+struct PendingCell {
+    raw: Box<[u8]>,
+    missing: u8,
+}
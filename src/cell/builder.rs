@@ -8,7 +8,7 @@ use crate::cell::{
     Cell, CellDescriptor, CellImpl, CellSlice, CellType, DynCell, HashBytes, LevelMask,
     MAX_BIT_LEN, MAX_REF_COUNT,
 };
-use crate::error::Error;
+use crate::error::{CapacityError, Error};
 use crate::util::{ArrayVec, Bitstring};
 
 use super::CellFamily;
@@ -111,6 +111,23 @@ impl_store_for_tuples! {
     (t1: T1, t2: T2, t3: T3, t4: T4, t5: T5, t6: T6),
 }
 
+/// Stores all items one after another.
+///
+/// Can be used with erased values (e.g. `[Box<dyn Store>]`)
+/// to store a heterogeneous list of models without generics.
+impl<T: Store> Store for [T] {
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
+        for item in self {
+            ok!(item.store_into(builder, context));
+        }
+        Ok(())
+    }
+}
+
 impl<T: Store> Store for Option<T> {
     #[inline]
     fn store_into(
@@ -172,6 +189,13 @@ impl_primitive_store! {
     HashBytes => |b, v| b.store_u256(v),
 }
 
+impl<const N: usize> Store for [u8; N] {
+    #[inline]
+    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        builder.store_array(self)
+    }
+}
+
 /// Builder for constructing cells with densely packed data.
 pub struct CellBuilder {
     data: [u8; 128],
@@ -327,6 +351,17 @@ impl CellBuilder {
         }
     }
 
+    /// Resets the builder to an empty state so that it can be reused.
+    ///
+    /// Only the used part of the data is cleared.
+    pub fn clear(&mut self) {
+        let used = ((self.bit_len + 7) / 8) as usize;
+        self.data[..used].fill(0);
+        self.bit_len = 0;
+        self.is_exotic = false;
+        self.references = Default::default();
+    }
+
     /// Tries to create a cell builder with the specified data.
     ///
     /// NOTE: if `bits` is greater than `bytes * 8`, pads the value with zeros (as high bits).
@@ -388,6 +423,27 @@ impl CellBuilder {
         self.bit_len + bits <= MAX_BIT_LEN && self.references.len() + refs as usize <= MAX_REF_COUNT
     }
 
+    /// Checks whether there is enough remaining capacity to fit `bits` and `refs`.
+    ///
+    /// Unlike [`CellBuilder::has_capacity`], reports which capacity is exceeded.
+    pub fn check_capacity(&self, bits: u16, refs: u8) -> Result<(), CapacityError> {
+        let available = self.spare_bits_capacity();
+        if bits > available {
+            return Err(CapacityError::Bits {
+                required: bits,
+                available,
+            });
+        }
+        let available = self.spare_refs_capacity();
+        if refs > available {
+            return Err(CapacityError::Refs {
+                required: refs,
+                available,
+            });
+        }
+        Ok(())
+    }
+
     /// Returns whether this cell will be built as an exotic.
     #[inline]
     pub fn is_exotic(&self) -> bool {
@@ -430,8 +486,39 @@ impl CellBuilder {
         Ok(())
     }
 
+    /// Returns the current state of the builder which can be restored later.
+    ///
+    /// See [`CellBuilder::restore`].
+    #[inline]
+    pub fn checkpoint(&self) -> CellBuilderCheckpoint {
+        CellBuilderCheckpoint {
+            bit_len: self.bit_len,
+            ref_count: self.references.len() as u8,
+            is_exotic: self.is_exotic,
+        }
+    }
+
+    /// Removes all data bits and references which were stored
+    /// after the specified checkpoint.
+    ///
+    /// NOTE: The checkpoint must be created from the same builder
+    /// and the builder must not be rewound in between, otherwise
+    /// the restored data is undefined.
+    pub fn restore(&mut self, checkpoint: CellBuilderCheckpoint) -> Result<(), Error> {
+        if checkpoint.bit_len > self.bit_len
+            || checkpoint.ref_count as usize > self.references.len()
+        {
+            return Err(Error::CellUnderflow);
+        }
+
+        ok!(self.rewind(self.bit_len - checkpoint.bit_len));
+        self.references.truncate(checkpoint.ref_count as usize);
+        self.is_exotic = checkpoint.is_exotic;
+        Ok(())
+    }
+
     /// Tries to store the specified number of zero bits in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     pub fn store_zeros(&mut self, bits: u16) -> Result<(), Error> {
         if self.bit_len + bits <= MAX_BIT_LEN {
             self.bit_len += bits;
@@ -442,13 +529,13 @@ impl CellBuilder {
     }
 
     /// Tries to store the specified number of set bits in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     pub fn store_ones(&mut self, bits: u16) -> Result<(), Error> {
         self.store_raw(crate::cell::cell_impl::ALL_ONES_CELL.data(), bits)
     }
 
     /// Tries to store one zero bit in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     pub fn store_bit_zero(&mut self) -> Result<(), Error> {
         let fits = self.bit_len < MAX_BIT_LEN;
         self.bit_len += fits as u16;
@@ -460,7 +547,7 @@ impl CellBuilder {
     }
 
     /// Tries to store one non-zero bit in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     pub fn store_bit_one(&mut self) -> Result<(), Error> {
         if self.bit_len < MAX_BIT_LEN {
             let q = (self.bit_len / 8) as usize;
@@ -474,7 +561,7 @@ impl CellBuilder {
     }
 
     /// Tries to store one bit in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     pub fn store_bit(&mut self, value: bool) -> Result<(), Error> {
         if value {
             self.store_bit_one()
@@ -483,8 +570,48 @@ impl CellBuilder {
         }
     }
 
+    /// Tries to store all bits from the iterator in the cell,
+    /// returning an error if there is not enough remaining capacity.
+    ///
+    /// NOTE: the builder is left unchanged if the iterator yields too many bits.
+    pub fn store_bits_from_iter<I>(&mut self, bits: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = bool>,
+    {
+        let capacity = self.spare_bits_capacity();
+
+        let mut data = [0u8; 128];
+        let mut bit_len = 0u16;
+        for bit in bits {
+            if bit_len >= capacity {
+                return Err(Error::CellOverflow);
+            }
+            data[(bit_len / 8) as usize] |= (bit as u8) << (7 - bit_len % 8);
+            bit_len += 1;
+        }
+
+        store_raw(&mut self.data, &mut self.bit_len, &data, bit_len)
+    }
+
+    /// Tries to store all bits of the bit slice in the cell,
+    /// returning an error if there is not enough remaining capacity.
+    #[cfg(feature = "bitvec")]
+    pub fn store_bitslice<T, O>(
+        &mut self,
+        bits: &bitvec::slice::BitSlice<T, O>,
+    ) -> Result<(), Error>
+    where
+        T: bitvec::store::BitStore,
+        O: bitvec::order::BitOrder,
+    {
+        if bits.len() > self.spare_bits_capacity() as usize {
+            return Err(Error::CellOverflow);
+        }
+        self.store_bits_from_iter(bits.iter().by_vals())
+    }
+
     /// Tries to store `u8` in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     pub fn store_u8(&mut self, value: u8) -> Result<(), Error> {
         if self.bit_len + 8 <= MAX_BIT_LEN {
             let q = (self.bit_len / 8) as usize;
@@ -509,31 +636,31 @@ impl CellBuilder {
     }
 
     /// Tries to store `u16` in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     pub fn store_u16(&mut self, value: u16) -> Result<(), Error> {
         impl_store_uint!(self, value, bytes: 2, bits: 16)
     }
 
     /// Tries to store `u32` in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     pub fn store_u32(&mut self, value: u32) -> Result<(), Error> {
         impl_store_uint!(self, value, bytes: 4, bits: 32)
     }
 
     /// Tries to store `u64` in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     pub fn store_u64(&mut self, value: u64) -> Result<(), Error> {
         impl_store_uint!(self, value, bytes: 8, bits: 64)
     }
 
     /// Tries to store `u128` in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     pub fn store_u128(&mut self, value: u128) -> Result<(), Error> {
         impl_store_uint!(self, value, bytes: 16, bits: 128)
     }
 
     /// Tries to store 32 bytes in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     #[inline]
     pub fn store_u256<T>(&mut self, value: &T) -> Result<(), Error>
     where
@@ -583,7 +710,7 @@ impl CellBuilder {
     }
 
     /// Tries to store `u8` in the cell (but only the specified number of bits),
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     ///
     /// NOTE: if `bits` is greater than **8**, pads the value with zeros (as high bits).
     pub fn store_small_uint(&mut self, mut value: u8, mut bits: u16) -> Result<(), Error> {
@@ -626,7 +753,7 @@ impl CellBuilder {
     }
 
     /// Tries to store `u64` in the cell (but only the specified number of bits),
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     ///
     /// NOTE: if `bits` is greater than **64**, pads the value with zeros (as high bits).
     pub fn store_uint(&mut self, mut value: u64, mut bits: u16) -> Result<(), Error> {
@@ -691,15 +818,122 @@ impl CellBuilder {
     }
 
     /// Tries to store bytes in the cell (but only the specified number of bits),
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     ///
     /// NOTE: if `bits` is greater than `bytes * 8`, pads the value with zeros (as high bits).
     pub fn store_raw(&mut self, value: &[u8], bits: u16) -> Result<(), Error> {
         store_raw(&mut self.data, &mut self.bit_len, value, bits)
     }
 
+    /// Tries to store an unsigned big integer in the cell
+    /// (but only the specified number of bits),
+    /// returning an error if there is not enough remaining capacity.
+    ///
+    /// NOTE: Returns [`Error::IntOverflow`] if the value doesn't fit into `bits`.
+    #[cfg(feature = "bigint")]
+    pub fn store_biguint(&mut self, value: &num_bigint::BigUint, bits: u16) -> Result<(), Error> {
+        if value.bits() > bits as u64 {
+            return Err(Error::IntOverflow);
+        }
+        self.store_bigint_bytes(&value.to_bytes_be(), false, bits)
+    }
+
+    /// Tries to store a big integer in the cell (but only the specified number of bits),
+    /// returning an error if there is not enough remaining capacity.
+    ///
+    /// Signed values are stored in two's complement representation.
+    ///
+    /// NOTE: Returns [`Error::IntOverflow`] if the value doesn't fit into `bits`
+    /// (or if it is negative and `signed` is `false`).
+    #[cfg(feature = "bigint")]
+    pub fn store_bigint(
+        &mut self,
+        value: &num_bigint::BigInt,
+        bits: u16,
+        signed: bool,
+    ) -> Result<(), Error> {
+        let is_negative = value.sign() == num_bigint::Sign::Minus;
+        let magnitude = value.magnitude();
+        if !signed {
+            if is_negative {
+                return Err(Error::IntOverflow);
+            }
+            return self.store_biguint(magnitude, bits);
+        }
+
+        let fits = match bits.checked_sub(1) {
+            Some(value_bits) => {
+                magnitude.bits() <= value_bits as u64
+                    // Min value for the specified number of bits
+                    || is_negative
+                        && magnitude.bits() == bits as u64
+                        && magnitude.trailing_zeros() == Some(value_bits as u64)
+            }
+            None => magnitude.bits() == 0,
+        };
+        if !fits {
+            return Err(Error::IntOverflow);
+        }
+        self.store_bigint_bytes(&value.to_signed_bytes_be(), is_negative, bits)
+    }
+
+    /// Stores the lowest `bits` of the big-endian bytes,
+    /// extending the value with the sign bit if needed.
+    #[cfg(feature = "bigint")]
+    fn store_bigint_bytes(
+        &mut self,
+        bytes: &[u8],
+        is_negative: bool,
+        bits: u16,
+    ) -> Result<(), Error> {
+        if self.bit_len + bits > MAX_BIT_LEN {
+            return Err(Error::CellOverflow);
+        }
+
+        let len = ((bits + 7) / 8) as usize;
+        let mut buffer = [if is_negative { 0xff } else { 0 }; 128];
+        let buffer = &mut buffer[..len];
+
+        let bytes = &bytes[bytes.len().saturating_sub(len)..];
+        buffer[len - bytes.len()..].copy_from_slice(bytes);
+
+        // Align the value to the first bit of the buffer
+        let shift = (len * 8) as u16 - bits;
+        if shift > 0 {
+            for i in 0..len {
+                let next = buffer.get(i + 1).copied().unwrap_or_default();
+                buffer[i] = (buffer[i] << shift) | (next >> (8 - shift));
+            }
+        }
+
+        store_raw(&mut self.data, &mut self.bit_len, buffer, bits)
+    }
+
+    /// Tries to store all bytes of the fixed-size array in the cell,
+    /// returning an error if there is not enough remaining capacity.
+    #[inline]
+    pub fn store_array<const N: usize>(&mut self, value: &[u8; N]) -> Result<(), Error> {
+        if N > MAX_BIT_LEN as usize / 8 {
+            return Err(Error::CellOverflow);
+        }
+        store_raw(&mut self.data, &mut self.bit_len, value, N as u16 * 8)
+    }
+
+    /// Tries to store bytes in the cell (but only the specified number of bits),
+    /// returning the exceeded capacity if there is not enough space.
+    ///
+    /// See [`CellBuilder::store_raw`].
+    pub fn try_store_raw(&mut self, value: &[u8], bits: u16) -> Result<(), CapacityError> {
+        ok!(self.check_capacity(bits, 0));
+        // NOTE: capacity was checked above, so this can't fail
+        store_raw(&mut self.data, &mut self.bit_len, value, bits).map_err(|_| CapacityError::Bits {
+            required: bits,
+            available: self.spare_bits_capacity(),
+        })
+    }
+
     /// Tries to store all data bits of the specified cell in the current cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     #[inline]
     pub fn store_cell_data<T>(&mut self, value: T) -> Result<(), Error>
     where
@@ -717,7 +951,7 @@ impl CellBuilder {
     }
 
     /// Tries to store the remaining slice data in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     #[inline]
     pub fn store_slice_data<'a, T>(&mut self, value: T) -> Result<(), Error>
     where
@@ -751,7 +985,7 @@ impl CellBuilder {
     }
 
     /// Tries to prepend bytes to the cell data (but only the specified number of bits),
-    /// returning `false` if there is not enough capacity.
+    /// returning an error if there is not enough capacity.
     ///
     /// NOTE: if `bits` is greater than `bytes * 8`, pads the value with zeros (as high bits).
     pub fn prepend_raw(&mut self, value: &[u8], bits: u16) -> Result<(), Error> {
@@ -809,47 +1043,9 @@ fn store_raw(
             return Ok(());
         }
 
-        let q = (*bit_len / 8) as usize;
-        let r = *bit_len % 8;
-        // SAFETY: q is in range 0..=127, r is in range 0..=7
-        unsafe {
-            let mut data_ptr = data.as_mut_ptr().add(q);
-            let mut value_ptr = value.as_ptr();
-
-            if r == 0 {
-                let byte_len = ((bits + 7) / 8) as usize;
-                debug_assert!(q + byte_len <= 128);
-                debug_assert!(byte_len <= value.len());
-
-                std::ptr::copy_nonoverlapping(value_ptr, data_ptr, byte_len);
-
-                let bits_r = bits % 8;
-                if bits_r != 0 {
-                    *data_ptr.add(byte_len - 1) &= 0xff << (8 - bits_r);
-                }
-            } else {
-                let byte_len = ((bits + r + 7) / 8) as usize - 1;
-                let value_len = ((bits + 7) / 8) as usize;
-                debug_assert!(q + byte_len <= 128);
-                debug_assert!(byte_len <= value_len && value_len <= value.len());
-
-                let shift = 8 - r;
-                for _ in 0..byte_len {
-                    *data_ptr |= *value_ptr >> r;
-                    data_ptr = data_ptr.add(1);
-                    *data_ptr = *value_ptr << shift;
-                    value_ptr = value_ptr.add(1);
-                }
-                if byte_len < value_len {
-                    *data_ptr |= *value_ptr >> r;
-                }
-
-                let bits_r = (r + bits) % 8;
-                if bits_r != 0 {
-                    *data_ptr &= 0xff << (8 - bits_r);
-                }
-            }
-        }
+        // SAFETY: `bits` is at most `value.len() * 8` and
+        // `bit_len + bits` is at most `MAX_BIT_LEN`
+        unsafe { crate::util::bits::write_bits_unchecked(data, *bit_len, value, bits) };
         *bit_len += bits;
         Ok(())
     } else {
@@ -865,7 +1061,7 @@ impl CellBuilder {
     }
 
     /// Tries to store a child in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     pub fn store_reference(&mut self, cell: Cell) -> Result<(), Error> {
         if self.references.len() < MAX_REF_COUNT {
             // SAFETY: reference count is in the valid range
@@ -876,13 +1072,22 @@ impl CellBuilder {
         }
     }
 
+    /// Tries to store a child in the cell,
+    /// returning the exceeded capacity if there is not enough space.
+    pub fn try_store_reference(&mut self, cell: Cell) -> Result<(), CapacityError> {
+        ok!(self.check_capacity(0, 1));
+        // SAFETY: reference count is in the valid range
+        unsafe { self.references.push(cell) }
+        Ok(())
+    }
+
     /// Sets children of the cell.
     pub fn set_references(&mut self, refs: CellRefsBuilder) {
         self.references = refs.0;
     }
 
     /// Tries to append a builder (its data and references),
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     pub fn store_builder(&mut self, builder: &Self) -> Result<(), Error> {
         if self.bit_len + builder.bit_len <= MAX_BIT_LEN
             && self.references.len() + builder.references.len() <= MAX_REF_COUNT
@@ -897,8 +1102,20 @@ impl CellBuilder {
         }
     }
 
+    /// Tries to append a builder (its data and references),
+    /// returning the exceeded capacity if there is not enough space.
+    pub fn try_store_builder(&mut self, builder: &Self) -> Result<(), CapacityError> {
+        ok!(self.check_capacity(builder.bit_len, builder.reference_count()));
+        // NOTE: capacity was checked above, so this can't fail
+        self.store_builder(builder)
+            .map_err(|_| CapacityError::Bits {
+                required: builder.bit_len,
+                available: self.spare_bits_capacity(),
+            })
+    }
+
     /// Tries to append a cell slice (its data and references),
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     #[inline]
     pub fn store_slice<'a, T>(&mut self, value: T) -> Result<(), Error>
     where
@@ -1042,6 +1259,62 @@ impl CellBuilder {
     }
 }
 
+/// A pool of reusable boxed cell builders.
+///
+/// Can be used in hot loops to avoid initializing and moving
+/// a whole [`CellBuilder`] for each built cell.
+pub struct CellBuilderPool {
+    // NOTE: builders are boxed to make them cheap to move
+    #[allow(clippy::vec_box)]
+    builders: Vec<Box<CellBuilder>>,
+    max_size: usize,
+}
+
+impl CellBuilderPool {
+    /// Creates a pool which keeps at most `max_size` unused builders.
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            builders: Vec::new(),
+            max_size,
+        }
+    }
+
+    /// Returns an empty builder (either reused or a new one).
+    pub fn get(&mut self) -> Box<CellBuilder> {
+        self.builders.pop().unwrap_or_default()
+    }
+
+    /// Clears and returns the builder back to the pool.
+    pub fn put(&mut self, mut builder: Box<CellBuilder>) {
+        if self.builders.len() < self.max_size {
+            builder.clear();
+            self.builders.push(builder);
+        }
+    }
+
+    /// Returns the number of unused builders in the pool.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.builders.len()
+    }
+
+    /// Returns `true` if there are no unused builders in the pool.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.builders.is_empty()
+    }
+}
+
+/// Saved state of the [`CellBuilder`].
+///
+/// Created by [`CellBuilder::checkpoint`] and used by [`CellBuilder::restore`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CellBuilderCheckpoint {
+    bit_len: u16,
+    ref_count: u8,
+    is_exotic: bool,
+}
+
 /// Builder for constructing cell references array.
 ///
 /// Can be used later for [`CellBuilder::set_references`].
@@ -1051,7 +1324,7 @@ pub struct CellRefsBuilder(ArrayVec<Cell, MAX_REF_COUNT>);
 
 impl CellRefsBuilder {
     /// Tries to store a child in the cell,
-    /// returning `false` if there is not enough remaining capacity.
+    /// returning an error if there is not enough remaining capacity.
     pub fn store_reference(&mut self, cell: Cell) -> Result<(), Error> {
         if self.0.len() < MAX_REF_COUNT {
             // SAFETY: reference count is in the valid range
@@ -1228,6 +1501,149 @@ mod tests {
         assert_ne!(cell1.as_ref(), cell3.as_ref());
     }
 
+    #[test]
+    fn capacity_errors() {
+        let mut builder = CellBuilder::new();
+        builder.try_store_raw(&[0xff; 127], 1000).unwrap();
+        assert_eq!(
+            builder.try_store_raw(&[0xff; 4], 32),
+            Err(CapacityError::Bits {
+                required: 32,
+                available: 23
+            })
+        );
+        assert_eq!(builder.bit_len(), 1000);
+
+        for _ in 0..4 {
+            builder.try_store_reference(Cell::empty_cell()).unwrap();
+        }
+        assert_eq!(
+            builder.try_store_reference(Cell::empty_cell()),
+            Err(CapacityError::Refs {
+                required: 1,
+                available: 0
+            })
+        );
+
+        let mut other = CellBuilder::new();
+        other.store_u16(0xabcd).unwrap();
+        other.store_reference(Cell::empty_cell()).unwrap();
+
+        let mut target = CellBuilder::new();
+        target.store_zeros(1000).unwrap();
+        assert_eq!(target.try_store_builder(&other), Ok(()));
+        assert_eq!(
+            target.try_store_builder(&other),
+            Err(CapacityError::Bits {
+                required: 16,
+                available: 7
+            })
+        );
+        assert_eq!(
+            target.check_capacity(7, 4),
+            Err(CapacityError::Refs {
+                required: 4,
+                available: 3
+            })
+        );
+        assert_eq!(
+            Error::from(CapacityError::Refs {
+                required: 1,
+                available: 0
+            }),
+            Error::CellOverflow
+        );
+    }
+
+    #[test]
+    fn store_bits_from_iter() {
+        let mut builder = CellBuilder::new();
+        builder.store_bit_one().unwrap();
+        builder
+            .store_bits_from_iter((0..12).map(|i| i % 3 == 0))
+            .unwrap();
+        assert_eq!(builder.bit_len(), 13);
+        assert_eq!(&builder.raw_data()[..2], &[0b1100_1001, 0b0010_0000]);
+
+        // Builder is unchanged on overflow
+        assert_eq!(
+            builder.store_bits_from_iter(std::iter::repeat(true).take(1011)),
+            Err(Error::CellOverflow)
+        );
+        assert_eq!(builder.bit_len(), 13);
+        builder
+            .store_bits_from_iter(std::iter::repeat(true).take(1010))
+            .unwrap();
+        assert_eq!(builder.spare_bits_capacity(), 0);
+
+        #[cfg(feature = "bitvec")]
+        {
+            use bitvec::prelude::*;
+
+            let bits = bits![u8, Lsb0; 1, 0, 1, 1, 0, 0, 0, 1, 1];
+            let mut builder = CellBuilder::new();
+            builder.store_bitslice(bits).unwrap();
+            assert_eq!(builder.bit_len(), 9);
+            assert_eq!(&builder.raw_data()[..2], &[0b1011_0001, 0b1000_0000]);
+
+            builder.store_zeros(1014).unwrap();
+            assert_eq!(builder.store_bitslice(bits), Err(Error::CellOverflow));
+        }
+    }
+
+    #[test]
+    fn store_erased_values() {
+        let cell = CellBuilder::build_from(0xdeadbeefu32).unwrap();
+        let values: Vec<Box<dyn Store>> = vec![
+            Box::new(0x12u8),
+            Box::new(true),
+            Box::new(Some(0xabcdu16)),
+            Box::new(cell.clone()),
+        ];
+        let erased = CellBuilder::build_from(values.as_slice()).unwrap();
+
+        let mut builder = CellBuilder::new();
+        builder.store_u8(0x12).unwrap();
+        builder.store_bit_one().unwrap();
+        builder.store_bit_one().unwrap();
+        builder.store_u16(0xabcd).unwrap();
+        builder.store_reference(cell).unwrap();
+        assert_eq!(erased.as_ref(), builder.build().unwrap().as_ref());
+
+        let value: &dyn Store = &0x1234u16;
+        let cell = CellBuilder::build_from(value).unwrap();
+        assert_eq!(cell.parse::<u16>().unwrap(), 0x1234);
+    }
+
+    #[test]
+    fn reuse_builders() {
+        let mut builder = CellBuilder::new();
+        builder.store_u32(0xdeadbeef).unwrap();
+        builder.store_bit_one().unwrap();
+        builder.store_reference(Cell::empty_cell()).unwrap();
+        builder.set_exotic(true);
+
+        builder.clear();
+        assert_eq!(builder, CellBuilder::new());
+        assert_eq!(builder.raw_data(), &[0; 128]);
+
+        let mut pool = CellBuilderPool::new(1);
+        let mut first = pool.get();
+        first.store_u64(u64::MAX).unwrap();
+        let expected = first.as_ref().clone().build().unwrap();
+
+        let second = pool.get();
+        pool.put(first);
+        pool.put(second);
+        assert_eq!(pool.len(), 1);
+
+        let mut builder = pool.get();
+        assert!(pool.is_empty());
+        assert_eq!(builder.bit_len(), 0);
+        builder.store_u64(u64::MAX).unwrap();
+        assert_eq!(builder.build().unwrap().as_ref(), expected.as_ref());
+    }
+
     #[test]
     fn compare_builders() {
         let mut a = CellBuilder::new();
@@ -1309,6 +1725,37 @@ mod tests {
         assert_eq!(builder.data, target);
     }
 
+    #[test]
+    fn restore_builder() {
+        let mut builder = CellBuilder::new();
+        builder.store_u16(0xabcd).unwrap();
+        builder.store_reference(Cell::empty_cell()).unwrap();
+        let expected = builder.clone().build().unwrap();
+
+        let checkpoint = builder.checkpoint();
+        builder.store_bit_one().unwrap();
+        builder.store_u32(0xffffffff).unwrap();
+        builder.store_reference(Cell::empty_cell()).unwrap();
+        builder.store_reference(Cell::empty_cell()).unwrap();
+        builder.set_exotic(true);
+
+        builder.restore(checkpoint).unwrap();
+        assert_eq!(builder.checkpoint(), checkpoint);
+        assert_eq!(builder.data[2..], [0u8; 126]);
+        assert_eq!(builder.clone().build().unwrap().as_ref(), expected.as_ref());
+
+        // Restoring the same checkpoint twice is a no-op
+        builder.restore(checkpoint).unwrap();
+        assert_eq!(builder.build().unwrap().as_ref(), expected.as_ref());
+
+        // Checkpoint from a longer builder
+        let mut builder = CellBuilder::new();
+        builder.store_u8(0xff).unwrap();
+        let checkpoint = builder.checkpoint();
+        builder.rewind(8).unwrap();
+        assert_eq!(builder.restore(checkpoint), Err(Error::CellUnderflow));
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)] // takes too long to execute on miri
     fn store_raw() {
@@ -1323,6 +1770,91 @@ mod tests {
         }
     }
 
+    #[test]
+    fn store_array() {
+        let mut builder = CellBuilder::new();
+        builder.store_bit_one().unwrap();
+        builder.store_array(&[0xaa; 64]).unwrap();
+        builder.store_array(&[0x55; 32]).unwrap();
+        builder.store_array(&[0u8; 0]).unwrap();
+        assert_eq!(builder.bit_len(), 1 + 512 + 256);
+
+        assert!(builder.store_array(&[0xff; 32]).is_err());
+        assert!(builder.store_array(&[0xff; 129]).is_err());
+        assert_eq!(builder.bit_len(), 1 + 512 + 256);
+
+        let cell = builder.build().unwrap();
+        let mut slice = cell.as_slice().unwrap();
+        assert!(slice.load_bit().unwrap());
+        assert_eq!(slice.load_array::<64>().unwrap(), [0xaa; 64]);
+        assert_eq!(slice.load_u256().unwrap(), HashBytes([0x55; 32]));
+    }
+
+    #[test]
+    #[cfg(feature = "bigint")]
+    fn store_bigint() {
+        use num_bigint::{BigInt, BigUint};
+
+        for bits in [0u16, 1, 7, 8, 9, 63, 64, 65, 127, 128, 256, 257, 1022] {
+            let one = BigInt::from(1);
+            let (min, max) = match bits {
+                0 => (BigInt::default(), BigInt::default()),
+                _ => (-(&one << (bits - 1)), (&one << (bits - 1)) - 1),
+            };
+            let umax = (BigUint::from(1u32) << bits) - 1u32;
+
+            let mut values = vec![(BigInt::default(), true), (min.clone(), true)];
+            values.push((max.clone(), true));
+            values.push((BigInt::from(umax.clone()), false));
+            if bits > 1 {
+                values.push((BigInt::from(-1), true));
+                values.push((one.clone(), true));
+            }
+
+            for (value, signed) in values {
+                let mut builder = CellBuilder::new();
+                builder.store_bit_one().unwrap();
+                builder.store_bigint(&value, bits, signed).unwrap();
+                assert_eq!(builder.bit_len(), 1 + bits);
+
+                if bits <= 64 {
+                    // Same layout as for the primitive integers
+                    let mut expected = CellBuilder::new();
+                    expected.store_bit_one().unwrap();
+                    let (_, digits) = value.to_u64_digits();
+                    let int = match value.sign() {
+                        num_bigint::Sign::Minus => digits[0].wrapping_neg(),
+                        _ => digits.first().copied().unwrap_or_default(),
+                    };
+                    let int = int & u64::MAX.checked_shr(64 - bits as u32).unwrap_or_default();
+                    expected.store_uint(int, bits).unwrap();
+                    assert_eq!(builder, expected);
+                }
+
+                let cell = builder.build().unwrap();
+                let mut slice = cell.as_slice().unwrap();
+                assert!(slice.load_bit().unwrap());
+                assert_eq!(slice.load_bigint(bits, signed).unwrap(), value);
+                assert!(slice.is_data_empty());
+            }
+
+            // Overflow
+            let mut builder = CellBuilder::new();
+            for (value, signed) in [(&min - 1, true), (&max + 1, true), (-one, false)] {
+                let res = builder.store_bigint(&value, bits, signed);
+                assert_eq!(res, Err(Error::IntOverflow));
+            }
+            let res = builder.store_biguint(&(umax + 1u32), bits);
+            assert_eq!(res, Err(Error::IntOverflow));
+            assert_eq!(builder.bit_len(), 0);
+        }
+
+        let mut builder = CellBuilder::new();
+        builder.store_bit_one().unwrap();
+        let res = builder.store_bigint(&BigInt::default(), 1023, true);
+        assert_eq!(res, Err(Error::CellOverflow));
+    }
+
     #[test]
     fn prepend_raw() {
         let mut builder = CellBuilder::new();
@@ -4,7 +4,8 @@ use std::mem::MaybeUninit;
 #[cfg(feature = "stats")]
 use super::CellTreeStats;
 use super::{
-    Cell, CellDescriptor, CellFamily, CellImpl, DynCell, HashBytes, EMPTY_CELL_HASH, MAX_REF_COUNT,
+    Cell, CellDescriptor, CellFamily, CellImpl, DynCell, HashBytes, LevelMask, EMPTY_CELL_HASH,
+    MAX_REF_COUNT,
 };
 use crate::util::TryAsMut;
 
@@ -292,86 +293,87 @@ impl OrdinaryCellHeader {
     }
 }
 
-impl Drop for OrdinaryCellHeader {
-    fn drop(&mut self) {
-        // Returns the nearest ancestor and its consumed next child.
-        // Returns `None` if no ancestors with children found.
-        #[inline]
-        fn take_ancestor_next_child(parent: Cell) -> Option<(Cell, Cell)> {
-            let mut ancestor = parent;
-            while let Some(ancestor_ref) = ancestor.try_as_mut() {
-                // Try to get the next child from the direct ancestor
-                if let Some(next_child) = ancestor_ref.take_next_child() {
-                    return Some((ancestor, next_child));
-                } else if let Some(grand_ancestor) = ancestor_ref.take_first_child() {
-                    // Drop `ancestor` as it is now a leaf node
-                    drop(ancestor);
-
-                    // Move one level deeper
-                    ancestor = grand_ancestor;
-                } else {
-                    // Break on leaf node
-                    break;
-                }
+// Returns the nearest ancestor and its consumed next child.
+// Returns `None` if no ancestors with children found.
+#[inline]
+fn take_ancestor_next_child(parent: Cell) -> Option<(Cell, Cell)> {
+    let mut ancestor = parent;
+    while let Some(ancestor_ref) = ancestor.try_as_mut() {
+        // Try to get the next child from the direct ancestor
+        if let Some(next_child) = ancestor_ref.take_next_child() {
+            return Some((ancestor, next_child));
+        } else if let Some(grand_ancestor) = ancestor_ref.take_first_child() {
+            // Drop `ancestor` as it is now a leaf node
+            drop(ancestor);
+
+            // Move one level deeper
+            ancestor = grand_ancestor;
+        } else {
+            // Break on leaf node
+            break;
+        }
+    }
+    None
+}
+
+fn main_deep_safe_drop(mut parent: Cell) {
+    // Consume first child from parent.
+    let mut current = 'curr: {
+        if let Some(parent) = parent.try_as_mut() {
+            if let Some(first_child) = parent.take_first_child() {
+                break 'curr first_child;
             }
-            None
         }
+        return;
+    };
 
-        fn main_deep_safe_drop(mut parent: Cell) {
-            // Consume first child from parent.
-            let mut current = 'curr: {
-                if let Some(parent) = parent.try_as_mut() {
-                    if let Some(first_child) = parent.take_first_child() {
-                        break 'curr first_child;
-                    }
+    loop {
+        // If current node is unique
+        if let Some(current_ref) = current.try_as_mut() {
+            // Try to replace its first child with the current parent
+            match current_ref.replace_first_child(parent) {
+                Ok(first_child) => {
+                    // Move one layer lower
+                    parent = current;
+                    current = first_child;
+                    continue;
                 }
-                return;
-            };
+                Err(returned_parent) => {
+                    parent = returned_parent;
 
-            loop {
-                // If current node is unique
-                if let Some(current_ref) = current.try_as_mut() {
-                    // Try to replace its first child with the current parent
-                    match current_ref.replace_first_child(parent) {
-                        Ok(first_child) => {
-                            // Move one layer lower
-                            parent = current;
-                            current = first_child;
-                            continue;
-                        }
-                        Err(returned_parent) => {
-                            parent = returned_parent;
-
-                            // Current node is now a leaf, drop it
-                            drop(current);
-                        }
-                    }
+                    // Current node is now a leaf, drop it
+                    drop(current);
                 }
-
-                // Find the next child
-                let Some((ancestor, child)) = take_ancestor_next_child(parent) else {
-                    return;
-                };
-
-                parent = ancestor;
-                current = child;
             }
         }
 
-        fn deep_drop_impl(cell: &mut Cell) {
-            let Some(cell) = cell.try_as_mut() else {
-                return;
-            };
+        // Find the next child
+        let Some((ancestor, child)) = take_ancestor_next_child(parent) else {
+            return;
+        };
 
-            if let Some(first_child) = cell.take_first_child() {
-                main_deep_safe_drop(first_child);
+        parent = ancestor;
+        current = child;
+    }
+}
 
-                while let Some(next_child) = cell.take_next_child() {
-                    main_deep_safe_drop(next_child);
-                }
-            }
+/// Drops children of the uniquely owned cell without recursion.
+pub(crate) fn deep_drop_impl(cell: &mut Cell) {
+    let Some(cell) = cell.try_as_mut() else {
+        return;
+    };
+
+    if let Some(first_child) = cell.take_first_child() {
+        main_deep_safe_drop(first_child);
+
+        while let Some(next_child) = cell.take_next_child() {
+            main_deep_safe_drop(next_child);
         }
+    }
+}
 
+impl Drop for OrdinaryCellHeader {
+    fn drop(&mut self) {
         let references_ptr = self.references.as_mut_ptr() as *mut Cell;
         debug_assert!(self.descriptor.reference_count() <= MAX_REF_COUNT as u8);
 
@@ -514,6 +516,122 @@ impl CellImpl for LibraryReference {
     }
 }
 
+/// Placeholder for a cell which is stored externally.
+///
+/// Contains only the representation hash and depth of the original cell,
+/// so it can be referenced by other cells without changing their hashes.
+/// The data of this cell is its serialized representation (hash and depth).
+/// Absent cells can't be loaded as slices, and their stats are unknown
+/// so they are counted as a single empty cell.
+///
+/// NOTE: The serialized form contains no level mask, so absent cells
+/// always have level 0 and return the representation hash and depth
+/// for all levels. Cells with a non-zero level (e.g. containing pruned
+/// branches) should not be replaced with them, as it would change
+/// the higher hashes of their parents.
+pub struct AbsentCell {
+    repr_hash: HashBytes,
+    data: [u8; 34],
+}
+
+impl AbsentCell {
+    const BIT_LEN: u16 = (32 + 2) * 8;
+
+    /// Descriptor of the absent cell.
+    ///
+    /// Absent cells have no references and an empty level mask.
+    pub const DESCRIPTOR: CellDescriptor = CellDescriptor::new([
+        CellDescriptor::IS_EXOTIC_MASK | (LevelMask::EMPTY.to_byte() << 5),
+        CellDescriptor::compute_d2(Self::BIT_LEN),
+    ]);
+
+    /// The first descriptor byte of the absent cell in a serialized BOC.
+    ///
+    /// NOTE: Only the serialized form uses the reference count `7`.
+    pub(crate) const RAW_D1: u8 = CellDescriptor::REF_COUNT_MASK | CellDescriptor::IS_EXOTIC_MASK;
+
+    /// Creates a placeholder for the cell with the specified
+    /// representation hash and depth.
+    ///
+    /// The replaced cell is expected to have level 0 (see [`AbsentCell`]).
+    pub fn new(repr_hash: &HashBytes, repr_depth: u16) -> Self {
+        let mut data = [0; 34];
+        data[..32].copy_from_slice(repr_hash.as_slice());
+        data[32..].copy_from_slice(&repr_depth.to_be_bytes());
+        Self {
+            repr_hash: *repr_hash,
+            data,
+        }
+    }
+
+    /// Creates a placeholder from its serialized representation.
+    pub fn from_data(data: [u8; 34]) -> Self {
+        Self {
+            repr_hash: HashBytes::from_slice(&data[..32]),
+            data,
+        }
+    }
+
+    /// Wraps the placeholder into a cell.
+    pub fn into_cell(self) -> Cell {
+        Cell::from_impl(self)
+    }
+}
+
+impl CellImpl for AbsentCell {
+    fn descriptor(&self) -> CellDescriptor {
+        Self::DESCRIPTOR
+    }
+
+    fn data(&self) -> &[u8] {
+        self.data.as_ref()
+    }
+
+    fn bit_len(&self) -> u16 {
+        Self::BIT_LEN
+    }
+
+    fn reference(&self, _: u8) -> Option<&DynCell> {
+        None
+    }
+
+    fn reference_cloned(&self, _: u8) -> Option<Cell> {
+        None
+    }
+
+    fn virtualize(&self) -> &DynCell {
+        self
+    }
+
+    fn hash(&self, _: u8) -> &HashBytes {
+        &self.repr_hash
+    }
+
+    fn depth(&self, _: u8) -> u16 {
+        u16::from_be_bytes([self.data[32], self.data[33]])
+    }
+
+    fn take_first_child(&mut self) -> Option<Cell> {
+        None
+    }
+
+    fn replace_first_child(&mut self, parent: Cell) -> ReplacedChild {
+        Err(parent)
+    }
+
+    fn take_next_child(&mut self) -> Option<Cell> {
+        None
+    }
+
+    #[cfg(feature = "stats")]
+    fn stats(&self) -> CellTreeStats {
+        CellTreeStats {
+            bit_count: 0,
+            cell_count: 1,
+        }
+    }
+}
+
 type PrunedBranch<const N: usize> = HeaderWithData<PrunedBranchHeader, N>;
 
 struct PrunedBranchHeader {
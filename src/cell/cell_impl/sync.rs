@@ -60,6 +60,27 @@ impl PartialEq for Cell {
     }
 }
 
+impl Ord for Cell {
+    #[inline]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.as_ref().cmp(other.0.as_ref())
+    }
+}
+
+impl PartialOrd for Cell {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl std::hash::Hash for Cell {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::hash::Hash::hash(self.0.as_ref(), state)
+    }
+}
+
 impl From<Cell> for Arc<DynCell> {
     #[inline]
     fn from(value: Cell) -> Self {
@@ -136,6 +157,8 @@ pub struct EmptyCellContext;
 
 impl CellContext for EmptyCellContext {
     fn finalize_cell(&mut self, ctx: CellParts) -> Result<Cell, Error> {
+        inc_counter!("everscale_types_cell_finalize_total");
+
         let hashes = ok!(ctx.compute_hashes());
         // SAFETY: ctx now represents a well-formed cell
         Ok(unsafe { make_cell(ctx, hashes) })
@@ -0,0 +1,197 @@
+use std::panic::Location;
+
+use super::{
+    Cell, CellBuilder, CellContext, CellFamily, CellParts, DisplayCellRoot, DynCell, HashBytes,
+    LoadMode, Store,
+};
+use crate::error::Error;
+
+/// Origin of the built cell.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct CellOrigin {
+    /// Name of the stored model type.
+    pub type_name: &'static str,
+    /// Location of the build call.
+    pub location: &'static Location<'static>,
+}
+
+impl std::fmt::Display for CellOrigin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_fmt(format_args!("{} at {}", self.type_name, self.location))
+    }
+}
+
+/// [`CellContext`] wrapper which records the origin of each built cell.
+///
+/// Forwards all calls to the inner cell context and remembers the model type and
+/// the call site of [`build_from`] for all cells finalized during it.
+/// Nested cells which were already built keep their first origin.
+///
+/// [`build_from`]: DebugCellContext::build_from
+pub struct DebugCellContext<C = <Cell as CellFamily>::EmptyCellContext> {
+    inner: C,
+    current: Option<CellOrigin>,
+    origins: ahash::HashMap<HashBytes, CellOrigin>,
+}
+
+impl DebugCellContext {
+    /// Creates a debug context over the empty cell context.
+    pub fn new() -> Self {
+        Self::with_context(Cell::empty_context())
+    }
+}
+
+impl Default for DebugCellContext {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<C: CellContext> DebugCellContext<C> {
+    /// Creates a debug context over the specified cell context.
+    pub fn with_context(inner: C) -> Self {
+        Self {
+            inner,
+            current: None,
+            origins: Default::default(),
+        }
+    }
+
+    /// Builds a new cell from the specified data,
+    /// recording its type and the caller location.
+    #[track_caller]
+    pub fn build_from<T: Store>(&mut self, data: T) -> Result<Cell, Error> {
+        let origin = CellOrigin {
+            type_name: std::any::type_name::<T>().trim_start_matches('&'),
+            location: Location::caller(),
+        };
+
+        let prev = self.current.replace(origin);
+        let res = CellBuilder::build_from_ext(data, self);
+        self.current = prev;
+        res
+    }
+
+    /// Returns the recorded origin of the cell with the specified
+    /// representation hash.
+    pub fn origin(&self, repr_hash: &HashBytes) -> Option<&CellOrigin> {
+        self.origins.get(repr_hash)
+    }
+
+    /// Returns the number of cells with known origins.
+    pub fn len(&self) -> usize {
+        self.origins.len()
+    }
+
+    /// Returns `true` if no cells were recorded.
+    pub fn is_empty(&self) -> bool {
+        self.origins.is_empty()
+    }
+
+    /// Returns an object that implements [`Display`] for printing all
+    /// cells in the cell tree with their origins.
+    ///
+    /// [`Display`]: std::fmt::Display
+    pub fn display_tree<'a>(&'a self, cell: &'a DynCell) -> DisplayDebugCellTree<'a, C> {
+        DisplayDebugCellTree {
+            context: self,
+            cell,
+        }
+    }
+
+    /// Returns the underlying cell context.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+impl<C: CellContext> CellContext for DebugCellContext<C> {
+    fn finalize_cell(&mut self, cell: CellParts<'_>) -> Result<Cell, Error> {
+        let cell = ok!(self.inner.finalize_cell(cell));
+        if let Some(origin) = self.current {
+            self.origins.entry(*cell.repr_hash()).or_insert(origin);
+        }
+        Ok(cell)
+    }
+
+    #[inline]
+    fn load_cell(&mut self, cell: Cell, mode: LoadMode) -> Result<Cell, Error> {
+        self.inner.load_cell(cell, mode)
+    }
+
+    #[inline]
+    fn load_dyn_cell<'a>(
+        &mut self,
+        cell: &'a DynCell,
+        mode: LoadMode,
+    ) -> Result<&'a DynCell, Error> {
+        self.inner.load_dyn_cell(cell, mode)
+    }
+}
+
+/// Helper struct to print all cells in the cell tree with their origins.
+pub struct DisplayDebugCellTree<'a, C> {
+    context: &'a DebugCellContext<C>,
+    cell: &'a DynCell,
+}
+
+impl<C: CellContext> std::fmt::Display for DisplayDebugCellTree<'_, C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut stack = vec![(0, self.cell)];
+
+        while let Some((level, cell)) = stack.pop() {
+            ok!(std::fmt::Display::fmt(&DisplayCellRoot { cell, level }, f));
+
+            if let Some(origin) = self.context.origin(cell.repr_hash()) {
+                let indent = level * 2;
+                ok!(f.write_fmt(format_args!("{:indent$}origin: {origin}\n", "")));
+            }
+
+            let reference_count = cell.reference_count();
+            for i in (0..reference_count).rev() {
+                if let Some(child) = cell.reference(i) {
+                    stack.push((level + 1, child));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "models"))]
+mod tests {
+    use super::*;
+    use crate::models::{Lazy, StateInit};
+
+    #[test]
+    fn debug_cell_origins() {
+        let mut context = DebugCellContext::new();
+
+        let code = context.build_from(0xdeadbeafu32).unwrap();
+        let line = line!() - 1;
+        let state_init = StateInit {
+            code: Some(code.clone()),
+            data: Some(Cell::empty_cell()),
+            ..Default::default()
+        };
+        let cell = context.build_from(Lazy::new(&state_init).unwrap()).unwrap();
+
+        // Already built cells keep their origins
+        let origin = context.origin(code.repr_hash()).unwrap();
+        assert_eq!(origin.type_name, "u32");
+        assert_eq!(origin.location.file(), file!());
+        assert_eq!(origin.location.line(), line);
+
+        let origin = context.origin(cell.repr_hash()).unwrap();
+        assert!(origin.type_name.contains("Lazy"));
+
+        // Empty cell was not built in this context
+        assert_eq!(context.origin(Cell::empty_cell_ref().repr_hash()), None);
+        assert_eq!(context.len(), 2);
+
+        let tree = context.display_tree(cell.as_ref()).to_string();
+        assert!(tree.contains(&format!("origin: u32 at {}:{line}", file!())));
+    }
+}
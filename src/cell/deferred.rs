@@ -0,0 +1,323 @@
+use std::sync::Arc;
+
+use once_cell::sync::OnceCell;
+use sha2::digest::Digest;
+
+use super::cell_impl::deep_drop_impl;
+use super::{
+    Cell, CellContainer, CellContext, CellDescriptor, CellFamily, CellImpl, CellParts, DynCell,
+    HashBytes, LoadMode,
+};
+use crate::error::Error;
+
+#[cfg(feature = "stats")]
+use super::CellTreeStats;
+
+/// Cell context which computes cell hashes on a thread pool.
+///
+/// Ordinary cells are returned right after the validation and their
+/// hashes are computed in the background, so building lots of cells
+/// (e.g. during collation) doesn't wait for hashing. Accessing hashes
+/// of a cell which is not processed yet (e.g. when encoding it as BOC)
+/// computes them in place or waits for the worker.
+///
+/// Leaf cells, exotic cells and cells with non-zero level
+/// are finalized immediately.
+#[derive(Default, Clone)]
+pub struct DeferredCellContext {
+    pool: Option<Arc<rayon::ThreadPool>>,
+}
+
+impl DeferredCellContext {
+    /// Creates a context which uses the global thread pool.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a context which uses the specified thread pool.
+    pub fn with_pool(pool: Arc<rayon::ThreadPool>) -> Self {
+        Self { pool: Some(pool) }
+    }
+}
+
+impl std::fmt::Debug for DeferredCellContext {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DeferredCellContext")
+            .field("custom_pool", &self.pool.is_some())
+            .finish()
+    }
+}
+
+impl CellContext for DeferredCellContext {
+    fn finalize_cell(&mut self, ctx: CellParts<'_>) -> Result<Cell, Error> {
+        if ctx.descriptor.is_exotic() || !ctx.children_mask.is_empty() || ctx.references.is_empty()
+        {
+            return Cell::empty_context().finalize_cell(ctx);
+        }
+
+        let mut depth = 0u16;
+        for child in ctx.references.as_ref() {
+            let Some(next_depth) = child.repr_depth().checked_add(1) else {
+                return Err(Error::DepthOverflow);
+            };
+            depth = std::cmp::max(depth, next_depth);
+        }
+
+        let cell = CellContainer::new(DeferredCell {
+            descriptor: ctx.descriptor,
+            bit_len: ctx.bit_len,
+            #[cfg(feature = "stats")]
+            stats: ctx.stats,
+            depth,
+            hash: OnceCell::new(),
+            data: Box::from(ctx.data),
+            references: ctx.references.as_ref().iter().cloned().map(Some).collect(),
+        });
+
+        let pending = cell.clone();
+        let task = move || {
+            pending.repr_hash();
+        };
+        match &self.pool {
+            Some(pool) => pool.spawn(task),
+            None => rayon::spawn(task),
+        }
+
+        Ok(Cell::from(cell as CellContainer<DynCell>))
+    }
+
+    #[inline]
+    fn load_cell(&mut self, cell: Cell, _: LoadMode) -> Result<Cell, Error> {
+        Ok(cell)
+    }
+
+    #[inline]
+    fn load_dyn_cell<'a>(&mut self, cell: &'a DynCell, _: LoadMode) -> Result<&'a DynCell, Error> {
+        Ok(cell)
+    }
+}
+
+/// Ordinary cell with zero level and lazily computed hash.
+struct DeferredCell {
+    descriptor: CellDescriptor,
+    bit_len: u16,
+    #[cfg(feature = "stats")]
+    stats: CellTreeStats,
+    depth: u16,
+    hash: OnceCell<HashBytes>,
+    data: Box<[u8]>,
+    references: Vec<Option<Cell>>,
+}
+
+impl DeferredCell {
+    fn repr_hash(&self) -> &HashBytes {
+        if let Some(hash) = self.hash.get() {
+            return hash;
+        }
+
+        // Compute pending hashes of children bottom-up, so that
+        // the hash of each cell below doesn't recurse into its children
+        let mut visited = ahash::HashSet::<*const u8>::default();
+        let mut stack = Vec::<(&DynCell, bool)>::new();
+        for child in self.references.iter().flatten() {
+            stack.push((child.as_ref(), false));
+        }
+        while let Some((cell, children_ready)) = stack.pop() {
+            if cell.is_hash_ready() {
+                continue;
+            } else if children_ready {
+                cell.repr_hash();
+                continue;
+            } else if !visited.insert(cell as *const DynCell as *const u8) {
+                continue;
+            }
+
+            stack.push((cell, true));
+            for child in cell.references() {
+                if !child.is_hash_ready() {
+                    stack.push((child, false));
+                }
+            }
+        }
+
+        self.hash.get_or_init(|| {
+            let d1 = self.descriptor.d1
+                & !(CellDescriptor::LEVEL_MASK | CellDescriptor::STORE_HASHES_MASK);
+
+            let mut hasher = sha2::Sha256::new();
+            hasher.update([d1, self.descriptor.d2]);
+            hasher.update(&self.data);
+
+            // NOTE: All children have zero level
+            let references = self.references.iter().flatten();
+            for child in references.clone() {
+                hasher.update(child.repr_depth().to_be_bytes());
+            }
+            for child in references {
+                hasher.update(child.repr_hash().as_slice());
+            }
+
+            hasher.finalize().into()
+        })
+    }
+}
+
+impl CellImpl for DeferredCell {
+    fn descriptor(&self) -> CellDescriptor {
+        self.descriptor
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn bit_len(&self) -> u16 {
+        self.bit_len
+    }
+
+    fn reference(&self, index: u8) -> Option<&DynCell> {
+        Some(self.references.get(index as usize)?.as_ref()?.as_ref())
+    }
+
+    fn reference_cloned(&self, index: u8) -> Option<Cell> {
+        self.references.get(index as usize)?.clone()
+    }
+
+    fn virtualize(&self) -> &DynCell {
+        self
+    }
+
+    fn hash(&self, _: u8) -> &HashBytes {
+        self.repr_hash()
+    }
+
+    fn depth(&self, _: u8) -> u16 {
+        self.depth
+    }
+
+    fn is_hash_ready(&self) -> bool {
+        self.hash.get().is_some()
+    }
+
+    fn take_first_child(&mut self) -> Option<Cell> {
+        self.references.first_mut()?.take()
+    }
+
+    fn replace_first_child(&mut self, parent: Cell) -> Result<Cell, Cell> {
+        match self.references.first_mut() {
+            Some(slot @ Some(_)) => Ok(slot.replace(parent).unwrap()),
+            _ => Err(parent),
+        }
+    }
+
+    fn take_next_child(&mut self) -> Option<Cell> {
+        if self.references.len() > 1 {
+            self.references.pop().flatten()
+        } else {
+            None
+        }
+    }
+
+    #[cfg(feature = "stats")]
+    fn stats(&self) -> CellTreeStats {
+        self.stats
+    }
+}
+
+impl Drop for DeferredCell {
+    fn drop(&mut self) {
+        for mut child in self.references.drain(..).flatten() {
+            deep_drop_impl(&mut child);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boc::Boc;
+    use crate::cell::CellBuilder;
+
+    #[test]
+    fn deferred_hashes() {
+        fn build_tree(context: &mut dyn CellContext) -> Cell {
+            let mut cells = (0..64u32)
+                .map(|i| CellBuilder::build_from(i).unwrap())
+                .collect::<Vec<_>>();
+            while cells.len() > 1 {
+                cells = cells
+                    .chunks(3)
+                    .enumerate()
+                    .map(|(i, chunk)| {
+                        let mut builder = CellBuilder::new();
+                        builder.store_u8(i as u8).unwrap();
+                        for child in chunk {
+                            builder.store_reference(child.clone()).unwrap();
+                        }
+                        builder.build_ext(context).unwrap()
+                    })
+                    .collect();
+            }
+            cells.pop().unwrap()
+        }
+
+        let expected = build_tree(&mut Cell::empty_context());
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(2)
+            .build()
+            .unwrap();
+        for mut context in [
+            DeferredCellContext::new(),
+            DeferredCellContext::with_pool(Arc::new(pool)),
+        ] {
+            let cell = build_tree(&mut context);
+            assert_eq!(cell.repr_depth(), expected.repr_depth());
+            assert_eq!(cell.repr_hash(), expected.repr_hash());
+            assert_eq!(Boc::encode(&cell), Boc::encode(&expected));
+        }
+
+        // Deep chains are dropped without recursion
+        let mut context = DeferredCellContext::new();
+        let mut cell = Cell::empty_cell();
+        for _ in 0..60000 {
+            let mut builder = CellBuilder::new();
+            builder.store_reference(cell).unwrap();
+            cell = builder.build_ext(&mut context).unwrap();
+        }
+        assert_eq!(cell.repr_depth(), 60000);
+        drop(cell);
+    }
+
+    #[test]
+    fn deferred_deep_chain_hash() {
+        fn build_chain(context: &mut dyn CellContext) -> Cell {
+            let mut cell = Cell::empty_cell();
+            for i in 0..60000u32 {
+                let mut builder = CellBuilder::new();
+                builder.store_u32(i).unwrap();
+                builder.store_reference(cell).unwrap();
+                cell = builder.build_ext(context).unwrap();
+            }
+            cell
+        }
+
+        let expected = build_chain(&mut Cell::empty_context());
+
+        // Block the only worker, so that no hashes are computed in the background
+        let pool = Arc::new(
+            rayon::ThreadPoolBuilder::new()
+                .num_threads(1)
+                .build()
+                .unwrap(),
+        );
+        let (unblock, blocked) = std::sync::mpsc::channel::<()>();
+        pool.spawn(move || blocked.recv().unwrap());
+
+        let cell = build_chain(&mut DeferredCellContext::with_pool(pool));
+        assert!(!cell.is_hash_ready());
+        assert_eq!(cell.repr_hash(), expected.repr_hash());
+        assert_eq!(Boc::encode(&cell), Boc::encode(&expected));
+        unblock.send(()).unwrap();
+    }
+}
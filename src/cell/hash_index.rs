@@ -0,0 +1,258 @@
+use std::collections::hash_map::Entry;
+use std::collections::VecDeque;
+
+use super::{Cell, DynCell, HashBytes};
+
+/// Lookup table from representation hashes to cells.
+///
+/// Built once per tree to speed up repeated lookups and membership checks
+/// which would otherwise re-walk the tree (see [`DynCell::contains_hash`]).
+/// Can be extended with new versions of the tree, walking only the cells
+/// which were not indexed before.
+#[derive(Default, Clone)]
+pub struct CellHashIndex {
+    cells: ahash::HashMap<HashBytes, IndexedCell>,
+}
+
+impl CellHashIndex {
+    /// Builds an index of all cells in the tree.
+    pub fn new(root: &Cell) -> Self {
+        Self::with_max_depth(root, u16::MAX)
+    }
+
+    /// Builds an index of cells at most `max_depth` levels below the root.
+    pub fn with_max_depth(root: &Cell, max_depth: u16) -> Self {
+        let mut index = Self::default();
+        index.insert_tree_ext(root, max_depth);
+        index
+    }
+
+    /// Adds all cells of the tree to the index.
+    ///
+    /// Returns the number of newly indexed cells.
+    ///
+    /// See [`insert_tree_ext`] for details.
+    ///
+    /// [`insert_tree_ext`]: CellHashIndex::insert_tree_ext
+    pub fn insert_tree(&mut self, root: &Cell) -> usize {
+        self.insert_tree_ext(root, u16::MAX)
+    }
+
+    /// Adds cells at most `max_depth` levels below the root to the index.
+    ///
+    /// Subtrees which are already indexed at the same or lower depth and
+    /// were explored at least as deep are not visited, so adding a new
+    /// version of the tree only walks the changed cells. Depths of the
+    /// shared cells are updated to the minimal depth among all inserted roots.
+    ///
+    /// Returns the number of newly indexed cells.
+    pub fn insert_tree_ext(&mut self, root: &Cell, max_depth: u16) -> usize {
+        let mut inserted = 0;
+
+        let mut queue = VecDeque::from([(root.clone(), 0u16)]);
+        while let Some((cell, depth)) = queue.pop_front() {
+            // NOTE: Cells are only queued within `max_depth`
+            let explored = max_depth - depth;
+
+            match self.cells.entry(*cell.repr_hash()) {
+                Entry::Occupied(mut entry) => {
+                    let item = entry.get_mut();
+                    if item.depth <= depth && item.explored >= explored {
+                        continue;
+                    }
+                    item.depth = std::cmp::min(item.depth, depth);
+                    item.explored = std::cmp::max(item.explored, explored);
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(IndexedCell {
+                        cell: cell.clone(),
+                        depth,
+                        explored,
+                    });
+                    inserted += 1;
+                }
+            }
+
+            if depth < max_depth {
+                for child in cell.references().cloned() {
+                    queue.push_back((child, depth + 1));
+                }
+            }
+        }
+
+        inserted
+    }
+
+    /// Returns the cell with the specified representation hash.
+    #[inline]
+    pub fn get(&self, hash: &HashBytes) -> Option<&Cell> {
+        self.cells.get(hash).map(|item| &item.cell)
+    }
+
+    /// Returns `true` if the index contains a cell with the specified hash.
+    #[inline]
+    pub fn contains(&self, hash: &HashBytes) -> bool {
+        self.cells.contains_key(hash)
+    }
+
+    /// Returns `true` if the index contains a cell with the specified hash
+    /// at most `max_depth` levels below the root.
+    pub fn contains_within(&self, hash: &HashBytes, max_depth: u16) -> bool {
+        matches!(self.cells.get(hash), Some(item) if item.depth <= max_depth)
+    }
+
+    /// Returns the minimal number of levels between the root and
+    /// the cell with the specified hash.
+    #[inline]
+    pub fn depth_of(&self, hash: &HashBytes) -> Option<u16> {
+        self.cells.get(hash).map(|item| item.depth)
+    }
+
+    /// Gets an iterator over all indexed cells in arbitrary order.
+    pub fn cells(&self) -> impl Iterator<Item = &Cell> {
+        self.cells.values().map(|item| &item.cell)
+    }
+
+    /// Returns the number of unique cells in the index.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns `true` if the index is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
+
+impl std::fmt::Debug for CellHashIndex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CellHashIndex")
+            .field("len", &self.cells.len())
+            .finish()
+    }
+}
+
+#[derive(Clone)]
+struct IndexedCell {
+    cell: Cell,
+    depth: u16,
+    /// The number of levels below the cell which were indexed.
+    explored: u16,
+}
+
+/// Visits each unique cell in the tree once, at its minimal depth.
+///
+/// Stops when the visitor returns `true` and returns `true` in that case.
+pub(crate) fn walk_unique<'a, F>(root: &'a DynCell, max_depth: u16, mut f: F) -> bool
+where
+    F: FnMut(&'a DynCell, u16) -> bool,
+{
+    let mut visited = ahash::HashSet::default();
+    visited.insert(root.repr_hash());
+
+    // NOTE: Breadth-first order guarantees that each cell is first
+    // reached at its minimal depth.
+    let mut queue = VecDeque::from([(root, 0u16)]);
+    while let Some((cell, depth)) = queue.pop_front() {
+        if f(cell, depth) {
+            return true;
+        }
+
+        if depth >= max_depth {
+            continue;
+        }
+
+        for child in cell.references() {
+            if visited.insert(child.repr_hash()) {
+                queue.push_back((child, depth + 1));
+            }
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cell::{CellBuilder, CellFamily};
+
+    #[test]
+    fn hash_membership() {
+        let leaf = CellBuilder::build_from(0xdeadbeafu32).unwrap();
+        let other = CellBuilder::build_from(0xcafeu16).unwrap();
+        let middle = CellBuilder::build_from((leaf.clone(), 123u8)).unwrap();
+        let root = CellBuilder::build_from((middle.clone(), leaf.clone(), 1u8)).unwrap();
+
+        // Direct checks
+        assert!(root.contains_hash(root.repr_hash(), 0));
+        assert!(!root.contains_hash(middle.repr_hash(), 0));
+        assert!(root.contains_hash(middle.repr_hash(), 1));
+        // Shared cell is found at its minimal depth
+        assert!(root.contains_hash(leaf.repr_hash(), 1));
+        assert!(!root.contains_hash(other.repr_hash(), u16::MAX));
+        assert!(!middle.contains_hash(root.repr_hash(), u16::MAX));
+
+        // Index checks
+        let index = CellHashIndex::new(&root);
+        assert_eq!(index.len(), 3);
+        assert!(index.contains(leaf.repr_hash()));
+        assert!(!index.contains(other.repr_hash()));
+        assert_eq!(index.get(leaf.repr_hash()), Some(&leaf));
+        assert_eq!(index.get(other.repr_hash()), None);
+        assert_eq!(index.depth_of(root.repr_hash()), Some(0));
+        assert_eq!(index.depth_of(middle.repr_hash()), Some(1));
+        assert_eq!(index.depth_of(leaf.repr_hash()), Some(1));
+        assert!(index.contains_within(leaf.repr_hash(), 1));
+        assert!(!index.contains_within(middle.repr_hash(), 0));
+
+        let index = CellHashIndex::with_max_depth(&root, 0);
+        assert_eq!(index.len(), 1);
+        assert!(!index.contains(leaf.repr_hash()));
+
+        let index = CellHashIndex::new(&Cell::empty_cell());
+        assert_eq!(index.len(), 1);
+        assert!(CellHashIndex::default().is_empty());
+    }
+
+    #[test]
+    fn hash_index_updates() {
+        let leaf = CellBuilder::build_from(0xdeadbeafu32).unwrap();
+        let deep = CellBuilder::build_from((leaf.clone(), 1u8)).unwrap();
+        let middle = CellBuilder::build_from((deep.clone(), 2u8)).unwrap();
+        let root = CellBuilder::build_from((middle.clone(), 3u8)).unwrap();
+
+        let mut index = CellHashIndex::new(&root);
+        assert_eq!(index.len(), 4);
+        assert_eq!(index.depth_of(leaf.repr_hash()), Some(3));
+
+        // Only new cells are indexed
+        let new_root = CellBuilder::build_from((middle.clone(), 4u8)).unwrap();
+        assert_eq!(index.insert_tree(&new_root), 1);
+        assert_eq!(index.len(), 5);
+        assert_eq!(index.get(new_root.repr_hash()), Some(&new_root));
+        assert_eq!(index.depth_of(leaf.repr_hash()), Some(3));
+
+        // Depths of shared cells are updated
+        let shallow_root = CellBuilder::build_from((deep.clone(), 5u8)).unwrap();
+        assert_eq!(index.insert_tree(&shallow_root), 1);
+        assert_eq!(index.depth_of(deep.repr_hash()), Some(1));
+        assert_eq!(index.depth_of(leaf.repr_hash()), Some(2));
+        assert_eq!(index.depth_of(middle.repr_hash()), Some(1));
+
+        assert_eq!(index.insert_tree(&root), 0);
+        assert_eq!(index.cells().count(), index.len());
+
+        // Subtrees are walked again with a larger max depth
+        let mut index = CellHashIndex::with_max_depth(&root, 1);
+        assert_eq!(index.len(), 2);
+        assert_eq!(index.insert_tree_ext(&root, 2), 1);
+        assert_eq!(index.depth_of(deep.repr_hash()), Some(2));
+        assert_eq!(index.insert_tree_ext(&root, 2), 0);
+        assert_eq!(index.insert_tree(&root), 1);
+        assert_eq!(index.depth_of(leaf.repr_hash()), Some(3));
+        assert_eq!(index.insert_tree(&root), 0);
+    }
+}
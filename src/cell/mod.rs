@@ -6,19 +6,26 @@ use std::str::FromStr;
 use crate::error::{Error, ParseHashBytesError};
 use crate::util::Bitstring;
 
-pub use self::builder::{CellBuilder, CellRefsBuilder, Store};
+pub use self::builder::{
+    CellBuilder, CellBuilderCheckpoint, CellBuilderPool, CellRefsBuilder, Store,
+};
 pub use self::cell_context::{CellContext, CellParts, LoadMode};
-pub use self::cell_impl::{StaticCell, VirtualCellWrapper};
+pub use self::cell_impl::{AbsentCell, StaticCell, VirtualCellWrapper};
+pub use self::debug_context::{CellOrigin, DebugCellContext, DisplayDebugCellTree};
+pub use self::hash_index::CellHashIndex;
 pub use self::slice::{CellSlice, CellSliceParts, CellSliceRange, CellSliceSize, ExactSize, Load};
 pub use self::usage_tree::{UsageTree, UsageTreeMode, UsageTreeWithSubtrees};
 
+#[cfg(feature = "rayon")]
+pub use self::deferred::DeferredCellContext;
+
 #[cfg(not(feature = "sync"))]
 pub use self::cell_impl::rc::Cell;
 
 #[cfg(feature = "sync")]
 pub use self::cell_impl::sync::Cell;
 
-pub use everscale_types_proc::{Load, Store};
+pub use everscale_types_proc::{EquivalentRepr, Load, Store};
 
 /// Generic cell implementation.
 mod cell_impl;
@@ -34,6 +41,16 @@ mod builder;
 
 mod usage_tree;
 
+/// Cell context wrapper for tracking cell origins.
+mod debug_context;
+
+/// Cell hash lookup utils.
+mod hash_index;
+
+/// Background cell finalization.
+#[cfg(feature = "rayon")]
+mod deferred;
+
 #[cfg(feature = "sync")]
 #[doc(hidden)]
 mod __checks {
@@ -45,11 +62,111 @@ mod __checks {
 }
 
 /// Marker trait which allows casting lazy-loaded data.
+///
+/// Implemented for types with the same cell representation as `T`
+/// (e.g. borrowing and owning variants of the same model).
+/// Can be derived with the `#[equivalent_repr(...)]` attribute
+/// which lists all types that `Self` can be casted to.
+///
+/// Typed wrappers can then be casted without reserialization
+/// (e.g. [`Dict::cast_ref`] or `Lazy::cast_ref`),
+/// and values can be converted with [`cast_owned`].
+///
+/// [`Dict::cast_ref`]: crate::dict::Dict::cast_ref
 pub trait EquivalentRepr<T> {}
 
 /// All types are equivalent to itself.
 impl<T> EquivalentRepr<T> for T {}
 
+/// Converts the value into an equivalent type by storing it
+/// into a new cell and loading it back.
+///
+/// Can be used to get an owning variant of a borrowing model.
+pub fn cast_owned<T, Q>(value: &T) -> Result<Q, Error>
+where
+    T: Store,
+    Q: EquivalentRepr<T> + for<'a> Load<'a>,
+{
+    let cell = ok!(CellBuilder::build_from(value));
+    cell.as_ref().parse::<Q>()
+}
+
+/// Concatenates the contents of owned slices into a new chain of cells.
+///
+/// See [`concat_slices_ext`] for details.
+pub fn concat_slices(parts: &[CellSliceParts]) -> Result<Cell, Error> {
+    concat_slices_ext(parts, &mut Cell::empty_context())
+}
+
+/// Concatenates the contents of owned slices into a new chain of cells
+/// using the specified cell context.
+///
+/// Data bits and references of all slices are stored in order. When the
+/// next cell is needed, it is stored as the last reference of the previous
+/// one, so each cell except the last contains at most three references of
+/// its own.
+pub fn concat_slices_ext(
+    parts: &[CellSliceParts],
+    context: &mut dyn CellContext,
+) -> Result<Cell, Error> {
+    let mut slices = Vec::with_capacity(parts.len());
+    let mut remaining_bits = 0usize;
+    let mut remaining_refs = 0usize;
+    for (cell, range) in parts {
+        let slice = ok!(range.apply(cell));
+        remaining_bits += slice.remaining_bits() as usize;
+        remaining_refs += slice.remaining_refs() as usize;
+        slices.push(slice);
+    }
+
+    let mut data = slices.iter().copied().filter(|s| !s.is_data_empty());
+    let mut refs = slices.iter().flat_map(|s| s.references().cloned());
+    let mut data_slice = data.next();
+
+    let mut chain = Vec::new();
+    loop {
+        let is_last = remaining_bits <= MAX_BIT_LEN as usize && remaining_refs <= MAX_REF_COUNT;
+
+        let mut builder = CellBuilder::new();
+        while let Some(slice) = &mut data_slice {
+            let bits = std::cmp::min(slice.remaining_bits(), builder.spare_bits_capacity());
+            if bits == 0 {
+                break;
+            }
+            ok!(builder.store_slice_data(slice.get_prefix(bits, 0)));
+            ok!(slice.advance(bits, 0));
+            remaining_bits -= bits as usize;
+
+            if slice.is_data_empty() {
+                data_slice = data.next();
+            }
+        }
+
+        let ref_limit = if is_last {
+            MAX_REF_COUNT
+        } else {
+            MAX_REF_COUNT - 1
+        };
+        for cell in refs.by_ref().take(ref_limit) {
+            ok!(builder.store_reference(cell));
+            remaining_refs -= 1;
+        }
+
+        chain.push(builder);
+        if is_last {
+            break;
+        }
+    }
+
+    // Build cells starting from the end of the chain
+    let mut result = ok!(chain.pop().unwrap_or_default().build_ext(context));
+    for mut builder in chain.into_iter().rev() {
+        ok!(builder.store_reference(result));
+        result = ok!(builder.build_ext(context));
+    }
+    Ok(result)
+}
+
 /// Cell implementation family.
 pub trait CellFamily: Sized {
     /// The default cell context type.
@@ -84,6 +201,49 @@ pub type DynCell = dyn CellImpl;
 #[cfg(feature = "sync")]
 pub type DynCell = dyn CellImpl + Send + Sync;
 
+/// Shared pointer type of the [`Cell`].
+///
+/// Either [`Rc`] or [`Arc`] depending on the `sync` feature.
+///
+/// [`Rc`]: std::rc::Rc
+/// [`Arc`]: std::sync::Arc
+#[cfg(not(feature = "sync"))]
+pub type CellContainer<T> = std::rc::Rc<T>;
+
+/// Shared pointer type of the [`Cell`].
+///
+/// Either [`Rc`] or [`Arc`] depending on the `sync` feature.
+///
+/// [`Rc`]: std::rc::Rc
+/// [`Arc`]: std::sync::Arc
+#[cfg(feature = "sync")]
+pub type CellContainer<T> = std::sync::Arc<T>;
+
+impl Cell {
+    /// Wraps a custom cell implementation into a cell
+    /// of the current cell family.
+    pub fn from_impl<
+        #[cfg(not(feature = "sync"))] T: CellImpl + 'static,
+        #[cfg(feature = "sync")] T: CellImpl + Send + Sync + 'static,
+    >(
+        cell: T,
+    ) -> Self {
+        Cell::from(CellContainer::new(cell) as CellContainer<DynCell>)
+    }
+}
+
+/// Hash map keyed by cells.
+///
+/// Cells are compared and hashed by their representation hash,
+/// so lookups can also be made by `&DynCell`.
+pub type CellHashMap<V, S = ahash::RandomState> = std::collections::HashMap<Cell, V, S>;
+
+/// Hash set of cells.
+///
+/// Cells are compared and hashed by their representation hash,
+/// so lookups can also be made by `&DynCell`.
+pub type CellHashSet<S = ahash::RandomState> = std::collections::HashSet<Cell, S>;
+
 impl AsRef<DynCell> for DynCell {
     #[inline(always)]
     fn as_ref(&self) -> &Self {
@@ -160,6 +320,13 @@ pub trait CellImpl {
     /// NOTE: identical cells are counted each time they occur in the tree.
     #[cfg(feature = "stats")]
     fn stats(&self) -> CellTreeStats;
+
+    /// Returns `true` if the cell hashes are already computed.
+    ///
+    /// NOTE: Only cells with lazily computed hashes can return `false`.
+    fn is_hash_ready(&self) -> bool {
+        true
+    }
 }
 
 impl DynCell {
@@ -256,6 +423,15 @@ impl DynCell {
         StorageStat::compute_for_cell(self, limit)
     }
 
+    /// Returns `true` if this cell or any cell at most `max_depth` levels
+    /// below it has the specified representation hash.
+    ///
+    /// Each unique cell is visited at most once. Use [`CellHashIndex`]
+    /// for repeated checks over the same tree.
+    pub fn contains_hash(&self, hash: &HashBytes, max_depth: u16) -> bool {
+        hash_index::walk_unique(self, max_depth, |cell, _| cell.repr_hash() == hash)
+    }
+
     /// Returns an object that implements [`Debug`] for printing only
     /// the root cell of the cell tree.
     ///
@@ -326,6 +502,15 @@ impl DynCell {
     pub fn parse<'a, T: Load<'a>>(&'a self) -> Result<T, Error> {
         T::load_from(&mut ok!(self.as_slice()))
     }
+
+    /// Converts this cell into a slice and tries to load the specified type
+    /// from it, rejecting non-canonical encodings.
+    ///
+    /// See [`CellSlice::load_strict`].
+    #[inline]
+    pub fn parse_strict<'a, T: Load<'a> + Store>(&'a self) -> Result<T, Error> {
+        ok!(self.as_slice()).load_strict()
+    }
 }
 
 impl std::fmt::Debug for DynCell {
@@ -350,6 +535,29 @@ impl PartialEq<DynCell> for DynCell {
     }
 }
 
+impl Ord for DynCell {
+    #[inline]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.repr_hash().cmp(other.repr_hash())
+    }
+}
+
+impl PartialOrd for DynCell {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Hashes the cell by its representation hash, so the result is the same
+/// for all instances of the same cell (regardless of its origin).
+impl std::hash::Hash for DynCell {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::hash::Hash::hash(self.repr_hash(), state)
+    }
+}
+
 #[cfg(feature = "serde")]
 impl serde::Serialize for DynCell {
     fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
@@ -558,6 +766,41 @@ impl HashBytes {
     /// Array of zero bytes.
     pub const ZERO: Self = Self([0; 32]);
 
+    /// Parses a hex-encoded hash in a const context.
+    ///
+    /// ```
+    /// # use everscale_types::prelude::*;
+    /// const HASH: HashBytes = HashBytes::from_hex_const(
+    ///     "96a296d224f285c67bee93c30f8a309157f0daa35dc5b87e410b78630a09cfc7",
+    /// );
+    /// assert_eq!(HASH, *Cell::empty_cell().repr_hash());
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the string is not exactly 64 hex characters.
+    pub const fn from_hex_const(s: &str) -> Self {
+        const fn hex_digit(c: u8) -> u8 {
+            match c {
+                b'0'..=b'9' => c - b'0',
+                b'a'..=b'f' => c - b'a' + 10,
+                b'A'..=b'F' => c - b'A' + 10,
+                _ => panic!("invalid hex character"),
+            }
+        }
+
+        let s = s.as_bytes();
+        assert!(s.len() == 64, "expected string of 64 hex characters");
+
+        let mut result = [0u8; 32];
+        let mut i = 0;
+        while i < 32 {
+            result[i] = (hex_digit(s[i * 2]) << 4) | hex_digit(s[i * 2 + 1]);
+            i += 1;
+        }
+        Self(result)
+    }
+
     /// Converts slice to a hash bytes.
     ///
     /// # Panics
@@ -651,6 +894,20 @@ impl std::borrow::BorrowMut<[u8]> for HashBytes {
     }
 }
 
+impl std::borrow::Borrow<[u8; 32]> for HashBytes {
+    #[inline(always)]
+    fn borrow(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl std::borrow::BorrowMut<[u8; 32]> for HashBytes {
+    #[inline(always)]
+    fn borrow_mut(&mut self) -> &mut [u8; 32] {
+        &mut self.0
+    }
+}
+
 impl std::borrow::Borrow<HashBytes> for [u8; 32] {
     #[inline(always)]
     fn borrow(&self) -> &HashBytes {
@@ -693,6 +950,15 @@ impl From<[u8; 32]> for HashBytes {
     }
 }
 
+impl TryFrom<&[u8]> for HashBytes {
+    type Error = std::array::TryFromSliceError;
+
+    #[inline]
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        value.try_into().map(Self)
+    }
+}
+
 impl From<sha2::digest::Output<sha2::Sha256>> for HashBytes {
     #[inline(always)]
     fn from(value: sha2::digest::Output<sha2::Sha256>) -> Self {
@@ -1046,7 +1312,13 @@ impl CellDescriptor {
     /// Returns whether this cell refers to some external data.
     #[inline(always)]
     pub const fn is_absent(self) -> bool {
-        self.d1 == (Self::REF_COUNT_MASK | Self::IS_EXOTIC_MASK)
+        self.d1 == AbsentCell::DESCRIPTOR.d1 && self.d2 == AbsentCell::DESCRIPTOR.d2
+    }
+
+    /// Returns whether this serialized descriptor marks an absent cell.
+    #[inline(always)]
+    pub(crate) const fn is_absent_raw(self) -> bool {
+        self.d1 == AbsentCell::RAW_D1
     }
 
     /// Returns whether this cell should store hashes in data.
@@ -1511,6 +1783,92 @@ pub const MAX_REF_COUNT: usize = 4;
 mod tests {
     use super::*;
 
+    #[test]
+    fn hash_bytes_borrow() {
+        const HASH: HashBytes = HashBytes::from_hex_const(
+            "00112233445566778899AABBCCDDEEFF00112233445566778899aabbccddeeff",
+        );
+        assert_eq!(
+            HASH,
+            "00112233445566778899aabbccddeeff00112233445566778899aabbccddeeff"
+                .parse::<HashBytes>()
+                .unwrap()
+        );
+        assert_eq!(HASH[1], 0x11);
+        assert_eq!(&HASH[30..], &[0xee, 0xff]);
+
+        let mut map = ahash::HashMap::<HashBytes, u32>::default();
+        map.insert(HASH, 123);
+        assert_eq!(map.get(&HASH.0), Some(&123));
+        assert_eq!(map.get(HASH.as_slice()), Some(&123));
+
+        let mut map = ahash::HashMap::<[u8; 32], u32>::default();
+        map.insert(HASH.0, 123);
+        assert_eq!(map.get(&HASH), Some(&123));
+
+        assert_eq!(HashBytes::try_from(HASH.as_slice()).unwrap(), HASH);
+        assert!(HashBytes::try_from(&HASH[1..]).is_err());
+    }
+
+    #[test]
+    fn cell_keyed_containers() {
+        let first = CellBuilder::build_from(0xdeadbeafu32).unwrap();
+        let same = CellBuilder::build_from(0xdeadbeafu32).unwrap();
+        let other = CellBuilder::build_from(0xcafeu16).unwrap();
+        let virtualized = Cell::virtualize(first.clone());
+
+        let mut map = CellHashMap::<u32>::default();
+        assert!(map.insert(first.clone(), 1).is_none());
+        assert_eq!(map.insert(same.clone(), 2), Some(1));
+        assert_eq!(map.len(), 1);
+
+        assert_eq!(map.get(&same), Some(&2));
+        assert_eq!(map.get(same.as_ref()), Some(&2));
+        assert_eq!(map.get(virtualized.as_ref()), Some(&2));
+        assert_eq!(map.get(other.as_ref()), None);
+
+        let set = [first, same, other.clone()]
+            .into_iter()
+            .collect::<CellHashSet>();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(other.as_ref()));
+    }
+
+    #[test]
+    fn equivalent_repr_casts() {
+        use crate::dict::Dict;
+
+        #[derive(Debug, Store, Load, EquivalentRepr)]
+        #[equivalent_repr(Item)]
+        struct RawItem {
+            value: u32,
+            child: Option<Cell>,
+        }
+
+        #[derive(Debug, Store, Load, EquivalentRepr)]
+        #[equivalent_repr(RawItem)]
+        struct Item {
+            value: u32,
+            child: Dict<u16, u16>,
+        }
+
+        let mut child = Dict::<u16, u16>::new();
+        child.set(1, 2).unwrap();
+        let item = Item { value: 123, child };
+
+        let raw = cast_owned::<_, RawItem>(&item).unwrap();
+        assert_eq!(raw.value, 123);
+        assert_eq!(&raw.child, item.child.root());
+
+        let mut dict = Dict::<u32, RawItem>::new();
+        dict.set(1, raw).unwrap();
+
+        let dict = dict.cast_ref::<u32, Item>();
+        let item = dict.get(1).unwrap().unwrap();
+        assert_eq!(item.value, 123);
+        assert_eq!(item.child.get(1).unwrap(), Some(2));
+    }
+
     #[test]
     fn correct_level() {
         const LEVEL: [u8; 8] = [0, 1, 1, 2, 1, 2, 2, 3];
@@ -1585,4 +1943,106 @@ mod tests {
         assert_eq!(pruned3.repr_hash(), cell.repr_hash());
         assert_eq!(pruned3.repr_depth(), cell.repr_depth());
     }
+    #[test]
+    fn cells_as_keys() {
+        use std::collections::{BTreeSet, HashSet};
+
+        let cells = (0..10u32)
+            .map(|i| CellBuilder::build_from(i).unwrap())
+            .collect::<Vec<_>>();
+
+        let hash_set = cells.iter().cloned().collect::<HashSet<_>>();
+        let ordered = cells.iter().cloned().collect::<BTreeSet<_>>();
+        assert_eq!(hash_set.len(), cells.len());
+        assert_eq!(ordered.len(), cells.len());
+
+        // Cells are ordered by their representation hash
+        let mut prev: Option<&Cell> = None;
+        for cell in &ordered {
+            if let Some(prev) = prev {
+                assert!(prev.repr_hash() < cell.repr_hash());
+            }
+            prev = Some(cell);
+        }
+
+        // Rebuilt cells are equal to the original ones
+        let rebuilt = CellBuilder::build_from(5u32).unwrap();
+        assert!(hash_set.contains(&rebuilt));
+        assert!(ordered.contains(&rebuilt));
+        assert!(hash_set.contains(rebuilt.as_ref()));
+    }
+
+    #[test]
+    fn concat_owned_slices() {
+        fn make_part(byte: u8, bits: u16, refs: u8) -> CellSliceParts {
+            let mut builder = CellBuilder::new();
+            builder.store_u8(0xff).unwrap();
+            builder.store_raw(&[byte; 128], bits).unwrap();
+            for i in 0..refs {
+                let child = CellBuilder::build_from((byte, i)).unwrap();
+                builder.store_reference(child).unwrap();
+            }
+            let cell = builder.build().unwrap();
+
+            // Skip the prefix byte
+            let mut range = CellSliceRange::full(cell.as_ref());
+            range.advance(8, 0).unwrap();
+            (cell, range)
+        }
+
+        fn collect_chain(mut cell: Cell, len: usize) -> (Vec<bool>, Vec<Cell>) {
+            let mut bits = Vec::new();
+            let mut refs = Vec::new();
+            for i in 0..len {
+                let mut slice = cell.as_slice().unwrap();
+                while !slice.is_data_empty() {
+                    bits.push(slice.load_bit().unwrap());
+                }
+                refs.extend(slice.references().cloned());
+                if i + 1 < len {
+                    cell = refs.pop().unwrap();
+                }
+            }
+            (bits, refs)
+        }
+
+        // Small parts fit into a single cell
+        let parts = [make_part(0xaa, 12, 2), make_part(0x55, 4, 2)];
+        let cell = concat_slices(&parts).unwrap();
+        assert_eq!(cell.bit_len(), 16);
+        assert_eq!(cell.reference_count(), 4);
+        assert_eq!(cell.data(), &[0xaa, 0xa5]);
+        assert_eq!(cell.reference(3), parts[1].0.reference(1));
+
+        // Large parts are spilled
+        let parts = [
+            make_part(0xaa, 800, 3),
+            make_part(0x55, 600, 0),
+            make_part(0x0f, 900, 4),
+        ];
+        let cell = concat_slices(&parts).unwrap();
+
+        let mut expected_bits = Vec::new();
+        let mut expected_refs = Vec::new();
+        for (cell, range) in &parts {
+            let mut slice = range.apply(cell).unwrap();
+            expected_refs.extend(slice.references().cloned());
+            while !slice.is_data_empty() {
+                expected_bits.push(slice.load_bit().unwrap());
+            }
+        }
+
+        // 2300 bits and 7 refs require three cells
+        assert_eq!(cell.bit_len(), MAX_BIT_LEN);
+        assert_eq!(cell.reference_count(), 4);
+        let (bits, refs) = collect_chain(cell, 3);
+        assert_eq!(bits, expected_bits);
+        assert_eq!(refs, expected_refs);
+
+        // Empty parts produce an empty cell
+        let cell = concat_slices(&[]).unwrap();
+        assert_eq!(cell.as_ref(), Cell::empty_cell_ref());
+        let cell = concat_slices(&[(Cell::empty_cell(), CellSliceRange::empty())]).unwrap();
+        assert_eq!(cell.as_ref(), Cell::empty_cell_ref());
+    }
 }
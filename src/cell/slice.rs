@@ -3,8 +3,8 @@ use std::rc::Rc;
 use std::sync::Arc;
 
 use crate::cell::{
-    Cell, CellTreeStats, CellType, DynCell, HashBytes, LevelMask, RefsIter, StorageStat,
-    MAX_BIT_LEN, MAX_REF_COUNT,
+    Cell, CellBuilder, CellTreeStats, CellType, DynCell, HashBytes, LevelMask, RefsIter,
+    StorageStat, Store, MAX_BIT_LEN, MAX_REF_COUNT,
 };
 use crate::error::Error;
 use crate::util::{unlikely, Bitstring};
@@ -160,6 +160,13 @@ impl_primitive_loads! {
     HashBytes => |s| s.load_u256(),
 }
 
+impl<const N: usize> Load<'_> for [u8; N] {
+    #[inline]
+    fn load_from(slice: &mut CellSlice) -> Result<Self, Error> {
+        slice.load_array()
+    }
+}
+
 impl<'a> Load<'a> for &'a DynCell {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
         slice.load_reference()
@@ -227,7 +234,7 @@ impl CellSliceRange {
     }
 
     /// Constructs a new cell slice from the specified cell using the current range.
-    /// Returns an error if the cell is pruned.
+    /// Returns an error if the cell is pruned or absent.
     ///
     /// NOTE: the resulting range will be truncated to cell bounds.
     #[inline]
@@ -236,9 +243,12 @@ impl CellSliceRange {
         T: AsRef<DynCell> + ?Sized,
     {
         fn apply_impl(range: CellSliceRange, cell: &DynCell) -> Result<CellSlice<'_>, Error> {
-            // Handle pruned branch access
-            if unlikely(cell.descriptor().is_pruned_branch()) {
+            // Handle pruned branch or absent cell access
+            let descriptor = cell.descriptor();
+            if unlikely(descriptor.is_pruned_branch()) {
                 Err(Error::PrunedBranchAccess)
+            } else if unlikely(descriptor.is_absent()) {
+                Err(Error::AbsentCellAccess)
             } else {
                 let bits_end = std::cmp::min(range.bits_end, cell.bit_len());
                 let refs_end = std::cmp::min(range.refs_end, cell.reference_count());
@@ -347,6 +357,23 @@ impl CellSliceRange {
         }
     }
 
+    /// Advances the start of data and refs windows.
+    ///
+    /// If `strict` is `true`, fails when `bits` or `refs` are greater than
+    /// the remainder (same as [`advance`]). Otherwise, the windows are advanced
+    /// to the end when there is not enough bits or refs.
+    ///
+    /// [`advance`]: CellSliceRange::advance
+    pub fn advance_ext(&mut self, bits: u16, refs: u8, strict: bool) -> Result<(), Error> {
+        if strict {
+            return self.advance(bits, refs);
+        }
+
+        self.bits_start = std::cmp::min(self.bits_start.saturating_add(bits), self.bits_end);
+        self.refs_start = std::cmp::min(self.refs_start.saturating_add(refs), self.refs_end);
+        Ok(())
+    }
+
     /// Returns a slice range starting at the same bits and refs offsets,
     /// and containing no more than `bits` of data and `refs` of children.
     pub fn get_prefix(&self, bits: u16, refs: u8) -> Self {
@@ -399,11 +426,14 @@ impl<'a> AsMut<CellSlice<'a>> for CellSlice<'a> {
 
 impl<'a> CellSlice<'a> {
     /// Constructs a new cell slice from the specified cell.
-    /// Returns an error if the cell is pruned.
+    /// Returns an error if the cell is pruned or absent.
     pub fn new(cell: &'a DynCell) -> Result<Self, Error> {
-        // Handle pruned branch access
-        if unlikely(cell.descriptor().is_pruned_branch()) {
+        // Handle pruned branch or absent cell access
+        let descriptor = cell.descriptor();
+        if unlikely(descriptor.is_pruned_branch()) {
             Err(Error::PrunedBranchAccess)
+        } else if unlikely(descriptor.is_absent()) {
+            Err(Error::AbsentCellAccess)
         } else {
             Ok(Self {
                 range: CellSliceRange::full(cell),
@@ -616,6 +646,65 @@ impl<'a> CellSlice<'a> {
         self.range.advance(bits, refs)
     }
 
+    /// Advances the start of data and refs windows.
+    ///
+    /// If `strict` is `true`, fails when `bits` or `refs` are greater than
+    /// the remainder (same as [`advance`]). Otherwise, the windows are advanced
+    /// to the end when there is not enough bits or refs.
+    ///
+    /// [`advance`]: CellSlice::advance
+    pub fn advance_ext(&mut self, bits: u16, refs: u8, strict: bool) -> Result<(), Error> {
+        self.range.advance_ext(bits, refs, strict)
+    }
+
+    /// Skips a value of the specified type.
+    ///
+    /// Uses [`Load`] implementation of the type to find the end of the value.
+    /// The slice is not modified if the value could not be loaded.
+    pub fn skip_value<T: Load<'a>>(&mut self) -> Result<(), Error> {
+        let mut slice = *self;
+        match T::load_from(&mut slice) {
+            Ok(_) => {
+                *self = slice;
+                Ok(())
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Loads a value of the specified type and checks that it is encoded canonically.
+    ///
+    /// The loaded value is stored again using its [`Store`] implementation,
+    /// and the result must match the loaded bits and refs exactly. This rejects
+    /// encodings which are accepted by [`Load`] for interop, but are never produced
+    /// by the canonical serializer (e.g. `VarUint` values with leading zero bytes).
+    ///
+    /// NOTE: Only the data of this slice is checked. Dictionaries in references
+    /// can be checked separately with [`Dict::check_canonical`].
+    ///
+    /// The slice is not modified if the value could not be loaded or is not canonical.
+    ///
+    /// [`Dict::check_canonical`]: crate::dict::Dict::check_canonical
+    pub fn load_strict<T: Load<'a> + Store>(&mut self) -> Result<T, Error> {
+        let mut slice = *self;
+        let value = ok!(T::load_from(&mut slice));
+
+        let loaded = self.get_prefix(
+            self.remaining_bits() - slice.remaining_bits(),
+            self.remaining_refs() - slice.remaining_refs(),
+        );
+
+        let mut builder = CellBuilder::new();
+        ok!(value.store_into(&mut builder, &mut Cell::empty_context()));
+        // NOTE: Builder cells have no hash, so the fast path is skipped
+        if ok!(loaded.cmp_by_content_only(&builder.as_full_slice())) != std::cmp::Ordering::Equal {
+            return Err(Error::InvalidData);
+        }
+
+        *self = slice;
+        Ok(value)
+    }
+
     /// Compares two slices by their data window **content** and refs.
     ///
     /// NOTE: this method is quite computationally heavy as it compares the content
@@ -781,81 +870,21 @@ impl<'a> CellSlice<'a> {
             return 0;
         }
         let self_remaining_bits = self.range.bits_end - self.range.bits_start;
-        let self_data = self.cell.data();
         let other_remaining_bits = other.range.bits_end - other.range.bits_start;
-        let other_data = other.cell.data();
 
         // Compute max prefix length in bits
         let max_bit_len = std::cmp::min(self_remaining_bits, other_remaining_bits).min(max_hint);
 
-        // Compute shifts and data offsets
-        let self_r = self.range.bits_start % 8;
-        let self_q = (self.range.bits_start / 8) as usize;
-        let other_r = other.range.bits_start % 8;
-        let other_q = (other.range.bits_start / 8) as usize;
-
-        // Compute remaining bytes to check
-        let self_bytes = (((self_r + max_bit_len) + 7) / 8) as usize;
-        debug_assert!((self_q + self_bytes) <= self_data.len());
-        let other_bytes = (((other_r + max_bit_len) + 7) / 8) as usize;
-        debug_assert!((other_q + other_bytes) <= other_data.len());
-
-        let aligned_bytes = std::cmp::min(self_bytes, other_bytes);
-
-        let mut prefix_len: u16 = 0;
-
+        // SAFETY: slice ranges are always within the cell data
         unsafe {
-            let self_data_ptr = self_data.as_ptr().add(self_q);
-            let other_data_ptr = other_data.as_ptr().add(other_q);
-
-            // Get first bytes aligned to the left
-            let mut self_byte = *self_data_ptr << self_r;
-            let mut other_byte = *other_data_ptr << other_r;
-
-            // For all aligned bytes except the first
-            for i in 1..aligned_bytes {
-                // Concat previous bits with current bits
-                // NOTE: shift as `u16` to allow overflow
-                let next_self_byte = *self_data_ptr.add(i);
-                self_byte |= ((next_self_byte as u16) >> (8 - self_r)) as u8;
-                let next_other_byte = *other_data_ptr.add(i);
-                other_byte |= ((next_other_byte as u16) >> (8 - other_r)) as u8;
-
-                // XOR bytes to check equality
-                match self_byte ^ other_byte {
-                    // All bits are equal, update current bytes and move forward
-                    0 => {
-                        prefix_len += 8;
-                        self_byte = next_self_byte << self_r;
-                        other_byte = next_other_byte << other_r;
-                    }
-                    // Some bits are not equal
-                    x => {
-                        // Number of leading zeros is the number of equal bits
-                        return std::cmp::min(prefix_len + x.leading_zeros() as u16, max_bit_len);
-                    }
-                }
-            }
-
-            // Concat remaining bits
-            if self_r > 0 && aligned_bytes < self_bytes {
-                self_byte |= *self_data_ptr.add(aligned_bytes) >> (8 - self_r);
-            }
-            if other_r > 0 && aligned_bytes < other_bytes {
-                other_byte |= *other_data_ptr.add(aligned_bytes) >> (8 - other_r);
-            }
-
-            // Apply last byte mask
-            let last_byte_mask = 0xff << ((8 - max_bit_len % 8) % 8);
-            self_byte &= last_byte_mask;
-            other_byte &= last_byte_mask;
-
-            // Count the number of remaining equal bits
-            prefix_len += (self_byte ^ other_byte).leading_zeros() as u16;
+            crate::util::bits::common_prefix_len_unchecked(
+                self.cell.data(),
+                self.range.bits_start,
+                other.cell.data(),
+                other.range.bits_start,
+                max_bit_len,
+            )
         }
-
-        // Return the longest prefix (without equal bits from the last byte mask)
-        std::cmp::min(prefix_len, max_bit_len)
     }
 
     /// Checks whether the current slice consists of the same bits,
@@ -1079,6 +1108,14 @@ impl<'a> CellSlice<'a> {
         res
     }
 
+    /// Reads the next `u32` without advancing the bits window start.
+    ///
+    /// Equivalent to `get_u32(0)`.
+    #[inline]
+    pub fn peek_u32(&self) -> Result<u32, Error> {
+        self.get_u32(0)
+    }
+
     /// Reads `u64` starting from the `offset`.
     pub fn get_u64(&self, offset: u16) -> Result<u64, Error> {
         if self.range.bits_start + offset + 64 <= self.range.bits_end {
@@ -1228,6 +1265,26 @@ impl<'a> CellSlice<'a> {
         res
     }
 
+    /// Reads `N` bytes starting from the `offset`.
+    pub fn get_array<const N: usize>(&self, offset: u16) -> Result<[u8; N], Error> {
+        let mut result = [0; N];
+        if N <= MAX_BIT_LEN as usize / 8 {
+            let bits = N as u16 * 8;
+            if self.range.bits_start + offset + bits <= self.range.bits_end {
+                ok!(self.get_raw(offset, &mut result, bits));
+                return Ok(result);
+            }
+        }
+        Err(Error::CellUnderflow)
+    }
+
+    /// Tries to read the next `N` bytes, incrementing the bits window start.
+    pub fn load_array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let res = self.get_array::<N>(0);
+        self.range.bits_start += N as u16 * 8 * res.is_ok() as u16;
+        res
+    }
+
     /// Returns a small subset of `bits` (0..=8) starting from the `offset`.
     ///
     /// NOTE: Reading zero bits always succeeds,
@@ -1369,43 +1426,13 @@ impl<'a> CellSlice<'a> {
                 return Err(Error::CellUnderflow);
             };
 
-            let r = index % 8;
-            let q = (index / 8) as usize;
-
-            // SAFETY: q will be checked to be in range 0..data_len,
-            // r is in range 0..=7, target is guaranteed to be `target_len`
-            unsafe {
-                let mut data_ptr = data.as_ptr().add(q);
-                let target_ptr = target.as_mut_ptr();
-
-                if r == 0 && q + target_len <= data_len {
-                    std::ptr::copy_nonoverlapping(data_ptr, target_ptr, target_len);
-                } else if r != 0 {
-                    let byte_len = ((bits + r + 7) / 8) as usize - 1;
-                    if q + byte_len > data_len {
-                        return Err(Error::CellUnderflow);
-                    }
-
-                    let shift = 8 - r;
-                    for i in 0..byte_len {
-                        let target = target_ptr.add(i);
-                        *target = *data_ptr << r;
-                        data_ptr = data_ptr.add(1);
-                        *target |= *data_ptr >> shift;
-                    }
-                    if byte_len < target_len {
-                        *target_ptr.add(byte_len) = *data_ptr << r;
-                    }
-                } else {
-                    return Err(Error::CellUnderflow);
-                }
-
-                let bits_r = bits % 8;
-                if bits_r != 0 {
-                    *target_ptr.add(target_len - 1) &= 0xff << (8 - bits_r);
-                }
-                Ok(target)
+            if index as usize + bits as usize > data_len * 8 {
+                return Err(Error::CellUnderflow);
             }
+
+            // SAFETY: target length and data bounds are checked above
+            unsafe { crate::util::bits::read_bits_unchecked(data, index, target, bits) };
+            Ok(target)
         } else {
             Err(Error::CellUnderflow)
         }
@@ -1423,6 +1450,36 @@ impl<'a> CellSlice<'a> {
         res
     }
 
+    /// Tries to read the next unsigned big integer (but only the specified number of bits),
+    /// incrementing the bits window start.
+    #[cfg(feature = "bigint")]
+    pub fn load_biguint(&mut self, bits: u16) -> Result<num_bigint::BigUint, Error> {
+        let mut buffer = [0u8; 128];
+        let bytes = ok!(self.load_raw(&mut buffer, bits));
+
+        let mut int = num_bigint::BigUint::from_bytes_be(bytes);
+        int >>= (bytes.len() * 8) as u16 - bits;
+        Ok(int)
+    }
+
+    /// Tries to read the next big integer (but only the specified number of bits),
+    /// incrementing the bits window start.
+    ///
+    /// Signed values are read in two's complement representation.
+    #[cfg(feature = "bigint")]
+    pub fn load_bigint(&mut self, bits: u16, signed: bool) -> Result<num_bigint::BigInt, Error> {
+        if !signed {
+            return self.load_biguint(bits).map(num_bigint::BigInt::from);
+        }
+
+        let mut buffer = [0u8; 128];
+        let bytes = ok!(self.load_raw(&mut buffer, bits));
+
+        let mut int = num_bigint::BigInt::from_signed_bytes_be(bytes);
+        int >>= (bytes.len() * 8) as u16 - bits;
+        Ok(int)
+    }
+
     /// Reads all remaining bits and refs into the new slice.
     pub fn load_remaining(&mut self) -> CellSlice<'a> {
         let result = *self;
@@ -1952,6 +2009,26 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn load_arrays() -> anyhow::Result<()> {
+        let cell = build_cell(|b| {
+            b.store_bit_one()?;
+            b.store_raw(&[0xaa; 64], 512)?;
+            b.store_raw(&[0x55; 3], 24)
+        });
+        let mut slice = cell.as_slice()?;
+        assert!(slice.try_advance(1, 0));
+
+        assert_eq!(slice.get_array::<3>(512)?, [0x55; 3]);
+        assert_eq!(slice.load_array::<64>()?, [0xaa; 64]);
+        assert!(slice.load_array::<4>().is_err());
+        assert_eq!(slice.load_array::<3>()?, [0x55; 3]);
+        assert_eq!(slice.load_array::<0>()?, [0u8; 0]);
+        assert!(slice.is_data_empty());
+
+        Ok(())
+    }
+
     #[test]
     fn compare_by_content() -> anyhow::Result<()> {
         fn cmp<L, R>(l: L, r: R) -> Result<std::cmp::Ordering, Error>
@@ -1998,4 +2075,44 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn skip_values() -> anyhow::Result<()> {
+        use crate::num::Tokens;
+
+        let child = Cell::empty_cell();
+        let cell = build_cell(|b| {
+            Tokens::new(123456).store_into(b, &mut Cell::empty_context())?;
+            b.store_u32(0xdeadbeaf)?;
+            b.store_reference(child.clone())?;
+            b.store_bit_one()
+        });
+
+        let mut slice = cell.as_slice()?;
+        slice.skip_value::<Tokens>()?;
+        slice.skip_value::<u32>()?;
+        slice.skip_value::<Cell>()?;
+        assert_eq!(slice.remaining_bits(), 1);
+        assert_eq!(slice.remaining_refs(), 0);
+
+        // Slice is not modified on error
+        let before = slice;
+        assert!(slice.skip_value::<u32>().is_err());
+        assert_eq!(slice, before);
+
+        // Strict advance
+        let mut slice = cell.as_slice()?;
+        assert!(slice.advance_ext(1000, 0, true).is_err());
+        assert!(slice.advance_ext(0, 2, true).is_err());
+        slice.advance_ext(10, 1, true)?;
+        assert_eq!(slice.bits_offset(), 10);
+        assert_eq!(slice.refs_offset(), 1);
+
+        // Relaxed advance
+        slice.advance_ext(1000, 2, false)?;
+        assert!(slice.is_data_empty());
+        assert!(slice.is_refs_empty());
+
+        Ok(())
+    }
 }
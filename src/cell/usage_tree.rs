@@ -1,5 +1,5 @@
 use super::cell_impl::VirtualCellWrapper;
-use super::{Cell, CellDescriptor, CellImpl, DynCell, HashBytes};
+use super::{Cell, CellContainer, CellDescriptor, CellImpl, DynCell, HashBytes};
 use crate::util::TryAsMut;
 
 #[cfg(feature = "stats")]
@@ -115,16 +115,7 @@ impl CellImpl for UsageCell {
 
     fn reference_cloned(&self, index: u8) -> Option<Cell> {
         let cell = self.load_reference(index)?.clone();
-
-        #[cfg(not(feature = "sync"))]
-        {
-            Some(Cell::from(cell as std::rc::Rc<DynCell>))
-        }
-
-        #[cfg(feature = "sync")]
-        {
-            Some(Cell::from(cell as std::sync::Arc<DynCell>))
-        }
+        Some(Cell::from(cell as CellContainer<DynCell>))
     }
 
     fn virtualize(&self) -> &DynCell {
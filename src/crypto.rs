@@ -0,0 +1,360 @@
+//! Ed25519 public key and signature types.
+
+use std::str::FromStr;
+
+use everscale_crypto::ed25519;
+
+use crate::cell::*;
+use crate::error::{Error, ParseHashBytesError};
+
+/// Ed25519 public key.
+///
+/// Stored as `ed25519_pubkey#8e81278a pubkey:bits256`.
+#[derive(Default, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[repr(transparent)]
+pub struct PublicKey(pub [u8; 32]);
+
+impl PublicKey {
+    const TAG: u32 = 0x8e81278a;
+
+    /// Wraps a reference to an internal array into a public key.
+    #[inline(always)]
+    pub const fn wrap(value: &[u8; 32]) -> &Self {
+        // SAFETY: PublicKey is #[repr(transparent)]
+        unsafe { &*(value as *const [u8; 32] as *const Self) }
+    }
+
+    /// Returns an internal array.
+    #[inline(always)]
+    pub const fn as_array(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// Returns a slice containing the entire array.
+    #[inline(always)]
+    pub const fn as_slice(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+
+    /// Encodes the public key as a base64 string.
+    #[cfg(feature = "base64")]
+    pub fn to_base64(&self) -> String {
+        crate::util::encode_base64(self.0)
+    }
+
+    /// Converts the public key into an [`ed25519::PublicKey`].
+    ///
+    /// Returns `None` if the bytes are not a valid curve point.
+    pub fn to_ed25519(&self) -> Option<ed25519::PublicKey> {
+        ed25519::PublicKey::from_bytes(self.0)
+    }
+
+    /// Converts the public key into an [`ed25519_dalek::VerifyingKey`].
+    ///
+    /// Returns `None` if the bytes are not a valid curve point.
+    #[cfg(feature = "abi")]
+    pub fn to_verifying_key(&self) -> Option<ed25519_dalek::VerifyingKey> {
+        ed25519_dalek::VerifyingKey::from_bytes(&self.0).ok()
+    }
+
+    /// Verifies the signature of the specified data.
+    ///
+    /// Returns `false` for invalid public keys.
+    pub fn verify_raw(&self, data: &[u8], signature: &Signature) -> bool {
+        match self.to_ed25519() {
+            Some(public_key) => public_key.verify_raw(data, signature.as_ref()),
+            None => false,
+        }
+    }
+}
+
+impl AsRef<[u8; 32]> for PublicKey {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8; 32] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for PublicKey {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl From<[u8; 32]> for PublicKey {
+    #[inline(always)]
+    fn from(value: [u8; 32]) -> Self {
+        Self(value)
+    }
+}
+
+impl From<HashBytes> for PublicKey {
+    #[inline(always)]
+    fn from(value: HashBytes) -> Self {
+        Self(value.0)
+    }
+}
+
+impl From<PublicKey> for HashBytes {
+    #[inline(always)]
+    fn from(value: PublicKey) -> Self {
+        Self(value.0)
+    }
+}
+
+impl From<ed25519::PublicKey> for PublicKey {
+    #[inline]
+    fn from(value: ed25519::PublicKey) -> Self {
+        Self(value.to_bytes())
+    }
+}
+
+impl From<&ed25519::PublicKey> for PublicKey {
+    #[inline]
+    fn from(value: &ed25519::PublicKey) -> Self {
+        Self(value.to_bytes())
+    }
+}
+
+#[cfg(feature = "abi")]
+impl From<ed25519_dalek::VerifyingKey> for PublicKey {
+    #[inline]
+    fn from(value: ed25519_dalek::VerifyingKey) -> Self {
+        Self(value.to_bytes())
+    }
+}
+
+#[cfg(feature = "abi")]
+impl From<&ed25519_dalek::VerifyingKey> for PublicKey {
+    #[inline]
+    fn from(value: &ed25519_dalek::VerifyingKey) -> Self {
+        Self(value.to_bytes())
+    }
+}
+
+impl FromStr for PublicKey {
+    type Err = ParseHashBytesError;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HashBytes::from_str(s).map(Self::from)
+    }
+}
+
+impl std::fmt::Display for PublicKey {
+    #[inline]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(HashBytes::wrap(&self.0), f)
+    }
+}
+
+impl std::fmt::Debug for PublicKey {
+    #[inline(always)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl Store for PublicKey {
+    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        ok!(builder.store_u32(Self::TAG));
+        builder.store_array(&self.0)
+    }
+}
+
+impl<'a> Load<'a> for PublicKey {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        match slice.load_u32() {
+            Ok(Self::TAG) => {}
+            Ok(_) => return Err(Error::InvalidTag),
+            Err(e) => return Err(e),
+        }
+
+        match slice.load_array() {
+            Ok(public_key) => Ok(Self(public_key)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for PublicKey {
+    #[inline]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        HashBytes::wrap(&self.0).serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for PublicKey {
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        HashBytes::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// Ed25519 signature.
+///
+/// Stored as `ed25519_signature#5 R:bits256 s:bits256`.
+#[derive(Copy, Clone, Eq, PartialEq, Hash)]
+#[repr(transparent)]
+pub struct Signature(pub [u8; 64]);
+
+impl Signature {
+    const TAG_LEN: u16 = 4;
+
+    const TAG: u8 = 0x5;
+
+    /// Returns an internal array.
+    #[inline(always)]
+    pub const fn as_array(&self) -> &[u8; 64] {
+        &self.0
+    }
+
+    /// Returns a slice containing the entire array.
+    #[inline(always)]
+    pub const fn as_slice(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+
+    /// Encodes the signature as a base64 string.
+    #[cfg(feature = "base64")]
+    pub fn to_base64(&self) -> String {
+        crate::util::encode_base64(self.0)
+    }
+}
+
+impl Default for Signature {
+    #[inline]
+    fn default() -> Self {
+        Self([0; 64])
+    }
+}
+
+impl AsRef<[u8; 64]> for Signature {
+    #[inline]
+    fn as_ref(&self) -> &[u8; 64] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for Signature {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref()
+    }
+}
+
+impl From<[u8; 64]> for Signature {
+    #[inline(always)]
+    fn from(value: [u8; 64]) -> Self {
+        Self(value)
+    }
+}
+
+#[cfg(feature = "abi")]
+impl From<ed25519_dalek::Signature> for Signature {
+    #[inline]
+    fn from(value: ed25519_dalek::Signature) -> Self {
+        Self(value.to_bytes())
+    }
+}
+
+#[cfg(feature = "abi")]
+impl From<&ed25519_dalek::Signature> for Signature {
+    #[inline]
+    fn from(value: &ed25519_dalek::Signature) -> Self {
+        Self(value.to_bytes())
+    }
+}
+
+#[cfg(feature = "abi")]
+impl From<Signature> for ed25519_dalek::Signature {
+    #[inline]
+    fn from(value: Signature) -> Self {
+        Self::from_bytes(&value.0)
+    }
+}
+
+impl std::fmt::Display for Signature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let mut output = [0u8; 128];
+        hex::encode_to_slice(self.0, &mut output).ok();
+
+        // SAFETY: output is guaranteed to contain only [0-9a-f]
+        let output = unsafe { std::str::from_utf8_unchecked(&output) };
+        f.write_str(output)
+    }
+}
+
+impl std::fmt::Debug for Signature {
+    #[inline(always)]
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(self, f)
+    }
+}
+
+impl Store for Signature {
+    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        ok!(builder.store_small_uint(Self::TAG, Self::TAG_LEN));
+        builder.store_array(&self.0)
+    }
+}
+
+impl<'a> Load<'a> for Signature {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        match slice.load_small_uint(Self::TAG_LEN) {
+            Ok(Self::TAG) => {}
+            Ok(_) => return Err(Error::InvalidTag),
+            Err(e) => return Err(e),
+        }
+
+        match slice.load_array() {
+            Ok(signature) => Ok(Self(signature)),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "abi")]
+    fn crypto_wrappers() {
+        use ed25519_dalek::Signer;
+
+        let key = ed25519_dalek::SigningKey::from_bytes(&[0x11; 32]);
+        let data = b"hello world";
+
+        let public_key = PublicKey::from(key.verifying_key());
+        let signature = Signature::from(key.sign(data));
+        assert_eq!(public_key.to_verifying_key(), Some(key.verifying_key()));
+        assert_eq!(ed25519_dalek::Signature::from(signature), key.sign(data));
+        assert!(public_key.verify_raw(data, &signature));
+        assert!(!public_key.verify_raw(b"other data", &signature));
+        assert!(!PublicKey([0xff; 32]).verify_raw(data, &signature));
+
+        // Display/FromStr
+        let parsed = public_key.to_string().parse::<PublicKey>().unwrap();
+        assert_eq!(parsed, public_key);
+        assert_eq!(signature.to_string(), hex::encode(signature.0));
+
+        // Store/Load
+        let cell = CellBuilder::build_from(public_key).unwrap();
+        assert_eq!(cell.bit_len(), 32 + 256);
+        assert_eq!(cell.parse::<PublicKey>().unwrap(), public_key);
+
+        let cell = CellBuilder::build_from(signature).unwrap();
+        assert_eq!(cell.bit_len(), 4 + 512);
+        assert_eq!(cell.parse::<Signature>().unwrap(), signature);
+    }
+}
@@ -1,30 +1,17 @@
 use std::borrow::Borrow;
 use std::marker::PhantomData;
 
-use super::{aug_dict_insert, aug_dict_remove_owned, SetMode};
+use super::{
+    aug_dict_insert, aug_dict_merge, aug_dict_remove_bound_owned, aug_dict_remove_owned,
+    build_aug_dict_from_sorted, dict_split_by_prefix, SetMode,
+};
 use crate::cell::*;
 use crate::error::*;
 use crate::util::*;
 
 use super::raw::*;
 use super::typed::*;
-use super::{read_label, AugDictFn, DictKey};
-
-// TODO: Just use load instead?
-pub(crate) trait AugDictSkipValue<'a> {
-    fn skip_value(slice: &mut CellSlice<'a>) -> bool;
-}
-
-impl<'a> AugDictSkipValue<'a> for crate::num::Tokens {
-    #[inline]
-    fn skip_value(slice: &mut CellSlice<'a>) -> bool {
-        if let Ok(token_bytes) = slice.load_small_uint(4) {
-            slice.try_advance(8 * token_bytes as u16, 0)
-        } else {
-            false
-        }
-    }
-}
+use super::{read_label, with_key_slice, AugDictFn, DictBound, DictKey};
 
 /// Typed augmented dictionary with fixed length keys.
 ///
@@ -125,14 +112,17 @@ impl<K, A: Default, V> AugDict<K, A, V> {
 }
 
 impl<K: DictKey, A, V> AugDict<K, A, V> {
-    #[allow(unused)]
-    pub(crate) fn load_from_root<'a>(
+    /// Loads a non-empty dictionary from a root cell.
+    ///
+    /// NOTE: Value [`Load`] implementation is used to skip the value
+    /// of the root leaf (if the dictionary has only one entry).
+    pub fn load_from_root<'a>(
         slice: &mut CellSlice<'a>,
         context: &mut dyn CellContext,
     ) -> Result<Self, Error>
     where
         A: Load<'a>,
-        V: AugDictSkipValue<'a>,
+        V: Load<'a>,
     {
         let (extra, root) = ok!(load_from_root::<A, V>(slice, K::BITS, context));
 
@@ -164,6 +154,80 @@ where
         };
         Ok(())
     }
+
+    /// Splits the dictionary into two by the first bit of the key.
+    ///
+    /// Returns the dictionaries with keys which start with `0` and `1` respectively.
+    pub fn split(&self) -> Result<(Self, Self), Error> {
+        self.split_by_prefix(&CellSlice::default())
+    }
+
+    /// Splits the dictionary into two by the key bit which follows the `key_prefix`.
+    ///
+    /// Use [`split_by_prefix_ext`] if you need to use a custom cell context.
+    ///
+    /// [`split_by_prefix_ext`]: AugDict::split_by_prefix_ext
+    pub fn split_by_prefix(&self, key_prefix: &CellSlice<'_>) -> Result<(Self, Self), Error> {
+        self.split_by_prefix_ext(key_prefix, &mut Cell::empty_context())
+    }
+
+    /// Splits the dictionary into two by the key bit which follows the `key_prefix`.
+    ///
+    /// Root extra values of both parts are taken from their root nodes,
+    /// so no recomputation is needed.
+    pub fn split_by_prefix_ext(
+        &self,
+        key_prefix: &CellSlice<'_>,
+        context: &mut dyn CellContext,
+    ) -> Result<(Self, Self), Error> {
+        let (left, right) = ok!(dict_split_by_prefix(
+            self.dict.root.as_ref(),
+            key_prefix,
+            K::BITS,
+            context
+        ));
+
+        let make_part = |root: Option<Cell>| {
+            let mut part = Self {
+                dict: Dict::from(root),
+                extra: A::default(),
+                _key: PhantomData,
+                _value: PhantomData,
+            };
+            ok!(part.update_root_extra());
+            Ok::<_, Error>(part)
+        };
+        Ok((ok!(make_part(left)), ok!(make_part(right))))
+    }
+
+    /// Merges the dictionary with another one with disjoint keys.
+    ///
+    /// Use [`merge_ext`] if you need to use a custom cell context.
+    ///
+    /// [`merge_ext`]: AugDict::merge_ext
+    pub fn merge(&mut self, other: &Self, comparator: AugDictFn) -> Result<(), Error> {
+        self.merge_ext(other, comparator, &mut Cell::empty_context())
+    }
+
+    /// Merges the dictionary with another one with disjoint keys.
+    ///
+    /// Returns [`Error::InvalidData`] if some key is present in both dictionaries.
+    /// The dictionary is left unchanged in case of an error.
+    pub fn merge_ext(
+        &mut self,
+        other: &Self,
+        comparator: AugDictFn,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
+        ok!(aug_dict_merge(
+            &mut self.dict.root,
+            &other.dict.root,
+            K::BITS,
+            comparator,
+            context
+        ));
+        self.update_root_extra()
+    }
 }
 
 fn load_from_root<'a, A, V>(
@@ -173,7 +237,7 @@ fn load_from_root<'a, A, V>(
 ) -> Result<(A, Cell), Error>
 where
     A: Load<'a>,
-    V: AugDictSkipValue<'a>,
+    V: Load<'a>,
 {
     let root = *slice;
 
@@ -185,9 +249,7 @@ where
         ok!(A::load_from(slice))
     } else {
         let extra = ok!(A::load_from(slice));
-        if !V::skip_value(slice) {
-            return Err(Error::CellUnderflow);
-        }
+        ok!(slice.skip_value::<V>());
         extra
     };
 
@@ -254,6 +316,44 @@ where
     for<'a> A: Default + Store + Load<'a>,
     V: Store,
 {
+    /// Builds an augmented dictionary from entries sorted by their keys.
+    ///
+    /// Extra values of forks are computed with the `comparator`,
+    /// so each node is built only once instead of inserting entries
+    /// one by one.
+    ///
+    /// NOTE: Keys must be unique and sorted in the unsigned order
+    /// (even for signed keys), otherwise [`Error::InvalidData`] is returned.
+    pub fn try_from_sorted_iter<I>(entries: I, comparator: AugDictFn) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (K, A, V)>,
+    {
+        let context = &mut Cell::empty_context();
+
+        let mut items = Vec::new();
+        for (key, extra, value) in entries {
+            let mut builder = CellBuilder::new();
+            ok!(key.store_into(&mut builder, context));
+            items.push((builder, extra, value));
+        }
+
+        let root = ok!(build_aug_dict_from_sorted(
+            &items,
+            K::BITS,
+            comparator,
+            context
+        ));
+
+        let mut result = Self {
+            dict: Dict::from(root),
+            extra: A::default(),
+            _key: PhantomData,
+            _value: PhantomData,
+        };
+        ok!(result.update_root_extra());
+        Ok(result)
+    }
+
     /// Sets the augmented value associated with the key in the aug dictionary.
     ///
     /// Use [`set_ext`] if you need to use a custom cell context.
@@ -423,6 +523,96 @@ where
         self.remove_impl(key.borrow(), comparator, context)
     }
 
+    /// Removes the lowest key from the dictionary.
+    /// Returns an optional removed key, augmented value and value.
+    ///
+    /// Extras of the affected forks are recomputed in the same traversal.
+    pub fn remove_min(
+        &mut self,
+        signed: bool,
+        comparator: AugDictFn,
+    ) -> Result<Option<(K, A, V)>, Error>
+    where
+        for<'a> V: Load<'a> + 'static,
+    {
+        self.remove_bound(DictBound::Min, signed, comparator)
+    }
+
+    /// Removes the largest key from the dictionary.
+    /// Returns an optional removed key, augmented value and value.
+    ///
+    /// Extras of the affected forks are recomputed in the same traversal.
+    pub fn remove_max(
+        &mut self,
+        signed: bool,
+        comparator: AugDictFn,
+    ) -> Result<Option<(K, A, V)>, Error>
+    where
+        for<'a> V: Load<'a> + 'static,
+    {
+        self.remove_bound(DictBound::Max, signed, comparator)
+    }
+
+    /// Removes the specified dictionary bound.
+    /// Returns an optional removed key, augmented value and value.
+    ///
+    /// Use [`remove_bound_raw_ext`] if you need to use a custom cell context.
+    ///
+    /// [`remove_bound_raw_ext`]: AugDict::remove_bound_raw_ext
+    pub fn remove_bound(
+        &mut self,
+        bound: DictBound,
+        signed: bool,
+        comparator: AugDictFn,
+    ) -> Result<Option<(K, A, V)>, Error>
+    where
+        for<'a> V: Load<'a> + 'static,
+    {
+        let removed =
+            ok!(self.remove_bound_raw_ext(bound, signed, comparator, &mut Cell::empty_context()));
+        match removed {
+            Some((key, (cell, range))) => {
+                let mut slice = ok!(range.apply(&cell));
+                let extra = ok!(A::load_from(&mut slice));
+                let value = ok!(V::load_from(&mut slice));
+                Ok(Some((key, extra, value)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Removes the specified dictionary bound.
+    /// Returns an optional removed key and value as cell slice parts.
+    ///
+    /// Dict is rebuilt using the provided cell context.
+    pub fn remove_bound_raw_ext(
+        &mut self,
+        bound: DictBound,
+        signed: bool,
+        comparator: AugDictFn,
+        context: &mut dyn CellContext,
+    ) -> Result<Option<(K, CellSliceParts)>, Error> {
+        let removed = ok!(aug_dict_remove_bound_owned(
+            &mut self.dict.root,
+            K::BITS,
+            bound,
+            signed,
+            comparator,
+            context
+        ));
+
+        let res = match removed {
+            Some((key, value)) => match K::from_raw_data(key.raw_data()) {
+                Some(key) => Some((key, value)),
+                None => return Err(Error::CellUnderflow),
+            },
+            None => return Ok(None),
+        };
+
+        ok!(self.update_root_extra());
+        Ok(res)
+    }
+
     fn insert_impl(
         &mut self,
         key: &K,
@@ -432,18 +622,18 @@ where
         comparator: AugDictFn,
         context: &mut dyn CellContext,
     ) -> Result<bool, Error> {
-        let mut key_builder = CellBuilder::new();
-        ok!(key.store_into(&mut key_builder, &mut Cell::empty_context()));
-        let inserted = ok!(aug_dict_insert(
-            &mut self.dict.root,
-            &mut key_builder.as_data_slice(),
-            K::BITS,
-            extra,
-            value,
-            mode,
-            comparator,
-            context,
-        ));
+        let inserted = ok!(with_key_slice(key, |mut key| {
+            aug_dict_insert(
+                &mut self.dict.root,
+                &mut key,
+                K::BITS,
+                extra,
+                value,
+                mode,
+                comparator,
+                context,
+            )
+        }));
 
         if inserted {
             ok!(self.update_root_extra());
@@ -458,16 +648,16 @@ where
         comparator: AugDictFn,
         context: &mut dyn CellContext,
     ) -> Result<Option<(Cell, CellSliceRange)>, Error> {
-        let mut key_builder = CellBuilder::new();
-        ok!(key.store_into(&mut key_builder, &mut Cell::empty_context()));
-        let res = ok!(aug_dict_remove_owned(
-            &mut self.dict.root,
-            &mut key_builder.as_data_slice(),
-            K::BITS,
-            false,
-            comparator,
-            context,
-        ));
+        let res = ok!(with_key_slice(key, |mut key| {
+            aug_dict_remove_owned(
+                &mut self.dict.root,
+                &mut key,
+                K::BITS,
+                false,
+                comparator,
+                context,
+            )
+        }));
 
         if res.is_some() {
             ok!(self.update_root_extra());
@@ -501,6 +691,20 @@ where
         AugIter::new(self.dict.root())
     }
 
+    /// Gets an iterator over the entries of the dictionary within
+    /// the specified range of keys, sorted by key.
+    /// The iterator element type is `Result<(K, (A, V))>`.
+    ///
+    /// See [`Dict::range`] for more.
+    pub fn range<'a, R>(&'a self, range: R) -> Result<Range<'a, K, (A, V)>, Error>
+    where
+        K: Store,
+        R: std::ops::RangeBounds<K>,
+        (A, V): Load<'a>,
+    {
+        self.dict.range(range)
+    }
+
     /// Gets an iterator over the keys of the dictionary, in sorted order.
     /// The iterator element type is `Result<K>`.
     ///
@@ -527,11 +731,13 @@ where
     ///
     /// If the dictionary is invalid, finishes after the first invalid element,
     /// returning an error.
+    ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
     pub fn values<'a>(&'a self) -> Values<'a, (A, V)>
     where
         V: Load<'a>,
     {
-        Values::new(self.dict.root(), K::BITS)
+        Values::new_ext(self.dict.root(), K::BITS, false, K::SIGNED)
     }
 }
 
@@ -545,6 +751,8 @@ where
     /// If the dictionary is invalid, finishes after the first invalid element,
     /// returning an error.
     ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
+    ///
     /// # Performance
     ///
     /// In the current implementation, iterating over dictionary builds a key
@@ -553,7 +761,7 @@ where
     /// [`values`]: AugDict::values
     /// [`raw_values`]: AugDict::raw_values
     pub fn raw_iter(&'_ self) -> RawIter<'_> {
-        RawIter::new(self.dict.root(), K::BITS)
+        RawIter::new_ext(self.dict.root(), K::BITS, false, K::SIGNED)
     }
 
     /// Gets an iterator over the raw keys of the dictionary, in sorted order.
@@ -562,6 +770,8 @@ where
     /// If the dictionary is invalid, finishes after the first invalid element,
     /// returning an error.
     ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
+    ///
     /// # Performance
     ///
     /// In the current implementation, iterating over dictionary builds a key
@@ -570,7 +780,7 @@ where
     /// [`values`]: AugDict::values
     /// [`raw_values`]: AugDict::raw_values
     pub fn raw_keys(&'_ self) -> RawKeys<'_> {
-        RawKeys::new(self.dict.root(), K::BITS)
+        RawKeys::new_ext(self.dict.root(), K::BITS, false, K::SIGNED)
     }
 }
 
@@ -583,8 +793,10 @@ where
     ///
     /// If the dictionary is invalid, finishes after the first invalid element,
     /// returning an error.
+    ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
     pub fn raw_values(&'_ self) -> RawValues<'_> {
-        RawValues::new(self.dict.root(), K::BITS)
+        RawValues::new_ext(self.dict.root(), K::BITS, false, K::SIGNED)
     }
 }
 
@@ -687,6 +899,13 @@ where
         self.inner = self.inner.signed();
         self
     }
+
+    /// Changes the behavior of the iterator to use the raw bit order.
+    #[inline]
+    pub fn unsigned(mut self) -> Self {
+        self.inner = self.inner.unsigned();
+        self
+    }
 }
 
 impl<'a, K, A, V> Iterator for AugIter<'a, K, A, V>
@@ -806,6 +1025,101 @@ mod tests {
         assert!(dict.is_empty());
     }
 
+    #[test]
+    fn dict_remove_bounds() {
+        let mut dict = AugDict::<i32, u32, u32>::new();
+        for i in -10..10 {
+            dict.set(i, i.unsigned_abs(), (i * 10) as u32, u32_add_comp)
+                .unwrap();
+        }
+        assert_eq!(*dict.root_extra(), 100);
+
+        // Unsigned order
+        assert_eq!(
+            dict.remove_min(false, u32_add_comp).unwrap(),
+            Some((0, 0, 0))
+        );
+        assert_eq!(
+            dict.remove_max(false, u32_add_comp).unwrap(),
+            Some((-1, 1, -10i32 as u32))
+        );
+        assert_eq!(*dict.root_extra(), 99);
+
+        // Signed order
+        let mut expected_extra = 99;
+        for i in -10i32..=-2 {
+            let (key, extra, value) = dict.remove_min(true, u32_add_comp).unwrap().unwrap();
+            assert_eq!((key, extra, value), (i, i.unsigned_abs(), (i * 10) as u32));
+            expected_extra -= extra;
+            assert_eq!(*dict.root_extra(), expected_extra);
+        }
+        for i in (1..10).rev() {
+            let (key, extra, _) = dict.remove_max(true, u32_add_comp).unwrap().unwrap();
+            assert_eq!(key, i);
+            expected_extra -= extra;
+            assert_eq!(*dict.root_extra(), expected_extra);
+
+            // Extras are the same as after a regular removal
+            let mut rebuilt = AugDict::<i32, u32, u32>::new();
+            for entry in dict.iter() {
+                let (key, extra, value) = entry.unwrap();
+                rebuilt.set(key, extra, value, u32_add_comp).unwrap();
+            }
+            assert_eq!(rebuilt.dict(), dict.dict());
+        }
+
+        assert!(dict.is_empty());
+        assert_eq!(*dict.root_extra(), 0);
+        assert_eq!(dict.remove_min(false, u32_add_comp).unwrap(), None);
+    }
+
+    #[test]
+    fn dict_remove_bounds_under_labeled_root() {
+        // The root fork has a non-empty label, so it must be rebuilt
+        // with the full key length of its segment.
+        let entries = [(0x100u32, 1u32), (0x101, 2), (0x180, 4), (0x1c0, 8)];
+
+        let mut dict = AugDict::<u32, u32, u32>::new();
+        for (key, extra) in entries {
+            dict.set(key, extra, key, u32_add_comp).unwrap();
+        }
+
+        assert_eq!(
+            dict.remove_min(false, u32_add_comp).unwrap(),
+            Some((0x100, 1, 0x100))
+        );
+        assert_eq!(
+            dict.remove_max(false, u32_add_comp).unwrap(),
+            Some((0x1c0, 8, 0x1c0))
+        );
+        assert_eq!(*dict.root_extra(), 6);
+
+        let mut rebuilt = AugDict::<u32, u32, u32>::new();
+        for (key, extra) in &entries[1..3] {
+            rebuilt.set(key, extra, key, u32_add_comp).unwrap();
+        }
+        assert_eq!(rebuilt.dict(), dict.dict());
+        assert_eq!(rebuilt.root_extra(), dict.root_extra());
+    }
+
+    #[test]
+    fn dict_load_from_root() {
+        let mut dict = AugDict::<u32, bool, u16>::new();
+        for i in 0..3 {
+            dict.set(i, i == 1, i as u16 * 100, bool_or_comp).unwrap();
+
+            // Root is a leaf for a single entry, so the value is skipped
+            let root = dict.dict.root.clone().unwrap();
+            let mut slice = root.as_slice().unwrap();
+            let loaded =
+                AugDict::<u32, bool, u16>::load_from_root(&mut slice, &mut Cell::empty_context())
+                    .unwrap();
+            assert!(slice.is_data_empty() && slice.is_refs_empty());
+            assert_eq!(loaded.root_extra(), dict.root_extra());
+            assert_eq!(loaded.get(i).unwrap(), Some((i == 1, i as u16 * 100)));
+        }
+    }
+
     #[test]
     fn dict_iter() {
         let mut dict = AugDict::<u32, u32, u32>::new();
@@ -827,6 +1141,9 @@ mod tests {
             assert_eq!(key, i as u32);
             assert_eq!(value, 9 - i as u32);
         }
+
+        let range = dict.range(3..6).unwrap().collect::<Result<Vec<_>, _>>();
+        assert_eq!(range.unwrap(), [(3, (3, 6)), (4, (4, 5)), (5, (5, 4))]);
     }
 
     #[test]
@@ -869,6 +1186,17 @@ mod tests {
         let serialized = CellBuilder::build_from(&new_dict).unwrap();
         assert_eq!(serialized.repr_hash(), boc.repr_hash());
 
+        // Bulk build from sorted entries
+        let sorted_dict =
+            AugDict::<HashBytes, CurrencyCollection, AccountBlock>::try_from_sorted_iter(
+                data.iter().rev().cloned(),
+                cc_add_comp,
+            )
+            .unwrap();
+        assert_eq!(sorted_dict.root_extra(), original_dict.root_extra());
+        let serialized = CellBuilder::build_from(&sorted_dict).unwrap();
+        assert_eq!(serialized.repr_hash(), boc.repr_hash());
+
         for (key, _, _) in data.iter() {
             new_dict.remove(key, cc_add_comp).unwrap();
         }
@@ -876,6 +1204,86 @@ mod tests {
         assert_eq!(new_dict.root_extra(), &CurrencyCollection::ZERO);
     }
 
+    #[test]
+    fn dict_from_sorted_iter() {
+        let entries = (0..1000)
+            .map(|i| (i * 3, i % 7, i))
+            .collect::<Vec<(u32, u32, u32)>>();
+
+        let mut dict = AugDict::<u32, u32, u32>::new();
+        for (key, aug, value) in &entries {
+            dict.set(key, aug, value, u32_add_comp).unwrap();
+        }
+
+        let sorted_dict =
+            AugDict::<u32, u32, u32>::try_from_sorted_iter(entries.clone(), u32_add_comp).unwrap();
+        assert_eq!(sorted_dict, dict);
+        assert_eq!(sorted_dict.root_extra(), dict.root_extra());
+
+        // Empty and single entry dicts
+        let empty = AugDict::<u32, u32, u32>::try_from_sorted_iter([], u32_add_comp).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(*empty.root_extra(), 0);
+
+        let single =
+            AugDict::<u32, u32, u32>::try_from_sorted_iter([(1, 2, 3)], u32_add_comp).unwrap();
+        assert_eq!(single.get(1).unwrap(), Some((2, 3)));
+        assert_eq!(*single.root_extra(), 2);
+
+        // Unsorted and duplicate keys
+        for entries in [[(2, 0, 0), (1, 0, 0)], [(1, 0, 0), (1, 0, 0)]] {
+            let res = AugDict::<u32, u32, u32>::try_from_sorted_iter(entries, u32_add_comp);
+            assert_eq!(res.unwrap_err(), Error::InvalidData);
+        }
+    }
+
+    #[test]
+    fn dict_split_merge() {
+        let entries = (0..100u32)
+            .map(|i| (i.wrapping_mul(0x9e3779b9), i % 7, i))
+            .collect::<Vec<_>>();
+
+        let filter = |prefix: u32, len: u32| {
+            let mut dict = AugDict::<u32, u32, u32>::new();
+            for (key, aug, value) in &entries {
+                if key >> (32 - len) == prefix {
+                    dict.set(key, aug, value, u32_add_comp).unwrap();
+                }
+            }
+            dict
+        };
+
+        let mut dict = AugDict::<u32, u32, u32>::new();
+        for (key, aug, value) in &entries {
+            dict.set(key, aug, value, u32_add_comp).unwrap();
+        }
+
+        // NOTE: Extra values are also compared
+        let (left, right) = dict.split().unwrap();
+        assert_eq!(left, filter(0, 1));
+        assert_eq!(right, filter(1, 1));
+        assert_eq!(left.root_extra() + right.root_extra(), *dict.root_extra());
+
+        let mut merged = left.clone();
+        merged.merge(&right, u32_add_comp).unwrap();
+        assert_eq!(merged, dict);
+
+        let mut key_prefix = CellBuilder::new();
+        key_prefix.store_small_uint(0b10, 2).unwrap();
+        let (left, right) = filter(0b10, 2)
+            .split_by_prefix(&key_prefix.as_data_slice())
+            .unwrap();
+        assert_eq!(left, filter(0b100, 3));
+        assert_eq!(right, filter(0b101, 3));
+
+        // Keys must be disjoint
+        assert_eq!(
+            merged.merge(&right, u32_add_comp).unwrap_err(),
+            Error::InvalidData
+        );
+        assert_eq!(merged, dict);
+    }
+
     fn bool_or_comp(
         left: &mut CellSlice<'_>,
         right: &mut CellSlice<'_>,
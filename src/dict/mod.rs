@@ -4,6 +4,9 @@ pub use aug::*;
 pub use raw::*;
 pub use typed::*;
 
+use once_cell::sync::OnceCell;
+use sha2::digest::Digest;
+
 use crate::cell::*;
 use crate::error::Error;
 
@@ -11,13 +14,38 @@ mod aug;
 mod raw;
 mod typed;
 
+#[cfg(feature = "rayon")]
+mod par;
+
 /// Type which can be used as a dictionary key.
+///
+/// Keys are stored as fixed-length bit strings, so dictionaries are
+/// ordered by the big-endian representation of their keys. For integers
+/// (including 128-bit ones) and 256-bit hashes this is the numeric order,
+/// see [`DictKey::SIGNED`] for signed integers.
 pub trait DictKey: Sized {
     /// Length in bits for a dictionary key.
     const BITS: u16;
 
+    /// Whether the key is a signed integer in two's complement form.
+    ///
+    /// Typed iterators over dictionaries with signed keys
+    /// use the signed order by default.
+    const SIGNED: bool = false;
+
     /// Creates a key from a raw builder data.
     fn from_raw_data(raw_data: &[u8; 128]) -> Option<Self>;
+
+    /// Writes key bits into the buffer without using a cell builder.
+    ///
+    /// Returns `false` if the key doesn't support this fast path,
+    /// so it will be stored using [`Store`] instead.
+    #[doc(hidden)]
+    #[inline]
+    fn write_raw_key(&self, buffer: &mut [u8; 32]) -> bool {
+        _ = buffer;
+        false
+    }
 }
 
 macro_rules! impl_dict_key {
@@ -35,20 +63,184 @@ macro_rules! impl_dict_key {
 
 impl_dict_key! {
     bool => 1 => |d| d[0] & 0x80 != 0,
-    u8 => 8 => |d| d[0],
-    i8 => 8 => |d| d[0] as i8,
-    u16 => 16 => |d| u16::from_be_bytes([d[0], d[1]]),
-    i16 => 16 => |d| i16::from_be_bytes([d[0], d[1]]),
-    u32 => 32 => |d| u32::from_be_bytes(d[..4].try_into().unwrap()),
-    i32 => 32 => |d| i32::from_be_bytes(d[..4].try_into().unwrap()),
-    u64 => 64 => |d| u64::from_be_bytes(d[..8].try_into().unwrap()),
-    i64 => 64 => |d| i64::from_be_bytes(d[..8].try_into().unwrap()),
-    u128 => 128 => |d| u128::from_be_bytes(d[..16].try_into().unwrap()),
-    i128 => 128 => |d| i128::from_be_bytes(d[..16].try_into().unwrap()),
-    [u8; 16] => 128 => |d| d[..16].try_into().unwrap(),
-    [u8; 20] => 160 => |d| d[..20].try_into().unwrap(),
-    [u8; 32] => 256 => |d| d[..32].try_into().unwrap(),
-    HashBytes => 256 => |d| HashBytes(d[..32].try_into().unwrap()),
+    (i32, HashBytes) => 288 => |d| (
+        i32::from_be_bytes(d[..4].try_into().unwrap()),
+        HashBytes(d[4..36].try_into().unwrap()),
+    ),
+}
+
+macro_rules! impl_int_dict_key {
+    ($($ty:ty => $bits:literal => $signed:literal),*$(,)?) => {
+        $(impl DictKey for $ty {
+            const BITS: u16 = $bits;
+            const SIGNED: bool = $signed;
+
+            #[inline]
+            fn from_raw_data(raw_data: &[u8; 128]) -> Option<Self> {
+                Some(<$ty>::from_be_bytes(raw_data[..$bits / 8].try_into().unwrap()))
+            }
+
+            #[inline]
+            fn write_raw_key(&self, buffer: &mut [u8; 32]) -> bool {
+                buffer[..$bits / 8].copy_from_slice(&self.to_be_bytes());
+                true
+            }
+        })*
+    };
+}
+
+impl_int_dict_key! {
+    u8 => 8 => false,
+    i8 => 8 => true,
+    u16 => 16 => false,
+    i16 => 16 => true,
+    u32 => 32 => false,
+    i32 => 32 => true,
+    u64 => 64 => false,
+    i64 => 64 => true,
+    u128 => 128 => false,
+    i128 => 128 => true,
+}
+
+macro_rules! impl_bytes_dict_key {
+    ($($len:literal),*$(,)?) => {
+        $(impl DictKey for [u8; $len] {
+            const BITS: u16 = $len * 8;
+
+            #[inline]
+            fn from_raw_data(raw_data: &[u8; 128]) -> Option<Self> {
+                Some(raw_data[..$len].try_into().unwrap())
+            }
+
+            #[inline]
+            fn write_raw_key(&self, buffer: &mut [u8; 32]) -> bool {
+                buffer[..$len].copy_from_slice(self);
+                true
+            }
+        })*
+    };
+}
+
+impl_bytes_dict_key! { 16, 20, 32 }
+
+impl DictKey for HashBytes {
+    const BITS: u16 = 256;
+
+    #[inline]
+    fn from_raw_data(raw_data: &[u8; 128]) -> Option<Self> {
+        Some(HashBytes(raw_data[..32].try_into().unwrap()))
+    }
+
+    #[inline]
+    fn write_raw_key(&self, buffer: &mut [u8; 32]) -> bool {
+        *buffer = self.0;
+        true
+    }
+}
+
+/// Calls `f` with a slice of key data.
+///
+/// Uses a stack buffer instead of a cell builder for keys
+/// which support it (see [`DictKey::write_raw_key`]).
+#[inline]
+pub(crate) fn with_key_slice<K, R, F>(key: &K, f: F) -> Result<R, Error>
+where
+    K: DictKey + Store,
+    F: FnOnce(CellSlice<'_>) -> Result<R, Error>,
+{
+    let mut cell = RawKeyCell {
+        data: [0; 32],
+        bit_len: K::BITS,
+        hash: OnceCell::new(),
+    };
+    if K::BITS <= 256 && key.write_raw_key(&mut cell.data) {
+        // SAFETY: raw key cell is an ordinary cell
+        f(unsafe { CellSlice::new_unchecked(&cell) })
+    } else {
+        let mut builder = CellBuilder::new();
+        ok!(key.store_into(&mut builder, &mut Cell::empty_context()));
+        f(builder.as_data_slice())
+    }
+}
+
+/// Intermediate data cell for keys up to 256 bits.
+struct RawKeyCell {
+    data: [u8; 32],
+    bit_len: u16,
+    hash: OnceCell<HashBytes>,
+}
+
+impl CellImpl for RawKeyCell {
+    fn descriptor(&self) -> CellDescriptor {
+        CellDescriptor {
+            d1: 0,
+            d2: CellDescriptor::compute_d2(self.bit_len),
+        }
+    }
+
+    fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn bit_len(&self) -> u16 {
+        self.bit_len
+    }
+
+    fn reference(&self, _: u8) -> Option<&DynCell> {
+        None
+    }
+
+    fn reference_cloned(&self, _: u8) -> Option<Cell> {
+        None
+    }
+
+    fn virtualize(&self) -> &DynCell {
+        self
+    }
+
+    fn hash(&self, _: u8) -> &HashBytes {
+        self.hash.get_or_init(|| {
+            let byte_len = (self.bit_len as usize + 7) / 8;
+
+            // Append the completion tag
+            let mut data = self.data;
+            let rem = self.bit_len % 8;
+            if rem != 0 {
+                let last = &mut data[byte_len - 1];
+                *last = (*last & (0xff << (8 - rem))) | (0x80 >> rem);
+            }
+
+            let descriptor = self.descriptor();
+            let mut hasher = sha2::Sha256::new();
+            hasher.update([descriptor.d1, descriptor.d2]);
+            hasher.update(&data[..byte_len]);
+            hasher.finalize().into()
+        })
+    }
+
+    fn depth(&self, _: u8) -> u16 {
+        0
+    }
+
+    fn take_first_child(&mut self) -> Option<Cell> {
+        None
+    }
+
+    fn replace_first_child(&mut self, parent: Cell) -> Result<Cell, Cell> {
+        Err(parent)
+    }
+
+    fn take_next_child(&mut self) -> Option<Cell> {
+        None
+    }
+
+    #[cfg(feature = "stats")]
+    fn stats(&self) -> CellTreeStats {
+        CellTreeStats {
+            bit_count: self.bit_len as u64,
+            cell_count: 1,
+        }
+    }
 }
 
 /// Dictionary insertion mode.
@@ -98,6 +290,8 @@ pub fn dict_remove_owned(
     allow_subtree: bool,
     context: &mut dyn CellContext,
 ) -> Result<Option<CellSliceParts>, Error> {
+    inc_counter!("everscale_types_dict_ops_total", "op" => "remove");
+
     if !allow_subtree && key.remaining_bits() != key_bit_len {
         return Err(Error::CellUnderflow);
     }
@@ -240,6 +434,8 @@ pub fn dict_insert(
     mode: SetMode,
     context: &mut dyn CellContext,
 ) -> Result<bool, Error> {
+    inc_counter!("everscale_types_dict_ops_total", "op" => "insert");
+
     if key.remaining_bits() != key_bit_len {
         return Err(Error::CellUnderflow);
     }
@@ -467,6 +663,8 @@ pub fn dict_insert_owned(
     mode: SetMode,
     context: &mut dyn CellContext,
 ) -> Result<(bool, Option<CellSliceParts>), Error> {
+    inc_counter!("everscale_types_dict_ops_total", "op" => "insert");
+
     fn last(
         stack: &[Segment],
         context: &mut dyn CellContext,
@@ -608,6 +806,8 @@ pub fn dict_get<'a: 'b, 'b>(
     mut key: CellSlice<'b>,
     context: &mut dyn CellContext,
 ) -> Result<Option<CellSlice<'a>>, Error> {
+    inc_counter!("everscale_types_dict_ops_total", "op" => "get");
+
     if key.remaining_bits() != key_bit_len {
         return Err(Error::CellUnderflow);
     }
@@ -661,6 +861,8 @@ pub fn dict_get_owned(
     mut key: CellSlice<'_>,
     context: &mut dyn CellContext,
 ) -> Result<Option<CellSliceParts>, Error> {
+    inc_counter!("everscale_types_dict_ops_total", "op" => "get");
+
     if key.remaining_bits() != key_bit_len {
         return Err(Error::CellUnderflow);
     }
@@ -813,6 +1015,8 @@ pub fn dict_find_owned(
     signed: bool,
     context: &mut dyn CellContext,
 ) -> Result<Option<DictOwnedEntry>, Error> {
+    inc_counter!("everscale_types_dict_ops_total", "op" => "find");
+
     if key.remaining_bits() != key_bit_len {
         return Err(Error::CellUnderflow);
     }
@@ -1015,6 +1219,8 @@ pub fn dict_find_bound<'a: 'b, 'b>(
     signed: bool,
     context: &mut dyn CellContext,
 ) -> Result<Option<(CellBuilder, CellSlice<'b>)>, Error> {
+    inc_counter!("everscale_types_dict_ops_total", "op" => "find");
+
     let mut data = match dict {
         Some(data) => ok!(context
             .load_dyn_cell(data.as_ref(), LoadMode::Full)
@@ -1069,6 +1275,8 @@ pub fn dict_find_bound_owned(
     signed: bool,
     context: &mut dyn CellContext,
 ) -> Result<Option<(CellBuilder, CellSliceParts)>, Error> {
+    inc_counter!("everscale_types_dict_ops_total", "op" => "find");
+
     let root = match dict {
         Some(data) => ok!(context.load_cell(data.clone(), LoadMode::Full)),
         None => return Ok(None),
@@ -1131,10 +1339,35 @@ pub fn dict_find_bound_owned(
 
 /// Removes the specified dict bound and returns a removed key and cell slice parts.
 pub fn dict_remove_bound_owned(
+    dict: &mut Option<Cell>,
+    key_bit_len: u16,
+    bound: DictBound,
+    signed: bool,
+    context: &mut dyn CellContext,
+) -> Result<Option<DictOwnedEntry>, Error> {
+    inc_counter!("everscale_types_dict_ops_total", "op" => "remove");
+    dict_remove_bound_impl(dict, key_bit_len, bound, signed, None, context)
+}
+
+/// Removes the specified aug dict bound and returns a removed key and cell slice parts.
+/// Extras of the affected forks are recomputed using the comparator.
+pub fn aug_dict_remove_bound_owned(
+    dict: &mut Option<Cell>,
+    key_bit_len: u16,
+    bound: DictBound,
+    signed: bool,
+    comparator: AugDictFn,
+    context: &mut dyn CellContext,
+) -> Result<Option<DictOwnedEntry>, Error> {
+    dict_remove_bound_impl(dict, key_bit_len, bound, signed, Some(comparator), context)
+}
+
+fn dict_remove_bound_impl(
     dict: &mut Option<Cell>,
     mut key_bit_len: u16,
     bound: DictBound,
     signed: bool,
+    comparator: Option<AugDictFn>,
     context: &mut dyn CellContext,
 ) -> Result<Option<DictOwnedEntry>, Error> {
     let root = match &dict {
@@ -1185,7 +1418,7 @@ pub fn dict_remove_bound_owned(
         stack.push(Segment {
             data,
             next_branch,
-            key_bit_len: 0,
+            key_bit_len: prev_key_bit_len,
         });
         data = child;
     };
@@ -1237,84 +1470,334 @@ pub fn dict_remove_bound_owned(
         return Ok(Some((key, (root, removed))));
     };
 
-    *dict = Some(ok!(rebuild_dict_from_stack(stack, leaf, context)));
+    *dict = Some(ok!(match comparator {
+        Some(comparator) => rebuild_aug_dict_from_stack(stack, leaf, comparator, context),
+        None => rebuild_dict_from_stack(stack, leaf, context),
+    }));
     Ok(Some((key, removed)))
 }
 
-/// Splits one dectionary into two
-/// Returns two optional dictionaries as CellSlice representation
+/// Splits the dictionary into two by the first bit of the key.
+///
+/// Returns the dictionaries with keys which start with `0` and `1` respectively.
 pub fn dict_split(
     dict: Option<&'_ Cell>,
     key_bit_len: u16,
     context: &mut dyn CellContext,
 ) -> Result<(Option<Cell>, Option<Cell>), Error> {
-    let mut remaining_data = match dict {
-        Some(data) => ok!(context
-            .load_dyn_cell(data.as_ref(), LoadMode::Full)
-            .and_then(CellSlice::new)),
+    dict_split_by_prefix(dict, &CellSlice::default(), key_bit_len, context)
+}
+
+/// Splits the dictionary into two by the key bit which follows the `key_prefix`.
+///
+/// Returns the dictionaries with keys which start with `key_prefix + 0`
+/// and `key_prefix + 1` respectively. All keys must start with the `key_prefix`,
+/// otherwise [`Error::InvalidData`] is returned.
+///
+/// Only the root and its branches are rebuilt, all other subtrees are reused as is.
+pub fn dict_split_by_prefix(
+    dict: Option<&'_ Cell>,
+    key_prefix: &CellSlice,
+    key_bit_len: u16,
+    context: &mut dyn CellContext,
+) -> Result<(Option<Cell>, Option<Cell>), Error> {
+    if key_prefix.remaining_bits() >= key_bit_len {
+        return Err(Error::CellUnderflow);
+    }
+
+    let root = match dict {
+        Some(root) => root,
         None => return Ok((None, None)),
     };
 
-    let parent_label = ok!(read_label(&mut remaining_data, key_bit_len));
+    let mut data = ok!(context
+        .load_dyn_cell(root.as_ref(), LoadMode::Full)
+        .and_then(CellSlice::new));
+    let label = ok!(read_label(&mut data, key_bit_len));
+    let lcp_len = label
+        .longest_common_data_prefix(key_prefix)
+        .remaining_bits();
+
+    // NOTE: All keys of a valid dictionary start with the root label,
+    // so the other keys can only be in the root fork branches.
+    if lcp_len < key_prefix.remaining_bits() {
+        // Keys don't start with the prefix
+        return Err(Error::InvalidData);
+    } else if lcp_len < label.remaining_bits() {
+        // All keys of the dictionary are on the same side
+        return Ok(if ok!(label.get_bit(lcp_len)) {
+            (None, Some(root.clone()))
+        } else {
+            (Some(root.clone()), None)
+        });
+    }
 
-    let left_child = match remaining_data.get_reference_cloned(0u8) {
-        Ok(left_child) => {
-            let mut new_left = CellBuilder::new();
-            ok!(new_left.store_slice(parent_label));
-            ok!(new_left.store_bit(false));
-            ok!(new_left.store_slice(ok!(left_child.as_slice())));
-            Some(ok!(new_left.build()))
-        }
-        Err(_) => None,
-    };
+    // Root is a fork at the split point, prepend the prefix to the branch labels
+    if data.remaining_refs() < 2 {
+        return Err(Error::CellUnderflow);
+    }
+    let child_key_bit_len = key_bit_len - lcp_len - 1;
 
-    let right_child = match remaining_data.get_reference_cloned(0u8) {
-        Ok(right_child) => {
-            let mut new_right = CellBuilder::new();
-            ok!(new_right.store_slice(parent_label));
-            ok!(new_right.store_bit(false));
-            ok!(new_right.store_slice(ok!(right_child.as_slice())));
-            Some(ok!(new_right.build()))
-        }
-        Err(_) => None,
+    let mut build_branch = |bit: bool| -> Result<Cell, Error> {
+        let mut child = ok!(context
+            .load_dyn_cell(ok!(data.get_reference(bit as u8)), LoadMode::Full)
+            .and_then(CellSlice::new));
+        let rem = ok!(read_label(&mut child, child_key_bit_len));
+
+        let mut builder = CellBuilder::new();
+        ok!(write_label_parts(
+            key_prefix,
+            bit,
+            &rem,
+            key_bit_len,
+            &mut builder
+        ));
+        ok!(builder.store_slice(child));
+        builder.build_ext(context)
     };
 
-    Ok((left_child, right_child))
+    let left = ok!(build_branch(false));
+    let right = ok!(build_branch(true));
+    Ok((Some(left), Some(right)))
 }
 
-///Merges two dictionaries into one (left)
+/// Merges two dictionaries with disjoint keys into one (left).
+///
+/// Returns [`Error::InvalidData`] if some key is present in both dictionaries.
 pub fn dict_merge(
     left: &mut Option<Cell>,
     right: &Option<Cell>,
-    key_bit_length: u16,
+    key_bit_len: u16,
     context: &mut dyn CellContext,
 ) -> Result<(), Error> {
-    match (&left, right) {
-        (None, None) | (Some(_), None) => return Ok(()),
-        (None, Some(right)) => {
-            *left = Some(right.clone());
-            return Ok(());
+    if left.is_none() {
+        *left = right.clone();
+        return Ok(());
+    }
+
+    let mut result = left.clone();
+    for entry in RawIter::new(right, key_bit_len) {
+        let (key, value) = ok!(entry);
+        let added = ok!(dict_insert(
+            &mut result,
+            &mut key.as_data_slice(),
+            key_bit_len,
+            &value,
+            SetMode::Add,
+            context
+        ));
+        if !added {
+            return Err(Error::InvalidData);
         }
-        (Some(left_cell), Some(right_cell)) => {
-            let bit_len = left_cell.bit_len();
-            if bit_len != right_cell.bit_len() && bit_len != key_bit_length {
-                return Err(Error::InvalidCell); //KEY LENGTH ERROR?
-            }
+    }
 
-            let mut right_dict_iter = RawIter::new(right, key_bit_length);
-            while let Some(Ok((key, value))) = right_dict_iter.next() {
-                let current_bit_len = key.bit_len();
+    *left = result;
+    Ok(())
+}
 
-                dict_insert(
-                    left,
-                    &mut key.as_data_slice(),
-                    current_bit_len,
-                    &value,
-                    SetMode::Set,
-                    context,
-                )?;
+/// Merges two augmented dictionaries with disjoint keys into one (left).
+///
+/// Returns [`Error::InvalidData`] if some key is present in both dictionaries.
+pub fn aug_dict_merge(
+    left: &mut Option<Cell>,
+    right: &Option<Cell>,
+    key_bit_len: u16,
+    comparator: AugDictFn,
+    context: &mut dyn CellContext,
+) -> Result<(), Error> {
+    if left.is_none() {
+        *left = right.clone();
+        return Ok(());
+    }
+
+    let mut result = left.clone();
+    for entry in RawIter::new(right, key_bit_len) {
+        // NOTE: Leaf data contains both the extra and the value,
+        // so it is stored as is
+        let (key, leaf_data) = ok!(entry);
+        let added = ok!(aug_dict_insert(
+            &mut result,
+            &mut key.as_data_slice(),
+            key_bit_len,
+            &leaf_data,
+            &(),
+            SetMode::Add,
+            comparator,
+            context
+        ));
+        if !added {
+            return Err(Error::InvalidData);
+        }
+    }
+
+    *left = result;
+    Ok(())
+}
+
+/// Builds a dictionary from entries sorted by their keys in the unsigned order.
+///
+/// Keys must be unique and contain exactly `key_bit_len` bits.
+/// Unlike inserting entries one by one, each node is built only once.
+pub fn build_dict_from_sorted<V: Store>(
+    entries: &[(CellBuilder, V)],
+    key_bit_len: u16,
+    context: &mut dyn CellContext,
+) -> Result<Option<Cell>, Error> {
+    if entries.is_empty() {
+        return Ok(None);
+    }
+    ok!(check_sorted_keys(
+        entries.iter().map(|(key, _)| key),
+        key_bit_len
+    ));
+
+    build_subtree_from_sorted(
+        entries,
+        |(key, _)| key,
+        0,
+        key_bit_len,
+        &mut |key, key_bit_len, (_, value), context| make_leaf(key, key_bit_len, value, context),
+        &mut |_, _, _, _, _| Ok(()),
+        context,
+    )
+    .map(Some)
+}
+
+/// Builds an augmented dictionary from entries sorted by their keys in the unsigned order.
+///
+/// Keys must be unique and contain exactly `key_bit_len` bits.
+/// Extra values of forks are computed with the `comparator` from the extra values
+/// of their children, so each node is built only once.
+pub fn build_aug_dict_from_sorted<A: Store, V: Store>(
+    entries: &[(CellBuilder, A, V)],
+    key_bit_len: u16,
+    comparator: AugDictFn,
+    context: &mut dyn CellContext,
+) -> Result<Option<Cell>, Error> {
+    if entries.is_empty() {
+        return Ok(None);
+    }
+    ok!(check_sorted_keys(
+        entries.iter().map(|(key, ..)| key),
+        key_bit_len
+    ));
+
+    build_subtree_from_sorted(
+        entries,
+        |(key, ..)| key,
+        0,
+        key_bit_len,
+        &mut |key, key_bit_len, (_, extra, value), context| {
+            make_leaf_with_extra(key, key_bit_len, extra, value, context)
+        },
+        &mut |builder, left, right, child_key_bit_len, context| {
+            // Compute the fork extra from the children extras
+            let left_slice = &mut ok!(left.as_slice());
+            let right_slice = &mut ok!(right.as_slice());
+            ok!(read_label(left_slice, child_key_bit_len));
+            ok!(read_label(right_slice, child_key_bit_len));
+            comparator(left_slice, right_slice, builder, context)
+        },
+        context,
+    )
+    .map(Some)
+}
+
+type SortedLeafFn<'a, T> =
+    dyn FnMut(&CellSlice<'_>, u16, &T, &mut dyn CellContext) -> Result<Cell, Error> + 'a;
+
+type SortedForkFn<'a> =
+    dyn FnMut(&mut CellBuilder, &Cell, &Cell, u16, &mut dyn CellContext) -> Result<(), Error> + 'a;
+
+/// Builds a subtree from a non-empty range of sorted entries.
+///
+/// `write_leaf` creates a leaf node for a single entry.
+/// `write_fork` is called for each fork after its label and both
+/// children references were stored.
+fn build_subtree_from_sorted<T>(
+    entries: &[T],
+    key: fn(&T) -> &CellBuilder,
+    offset: u16,
+    key_bit_len: u16,
+    write_leaf: &mut SortedLeafFn<'_, T>,
+    write_fork: &mut SortedForkFn<'_>,
+    context: &mut dyn CellContext,
+) -> Result<Cell, Error> {
+    let mut first = key(&entries[0]).as_data_slice();
+    ok!(first.advance(offset, 0));
+
+    if entries.len() == 1 {
+        return write_leaf(&first, key_bit_len, &entries[0], context);
+    }
+
+    let mut last = key(&entries[entries.len() - 1]).as_data_slice();
+    ok!(last.advance(offset, 0));
+
+    // All keys in the sorted range share the prefix of the first and the last keys
+    let prefix = first.longest_common_data_prefix(&last);
+    let prefix_len = prefix.remaining_bits();
+    if prefix_len >= key_bit_len {
+        return Err(Error::InvalidData);
+    }
+
+    let mut builder = CellBuilder::new();
+    ok!(write_label(&prefix, key_bit_len, &mut builder));
+
+    // Split entries by the next bit after the prefix
+    let fork_offset = offset + prefix_len;
+    let mid = entries.partition_point(|entry| {
+        !matches!(key(entry).as_data_slice().get_bit(fork_offset), Ok(true))
+    });
+
+    let child_key_bit_len = key_bit_len - prefix_len - 1;
+    let (left, right) = (&entries[..mid], &entries[mid..]);
+    let left = ok!(build_subtree_from_sorted(
+        left,
+        key,
+        fork_offset + 1,
+        child_key_bit_len,
+        write_leaf,
+        write_fork,
+        context
+    ));
+    let right = ok!(build_subtree_from_sorted(
+        right,
+        key,
+        fork_offset + 1,
+        child_key_bit_len,
+        write_leaf,
+        write_fork,
+        context
+    ));
+    ok!(builder.store_reference(left.clone()));
+    ok!(builder.store_reference(right.clone()));
+    ok!(write_fork(
+        &mut builder,
+        &left,
+        &right,
+        child_key_bit_len,
+        context
+    ));
+
+    builder.build_ext(context)
+}
+
+/// Checks that keys have the same length and are sorted
+fn check_sorted_keys<'a, I>(keys: I, key_bit_len: u16) -> Result<(), Error>
+where
+    I: IntoIterator<Item = &'a CellBuilder>,
+{
+    let mut prev = None::<&CellBuilder>;
+    for key in keys {
+        if key.bit_len() != key_bit_len {
+            return Err(Error::InvalidData);
+        }
+        if let Some(prev) = prev {
+            if prev.raw_data() >= key.raw_data() {
+                return Err(Error::InvalidData);
             }
         }
+        prev = Some(key);
     }
     Ok(())
 }
@@ -1476,6 +1959,145 @@ impl DictBound {
     }
 }
 
+/// Dictionary tree shape statistics.
+///
+/// See [`dict_tree_stats`].
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct DictTreeStats {
+    /// Number of fork nodes.
+    pub forks: u64,
+    /// Number of leaf nodes (dictionary entries).
+    pub leaves: u64,
+    /// Total length of all edge labels in bits.
+    pub label_bits: u64,
+    /// Bits spent on encoding label tags and lengths.
+    pub label_overhead_bits: u64,
+    /// Number of leaves at each depth (number of forks from the root).
+    pub depth_distribution: Vec<u64>,
+}
+
+impl DictTreeStats {
+    /// Returns the total number of nodes.
+    #[inline]
+    pub fn node_count(&self) -> u64 {
+        self.forks + self.leaves
+    }
+
+    /// Returns the average label length in bits.
+    pub fn avg_label_len(&self) -> f64 {
+        match self.node_count() {
+            0 => 0.0,
+            nodes => self.label_bits as f64 / nodes as f64,
+        }
+    }
+
+    /// Returns the maximum leaf depth.
+    #[inline]
+    pub fn max_depth(&self) -> usize {
+        self.depth_distribution.len().saturating_sub(1)
+    }
+
+    /// Returns the total number of bits spent on labels (including their encoding).
+    #[inline]
+    pub fn total_label_bits(&self) -> u64 {
+        self.label_bits + self.label_overhead_bits
+    }
+}
+
+/// Traverses the whole dictionary and computes its tree shape statistics.
+///
+/// Can be used to find pathological key layouts with long label encodings
+/// or unbalanced trees.
+pub fn dict_tree_stats(
+    dict: Option<&Cell>,
+    key_bit_len: u16,
+    context: &mut dyn CellContext,
+) -> Result<DictTreeStats, Error> {
+    let mut stats = DictTreeStats::default();
+
+    let mut stack = Vec::new();
+    if let Some(root) = dict {
+        stack.push((root.clone(), key_bit_len, 0usize));
+    }
+
+    while let Some((cell, key_bit_len, depth)) = stack.pop() {
+        let cell = ok!(context.load_cell(cell, LoadMode::Full));
+        let mut data = ok!(cell.as_slice());
+
+        let prev_bits = data.remaining_bits();
+        let label = ok!(read_label(&mut data, key_bit_len));
+        let label_len = label.remaining_bits();
+        let encoded_len = prev_bits - data.remaining_bits();
+
+        stats.label_bits += label_len as u64;
+        stats.label_overhead_bits += encoded_len.saturating_sub(label_len) as u64;
+
+        match key_bit_len.checked_sub(label_len + 1) {
+            Some(remaining) => {
+                stats.forks += 1;
+                for i in (0..2).rev() {
+                    let child = ok!(data.get_reference_cloned(i));
+                    stack.push((child, remaining, depth + 1));
+                }
+            }
+            None => {
+                stats.leaves += 1;
+                if stats.depth_distribution.len() <= depth {
+                    stats.depth_distribution.resize(depth + 1, 0);
+                }
+                stats.depth_distribution[depth] += 1;
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Traverses the whole dictionary and checks that all labels are
+/// encoded exactly as [`write_label`] would encode them, and that forks
+/// contain only the label and two children.
+///
+/// Returns [`Error::InvalidData`] for the first non-canonical node.
+pub fn dict_check_canonical(
+    dict: Option<&Cell>,
+    key_bit_len: u16,
+    context: &mut dyn CellContext,
+) -> Result<(), Error> {
+    let mut stack = Vec::new();
+    if let Some(root) = dict {
+        stack.push((root.clone(), key_bit_len));
+    }
+
+    while let Some((cell, key_bit_len)) = stack.pop() {
+        let cell = ok!(context.load_cell(cell, LoadMode::Full));
+        let mut data = ok!(cell.as_slice());
+
+        let encoded = data;
+        let label = ok!(read_label(&mut data, key_bit_len));
+        let encoded = encoded.get_prefix(encoded.remaining_bits() - data.remaining_bits(), 0);
+
+        let mut canonical = CellBuilder::new();
+        ok!(write_label(&label, key_bit_len, &mut canonical));
+        if ok!(encoded.cmp_by_content_only(&canonical.as_data_slice())) != std::cmp::Ordering::Equal
+        {
+            return Err(Error::InvalidData);
+        }
+
+        if let Some(remaining) = key_bit_len.checked_sub(label.remaining_bits() + 1) {
+            // Forks must contain only two children
+            if !data.is_data_empty() || data.remaining_refs() != 2 {
+                return Err(Error::InvalidData);
+            }
+            for i in (0..2).rev() {
+                let child = ok!(data.get_reference_cloned(i));
+                stack.push((child, remaining));
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Loads a non-empty dictionary from the root cell.
 pub fn dict_load_from_root(
     slice: &mut CellSlice<'_>,
@@ -1681,14 +2303,10 @@ fn write_label_parts(
     let hml_same_len = 3 + bits_for_len;
 
     if hml_same_len < hml_long_len && hml_same_len < hml_short_len {
-        if let Some(pfx_bit) = pfx.test_uniform() {
-            if pfx_bit == bit {
-                if let Some(rem_bit) = rem.test_uniform() {
-                    if rem_bit == bit {
-                        return write_hml_same(bit, remaining_bits, bits_for_len, label);
-                    }
-                }
-            }
+        // NOTE: Empty parts are uniform with any bit
+        let is_same = |part: &CellSlice| part.is_data_empty() || part.test_uniform() == Some(bit);
+        if is_same(pfx) && is_same(rem) {
+            return write_hml_same(bit, remaining_bits, bits_for_len, label);
         }
     }
 
@@ -1704,7 +2322,10 @@ fn write_label_parts(
     label.store_slice_data(rem)
 }
 
-fn read_label<'a>(label: &mut CellSlice<'a>, key_bit_len: u16) -> Result<CellSlice<'a>, Error> {
+pub(crate) fn read_label<'a>(
+    label: &mut CellSlice<'a>,
+    key_bit_len: u16,
+) -> Result<CellSlice<'a>, Error> {
     let bits_for_len = (16 - key_bit_len.leading_zeros()) as u16;
 
     if bits_for_len == 0 && label.is_data_empty() {
@@ -1822,12 +2443,367 @@ impl From<bool> for Branch {
 mod tests {
     use super::*;
 
+    #[test]
+    fn raw_key_cell_hash() {
+        fn check<K: DictKey + Store>(key: K) {
+            let expected = CellBuilder::build_from(&key).unwrap();
+            with_key_slice(&key, |slice| {
+                assert_eq!(slice.cell().repr_hash(), expected.repr_hash());
+                Ok(())
+            })
+            .unwrap();
+        }
+
+        check(0x12u8);
+        check(-1i32);
+        check(u128::MAX - 1);
+        check(HashBytes([0xab; 32]));
+        check([0x55u8; 20]);
+
+        // Not aligned keys
+        let mut builder = CellBuilder::new();
+        builder.store_uint(0xabc, 12).unwrap();
+        let expected = builder.build().unwrap();
+
+        let mut cell = RawKeyCell {
+            data: [0; 32],
+            bit_len: 12,
+            hash: OnceCell::new(),
+        };
+        cell.data[..2].copy_from_slice(&[0xab, 0xcf]);
+        assert_eq!(cell.virtualize().repr_hash(), expected.repr_hash());
+    }
+
+    #[test]
+    fn dict_raw_keys() {
+        let mut dict = Dict::<HashBytes, u32>::new();
+        let mut raw = RawDict::<256>::new();
+        for i in 0..100u32 {
+            let key = HashBytes::from([i as u8; 32]);
+            dict.set(key, i).unwrap();
+
+            let key = CellBuilder::build_from(key).unwrap();
+            let value = CellBuilder::build_from(i).unwrap();
+            raw.set(key.as_slice().unwrap(), value.as_slice().unwrap())
+                .unwrap();
+        }
+        assert_eq!(dict.root(), &raw.0);
+
+        for i in 0..100u32 {
+            let key = HashBytes::from([i as u8; 32]);
+            assert!(dict.contains_key(key).unwrap());
+            assert_eq!(dict.get(key).unwrap(), Some(i));
+            assert_eq!(
+                dict.get_next(key, false).unwrap().map(|(_, v)| v),
+                (i < 99).then_some(i + 1)
+            );
+        }
+
+        for i in (0..100u32).step_by(2) {
+            assert_eq!(dict.remove([i as u8; 32]).unwrap(), Some(i));
+        }
+        assert_eq!(dict.values().count(), 50);
+    }
+
+    #[test]
+    fn dict_wide_keys_order() {
+        // 128-bit integers
+        let keys = [
+            0u128,
+            1,
+            u64::MAX as u128,
+            u64::MAX as u128 + 1,
+            1 << 127,
+            u128::MAX - 1,
+            u128::MAX,
+        ];
+        let mut dict = Dict::<u128, u8>::new();
+        for (i, key) in keys.iter().enumerate().rev() {
+            dict.set(key, i as u8).unwrap();
+        }
+        let parsed = dict.keys().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(parsed, keys);
+        assert_eq!(dict.get_min(false).unwrap(), Some((0, 0)));
+        assert_eq!(dict.get_max(false).unwrap(), Some((u128::MAX, 6)));
+        assert_eq!(
+            dict.get_next(u64::MAX as u128, false).unwrap(),
+            Some((u64::MAX as u128 + 1, 3))
+        );
+
+        // 256-bit integers as big-endian bytes
+        fn from_words(hi: u128, lo: u128) -> HashBytes {
+            let mut result = HashBytes::ZERO;
+            result.0[..16].copy_from_slice(&hi.to_be_bytes());
+            result.0[16..].copy_from_slice(&lo.to_be_bytes());
+            result
+        }
+
+        let words = [(0, 0), (0, u128::MAX), (1, 0), (1, 1), (u128::MAX, 0)];
+        let mut hashes = Dict::<HashBytes, u8>::new();
+        let mut bytes = Dict::<[u8; 32], u8>::new();
+        for (i, (hi, lo)) in words.iter().enumerate().rev() {
+            let key = from_words(*hi, *lo);
+            hashes.set(key, i as u8).unwrap();
+            bytes.set(key.0, i as u8).unwrap();
+        }
+        assert_eq!(hashes.root(), bytes.root());
+
+        let parsed = hashes.keys().collect::<Result<Vec<_>, _>>().unwrap();
+        let expected = words.map(|(hi, lo)| from_words(hi, lo));
+        assert_eq!(parsed, expected);
+
+        // Byte arrays use the fast path
+        let mut dict = Dict::<[u8; 20], u8>::new();
+        for i in 0..10u8 {
+            let mut key = [0u8; 20];
+            key[19 - i as usize % 20] = i;
+            dict.set(key, i).unwrap();
+            assert_eq!(dict.get(key).unwrap(), Some(i));
+        }
+        assert_eq!(dict.values().count(), 10);
+    }
+
+    #[test]
+    fn dict_int_raw_keys() {
+        fn check<K>(keys: impl Iterator<Item = K>)
+        where
+            K: DictKey + Store + Copy + Ord + std::fmt::Debug,
+        {
+            let mut dict = Dict::<K, u8>::new();
+
+            let mut keys = keys.collect::<Vec<_>>();
+            for (i, key) in keys.iter().enumerate() {
+                dict.set(key, i as u8).unwrap();
+            }
+
+            // Compare with keys stored with a cell builder
+            let mut expected = None;
+            for (i, key) in keys.iter().enumerate() {
+                let key = CellBuilder::build_from(key).unwrap();
+                let value = CellBuilder::build_from(i as u8).unwrap();
+                dict_insert(
+                    &mut expected,
+                    &mut key.as_slice().unwrap(),
+                    K::BITS,
+                    &value.as_slice().unwrap(),
+                    SetMode::Set,
+                    &mut Cell::empty_context(),
+                )
+                .unwrap();
+            }
+            assert_eq!(dict.root(), &expected);
+
+            for (i, key) in keys.iter().enumerate() {
+                assert_eq!(dict.get(key).unwrap(), Some(i as u8));
+            }
+
+            keys.sort_by_key(|key| {
+                let mut builder = CellBuilder::new();
+                key.store_into(&mut builder, &mut Cell::empty_context())
+                    .unwrap();
+                builder
+            });
+            let parsed = dict.keys().unsigned().collect::<Result<Vec<_>, _>>();
+            assert_eq!(parsed.unwrap(), keys);
+
+            // NOTE: Signed keys are iterated in the signed order by default,
+            // so the raw bit order is only preserved for unsigned keys.
+            if K::SIGNED {
+                keys.sort();
+            }
+            let parsed = dict.keys().collect::<Result<Vec<_>, _>>().unwrap();
+            assert_eq!(parsed, keys);
+        }
+
+        check((0..100u32).map(|i| i.wrapping_mul(0x9e3779b9)));
+        check((-50..50i32).map(|i| i.wrapping_mul(1234567)));
+        check((0..100u64).map(|i| i.wrapping_mul(0x9e3779b97f4a7c15)));
+        check((-50..50i64).map(|i| i * 1_000_000_007));
+        check(0..=255u8);
+        check((-50..50i16).map(|i| i * 311));
+        check((0..100u128).map(|i| i << 100 | i));
+    }
+
+    #[test]
+    fn dict_tree_shape() {
+        let mut dict = Dict::<u8, u8>::new();
+        assert_eq!(dict.tree_stats().unwrap(), DictTreeStats::default());
+
+        dict.set(0, 0).unwrap();
+        dict.set(1, 1).unwrap();
+
+        let stats = dict.tree_stats().unwrap();
+        assert_eq!(stats.forks, 1);
+        assert_eq!(stats.leaves, 2);
+        assert_eq!(stats.node_count(), 3);
+        // Root label is `0000000` and leaves have empty labels
+        assert_eq!(stats.label_bits, 7);
+        assert_eq!(stats.depth_distribution, vec![0, 2]);
+        assert_eq!(stats.max_depth(), 1);
+
+        let mut dict = Dict::<u32, u8>::new();
+        for i in 0..256u32 {
+            dict.set(i, i as u8).unwrap();
+        }
+
+        let stats = dict.tree_stats().unwrap();
+        assert_eq!(stats.forks, 255);
+        assert_eq!(stats.leaves, 256);
+        assert_eq!(stats.depth_distribution[8], 256);
+        assert_eq!(stats.max_depth(), 8);
+        assert_eq!(stats.label_bits, 24);
+        assert!(stats.avg_label_len() < 1.0);
+
+        let raw = RawDict::<32>(dict.root().clone());
+        assert_eq!(raw.tree_stats().unwrap(), stats);
+    }
+
+    #[test]
+    fn dict_canonical_labels() {
+        let mut dict = Dict::<u32, u32>::new();
+        assert!(dict.check_canonical().is_ok());
+        for i in [0, 1, 123, 0xffffffff, 0x12345678] {
+            dict.set(i, i).unwrap();
+        }
+        assert!(dict.check_canonical().is_ok());
+        assert!(RawDict::<32>(dict.root().clone()).check_canonical().is_ok());
+
+        // Single leaf with a long label encoded as `hml_short`
+        let root = build_cell(|b| {
+            write_hml_short_tag(32, b)?;
+            b.store_u32(0x12345678)?;
+            b.store_u32(1)
+        });
+        let dict = Dict::<u32, u32>::from(Some(root));
+        assert_eq!(dict.get(0x12345678).unwrap(), Some(1));
+        assert_eq!(dict.check_canonical().unwrap_err(), Error::InvalidData);
+
+        // Uniform label encoded as `hml_long`
+        let root = build_cell(|b| {
+            write_hml_long_tag(32, 6, b)?;
+            b.store_u32(0)?;
+            b.store_u32(2)
+        });
+        let dict = Dict::<u32, u32>::from(Some(root));
+        assert_eq!(dict.get(0).unwrap(), Some(2));
+        assert_eq!(dict.check_canonical().unwrap_err(), Error::InvalidData);
+
+        // The same labels with the shortest encoding
+        let root = build_cell(|b| {
+            write_label(&build_cell(|b| b.store_u32(0)).as_slice()?, 32, b)?;
+            b.store_u32(2)
+        });
+        let dict = Dict::<u32, u32>::from(Some(root));
+        assert_eq!(dict.get(0).unwrap(), Some(2));
+        assert!(dict.check_canonical().is_ok());
+
+        // Fork label `101100` encoded as `hml_long` of the same length as `hml_short`
+        let mut dict = Dict::<u32, u32>::new();
+        dict.set(0xb0000000, 1).unwrap();
+        dict.set(0xb2000000, 2).unwrap();
+        assert!(dict.check_canonical().is_ok());
+
+        let canonical = dict.root().clone().unwrap();
+        let rebuild_root = |f: &dyn Fn(&mut CellBuilder) -> Result<(), Error>| {
+            let root = build_cell(|b| {
+                f(b)?;
+                b.store_reference(canonical.reference_cloned(0).unwrap())?;
+                b.store_reference(canonical.reference_cloned(1).unwrap())
+            });
+            Dict::<u32, u32>::from(Some(root))
+        };
+
+        let dict = rebuild_root(&|b| {
+            write_hml_long_tag(6, 6, b)?;
+            b.store_small_uint(0b101100, 6)
+        });
+        assert_eq!(dict.root().as_ref().unwrap().bit_len(), canonical.bit_len());
+        assert_eq!(dict.get(0xb2000000).unwrap(), Some(2));
+        assert_eq!(dict.check_canonical().unwrap_err(), Error::InvalidData);
+
+        // Forks with extra data or references
+        let dict = rebuild_root(&|b| {
+            write_hml_short_tag(6, b)?;
+            b.store_small_uint(0b101100, 6)?;
+            b.store_bit_one()
+        });
+        assert_eq!(dict.get(0xb2000000).unwrap(), Some(2));
+        assert_eq!(dict.check_canonical().unwrap_err(), Error::InvalidData);
+
+        let dict = rebuild_root(&|b| {
+            write_hml_short_tag(6, b)?;
+            b.store_small_uint(0b101100, 6)?;
+            b.store_reference(Cell::empty_cell())
+        });
+        assert_eq!(dict.check_canonical().unwrap_err(), Error::InvalidData);
+
+        let dict = rebuild_root(&|b| {
+            write_hml_short_tag(6, b)?;
+            b.store_small_uint(0b101100, 6)
+        });
+        assert!(dict.check_canonical().is_ok());
+    }
+
     fn build_cell<F: FnOnce(&mut CellBuilder) -> Result<(), Error>>(f: F) -> Cell {
         let mut builder = CellBuilder::new();
         f(&mut builder).unwrap();
         builder.build().unwrap()
     }
 
+    #[test]
+    fn dict_remove_bound_same_labels() -> anyhow::Result<()> {
+        // Removing a bound collapses the root fork into a single leaf,
+        // its label (`pfx + bit + rem`) consists of 32 equal bits and
+        // must use the `hml_same` form even if `pfx` or `rem` is empty.
+        //
+        // Leaves are encoded by hand: `11` + bit + `100000` (len) + value.
+        const ONES_BOC: &str = "b5ee9c720101010100050000 05f000c0";
+        const ZEROS_BOC: &str = "b5ee9c720101010100050000 05d00040";
+
+        let decode = |hex_boc: &str| {
+            let bytes = hex::decode(hex_boc.replace(' ', "")).unwrap();
+            crate::boc::Boc::decode(bytes).unwrap()
+        };
+
+        // Empty `pfx`: root label is empty, the remaining leaf has 31 ones
+        let mut dict = Dict::<u32, u8>::new();
+        dict.set(0, 0)?;
+        dict.set(u32::MAX, 1)?;
+        dict.remove_min_raw(false)?;
+
+        let expected = build_cell(|b| {
+            b.store_small_uint(0b11, 2)?;
+            b.store_bit_one()?;
+            b.store_small_uint(32, 6)?;
+            b.store_u8(1)
+        });
+        assert_eq!(expected.repr_hash(), decode(ONES_BOC).repr_hash());
+        assert_eq!(
+            dict.root().as_ref().unwrap().repr_hash(),
+            expected.repr_hash()
+        );
+
+        // Empty `rem`: root label has 31 zeros, the remaining leaf label is empty
+        let mut dict = Dict::<u32, u8>::new();
+        dict.set(0, 0)?;
+        dict.set(1, 1)?;
+        dict.remove_max_raw(false)?;
+
+        let expected = build_cell(|b| {
+            b.store_small_uint(0b11, 2)?;
+            b.store_bit_zero()?;
+            b.store_small_uint(32, 6)?;
+            b.store_u8(0)
+        });
+        assert_eq!(expected.repr_hash(), decode(ZEROS_BOC).repr_hash());
+        assert_eq!(
+            dict.root().as_ref().unwrap().repr_hash(),
+            expected.repr_hash()
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn labels() -> anyhow::Result<()> {
         let key_bit_len = 6;
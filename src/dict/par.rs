@@ -0,0 +1,198 @@
+use rayon::prelude::*;
+
+use crate::cell::*;
+use crate::error::Error;
+
+use super::{read_label, Dict, DictKey, RawDict, RawIntoIter};
+
+impl<const N: u16> RawDict<N> {
+    /// Gets a parallel iterator over the owned entries of the dictionary.
+    /// The iterator element type is `Result<(CellBuilder, CellSliceParts)>`.
+    ///
+    /// The tree is split at the top forks into independent subtrees
+    /// which are iterated on worker threads. Collecting into an ordered
+    /// collection preserves the raw key bit order (the unsigned order).
+    ///
+    /// If the dictionary is invalid, each subtree finishes after
+    /// the first invalid element, returning an error.
+    pub fn par_iter(
+        &self,
+    ) -> impl ParallelIterator<Item = Result<(CellBuilder, CellSliceParts), Error>> {
+        par_iter_raw(self.0.as_ref(), N)
+    }
+}
+
+impl<K, V> Dict<K, V>
+where
+    K: DictKey + Send,
+    for<'a> V: Load<'a> + Send,
+{
+    /// Gets a parallel iterator over the owned entries of the dictionary.
+    /// The iterator element type is `Result<(K, V)>`.
+    ///
+    /// NOTE: Entries are in the raw key bit order even for signed keys,
+    /// so unlike [`Dict::iter`] negative keys go after the non-negative ones.
+    ///
+    /// See [`RawDict::par_iter`] for more.
+    pub fn par_iter(&self) -> impl ParallelIterator<Item = Result<(K, V), Error>> {
+        par_iter_raw(self.root.as_ref(), K::BITS).map(|entry| {
+            let (key, (cell, range)) = ok!(entry);
+            let Some(key) = K::from_raw_data(key.raw_data()) else {
+                return Err(Error::CellUnderflow);
+            };
+            let value = ok!(V::load_from(&mut ok!(range.apply(&cell))));
+            Ok((key, value))
+        })
+    }
+}
+
+fn par_iter_raw(
+    root: Option<&Cell>,
+    bit_len: u16,
+) -> impl ParallelIterator<Item = Result<(CellBuilder, CellSliceParts), Error>> {
+    let parts = match split_subtrees(root, bit_len, rayon::current_num_threads() * 4) {
+        Ok(parts) => parts.into_iter().map(Ok).collect::<Vec<_>>(),
+        Err(e) => vec![Err(e)],
+    };
+
+    parts.into_par_iter().flat_map_iter(|part| match part {
+        Ok(part) => SubtreeIter {
+            inner: Some(RawIntoIter::new(Some(part.root), part.bit_len)),
+            prefix: part.prefix,
+            error: None,
+        },
+        Err(e) => SubtreeIter {
+            inner: None,
+            prefix: CellBuilder::new(),
+            error: Some(e),
+        },
+    })
+}
+
+struct Subtree {
+    prefix: CellBuilder,
+    root: Cell,
+    bit_len: u16,
+}
+
+/// Splits the dictionary at the top forks until there are at least
+/// `target` subtrees or there are no more forks to split.
+fn split_subtrees(root: Option<&Cell>, bit_len: u16, target: usize) -> Result<Vec<Subtree>, Error> {
+    let Some(root) = root else {
+        return Ok(Vec::new());
+    };
+
+    let mut parts = vec![Subtree {
+        prefix: CellBuilder::new(),
+        root: root.clone(),
+        bit_len,
+    }];
+
+    let context = &mut Cell::empty_context();
+    while parts.len() < target {
+        let mut next = Vec::with_capacity(parts.len() * 2);
+        let mut changed = false;
+
+        for part in parts {
+            let cell = ok!(context.load_cell(part.root.clone(), LoadMode::Full));
+            let mut data = ok!(cell.as_slice());
+            let label = ok!(read_label(&mut data, part.bit_len));
+
+            // Leaves can't be split further
+            let Some(remaining) = part.bit_len.checked_sub(label.remaining_bits() + 1) else {
+                next.push(part);
+                continue;
+            };
+
+            for bit in [false, true] {
+                let mut prefix = part.prefix.clone();
+                ok!(prefix.store_slice_data(label));
+                ok!(prefix.store_bit(bit));
+                next.push(Subtree {
+                    prefix,
+                    root: ok!(data.get_reference_cloned(bit as u8)),
+                    bit_len: remaining,
+                });
+            }
+            changed = true;
+        }
+
+        parts = next;
+        if !changed {
+            break;
+        }
+    }
+
+    Ok(parts)
+}
+
+struct SubtreeIter {
+    prefix: CellBuilder,
+    inner: Option<RawIntoIter>,
+    error: Option<Error>,
+}
+
+impl Iterator for SubtreeIter {
+    type Item = Result<(CellBuilder, CellSliceParts), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+
+        Some(match self.inner.as_mut()?.next()? {
+            Ok((suffix, value)) => {
+                let mut key = self.prefix.clone();
+                match key.store_builder(&suffix) {
+                    Ok(()) => Ok((key, value)),
+                    Err(e) => Err(e),
+                }
+            }
+            Err(e) => Err(e),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dict_par_iter() {
+        let mut dict = Dict::<u32, u64>::new();
+        for i in 0..5000u32 {
+            dict.set(i.wrapping_mul(0x9e3779b9), i as u64).unwrap();
+        }
+
+        let expected = dict.iter().collect::<Result<Vec<_>, _>>().unwrap();
+        let entries = dict.par_iter().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(entries, expected);
+
+        let raw = dict
+            .root()
+            .clone()
+            .map(|root| RawDict::<32>(Some(root)))
+            .unwrap();
+        assert_eq!(raw.par_iter().count(), expected.len());
+
+        let empty = Dict::<u32, u64>::new();
+        assert_eq!(empty.par_iter().count(), 0);
+
+        let mut single = Dict::<u32, u64>::new();
+        single.set(123, 456).unwrap();
+        let entries = single.par_iter().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(entries, vec![(123, 456)]);
+
+        // Signed keys are in the raw bit order
+        let mut signed = Dict::<i32, u64>::new();
+        for i in -100..100i32 {
+            signed.set(i, i as u64).unwrap();
+        }
+        let keys = signed
+            .par_iter()
+            .map(|entry| entry.map(|(key, _)| key))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(keys, (0..100).chain(-100..0).collect::<Vec<_>>());
+    }
+}
@@ -3,9 +3,10 @@ use crate::error::Error;
 use crate::util::{unlikely, IterStatus};
 
 use super::{
-    dict_find_bound, dict_find_bound_owned, dict_find_owned, dict_get, dict_get_owned,
-    dict_get_subdict, dict_insert, dict_load_from_root, dict_remove_bound_owned, dict_remove_owned,
-    dict_split, read_label, DictBound, DictOwnedEntry, SetMode,
+    dict_check_canonical, dict_find_bound, dict_find_bound_owned, dict_find_owned, dict_get,
+    dict_get_owned, dict_get_subdict, dict_insert, dict_load_from_root, dict_remove_bound_owned,
+    dict_remove_owned, dict_split, dict_tree_stats, read_label, DictBound, DictOwnedEntry,
+    DictTreeStats, SetMode,
 };
 
 /// Dictionary with fixed length keys (where `N` is a number of bits in each key).
@@ -415,7 +416,25 @@ impl<const N: u16> RawDict<N> {
         dict_remove_bound_owned(&mut self.0, N, bound, signed, context)
     }
 
-    ///Split dictionary to 2 dictionaries on a root level
+    /// Traverses the dictionary and computes its tree shape statistics.
+    ///
+    /// See [`DictTreeStats`] for the reported metrics.
+    pub fn tree_stats(&self) -> Result<DictTreeStats, Error> {
+        dict_tree_stats(self.0.as_ref(), N, &mut Cell::empty_context())
+    }
+
+    /// Traverses the dictionary and checks that all labels use
+    /// the shortest possible encoding.
+    ///
+    /// Can be used together with [`CellSlice::load_strict`] to reject
+    /// non-canonical encodings of the values from untrusted sources.
+    pub fn check_canonical(&self) -> Result<(), Error> {
+        dict_check_canonical(self.0.as_ref(), N, &mut Cell::empty_context())
+    }
+
+    /// Splits the dictionary into two by the first bit of the key.
+    ///
+    /// Returns the dictionaries with keys which start with `0` and `1` respectively.
     pub fn split(
         &self,
         context: &mut dyn CellContext,
@@ -624,6 +643,13 @@ impl<'a> RawOwnedIter<'a> {
         self
     }
 
+    /// Changes the behavior of the iterator to use the raw bit order.
+    #[inline]
+    pub fn unsigned(mut self) -> Self {
+        self.inner.signed = false;
+        self
+    }
+
     /// Returns whether the iterator direction was reversed.
     #[inline]
     pub fn is_reversed(&self) -> bool {
@@ -645,6 +671,128 @@ impl<'a> Iterator for RawOwnedIter<'a> {
     }
 }
 
+impl<const N: u16> IntoIterator for RawDict<N> {
+    type Item = Result<(CellBuilder, CellSliceParts), Error>;
+    type IntoIter = RawIntoIter;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        RawIntoIter::new(self.0, N)
+    }
+}
+
+/// An owning iterator over the entries of a [`RawDict`] or a [`Dict`].
+///
+/// This struct is created by the `into_iter` method on [`RawDict`]
+/// (provided by the [`IntoIterator`] trait).
+///
+/// # Performance
+///
+/// Unlike [`RawOwnedIter`], each step searches for the next key from the root,
+/// so this iterator doesn't borrow the dictionary and can be moved across threads.
+///
+/// [`Dict`]: crate::dict::Dict
+#[derive(Clone)]
+pub struct RawIntoIter {
+    root: Option<Cell>,
+    bit_len: u16,
+    prev_key: Option<CellBuilder>,
+    reversed: bool,
+    signed: bool,
+    status: IterStatus,
+}
+
+impl RawIntoIter {
+    /// Creates an owning iterator over the entries of a dictionary.
+    pub fn new(root: Option<Cell>, bit_len: u16) -> Self {
+        Self {
+            root,
+            bit_len,
+            prev_key: None,
+            reversed: false,
+            signed: false,
+            status: IterStatus::Valid,
+        }
+    }
+
+    /// Changes the direction of the iterator to descending.
+    #[inline]
+    pub fn reversed(mut self) -> Self {
+        self.reversed = true;
+        self
+    }
+
+    /// Changes the behavior of the iterator to reverse the high bit.
+    #[inline]
+    pub fn signed(mut self) -> Self {
+        self.signed = true;
+        self
+    }
+
+    /// Changes the behavior of the iterator to use the raw bit order.
+    #[inline]
+    pub fn unsigned(mut self) -> Self {
+        self.signed = false;
+        self
+    }
+
+    #[inline]
+    pub(crate) fn finish(&mut self, err: Error) -> Error {
+        self.status = IterStatus::Broken;
+        err
+    }
+
+    fn next_entry(&mut self) -> Result<Option<(CellBuilder, CellSliceParts)>, Error> {
+        let context = &mut Cell::empty_context();
+        let (bound, towards) = if self.reversed {
+            (DictBound::Max, DictBound::Min)
+        } else {
+            (DictBound::Min, DictBound::Max)
+        };
+
+        match &self.prev_key {
+            None => dict_find_bound_owned(
+                self.root.as_ref(),
+                self.bit_len,
+                bound,
+                self.signed,
+                context,
+            ),
+            Some(prev_key) => dict_find_owned(
+                self.root.as_ref(),
+                self.bit_len,
+                prev_key.as_data_slice(),
+                towards,
+                false,
+                self.signed,
+                context,
+            ),
+        }
+    }
+}
+
+impl Iterator for RawIntoIter {
+    type Item = Result<(CellBuilder, CellSliceParts), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if unlikely(!self.status.is_valid()) {
+            return None;
+        }
+
+        match self.next_entry() {
+            Ok(Some((key, value))) => {
+                self.prev_key = Some(key.clone());
+                Some(Ok((key, value)))
+            }
+            Ok(None) => {
+                self.status = IterStatus::Broken;
+                None
+            }
+            Err(e) => Some(Err(self.finish(e))),
+        }
+    }
+}
+
 /// An iterator over the entries of a [`RawDict`] or a [`Dict`].
 ///
 /// This struct is created by the [`iter`] method on [`RawDict`] or the [`raw_iter`] method on [`Dict`].
@@ -701,6 +849,88 @@ impl<'a> RawIter<'a> {
         }
     }
 
+    /// Creates an iterator over the entries of a dictionary which starts
+    /// from the specified key (skipping it if `inclusive` is `false`).
+    ///
+    /// All entries which come before the key in the iteration order
+    /// are skipped without visiting them.
+    pub fn new_from(
+        root: &'a Option<Cell>,
+        bit_len: u16,
+        mut key: CellSlice<'_>,
+        inclusive: bool,
+        reversed: bool,
+        signed: bool,
+    ) -> Result<Self, Error> {
+        let mut iter = Self::new_ext(root, bit_len, reversed, signed);
+        if iter.status.is_pruned() {
+            return Err(Error::PrunedBranchAccess);
+        } else if key.remaining_bits() != bit_len {
+            return Err(Error::CellUnderflow);
+        }
+
+        loop {
+            let is_root = iter.segments.len() == 1;
+            let Some(segment) = iter.segments.last_mut() else {
+                break;
+            };
+
+            // Read the next key part from the latest segment
+            let mut data = segment.data;
+            let prefix = ok!(read_label(&mut data, segment.remaining_bit_len));
+
+            let lcp_len = key.longest_common_data_prefix(&prefix).remaining_bits();
+            if lcp_len < prefix.remaining_bits() {
+                // Labels diverged, so the whole subtree is either after the key or before it
+                let offset = bit_len - segment.remaining_bit_len + lcp_len;
+                let label_bit = ok!(prefix.get_bit(lcp_len));
+                if !(label_bit ^ reversed ^ (signed && offset == 0)) {
+                    iter.segments.pop();
+                    ok!(iter.builder.rewind(!iter.segments.is_empty() as u16));
+                }
+                break;
+            }
+            ok!(key.advance(lcp_len, 0));
+
+            match segment.remaining_bit_len.checked_sub(lcp_len) {
+                // Found the key itself
+                Some(0) => {
+                    if !inclusive {
+                        iter.segments.pop();
+                        ok!(iter.builder.rewind(!iter.segments.is_empty() as u16));
+                    }
+                    break;
+                }
+                // Descend into the branch with the key
+                Some(remaining) => {
+                    if data.remaining_refs() < 2 {
+                        return Err(Error::CellUnderflow);
+                    }
+
+                    let next_bit = ok!(key.load_bit());
+                    let first_bit = reversed ^ (signed && is_root && prefix.is_data_empty());
+                    let child = ok!(data.cell().get_reference_as_slice(next_bit as u8));
+                    data.try_advance(0, 1 + (next_bit != first_bit) as u8);
+
+                    ok!(iter.builder.store_slice_data(prefix));
+                    ok!(iter.builder.store_bit(next_bit));
+
+                    segment.data = data;
+                    segment.prefix = Some(prefix);
+                    segment.remaining_bit_len = remaining - 1;
+                    iter.segments.push(IterSegment {
+                        data: child,
+                        prefix: None,
+                        remaining_bit_len: remaining - 1,
+                    });
+                }
+                None => return Err(Error::CellUnderflow),
+            }
+        }
+
+        Ok(iter)
+    }
+
     /// Changes the direction of the iterator to descending.
     #[inline]
     pub fn reversed(mut self) -> Self {
@@ -715,6 +945,13 @@ impl<'a> RawIter<'a> {
         self
     }
 
+    /// Changes the behavior of the iterator to use the raw bit order.
+    #[inline]
+    pub fn unsigned(mut self) -> Self {
+        self.signed = false;
+        self
+    }
+
     /// Returns whether the iterator direction was reversed.
     #[inline]
     pub fn is_reversed(&self) -> bool {
@@ -957,6 +1194,14 @@ impl<'a> UnionRawIter<'a> {
         self
     }
 
+    /// Changes the behavior of the iterator to use the raw bit order.
+    #[inline]
+    pub fn unsigned(mut self) -> Self {
+        self.left.signed = false;
+        self.right.signed = false;
+        self
+    }
+
     /// Returns whether the iterator direction was reversed.
     #[inline]
     pub fn is_reversed(&self) -> bool {
@@ -1138,6 +1383,13 @@ impl<'a> RawKeys<'a> {
         self
     }
 
+    /// Changes the behavior of the iterator to use the raw bit order.
+    #[inline]
+    pub fn unsigned(mut self) -> Self {
+        self.inner.signed = false;
+        self
+    }
+
     /// Returns whether the iterator direction was reversed.
     #[inline]
     pub fn is_reversed(&self) -> bool {
@@ -1206,6 +1458,13 @@ impl<'a> RawOwnedValues<'a> {
         self
     }
 
+    /// Changes the behavior of the iterator to use the raw bit order.
+    #[inline]
+    pub fn unsigned(mut self) -> Self {
+        self.inner.signed = false;
+        self
+    }
+
     /// Returns whether the iterator direction was reversed.
     #[inline]
     pub fn is_reversed(&self) -> bool {
@@ -1312,6 +1571,13 @@ impl<'a> RawValues<'a> {
         self
     }
 
+    /// Changes the behavior of the iterator to use the raw bit order.
+    #[inline]
+    pub fn unsigned(mut self) -> Self {
+        self.signed = false;
+        self
+    }
+
     /// Returns whether the iterator direction was reversed.
     #[inline]
     pub fn is_reversed(&self) -> bool {
@@ -1562,6 +1828,20 @@ mod tests {
             }
         }
 
+        // All keys start with zero bit
+        let (left, right) = dict.split(context)?;
+        assert_eq!(&left, dict.root());
+        assert!(right.is_none());
+
+        // Keys on both sides
+        dict.add(CellBuilder::build_from(u32::MAX)?.as_slice()?, 0u32)?;
+        let (left, right) = dict.split(context)?;
+        let left = RawDict::<32>::from(left);
+        let right = RawDict::<32>::from(right);
+        assert_eq!(left.iter().count(), 10);
+        assert_eq!(right.iter().count(), 1);
+        assert!(right.contains_key(CellBuilder::build_from(u32::MAX)?.as_slice()?)?);
+
         Ok(())
     }
 
@@ -1686,6 +1966,65 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn dict_iter_from() -> anyhow::Result<()> {
+        let mut dict = Dict::<i16, u8>::new();
+        for i in -20..20 {
+            dict.set(i * 3, 0)?;
+        }
+        let root = dict.root().clone();
+
+        for reversed in [false, true] {
+            for signed in [false, true] {
+                let all = RawIter::new_ext(&root, 16, reversed, signed)
+                    .map(|entry| entry.map(|(key, _)| key.as_data_slice().load_u16()))
+                    .collect::<Result<Result<Vec<_>, _>, _>>()??;
+
+                for from in -70i16..70 {
+                    let from = from as u16;
+                    let key = CellBuilder::build_from(from)?;
+
+                    for inclusive in [false, true] {
+                        let mut iter = RawIter::new_from(
+                            &root,
+                            16,
+                            key.as_slice()?,
+                            inclusive,
+                            reversed,
+                            signed,
+                        )?;
+                        let first = iter.next().transpose()?;
+                        let first = match first {
+                            Some((key, _)) => Some(key.as_data_slice().load_u16()?),
+                            None => None,
+                        };
+
+                        let flip = |key: u16| if signed { key ^ 0x8000 } else { key };
+                        let expected = all.iter().copied().find(|&key| {
+                            let (key, from) = (flip(key), flip(from));
+                            if reversed {
+                                key < from || inclusive && key == from
+                            } else {
+                                key > from || inclusive && key == from
+                            }
+                        });
+                        assert_eq!(first, expected);
+
+                        // Remaining entries are the same as for the full iterator
+                        let rest = iter.count();
+                        let expected_rest = match expected {
+                            Some(key) => all.len() - all.iter().position(|&k| k == key).unwrap(),
+                            None => 0,
+                        };
+                        assert_eq!(rest + first.is_some() as usize, expected_rest);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn dict_iter_union() -> anyhow::Result<()> {
         let mut left = RawDict::<32>::new();
@@ -1,5 +1,6 @@
 use std::borrow::Borrow;
 use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
 
 use crate::cell::*;
 use crate::dict::dict_remove_owned;
@@ -7,8 +8,9 @@ use crate::error::Error;
 use crate::util::*;
 
 use super::{
-    dict_find_bound, dict_find_owned, dict_get, dict_insert, dict_load_from_root, DictBound,
-    DictKey, SetMode,
+    build_dict_from_sorted, dict_check_canonical, dict_find_bound, dict_find_owned, dict_get,
+    dict_insert, dict_insert_owned, dict_load_from_root, dict_merge, dict_split_by_prefix,
+    dict_tree_stats, with_key_slice, DictBound, DictKey, DictTreeStats, SetMode,
 };
 use super::{dict_remove_bound_owned, raw::*};
 
@@ -179,15 +181,15 @@ where
         where
             K: Store + DictKey,
         {
-            let mut builder = CellBuilder::new();
-            ok!(key.store_into(&mut builder, &mut Cell::empty_context()));
-            Ok(ok!(dict_get(
-                root.as_ref(),
-                K::BITS,
-                builder.as_data_slice(),
-                &mut Cell::empty_context()
-            ))
-            .is_some())
+            with_key_slice(key, |key| {
+                Ok(ok!(dict_get(
+                    root.as_ref(),
+                    K::BITS,
+                    key,
+                    &mut Cell::empty_context()
+                ))
+                .is_some())
+            })
         }
         contains_key_impl(&self.root, key.borrow())
     }
@@ -211,16 +213,9 @@ where
             K: Store + DictKey,
             V: Load<'a>,
         {
-            let Some(mut value) = ({
-                let mut builder = CellBuilder::new();
-                ok!(key.store_into(&mut builder, &mut Cell::empty_context()));
-                ok!(dict_get(
-                    root.as_ref(),
-                    K::BITS,
-                    builder.as_data_slice(),
-                    &mut Cell::empty_context()
-                ))
-            }) else {
+            let Some(mut value) = ok!(with_key_slice(key, |key| {
+                dict_get(root.as_ref(), K::BITS, key, &mut Cell::empty_context())
+            })) else {
                 return Ok(None);
             };
 
@@ -245,14 +240,9 @@ where
         where
             K: Store + DictKey,
         {
-            let mut builder = CellBuilder::new();
-            ok!(key.store_into(&mut builder, &mut Cell::empty_context()));
-            dict_get(
-                root.as_ref(),
-                K::BITS,
-                builder.as_data_slice(),
-                &mut Cell::empty_context(),
-            )
+            with_key_slice(key, |key| {
+                dict_get(root.as_ref(), K::BITS, key, &mut Cell::empty_context())
+            })
         }
 
         get_raw_impl(&self.root, key.borrow())
@@ -367,6 +357,70 @@ where
     {
         self.add_ext(key, value, &mut Cell::empty_context())
     }
+
+    /// Builds a dictionary from entries in arbitrary order.
+    ///
+    /// Entries are sorted first, so each node is built only once.
+    /// For duplicate keys the last value is used.
+    pub fn try_from_iter<I>(entries: I) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (K, V)>,
+    {
+        let context = &mut Cell::empty_context();
+
+        let mut items = Vec::new();
+        for (key, value) in entries {
+            let mut builder = CellBuilder::new();
+            ok!(key.store_into(&mut builder, context));
+            items.push((builder, value));
+        }
+
+        // NOTE: Reverse before the stable sort to keep the last value on dedup
+        items.reverse();
+        items.sort_by(|(a, _), (b, _)| a.raw_data().cmp(b.raw_data()));
+        items.dedup_by(|(a, _), (b, _)| a.raw_data() == b.raw_data());
+
+        Ok(Self::from(ok!(build_dict_from_sorted(
+            &items,
+            K::BITS,
+            context
+        ))))
+    }
+}
+
+/// # Panics
+///
+/// Panics if some entry can't be stored (e.g. the value doesn't fit into a cell).
+/// Use [`Dict::try_from_iter`] to handle such errors.
+impl<K, V> FromIterator<(K, V)> for Dict<K, V>
+where
+    K: Store + DictKey,
+    V: Store,
+{
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        match Self::try_from_iter(iter) {
+            Ok(dict) => dict,
+            Err(e) => panic!("failed to build a dictionary: {e}"),
+        }
+    }
+}
+
+/// # Panics
+///
+/// Panics if some entry can't be stored (e.g. the value doesn't fit into a cell).
+/// Use [`Dict::set`] to handle such errors.
+impl<K, V> Extend<(K, V)> for Dict<K, V>
+where
+    K: Store + DictKey,
+    V: Store,
+{
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            if let Err(e) = self.set(key, value) {
+                panic!("failed to extend a dictionary: {e}");
+            }
+        }
+    }
 }
 
 impl<K, V> Dict<K, V>
@@ -393,6 +447,37 @@ where
         Iter::new(&self.root)
     }
 
+    /// Gets an iterator over the entries of the dictionary, sorted by key,
+    /// starting from the specified key (inclusive).
+    /// The iterator element type is `Result<(K, V)>`.
+    ///
+    /// See [`range`] for more.
+    ///
+    /// [`range`]: Dict::range
+    pub fn iter_from<'a, Q>(&'a self, key: Q) -> Result<Range<'a, K, V>, Error>
+    where
+        Q: Borrow<K>,
+        V: Load<'a>,
+    {
+        Range::new(&self.root, Bound::Included(key.borrow()), Bound::Unbounded)
+    }
+
+    /// Gets an iterator over the entries of the dictionary within
+    /// the specified range of keys, sorted by key.
+    /// The iterator element type is `Result<(K, V)>`.
+    ///
+    /// Entries before the start bound are skipped without visiting them,
+    /// so this can be used to iterate over a large dictionary in pages.
+    ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are compared in the signed order.
+    pub fn range<'a, R>(&'a self, range: R) -> Result<Range<'a, K, V>, Error>
+    where
+        R: RangeBounds<K>,
+        V: Load<'a>,
+    {
+        Range::new(&self.root, range.start_bound(), range.end_bound())
+    }
+
     /// Gets an iterator over the entries of two dictionaries, sorted by key.
     /// The iterator element type is `Result<(K, Option<V>, Option<V>)>`.
     ///
@@ -494,20 +579,18 @@ where
             for<'a> V: Load<'a>,
         {
             let context = &mut Cell::empty_context();
-            let Some((key, (cell, range))) = ({
-                let mut builder = CellBuilder::new();
-                ok!(key.store_into(&mut builder, context));
-                // TODO: add `dict_find` with non-owned return type
-                ok!(dict_find_owned(
+            // TODO: add `dict_find` with non-owned return type
+            let Some((key, (cell, range))) = ok!(with_key_slice(key, |key| {
+                dict_find_owned(
                     root.as_ref(),
                     K::BITS,
-                    builder.as_data_slice(),
+                    key,
                     towards,
                     inclusive,
                     signed,
                     context,
-                ))
-            }) else {
+                )
+            })) else {
                 return Ok(None);
             };
             let value = &mut ok!(range.apply(&cell));
@@ -526,16 +609,84 @@ impl<K, V> Dict<K, V>
 where
     K: DictKey,
 {
+    /// Traverses the dictionary and computes its tree shape statistics.
+    ///
+    /// See [`DictTreeStats`] for the reported metrics.
+    pub fn tree_stats(&self) -> Result<DictTreeStats, Error> {
+        dict_tree_stats(self.root.as_ref(), K::BITS, &mut Cell::empty_context())
+    }
+
+    /// Traverses the dictionary and checks that all labels use
+    /// the shortest possible encoding.
+    ///
+    /// Can be used together with [`CellSlice::load_strict`] to reject
+    /// non-canonical encodings of the values from untrusted sources.
+    pub fn check_canonical(&self) -> Result<(), Error> {
+        dict_check_canonical(self.root.as_ref(), K::BITS, &mut Cell::empty_context())
+    }
+
+    /// Splits the dictionary into two by the first bit of the key.
+    ///
+    /// Returns the dictionaries with keys which start with `0` and `1` respectively.
+    pub fn split(&self) -> Result<(Self, Self), Error> {
+        self.split_by_prefix(&CellSlice::default())
+    }
+
+    /// Splits the dictionary into two by the key bit which follows the `key_prefix`.
+    ///
+    /// Use [`split_by_prefix_ext`] if you need to use a custom cell context.
+    ///
+    /// [`split_by_prefix_ext`]: Dict::split_by_prefix_ext
+    pub fn split_by_prefix(&self, key_prefix: &CellSlice<'_>) -> Result<(Self, Self), Error> {
+        self.split_by_prefix_ext(key_prefix, &mut Cell::empty_context())
+    }
+
+    /// Splits the dictionary into two by the key bit which follows the `key_prefix`.
+    ///
+    /// See [`dict_split_by_prefix`] for details.
+    pub fn split_by_prefix_ext(
+        &self,
+        key_prefix: &CellSlice<'_>,
+        context: &mut dyn CellContext,
+    ) -> Result<(Self, Self), Error> {
+        let (left, right) = ok!(dict_split_by_prefix(
+            self.root.as_ref(),
+            key_prefix,
+            K::BITS,
+            context
+        ));
+        Ok((Self::from(left), Self::from(right)))
+    }
+
+    /// Merges the dictionary with another one with disjoint keys.
+    ///
+    /// Use [`merge_ext`] if you need to use a custom cell context.
+    ///
+    /// [`merge_ext`]: Dict::merge_ext
+    pub fn merge(&mut self, other: &Self) -> Result<(), Error> {
+        self.merge_ext(other, &mut Cell::empty_context())
+    }
+
+    /// Merges the dictionary with another one with disjoint keys.
+    ///
+    /// Returns [`Error::InvalidData`] if some key is present in both dictionaries.
+    /// The dictionary is left unchanged in case of an error.
+    pub fn merge_ext(&mut self, other: &Self, context: &mut dyn CellContext) -> Result<(), Error> {
+        dict_merge(&mut self.root, &other.root, K::BITS, context)
+    }
+
     /// Gets an iterator over the values of the dictionary, in order by key.
     /// The iterator element type is `Result<V>`.
     ///
     /// If the dictionary is invalid, finishes after the first invalid element,
     /// returning an error.
+    ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
     pub fn values<'a>(&'a self) -> Values<'a, V>
     where
         V: Load<'a>,
     {
-        Values::new(&self.root, K::BITS)
+        Values::new_ext(&self.root, K::BITS, false, K::SIGNED)
     }
 
     /// Returns the lowest key and a value corresponding to the key.
@@ -632,9 +783,9 @@ where
         where
             K: Store + DictKey,
         {
-            let mut builder = CellBuilder::new();
-            ok!(key.store_into(&mut builder, &mut Cell::empty_context()));
-            dict_remove_owned(root, &mut builder.as_data_slice(), K::BITS, false, context)
+            with_key_slice(key, |mut key| {
+                dict_remove_owned(root, &mut key, K::BITS, false, context)
+            })
         }
 
         remove_raw_ext_impl(&mut self.root, key.borrow(), context)
@@ -646,6 +797,8 @@ where
     /// If the dictionary is invalid, finishes after the first invalid element,
     /// returning an error.
     ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
+    ///
     /// # Performance
     ///
     /// In the current implementation, iterating over dictionary builds a key
@@ -654,7 +807,7 @@ where
     /// [`values`]: Dict::values
     /// [`raw_values`]: Dict::raw_values
     pub fn raw_iter(&'_ self) -> RawIter<'_> {
-        RawIter::new(&self.root, K::BITS)
+        RawIter::new_ext(&self.root, K::BITS, false, K::SIGNED)
     }
 
     /// Gets an iterator over the raw entries of two dictionaries, sorted by key.
@@ -663,12 +816,14 @@ where
     /// If the dictionary is invalid, finishes after the first invalid element,
     /// returning an error.
     ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
+    ///
     /// # Performance
     ///
     /// In the current implementation, iterating over dictionary builds a key
     /// for each element.
     pub fn raw_iter_union<'a>(&'a self, other: &'a Self) -> UnionRawIter<'a> {
-        UnionRawIter::new(&self.root, &other.root, K::BITS)
+        UnionRawIter::new_ext(&self.root, &other.root, K::BITS, false, K::SIGNED)
     }
 
     /// Gets an iterator over the raw keys of the dictionary, in sorted order.
@@ -677,6 +832,8 @@ where
     /// If the dictionary is invalid, finishes after the first invalid element,
     /// returning an error.
     ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
+    ///
     /// # Performance
     ///
     /// In the current implementation, iterating over dictionary builds a key
@@ -685,7 +842,7 @@ where
     /// [`values`]: Dict::values
     /// [`raw_values`]: Dict::raw_values
     pub fn raw_keys(&'_ self) -> RawKeys<'_> {
-        RawKeys::new(&self.root, K::BITS)
+        RawKeys::new_ext(&self.root, K::BITS, false, K::SIGNED)
     }
 }
 
@@ -698,8 +855,10 @@ where
     ///
     /// If the dictionary is invalid, finishes after the first invalid element,
     /// returning an error.
+    ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
     pub fn raw_values(&'_ self) -> RawValues<'_> {
-        RawValues::new(&self.root, K::BITS)
+        RawValues::new_ext(&self.root, K::BITS, false, K::SIGNED)
     }
 }
 
@@ -763,16 +922,135 @@ where
         K: Store + DictKey,
         V: Store,
     {
-        let mut key_builder = CellBuilder::new();
-        ok!(key.store_into(&mut key_builder, &mut Cell::empty_context()));
-        dict_insert(
-            &mut self.root,
-            &mut key_builder.as_data_slice(),
-            K::BITS,
-            value,
-            mode,
-            context,
-        )
+        with_key_slice(key, |mut key| {
+            dict_insert(&mut self.root, &mut key, K::BITS, value, mode, context)
+        })
+    }
+}
+
+impl<K, V> Dict<K, V>
+where
+    K: Store + DictKey,
+{
+    /// Gets the given key's entry in the dictionary for in-place insertion.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use everscale_types::dict::Dict;
+    /// let mut dict = Dict::<u32, u64>::new();
+    /// for (account, amount) in [(1, 10), (2, 20), (1, 30)] {
+    ///     let total = dict.entry(account).or_default()?;
+    ///     dict.set(account, total + amount)?;
+    /// }
+    /// assert_eq!(dict.get(1)?, Some(40));
+    /// # Ok::<_, everscale_types::error::Error>(())
+    /// ```
+    pub fn entry(&mut self, key: K) -> DictEntry<'_, K, V> {
+        DictEntry { dict: self, key }
+    }
+}
+
+/// A view into a single entry of a dictionary.
+///
+/// This struct is created by the [`entry`] method on [`Dict`].
+///
+/// [`entry`]: Dict::entry
+pub struct DictEntry<'a, K, V> {
+    dict: &'a mut Dict<K, V>,
+    key: K,
+}
+
+impl<K, V> DictEntry<'_, K, V>
+where
+    K: Store + DictKey,
+    for<'a> V: Store + Load<'a> + 'static,
+{
+    /// Returns a reference to the entry key.
+    #[inline]
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Returns the existing value or inserts the specified one.
+    pub fn or_insert(self, value: V) -> Result<V, Error> {
+        self.or_insert_with(|| value)
+    }
+
+    /// Returns the existing value or inserts the default one.
+    pub fn or_default(self) -> Result<V, Error>
+    where
+        V: Default,
+    {
+        self.or_insert_with(V::default)
+    }
+
+    /// Returns the existing value or inserts the result of the function.
+    ///
+    /// Use [`or_insert_with_ext`] if you need to use a custom cell context.
+    ///
+    /// [`or_insert_with_ext`]: DictEntry::or_insert_with_ext
+    pub fn or_insert_with<F>(self, f: F) -> Result<V, Error>
+    where
+        F: FnOnce() -> V,
+    {
+        self.or_insert_with_ext(f, &mut Cell::empty_context())
+    }
+
+    /// Returns the existing value or inserts the result of the function.
+    ///
+    /// The dictionary is traversed only once, the function is called
+    /// only if the key is missing.
+    pub fn or_insert_with_ext<F>(self, f: F, context: &mut dyn CellContext) -> Result<V, Error>
+    where
+        F: FnOnce() -> V,
+    {
+        let value = LazyValue {
+            f: std::cell::Cell::new(Some(f)),
+            value: std::cell::RefCell::new(None),
+        };
+
+        let root = &mut self.dict.root;
+        let (_, existing) = ok!(with_key_slice(&self.key, |mut key| {
+            dict_insert_owned(root, &mut key, K::BITS, &value, SetMode::Add, context)
+        }));
+
+        match existing {
+            Some((cell, range)) => {
+                let mut slice = ok!(range.apply(&cell));
+                V::load_from(&mut slice)
+            }
+            None => match value.value.into_inner() {
+                Some(value) => Ok(value),
+                None => Err(Error::InvalidData),
+            },
+        }
+    }
+}
+
+/// Value which is computed only when it is stored.
+struct LazyValue<F, V> {
+    f: std::cell::Cell<Option<F>>,
+    value: std::cell::RefCell<Option<V>>,
+}
+
+impl<F, V> Store for LazyValue<F, V>
+where
+    F: FnOnce() -> V,
+    V: Store,
+{
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
+        let Some(f) = self.f.take() else {
+            return Err(Error::InvalidData);
+        };
+        let value = f();
+        ok!(value.store_into(builder, context));
+        *self.value.borrow_mut() = Some(value);
+        Ok(())
     }
 }
 
@@ -858,9 +1136,11 @@ where
     K: DictKey,
 {
     /// Creates an iterator over the entries of a dictionary.
+    ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
     pub fn new(root: &'a Option<Cell>) -> Self {
         Self {
-            inner: RawIter::new(root, K::BITS),
+            inner: RawIter::new_ext(root, K::BITS, false, K::SIGNED),
             _key: PhantomData,
             _value: PhantomData,
         }
@@ -879,6 +1159,13 @@ where
         self.inner = self.inner.signed();
         self
     }
+
+    /// Changes the behavior of the iterator to use the raw bit order.
+    #[inline]
+    pub fn unsigned(mut self) -> Self {
+        self.inner = self.inner.unsigned();
+        self
+    }
 }
 
 impl<'a, K, V> Iterator for Iter<'a, K, V>
@@ -906,6 +1193,124 @@ where
     }
 }
 
+impl<'a, K, V> IntoIterator for &'a Dict<K, V>
+where
+    K: DictKey + Store,
+    V: Load<'a>,
+{
+    type Item = Result<(K, V), Error>;
+    type IntoIter = Iter<'a, K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<K, V> IntoIterator for Dict<K, V>
+where
+    K: DictKey,
+    for<'a> V: Load<'a>,
+{
+    type Item = Result<(K, V), Error>;
+    type IntoIter = IntoIter<K, V>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        IntoIter::new(self.root)
+    }
+}
+
+/// An owning iterator over the entries of a [`Dict`].
+///
+/// This struct is created by the `into_iter` method on [`Dict`]
+/// (provided by the [`IntoIterator`] trait). See [`RawIntoIter`] for more.
+pub struct IntoIter<K, V> {
+    inner: RawIntoIter,
+    _key: PhantomData<K>,
+    _value: PhantomData<V>,
+}
+
+impl<K, V> Clone for IntoIter<K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            _key: PhantomData,
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<K, V> IntoIter<K, V>
+where
+    K: DictKey,
+{
+    /// Creates an owning iterator over the entries of a dictionary.
+    ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
+    pub fn new(root: Option<Cell>) -> Self {
+        let mut inner = RawIntoIter::new(root, K::BITS);
+        if K::SIGNED {
+            inner = inner.signed();
+        }
+
+        Self {
+            inner,
+            _key: PhantomData,
+            _value: PhantomData,
+        }
+    }
+
+    /// Changes the direction of the iterator to descending.
+    #[inline]
+    pub fn reversed(mut self) -> Self {
+        self.inner = self.inner.reversed();
+        self
+    }
+
+    /// Changes the behavior of the iterator to reverse the high bit.
+    #[inline]
+    pub fn signed(mut self) -> Self {
+        self.inner = self.inner.signed();
+        self
+    }
+
+    /// Changes the behavior of the iterator to use the raw bit order.
+    #[inline]
+    pub fn unsigned(mut self) -> Self {
+        self.inner = self.inner.unsigned();
+        self
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V>
+where
+    K: DictKey,
+    for<'a> V: Load<'a>,
+{
+    type Item = Result<(K, V), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        Some(match self.inner.next()? {
+            Ok((key, (cell, range))) => {
+                let err = if let Some(key) = K::from_raw_data(key.raw_data()) {
+                    match range
+                        .apply(&cell)
+                        .and_then(|ref mut value| V::load_from(value))
+                    {
+                        Ok(value) => return Some(Ok((key, value))),
+                        Err(e) => e,
+                    }
+                } else {
+                    Error::CellUnderflow
+                };
+                Err(self.inner.finish(err))
+            }
+            Err(e) => Err(e),
+        })
+    }
+}
+
 /// An iterator over the entries across two [`Dict`].
 ///
 /// This struct is created by the [`iter_union`] method on [`Dict`].
@@ -922,9 +1327,11 @@ where
     K: DictKey,
 {
     /// Creates an iterator over the entries of a dictionary.
+    ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
     pub fn new(left_root: &'a Option<Cell>, right_root: &'a Option<Cell>) -> Self {
         Self {
-            inner: UnionRawIter::new(left_root, right_root, K::BITS),
+            inner: UnionRawIter::new_ext(left_root, right_root, K::BITS, false, K::SIGNED),
             _key: PhantomData,
             _value: PhantomData,
         }
@@ -943,6 +1350,13 @@ where
         self.inner = self.inner.signed();
         self
     }
+
+    /// Changes the behavior of the iterator to use the raw bit order.
+    #[inline]
+    pub fn unsigned(mut self) -> Self {
+        self.inner = self.inner.unsigned();
+        self
+    }
 }
 
 impl<'a, K, V> Iterator for UnionIter<'a, K, V>
@@ -986,6 +1400,135 @@ where
     }
 }
 
+/// An iterator over the entries of a [`Dict`] within the range of keys.
+///
+/// This struct is created by the [`range`] and [`iter_from`] methods on [`Dict`].
+/// See their documentation for more.
+///
+/// [`range`]: Dict::range
+/// [`iter_from`]: Dict::iter_from
+pub struct Range<'a, K, V> {
+    inner: RawIter<'a>,
+    end: Bound<CellBuilder>,
+    finished: bool,
+    _key: PhantomData<K>,
+    _value: PhantomData<V>,
+}
+
+impl<K, V> Clone for Range<'_, K, V> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            end: self.end.clone(),
+            finished: self.finished,
+            _key: PhantomData,
+            _value: PhantomData,
+        }
+    }
+}
+
+impl<'a, K, V> Range<'a, K, V>
+where
+    K: DictKey + Store,
+{
+    /// Creates an iterator over the entries of a dictionary within the range of keys.
+    ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
+    pub fn new(root: &'a Option<Cell>, start: Bound<&K>, end: Bound<&K>) -> Result<Self, Error> {
+        let inner = match start {
+            Bound::Included(key) | Bound::Excluded(key) => {
+                let inclusive = matches!(start, Bound::Included(_));
+                ok!(with_key_slice(key, |key| RawIter::new_from(
+                    root,
+                    K::BITS,
+                    key,
+                    inclusive,
+                    false,
+                    K::SIGNED
+                )))
+            }
+            Bound::Unbounded => RawIter::new_ext(root, K::BITS, false, K::SIGNED),
+        };
+
+        let build_key = |key: &K| {
+            let mut builder = CellBuilder::new();
+            ok!(key.store_into(&mut builder, &mut Cell::empty_context()));
+            Ok::<_, Error>(builder)
+        };
+        let end = match end {
+            Bound::Included(key) => Bound::Included(ok!(build_key(key))),
+            Bound::Excluded(key) => Bound::Excluded(ok!(build_key(key))),
+            Bound::Unbounded => Bound::Unbounded,
+        };
+
+        Ok(Self {
+            inner,
+            end,
+            finished: false,
+            _key: PhantomData,
+            _value: PhantomData,
+        })
+    }
+
+    fn is_before_end(&self, key: &CellBuilder) -> bool {
+        let (end, inclusive) = match &self.end {
+            Bound::Included(end) => (end, true),
+            Bound::Excluded(end) => (end, false),
+            Bound::Unbounded => return true,
+        };
+
+        let len = ((K::BITS + 7) / 8) as usize;
+        let key = &key.raw_data()[..len];
+        let end = &end.raw_data()[..len];
+        let ordering = match (key.split_first(), end.split_first()) {
+            (Some((key_first, key)), Some((end_first, end))) if K::SIGNED => (key_first ^ 0x80)
+                .cmp(&(end_first ^ 0x80))
+                .then(key.cmp(end)),
+            _ => key.cmp(end),
+        };
+
+        match ordering {
+            std::cmp::Ordering::Less => true,
+            std::cmp::Ordering::Equal => inclusive,
+            std::cmp::Ordering::Greater => false,
+        }
+    }
+}
+
+impl<'a, K, V> Iterator for Range<'a, K, V>
+where
+    K: DictKey + Store,
+    V: Load<'a>,
+{
+    type Item = Result<(K, V), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        Some(match self.inner.next()? {
+            Ok((key, mut value)) => {
+                if !self.is_before_end(&key) {
+                    self.finished = true;
+                    return None;
+                }
+
+                let err = if let Some(key) = K::from_raw_data(key.raw_data()) {
+                    match V::load_from(&mut value) {
+                        Ok(value) => return Some(Ok((key, value))),
+                        Err(e) => e,
+                    }
+                } else {
+                    Error::CellUnderflow
+                };
+                Err(self.inner.finish(err))
+            }
+            Err(e) => Err(e),
+        })
+    }
+}
+
 /// An iterator over the keys of a [`Dict`].
 ///
 /// This struct is created by the [`keys`] method on [`Dict`]. See its
@@ -1011,9 +1554,11 @@ where
     K: DictKey,
 {
     /// Creates an iterator over the keys of a dictionary.
+    ///
+    /// NOTE: Signed keys (see [`DictKey::SIGNED`]) are iterated in the signed order.
     pub fn new(root: &'a Option<Cell>) -> Self {
         Self {
-            inner: RawIter::new(root, K::BITS),
+            inner: RawIter::new_ext(root, K::BITS, false, K::SIGNED),
             _key: PhantomData,
         }
     }
@@ -1031,6 +1576,13 @@ where
         self.inner = self.inner.signed();
         self
     }
+
+    /// Changes the behavior of the iterator to use the raw bit order.
+    #[inline]
+    pub fn unsigned(mut self) -> Self {
+        self.inner = self.inner.unsigned();
+        self
+    }
 }
 
 impl<'a, K> Iterator for Keys<'a, K>
@@ -1072,8 +1624,14 @@ impl<V> Clone for Values<'_, V> {
 impl<'a, V> Values<'a, V> {
     /// Creates an iterator over the values of a dictionary.
     pub fn new(root: &'a Option<Cell>, bit_len: u16) -> Self {
+        Self::new_ext(root, bit_len, false, false)
+    }
+
+    /// Creates an iterator over the values of a dictionary with explicit
+    /// direction and behavior.
+    pub fn new_ext(root: &'a Option<Cell>, bit_len: u16, reversed: bool, signed: bool) -> Self {
         Self {
-            inner: RawValues::new(root, bit_len),
+            inner: RawValues::new_ext(root, bit_len, reversed, signed),
             _value: PhantomData,
         }
     }
@@ -1091,6 +1649,13 @@ impl<'a, V> Values<'a, V> {
         self.inner = self.inner.signed();
         self
     }
+
+    /// Changes the behavior of the iterator to use the raw bit order.
+    #[inline]
+    pub fn unsigned(mut self) -> Self {
+        self.inner = self.inner.unsigned();
+        self
+    }
 }
 
 impl<'a, V> Iterator for Values<'a, V>
@@ -1157,6 +1722,52 @@ mod tests {
         assert_eq!(dict.get_max(false).unwrap(), Some((3, 0xff)));
     }
 
+    #[test]
+    fn dict_entry() {
+        let mut dict = Dict::<u32, u64>::new();
+
+        // Inserts missing values
+        assert_eq!(dict.entry(1).or_insert(10).unwrap(), 10);
+        assert_eq!(dict.entry(2).or_default().unwrap(), 0);
+        assert_eq!(dict.get(1).unwrap(), Some(10));
+        assert_eq!(dict.get(2).unwrap(), Some(0));
+
+        // Keeps existing values
+        let mut called = false;
+        let value = dict
+            .entry(1)
+            .or_insert_with(|| {
+                called = true;
+                20
+            })
+            .unwrap();
+        assert_eq!(value, 10);
+        assert!(!called);
+        assert_eq!(dict.entry(2).or_insert(30).unwrap(), 0);
+
+        // Accumulate values
+        for i in 0..100u32 {
+            let entry = dict.entry(i % 7);
+            assert_eq!(*entry.key(), i % 7);
+            let total = entry.or_default().unwrap();
+            dict.set(i % 7, total + i as u64).unwrap();
+        }
+
+        let mut expected = Dict::<u32, u64>::new();
+        for i in 0..7u32 {
+            let sum = (0..100u32)
+                .filter(|x| x % 7 == i)
+                .map(u64::from)
+                .sum::<u64>();
+            let initial = match i {
+                1 => 10,
+                _ => 0,
+            };
+            expected.set(i, initial + sum).unwrap();
+        }
+        assert_eq!(dict, expected);
+    }
+
     #[test]
     fn dict_remove_bounds() {
         let mut dict = Dict::<i32, bool>::new();
@@ -1315,6 +1926,280 @@ mod tests {
         assert_eq!(signed_range_iter.next(), None);
     }
 
+    #[test]
+    fn dict_split_merge() {
+        let dict = (0..100u32)
+            .map(|i| (i.wrapping_mul(0x9e3779b9), i))
+            .collect::<Dict<u32, u32>>();
+
+        let filter = |prefix: u32, len: u32| {
+            dict.iter()
+                .map(Result::unwrap)
+                .filter(|(key, _)| key >> (32 - len) == prefix)
+                .collect::<Dict<u32, u32>>()
+        };
+
+        // Split by the first bit
+        let (left, right) = dict.split().unwrap();
+        assert_eq!(left, filter(0, 1));
+        assert_eq!(right, filter(1, 1));
+        assert!(!left.is_empty() && !right.is_empty());
+
+        let mut merged = left.clone();
+        merged.merge(&right).unwrap();
+        assert_eq!(merged, dict);
+
+        // Split by longer prefixes
+        for len in 1..6 {
+            for prefix in 0..(1u32 << len) {
+                let part = filter(prefix, len);
+
+                let mut key_prefix = CellBuilder::new();
+                key_prefix.store_uint(prefix as u64, len as u16).unwrap();
+                let (left, right) = part.split_by_prefix(&key_prefix.as_data_slice()).unwrap();
+                assert_eq!(left, filter(prefix << 1, len + 1));
+                assert_eq!(right, filter((prefix << 1) | 1, len + 1));
+
+                let mut merged = right;
+                merged.merge(&left).unwrap();
+                assert_eq!(merged, part);
+            }
+        }
+
+        // Keys which don't start with the prefix
+        let key_prefix = CellBuilder::build_from(true).unwrap();
+        assert_eq!(
+            dict.split_by_prefix(&key_prefix.as_slice().unwrap())
+                .unwrap_err(),
+            Error::InvalidData
+        );
+
+        // Prefix must be shorter than the key
+        let key_prefix = CellBuilder::build_from(0u32).unwrap();
+        assert_eq!(
+            dict.split_by_prefix(&key_prefix.as_slice().unwrap())
+                .unwrap_err(),
+            Error::CellUnderflow
+        );
+
+        // Empty dictionary
+        let (left, right) = Dict::<u32, u32>::new().split().unwrap();
+        assert!(left.is_empty() && right.is_empty());
+
+        // Keys must be disjoint
+        let mut merged = left.clone();
+        merged.merge(&dict).unwrap();
+        assert_eq!(merged, dict);
+        assert_eq!(merged.merge(&filter(1, 1)).unwrap_err(), Error::InvalidData);
+        assert_eq!(merged, dict);
+    }
+
+    #[test]
+    fn dict_range() {
+        fn check<K>(keys: &[K], bounds: &[K])
+        where
+            K: Store + DictKey + Copy + Ord + std::fmt::Debug,
+        {
+            let mut dict = Dict::<K, u32>::new();
+            for (i, key) in keys.iter().enumerate() {
+                dict.set(key, i as u32).unwrap();
+            }
+            let entries = dict.iter().collect::<Result<Vec<_>, _>>().unwrap();
+
+            let check_range = |range: (Bound<&K>, Bound<&K>)| {
+                let expected = entries
+                    .iter()
+                    .filter(|(key, _)| range.contains(key))
+                    .copied()
+                    .collect::<Vec<_>>();
+                let actual = dict.range(range).unwrap().collect::<Result<Vec<_>, _>>();
+                assert_eq!(actual.unwrap(), expected, "range: {range:?}");
+            };
+
+            for start in bounds {
+                let from = dict
+                    .iter_from(start)
+                    .unwrap()
+                    .collect::<Result<Vec<_>, _>>();
+                let expected = entries.iter().filter(|(key, _)| key >= start);
+                assert!(from.unwrap().iter().eq(expected));
+
+                check_range((Bound::Excluded(start), Bound::Unbounded));
+                check_range((Bound::Unbounded, Bound::Included(start)));
+                check_range((Bound::Unbounded, Bound::Excluded(start)));
+                for end in bounds {
+                    check_range((Bound::Included(start), Bound::Included(end)));
+                    check_range((Bound::Included(start), Bound::Excluded(end)));
+                    check_range((Bound::Excluded(start), Bound::Excluded(end)));
+                }
+            }
+        }
+
+        // Empty dict
+        check::<u32>(&[], &[0, 1, u32::MAX]);
+        // Single entry
+        check::<u32>(&[123], &[0, 122, 123, 124, u32::MAX]);
+
+        let keys = (0..100).map(|i| i * 7 + 3).collect::<Vec<u32>>();
+        let bounds = (0..110).map(|i| i * 5).collect::<Vec<u32>>();
+        check(&keys, &bounds);
+
+        let keys = (-50..50).map(|i| i * 7 + 3).collect::<Vec<i32>>();
+        let bounds = (-60..60)
+            .map(|i| i * 5)
+            .chain([i32::MIN, i32::MAX])
+            .collect::<Vec<i32>>();
+        check(&keys, &bounds);
+    }
+
+    #[test]
+    fn dict_signed_keys() {
+        fn check<K>()
+        where
+            K: Store + DictKey + From<i8> + Copy + Ord + Default + std::fmt::Debug,
+            for<'a> K: Load<'a>,
+        {
+            let expected = (-20..20).map(K::from).collect::<Vec<_>>();
+
+            let mut dict = Dict::<K, ()>::new();
+            for key in expected.iter().rev() {
+                dict.set(key, ()).unwrap();
+            }
+
+            let keys = dict.keys().map(Result::unwrap).collect::<Vec<_>>();
+            assert_eq!(keys, expected);
+            let keys = dict
+                .iter()
+                .map(|entry| entry.unwrap().0)
+                .collect::<Vec<_>>();
+            assert_eq!(keys, expected);
+            let keys = dict.clone().into_iter().map(|entry| entry.unwrap().0);
+            assert_eq!(keys.collect::<Vec<_>>(), expected);
+            let keys = dict.iter_union(&dict).map(|entry| entry.unwrap().0);
+            assert_eq!(keys.collect::<Vec<_>>(), expected);
+
+            let keys = dict.keys().reversed().map(Result::unwrap);
+            assert!(keys.eq(expected.iter().rev().copied()));
+
+            // Values are iterated in the same order as keys
+            let mut dict = Dict::<K, K>::new();
+            for key in &expected {
+                dict.set(key, key).unwrap();
+            }
+            let values = dict.values().map(Result::unwrap).collect::<Vec<_>>();
+            assert_eq!(values, expected);
+            for (key, value) in dict.raw_keys().zip(dict.raw_values()) {
+                let key = K::load_from(&mut key.unwrap().as_data_slice()).unwrap();
+                assert_eq!(K::load_from(&mut value.unwrap()).unwrap(), key);
+            }
+            for (key, entry) in expected.iter().zip(dict.raw_iter()) {
+                let (_, mut value) = entry.unwrap();
+                assert_eq!(K::load_from(&mut value).unwrap(), *key);
+            }
+
+            // Raw bit order puts negative keys after non-negative ones
+            let (negative, positive): (Vec<_>, Vec<_>) =
+                expected.iter().partition(|key| **key < K::default());
+            let keys = dict.keys().unsigned().map(Result::unwrap);
+            assert!(keys.eq(positive.into_iter().chain(negative)));
+        }
+
+        check::<i8>();
+        check::<i16>();
+        check::<i32>();
+        check::<i64>();
+        check::<i128>();
+    }
+
+    #[test]
+    fn dict_from_iter() {
+        fn check<K>(keys: Vec<K>)
+        where
+            K: Store + DictKey + Clone + std::fmt::Debug,
+        {
+            let mut expected = Dict::<K, u64>::new();
+            for (i, key) in keys.iter().enumerate() {
+                expected.set(key.clone(), i as u64).unwrap();
+            }
+
+            let dict = keys
+                .iter()
+                .cloned()
+                .enumerate()
+                .map(|(i, key)| (key, i as u64))
+                .collect::<Dict<K, u64>>();
+            assert_eq!(dict, expected);
+
+            let mut extended = Dict::<K, u64>::new();
+            extended.extend(keys.into_iter().enumerate().map(|(i, key)| (key, i as u64)));
+            assert_eq!(extended, expected);
+        }
+
+        check::<u32>(vec![]);
+        check::<u32>(vec![123]);
+        check::<u32>(vec![5, 1, 10, 3, 1, 0, u32::MAX, 5]);
+        check::<i32>((-100..100).rev().collect());
+        check::<u8>((0..=255).collect());
+        check::<HashBytes>(
+            (0..50u8)
+                .map(|i| HashBytes([i.wrapping_mul(37); 32]))
+                .collect(),
+        );
+        check::<(i32, HashBytes)>(
+            (0..50u8)
+                .map(|i| (i as i32 % 3 - 1, HashBytes([i.wrapping_mul(13); 32])))
+                .collect(),
+        );
+
+        // Collect from a map
+        let map = (0..1000u32)
+            .map(|i| (i.wrapping_mul(2654435761), i))
+            .collect::<std::collections::BTreeMap<_, _>>();
+        let dict = map.clone().into_iter().collect::<Dict<u32, u32>>();
+        let parsed = dict.iter().collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(parsed, map.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn dict_into_iter() {
+        let mut dict = Dict::<i32, Cell>::new();
+        for i in -10..10 {
+            let value = CellBuilder::build_from(i as u64).unwrap();
+            dict.set(i, value).unwrap();
+        }
+
+        let borrowed = (&dict)
+            .into_iter()
+            .map(|entry| entry.unwrap().0)
+            .collect::<Vec<_>>();
+
+        let keys = dict
+            .clone()
+            .into_iter()
+            .signed()
+            .reversed()
+            .map(|entry| entry.unwrap().0)
+            .collect::<Vec<_>>();
+        assert_eq!(keys, (-10..10).rev().collect::<Vec<_>>());
+
+        let into_keys = move || {
+            dict.into_iter()
+                .map(|entry| {
+                    let (key, value) = entry.unwrap();
+                    assert_eq!(value.parse::<u64>().unwrap(), key as u64);
+                    key
+                })
+                .collect::<Vec<_>>()
+        };
+
+        #[cfg(feature = "sync")]
+        let keys = std::thread::spawn(into_keys).join().unwrap();
+        #[cfg(not(feature = "sync"))]
+        let keys = into_keys();
+
+        assert_eq!(keys, borrowed);
+    }
+
     #[test]
     fn dict_next_prev_unsigned() {
         let mut dict = Dict::<u32, u32>::new();
@@ -1461,9 +2346,27 @@ mod tests {
             assert_eq!(values.next(), None);
         }
 
-        // Unsigned
+        // NOTE: `i32` keys are iterated in the signed order by default,
+        // while before the default was the raw bit order (now `unsigned`).
         compare_iter_values(
             left.iter_union(&right),
+            &[
+                (-4, Some(-4), None),
+                (-3, Some(-3), None),
+                (-2, Some(-2), Some(98)),
+                (-1, Some(-1), Some(99)),
+                (0, Some(0), Some(100)),
+                (1, Some(1), Some(101)),
+                (2, Some(2), Some(102)),
+                (3, Some(3), Some(103)),
+                (4, None, Some(104)),
+                (5, None, Some(105)),
+            ],
+        );
+
+        // Unsigned
+        compare_iter_values(
+            left.iter_union(&right).unsigned(),
             &[
                 (0, Some(0), Some(100)),
                 (1, Some(1), Some(101)),
@@ -1480,7 +2383,7 @@ mod tests {
 
         // Unsigned reversed
         compare_iter_values(
-            left.iter_union(&right).reversed(),
+            left.iter_union(&right).unsigned().reversed(),
             &[
                 (-1, Some(-1), Some(99)),
                 (-2, Some(-2), Some(98)),
@@ -12,6 +12,9 @@ pub enum Error {
     /// Something tried to load a pruned branch cell.
     #[error("pruned branch access")]
     PrunedBranchAccess,
+    /// Something tried to load an absent cell.
+    #[error("absent cell access")]
+    AbsentCellAccess,
     /// Cell contains invalid descriptor or data.
     #[error("invalid cell")]
     InvalidCell,
@@ -44,6 +47,34 @@ pub enum Error {
     Unbalanced,
 }
 
+/// Error type for cell builder capacity related errors.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, thiserror::Error)]
+pub enum CapacityError {
+    /// There were not enough bits capacity in the cell builder.
+    #[error("not enough bits capacity: required {required}, available {available}")]
+    Bits {
+        /// Number of bits to store.
+        required: u16,
+        /// Remaining bits capacity.
+        available: u16,
+    },
+    /// There were not enough refs capacity in the cell builder.
+    #[error("not enough refs capacity: required {required}, available {available}")]
+    Refs {
+        /// Number of references to store.
+        required: u8,
+        /// Remaining references capacity.
+        available: u8,
+    },
+}
+
+impl From<CapacityError> for Error {
+    #[inline]
+    fn from(_: CapacityError) -> Self {
+        Self::CellOverflow
+    }
+}
+
 /// Error type for integer parsing related errors.
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum ParseIntError {
@@ -85,6 +116,9 @@ pub enum ParseAddrError {
     /// Too many address parts.
     #[error("unexpected address part")]
     UnexpectedPart,
+    /// Address string doesn't match any of the allowed formats.
+    #[error("invalid address format")]
+    BadFormat,
 }
 
 /// Error type for block id parsing related errors.
@@ -113,6 +147,57 @@ pub enum ParseBlockIdError {
     UnexpectedPart,
 }
 
+/// Error type for block proof chain verification.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum ProofChainError {
+    /// Failed to parse the proof contents.
+    #[error("invalid proof structure")]
+    InvalidStructure(#[from] Error),
+    /// Proof chain contains no links.
+    #[error("empty proof chain")]
+    EmptyChain,
+    /// Proof is not for the masterchain block.
+    #[error("expected a proof for the masterchain block")]
+    NotMasterchain,
+    /// Merkle proof or block info doesn't match the block id.
+    #[error("proof doesn't match the block id")]
+    BlockIdMismatch,
+    /// Intermediate link is not a key block.
+    #[error("expected a key block")]
+    NotKeyBlock,
+    /// Block doesn't reference the last trusted key block.
+    #[error("unexpected previous key block seqno: expected {expected}, got {actual}")]
+    PrevKeyBlockMismatch {
+        /// Seqno of the last trusted key block.
+        expected: u32,
+        /// Seqno from the block info.
+        actual: u32,
+    },
+    /// Last link is not for the target block.
+    #[error("proof chain doesn't end with the target block")]
+    TargetMismatch,
+    /// Proof has no signatures.
+    #[error("block signatures not found")]
+    SignaturesMissing,
+    /// Block was produced by an unexpected validator subset.
+    #[error("validator set mismatch")]
+    ValidatorSetMismatch,
+    /// Signature of some validator from the subset is invalid.
+    #[error("invalid block signature")]
+    InvalidSignature,
+    /// Signatures weight is not enough for the consensus.
+    #[error("not enough signatures weight: {signed} of {total}")]
+    NotEnoughWeight {
+        /// Total weight of valid signatures.
+        signed: u64,
+        /// Total weight of the validator subset.
+        total: u64,
+    },
+    /// Key block has no config.
+    #[error("key block config not found")]
+    ConfigMissing,
+}
+
 /// Error type for global capability parsing related errors.
 #[derive(Debug, Clone, thiserror::Error)]
 pub enum ParseGlobalCapabilityError {
@@ -0,0 +1,516 @@
+//! Deterministic generators of synthetic cell trees, shard states and blocks.
+//!
+//! All generators are driven by the provided random number generator,
+//! so the same seeded generator always produces the same trees. Use a
+//! portable generator (e.g. `rand_xorshift::XorShiftRng`) to get the same
+//! results on all platforms.
+
+use std::ops::RangeInclusive;
+
+use rand::Rng;
+
+use crate::boc::Boc;
+use crate::cell::*;
+use crate::dict::{AugDict, Dict};
+use crate::error::Error;
+use crate::merkle::MerkleUpdate;
+use crate::models::*;
+use crate::num::{Tokens, VarUint56};
+
+/// Generates a tree of exactly `cell_count` unique cells with random data.
+///
+/// Each cell contains at least 64 random data bits and up to 4 children.
+/// At least one cell is always generated.
+pub fn gen_cell_tree<R: Rng + ?Sized>(rng: &mut R, cell_count: usize) -> Result<Cell, Error> {
+    const MIN_BITS: u16 = 64;
+
+    let mut builder = CellBuilder::new();
+
+    let mut data = [0u8; 128];
+    rng.fill(&mut data[..]);
+    ok!(builder.store_raw(&data, rng.gen_range(MIN_BITS..=MAX_BIT_LEN)));
+
+    let remaining = cell_count.saturating_sub(1);
+    let child_count = remaining.min(MAX_REF_COUNT);
+    if child_count > 0 {
+        // Split the remaining cells between children at random points
+        let mut points = [0; MAX_REF_COUNT + 1];
+        for point in &mut points[1..child_count] {
+            *point = rng.gen_range(0..=remaining - child_count);
+        }
+        points[child_count] = remaining - child_count;
+        points[..=child_count].sort_unstable();
+
+        for window in points[..=child_count].windows(2) {
+            let child = ok!(gen_cell_tree(rng, window[1] - window[0] + 1));
+            ok!(builder.store_reference(child));
+        }
+    }
+
+    builder.build()
+}
+
+/// Synthetic shard state generator.
+#[derive(Debug, Clone)]
+pub struct StateGen {
+    /// Global network id.
+    pub global_id: i32,
+    /// Shard of the generated state. All account addresses are inside it.
+    pub shard_ident: ShardIdent,
+    /// Unix timestamp when the state was created.
+    pub gen_utime: u32,
+    /// Number of accounts.
+    pub accounts: usize,
+    /// Number of distinct contract codes shared between accounts.
+    ///
+    /// Each account gets its own code if zero.
+    pub code_variants: usize,
+    /// Number of cells in the account code.
+    pub code_cells: RangeInclusive<usize>,
+    /// Number of cells in the account data.
+    pub data_cells: RangeInclusive<usize>,
+    /// Account balance in native currency.
+    pub balance: RangeInclusive<u128>,
+}
+
+impl Default for StateGen {
+    fn default() -> Self {
+        Self {
+            global_id: 0,
+            shard_ident: ShardIdent::BASECHAIN,
+            gen_utime: 0,
+            accounts: 1000,
+            code_variants: 10,
+            code_cells: 1..=50,
+            data_cells: 1..=10,
+            balance: 1_000_000_000..=1_000_000_000_000,
+        }
+    }
+}
+
+impl StateGen {
+    /// Logical time span reserved for each account.
+    const LT_STEP: u64 = 1000;
+
+    /// Generates a new shard state.
+    pub fn generate<R: Rng + ?Sized>(&self, rng: &mut R) -> Result<ShardStateUnsplit, Error> {
+        let mut codes = Vec::with_capacity(self.code_variants);
+        for _ in 0..self.code_variants {
+            let cell_count = rng.gen_range(self.code_cells.clone());
+            codes.push(ok!(gen_cell_tree(rng, cell_count)));
+        }
+
+        let workchain = match i8::try_from(self.shard_ident.workchain()) {
+            Ok(workchain) => workchain,
+            Err(_) => return Err(Error::InvalidData),
+        };
+
+        let mut accounts = ShardAccounts::new();
+        let mut lt = 0;
+        for _ in 0..self.accounts {
+            lt += Self::LT_STEP;
+
+            let code = if codes.is_empty() {
+                let cell_count = rng.gen_range(self.code_cells.clone());
+                ok!(gen_cell_tree(rng, cell_count))
+            } else {
+                codes[rng.gen_range(0..codes.len())].clone()
+            };
+            let cell_count = rng.gen_range(self.data_cells.clone());
+            let data = ok!(gen_cell_tree(rng, cell_count));
+
+            let account = Account {
+                address: IntAddr::Std(StdAddr::new(workchain, gen_address(rng, &self.shard_ident))),
+                storage_stat: Default::default(),
+                last_trans_lt: lt,
+                balance: CurrencyCollection::new(rng.gen_range(self.balance.clone())),
+                state: AccountState::Active(StateInit {
+                    code: Some(code),
+                    data: Some(data),
+                    ..Default::default()
+                }),
+                init_code_hash: None,
+            };
+            ok!(set_account(rng, &mut accounts, account, self.gen_utime));
+        }
+
+        Ok(ShardStateUnsplit {
+            global_id: self.global_id,
+            shard_ident: self.shard_ident,
+            seqno: 0,
+            vert_seqno: 0,
+            gen_utime: self.gen_utime,
+            gen_utime_ms: None,
+            gen_lt: lt + Self::LT_STEP,
+            min_ref_mc_seqno: 0,
+            out_msg_queue_info: ok!(empty_out_msg_queue_info()),
+            before_split: false,
+            total_balance: accounts.root_extra().balance.clone(),
+            accounts: ok!(Lazy::new(&accounts)),
+            overload_history: 0,
+            underload_history: 0,
+            total_validator_fees: CurrencyCollection::ZERO,
+            libraries: Dict::new(),
+            master_ref: None,
+            custom: None,
+            shard_block_refs: None,
+        })
+    }
+}
+
+/// Synthetic block generator.
+///
+/// Each block updates the data of several existing accounts
+/// and contains a Merkle update of the shard state.
+///
+/// NOTE: Generated blocks don't contain messages and transactions.
+#[derive(Debug, Clone)]
+pub struct BlockGen {
+    /// Number of accounts updated in each block.
+    pub updated_accounts: RangeInclusive<usize>,
+    /// Number of cells in the new account data.
+    pub data_cells: RangeInclusive<usize>,
+    /// Time between blocks in seconds.
+    pub utime_step: u32,
+    /// Logical time span of each block.
+    pub lt_step: u64,
+}
+
+impl Default for BlockGen {
+    fn default() -> Self {
+        Self {
+            updated_accounts: 1..=100,
+            data_cells: 1..=10,
+            utime_step: 3,
+            lt_step: 1_000_000,
+        }
+    }
+}
+
+impl BlockGen {
+    /// Generates a block on top of the specified shard state.
+    ///
+    /// `prev_block` is a reference to the block which produced `prev_state`.
+    pub fn generate<R: Rng + ?Sized>(
+        &self,
+        rng: &mut R,
+        prev_block: &BlockRef,
+        prev_state: &Cell,
+    ) -> Result<GeneratedBlock, Error> {
+        let mut state = ok!(prev_state.parse::<ShardStateUnsplit>());
+        let mut accounts = ok!(state.load_accounts());
+
+        let start_lt = state.gen_lt + 1;
+        let end_lt = start_lt + self.lt_step;
+        let gen_utime = state.gen_utime.saturating_add(self.utime_step);
+
+        if !accounts.is_empty() {
+            let updated_accounts = rng.gen_range(self.updated_accounts.clone());
+            let lt_step = std::cmp::max(self.lt_step / (updated_accounts as u64 + 1), 1);
+
+            let mut lt = start_lt;
+            for _ in 0..updated_accounts {
+                let key = gen_address(rng, &state.shard_ident);
+                let Some(shard_account) = ok!(find_account(&accounts, &key)) else {
+                    break;
+                };
+                let Some(mut account) = ok!(shard_account.load_account()) else {
+                    continue;
+                };
+
+                lt = std::cmp::min(lt + lt_step, end_lt);
+                let cell_count = rng.gen_range(self.data_cells.clone());
+                let data = ok!(gen_cell_tree(rng, cell_count));
+                if let AccountState::Active(state_init) = &mut account.state {
+                    state_init.data = Some(data);
+                }
+                account.last_trans_lt = lt;
+                ok!(set_account(rng, &mut accounts, account, gen_utime));
+            }
+        }
+
+        let prev_total_balance = state.total_balance.clone();
+        state.seqno += 1;
+        state.gen_utime = gen_utime;
+        state.gen_lt = end_lt;
+        state.total_balance = accounts.root_extra().balance.clone();
+        state.accounts = ok!(Lazy::new(&accounts));
+        let new_state = ok!(CellBuilder::build_from(&state));
+
+        let state_update =
+            ok!(MerkleUpdate::create_diff(prev_state.as_ref(), new_state.as_ref()).build());
+
+        let info = BlockInfo {
+            version: 0,
+            after_merge: false,
+            before_split: false,
+            after_split: false,
+            want_split: false,
+            want_merge: false,
+            key_block: false,
+            flags: 0,
+            seqno: state.seqno,
+            vert_seqno: state.vert_seqno,
+            shard: state.shard_ident,
+            gen_utime,
+            gen_utime_ms: None,
+            start_lt,
+            end_lt,
+            gen_validator_list_hash_short: 0,
+            gen_catchain_seqno: 0,
+            min_ref_mc_seqno: state.min_ref_mc_seqno,
+            prev_key_block_seqno: 0,
+            gen_software: Default::default(),
+            master_ref: None,
+            prev_ref: ok!(CellBuilder::build_from(PrevBlockRef::Single(
+                prev_block.clone()
+            ))),
+            prev_vert_ref: None,
+        };
+
+        let value_flow = ValueFlow {
+            from_prev_block: prev_total_balance,
+            to_next_block: state.total_balance.clone(),
+            imported: CurrencyCollection::ZERO,
+            exported: CurrencyCollection::ZERO,
+            fees_collected: CurrencyCollection::ZERO,
+            fees_imported: CurrencyCollection::ZERO,
+            recovered: CurrencyCollection::ZERO,
+            created: CurrencyCollection::ZERO,
+            minted: CurrencyCollection::ZERO,
+            copyleft_rewards: Dict::new(),
+        };
+
+        let extra = BlockExtra {
+            // ahme_empty$0 extra:ImportFees
+            in_msg_description: ok!(CellBuilder::build_from((
+                false,
+                Tokens::ZERO,
+                CurrencyCollection::ZERO
+            ))),
+            // ahme_empty$0 extra:CurrencyCollection
+            out_msg_description: ok!(CellBuilder::build_from((false, CurrencyCollection::ZERO))),
+            account_blocks: ok!(Lazy::new(&AugDict::new())),
+            rand_seed: rng.gen(),
+            created_by: rng.gen(),
+            custom: None,
+            shard_block_refs: None,
+        };
+
+        let block = ok!(CellBuilder::build_from(Block {
+            global_id: state.global_id,
+            info: ok!(Lazy::new(&info)),
+            value_flow: ok!(Lazy::new(&value_flow)),
+            state_update: ok!(Lazy::new(&state_update)),
+            out_msg_queue_updates: None,
+            extra: ok!(Lazy::new(&extra)),
+        }));
+
+        Ok(GeneratedBlock {
+            block,
+            state: new_state,
+        })
+    }
+}
+
+/// Generated block with the resulting shard state.
+#[derive(Debug, Clone)]
+pub struct GeneratedBlock {
+    /// Root cell of the block.
+    pub block: Cell,
+    /// Root cell of the shard state after this block.
+    pub state: Cell,
+}
+
+impl GeneratedBlock {
+    /// Returns a reference to this block which can be used to generate the next one.
+    pub fn block_ref(&self) -> Result<BlockRef, Error> {
+        let block = ok!(self.block.parse::<Block>());
+        let info = ok!(block.info.load());
+        Ok(BlockRef {
+            end_lt: info.end_lt,
+            seqno: info.seqno,
+            root_hash: *self.block.repr_hash(),
+            file_hash: Boc::encode_with_file_hash(self.block.as_ref()).1,
+        })
+    }
+}
+
+fn gen_address<R: Rng + ?Sized>(rng: &mut R, shard_ident: &ShardIdent) -> HashBytes {
+    let mut address: HashBytes = rng.gen();
+
+    let prefix_len = shard_ident.prefix_len();
+    if prefix_len > 0 {
+        let mask = u64::MAX << (64 - prefix_len);
+        let prefix = u64::from_be_bytes(address.0[..8].try_into().unwrap());
+        let prefix = (shard_ident.prefix() & mask) | (prefix & !mask);
+        address.0[..8].copy_from_slice(&prefix.to_be_bytes());
+    }
+
+    address
+}
+
+/// Returns the first account with an id greater than or equal to the key
+/// (or the first account if there is no such account).
+fn find_account(accounts: &ShardAccounts, key: &HashBytes) -> Result<Option<ShardAccount>, Error> {
+    let dict = accounts.dict();
+    let entry = match ok!(dict.get_or_next(key, false)) {
+        Some(entry) => Some(entry),
+        None => ok!(dict.get_min(false)),
+    };
+    Ok(entry.map(|(_, (_, shard_account))| shard_account))
+}
+
+fn set_account<R: Rng + ?Sized>(
+    rng: &mut R,
+    accounts: &mut ShardAccounts,
+    mut account: Account,
+    last_paid: u32,
+) -> Result<(), Error> {
+    let stats = match &account.state {
+        AccountState::Active(state_init) => {
+            let cell = ok!(CellBuilder::build_from(state_init));
+            cell.compute_unique_stats(usize::MAX).unwrap_or_default()
+        }
+        _ => Default::default(),
+    };
+    account.storage_stat = StorageInfo {
+        used: StorageUsed {
+            cells: VarUint56::new(stats.cell_count + 1),
+            bits: VarUint56::new(stats.bit_count),
+            public_cells: VarUint56::ZERO,
+        },
+        last_paid,
+        due_payment: None,
+    };
+
+    let key = match &account.address {
+        IntAddr::Std(addr) => addr.address,
+        IntAddr::Var(_) => return Err(Error::InvalidData),
+    };
+    let info = DepthBalanceInfo {
+        split_depth: 0,
+        balance: account.balance.clone(),
+    };
+    let shard_account = ShardAccount {
+        last_trans_hash: rng.gen(),
+        last_trans_lt: account.last_trans_lt,
+        account: ok!(Lazy::new(&OptionalAccount(Some(account)))),
+    };
+    accounts.set(key, info, shard_account).map(|_| ())
+}
+
+fn empty_out_msg_queue_info() -> Result<Cell, Error> {
+    let mut builder = CellBuilder::new();
+    // out_queue:(HashmapAugE 352 EnqueuedMsg uint64)
+    ok!(builder.store_bit_zero());
+    ok!(builder.store_u64(0));
+    // proc_info:(HashmapE 96 ProcessedUpto)
+    ok!(builder.store_bit_zero());
+    // ihr_pending:(HashmapE 320 IhrPendingSince)
+    ok!(builder.store_bit_zero());
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    #[test]
+    fn deterministic_generators() {
+        let state_gen = StateGen {
+            accounts: 100,
+            ..Default::default()
+        };
+        let block_gen = BlockGen {
+            updated_accounts: 1..=10,
+            ..Default::default()
+        };
+
+        let generate = |seed: u64| {
+            let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(seed);
+
+            let zerostate = state_gen.generate(&mut rng).unwrap();
+            let zerostate = CellBuilder::build_from(&zerostate).unwrap();
+            let mut block_ref = BlockRef {
+                end_lt: 0,
+                seqno: 0,
+                root_hash: *zerostate.repr_hash(),
+                file_hash: HashBytes::ZERO,
+            };
+
+            let mut state = zerostate.clone();
+            let mut blocks = Vec::new();
+            for _ in 0..5 {
+                let block = block_gen.generate(&mut rng, &block_ref, &state).unwrap();
+                block_ref = block.block_ref().unwrap();
+                state = block.state.clone();
+                blocks.push(block);
+            }
+            (zerostate, blocks)
+        };
+
+        let hashes = |blocks: &[GeneratedBlock]| {
+            blocks
+                .iter()
+                .map(|b| *b.block.repr_hash())
+                .collect::<Vec<_>>()
+        };
+
+        let (zerostate, blocks) = generate(123);
+        assert_eq!(zerostate, generate(123).0);
+        assert_eq!(hashes(&blocks), hashes(&generate(123).1));
+        assert_ne!(zerostate, generate(321).0);
+
+        let mut prev_state = zerostate;
+        for (i, generated) in blocks.iter().enumerate() {
+            let block = generated.block.parse::<Block>().unwrap();
+            let info = block.load_info().unwrap();
+            assert_eq!(info.seqno, i as u32 + 1);
+
+            let state = generated.state.parse::<ShardStateUnsplit>().unwrap();
+            assert_eq!(state.seqno, info.seqno);
+            assert_eq!(state.gen_lt, info.end_lt);
+
+            let accounts = state.load_accounts().unwrap();
+            assert_eq!(accounts.iter().count(), 100);
+            assert_eq!(accounts.root_extra().balance, state.total_balance);
+
+            // State update is applicable to the previous state
+            let update = block.load_state_update().unwrap();
+            let new_state = update.apply(&prev_state).unwrap();
+            assert_eq!(new_state.repr_hash(), generated.state.repr_hash());
+
+            // Only changed cells are included
+            let stats = |cell: &Cell| cell.compute_unique_stats(usize::MAX).unwrap().cell_count;
+            assert!(stats(&update.old) < stats(&prev_state) / 2);
+            prev_state = generated.state.clone();
+        }
+    }
+
+    #[test]
+    fn workchain_out_of_range() {
+        let state_gen = StateGen {
+            shard_ident: ShardIdent::new_full(1000),
+            accounts: 1,
+            ..Default::default()
+        };
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(0);
+        assert_eq!(
+            state_gen.generate(&mut rng).unwrap_err(),
+            Error::InvalidData
+        );
+    }
+
+    #[test]
+    fn cell_tree_size() {
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(0);
+        for cell_count in [0, 1, 2, 5, 100, 1000] {
+            let cell = gen_cell_tree(&mut rng, cell_count).unwrap();
+            let stats = cell.compute_unique_stats(usize::MAX).unwrap();
+            assert_eq!(stats.cell_count, cell_count.max(1) as u64);
+        }
+    }
+}
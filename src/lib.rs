@@ -26,6 +26,12 @@
 //! TVM execution with gas tracking), so [`CellBuilder::build_ext`] accepts
 //! a [`CellContext`] parameter which can be used to track and modify cells creation.
 //!
+//! There is a single [`Cell`] type backed by an [`Rc`] or, with the `sync`
+//! feature (enabled by default), by an [`Arc`]. All models and helpers use it
+//! directly, so code doesn't need to be generic over the cell family. Custom
+//! cell implementations can be wrapped with [`Cell::from_impl`], and
+//! [`CellContainer`] is the matching shared pointer type.
+//!
 //! ## BOC
 //!
 //! BOC (Bag Of Cells) is a format for representing a tree of cells as bytes.
@@ -88,8 +94,8 @@
 //! [`Cell`]: cell::Cell
 //! [`Rc`]: std::rc::Rc
 //! [`Arc`]: std::sync::Arc
-//! [`RcCell`]: prelude::RcCell
-//! [`ArcCell`]: prelude::ArcCell
+//! [`CellContainer`]: cell::CellContainer
+//! [`Cell::from_impl`]: cell::Cell::from_impl
 //! [`CellSlice`]: cell::CellSlice
 //! [`CellBuilder`]: cell::CellBuilder
 //! [`Cell::as_slice`]: cell::CellImpl::as_slice
@@ -127,6 +133,21 @@ macro_rules! assert_impl_all {
     };
 }
 
+/// Increments a counter through the `metrics` facade (if the feature is enabled).
+#[cfg(feature = "metrics")]
+macro_rules! inc_counter {
+    ($name:literal $(, $key:literal => $value:literal)* $(; $amount:expr)?) => {
+        ::metrics::counter!($name $(, $key => $value)*).increment(inc_counter!(@amount $($amount)?))
+    };
+    (@amount) => { 1 };
+    (@amount $amount:expr) => { $amount as u64 };
+}
+
+#[cfg(not(feature = "metrics"))]
+macro_rules! inc_counter {
+    ($($tt:tt)*) => {};
+}
+
 extern crate self as everscale_types;
 
 pub mod boc;
@@ -137,12 +158,21 @@ pub mod num;
 pub mod prelude;
 pub mod util;
 
+#[cfg(feature = "models")]
+pub mod crypto;
+
 #[cfg(feature = "models")]
 pub mod models;
 
 #[cfg(feature = "abi")]
 pub mod abi;
 
+#[cfg(all(feature = "models", feature = "rand"))]
+pub mod gen;
+
+#[cfg(feature = "testdata")]
+pub mod testdata;
+
 pub mod error;
 
 #[cfg(test)]
@@ -154,3 +154,44 @@ fn proof_with_subtree() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(feature = "models")]
+#[test]
+fn proof_for_account_state() {
+    use crate::models::ShardStateUnsplit;
+
+    let root = Boc::decode(include_bytes!("../../models/shard/tests/new_zerostate.boc")).unwrap();
+    let elector = HashBytes([0x33; 32]);
+
+    // Visit the account through the tracked state
+    let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+    let tracked = usage_tree.track(&root);
+    let state = tracked.parse::<ShardStateUnsplit>().unwrap();
+    let expected = state
+        .load_accounts()
+        .unwrap()
+        .get(elector)
+        .unwrap()
+        .unwrap();
+
+    // Build the proof from the visited cells
+    let proof = MerkleProof::create(root.as_ref(), usage_tree)
+        .build()
+        .unwrap();
+    assert_eq!(proof.hash, *root.repr_hash());
+
+    let virtual_root = proof.cell.as_ref().virtualize();
+    assert_eq!(virtual_root.repr_hash(), root.repr_hash());
+
+    let state = virtual_root.parse::<ShardStateUnsplit>().unwrap();
+    let accounts = state.load_accounts().unwrap();
+    let account = accounts.get(elector).unwrap().unwrap();
+    assert_eq!(
+        account.account.inner().repr_hash(),
+        expected.account.inner().repr_hash()
+    );
+    assert_eq!(account.last_trans_lt, expected.last_trans_lt);
+
+    // Other accounts are pruned
+    assert!(accounts.get(HashBytes([0x55; 32])).is_err());
+}
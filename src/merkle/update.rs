@@ -115,6 +115,21 @@ impl MerkleUpdate {
         MerkleUpdateBuilder::new(old, new, f)
     }
 
+    /// Starts building a Merkle update between the specified cells,
+    /// finding changed cells by walking both trees.
+    ///
+    /// Trees are walked level by level, and subtrees which are found
+    /// in both trees are not visited further. Unchanged subtrees at the same
+    /// depth are always pruned, but a subtree which is moved to another depth
+    /// may be partially expanded before it is found in the other tree,
+    /// so the update is valid but not necessarily minimal.
+    pub fn create_diff<'a>(
+        old: &'a DynCell,
+        new: &'a DynCell,
+    ) -> MerkleUpdateBuilder<'a, ahash::HashSet<&'a HashBytes>> {
+        MerkleUpdateBuilder::new(old, new, find_old_cells(old, new))
+    }
+
     /// Tries to apply this Merkle update to the specified cell,
     /// producing a new cell and using an empty cell context.
     pub fn apply(&self, old: &Cell) -> Result<Cell, Error> {
@@ -616,6 +631,48 @@ impl<'a: 'b, 'b> BuilderImpl<'a, 'b> {
     }
 }
 
+/// Returns the old cells which are visited while looking for subtrees
+/// reused in the new tree (a superset of the cells required for a Merkle update).
+fn find_old_cells<'a>(old: &'a DynCell, new: &'a DynCell) -> ahash::HashSet<&'a HashBytes> {
+    let mut old_seen = ahash::HashSet::default();
+    let mut new_seen = ahash::HashSet::default();
+    old_seen.insert(old.repr_hash());
+    new_seen.insert(new.repr_hash());
+
+    let mut old_level = vec![old];
+    let mut new_level = vec![new];
+    while !old_level.is_empty() || !new_level.is_empty() {
+        // NOTE: Cells which were found in the other tree are not expanded
+        let mut next = Vec::new();
+        for cell in old_level {
+            if new_seen.contains(cell.repr_hash()) {
+                continue;
+            }
+            for child in cell.references() {
+                if old_seen.insert(child.repr_hash()) {
+                    next.push(child);
+                }
+            }
+        }
+        old_level = next;
+
+        let mut next = Vec::new();
+        for cell in new_level {
+            if old_seen.contains(cell.repr_hash()) {
+                continue;
+            }
+            for child in cell.references() {
+                if new_seen.insert(child.repr_hash()) {
+                    next.push(child);
+                }
+            }
+        }
+        new_level = next;
+    }
+
+    old_seen
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -693,6 +750,53 @@ mod tests {
         assert_eq!(after_apply.as_ref(), new_dict_cell.as_ref());
     }
 
+    #[test]
+    fn dict_merkle_update_diff() {
+        let mut dict = Dict::<u32, u32>::new();
+        for i in 0..100 {
+            dict.add(i, i * 10).unwrap();
+        }
+        let old_dict_cell = CellBuilder::build_from(&dict).unwrap();
+
+        // Same trees
+        let merkle_update =
+            MerkleUpdate::create_diff(old_dict_cell.as_ref(), old_dict_cell.as_ref())
+                .build()
+                .unwrap();
+        let after_apply = merkle_update.apply(&old_dict_cell).unwrap();
+        assert_eq!(after_apply.as_ref(), old_dict_cell.as_ref());
+
+        // Changed trees
+        dict.set(0, 1).unwrap();
+        dict.remove(50).unwrap();
+        dict.add(1000, 1).unwrap();
+        let new_dict_cell = CellBuilder::build_from(&dict).unwrap();
+
+        let merkle_update =
+            MerkleUpdate::create_diff(old_dict_cell.as_ref(), new_dict_cell.as_ref())
+                .build()
+                .unwrap();
+        let after_apply = merkle_update.apply(&old_dict_cell).unwrap();
+        assert_eq!(after_apply.as_ref(), new_dict_cell.as_ref());
+
+        let full_update = MerkleUpdate::create(
+            old_dict_cell.as_ref(),
+            new_dict_cell.as_ref(),
+            visit_all_cells(&old_dict_cell),
+        )
+        .build()
+        .unwrap();
+        assert_eq!(merkle_update, full_update);
+
+        // Completely different trees
+        let other_cell = CellBuilder::build_from((123u32, 456u64)).unwrap();
+        let merkle_update = MerkleUpdate::create_diff(old_dict_cell.as_ref(), other_cell.as_ref())
+            .build()
+            .unwrap();
+        let after_apply = merkle_update.apply(&old_dict_cell).unwrap();
+        assert_eq!(after_apply.as_ref(), other_cell.as_ref());
+    }
+
     #[test]
     fn dict_removed_cells_diff() {
         // Create dict with keys 0..10
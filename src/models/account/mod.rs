@@ -5,9 +5,15 @@ use crate::dict::*;
 use crate::error::*;
 use crate::num::*;
 
+use crate::merkle::MerkleProof;
+use crate::models::config::StoragePrices;
 use crate::models::currency::CurrencyCollection;
-use crate::models::message::IntAddr;
-use crate::models::Lazy;
+use crate::models::message::{IntAddr, StdAddr};
+use crate::models::shard::ShardStateUnsplit;
+use crate::models::{FormatVersion, Lazy};
+
+#[cfg(test)]
+mod tests;
 
 /// Amount of unique cells and bits for shard states.
 #[derive(Debug, Default, Clone, Eq, PartialEq, Store, Load)]
@@ -56,7 +62,7 @@ impl StorageUsed {
             public_cells: Default::default(),
         };
 
-        if res.cells.is_valid() || !res.bits.is_valid() {
+        if !res.cells.is_valid() || !res.bits.is_valid() {
             return Err(Error::IntOverflow);
         }
 
@@ -94,6 +100,47 @@ pub struct StorageInfo {
     pub due_payment: Option<Tokens>,
 }
 
+impl StorageInfo {
+    /// Computes the total storage debt of the account at the specified time.
+    ///
+    /// The result is the accumulated [`due_payment`] plus the fee for storing
+    /// the account state since [`last_paid`] up to `now`, rounded up to the nearest
+    /// nanotoken. Nothing is charged for accounts with zero `last_paid`.
+    ///
+    /// [`due_payment`]: StorageInfo::due_payment
+    /// [`last_paid`]: StorageInfo::last_paid
+    pub fn compute_fee(&self, prices: &StoragePrices, now: u32, is_masterchain: bool) -> Tokens {
+        let (bit_price, cell_price) = if is_masterchain {
+            (prices.mc_bit_price_ps, prices.mc_cell_price_ps)
+        } else {
+            (prices.bit_price_ps, prices.cell_price_ps)
+        };
+
+        let delta = if self.last_paid == 0 {
+            0
+        } else {
+            now.saturating_sub(self.last_paid)
+        };
+
+        let bits = (self.used.bits.into_inner() as u128).saturating_mul(bit_price as u128);
+        let cells = (self.used.cells.into_inner() as u128).saturating_mul(cell_price as u128);
+        let fee = bits
+            .saturating_add(cells)
+            .saturating_mul(delta as u128)
+            .saturating_add(0xffff)
+            >> 16;
+
+        let due_payment = match self.due_payment {
+            Some(due_payment) => due_payment.into_inner(),
+            None => 0,
+        };
+        Tokens::new(std::cmp::min(
+            due_payment.saturating_add(fee),
+            Tokens::MAX.into_inner(),
+        ))
+    }
+}
+
 /// Brief account status.
 #[derive(Debug, Clone, Copy, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -158,6 +205,118 @@ impl ShardAccount {
         let OptionalAccount(account) = ok!(self.account.load());
         Ok(account)
     }
+
+    /// Returns the code hash of the active account without parsing it.
+    ///
+    /// See [`OptionalAccount::peek_code_hash`].
+    pub fn code_hash(&self) -> Result<Option<HashBytes>, Error> {
+        OptionalAccount::peek_code_hash(&ok!(self.account.cell.as_slice()))
+    }
+}
+
+/// Account state envelope for persistent storage.
+///
+/// Unlike [`ShardAccount`], it is not tied to the shard accounts dictionary
+/// and can be stored separately with an optional proof of the state.
+///
+/// ```text
+/// stored_account_v1#01 account:^OptionalAccount last_trans_hash:bits256
+///     last_trans_lt:uint64 proof:(Maybe ^MerkleProof) = StoredAccount;
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StoredAccount {
+    /// Optional reference to account state.
+    pub account: Lazy<OptionalAccount>,
+    /// The exact hash of the last transaction.
+    pub last_trans_hash: HashBytes,
+    /// The exact logical time of the last transaction.
+    pub last_trans_lt: u64,
+    /// Merkle proof of the account in the shard state.
+    pub proof: Option<Lazy<MerkleProof>>,
+}
+
+impl StoredAccount {
+    const TAG_V1: u8 = 0x01;
+
+    /// Tries to load account data.
+    pub fn load_account(&self) -> Result<Option<Account>, Error> {
+        let OptionalAccount(account) = ok!(self.account.load());
+        Ok(account)
+    }
+
+    /// Returns the shard accounts entry for this account.
+    pub fn to_shard_account(&self) -> ShardAccount {
+        ShardAccount {
+            account: self.account.clone(),
+            last_trans_hash: self.last_trans_hash,
+            last_trans_lt: self.last_trans_lt,
+        }
+    }
+
+    /// Checks that the proof contains exactly this account state
+    /// at the specified address.
+    ///
+    /// Returns the representation hash of the proven shard state.
+    pub fn check_proof(&self, address: &HashBytes) -> Result<HashBytes, Error> {
+        let Some(proof) = &self.proof else {
+            return Err(Error::InvalidData);
+        };
+        let proof = ok!(proof.load());
+
+        let state = ok!(proof
+            .cell
+            .as_ref()
+            .virtualize()
+            .parse::<ShardStateUnsplit>());
+        let accounts = ok!(state.load_accounts());
+        match ok!(accounts.get(address)) {
+            Some(entry) if entry == self.to_shard_account() => Ok(proof.hash),
+            _ => Err(Error::InvalidData),
+        }
+    }
+}
+
+impl From<ShardAccount> for StoredAccount {
+    #[inline]
+    fn from(value: ShardAccount) -> Self {
+        Self {
+            account: value.account,
+            last_trans_hash: value.last_trans_hash,
+            last_trans_lt: value.last_trans_lt,
+            proof: None,
+        }
+    }
+}
+
+impl Store for StoredAccount {
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
+        ok!(builder.store_u8(Self::TAG_V1));
+        ok!(self.account.store_into(builder, context));
+        ok!(builder.store_u256(&self.last_trans_hash));
+        ok!(builder.store_u64(self.last_trans_lt));
+        self.proof.store_into(builder, context)
+    }
+}
+
+impl<'a> Load<'a> for StoredAccount {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        match slice.load_u8() {
+            Ok(Self::TAG_V1) => {}
+            Ok(_) => return Err(Error::InvalidTag),
+            Err(e) => return Err(e),
+        }
+
+        Ok(Self {
+            account: ok!(Lazy::load_from(slice)),
+            last_trans_hash: ok!(slice.load_u256()),
+            last_trans_lt: ok!(slice.load_u64()),
+            proof: ok!(Option::<Lazy<MerkleProof>>::load_from(slice)),
+        })
+    }
 }
 
 /// A wrapper for `Option<Account>` with customized representation.
@@ -203,6 +362,79 @@ impl OptionalAccount {
     }
 }
 
+impl OptionalAccount {
+    /// Returns the layout of the serialized account without parsing it.
+    ///
+    /// - [`FormatVersion::V1`] - the original layout without `init_code_hash`;
+    /// - [`FormatVersion::V2`] - the layout with optional `init_code_hash`.
+    ///
+    /// Returns `None` for non-existing accounts.
+    pub fn peek_format_version(slice: &CellSlice<'_>) -> Result<Option<FormatVersion>, Error> {
+        if ok!(slice.get_bit(0)) {
+            return Ok(Some(FormatVersion::V1));
+        } else if slice.remaining_bits() == 1 {
+            return Ok(None);
+        }
+
+        match ok!(slice.get_small_uint(1, 3)) {
+            0 => Ok(Some(FormatVersion::V1)),
+            1 => Ok(Some(FormatVersion::V2)),
+            _ => Err(Error::InvalidData),
+        }
+    }
+
+    /// Reads the code hash of the serialized account without parsing it.
+    ///
+    /// Only the fields before the code are skipped, so neither the extra
+    /// currencies nor the code itself are loaded.
+    ///
+    /// Returns `None` for non-existing, uninit and frozen accounts,
+    /// and for active accounts without code.
+    pub fn peek_code_hash(slice: &CellSlice<'_>) -> Result<Option<HashBytes>, Error> {
+        let mut slice = *slice;
+        if !ok!(slice.load_bit()) {
+            if slice.is_data_empty() {
+                return Ok(None);
+            } else if ok!(slice.load_small_uint(3)) > 1 {
+                return Err(Error::InvalidData);
+            }
+        }
+
+        ok!(slice.skip_value::<IntAddr>());
+        ok!(slice.skip_value::<StorageInfo>());
+        // last_trans_lt:uint64
+        ok!(slice.advance(64, 0));
+
+        // balance:CurrencyCollection
+        ok!(slice.skip_value::<Tokens>());
+        if ok!(slice.load_bit()) {
+            ok!(slice.advance(0, 1));
+        }
+
+        // Only active accounts have code
+        if !ok!(slice.load_bit()) {
+            return Ok(None);
+        }
+
+        // split_depth:(Maybe (## 5)) special:(Maybe TickTock)
+        if ok!(slice.load_bit()) {
+            ok!(slice.advance(5, 0));
+        }
+        if ok!(slice.load_bit()) {
+            ok!(slice.advance(SpecialFlags::BITS, 0));
+        }
+
+        // code:(Maybe ^Cell)
+        if !ok!(slice.load_bit()) {
+            return Ok(None);
+        }
+        match slice.load_reference() {
+            Ok(code) => Ok(Some(*code.repr_hash())),
+            Err(e) => Err(e),
+        }
+    }
+}
+
 impl AsRef<Option<Account>> for OptionalAccount {
     #[inline]
     fn as_ref(&self) -> &Option<Account> {
@@ -297,6 +529,138 @@ pub struct Account {
     pub init_code_hash: Option<HashBytes>,
 }
 
+impl Account {
+    /// Returns the code hash if the account is active and has code.
+    pub fn code_hash(&self) -> Option<HashBytes> {
+        self.state.code_hash()
+    }
+
+    /// Computes the difference between two account states.
+    pub fn diff(old: &Self, new: &Self) -> AccountDiff {
+        fn storage_delta(old: &VarUint56, new: &VarUint56) -> i64 {
+            (new.into_inner() as i64).wrapping_sub(old.into_inner() as i64)
+        }
+
+        let old_used = &old.storage_stat.used;
+        let new_used = &new.storage_stat.used;
+
+        AccountDiff {
+            balance_delta: (new.balance.tokens.into_inner() as i128)
+                .wrapping_sub(old.balance.tokens.into_inner() as i128),
+            other_balance_changed: old.balance.other != new.balance.other,
+            old_status: old.state.status(),
+            new_status: new.state.status(),
+            old_code_hash: old.state.code_hash(),
+            new_code_hash: new.state.code_hash(),
+            old_data_hash: old.state.data_hash(),
+            new_data_hash: new.state.data_hash(),
+            cells_delta: storage_delta(&old_used.cells, &new_used.cells),
+            bits_delta: storage_delta(&old_used.bits, &new_used.bits),
+        }
+    }
+
+    /// Replaces the code of the active account as the `SETCODE` action does.
+    ///
+    /// Data and libraries are preserved. If `record_init_code_hash` is set and
+    /// the account has no init code hash yet, the hash of the replaced code
+    /// is remembered as the init code hash.
+    ///
+    /// The used storage of the account is recomputed for the new state
+    /// (the number of public cells is preserved).
+    ///
+    /// Returns a hash of the new state, or an error if the account is not active.
+    pub fn apply_set_code(
+        &mut self,
+        code: Cell,
+        record_init_code_hash: bool,
+    ) -> Result<HashBytes, Error> {
+        let AccountState::Active(state) = &mut self.state else {
+            return Err(Error::InvalidData);
+        };
+
+        if record_init_code_hash && self.init_code_hash.is_none() {
+            self.init_code_hash = state.code.as_ref().map(|code| *code.repr_hash());
+        }
+
+        state.code = Some(code);
+        let hash = ok!(state.compute_hash());
+
+        let public_cells = self.storage_stat.used.public_cells;
+        self.storage_stat.used = ok!(StorageUsed::compute(self, usize::MAX));
+        self.storage_stat.used.public_cells = public_cells;
+
+        Ok(hash)
+    }
+}
+
+/// Difference between two account states.
+///
+/// See [`Account::diff`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct AccountDiff {
+    /// Native currency balance change.
+    pub balance_delta: i128,
+    /// Whether extra currencies balance has changed.
+    pub other_balance_changed: bool,
+    /// Account status before.
+    pub old_status: AccountStatus,
+    /// Account status after.
+    pub new_status: AccountStatus,
+    /// Code hash before (if account was active).
+    pub old_code_hash: Option<HashBytes>,
+    /// Code hash after (if account is active).
+    pub new_code_hash: Option<HashBytes>,
+    /// Data hash before (if account was active).
+    pub old_data_hash: Option<HashBytes>,
+    /// Data hash after (if account is active).
+    pub new_data_hash: Option<HashBytes>,
+    /// Change in the amount of unique cells.
+    pub cells_delta: i64,
+    /// Change in the total number of bits in unique cells.
+    pub bits_delta: i64,
+}
+
+impl AccountDiff {
+    /// Returns `true` if account status has changed.
+    #[inline]
+    pub fn status_changed(&self) -> bool {
+        self.old_status != self.new_status
+    }
+
+    /// Returns `true` if account code has changed.
+    #[inline]
+    pub fn code_changed(&self) -> bool {
+        self.old_code_hash != self.new_code_hash
+    }
+
+    /// Returns `true` if account data has changed.
+    #[inline]
+    pub fn data_changed(&self) -> bool {
+        self.old_data_hash != self.new_data_hash
+    }
+
+    /// Returns `true` if balance has changed in any currency.
+    #[inline]
+    pub fn balance_changed(&self) -> bool {
+        self.balance_delta != 0 || self.other_balance_changed
+    }
+
+    /// Returns `true` if storage usage has changed.
+    #[inline]
+    pub fn storage_changed(&self) -> bool {
+        self.cells_delta != 0 || self.bits_delta != 0
+    }
+
+    /// Returns `true` if there are no tracked changes.
+    pub fn is_empty(&self) -> bool {
+        !(self.balance_changed()
+            || self.status_changed()
+            || self.code_changed()
+            || self.data_changed()
+            || self.storage_changed())
+    }
+}
+
 /// State of an existing account.
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -318,6 +682,60 @@ impl AccountState {
             Self::Frozen(_) => AccountStatus::Frozen,
         }
     }
+
+    /// Returns a representation hash of the code (if the account is active).
+    pub fn code_hash(&self) -> Option<HashBytes> {
+        match self {
+            Self::Active(state) => state.code.as_ref().map(|code| *code.repr_hash()),
+            _ => None,
+        }
+    }
+
+    /// Returns a representation hash of the data (if the account is active).
+    pub fn data_hash(&self) -> Option<HashBytes> {
+        match self {
+            Self::Active(state) => state.data.as_ref().map(|data| *data.repr_hash()),
+            _ => None,
+        }
+    }
+
+    /// Returns a hash of the last known state (if the account is frozen).
+    pub fn frozen_hash(&self) -> Option<&HashBytes> {
+        match self {
+            Self::Frozen(hash) => Some(hash),
+            _ => None,
+        }
+    }
+
+    /// Returns `true` if the account is frozen with the hash of the specified state.
+    pub fn is_frozen_with(&self, state_init: &StateInit) -> Result<bool, Error> {
+        match self {
+            Self::Frozen(hash) => Ok(*hash == ok!(state_init.compute_hash())),
+            _ => Ok(false),
+        }
+    }
+
+    /// Returns a frozen state for the active account.
+    ///
+    /// Uninit and frozen states are returned as is.
+    pub fn freeze(&self) -> Result<Self, Error> {
+        match self {
+            Self::Active(state) => Ok(Self::Frozen(ok!(state.compute_hash()))),
+            _ => Ok(self.clone()),
+        }
+    }
+
+    /// Returns an active state restored from the proof.
+    ///
+    /// Fails if the account is not frozen or the proof doesn't match the frozen hash.
+    pub fn unfreeze(&self, proof: &UnfreezeProof) -> Result<Self, Error> {
+        match self {
+            Self::Frozen(hash) if *hash == proof.frozen_hash && proof.is_valid() => {
+                Ok(Self::Active(ok!(proof.state_init.load())))
+            }
+            _ => Err(Error::InvalidData),
+        }
+    }
 }
 
 impl Store for AccountState {
@@ -397,6 +815,32 @@ impl StateInit {
         }
     }
 
+    /// Computes a representation hash of the serialized state.
+    ///
+    /// This is the hash stored in [`AccountState::Frozen`].
+    pub fn compute_hash(&self) -> Result<HashBytes, Error> {
+        let cell = ok!(CellBuilder::build_from(self));
+        Ok(*cell.repr_hash())
+    }
+
+    /// Computes an address of the contract with this state in the specified workchain.
+    pub fn compute_address(&self, workchain: i8) -> Result<StdAddr, Error> {
+        Ok(StdAddr::new(workchain, ok!(self.compute_hash())))
+    }
+
+    /// Returns the same state with a new code.
+    ///
+    /// Data, libraries and flags are preserved.
+    pub fn with_code(&self, code: Cell) -> Self {
+        Self {
+            split_depth: self.split_depth,
+            special: self.special,
+            code: Some(code),
+            data: self.data.clone(),
+            libraries: self.libraries.clone(),
+        }
+    }
+
     /// Returns the number of data bits that this struct occupies.
     const fn bit_len(&self) -> u16 {
         (1 + self.split_depth.is_some() as u16 * SplitDepth::BITS)
@@ -417,6 +861,125 @@ impl ExactSize for StateInit {
     }
 }
 
+/// State which is required to unfreeze a frozen account.
+///
+/// See [`AccountState::unfreeze`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct UnfreezeProof {
+    /// Hash of the frozen account state.
+    pub frozen_hash: HashBytes,
+    /// Full account state before it was frozen.
+    pub state_init: Lazy<StateInit>,
+}
+
+impl UnfreezeProof {
+    /// Creates a proof for the specified state.
+    pub fn new(state_init: &StateInit) -> Result<Self, Error> {
+        let state_init = ok!(Lazy::new(state_init));
+        Ok(Self {
+            frozen_hash: *state_init.inner().repr_hash(),
+            state_init,
+        })
+    }
+
+    /// Returns `true` if the state matches the frozen hash.
+    pub fn is_valid(&self) -> bool {
+        *self.state_init.inner().repr_hash() == self.frozen_hash
+    }
+}
+
+/// Contract code with an attached salt.
+///
+/// Salted code is an ordinary cell with a single `JMPREF` instruction,
+/// the original code (e.g. a library cell) as the first child and
+/// the salt as the second one. The original code is executed unchanged,
+/// while the salt affects the code hash (and therefore the contract address).
+/// Contract can read its salt from the code returned by `MYCODE`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SaltedCode {
+    /// Original contract code.
+    pub code: Cell,
+    /// Salt cell.
+    pub salt: Cell,
+}
+
+impl SaltedCode {
+    /// `JMPREF` opcode.
+    const JMPREF: u16 = 0xdb3c;
+
+    /// Creates a salted code from the original code and salt.
+    pub const fn new(code: Cell, salt: Cell) -> Self {
+        Self { code, salt }
+    }
+
+    /// Tries to extract salted code from the deployed account state.
+    ///
+    /// Returns `None` if the account is not active or its code is not salted.
+    pub fn from_account_state(state: &AccountState) -> Option<Self> {
+        match state {
+            AccountState::Active(StateInit {
+                code: Some(code), ..
+            }) => code.parse::<Self>().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the same code with a new salt.
+    pub fn with_salt(&self, salt: Cell) -> Self {
+        Self {
+            code: self.code.clone(),
+            salt,
+        }
+    }
+
+    /// Returns a new code with the same salt (e.g. copied from a deployed account).
+    pub fn with_code(&self, code: Cell) -> Self {
+        Self {
+            code,
+            salt: self.salt.clone(),
+        }
+    }
+
+    /// Builds the salted code cell.
+    pub fn build(&self) -> Result<Cell, Error> {
+        CellBuilder::build_from(self)
+    }
+
+    /// Computes an address of the contract with this code and the specified data.
+    pub fn compute_address(&self, data: Option<Cell>, workchain: i8) -> Result<StdAddr, Error> {
+        let state_init = StateInit {
+            code: Some(ok!(self.build())),
+            data,
+            ..Default::default()
+        };
+        state_init.compute_address(workchain)
+    }
+}
+
+impl Store for SaltedCode {
+    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        ok!(builder.store_u16(Self::JMPREF));
+        ok!(builder.store_reference(self.code.clone()));
+        builder.store_reference(self.salt.clone())
+    }
+}
+
+impl<'a> Load<'a> for SaltedCode {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        // Salted code must occupy the whole cell
+        if slice.remaining_bits() != 16 || slice.remaining_refs() != 2 {
+            return Err(Error::InvalidData);
+        }
+        if ok!(slice.load_u16()) != Self::JMPREF {
+            return Err(Error::InvalidTag);
+        }
+        Ok(Self {
+            code: ok!(slice.load_reference_cloned()),
+            salt: ok!(slice.load_reference_cloned()),
+        })
+    }
+}
+
 /// Special transactions execution flags.
 #[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
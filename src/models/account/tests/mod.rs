@@ -0,0 +1,366 @@
+use super::*;
+use crate::prelude::Boc;
+
+fn make_account(balance: u128, state: AccountState, cells: u64, bits: u64) -> Account {
+    Account {
+        address: IntAddr::from((0, HashBytes([0x11; 32]))),
+        storage_stat: StorageInfo {
+            used: StorageUsed {
+                cells: VarUint56::new(cells),
+                bits: VarUint56::new(bits),
+                public_cells: VarUint56::ZERO,
+            },
+            last_paid: 0,
+            due_payment: None,
+        },
+        last_trans_lt: 0,
+        balance: CurrencyCollection::new(balance),
+        state,
+        init_code_hash: None,
+    }
+}
+
+#[test]
+fn account_diff() {
+    let code = Boc::decode_base64("te6ccgEBAQEABQAABv8AAA==").unwrap();
+    let data = Cell::empty_cell();
+
+    let uninit = make_account(1000, AccountState::Uninit, 1, 100);
+    let active = make_account(
+        400,
+        AccountState::Active(StateInit {
+            code: Some(code.clone()),
+            data: Some(data.clone()),
+            ..Default::default()
+        }),
+        3,
+        150,
+    );
+
+    let diff = Account::diff(&uninit, &uninit);
+    assert!(diff.is_empty());
+
+    let diff = Account::diff(&uninit, &active);
+    assert!(!diff.is_empty());
+    assert_eq!(diff.balance_delta, -600);
+    assert!(!diff.other_balance_changed);
+    assert!(diff.status_changed());
+    assert_eq!(diff.old_status, AccountStatus::Uninit);
+    assert_eq!(diff.new_status, AccountStatus::Active);
+    assert!(diff.code_changed());
+    assert_eq!(diff.old_code_hash, None);
+    assert_eq!(diff.new_code_hash, Some(*code.repr_hash()));
+    assert_eq!(diff.new_data_hash, Some(*data.repr_hash()));
+    assert_eq!(diff.cells_delta, 2);
+    assert_eq!(diff.bits_delta, 50);
+
+    let frozen = make_account(400, AccountState::Frozen(HashBytes([0x22; 32])), 1, 1);
+    let diff = Account::diff(&active, &frozen);
+    assert_eq!(diff.balance_delta, 0);
+    assert!(!diff.balance_changed());
+    assert_eq!(diff.new_status, AccountStatus::Frozen);
+    assert_eq!(diff.new_code_hash, None);
+    assert!(diff.data_changed());
+    assert_eq!(diff.cells_delta, -2);
+    assert_eq!(diff.bits_delta, -149);
+}
+
+#[test]
+fn account_format_version() {
+    fn peek(account: &OptionalAccount) -> Option<FormatVersion> {
+        let cell = CellBuilder::build_from(account).unwrap();
+        OptionalAccount::peek_format_version(&cell.as_slice().unwrap()).unwrap()
+    }
+
+    assert_eq!(peek(&OptionalAccount::EMPTY), None);
+
+    let mut account = make_account(1000, AccountState::Uninit, 1, 100);
+    assert_eq!(
+        peek(&OptionalAccount(Some(account.clone()))),
+        Some(FormatVersion::V1)
+    );
+
+    account.init_code_hash = Some(HashBytes([0x33; 32]));
+    let account = OptionalAccount(Some(account));
+    assert_eq!(peek(&account), Some(FormatVersion::V2));
+
+    // Both layouts are parsed
+    let cell = CellBuilder::build_from(&account).unwrap();
+    assert_eq!(cell.parse::<OptionalAccount>().unwrap(), account);
+}
+
+#[test]
+fn account_code_hash() {
+    fn peek(account: &OptionalAccount) -> Option<HashBytes> {
+        let shard_account = ShardAccount {
+            account: Lazy::new(account).unwrap(),
+            last_trans_hash: HashBytes::ZERO,
+            last_trans_lt: 0,
+        };
+        shard_account.code_hash().unwrap()
+    }
+
+    let code = Boc::decode_base64("te6ccgEBAQEABQAABv8AAA==").unwrap();
+    let code_hash = Some(*code.repr_hash());
+
+    assert_eq!(peek(&OptionalAccount::EMPTY), None);
+
+    let mut account = make_account(1000, AccountState::Uninit, 1, 100);
+    assert_eq!(peek(&OptionalAccount(Some(account.clone()))), None);
+
+    account.state = AccountState::Frozen(HashBytes([0x22; 32]));
+    assert_eq!(peek(&OptionalAccount(Some(account.clone()))), None);
+
+    account.state = AccountState::Active(StateInit {
+        data: Some(Cell::empty_cell()),
+        ..Default::default()
+    });
+    assert_eq!(peek(&OptionalAccount(Some(account.clone()))), None);
+
+    // Extra currencies and all optional state init fields
+    account
+        .balance
+        .other
+        .as_dict_mut()
+        .set(1, VarUint248::new(123))
+        .unwrap();
+    account.state = AccountState::Active(StateInit {
+        split_depth: Some(SplitDepth::new(5).unwrap()),
+        special: Some(SpecialFlags {
+            tick: true,
+            tock: false,
+        }),
+        code: Some(code.clone()),
+        data: Some(Cell::empty_cell()),
+        ..Default::default()
+    });
+    assert_eq!(account.code_hash(), code_hash);
+    assert_eq!(peek(&OptionalAccount(Some(account.clone()))), code_hash);
+
+    // New format with init code hash
+    account.init_code_hash = Some(HashBytes([0x33; 32]));
+    account.address = IntAddr::from((-1, HashBytes([0x44; 32])));
+    assert_eq!(peek(&OptionalAccount(Some(account))), code_hash);
+}
+
+#[test]
+fn freeze_unfreeze() {
+    let code = Boc::decode_base64("te6ccgEBAQEABQAABv8AAA==").unwrap();
+    let state_init = StateInit {
+        code: Some(code),
+        data: Some(Cell::empty_cell()),
+        ..Default::default()
+    };
+
+    let active = AccountState::Active(state_init.clone());
+    assert_eq!(active.frozen_hash(), None);
+    assert!(!active.is_frozen_with(&state_init).unwrap());
+
+    // Freeze
+    let frozen = active.freeze().unwrap();
+    let hash = *CellBuilder::build_from(&state_init).unwrap().repr_hash();
+    assert_eq!(frozen, AccountState::Frozen(hash));
+    assert_eq!(frozen.frozen_hash(), Some(&hash));
+    assert!(frozen.is_frozen_with(&state_init).unwrap());
+    assert!(!frozen.is_frozen_with(&StateInit::default()).unwrap());
+    assert_eq!(frozen.freeze().unwrap(), frozen);
+    assert_eq!(AccountState::Uninit.freeze().unwrap(), AccountState::Uninit);
+
+    // Unfreeze
+    let proof = UnfreezeProof::new(&state_init).unwrap();
+    assert!(proof.is_valid());
+    assert_eq!(proof.frozen_hash, hash);
+    assert_eq!(frozen.unfreeze(&proof).unwrap(), active);
+    assert!(active.unfreeze(&proof).is_err());
+
+    let other = UnfreezeProof::new(&StateInit::default()).unwrap();
+    assert!(frozen.unfreeze(&other).is_err());
+
+    let forged = UnfreezeProof {
+        frozen_hash: hash,
+        state_init: other.state_init,
+    };
+    assert!(!forged.is_valid());
+    assert!(frozen.unfreeze(&forged).is_err());
+}
+
+#[test]
+fn storage_fee() {
+    let prices = StoragePrices {
+        utime_since: 0,
+        bit_price_ps: 1,
+        cell_price_ps: 500,
+        mc_bit_price_ps: 1000,
+        mc_cell_price_ps: 500000,
+    };
+
+    let mut info = make_account(0, AccountState::Uninit, 3, 1000).storage_stat;
+    info.last_paid = 1000;
+
+    // Nothing to pay yet
+    assert_eq!(info.compute_fee(&prices, 1000, false), Tokens::ZERO);
+    assert_eq!(info.compute_fee(&prices, 500, false), Tokens::ZERO);
+
+    // (1000 * 1 + 3 * 500) * 100 / 2^16 = 3.81 -> 4
+    assert_eq!(info.compute_fee(&prices, 1100, false), Tokens::new(4));
+    // (1000 * 1000 + 3 * 500000) * 100 / 2^16 = 3814.69 -> 3815
+    assert_eq!(info.compute_fee(&prices, 1100, true), Tokens::new(3815));
+
+    // Due payment is accumulated
+    info.due_payment = Some(Tokens::new(10));
+    assert_eq!(info.compute_fee(&prices, 1000, false), Tokens::new(10));
+    assert_eq!(info.compute_fee(&prices, 1100, false), Tokens::new(14));
+
+    // Special accounts are not charged
+    info.last_paid = 0;
+    assert_eq!(info.compute_fee(&prices, 1100, false), Tokens::new(10));
+}
+
+#[test]
+fn salted_code() {
+    let code = Boc::decode_base64("te6ccgEBAQEABQAABv8AAA==").unwrap();
+    let data = Cell::empty_cell();
+    let salt = CellBuilder::build_from(0xdeadbeafu32).unwrap();
+
+    let salted = SaltedCode::new(code.clone(), salt.clone());
+    let cell = salted.build().unwrap();
+    assert_eq!(cell.data(), [0xdb, 0x3c]);
+    assert_eq!(cell.parse::<SaltedCode>().unwrap(), salted);
+
+    // Plain code is not salted
+    assert!(code.parse::<SaltedCode>().is_err());
+
+    // Salt affects the address
+    let plain = StateInit {
+        code: Some(code.clone()),
+        data: Some(data.clone()),
+        ..Default::default()
+    };
+    let address = salted.compute_address(Some(data.clone()), 0).unwrap();
+    assert_ne!(address, plain.compute_address(0).unwrap());
+    assert_ne!(
+        address,
+        salted
+            .with_salt(Cell::empty_cell())
+            .compute_address(Some(data.clone()), 0)
+            .unwrap()
+    );
+    assert_eq!(address.workchain, 0);
+
+    // Copy salt from the deployed account
+    let deployed = AccountState::Active(StateInit {
+        code: Some(cell),
+        data: Some(data.clone()),
+        ..Default::default()
+    });
+    let copied = SaltedCode::from_account_state(&deployed).unwrap();
+    assert_eq!(copied.salt, salt);
+
+    let new_code = Cell::empty_cell();
+    let new_salted = copied.with_code(new_code.clone());
+    assert_eq!(new_salted, SaltedCode::new(new_code, salt));
+
+    assert_eq!(
+        SaltedCode::from_account_state(&AccountState::Active(plain)),
+        None
+    );
+    assert_eq!(SaltedCode::from_account_state(&AccountState::Uninit), None);
+}
+
+#[test]
+fn set_code() {
+    let old_code = Boc::decode_base64("te6ccgEBAQEABQAABv8AAA==").unwrap();
+    let new_code = CellBuilder::build_from(0xdeadbeafu32).unwrap();
+    let state_init = StateInit {
+        special: Some(SpecialFlags {
+            tick: true,
+            tock: false,
+        }),
+        code: Some(old_code.clone()),
+        data: Some(CellBuilder::build_from(123u32).unwrap()),
+        ..Default::default()
+    };
+
+    let updated = state_init.with_code(new_code.clone());
+    assert_eq!(updated.code.as_ref(), Some(&new_code));
+    assert_eq!(updated.data, state_init.data);
+    assert_eq!(updated.special, state_init.special);
+    assert_ne!(
+        updated.compute_hash().unwrap(),
+        state_init.compute_hash().unwrap()
+    );
+
+    // Active account
+    let mut account = make_account(1000, AccountState::Active(state_init.clone()), 0, 0);
+    let hash = account.apply_set_code(new_code.clone(), true).unwrap();
+    assert_eq!(hash, updated.compute_hash().unwrap());
+    assert_eq!(account.state, AccountState::Active(updated));
+    assert_eq!(account.init_code_hash, Some(*old_code.repr_hash()));
+
+    // Storage stats are recomputed for the new state
+    let used = account.storage_stat.used.clone();
+    assert_eq!(used, StorageUsed::compute(&account, usize::MAX).unwrap());
+    assert_ne!(used.cells, VarUint56::ZERO);
+
+    // Init code hash is recorded only once
+    let other_code = CellBuilder::build_from(0xcafeu16).unwrap();
+    account.apply_set_code(other_code.clone(), true).unwrap();
+    assert_eq!(account.state.code_hash(), Some(*other_code.repr_hash()));
+    assert_eq!(account.init_code_hash, Some(*old_code.repr_hash()));
+    assert_ne!(account.storage_stat.used, used);
+
+    // Inactive accounts can't change code
+    let mut account = make_account(1000, AccountState::Uninit, 0, 0);
+    assert!(account.apply_set_code(new_code, false).is_err());
+    assert_eq!(account.state, AccountState::Uninit);
+}
+
+#[test]
+fn stored_account() {
+    let root = Boc::decode(include_bytes!("../../shard/tests/new_zerostate.boc")).unwrap();
+    let state = root.parse::<ShardStateUnsplit>().unwrap();
+
+    let elector = StdAddr::new(-1, HashBytes([0x33; 32]));
+    let config = StdAddr::new(-1, HashBytes([0x55; 32]));
+    let shard_account = state
+        .load_accounts()
+        .unwrap()
+        .get(elector.address)
+        .unwrap()
+        .unwrap();
+
+    // Without proof
+    let stored = StoredAccount::from(shard_account.clone());
+    let cell = CellBuilder::build_from(&stored).unwrap();
+    let parsed = cell.parse::<StoredAccount>().unwrap();
+    assert_eq!(parsed, stored);
+    assert_eq!(parsed.to_shard_account(), shard_account);
+    assert_eq!(
+        parsed.load_account().unwrap(),
+        shard_account.load_account().unwrap()
+    );
+    assert!(parsed.check_proof(&elector.address).is_err());
+
+    // With proof
+    let proof = state
+        .serialize_subset(std::slice::from_ref(&elector))
+        .unwrap();
+    let stored = StoredAccount {
+        proof: Some(Lazy::new(&proof).unwrap()),
+        ..stored
+    };
+    let cell = CellBuilder::build_from(&stored).unwrap();
+    let parsed = cell.parse::<StoredAccount>().unwrap();
+    assert_eq!(parsed, stored);
+    assert_eq!(
+        parsed.check_proof(&elector.address).unwrap(),
+        *root.repr_hash()
+    );
+    assert!(parsed.check_proof(&config.address).is_err());
+
+    // Unknown version
+    let mut data = cell.as_slice().unwrap();
+    data.advance(8, 0).unwrap();
+    let invalid = CellBuilder::build_from((0x02u8, data)).unwrap();
+    assert_eq!(invalid.parse::<StoredAccount>(), Err(Error::InvalidTag));
+}
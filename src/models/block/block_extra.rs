@@ -1,16 +1,17 @@
 use crate::cell::*;
-use crate::dict::{AugDict, AugDictSkipValue, Dict};
+use crate::dict::{self, AugDict, Dict, DictKey};
 use crate::error::Error;
-use crate::num::Uint15;
+use crate::num::{Tokens, Uint15};
 
 use crate::models::config::{BlockchainConfig, ValidatorDescription};
 use crate::models::currency::CurrencyCollection;
+use crate::models::message::{IntAddr, MessageSummary};
 use crate::models::transaction::{HashUpdate, Transaction};
-use crate::models::Lazy;
+use crate::models::{FormatVersion, Lazy};
 
-#[cfg(feature = "venom")]
-use super::ShardBlockRefs;
-use super::ShardHashes;
+use super::{ShardBlockRefs, ShardHashes};
+
+pub use crate::crypto::Signature;
 
 /// Block content.
 #[derive(Debug, Clone)]
@@ -28,13 +29,13 @@ pub struct BlockExtra {
     /// Additional block content.
     pub custom: Option<Lazy<McBlockExtra>>,
     /// References to the latest known blocks from all shards.
-    #[cfg(feature = "venom")]
-    pub shard_block_refs: ShardBlockRefs,
+    ///
+    /// NOTE: Only used in networks with fast finality.
+    pub shard_block_refs: Option<ShardBlockRefs>,
 }
 
 impl BlockExtra {
     const TAG_V1: u32 = 0x4a33f6fd;
-    #[cfg(feature = "venom")]
     const TAG_V2: u32 = 0x4a33f6fc;
 }
 
@@ -44,45 +45,41 @@ impl Store for BlockExtra {
         builder: &mut CellBuilder,
         context: &mut dyn CellContext,
     ) -> Result<(), Error> {
-        #[cfg(not(feature = "venom"))]
-        ok!(builder.store_u32(Self::TAG_V1));
-        #[cfg(feature = "venom")]
-        ok!(builder.store_u32(Self::TAG_V2));
+        let tag = if self.shard_block_refs.is_some() {
+            Self::TAG_V2
+        } else {
+            Self::TAG_V1
+        };
 
+        ok!(builder.store_u32(tag));
         ok!(builder.store_reference(self.in_msg_description.clone()));
         ok!(builder.store_reference(self.out_msg_description.clone()));
         ok!(builder.store_reference(self.account_blocks.cell.clone()));
         ok!(builder.store_u256(&self.rand_seed));
         ok!(builder.store_u256(&self.created_by));
 
-        #[cfg(not(feature = "venom"))]
-        ok!(self.custom.store_into(builder, context));
+        if let Some(shard_block_refs) = &self.shard_block_refs {
+            builder.store_reference({
+                let mut builder = CellBuilder::new();
 
-        #[cfg(feature = "venom")]
-        ok!(builder.store_reference({
-            let mut builder = CellBuilder::new();
-
-            ok!(self.custom.store_into(&mut builder, context));
-            ok!(self.shard_block_refs.store_into(&mut builder, context));
+                ok!(self.custom.store_into(&mut builder, context));
+                ok!(shard_block_refs.store_into(&mut builder, context));
 
-            ok!(builder.build_ext(context))
-        }));
-
-        Ok(())
+                ok!(builder.build_ext(context))
+            })
+        } else {
+            self.custom.store_into(builder, context)
+        }
     }
 }
 
 impl<'a> Load<'a> for BlockExtra {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
-        let tag = ok!(slice.load_u32());
-        #[cfg(not(feature = "venom"))]
-        if tag != Self::TAG_V1 {
-            return Err(Error::InvalidTag);
-        }
-        #[cfg(feature = "venom")]
-        if tag != Self::TAG_V1 && tag != Self::TAG_V2 {
-            return Err(Error::InvalidTag);
-        }
+        let with_shard_block_refs = match ok!(slice.load_u32()) {
+            Self::TAG_V1 => false,
+            Self::TAG_V2 => true,
+            _ => return Err(Error::InvalidTag),
+        };
 
         let in_msg_description = ok!(slice.load_reference_cloned());
         let out_msg_description = ok!(slice.load_reference_cloned());
@@ -90,15 +87,13 @@ impl<'a> Load<'a> for BlockExtra {
         let rand_seed = ok!(slice.load_u256());
         let created_by = ok!(slice.load_u256());
 
-        #[cfg(not(feature = "venom"))]
-        let custom = ok!(Option::<Lazy<_>>::load_from(slice));
-
-        #[cfg(feature = "venom")]
-        let (custom, shard_block_refs) = {
+        let (custom, shard_block_refs) = if with_shard_block_refs {
             let slice = &mut ok!(slice.load_reference_as_slice());
             let custom = ok!(Option::<Lazy<_>>::load_from(slice));
             let shard_block_refs = ok!(ShardBlockRefs::load_from(slice));
-            (custom, shard_block_refs)
+            (custom, Some(shard_block_refs))
+        } else {
+            (ok!(Option::<Lazy<_>>::load_from(slice)), None)
         };
 
         Ok(Self {
@@ -108,7 +103,6 @@ impl<'a> Load<'a> for BlockExtra {
             rand_seed,
             created_by,
             custom,
-            #[cfg(feature = "venom")]
             shard_block_refs,
         })
     }
@@ -127,6 +121,153 @@ impl BlockExtra {
     }
 }
 
+impl BlockExtra {
+    /// Collects hashes of all messages from the incoming and outgoing
+    /// message descriptions in a single pass.
+    ///
+    /// Accounts are taken from the transactions or the message headers,
+    /// message bodies and envelope fees are not parsed.
+    pub fn collect_message_hashes(&self) -> Result<Vec<MsgHashEntry>, Error> {
+        let mut result = Vec::new();
+        ok!(collect_descr_hashes(
+            &self.in_msg_description,
+            MsgDirection::In,
+            &mut result
+        ));
+        ok!(collect_descr_hashes(
+            &self.out_msg_description,
+            MsgDirection::Out,
+            &mut result
+        ));
+        Ok(result)
+    }
+}
+
+/// Message direction relative to the block.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum MsgDirection {
+    /// Message from the incoming message description.
+    In,
+    /// Message from the outgoing message description.
+    Out,
+}
+
+/// Message hash with its direction and account.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub struct MsgHashEntry {
+    /// Representation hash of the message.
+    pub hash: HashBytes,
+    /// Message description which contains this message.
+    pub direction: MsgDirection,
+    /// Destination account for incoming messages,
+    /// or source account for outgoing messages.
+    ///
+    /// NOTE: Can be `None` for non-standard addresses
+    /// or dequeued messages without an envelope.
+    pub account: Option<HashBytes>,
+}
+
+fn collect_descr_hashes(
+    descr: &Cell,
+    direction: MsgDirection,
+    result: &mut Vec<MsgHashEntry>,
+) -> Result<(), Error> {
+    let root = ok!(Option::<Cell>::load_from(&mut ok!(descr.as_slice())));
+
+    for entry in dict::RawIter::new(&root, 256) {
+        let (key, mut value) = ok!(entry);
+        let Some(hash) = HashBytes::from_raw_data(key.raw_data()) else {
+            return Err(Error::InvalidData);
+        };
+
+        let account = match direction {
+            MsgDirection::In => {
+                // Skip `ImportFees`
+                ok!(value.skip_value::<Tokens>());
+                ok!(value.skip_value::<CurrencyCollection>());
+                ok!(in_msg_account(&mut value))
+            }
+            MsgDirection::Out => {
+                ok!(value.skip_value::<CurrencyCollection>());
+                ok!(out_msg_account(&mut value))
+            }
+        };
+
+        result.push(MsgHashEntry {
+            hash,
+            direction,
+            account,
+        });
+    }
+
+    Ok(())
+}
+
+fn in_msg_account(slice: &mut CellSlice<'_>) -> Result<Option<HashBytes>, Error> {
+    match ok!(slice.load_small_uint(3)) {
+        // msg_import_ext, msg_import_ihr, msg_import_imm, msg_import_fin
+        0b000 | 0b010 | 0b011 | 0b100 => tx_account(slice),
+        // msg_import_tr, msg_discard_fin, msg_discard_tr
+        0b101..=0b111 => envelope_account(slice, MsgDirection::In),
+        _ => match ok!(slice.load_small_uint(2)) {
+            // msg_import_deferred_fin
+            0b00 => tx_account(slice),
+            // msg_import_deferred_tr
+            0b01 => envelope_account(slice, MsgDirection::In),
+            _ => Err(Error::InvalidTag),
+        },
+    }
+}
+
+fn out_msg_account(slice: &mut CellSlice<'_>) -> Result<Option<HashBytes>, Error> {
+    match ok!(slice.load_small_uint(3)) {
+        // msg_export_ext, msg_export_new, msg_export_imm
+        0b000..=0b010 => tx_account(slice),
+        // msg_export_tr, msg_export_deq_imm, msg_export_tr_req
+        0b011 | 0b100 | 0b111 => envelope_account(slice, MsgDirection::Out),
+        0b110 => match ok!(slice.load_bit()) {
+            // msg_export_deq_short
+            true => Ok(None),
+            // msg_export_deq
+            false => envelope_account(slice, MsgDirection::Out),
+        },
+        _ => match ok!(slice.load_small_uint(2)) {
+            // msg_export_new_defer
+            0b00 => tx_account(slice),
+            // msg_export_deferred_tr
+            0b01 => envelope_account(slice, MsgDirection::Out),
+            _ => Err(Error::InvalidTag),
+        },
+    }
+}
+
+/// Reads the account of the transaction from the second reference.
+fn tx_account(slice: &CellSlice<'_>) -> Result<Option<HashBytes>, Error> {
+    let mut tx = ok!(ok!(slice.get_reference(1)).as_slice());
+    if ok!(tx.load_small_uint(4)) != Transaction::TAG {
+        return Err(Error::InvalidTag);
+    }
+    Ok(Some(ok!(tx.load_u256())))
+}
+
+/// Reads the message address from the envelope in the first reference.
+fn envelope_account(
+    slice: &CellSlice<'_>,
+    direction: MsgDirection,
+) -> Result<Option<HashBytes>, Error> {
+    let envelope = ok!(slice.get_reference(0));
+    let msg = ok!(envelope.reference(0).ok_or(Error::CellUnderflow));
+    let summary = ok!(MessageSummary::from_cell(msg));
+    let addr = match direction {
+        MsgDirection::In => summary.dst,
+        MsgDirection::Out => summary.src,
+    };
+    Ok(match addr {
+        Some(IntAddr::Std(addr)) => Some(addr.address),
+        _ => None,
+    })
+}
+
 /// A group of account transactions.
 #[derive(Debug, Clone)]
 pub struct AccountBlock {
@@ -176,12 +317,6 @@ impl<'a> Load<'a> for AccountBlock {
     }
 }
 
-impl<'a> AugDictSkipValue<'a> for Lazy<Transaction> {
-    fn skip_value(slice: &mut CellSlice<'a>) -> bool {
-        slice.try_advance(0, 1)
-    }
-}
-
 /// Additional content for masterchain blocks.
 #[derive(Debug, Clone)]
 pub struct McBlockExtra {
@@ -205,6 +340,18 @@ pub struct McBlockExtra {
 impl McBlockExtra {
     const TAG_V1: u16 = 0xcca5;
     const TAG_V2: u16 = 0xdc75;
+
+    /// Returns the layout of the serialized block extra without parsing it.
+    ///
+    /// - [`FormatVersion::V1`] - the original layout;
+    /// - [`FormatVersion::V2`] - the layout with copyleft messages.
+    pub fn peek_format_version(slice: &CellSlice<'_>) -> Result<FormatVersion, Error> {
+        match ok!(slice.get_u16(0)) {
+            Self::TAG_V1 => Ok(FormatVersion::V1),
+            Self::TAG_V2 => Ok(FormatVersion::V2),
+            _ => Err(Error::InvalidTag),
+        }
+    }
 }
 
 impl Store for McBlockExtra {
@@ -330,7 +477,8 @@ impl BlockSignatureExt for Dict<u16, BlockSignature> {
         let mut weight = 0;
         for value in self.values() {
             let value = ok!(value);
-            if let Some(node) = unique_nodes.get(&value.node_id_short) {
+            // NOTE: Each node is counted only once
+            if let Some(node) = unique_nodes.remove(&value.node_id_short) {
                 if !node.verify_signature(data, &value.signature) {
                     return Err(Error::InvalidSignature);
                 }
@@ -341,50 +489,3 @@ impl BlockSignatureExt for Dict<u16, BlockSignature> {
         Ok(weight)
     }
 }
-
-/// Ed25519 signature.
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
-pub struct Signature(pub [u8; 64]);
-
-impl Signature {
-    const TAG_LEN: u16 = 4;
-
-    const TAG: u8 = 0x5;
-}
-
-impl Default for Signature {
-    #[inline]
-    fn default() -> Self {
-        Self([0; 64])
-    }
-}
-
-impl AsRef<[u8; 64]> for Signature {
-    #[inline]
-    fn as_ref(&self) -> &[u8; 64] {
-        &self.0
-    }
-}
-
-impl Store for Signature {
-    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
-        ok!(builder.store_small_uint(Self::TAG, Self::TAG_LEN));
-        builder.store_raw(&self.0, 512)
-    }
-}
-
-impl<'a> Load<'a> for Signature {
-    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
-        match slice.load_small_uint(Self::TAG_LEN) {
-            Ok(Self::TAG) => {}
-            Ok(_) => return Err(Error::InvalidTag),
-            Err(e) => return Err(e),
-        }
-
-        let mut result = Self::default();
-        match slice.load_raw(&mut result.0, 512) {
-            Ok(_) => Ok(result),
-            Err(e) => Err(e),
-        }
-    }
-}
@@ -2,6 +2,7 @@ use std::str::FromStr;
 
 use crate::cell::*;
 use crate::error::{Error, ParseBlockIdError};
+use crate::models::message::IntAddr;
 
 /// Full block id.
 #[derive(Debug, Default, Clone, Copy, Eq, Hash, PartialEq, Ord, PartialOrd, Store, Load)]
@@ -107,12 +108,22 @@ impl FromStr for BlockId {
     }
 }
 
+/// Formats block id as `shard:seqno:root_hash:file_hash`.
+///
+/// Alternate mode (`{:#}`) adds labels for hashes.
 impl std::fmt::Display for BlockId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!(
-            "{}:{}:{}:{}",
-            self.shard, self.seqno, self.root_hash, self.file_hash,
-        ))
+        if f.alternate() {
+            f.write_fmt(format_args!(
+                "{}:{} (root_hash: {}, file_hash: {})",
+                self.shard, self.seqno, self.root_hash, self.file_hash,
+            ))
+        } else {
+            f.write_fmt(format_args!(
+                "{}:{}:{}:{}",
+                self.shard, self.seqno, self.root_hash, self.file_hash,
+            ))
+        }
     }
 }
 
@@ -160,15 +171,22 @@ impl Default for ShardIdent {
     }
 }
 
+/// Formats shard ident as `workchain:prefix`.
+///
+/// Alternate mode (`{:#}`) also prints the shard depth.
 impl std::fmt::Display for ShardIdent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_fmt(format_args!("{}:{:016x}", self.workchain, self.prefix))
+        ok!(f.write_fmt(format_args!("{}:{:016x}", self.workchain, self.prefix)));
+        if f.alternate() {
+            ok!(f.write_fmt(format_args!(" (depth {})", self.prefix_len())));
+        }
+        Ok(())
     }
 }
 
 impl std::fmt::Debug for ShardIdent {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        std::fmt::Display::fmt(self, f)
+        f.write_fmt(format_args!("{}:{:016x}", self.workchain, self.prefix))
     }
 }
 
@@ -396,6 +414,23 @@ impl ShardIdent {
         true
     }
 
+    /// Returns `true` if the specified address could be stored in the current shard.
+    ///
+    /// NOTE: Unlike [`contains_account`], also checks the workchain and
+    /// supports variable-length addresses.
+    ///
+    /// [`contains_account`]: Self::contains_account
+    pub fn contains_address(&self, address: &IntAddr) -> bool {
+        self.workchain == address.workchain() && self.contains_prefix(address.prefix())
+    }
+
+    /// Returns `true` if the specified account prefix matches the shard prefix.
+    pub const fn contains_prefix(&self, account_prefix: u64) -> bool {
+        // All bits above the tag bit must be equal
+        let mask = self.prefix_tag_mask() << 1;
+        (self.prefix ^ account_prefix) & mask == 0
+    }
+
     #[inline]
     const fn prefix_tag(&self) -> u64 {
         self.prefix & self.prefix_tag_mask()
@@ -0,0 +1,272 @@
+use crate::cell::*;
+use crate::error::Error;
+
+use super::{BlockInfo, BlockRef, PrevBlockRef, ShardIdent};
+use crate::models::global_version::GlobalVersion;
+use crate::models::Lazy;
+
+/// Block info builder.
+///
+/// Derives the block seqno and the split/merge flags from the previous
+/// blocks and checks the relations between the fields on build:
+///
+/// - masterchain blocks can't split or merge, and can't reference
+///   another masterchain block, while shard blocks must do it;
+/// - the first block after merge can't be split right away;
+/// - only masterchain blocks can be key blocks;
+/// - the logical time range is not empty and starts after the previous
+///   blocks and the referenced masterchain block;
+/// - the creation time is greater than the creation time of the previous
+///   block (if specified);
+/// - the previous vertical block is referenced only with non-zero
+///   vertical seqno.
+#[derive(Debug, Clone)]
+pub struct BlockInfoBuilder {
+    info: BlockInfo,
+    prev: PrevBlockRef,
+    prev_gen_utime: Option<(u32, u16)>,
+    master_ref: Option<BlockRef>,
+    prev_vert_ref: Option<BlockRef>,
+}
+
+impl BlockInfoBuilder {
+    /// Creates a builder for the block which follows the specified block
+    /// in the same shard.
+    pub fn new(shard: ShardIdent, prev: BlockRef) -> Self {
+        Self::with_prev(shard, PrevBlockRef::Single(prev))
+    }
+
+    /// Creates a builder for the first block of the shard
+    /// after its parent shard was split.
+    pub fn after_split(shard: ShardIdent, prev: BlockRef) -> Self {
+        let mut builder = Self::with_prev(shard, PrevBlockRef::Single(prev));
+        builder.info.after_split = true;
+        builder
+    }
+
+    /// Creates a builder for the first block of the shard
+    /// after its child shards were merged.
+    pub fn after_merge(shard: ShardIdent, left: BlockRef, right: BlockRef) -> Self {
+        let mut builder = Self::with_prev(shard, PrevBlockRef::AfterMerge { left, right });
+        builder.info.after_merge = true;
+        builder
+    }
+
+    fn with_prev(shard: ShardIdent, prev: PrevBlockRef) -> Self {
+        let seqno = match &prev {
+            PrevBlockRef::Single(prev) => prev.seqno,
+            PrevBlockRef::AfterMerge { left, right } => std::cmp::max(left.seqno, right.seqno),
+        }
+        .saturating_add(1);
+
+        Self {
+            info: BlockInfo {
+                version: 0,
+                after_merge: false,
+                before_split: false,
+                after_split: false,
+                want_split: false,
+                want_merge: false,
+                key_block: false,
+                flags: 0,
+                seqno,
+                vert_seqno: 0,
+                shard,
+                gen_utime: 0,
+                gen_utime_ms: None,
+                start_lt: 0,
+                end_lt: 0,
+                gen_validator_list_hash_short: 0,
+                gen_catchain_seqno: 0,
+                min_ref_mc_seqno: 0,
+                prev_key_block_seqno: 0,
+                gen_software: GlobalVersion::default(),
+                master_ref: None,
+                prev_ref: Cell::empty_cell(),
+                prev_vert_ref: None,
+            },
+            prev,
+            prev_gen_utime: None,
+            master_ref: None,
+            prev_vert_ref: None,
+        }
+    }
+
+    /// Sets the block model version.
+    pub fn with_version(mut self, version: u32) -> Self {
+        self.info.version = version;
+        self
+    }
+
+    /// Sets the creation time of the block.
+    pub fn with_gen_utime(mut self, gen_utime: u32) -> Self {
+        self.info.gen_utime = gen_utime;
+        self
+    }
+
+    /// Sets the milliseconds part of the creation time of the block.
+    ///
+    /// NOTE: Only used in networks with fast finality.
+    pub fn with_gen_utime_ms(mut self, gen_utime_ms: u16) -> Self {
+        self.info.gen_utime_ms = Some(gen_utime_ms);
+        self
+    }
+
+    /// Sets the creation time of the previous block
+    /// to check that the time is increasing.
+    pub fn with_prev_gen_utime(mut self, gen_utime: u32, gen_utime_ms: Option<u16>) -> Self {
+        self.prev_gen_utime = Some((gen_utime, gen_utime_ms.unwrap_or_default()));
+        self
+    }
+
+    /// Sets the logical time range of the block.
+    pub fn with_lt_range(mut self, start_lt: u64, end_lt: u64) -> Self {
+        self.info.start_lt = start_lt;
+        self.info.end_lt = end_lt;
+        self
+    }
+
+    /// Sets the referenced masterchain block.
+    ///
+    /// NOTE: Required for shard blocks.
+    pub fn with_master_ref(mut self, master_ref: BlockRef) -> Self {
+        self.master_ref = Some(master_ref);
+        self
+    }
+
+    /// Sets the vertical seqno of the block.
+    pub fn with_vert_seqno(mut self, vert_seqno: u32) -> Self {
+        self.info.vert_seqno = vert_seqno;
+        self
+    }
+
+    /// Sets the reference to the previous vertical block.
+    pub fn with_prev_vert_ref(mut self, prev_vert_ref: BlockRef) -> Self {
+        self.prev_vert_ref = Some(prev_vert_ref);
+        self
+    }
+
+    /// Marks the block as the last block before the shard split.
+    pub fn with_before_split(mut self, before_split: bool) -> Self {
+        self.info.before_split = before_split;
+        self
+    }
+
+    /// Sets hints that the shard should split or merge.
+    pub fn with_split_merge_hints(mut self, want_split: bool, want_merge: bool) -> Self {
+        self.info.want_split = want_split;
+        self.info.want_merge = want_merge;
+        self
+    }
+
+    /// Marks the block as a key block.
+    pub fn with_key_block(mut self, key_block: bool) -> Self {
+        self.info.key_block = key_block;
+        self
+    }
+
+    /// Sets the catchain session info of the validators which produced the block.
+    pub fn with_catchain_info(
+        mut self,
+        gen_validator_list_hash_short: u32,
+        gen_catchain_seqno: u32,
+    ) -> Self {
+        self.info.gen_validator_list_hash_short = gen_validator_list_hash_short;
+        self.info.gen_catchain_seqno = gen_catchain_seqno;
+        self
+    }
+
+    /// Sets the minimal referenced seqno of the masterchain block.
+    pub fn with_min_ref_mc_seqno(mut self, min_ref_mc_seqno: u32) -> Self {
+        self.info.min_ref_mc_seqno = min_ref_mc_seqno;
+        self
+    }
+
+    /// Sets the previous key block seqno.
+    pub fn with_prev_key_block_seqno(mut self, prev_key_block_seqno: u32) -> Self {
+        self.info.prev_key_block_seqno = prev_key_block_seqno;
+        self
+    }
+
+    /// Sets the version and capabilities of the software that created the block.
+    pub fn with_gen_software(mut self, gen_software: GlobalVersion) -> Self {
+        self.info.gen_software = gen_software;
+        self.info.flags |= BlockInfo::FLAG_WITH_GEN_SOFTWARE;
+        self
+    }
+
+    /// Checks the invariants and builds the block info.
+    pub fn build(self) -> Result<BlockInfo, Error> {
+        let mut info = self.info;
+        let shard = &info.shard;
+
+        // Check split/merge flags
+        if shard.is_masterchain() {
+            // NOTE: Split/merge hints are ignored for the masterchain
+            if info.after_merge || info.after_split || info.before_split {
+                return Err(Error::InvalidData);
+            }
+        } else {
+            if info.key_block
+                || (info.after_split && shard.is_full())
+                || (info.after_merge && !shard.can_split())
+                || (info.before_split && !shard.can_split())
+                || (info.after_merge && info.before_split)
+                || (info.want_split && info.want_merge)
+            {
+                return Err(Error::InvalidData);
+            }
+        }
+
+        // Check masterchain reference
+        match (&self.master_ref, shard.is_masterchain()) {
+            (None, false) | (Some(_), true) => return Err(Error::InvalidData),
+            (Some(master_ref), false) if info.min_ref_mc_seqno > master_ref.seqno => {
+                return Err(Error::InvalidData)
+            }
+            (None, true) if info.prev_key_block_seqno >= info.seqno => {
+                return Err(Error::InvalidData)
+            }
+            _ => {}
+        }
+
+        // Check logical time range
+        let prev_end_lt = match &self.prev {
+            PrevBlockRef::Single(prev) => prev.end_lt,
+            PrevBlockRef::AfterMerge { left, right } => std::cmp::max(left.end_lt, right.end_lt),
+        };
+        let mc_end_lt = self
+            .master_ref
+            .as_ref()
+            .map(|r| r.end_lt)
+            .unwrap_or_default();
+        if info.start_lt >= info.end_lt || info.start_lt <= std::cmp::max(prev_end_lt, mc_end_lt) {
+            return Err(Error::InvalidData);
+        }
+
+        // Check creation time
+        if let Some(prev_gen_utime) = self.prev_gen_utime {
+            let gen_utime = (info.gen_utime, info.gen_utime_ms.unwrap_or_default());
+            if gen_utime <= prev_gen_utime {
+                return Err(Error::InvalidData);
+            }
+        }
+
+        // Check vertical seqno
+        if self.prev_vert_ref.is_some() && info.vert_seqno == 0 {
+            return Err(Error::InvalidData);
+        }
+
+        info.master_ref = match &self.master_ref {
+            Some(master_ref) => Some(ok!(Lazy::new(master_ref))),
+            None => None,
+        };
+        info.prev_ref = ok!(CellBuilder::build_from(&self.prev));
+        info.prev_vert_ref = match &self.prev_vert_ref {
+            Some(prev_vert_ref) => Some(ok!(Lazy::new(prev_vert_ref))),
+            None => None,
+        };
+
+        Ok(info)
+    }
+}
@@ -1,8 +1,10 @@
 use crate::cell::*;
 use crate::dict::Dict;
-use crate::error::Error;
+use crate::error::{Error, ProofChainError};
+use crate::merkle::MerkleProof;
 
-use super::{BlockId, BlockSignature};
+use super::{Block, BlockId, BlockInfo, BlockSignature, BlockSignatureExt};
+use crate::models::config::{CatchainConfig, ValidatorDescription, ValidatorSet};
 use crate::models::shard::ValidatorBaseInfo;
 
 /// Typed block proof.
@@ -84,3 +86,296 @@ pub struct BlockSignatures {
     /// Block signatures from all signers.
     pub signatures: Dict<u16, BlockSignature>,
 }
+
+/// Compact bitmap of participating validators.
+///
+/// Used by signature aggregation formats in networks with fast finality,
+/// where the `N`th bit is set if the `N`th validator in the list signed the block.
+///
+/// Serialized as `len:uint16` followed by `len` bits in the same cell.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct ValidatorBitmap {
+    len: u16,
+    data: Vec<u8>,
+}
+
+impl ValidatorBitmap {
+    /// Creates an empty bitmap for the specified number of validators.
+    pub fn new(len: u16) -> Self {
+        Self {
+            len,
+            data: vec![0; (len as usize + 7) / 8],
+        }
+    }
+
+    /// Returns the number of validators in the bitmap.
+    #[inline]
+    pub const fn len(&self) -> u16 {
+        self.len
+    }
+
+    /// Returns `true` if the bitmap has no validators.
+    #[inline]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns whether the validator with the specified index is set.
+    ///
+    /// Returns `false` if the index is out of bounds.
+    pub fn get(&self, index: u16) -> bool {
+        index < self.len && self.data[(index / 8) as usize] & (0x80 >> (index % 8)) != 0
+    }
+
+    /// Sets or clears the validator with the specified index.
+    ///
+    /// Returns `false` if the index is out of bounds.
+    pub fn set(&mut self, index: u16, value: bool) -> bool {
+        if index >= self.len {
+            return false;
+        }
+
+        let byte = &mut self.data[(index / 8) as usize];
+        let mask = 0x80 >> (index % 8);
+        if value {
+            *byte |= mask;
+        } else {
+            *byte &= !mask;
+        }
+        true
+    }
+
+    /// Returns the number of set validators.
+    pub fn count_ones(&self) -> usize {
+        self.data
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns an iterator over the indices of set validators.
+    pub fn iter_ones(&self) -> impl Iterator<Item = u16> + '_ {
+        (0..self.len).filter(|index| self.get(*index))
+    }
+
+    /// Computes the total weight of set validators in the list.
+    ///
+    /// Returns `None` if the list length differs from the bitmap length.
+    pub fn weight(&self, validators: &[ValidatorDescription]) -> Option<u64> {
+        if validators.len() != self.len as usize {
+            return None;
+        }
+
+        let mut weight = 0u64;
+        for index in self.iter_ones() {
+            weight = weight.saturating_add(validators[index as usize].weight);
+        }
+        Some(weight)
+    }
+
+    /// Returns `true` if set validators have more than 2/3
+    /// of the total weight of the validator set.
+    pub fn has_quorum(&self, vset: &ValidatorSet) -> bool {
+        match self.weight(&vset.list) {
+            Some(weight) => (weight as u128) * 3 > (vset.total_weight as u128) * 2,
+            None => false,
+        }
+    }
+}
+
+impl Store for ValidatorBitmap {
+    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        ok!(builder.store_u16(self.len));
+        builder.store_raw(&self.data, self.len)
+    }
+}
+
+impl<'a> Load<'a> for ValidatorBitmap {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        let mut result = Self::new(ok!(slice.load_u16()));
+        ok!(slice.load_raw(&mut result.data, result.len));
+        Ok(result)
+    }
+}
+
+/// Verifier of masterchain block proof links.
+///
+/// Starts from a trusted key block with its validator set and checks
+/// proofs of the next key blocks one by one, switching to the validator
+/// set from the config of each verified key block. This allows to trust
+/// any masterchain block knowing only some older key block.
+#[derive(Debug, Clone)]
+pub struct BlockProofChain {
+    key_block_id: BlockId,
+    validator_set: ValidatorSet,
+    catchain_config: CatchainConfig,
+}
+
+impl BlockProofChain {
+    /// Creates a verifier from the trusted key block id and its config params.
+    pub fn new(
+        key_block_id: BlockId,
+        validator_set: ValidatorSet,
+        catchain_config: CatchainConfig,
+    ) -> Self {
+        Self {
+            key_block_id,
+            validator_set,
+            catchain_config,
+        }
+    }
+
+    /// Creates a verifier from the trusted key block proof.
+    ///
+    /// NOTE: Signatures of the proof are not checked.
+    pub fn from_key_block_proof(proof: &BlockProof) -> Result<Self, ProofChainError> {
+        let (block, info) = ok!(parse_proof_block(proof));
+        if !info.key_block {
+            return Err(ProofChainError::NotKeyBlock);
+        }
+
+        let (validator_set, catchain_config) = ok!(load_key_block_params(&block));
+        Ok(Self::new(proof.proof_for, validator_set, catchain_config))
+    }
+
+    /// Returns the id of the last trusted key block.
+    pub fn key_block_id(&self) -> &BlockId {
+        &self.key_block_id
+    }
+
+    /// Returns the current validator set.
+    pub fn validator_set(&self) -> &ValidatorSet {
+        &self.validator_set
+    }
+
+    /// Returns the current catchain config.
+    pub fn catchain_config(&self) -> &CatchainConfig {
+        &self.catchain_config
+    }
+
+    /// Verifies proofs for all key blocks after the trusted one,
+    /// followed by the proof for the target block.
+    ///
+    /// Returns the info of the target block.
+    pub fn verify(
+        &mut self,
+        proofs: &[BlockProof],
+        target: &BlockId,
+    ) -> Result<BlockInfo, ProofChainError> {
+        let Some((last, links)) = proofs.split_last() else {
+            return Err(ProofChainError::EmptyChain);
+        };
+
+        for proof in links {
+            let info = ok!(self.check_link(proof));
+            if !info.key_block {
+                return Err(ProofChainError::NotKeyBlock);
+            }
+        }
+
+        if last.proof_for != *target {
+            return Err(ProofChainError::TargetMismatch);
+        }
+        self.check_link(last)
+    }
+
+    /// Verifies the proof for the block signed by the current validator set.
+    ///
+    /// If the block is a key block, it becomes the last trusted one.
+    pub fn check_link(&mut self, proof: &BlockProof) -> Result<BlockInfo, ProofChainError> {
+        let (block, info) = ok!(parse_proof_block(proof));
+        if info.prev_key_block_seqno != self.key_block_id.seqno {
+            return Err(ProofChainError::PrevKeyBlockMismatch {
+                expected: self.key_block_id.seqno,
+                actual: info.prev_key_block_seqno,
+            });
+        }
+
+        let Some(signatures) = &proof.signatures else {
+            return Err(ProofChainError::SignaturesMissing);
+        };
+
+        // Check that the block was produced by the expected subset
+        let Some((subset, hash_short)) = self.validator_set.compute_subset(
+            info.shard,
+            &self.catchain_config,
+            info.gen_catchain_seqno,
+        ) else {
+            return Err(ProofChainError::ValidatorSetMismatch);
+        };
+        if hash_short != info.gen_validator_list_hash_short
+            || signatures.validator_info.validator_list_hash_short != hash_short
+            || signatures.validator_info.catchain_seqno != info.gen_catchain_seqno
+        {
+            return Err(ProofChainError::ValidatorSetMismatch);
+        }
+
+        let data = Block::build_data_for_sign(&proof.proof_for);
+        let signed = match signatures.signatures.check_signatures(&subset, &data) {
+            Ok(signed) => signed,
+            Err(Error::InvalidSignature) => return Err(ProofChainError::InvalidSignature),
+            Err(e) => return Err(e.into()),
+        };
+        let total = subset
+            .iter()
+            .fold(0u64, |total, item| total.saturating_add(item.weight));
+        if (signed as u128) * 3 <= (total as u128) * 2 {
+            return Err(ProofChainError::NotEnoughWeight { signed, total });
+        }
+
+        if info.key_block {
+            let (validator_set, catchain_config) = ok!(load_key_block_params(&block));
+            self.key_block_id = proof.proof_for;
+            self.validator_set = validator_set;
+            self.catchain_config = catchain_config;
+        }
+        Ok(info)
+    }
+}
+
+fn parse_proof_block(proof: &BlockProof) -> Result<(Block, BlockInfo), ProofChainError> {
+    let block_id = &proof.proof_for;
+    if !block_id.is_masterchain() {
+        return Err(ProofChainError::NotMasterchain);
+    }
+
+    let merkle_proof = ok!(proof
+        .root
+        .parse::<MerkleProof>()
+        .map_err(ProofChainError::InvalidStructure));
+    if merkle_proof.hash != block_id.root_hash {
+        return Err(ProofChainError::BlockIdMismatch);
+    }
+
+    let block = ok!(merkle_proof
+        .cell
+        .parse::<Block>()
+        .map_err(ProofChainError::InvalidStructure));
+    let info = ok!(block.load_info().map_err(ProofChainError::InvalidStructure));
+    if info.seqno != block_id.seqno || info.shard != block_id.shard {
+        return Err(ProofChainError::BlockIdMismatch);
+    }
+
+    Ok((block, info))
+}
+
+fn load_key_block_params(block: &Block) -> Result<(ValidatorSet, CatchainConfig), ProofChainError> {
+    let extra = ok!(block
+        .load_extra()
+        .map_err(ProofChainError::InvalidStructure));
+    let Some(custom) = extra.custom else {
+        return Err(ProofChainError::ConfigMissing);
+    };
+    let custom = ok!(custom.load().map_err(ProofChainError::InvalidStructure));
+    let Some(config) = custom.config else {
+        return Err(ProofChainError::ConfigMissing);
+    };
+
+    let validator_set = ok!(config
+        .get_current_validator_set()
+        .map_err(ProofChainError::InvalidStructure));
+    let catchain_config = ok!(config
+        .get_catchain_config()
+        .map_err(ProofChainError::InvalidStructure));
+    Ok((validator_set, catchain_config))
+}
@@ -0,0 +1,127 @@
+use crate::cell::*;
+use crate::error::Error;
+use crate::merkle::MerkleUpdate;
+
+use crate::models::config::BlockchainConfig;
+
+use super::{Block, BlockExtra, BlockId, BlockInfo, McBlockExtra, ValueFlow};
+
+/// Block wrapper with an eagerly parsed [`BlockInfo`].
+///
+/// Other block parts are only loaded on demand, so consumers which need
+/// just a few fields don't decode the whole block.
+#[derive(Debug, Clone)]
+pub struct LazyBlock {
+    root: Cell,
+    block: Block,
+    info: BlockInfo,
+}
+
+impl LazyBlock {
+    /// Parses the block header and info from the root cell.
+    pub fn new(root: Cell) -> Result<Self, Error> {
+        let block = ok!(root.parse::<Block>());
+        let info = ok!(block.load_info());
+        Ok(Self { root, block, info })
+    }
+
+    /// Returns the root cell of the block.
+    #[inline]
+    pub fn root(&self) -> &Cell {
+        &self.root
+    }
+
+    /// Returns the representation hash of the block.
+    #[inline]
+    pub fn repr_hash(&self) -> &HashBytes {
+        self.root.repr_hash()
+    }
+
+    /// Returns the global network id.
+    #[inline]
+    pub fn global_id(&self) -> i32 {
+        self.block.global_id
+    }
+
+    /// Returns the parsed block info.
+    #[inline]
+    pub fn info(&self) -> &BlockInfo {
+        &self.info
+    }
+
+    /// Returns `true` if this is a key block.
+    #[inline]
+    pub fn is_key_block(&self) -> bool {
+        self.info.key_block
+    }
+
+    /// Returns the full block id using the provided file hash.
+    pub fn block_id(&self, file_hash: HashBytes) -> BlockId {
+        BlockId {
+            shard: self.info.shard,
+            seqno: self.info.seqno,
+            root_hash: *self.repr_hash(),
+            file_hash,
+        }
+    }
+
+    /// Tries to load tokens flow info.
+    pub fn load_value_flow(&self) -> Result<ValueFlow, Error> {
+        self.block.load_value_flow()
+    }
+
+    /// Tries to load state update.
+    pub fn load_state_update(&self) -> Result<MerkleUpdate, Error> {
+        self.block.load_state_update()
+    }
+
+    /// Tries to load block content.
+    pub fn load_extra(&self) -> Result<BlockExtra, Error> {
+        self.block.load_extra()
+    }
+
+    /// Tries to load additional masterchain block content.
+    ///
+    /// Returns `None` for shard blocks.
+    pub fn load_mc_extra(&self) -> Result<Option<McBlockExtra>, Error> {
+        if !self.info.shard.is_masterchain() {
+            return Ok(None);
+        }
+        match ok!(self.load_extra()).custom {
+            Some(custom) => custom.load().map(Some),
+            None => Ok(None),
+        }
+    }
+
+    /// Tries to load the blockchain config.
+    ///
+    /// Returns `None` for non-key blocks.
+    pub fn load_config(&self) -> Result<Option<BlockchainConfig>, Error> {
+        if !self.info.key_block {
+            return Ok(None);
+        }
+        match ok!(self.load_mc_extra()) {
+            Some(mc_extra) => Ok(mc_extra.config),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns the underlying block.
+    #[inline]
+    pub fn as_block(&self) -> &Block {
+        &self.block
+    }
+
+    /// Converts this wrapper into the underlying block.
+    #[inline]
+    pub fn into_block(self) -> Block {
+        self.block
+    }
+}
+
+impl AsRef<DynCell> for LazyBlock {
+    #[inline]
+    fn as_ref(&self) -> &DynCell {
+        self.root.as_ref()
+    }
+}
@@ -9,16 +9,20 @@ use crate::util::*;
 
 use crate::models::currency::CurrencyCollection;
 use crate::models::global_version::GlobalVersion;
-use crate::models::Lazy;
+use crate::models::{FormatVersion, Lazy};
 
 pub use self::block_extra::*;
 pub use self::block_id::*;
+pub use self::block_info_builder::*;
 pub use self::block_proof::*;
+pub use self::lazy_block::*;
 pub use self::shard_hashes::*;
 
 mod block_extra;
 mod block_id;
+mod block_info_builder;
 mod block_proof;
+mod lazy_block;
 mod shard_hashes;
 
 #[cfg(test)]
@@ -68,6 +72,53 @@ impl Block {
         self.extra.load()
     }
 
+    /// Collects hashes of all incoming and outgoing messages of the block.
+    ///
+    /// See [`BlockExtra::collect_message_hashes`] for details.
+    pub fn collect_message_hashes(&self) -> Result<Vec<MsgHashEntry>, Error> {
+        ok!(self.load_extra()).collect_message_hashes()
+    }
+
+    /// Returns the layout of the serialized block without parsing it.
+    ///
+    /// - [`FormatVersion::V1`] - the original layout;
+    /// - [`FormatVersion::V2`] - the layout with outgoing messages queue updates.
+    pub fn peek_format_version(slice: &CellSlice<'_>) -> Result<FormatVersion, Error> {
+        match ok!(slice.get_u32(0)) {
+            Self::TAG_V1 => Ok(FormatVersion::V1),
+            Self::TAG_V2 => Ok(FormatVersion::V2),
+            _ => Err(Error::InvalidTag),
+        }
+    }
+
+    /// Collects layouts of the block and its parts.
+    ///
+    /// NOTE: Only the root cells of the block parts are loaded.
+    pub fn format_report(&self) -> Result<BlockFormatReport, Error> {
+        let block = if self.out_msg_queue_updates.is_some() {
+            FormatVersion::V2
+        } else {
+            FormatVersion::V1
+        };
+
+        let value_flow = ok!(self.value_flow.cell.as_slice());
+        let value_flow = ok!(ValueFlow::peek_format_version(&value_flow));
+
+        let mc_extra = match ok!(self.load_extra()).custom {
+            Some(custom) => {
+                let custom = ok!(custom.cell.as_slice());
+                Some(ok!(McBlockExtra::peek_format_version(&custom)))
+            }
+            None => None,
+        };
+
+        Ok(BlockFormatReport {
+            block,
+            value_flow,
+            mc_extra,
+        })
+    }
+
     /// Builds a data for validators to sign.
     pub fn build_data_for_sign(block_id: &BlockId) -> [u8; Self::DATA_FOR_SIGN_SIZE] {
         let mut data = [0u8; Self::DATA_FOR_SIGN_SIZE];
@@ -78,6 +129,26 @@ impl Block {
     }
 }
 
+/// Layouts of the block and its parts.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct BlockFormatReport {
+    /// Block root layout.
+    pub block: FormatVersion,
+    /// Value flow layout.
+    pub value_flow: FormatVersion,
+    /// Masterchain block extra layout (if present).
+    pub mc_extra: Option<FormatVersion>,
+}
+
+impl BlockFormatReport {
+    /// Returns `true` if all parts use the original layout.
+    pub fn is_legacy(&self) -> bool {
+        self.block.is_legacy()
+            && self.value_flow.is_legacy()
+            && self.mc_extra.map(|v| v.is_legacy()).unwrap_or(true)
+    }
+}
+
 impl Store for Block {
     fn store_into(
         &self,
@@ -178,8 +249,9 @@ pub struct BlockInfo {
     /// Unix timestamp when the block was created.
     pub gen_utime: u32,
     /// Milliseconds part of the timestamp when the block was created.
-    #[cfg(feature = "venom")]
-    pub gen_utime_ms: u16,
+    ///
+    /// NOTE: Only used in networks with fast finality.
+    pub gen_utime_ms: Option<u16>,
     /// Logical time range start.
     pub start_lt: u64,
     /// Logical time range end.
@@ -206,7 +278,6 @@ pub struct BlockInfo {
 
 impl BlockInfo {
     const TAG_V1: u32 = 0x9bc7a987;
-    #[cfg(feature = "venom")]
     const TAG_V2: u32 = 0x9bc7a988;
     const FLAG_WITH_GEN_SOFTWARE: u8 = 0x1;
 
@@ -222,6 +293,12 @@ impl BlockInfo {
     pub fn load_prev_ref(&self) -> Result<PrevBlockRef, Error> {
         PrevBlockRef::load_from_cell(&self.prev_ref, self.after_merge)
     }
+
+    /// Returns `true` if the logical time is within the block
+    /// logical time window.
+    pub const fn contains_lt(&self, lt: u64) -> bool {
+        crate::models::time::is_lt_in_block(lt, self.start_lt, self.end_lt)
+    }
 }
 
 impl Store for BlockInfo {
@@ -239,19 +316,22 @@ impl Store for BlockInfo {
             | ((self.key_block as u8) << 1)
             | (self.prev_vert_ref.is_some() as u8);
 
-        #[cfg(not(feature = "venom"))]
-        ok!(builder.store_u32(Self::TAG_V1));
-        #[cfg(feature = "venom")]
-        ok!(builder.store_u32(Self::TAG_V2));
+        let tag = if self.gen_utime_ms.is_some() {
+            Self::TAG_V2
+        } else {
+            Self::TAG_V1
+        };
 
+        ok!(builder.store_u32(tag));
         ok!(builder.store_u32(self.version));
         ok!(builder.store_u16(u16::from_be_bytes([packed_flags, self.flags])));
         ok!(builder.store_u32(self.seqno));
         ok!(builder.store_u32(self.vert_seqno));
         ok!(self.shard.store_into(builder, context));
         ok!(builder.store_u32(self.gen_utime));
-        #[cfg(feature = "venom")]
-        ok!(builder.store_u16(self.gen_utime_ms));
+        if let Some(gen_utime_ms) = self.gen_utime_ms {
+            ok!(builder.store_u16(gen_utime_ms));
+        }
         ok!(builder.store_u64(self.start_lt));
         ok!(builder.store_u64(self.end_lt));
         ok!(builder.store_u32(self.gen_validator_list_hash_short));
@@ -281,15 +361,11 @@ impl<'a> Load<'a> for BlockInfo {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
         let with_ms = match slice.load_u32() {
             Ok(Self::TAG_V1) => false,
-            #[cfg(feature = "venom")]
             Ok(Self::TAG_V2) => true,
             Ok(_) => return Err(Error::InvalidTag),
             Err(e) => return Err(e),
         };
 
-        #[cfg(not(feature = "venom"))]
-        let _ = with_ms;
-
         let version = ok!(slice.load_u32());
         let [packed_flags, flags] = ok!(slice.load_u16()).to_be_bytes();
         let seqno = ok!(slice.load_u32());
@@ -299,8 +375,11 @@ impl<'a> Load<'a> for BlockInfo {
         let vert_seqno = ok!(slice.load_u32());
         let shard = ok!(ShardIdent::load_from(slice));
         let gen_utime = ok!(slice.load_u32());
-        #[cfg(feature = "venom")]
-        let gen_utime_ms = if with_ms { ok!(slice.load_u16()) } else { 0 };
+        let gen_utime_ms = if with_ms {
+            Some(ok!(slice.load_u16()))
+        } else {
+            None
+        };
         let start_lt = ok!(slice.load_u64());
         let end_lt = ok!(slice.load_u64());
         let gen_validator_list_hash_short = ok!(slice.load_u32());
@@ -345,7 +424,6 @@ impl<'a> Load<'a> for BlockInfo {
             vert_seqno,
             shard,
             gen_utime,
-            #[cfg(feature = "venom")]
             gen_utime_ms,
             start_lt,
             end_lt,
@@ -502,6 +580,18 @@ pub struct ValueFlow {
 impl ValueFlow {
     const TAG_V1: u32 = 0xb8e48dfb;
     const TAG_V2: u32 = 0xe0864f6d;
+
+    /// Returns the layout of the serialized value flow without parsing it.
+    ///
+    /// - [`FormatVersion::V1`] - the original layout;
+    /// - [`FormatVersion::V2`] - the layout with copyleft rewards.
+    pub fn peek_format_version(slice: &CellSlice<'_>) -> Result<FormatVersion, Error> {
+        match ok!(slice.get_u32(0)) {
+            Self::TAG_V1 => Ok(FormatVersion::V1),
+            Self::TAG_V2 => Ok(FormatVersion::V2),
+            _ => Err(Error::InvalidTag),
+        }
+    }
 }
 
 impl Store for ValueFlow {
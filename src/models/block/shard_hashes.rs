@@ -82,6 +82,115 @@ impl ShardHashes {
     pub fn contains_workchain<Q>(&self, workchain: i32) -> Result<bool, Error> {
         self.0.contains_key(workchain)
     }
+
+    /// Computes the shards configuration delta between this (older)
+    /// and the specified (newer) shard hashes.
+    ///
+    /// Changes are sorted by the shard ident of the widest shard involved.
+    /// Shards with the same latest block are omitted.
+    pub fn diff(&self, next: &Self) -> Result<Vec<ShardChange>, Error> {
+        let prev = ok!(self.latest_blocks().collect::<Result<Vec<_>, _>>());
+        let next = ok!(next.latest_blocks().collect::<Result<Vec<_>, _>>());
+
+        // NOTE: Shards of the same workchain are either nested or disjoint,
+        // so each change is rooted at a single shard.
+        let mut changes = Vec::new();
+        for prev_id in &prev {
+            let mut intersecting = next.iter().filter(|id| prev_id.shard.intersects(&id.shard));
+            let change = match intersecting.next() {
+                None => ShardChange::Removed(*prev_id),
+                Some(next_id) if next_id.shard == prev_id.shard => {
+                    if next_id == prev_id {
+                        continue;
+                    }
+                    ShardChange::Updated {
+                        prev: *prev_id,
+                        next: *next_id,
+                    }
+                }
+                Some(next_id) if prev_id.shard.is_ancestor_of(&next_id.shard) => {
+                    let mut into = vec![*next_id];
+                    into.extend(intersecting.copied());
+                    ShardChange::Split {
+                        prev: *prev_id,
+                        into,
+                    }
+                }
+                // Merges are handled below
+                Some(_) => continue,
+            };
+            changes.push((prev_id.shard, change));
+        }
+
+        for next_id in &next {
+            let mut intersecting = prev.iter().filter(|id| next_id.shard.intersects(&id.shard));
+            let change = match intersecting.next() {
+                None => ShardChange::Added(*next_id),
+                Some(prev_id)
+                    if prev_id.shard != next_id.shard
+                        && next_id.shard.is_ancestor_of(&prev_id.shard) =>
+                {
+                    let mut from = vec![*prev_id];
+                    from.extend(intersecting.copied());
+                    ShardChange::Merged {
+                        from,
+                        next: *next_id,
+                    }
+                }
+                // Splits and updates are handled above
+                Some(_) => continue,
+            };
+            changes.push((next_id.shard, change));
+        }
+
+        changes.sort_by_key(|(shard, _)| *shard);
+        Ok(changes.into_iter().map(|(_, change)| change).collect())
+    }
+}
+
+/// Shards configuration change between two masterchain blocks.
+///
+/// See [`ShardHashes::diff`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ShardChange {
+    /// A new shard appeared (e.g. with a new workchain).
+    Added(BlockId),
+    /// The shard disappeared (e.g. with a removed workchain).
+    Removed(BlockId),
+    /// The shard has a new latest block.
+    Updated {
+        /// The previous latest block.
+        prev: BlockId,
+        /// The new latest block.
+        next: BlockId,
+    },
+    /// The shard was split into several shards.
+    Split {
+        /// The latest block of the split shard.
+        prev: BlockId,
+        /// The latest blocks of the descendant shards.
+        into: Vec<BlockId>,
+    },
+    /// Several shards were merged into one.
+    Merged {
+        /// The latest blocks of the merged shards.
+        from: Vec<BlockId>,
+        /// The latest block of the common ancestor shard.
+        next: BlockId,
+    },
+}
+
+impl ShardChange {
+    /// Returns the latest blocks of all shards which exist after this change.
+    pub fn next_blocks(&self) -> &[BlockId] {
+        match self {
+            Self::Added(next) | Self::Updated { next, .. } | Self::Merged { next, .. } => {
+                std::slice::from_ref(next)
+            }
+            Self::Removed(_) => &[],
+            Self::Split { into, .. } => into,
+        }
+    }
 }
 
 /// A tree of the most recent descriptions for all currently existing shards
@@ -403,7 +512,8 @@ pub struct ShardDescription {
     /// Proofs from other workchains.
     pub proof_chain: Option<ProofChain>,
     /// Collator ranges for all possible validator sets.
-    #[cfg(feature = "venom")]
+    ///
+    /// NOTE: Only used in networks with fast finality.
     pub collators: Option<ShardCollators>,
 }
 
@@ -414,7 +524,6 @@ impl ShardDescription {
     const TAG_V2: u8 = 0xb;
     const TAG_V3: u8 = 0xc;
     const TAG_V4: u8 = 0xd;
-    #[cfg(feature = "venom")]
     const TAG_V5: u8 = 0xe;
 }
 
@@ -424,22 +533,19 @@ impl Store for ShardDescription {
         builder: &mut CellBuilder,
         context: &mut dyn CellContext,
     ) -> Result<(), Error> {
-        #[allow(unused_mut)]
-        let mut tag = if self.proof_chain.is_some() {
+        let tag = if self.collators.is_some() {
+            // Copyleft rewards are not supported in fast finality mode.
+            if !self.copyleft_rewards.is_empty() {
+                return Err(Error::InvalidData);
+            }
+            Self::TAG_V5
+        } else if self.proof_chain.is_some() {
             Self::TAG_V4
         } else if !self.copyleft_rewards.is_empty() {
             Self::TAG_V3
         } else {
             Self::TAG_V1
         };
-        #[cfg(feature = "venom")]
-        if self.collators.is_some() {
-            // Copyleft rewards are not supported in fast finality mode.
-            if !self.copyleft_rewards.is_empty() {
-                return Err(Error::InvalidData);
-            }
-            tag = Self::TAG_V5;
-        }
 
         let flags = ((self.before_split as u8) << 7)
             | ((self.before_merge as u8) << 6)
@@ -466,26 +572,19 @@ impl Store for ShardDescription {
             ok!(self.fees_collected.store_into(&mut builder, context));
             ok!(self.funds_created.store_into(&mut builder, context));
 
-            #[allow(unused_labels)]
-            'cell: {
-                #[cfg(feature = "venom")]
-                if self.collators.is_some() {
-                    ok!(self.proof_chain.store_into(&mut builder, context));
-                    ok!(self.collators.store_into(&mut builder, context));
-                    break 'cell;
-                }
-
-                if let Some(proof_chain) = &self.proof_chain {
-                    ok!(if self.copyleft_rewards.is_empty() {
-                        builder.store_bit_zero()
-                    } else {
-                        ok!(builder.store_bit_one());
-                        self.copyleft_rewards.store_into(&mut builder, context)
-                    });
-                    ok!(proof_chain.store_into(&mut builder, context));
-                } else if !self.copyleft_rewards.is_empty() {
-                    ok!(self.copyleft_rewards.store_into(&mut builder, context));
-                }
+            if self.collators.is_some() {
+                ok!(self.proof_chain.store_into(&mut builder, context));
+                ok!(self.collators.store_into(&mut builder, context));
+            } else if let Some(proof_chain) = &self.proof_chain {
+                ok!(if self.copyleft_rewards.is_empty() {
+                    builder.store_bit_zero()
+                } else {
+                    ok!(builder.store_bit_one());
+                    self.copyleft_rewards.store_into(&mut builder, context)
+                });
+                ok!(proof_chain.store_into(&mut builder, context));
+            } else if !self.copyleft_rewards.is_empty() {
+                ok!(self.copyleft_rewards.store_into(&mut builder, context));
             }
 
             ok!(builder.build_ext(context))
@@ -497,22 +596,17 @@ impl Store for ShardDescription {
 
 impl<'a> Load<'a> for ShardDescription {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
-        #[allow(unused_mut)]
         let (cont_in_cell, with_copyleft, mut with_proof_chain, with_collators) =
             match slice.load_small_uint(Self::TAG_LEN) {
                 Ok(Self::TAG_V1) => (true, false, false, false),
                 Ok(Self::TAG_V2) => (false, false, false, false),
                 Ok(Self::TAG_V3) => (true, true, false, false),
                 Ok(Self::TAG_V4) => (true, true, true, false),
-                #[cfg(feature = "venom")]
                 Ok(Self::TAG_V5) => (true, false, true, true),
                 Ok(_) => return Err(Error::InvalidTag),
                 Err(e) => return Err(e),
             };
 
-        #[cfg(not(feature = "venom"))]
-        let _ = with_collators;
-
         let seqno = ok!(slice.load_u32());
         let reg_mc_seqno = ok!(slice.load_u32());
         let start_lt = ok!(slice.load_u64());
@@ -550,20 +644,23 @@ impl<'a> Load<'a> for ShardDescription {
             Dict::new()
         };
 
-        #[cfg(feature = "venom")]
         if with_collators && !ok!(slice.load_bit()) {
             // Emulate optional bit
             with_proof_chain = false;
         }
 
         let proof_chain = if with_proof_chain {
-            Some(ok!(ProofChain::load_from(slice)))
+            // NOTE: Proof chain length is not limited in fast finality mode
+            Some(ok!(ProofChain::load_ext(slice, !with_collators)))
         } else {
             None
         };
 
-        #[cfg(feature = "venom")]
-        let collators = ok!(Option::<ShardCollators>::load_from(slice));
+        let collators = if with_collators {
+            ok!(Option::<ShardCollators>::load_from(slice))
+        } else {
+            None
+        };
 
         Ok(Self {
             seqno,
@@ -586,7 +683,6 @@ impl<'a> Load<'a> for ShardDescription {
             funds_created,
             copyleft_rewards,
             proof_chain,
-            #[cfg(feature = "venom")]
             collators,
         })
     }
@@ -688,11 +784,10 @@ impl Store for ProofChain {
     }
 }
 
-impl<'a> Load<'a> for ProofChain {
-    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+impl ProofChain {
+    fn load_ext(slice: &mut CellSlice<'_>, check_len: bool) -> Result<Self, Error> {
         let len = ok!(slice.load_u8());
-        #[cfg(not(feature = "venom"))]
-        if !(1..=8).contains(&len) {
+        if check_len && !(1..=8).contains(&len) {
             return Err(Error::InvalidData);
         }
         Ok(Self {
@@ -702,8 +797,14 @@ impl<'a> Load<'a> for ProofChain {
     }
 }
 
+impl<'a> Load<'a> for ProofChain {
+    #[inline]
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        Self::load_ext(slice, true)
+    }
+}
+
 /// Collator range description.
-#[cfg(feature = "venom")]
 #[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
 pub struct CollatorRange {
     /// Collator index in validator set.
@@ -715,7 +816,6 @@ pub struct CollatorRange {
 }
 
 /// Collator ranges for all possible validator sets.
-#[cfg(feature = "venom")]
 #[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
 #[tlb(tag = "#1")]
 pub struct ShardCollators {
@@ -734,7 +834,6 @@ pub struct ShardCollators {
 }
 
 /// Shard block reference.
-#[cfg(feature = "venom")]
 #[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
 pub struct ShardBlockRef {
     /// Sequence number of the referenced block.
@@ -749,11 +848,9 @@ pub struct ShardBlockRef {
 
 /// A tree of the most recent shard block references for all currently existing shards
 /// for all workchains except the masterchain.
-#[cfg(feature = "venom")]
 #[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
 pub struct ShardBlockRefs(Dict<i32, Cell>);
 
-#[cfg(feature = "venom")]
 impl ShardBlockRefs {
     /// Gets an iterator over the entries of the shard block references trees, sorted by
     /// shard ident. The iterator element is `Result<(ShardIdent, ShardBlockRef)>`.
@@ -798,12 +895,10 @@ impl ShardBlockRefs {
 ///
 /// [`iter`]: ShardBlockRefs::iter
 #[derive(Clone)]
-#[cfg(feature = "venom")]
 pub struct ShardBlockRefsIter<'a> {
     inner: ShardsTreeRawIter<'a>,
 }
 
-#[cfg(feature = "venom")]
 impl<'a> ShardBlockRefsIter<'a> {
     fn new(dict: &'a Option<Cell>) -> Self {
         Self {
@@ -812,7 +907,6 @@ impl<'a> ShardBlockRefsIter<'a> {
     }
 }
 
-#[cfg(feature = "venom")]
 impl Iterator for ShardBlockRefsIter<'_> {
     type Item = Result<(ShardIdent, ShardBlockRef), Error>;
 
@@ -829,14 +923,12 @@ impl Iterator for ShardBlockRefsIter<'_> {
 
 /// A tree of the most recent shard block references for all currently existing shards
 /// for a single workchain.
-#[cfg(feature = "venom")]
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct WorkchainShardBlockRefs {
     workchain: i32,
     root: Cell,
 }
 
-#[cfg(feature = "venom")]
 impl WorkchainShardBlockRefs {
     /// Gets an iterator over the keys of the shard block references tree, sorted by key.
     /// The iterator element type is `Result<ShardIdent>`.
@@ -882,12 +974,10 @@ impl WorkchainShardBlockRefs {
 ///
 /// [`iter`]: WorkchainShardBlockRefs::iter
 #[derive(Clone)]
-#[cfg(feature = "venom")]
 pub struct WorkchainShardBlockRefsIter<'a> {
     inner: WorkchainShardsTreeRawIter<'a>,
 }
 
-#[cfg(feature = "venom")]
 impl<'a> WorkchainShardBlockRefsIter<'a> {
     /// Creates an iterator over the entries of a [`WorkchainShardBlockRefs`].
     pub fn new(workchain: i32, root: &'a DynCell) -> Self {
@@ -897,7 +987,6 @@ impl<'a> WorkchainShardBlockRefsIter<'a> {
     }
 }
 
-#[cfg(feature = "venom")]
 impl Iterator for WorkchainShardBlockRefsIter<'_> {
     type Item = Result<(ShardIdent, ShardBlockRef), Error>;
 
@@ -1197,6 +1286,7 @@ mod test {
             funds_created: Default::default(),
             copyleft_rewards: Default::default(),
             proof_chain: None,
+            collators: None,
         };
         // arbitrary order
         let input = HashMap::from([
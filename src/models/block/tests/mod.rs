@@ -1,6 +1,7 @@
 use std::collections::HashMap;
 
 use super::*;
+use crate::error::ProofChainError;
 use crate::prelude::*;
 
 fn serialize_any<T: Store>(data: T) -> Cell {
@@ -145,6 +146,95 @@ fn masterchain_block_with_shards() {
     );
 }
 
+#[test]
+fn shard_hashes_diff() {
+    let block = Boc::decode(include_bytes!("mc_block_with_shards.boc"))
+        .unwrap()
+        .parse::<Block>()
+        .unwrap();
+    let custom = block.load_extra().unwrap().load_custom().unwrap().unwrap();
+    let (_, template) = custom.shards.iter().next().unwrap().unwrap();
+
+    let make_shards = |shards: &[(i32, u64, u32)]| {
+        let shards = shards
+            .iter()
+            .map(|&(workchain, prefix, seqno)| {
+                let mut descr = template.clone();
+                descr.seqno = seqno;
+                descr.root_hash = HashBytes([seqno as u8; 32]);
+                (ShardIdent::new(workchain, prefix).unwrap(), descr)
+            })
+            .collect::<Vec<_>>();
+        ShardHashes::from_shards(shards.iter().map(|(ident, descr)| (ident, descr))).unwrap()
+    };
+    let block_id = |workchain: i32, prefix: u64, seqno: u32| BlockId {
+        shard: ShardIdent::new(workchain, prefix).unwrap(),
+        seqno,
+        root_hash: HashBytes([seqno as u8; 32]),
+        file_hash: template.file_hash,
+    };
+
+    let prev = make_shards(&[
+        (0, 0x4000000000000000, 10),
+        (0, 0xa000000000000000, 20),
+        (0, 0xe000000000000000, 30),
+    ]);
+    assert!(prev.diff(&prev).unwrap().is_empty());
+
+    let next = make_shards(&[
+        (0, 0x2000000000000000, 11),
+        (0, 0x6000000000000000, 12),
+        (0, 0xc000000000000000, 31),
+        (1, 0x8000000000000000, 1),
+    ]);
+
+    let split = ShardChange::Split {
+        prev: block_id(0, 0x4000000000000000, 10),
+        into: vec![
+            block_id(0, 0x2000000000000000, 11),
+            block_id(0, 0x6000000000000000, 12),
+        ],
+    };
+    let merged = ShardChange::Merged {
+        from: vec![
+            block_id(0, 0xa000000000000000, 20),
+            block_id(0, 0xe000000000000000, 30),
+        ],
+        next: block_id(0, 0xc000000000000000, 31),
+    };
+    let added = ShardChange::Added(block_id(1, 0x8000000000000000, 1));
+
+    let diff = prev.diff(&next).unwrap();
+    assert_eq!(diff, [split.clone(), merged, added]);
+    assert_eq!(diff[0].next_blocks().len(), 2);
+    assert_eq!(diff[1].next_blocks(), [block_id(0, 0xc000000000000000, 31)]);
+
+    // Reverse order
+    let diff = next.diff(&prev).unwrap();
+    assert_eq!(diff.len(), 3);
+    assert!(matches!(&diff[0], ShardChange::Merged { from, .. } if from.len() == 2));
+    assert!(matches!(&diff[1], ShardChange::Split { into, .. } if into.len() == 2));
+    assert_eq!(
+        diff[2],
+        ShardChange::Removed(block_id(1, 0x8000000000000000, 1))
+    );
+    assert!(diff[2].next_blocks().is_empty());
+
+    // Updates
+    let next = make_shards(&[
+        (0, 0x4000000000000000, 10),
+        (0, 0xa000000000000000, 21),
+        (0, 0xe000000000000000, 30),
+    ]);
+    assert_eq!(
+        prev.diff(&next).unwrap(),
+        [ShardChange::Updated {
+            prev: block_id(0, 0xa000000000000000, 20),
+            next: block_id(0, 0xa000000000000000, 21),
+        }]
+    );
+}
+
 #[test]
 fn shard_block_empty() {
     check_block(include_bytes!("empty_shard_block.boc"), None);
@@ -155,6 +245,183 @@ fn shard_block_with_messages() {
     check_block(include_bytes!("simple_shard_block.boc"), None);
 }
 
+#[test]
+fn block_info_builder() {
+    fn rebuild(info: &BlockInfo) -> Result<BlockInfo, Error> {
+        let mut builder = match info.load_prev_ref().unwrap() {
+            PrevBlockRef::Single(prev) if info.after_split => {
+                BlockInfoBuilder::after_split(info.shard, prev)
+            }
+            PrevBlockRef::Single(prev) => BlockInfoBuilder::new(info.shard, prev),
+            PrevBlockRef::AfterMerge { left, right } => {
+                BlockInfoBuilder::after_merge(info.shard, left, right)
+            }
+        }
+        .with_version(info.version)
+        .with_gen_utime(info.gen_utime)
+        .with_lt_range(info.start_lt, info.end_lt)
+        .with_vert_seqno(info.vert_seqno)
+        .with_before_split(info.before_split)
+        .with_split_merge_hints(info.want_split, info.want_merge)
+        .with_key_block(info.key_block)
+        .with_catchain_info(info.gen_validator_list_hash_short, info.gen_catchain_seqno)
+        .with_min_ref_mc_seqno(info.min_ref_mc_seqno)
+        .with_prev_key_block_seqno(info.prev_key_block_seqno);
+        if info.flags & BlockInfo::FLAG_WITH_GEN_SOFTWARE != 0 {
+            builder = builder.with_gen_software(info.gen_software);
+        }
+        if let Some(master_ref) = info.load_master_ref().unwrap() {
+            builder = builder.with_master_ref(master_ref);
+        }
+        if let Some(prev_vert_ref) = &info.prev_vert_ref {
+            builder = builder.with_prev_vert_ref(prev_vert_ref.load().unwrap());
+        }
+        builder.build()
+    }
+
+    for boc in [
+        &include_bytes!("mc_key_block.boc")[..],
+        include_bytes!("mc_simple_block.boc"),
+        include_bytes!("mc_block_with_shards.boc"),
+        include_bytes!("empty_shard_block.boc"),
+        include_bytes!("simple_shard_block.boc"),
+    ] {
+        let block = Boc::decode(boc).unwrap().parse::<Block>().unwrap();
+        let info = block.load_info().unwrap();
+        assert_eq!(rebuild(&info).unwrap(), info);
+    }
+
+    let prev = BlockRef {
+        end_lt: 1_000_100,
+        seqno: 10,
+        root_hash: HashBytes([1; 32]),
+        file_hash: HashBytes([2; 32]),
+    };
+    let master_ref = BlockRef {
+        end_lt: 1_500_000,
+        seqno: 5,
+        root_hash: HashBytes([3; 32]),
+        file_hash: HashBytes([4; 32]),
+    };
+    let shard = ShardIdent::new_full(0);
+    let builder = BlockInfoBuilder::new(shard, prev.clone())
+        .with_gen_utime(100)
+        .with_prev_gen_utime(99, None)
+        .with_lt_range(2_000_000, 2_000_010)
+        .with_master_ref(master_ref.clone());
+
+    let info = builder.clone().build().unwrap();
+    assert_eq!(info.seqno, 11);
+    assert!(!info.after_merge && !info.after_split);
+    assert_eq!(info.load_master_ref().unwrap(), Some(master_ref.clone()));
+    assert_eq!(
+        info.load_prev_ref().unwrap(),
+        PrevBlockRef::Single(prev.clone())
+    );
+
+    // Shard blocks must reference the masterchain
+    assert!(BlockInfoBuilder::new(shard, prev.clone())
+        .with_lt_range(2_000_000, 2_000_010)
+        .build()
+        .is_err());
+    // Masterchain blocks can't reference the masterchain
+    assert!(BlockInfoBuilder::new(ShardIdent::MASTERCHAIN, prev.clone())
+        .with_lt_range(2_000_000, 2_000_010)
+        .with_master_ref(master_ref.clone())
+        .build()
+        .is_err());
+    // Full shard can't be produced after split
+    assert!(BlockInfoBuilder::after_split(shard, prev.clone())
+        .with_lt_range(2_000_000, 2_000_010)
+        .with_master_ref(master_ref.clone())
+        .build()
+        .is_err());
+    // Only masterchain blocks can be key blocks
+    assert!(builder.clone().with_key_block(true).build().is_err());
+    // Logical time must start after the masterchain block
+    assert!(builder
+        .clone()
+        .with_lt_range(1_200_000, 2_000_010)
+        .build()
+        .is_err());
+    // Creation time must increase
+    assert!(builder
+        .clone()
+        .with_prev_gen_utime(100, None)
+        .build()
+        .is_err());
+    assert!(builder
+        .clone()
+        .with_prev_gen_utime(100, None)
+        .with_gen_utime_ms(1)
+        .build()
+        .is_ok());
+    // Vertical block reference requires vertical seqno
+    assert!(builder
+        .clone()
+        .with_prev_vert_ref(prev.clone())
+        .build()
+        .is_err());
+
+    // Merge
+    let (left, right) = shard.split().unwrap();
+    let merged = BlockInfoBuilder::after_merge(shard, prev.clone(), BlockRef { seqno: 12, ..prev })
+        .with_lt_range(2_000_000, 2_000_010)
+        .with_master_ref(master_ref.clone())
+        .build()
+        .unwrap();
+    assert!(merged.after_merge);
+    assert_eq!(merged.seqno, 13);
+    // Merged block can't be split right away
+    assert!(
+        BlockInfoBuilder::after_merge(shard, prev.clone(), BlockRef { seqno: 12, ..prev })
+            .with_lt_range(2_000_000, 2_000_010)
+            .with_master_ref(master_ref.clone())
+            .with_before_split(true)
+            .build()
+            .is_err()
+    );
+    assert!(left.merge() == Some(shard) && right.merge() == Some(shard));
+}
+
+#[test]
+fn block_message_hashes() {
+    for boc in [
+        &include_bytes!("simple_shard_block.boc")[..],
+        include_bytes!("mc_block_with_shards.boc"),
+        include_bytes!("empty_shard_block.boc"),
+    ] {
+        let block = Boc::decode(boc).unwrap().parse::<Block>().unwrap();
+        let entries = block.collect_message_hashes().unwrap();
+        let entries = entries
+            .into_iter()
+            .map(|entry| ((entry.hash, entry.direction), entry.account))
+            .collect::<HashMap<_, _>>();
+
+        let mut expected = 0;
+        let account_blocks = block.load_extra().unwrap().account_blocks.load().unwrap();
+        for entry in account_blocks.iter() {
+            let (account, _, account_block) = entry.unwrap();
+            for entry in account_block.transactions.iter() {
+                let (_, _, tx) = entry.unwrap();
+                let tx = tx.load().unwrap();
+
+                if let Some(in_msg) = &tx.in_msg {
+                    let key = (*in_msg.repr_hash(), MsgDirection::In);
+                    assert_eq!(entries.get(&key), Some(&Some(account)));
+                    expected += 1;
+                }
+                for out_msg in tx.out_msgs.values() {
+                    let key = (*out_msg.unwrap().repr_hash(), MsgDirection::Out);
+                    assert_eq!(entries.get(&key), Some(&Some(account)));
+                    expected += 1;
+                }
+            }
+        }
+        assert!(entries.len() >= expected);
+    }
+}
+
 #[test]
 fn parse_block_id() {
     let block_id = BlockId {
@@ -167,6 +434,19 @@ fn parse_block_id() {
     let s = block_id.to_string();
     println!("S: {s}");
     assert_eq!(s.parse::<BlockId>().unwrap(), block_id);
+
+    let (left, _) = ShardIdent::BASECHAIN.split().unwrap();
+    assert_eq!(format!("{left}"), "0:4000000000000000");
+    assert_eq!(format!("{left:#}"), "0:4000000000000000 (depth 1)");
+    assert_eq!(format!("{left:?}"), "0:4000000000000000");
+    assert_eq!(format!("{left:#?}"), "0:4000000000000000");
+    assert_eq!(
+        format!("{block_id:#}"),
+        format!(
+            "-1:8000000000000000:123321 (root_hash: {}, file_hash: {})",
+            block_id.root_hash, block_id.file_hash
+        )
+    );
 }
 
 #[test]
@@ -342,3 +622,441 @@ fn proof_for_shardchain_block() {
 
     assert_eq!(serialize_any(proof).as_ref(), boc.as_ref());
 }
+
+#[test]
+fn block_format_report() {
+    for (boc, is_masterchain) in [
+        (&include_bytes!("mc_simple_block.boc")[..], true),
+        (include_bytes!("mc_key_block.boc"), true),
+        (include_bytes!("simple_shard_block.boc"), false),
+    ] {
+        let cell = Boc::decode(boc).unwrap();
+        let block = cell.parse::<Block>().unwrap();
+
+        let report = block.format_report().unwrap();
+        assert_eq!(
+            report.block,
+            Block::peek_format_version(&cell.as_slice().unwrap()).unwrap()
+        );
+        assert_eq!(report.mc_extra.is_some(), is_masterchain);
+
+        let value_flow = block.load_value_flow().unwrap();
+        assert_eq!(
+            report.value_flow.is_legacy(),
+            value_flow.copyleft_rewards.is_empty()
+        );
+    }
+
+    let mut builder = CellBuilder::new();
+    builder.store_u32(0xdeafbeaf).unwrap();
+    let cell = builder.build().unwrap();
+    assert!(Block::peek_format_version(&cell.as_slice().unwrap()).is_err());
+}
+
+#[test]
+fn fast_finality_layouts() {
+    fn tag_of(cell: &Cell) -> u32 {
+        cell.as_slice().unwrap().load_u32().unwrap()
+    }
+
+    let cell = Boc::decode(include_bytes!("simple_shard_block.boc")).unwrap();
+    let block = cell.parse::<Block>().unwrap();
+
+    // Block info
+    let mut info = block.load_info().unwrap();
+    assert_eq!(info.gen_utime_ms, None);
+    assert_eq!(tag_of(&serialize_any(&info)), BlockInfo::TAG_V1);
+
+    info.gen_utime_ms = Some(123);
+    let serialized = serialize_any(&info);
+    assert_eq!(tag_of(&serialized), BlockInfo::TAG_V2);
+    assert_eq!(serialized.parse::<BlockInfo>().unwrap(), info);
+
+    // Block extra
+    let mut extra = block.load_extra().unwrap();
+    assert!(extra.shard_block_refs.is_none());
+
+    let empty_refs = CellBuilder::build_from(false).unwrap();
+    extra.shard_block_refs = Some(empty_refs.parse::<ShardBlockRefs>().unwrap());
+    let serialized = serialize_any(&extra);
+    assert_eq!(tag_of(&serialized), 0x4a33f6fc);
+
+    let parsed = serialized.parse::<BlockExtra>().unwrap();
+    assert_eq!(parsed.shard_block_refs, extra.shard_block_refs);
+    assert_eq!(serialize_any(&parsed), serialized);
+}
+
+#[test]
+fn validator_bitmap() {
+    use std::num::NonZeroU16;
+
+    use crate::models::config::{ValidatorDescription, ValidatorSet};
+
+    let list = (0..10u64)
+        .map(|i| ValidatorDescription {
+            public_key: HashBytes([i as u8; 32]),
+            weight: i + 1,
+            adnl_addr: None,
+            mc_seqno_since: 0,
+            prev_total_weight: 0,
+        })
+        .collect::<Vec<_>>();
+    let vset = ValidatorSet {
+        utime_since: 0,
+        utime_until: 0,
+        main: NonZeroU16::new(10).unwrap(),
+        total_weight: list.iter().map(|item| item.weight).sum(),
+        list,
+    };
+    assert_eq!(vset.total_weight, 55);
+
+    let mut bitmap = ValidatorBitmap::new(10);
+    assert!(!bitmap.is_empty());
+    assert_eq!(bitmap.weight(&vset.list), Some(0));
+    assert!(!bitmap.set(10, true));
+
+    for index in [0, 5, 8, 9] {
+        assert!(bitmap.set(index, true));
+    }
+    assert_eq!(bitmap.count_ones(), 4);
+    assert_eq!(bitmap.iter_ones().collect::<Vec<_>>(), [0, 5, 8, 9]);
+    assert_eq!(bitmap.weight(&vset.list), Some(1 + 6 + 9 + 10));
+    assert!(!bitmap.has_quorum(&vset));
+    assert_eq!(bitmap.weight(&vset.list[..9]), None);
+
+    for index in [6, 7] {
+        bitmap.set(index, true);
+    }
+    assert_eq!(bitmap.weight(&vset.list), Some(41));
+    assert!(bitmap.has_quorum(&vset));
+
+    bitmap.set(0, false);
+    assert!(!bitmap.get(0));
+    assert!(!bitmap.get(100));
+
+    let cell = serialize_any(&bitmap);
+    assert_eq!(cell.bit_len(), 16 + 10);
+    assert_eq!(cell.parse::<ValidatorBitmap>().unwrap(), bitmap);
+}
+
+#[test]
+fn proof_chain_errors() {
+    let boc = Boc::decode(include_bytes!("mc_block_proof.boc")).unwrap();
+    let proof = boc.parse::<BlockProof>().unwrap();
+
+    // Proof is not for the key block
+    let err = BlockProofChain::from_key_block_proof(&proof).unwrap_err();
+    assert!(matches!(err, ProofChainError::NotKeyBlock));
+
+    let key_block = Boc::decode(include_bytes!("mc_key_block.boc"))
+        .unwrap()
+        .parse::<Block>()
+        .unwrap();
+    let extra = key_block.load_extra().unwrap();
+    let config = extra.custom.unwrap().load().unwrap().config.unwrap();
+    let vset = config.get_current_validator_set().unwrap();
+    let cc_config = config.get_catchain_config().unwrap();
+
+    let key_block_id = BlockId {
+        seqno: 13108149,
+        ..proof.proof_for
+    };
+    let mut chain = BlockProofChain::new(key_block_id, vset.clone(), cc_config);
+
+    // Validators from the unrelated key block
+    let err = chain.check_link(&proof).unwrap_err();
+    assert!(matches!(err, ProofChainError::ValidatorSetMismatch));
+
+    let err = chain.verify(&[], &proof.proof_for).unwrap_err();
+    assert!(matches!(err, ProofChainError::EmptyChain));
+    let err = chain
+        .verify(std::slice::from_ref(&proof), &key_block_id)
+        .unwrap_err();
+    assert!(matches!(err, ProofChainError::TargetMismatch));
+
+    // Different previous key block
+    let mut chain = BlockProofChain::new(proof.proof_for, vset, cc_config);
+    let err = chain.check_link(&proof).unwrap_err();
+    assert!(matches!(
+        err,
+        ProofChainError::PrevKeyBlockMismatch {
+            expected: 13121100,
+            actual: 13108149
+        }
+    ));
+
+    // Wrong root hash
+    let mut invalid = proof.clone();
+    invalid.proof_for.root_hash = HashBytes::ZERO;
+    let err = chain.check_link(&invalid).unwrap_err();
+    assert!(matches!(err, ProofChainError::BlockIdMismatch));
+
+    // Shard block proof
+    let boc = Boc::decode(include_bytes!("shard_block_proof.boc")).unwrap();
+    let proof = boc.parse::<BlockProof>().unwrap();
+    let err = chain.check_link(&proof).unwrap_err();
+    assert!(matches!(err, ProofChainError::NotMasterchain));
+}
+
+#[cfg(feature = "abi")]
+#[test]
+fn proof_chain_links() {
+    use std::num::NonZeroU16;
+
+    use ed25519_dalek::{Signer, SigningKey};
+
+    use crate::crypto::Signature;
+    use crate::merkle::MerkleProof;
+    use crate::models::config::{
+        CatchainConfig, ConfigParam34, ValidatorDescription, ValidatorSet,
+    };
+    use crate::models::shard::ValidatorBaseInfo;
+
+    let cc_config = CatchainConfig {
+        isolate_mc_validators: false,
+        shuffle_mc_validators: false,
+        mc_catchain_lifetime: 250,
+        shard_catchain_lifetime: 250,
+        shard_validators_lifetime: 1000,
+        shard_validators_num: 7,
+    };
+
+    let make_keys = |seed: u8| -> Vec<SigningKey> {
+        (0..4)
+            .map(|i| SigningKey::from_bytes(&[seed + i; 32]))
+            .collect()
+    };
+    let make_vset = |keys: &[SigningKey]| -> ValidatorSet {
+        let list = keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| ValidatorDescription {
+                public_key: HashBytes(key.verifying_key().to_bytes()),
+                weight: 10,
+                adnl_addr: None,
+                mc_seqno_since: 0,
+                prev_total_weight: i as u64 * 10,
+            })
+            .collect::<Vec<_>>();
+        ValidatorSet {
+            utime_since: 0,
+            utime_until: u32::MAX,
+            main: NonZeroU16::new(list.len() as u16).unwrap(),
+            total_weight: list.iter().map(|item| item.weight).sum(),
+            list,
+        }
+    };
+
+    let template = Boc::decode(include_bytes!("mc_key_block.boc"))
+        .unwrap()
+        .parse::<Block>()
+        .unwrap();
+
+    // Creates a proof for the block signed by the first `signers` validators
+    let make_proof = |seqno: u32,
+                      prev_key_block_seqno: u32,
+                      vset: &ValidatorSet,
+                      keys: &[SigningKey],
+                      signers: usize,
+                      next_vset: Option<&ValidatorSet>| {
+        let cc_seqno = seqno / 10;
+        let (subset, hash_short) = vset
+            .compute_subset(ShardIdent::MASTERCHAIN, &cc_config, cc_seqno)
+            .unwrap();
+
+        let mut block = template.clone();
+        let mut info = block.load_info().unwrap();
+        info.seqno = seqno;
+        info.key_block = next_vset.is_some();
+        info.prev_key_block_seqno = prev_key_block_seqno;
+        info.gen_catchain_seqno = cc_seqno;
+        info.gen_validator_list_hash_short = hash_short;
+        block.info = Lazy::new(&info).unwrap();
+
+        if let Some(next_vset) = next_vset {
+            let mut extra = block.load_extra().unwrap();
+            let mut custom = extra.custom.unwrap().load().unwrap();
+            let config = custom.config.as_mut().unwrap();
+            config.remove(35).unwrap();
+            config.set::<ConfigParam34>(next_vset).unwrap();
+            config.set_catchain_config(&cc_config).unwrap();
+            extra.custom = Some(Lazy::new(&custom).unwrap());
+            block.extra = Lazy::new(&extra).unwrap();
+        }
+
+        let block_cell = serialize_any(&block);
+        let block_id = BlockId {
+            shard: ShardIdent::MASTERCHAIN,
+            seqno,
+            root_hash: *block_cell.repr_hash(),
+            file_hash: HashBytes([seqno as u8; 32]),
+        };
+
+        let data = Block::build_data_for_sign(&block_id);
+        let mut signatures = Dict::<u16, BlockSignature>::new();
+        for (i, key) in keys.iter().take(signers).enumerate() {
+            let signature = BlockSignature {
+                node_id_short: HashBytes(tl_proto::hash(
+                    everscale_crypto::tl::PublicKey::Ed25519 {
+                        key: key.verifying_key().as_bytes(),
+                    },
+                )),
+                signature: Signature(key.sign(&data).to_bytes()),
+            };
+            signatures.set(i as u16, signature).unwrap();
+        }
+
+        BlockProof {
+            proof_for: block_id,
+            root: serialize_any(MerkleProof {
+                hash: *block_cell.repr_hash(),
+                depth: block_cell.repr_depth(),
+                cell: block_cell,
+            }),
+            signatures: Some(BlockSignatures {
+                validator_info: ValidatorBaseInfo {
+                    validator_list_hash_short: hash_short,
+                    catchain_seqno: cc_seqno,
+                },
+                signature_count: signers as u32,
+                total_weight: subset.iter().map(|item| item.weight).sum(),
+                signatures,
+            }),
+        }
+    };
+
+    let keys0 = make_keys(0);
+    let keys1 = make_keys(10);
+    let vset0 = make_vset(&keys0);
+    let vset1 = make_vset(&keys1);
+
+    let trusted_id = BlockId {
+        shard: ShardIdent::MASTERCHAIN,
+        seqno: 100,
+        root_hash: HashBytes::ZERO,
+        file_hash: HashBytes::ZERO,
+    };
+
+    let key_block = make_proof(200, 100, &vset0, &keys0, 3, Some(&vset1));
+    let target = make_proof(250, 200, &vset1, &keys1, 4, None);
+
+    // Full chain
+    let mut chain = BlockProofChain::new(trusted_id, vset0.clone(), cc_config);
+    let info = chain
+        .verify(&[key_block.clone(), target.clone()], &target.proof_for)
+        .unwrap();
+    assert_eq!(info.seqno, 250);
+    assert_eq!(chain.key_block_id(), &key_block.proof_for);
+    assert_eq!(chain.validator_set(), &vset1);
+
+    // Trusted key block proof
+    let chain = BlockProofChain::from_key_block_proof(&key_block).unwrap();
+    assert_eq!(chain.validator_set(), &vset1);
+    assert_eq!(chain.catchain_config(), &cc_config);
+
+    // Target signed by the old validator set
+    let mut chain = BlockProofChain::new(trusted_id, vset0.clone(), cc_config);
+    let target_old = make_proof(250, 200, &vset0, &keys0, 4, None);
+    let err = chain
+        .verify(
+            &[key_block.clone(), target_old.clone()],
+            &target_old.proof_for,
+        )
+        .unwrap_err();
+    assert!(matches!(err, ProofChainError::ValidatorSetMismatch));
+
+    // Non-key intermediate block
+    let mut chain = BlockProofChain::new(trusted_id, vset0.clone(), cc_config);
+    let non_key = make_proof(150, 100, &vset0, &keys0, 4, None);
+    let err = chain
+        .verify(&[non_key, target.clone()], &target.proof_for)
+        .unwrap_err();
+    assert!(matches!(err, ProofChainError::NotKeyBlock));
+
+    // Not enough signatures
+    let mut chain = BlockProofChain::new(trusted_id, vset0.clone(), cc_config);
+    let weak = make_proof(200, 100, &vset0, &keys0, 2, Some(&vset1));
+    let err = chain.check_link(&weak).unwrap_err();
+    assert!(matches!(
+        err,
+        ProofChainError::NotEnoughWeight {
+            signed: 20,
+            total: 40
+        }
+    ));
+
+    // Duplicate signatures are counted once
+    let mut duplicated = weak.clone();
+    let signatures = &mut duplicated.signatures.as_mut().unwrap().signatures;
+    let first = signatures.get(0).unwrap().unwrap();
+    signatures.set(2, first.clone()).unwrap();
+    signatures.set(3, first).unwrap();
+    let err = chain.check_link(&duplicated).unwrap_err();
+    assert!(matches!(
+        err,
+        ProofChainError::NotEnoughWeight { signed: 20, .. }
+    ));
+
+    // Invalid signature
+    let mut invalid = key_block.clone();
+    let signatures = &mut invalid.signatures.as_mut().unwrap().signatures;
+    let mut first = signatures.get(0).unwrap().unwrap();
+    first.signature.0[0] ^= 1;
+    signatures.set(0, first).unwrap();
+    let err = chain.check_link(&invalid).unwrap_err();
+    assert!(matches!(err, ProofChainError::InvalidSignature));
+
+    // State is not changed on errors
+    assert_eq!(chain.key_block_id(), &trusted_id);
+    chain.check_link(&key_block).unwrap();
+    assert_eq!(chain.key_block_id(), &key_block.proof_for);
+}
+
+#[test]
+fn lazy_block() {
+    let root = Boc::decode(include_bytes!("mc_key_block.boc")).unwrap();
+    let block = LazyBlock::new(root.clone()).unwrap();
+    let parsed = root.parse::<Block>().unwrap();
+    assert_eq!(block.info(), &parsed.load_info().unwrap());
+    assert_eq!(block.global_id(), parsed.global_id);
+    assert_eq!(block.repr_hash(), root.repr_hash());
+    assert!(block.is_key_block());
+
+    let mc_extra = block.load_mc_extra().unwrap().unwrap();
+    let config = block.load_config().unwrap().unwrap();
+    assert_eq!(mc_extra.config.as_ref(), Some(&config));
+    assert_eq!(
+        block.load_state_update().unwrap().new_hash,
+        parsed.load_state_update().unwrap().new_hash
+    );
+
+    let block_id = block.block_id(HashBytes::ZERO);
+    assert_eq!(block_id.shard, ShardIdent::MASTERCHAIN);
+    assert_eq!(block_id.seqno, block.info().seqno);
+    assert_eq!(&block_id.root_hash, root.repr_hash());
+    assert_eq!(block.as_block(), &parsed);
+
+    // Simple masterchain block
+    let root = Boc::decode(include_bytes!("mc_simple_block.boc")).unwrap();
+    let block = LazyBlock::new(root).unwrap();
+    assert!(!block.is_key_block());
+    assert!(block.load_mc_extra().unwrap().is_some());
+    assert!(block.load_config().unwrap().is_none());
+
+    // Shard block
+    let root = Boc::decode(include_bytes!("simple_shard_block.boc")).unwrap();
+    let block = LazyBlock::new(root).unwrap();
+    assert!(!block.info().shard.is_masterchain());
+    assert!(block.load_mc_extra().unwrap().is_none());
+    let info = block.info().clone();
+    assert_eq!(block.into_block().load_info().unwrap(), info);
+
+    // Only block info is required
+    let proof = Boc::decode(include_bytes!("mc_block_proof.boc"))
+        .unwrap()
+        .parse::<BlockProof>()
+        .unwrap();
+    let merkle_proof = proof.root.parse::<crate::merkle::MerkleProof>().unwrap();
+    let block = LazyBlock::new(merkle_proof.cell).unwrap();
+    assert_eq!(block.info().seqno, proof.proof_for.seqno);
+}
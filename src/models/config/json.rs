@@ -0,0 +1,95 @@
+use serde::ser::{Error, Serialize, SerializeMap, Serializer};
+
+use crate::boc::Boc;
+
+use super::{BlockchainConfig, BlockchainConfigParams};
+
+/// Options for the JSON dump of the blockchain config.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct ConfigJsonOptions {
+    /// Whether to indent the output.
+    pub pretty: bool,
+    /// Whether to include params without a known structure
+    /// (as hex-encoded BOCs).
+    pub include_unknown: bool,
+}
+
+impl Default for ConfigJsonOptions {
+    #[inline]
+    fn default() -> Self {
+        Self {
+            pretty: true,
+            include_unknown: true,
+        }
+    }
+}
+
+impl BlockchainConfig {
+    /// Returns a pretty JSON dump of the config.
+    ///
+    /// Known params are serialized as structures and unknown params
+    /// as hex-encoded BOCs. Params are ordered by their ids, so dumps
+    /// of different config versions can be diffed directly.
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        self.to_json_ext(ConfigJsonOptions::default())
+    }
+
+    /// Returns a JSON dump of the config with the specified options.
+    pub fn to_json_ext(&self, options: ConfigJsonOptions) -> Result<String, serde_json::Error> {
+        let dump = ConfigDump {
+            config: self,
+            options,
+        };
+        if options.pretty {
+            serde_json::to_string_pretty(&dump)
+        } else {
+            serde_json::to_string(&dump)
+        }
+    }
+}
+
+struct ConfigDump<'a> {
+    config: &'a BlockchainConfig,
+    options: ConfigJsonOptions,
+}
+
+impl Serialize for ConfigDump<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = ok!(serializer.serialize_map(Some(2)));
+        ok!(map.serialize_entry("address", &self.config.address));
+        ok!(map.serialize_entry(
+            "params",
+            &ParamsDump {
+                params: &self.config.params,
+                options: self.options,
+            }
+        ));
+        map.end()
+    }
+}
+
+struct ParamsDump<'a> {
+    params: &'a BlockchainConfigParams,
+    options: ConfigJsonOptions,
+}
+
+impl Serialize for ParamsDump<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = ok!(serializer.serialize_map(None));
+        for entry in self.params.0.iter() {
+            let (key, value) = match entry {
+                Ok(entry) => entry,
+                Err(e) => return Err(Error::custom(e)),
+            };
+
+            if !ok!(BlockchainConfigParams::serialize_known_param(
+                &mut map, key, &value
+            )) && self.options.include_unknown
+            {
+                let boc = Boc::encode(value.as_ref());
+                ok!(map.serialize_entry(&key, &hex::encode(boc)));
+            }
+        }
+        map.end()
+    }
+}
@@ -7,11 +7,18 @@ use crate::num::Tokens;
 
 use crate::models::currency::ExtraCurrencyCollection;
 use crate::models::global_version::GlobalVersion;
+use crate::models::message::IntAddr;
 
 pub use self::params::*;
 
+#[cfg(feature = "json")]
+pub use self::json::*;
+
 mod params;
 
+#[cfg(feature = "json")]
+mod json;
+
 #[cfg(test)]
 mod tests;
 
@@ -163,6 +170,20 @@ impl BlockchainConfigParams {
         )
     }
 
+    /// Returns config voting setup.
+    ///
+    /// Uses [`ConfigParam11`].
+    pub fn get_voting_setup(&self) -> Result<ConfigVotingSetup, Error> {
+        ok!(self.get::<ConfigParam11>()).ok_or(Error::CellUnderflow)
+    }
+
+    /// Updates config voting setup.
+    ///
+    /// Uses [`ConfigParam11`].
+    pub fn set_voting_setup(&mut self, setup: &ConfigVotingSetup) -> Result<bool, Error> {
+        self.set_raw(ConfigParam11::ID, ok!(CellBuilder::build_from(setup)))
+    }
+
     /// Returns a dictionary with workchain descriptions.
     ///
     /// Uses [`ConfigParam12`].
@@ -170,6 +191,13 @@ impl BlockchainConfigParams {
         ok!(self.get::<ConfigParam12>()).ok_or(Error::CellUnderflow)
     }
 
+    /// Returns a description of the specified workchain.
+    ///
+    /// Uses [`ConfigParam12`].
+    pub fn get_workchain(&self, workchain: i32) -> Result<Option<WorkchainDescription>, Error> {
+        ok!(self.get_workchains()).get(workchain)
+    }
+
     /// Updates a list of workchain descriptions.
     ///
     /// Uses [`ConfigParam12`].
@@ -180,6 +208,23 @@ impl BlockchainConfigParams {
         self.set_raw(ConfigParam12::ID, ok!(CellBuilder::build_from(workchains)))
     }
 
+    /// Returns `true` if the specified address is allowed by the workchains config.
+    ///
+    /// Masterchain addresses must always have a 256-bit account id. Addresses
+    /// in other workchains are checked against the workchain format from
+    /// [`ConfigParam12`]. Unknown or inactive workchains are not allowed.
+    pub fn is_valid_address(&self, address: &IntAddr) -> Result<bool, Error> {
+        if address.is_masterchain() {
+            return Ok(address.address_bit_len() == 256);
+        }
+
+        let workchains = ok!(self.get_workchains());
+        Ok(match ok!(workchains.get(address.workchain())) {
+            Some(workchain) => workchain.active && workchain.is_valid_address(address),
+            None => false,
+        })
+    }
+
     /// Returns a block creation reward for the specified workchain in tokens.
     ///
     /// Uses [`ConfigParam14`].
@@ -432,6 +477,53 @@ impl BlockchainConfigParams {
         }
     }
 
+    /// Returns misbehaviour punishment config.
+    ///
+    /// Uses [`ConfigParam40`].
+    pub fn get_misbehaviour_punishment_config(
+        &self,
+    ) -> Result<MisbehaviourPunishmentConfig, Error> {
+        ok!(self.get::<ConfigParam40>()).ok_or(Error::CellUnderflow)
+    }
+
+    /// Updates misbehaviour punishment config.
+    ///
+    /// Uses [`ConfigParam40`].
+    pub fn set_misbehaviour_punishment_config(
+        &mut self,
+        config: &MisbehaviourPunishmentConfig,
+    ) -> Result<bool, Error> {
+        self.set_raw(ConfigParam40::ID, ok!(CellBuilder::build_from(config)))
+    }
+
+    /// Returns size limits.
+    ///
+    /// Uses [`ConfigParam43`].
+    pub fn get_size_limits(&self) -> Result<SizeLimitsConfig, Error> {
+        ok!(self.get::<ConfigParam43>()).ok_or(Error::CellUnderflow)
+    }
+
+    /// Updates size limits.
+    ///
+    /// Uses [`ConfigParam43`].
+    pub fn set_size_limits(&mut self, limits: &SizeLimitsConfig) -> Result<bool, Error> {
+        self.set_raw(ConfigParam43::ID, ok!(CellBuilder::build_from(limits)))
+    }
+
+    /// Returns a list of temporary suspended addresses.
+    ///
+    /// Uses [`ConfigParam44`].
+    pub fn get_suspended_addresses(&self) -> Result<Option<SuspendedAddressList>, Error> {
+        self.get::<ConfigParam44>()
+    }
+
+    /// Updates a list of temporary suspended addresses.
+    ///
+    /// Uses [`ConfigParam44`].
+    pub fn set_suspended_addresses(&mut self, list: &SuspendedAddressList) -> Result<bool, Error> {
+        self.set_raw(ConfigParam44::ID, ok!(CellBuilder::build_from(list)))
+    }
+
     /// Returns `true` if the config contains a param for the specified id.
     pub fn contains<'a, T: KnownConfigParam<'a>>(&'a self) -> Result<bool, Error> {
         self.0.contains_key(T::ID)
@@ -713,19 +805,8 @@ macro_rules! define_config_params {
                         Err(e) => return Err(Error::custom(e)),
                     };
 
-                    {
-                        match key {
-                            $($($id => {
-                                let value = define_config_params!(
-                                    @ser
-                                    $ident,
-                                    value,
-                                    $($serde_attrs)*
-                                );
-                                ok!(map.serialize_entry(&key, &value));
-                            },)?)*
-                            _ => ok!(map.serialize_entry(&key, value.as_ref())),
-                        }
+                    if !ok!(Self::serialize_known_param(&mut map, key, &value)) {
+                        ok!(map.serialize_entry(&key, value.as_ref()));
                     }
                 }
 
@@ -733,6 +814,33 @@ macro_rules! define_config_params {
             }
         }
 
+        #[cfg(feature = "serde")]
+        impl BlockchainConfigParams {
+            /// Serializes a param with a known structure as a map entry.
+            ///
+            /// Returns `false` if the param has no known structure.
+            fn serialize_known_param<M>(map: &mut M, key: u32, value: &Cell) -> Result<bool, M::Error>
+            where
+                M: serde::ser::SerializeMap,
+            {
+                use serde::ser::Error;
+
+                match key {
+                    $($($id => {
+                        let value = define_config_params!(
+                            @ser
+                            $ident,
+                            value,
+                            $($serde_attrs)*
+                        );
+                        ok!(map.serialize_entry(&key, &value));
+                    },)?)*
+                    _ => return Ok(false),
+                }
+                Ok(true)
+            }
+        }
+
         #[cfg(feature = "serde")]
         impl<'de> serde::Deserialize<'de> for BlockchainConfigParams {
             fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
@@ -994,6 +1102,24 @@ define_config_params! {
     /// Contains a [`ValidatorSet`].
     #[serde(transparent)]
     37 => ConfigParam37(ValidatorSet),
+
+    /// Misbehaviour punishment config.
+    ///
+    /// Contains a [`MisbehaviourPunishmentConfig`].
+    #[serde(transparent)]
+    40 => ConfigParam40(MisbehaviourPunishmentConfig),
+
+    /// Size limits.
+    ///
+    /// Contains a [`SizeLimitsConfig`].
+    #[serde(transparent)]
+    43 => ConfigParam43(SizeLimitsConfig),
+
+    /// A list of temporary suspended addresses.
+    ///
+    /// Contains a [`SuspendedAddressList`].
+    #[serde(transparent)]
+    44 => ConfigParam44(SuspendedAddressList),
 }
 
 #[cfg(feature = "serde")]
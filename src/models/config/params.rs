@@ -1,14 +1,14 @@
 use std::borrow::Cow;
 use std::num::{NonZeroU16, NonZeroU32, NonZeroU8};
 
-use everscale_crypto::ed25519;
-
 use crate::cell::*;
+use crate::crypto::PublicKey;
 use crate::dict::Dict;
 use crate::error::Error;
 use crate::num::{Tokens, Uint12};
 
 use crate::models::block::ShardIdent;
+use crate::models::message::IntAddr;
 use crate::models::{Lazy, Signature};
 
 /// Config voting setup params.
@@ -45,6 +45,105 @@ pub struct ConfigProposalSetup {
     pub cell_price: u32,
 }
 
+impl ConfigVotingSetup {
+    /// Loads proposal configuration for critical or non-critical params.
+    pub fn load_setup(&self, is_critical: bool) -> Result<ConfigProposalSetup, Error> {
+        if is_critical {
+            self.critical_params.load()
+        } else {
+            self.normal_params.load()
+        }
+    }
+}
+
+/// Config change proposal.
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[tlb(tag = "#f3")]
+pub struct ConfigProposal {
+    /// Config param index.
+    pub param_id: i32,
+    /// New param value (or `None` to remove the param).
+    #[cfg_attr(feature = "serde", serde(with = "crate::boc::OptionBoc"))]
+    pub param_value: Option<Cell>,
+    /// Optional hash of the current param value.
+    ///
+    /// The proposal is applied only if the current value has the same hash.
+    pub if_hash_equal: Option<HashBytes>,
+}
+
+impl ConfigProposal {
+    /// Computes the hash of the serialized proposal.
+    ///
+    /// NOTE: config contract uses this hash as a key of the proposal.
+    pub fn compute_hash(&self) -> Result<HashBytes, Error> {
+        let cell = ok!(CellBuilder::build_from(self));
+        Ok(*cell.repr_hash())
+    }
+
+    /// Returns `true` if the proposal can be applied to the specified
+    /// current param value.
+    ///
+    /// Always `true` if [`if_hash_equal`] is not specified.
+    ///
+    /// [`if_hash_equal`]: Self::if_hash_equal
+    pub fn matches_current_value(&self, current: Option<&DynCell>) -> bool {
+        match (&self.if_hash_equal, current) {
+            (None, _) => true,
+            (Some(expected), Some(current)) => current.repr_hash() == expected,
+            (Some(_), None) => false,
+        }
+    }
+}
+
+/// Config change proposal status.
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[tlb(tag = "#ce")]
+pub struct ConfigProposalStatus {
+    /// Unix timestamp until which the proposal is stored.
+    pub expires: u32,
+    /// Proposal content.
+    pub proposal: Lazy<ConfigProposal>,
+    /// Whether the proposal changes a critical param.
+    pub is_critical: bool,
+    /// Indices of validators who voted in the current round.
+    pub voters: Dict<u16, ()>,
+    /// Remaining weight required to win the current round.
+    pub remaining_weight: i64,
+    /// Hash of the validator set for which the votes are collected.
+    pub validator_set_id: HashBytes,
+    /// The number of remaining voting rounds.
+    pub rounds_remaining: u8,
+    /// The number of won rounds.
+    pub wins: u8,
+    /// The number of lost rounds.
+    pub losses: u8,
+}
+
+impl ConfigProposalStatus {
+    /// Returns the hash of the proposal content.
+    pub fn proposal_hash(&self) -> &HashBytes {
+        self.proposal.inner().repr_hash()
+    }
+
+    /// Returns `true` if the validator with the specified index
+    /// has already voted in the current round.
+    pub fn has_voted(&self, validator_idx: u16) -> Result<bool, Error> {
+        self.voters.contains_key(validator_idx)
+    }
+
+    /// Returns `true` if the proposal has enough won rounds to be accepted.
+    pub fn is_accepted(&self, setup: &ConfigProposalSetup) -> bool {
+        self.wins >= setup.min_wins
+    }
+
+    /// Returns `true` if the proposal has too many lost rounds or no rounds left.
+    pub fn is_rejected(&self, setup: &ConfigProposalSetup) -> bool {
+        self.losses > setup.max_losses || self.rounds_remaining == 0 && !self.is_accepted(setup)
+    }
+}
+
 /// Workchain description.
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -69,17 +168,43 @@ pub struct WorkchainDescription {
     pub version: u32,
     /// Workchain format description.
     pub format: WorkchainFormat,
+    /// Split/merge timings (only for the new format).
+    pub split_merge_timings: Option<WorkchainSplitMergeTimings>,
 }
 
 impl WorkchainDescription {
-    const TAG: u8 = 0xa6;
+    const TAG_V1: u8 = 0xa6;
+    const TAG_V2: u8 = 0xa7;
 
     /// Returns `true` if the workchain description is valid.
     pub fn is_valid(&self) -> bool {
-        self.min_split <= self.max_split
+        self.actual_min_split <= self.min_split
+            && self.min_split <= self.max_split
             && self.max_split <= ShardIdent::MAX_SPLIT_DEPTH
             && self.format.is_valid()
     }
+
+    /// Returns `true` if the specified address is allowed in this workchain.
+    ///
+    /// NOTE: Workchain id of the address is not checked.
+    pub fn is_valid_address(&self, address: &IntAddr) -> bool {
+        self.format.is_valid_addr_len(address.address_bit_len())
+    }
+
+    /// Returns `true` if blocks can be produced in this workchain at the specified time.
+    pub fn is_enabled_at(&self, now: u32) -> bool {
+        self.active && now >= self.enabled_since
+    }
+
+    /// Returns `true` if the specified shard split depth is allowed.
+    pub fn is_split_allowed(&self, depth: u8) -> bool {
+        depth < self.max_split
+    }
+
+    /// Returns `true` if the shards of the specified split depth can be merged.
+    pub fn is_merge_allowed(&self, depth: u8) -> bool {
+        depth > self.min_split
+    }
 }
 
 impl Store for WorkchainDescription {
@@ -96,7 +221,13 @@ impl Store for WorkchainDescription {
             | ((self.active as u16) << 14)
             | ((self.accept_msgs as u16) << 13);
 
-        ok!(builder.store_u8(Self::TAG));
+        let tag = if self.split_merge_timings.is_some() {
+            Self::TAG_V2
+        } else {
+            Self::TAG_V1
+        };
+
+        ok!(builder.store_u8(tag));
         ok!(builder.store_u32(self.enabled_since));
         ok!(builder.store_u8(self.actual_min_split));
         ok!(builder.store_u8(self.min_split));
@@ -105,17 +236,24 @@ impl Store for WorkchainDescription {
         ok!(builder.store_u256(&self.zerostate_root_hash));
         ok!(builder.store_u256(&self.zerostate_file_hash));
         ok!(builder.store_u32(self.version));
-        self.format.store_into(builder, context)
+        ok!(self.format.store_into(builder, context));
+
+        if let Some(timings) = &self.split_merge_timings {
+            ok!(timings.store_into(builder, context));
+        }
+
+        Ok(())
     }
 }
 
 impl<'a> Load<'a> for WorkchainDescription {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
-        match slice.load_u8() {
-            Ok(Self::TAG) => {}
+        let with_timings = match slice.load_u8() {
+            Ok(Self::TAG_V1) => false,
+            Ok(Self::TAG_V2) => true,
             Ok(_) => return Err(Error::InvalidTag),
             Err(e) => return Err(e),
-        }
+        };
 
         let enabled_since = ok!(slice.load_u32());
         let actual_min_split = ok!(slice.load_u8());
@@ -137,6 +275,11 @@ impl<'a> Load<'a> for WorkchainDescription {
             zerostate_file_hash: ok!(slice.load_u256()),
             version: ok!(slice.load_u32()),
             format: ok!(WorkchainFormat::load_from(slice)),
+            split_merge_timings: if with_timings {
+                Some(ok!(WorkchainSplitMergeTimings::load_from(slice)))
+            } else {
+                None
+            },
         };
 
         let basic = flags & 0b1000_0000_0000_0000 != 0;
@@ -148,6 +291,21 @@ impl<'a> Load<'a> for WorkchainDescription {
     }
 }
 
+/// Workchain split/merge timings.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[tlb(tag = "#0")]
+pub struct WorkchainSplitMergeTimings {
+    /// Delay in seconds before the split/merge is performed.
+    pub split_merge_delay: u32,
+    /// Minimal interval in seconds between split/merge operations.
+    pub split_merge_interval: u32,
+    /// Minimal interval in seconds before the next split/merge is allowed.
+    pub min_split_merge_interval: u32,
+    /// The maximum delay in seconds for the split/merge.
+    pub max_split_merge_delay: u32,
+}
+
 /// Workchain format description.
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -174,6 +332,14 @@ impl WorkchainFormat {
     pub fn is_basic(&self) -> bool {
         matches!(self, Self::Basic(_))
     }
+
+    /// Returns `true` if the account id length is allowed by this format.
+    pub fn is_valid_addr_len(&self, bit_len: u16) -> bool {
+        match self {
+            Self::Basic(_) => bit_len == 256,
+            Self::Extended(format) => format.is_valid_addr_len(bit_len),
+        }
+    }
 }
 
 impl Store for WorkchainFormat {
@@ -238,6 +404,20 @@ impl WorkchainFormatExtended {
             && self.max_addr_len <= Uint12::new(1023)
             && self.addr_len_step <= Uint12::new(1023)
     }
+
+    /// Returns `true` if the account id length is allowed by this format.
+    pub fn is_valid_addr_len(&self, bit_len: u16) -> bool {
+        let bit_len = Uint12::new(bit_len);
+        if bit_len < self.min_addr_len || bit_len > self.max_addr_len {
+            return false;
+        }
+
+        let offset = bit_len.into_inner() - self.min_addr_len.into_inner();
+        match self.addr_len_step.into_inner() {
+            0 => offset == 0,
+            step => offset % step == 0,
+        }
+    }
 }
 
 /// Block creation reward.
@@ -338,6 +518,46 @@ impl GasLimitsPrices {
     const TAG_BASE: u8 = 0xdd;
     const TAG_EXT: u8 = 0xde;
     const TAG_FLAT_PFX: u8 = 0xd1;
+
+    /// Computes the fee for the specified amount of gas.
+    ///
+    /// The first [`flat_gas_limit`] units are charged with [`flat_gas_price`],
+    /// the rest are charged with [`gas_price`] (in 1/65536 units, rounded up).
+    ///
+    /// [`flat_gas_limit`]: GasLimitsPrices::flat_gas_limit
+    /// [`flat_gas_price`]: GasLimitsPrices::flat_gas_price
+    /// [`gas_price`]: GasLimitsPrices::gas_price
+    pub fn compute_gas_fee(&self, gas_used: u64) -> Tokens {
+        let fee = if gas_used <= self.flat_gas_limit {
+            self.flat_gas_price as u128
+        } else {
+            let gas = (gas_used - self.flat_gas_limit) as u128;
+            let fee = (gas * self.gas_price as u128 + 0xffff) >> 16;
+            fee + self.flat_gas_price as u128
+        };
+        Tokens::new(fee)
+    }
+
+    /// Computes the amount of gas which can be bought for the specified balance.
+    ///
+    /// The result never exceeds [`gas_limit`].
+    ///
+    /// [`gas_limit`]: GasLimitsPrices::gas_limit
+    pub fn compute_gas_limit(&self, balance: Tokens) -> u64 {
+        let balance = balance.into_inner();
+        if balance >= self.compute_gas_fee(self.gas_limit).into_inner() {
+            return self.gas_limit;
+        }
+
+        let flat_gas_price = self.flat_gas_price as u128;
+        if balance < flat_gas_price || self.gas_price == 0 {
+            return 0;
+        }
+
+        let gas = ((balance - flat_gas_price) << 16) / self.gas_price as u128;
+        let gas = gas.saturating_add(self.flat_gas_limit as u128);
+        std::cmp::min(gas, self.gas_limit as u128) as u64
+    }
 }
 
 impl Store for GasLimitsPrices {
@@ -508,9 +728,9 @@ pub struct ConsensusConfig {
     pub new_catchain_ids: bool,
     /// Number of block candidates per round.
     pub round_candidates: NonZeroU32,
-    /// Delay in seconds before proposing a new candidate.
+    /// Delay in milliseconds before proposing a new candidate.
     pub next_candidate_delay_ms: u32,
-    /// Catchain processing timeout in seconds.
+    /// Catchain processing timeout in milliseconds.
     pub consensus_timeout_ms: u32,
     /// Maximum number of attempts per round.
     pub fast_attempts: u32,
@@ -520,7 +740,7 @@ pub struct ConsensusConfig {
     pub catchain_max_deps: u32,
     /// The maximum block size in bytes.
     pub max_block_bytes: u32,
-    /// THe maximum size of a collated data in bytes.
+    /// The maximum size of a collated data in bytes.
     pub max_collated_bytes: u32,
 }
 
@@ -533,9 +753,20 @@ impl Store for ConsensusConfig {
     fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
         let flags = self.new_catchain_ids as u8;
 
-        ok!(builder.store_u8(Self::TAG_V2));
-        ok!(builder.store_u8(flags));
-        ok!(builder.store_u8(self.round_candidates.get() as u8));
+        match u8::try_from(self.round_candidates.get()) {
+            // Prefer the new format when possible
+            Ok(round_candidates) => {
+                ok!(builder.store_u8(Self::TAG_V2));
+                ok!(builder.store_u8(flags));
+                ok!(builder.store_u8(round_candidates));
+            }
+            // Fallback to the old format which has a wider `round_candidates` field
+            Err(_) if !self.new_catchain_ids => {
+                ok!(builder.store_u8(Self::TAG_V1));
+                ok!(builder.store_u32(self.round_candidates.get()));
+            }
+            Err(_) => return Err(Error::IntOverflow),
+        }
         ok!(builder.store_u32(self.next_candidate_delay_ms));
         ok!(builder.store_u32(self.consensus_timeout_ms));
         ok!(builder.store_u32(self.fast_attempts));
@@ -574,6 +805,196 @@ impl<'a> Load<'a> for ConsensusConfig {
     }
 }
 
+/// Misbehaviour punishment config.
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[tlb(tag = "#01")]
+pub struct MisbehaviourPunishmentConfig {
+    /// Default flat fine in tokens.
+    pub default_flat_fine: Tokens,
+    /// Default proportional fine.
+    pub default_proportional_fine: u32,
+    /// Flat fine multiplier for severe misbehaviour.
+    pub severity_flat_mult: u16,
+    /// Proportional fine multiplier for severe misbehaviour.
+    pub severity_proportional_mult: u16,
+    /// The number of blocks after which the misbehaviour can not be punished.
+    pub unpunishable_interval: u16,
+    /// Long interval duration in blocks.
+    pub long_interval: u16,
+    /// Flat fine multiplier for the long interval.
+    pub long_flat_mult: u16,
+    /// Proportional fine multiplier for the long interval.
+    pub long_proportional_mult: u16,
+    /// Medium interval duration in blocks.
+    pub medium_interval: u16,
+    /// Flat fine multiplier for the medium interval.
+    pub medium_flat_mult: u16,
+    /// Proportional fine multiplier for the medium interval.
+    pub medium_proportional_mult: u16,
+}
+
+/// Size limits config.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SizeLimitsConfig {
+    /// Max number of data bits in the message (without the root cell).
+    pub max_msg_bits: u32,
+    /// Max number of unique cells in the message (without the root cell).
+    pub max_msg_cells: u32,
+    /// Max number of cells in the library.
+    pub max_library_cells: u32,
+    /// Max depth of the VM data.
+    pub max_vm_data_depth: u16,
+    /// Max size of the serialized external message in bytes.
+    pub max_ext_msg_size: u32,
+    /// Max depth of the external message.
+    pub max_ext_msg_depth: u16,
+    /// Optional account state limits (since v2).
+    pub acc_state_limits: Option<AccountStateLimits>,
+}
+
+impl SizeLimitsConfig {
+    const TAG_V1: u8 = 0x01;
+    const TAG_V2: u8 = 0x02;
+}
+
+impl Store for SizeLimitsConfig {
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
+        let tag = if self.acc_state_limits.is_some() {
+            Self::TAG_V2
+        } else {
+            Self::TAG_V1
+        };
+
+        ok!(builder.store_u8(tag));
+        ok!(builder.store_u32(self.max_msg_bits));
+        ok!(builder.store_u32(self.max_msg_cells));
+        ok!(builder.store_u32(self.max_library_cells));
+        ok!(builder.store_u16(self.max_vm_data_depth));
+        ok!(builder.store_u32(self.max_ext_msg_size));
+        ok!(builder.store_u16(self.max_ext_msg_depth));
+
+        if let Some(limits) = &self.acc_state_limits {
+            ok!(limits.store_into(builder, context));
+        }
+
+        Ok(())
+    }
+}
+
+impl<'a> Load<'a> for SizeLimitsConfig {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        let with_acc_state_limits = match slice.load_u8() {
+            Ok(Self::TAG_V1) => false,
+            Ok(Self::TAG_V2) => true,
+            Ok(_) => return Err(Error::InvalidTag),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Self {
+            max_msg_bits: ok!(slice.load_u32()),
+            max_msg_cells: ok!(slice.load_u32()),
+            max_library_cells: ok!(slice.load_u32()),
+            max_vm_data_depth: ok!(slice.load_u16()),
+            max_ext_msg_size: ok!(slice.load_u32()),
+            max_ext_msg_depth: ok!(slice.load_u16()),
+            acc_state_limits: if with_acc_state_limits {
+                Some(ok!(AccountStateLimits::load_from(slice)))
+            } else {
+                None
+            },
+        })
+    }
+}
+
+/// Account state size limits.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct AccountStateLimits {
+    /// Max number of unique cells in the account state.
+    pub max_cells: u32,
+    /// Max number of data bits in the account state.
+    pub max_bits: u32,
+}
+
+/// A list of temporary suspended addresses.
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[tlb(tag = "#00")]
+pub struct SuspendedAddressList {
+    /// A set of suspended addresses (workchain and account id).
+    #[cfg_attr(feature = "serde", serde(with = "serde_address_set"))]
+    pub addresses: Dict<(i32, HashBytes), ()>,
+    /// Unix timestamp until which the addresses are suspended.
+    pub suspended_until: u32,
+}
+
+impl SuspendedAddressList {
+    /// Returns `true` if the specified address is suspended at the specified time.
+    pub fn is_suspended(&self, address: &IntAddr, now: u32) -> Result<bool, Error> {
+        if now >= self.suspended_until {
+            return Ok(false);
+        }
+        match address.account_id() {
+            Some(account) => self.addresses.contains_key((address.workchain(), account)),
+            None => Ok(false),
+        }
+    }
+
+    /// Gets an iterator over the suspended addresses.
+    ///
+    /// NOTE: addresses are sorted by the raw key bits, so negative workchains go last.
+    pub fn iter(&self) -> impl Iterator<Item = Result<(i32, HashBytes), Error>> + '_ {
+        self.addresses.keys()
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_address_set {
+    use super::*;
+
+    pub fn serialize<S>(
+        addresses: &Dict<(i32, HashBytes), ()>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::{Error, SerializeSeq};
+
+        let mut seq = ok!(serializer.serialize_seq(None));
+        for entry in addresses.keys() {
+            let (workchain, account) = ok!(entry.map_err(Error::custom));
+            ok!(seq.serialize_element(&format!("{workchain}:{account}")));
+        }
+        seq.end()
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Dict<(i32, HashBytes), ()>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        use serde::Deserialize;
+
+        let mut res = Dict::new();
+        for item in ok!(Vec::<String>::deserialize(deserializer)) {
+            let Some((workchain, account)) = item.split_once(':') else {
+                return Err(Error::custom("invalid address format"));
+            };
+            let workchain = ok!(workchain.parse::<i32>().map_err(Error::custom));
+            let account = ok!(account.parse::<HashBytes>().map_err(Error::custom));
+            ok!(res.set((workchain, account), ()).map_err(Error::custom));
+        }
+        Ok(res)
+    }
+}
+
 /// Validator set.
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize))]
@@ -897,20 +1318,18 @@ impl ValidatorDescription {
     const TAG_WITH_ADNL: u8 = 0x73;
     const TAG_WITH_MC_SEQNO: u8 = 0x93;
 
-    const PUBKEY_TAG: u32 = 0x8e81278a;
-
     /// Verifies message signature and current public key.
     pub fn verify_signature(&self, data: &[u8], signature: &Signature) -> bool {
-        if let Some(public_key) = ed25519::PublicKey::from_bytes(self.public_key.0) {
-            public_key.verify_raw(data, signature.as_ref())
-        } else {
-            false
-        }
+        PublicKey::wrap(&self.public_key.0).verify_raw(data, signature)
     }
 }
 
 impl Store for ValidatorDescription {
-    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
         let with_mc_seqno = self.mc_seqno_since != 0;
 
         let tag = if with_mc_seqno {
@@ -922,8 +1341,7 @@ impl Store for ValidatorDescription {
         };
 
         ok!(builder.store_u8(tag));
-        ok!(builder.store_u32(Self::PUBKEY_TAG));
-        ok!(builder.store_u256(&self.public_key));
+        ok!(PublicKey::wrap(&self.public_key.0).store_into(builder, context));
         ok!(builder.store_u64(self.weight));
 
         let mut adnl = self.adnl_addr.as_ref();
@@ -954,13 +1372,7 @@ impl<'a> Load<'a> for ValidatorDescription {
         };
 
         Ok(Self {
-            public_key: {
-                match slice.load_u32() {
-                    Ok(Self::PUBKEY_TAG) => ok!(slice.load_u256()),
-                    Ok(_) => return Err(Error::InvalidTag),
-                    Err(e) => return Err(e),
-                }
-            },
+            public_key: ok!(PublicKey::load_from(slice)).into(),
             weight: ok!(slice.load_u64()),
             adnl_addr: if with_adnl {
                 Some(ok!(slice.load_u256()))
@@ -1086,4 +1498,43 @@ mod tests {
         assert_eq!(vs11_first, vs11_second);
         assert_ne!(vs10_first, vs11_second);
     }
+
+    #[test]
+    fn gas_fees() {
+        let prices = GasLimitsPrices {
+            gas_price: 1000 << 16,
+            gas_limit: 1_000_000,
+            special_gas_limit: 100_000_000,
+            gas_credit: 10_000,
+            block_gas_limit: 11_000_000,
+            freeze_due_limit: 100_000_000,
+            delete_due_limit: 1_000_000_000,
+            flat_gas_limit: 100,
+            flat_gas_price: 100_000,
+        };
+
+        assert_eq!(prices.compute_gas_fee(0), Tokens::new(100_000));
+        assert_eq!(prices.compute_gas_fee(100), Tokens::new(100_000));
+        assert_eq!(prices.compute_gas_fee(101), Tokens::new(101_000));
+        assert_eq!(prices.compute_gas_fee(1100), Tokens::new(1_100_000));
+
+        assert_eq!(prices.compute_gas_limit(Tokens::ZERO), 0);
+        assert_eq!(prices.compute_gas_limit(Tokens::new(99_999)), 0);
+        assert_eq!(prices.compute_gas_limit(Tokens::new(100_000)), 100);
+        assert_eq!(prices.compute_gas_limit(Tokens::new(100_999)), 100);
+        assert_eq!(prices.compute_gas_limit(Tokens::new(1_100_000)), 1100);
+        assert_eq!(prices.compute_gas_limit(Tokens::MAX), prices.gas_limit);
+
+        // Fractional prices are rounded up
+        let prices = GasLimitsPrices {
+            gas_price: 1 << 15,
+            flat_gas_limit: 0,
+            flat_gas_price: 0,
+            ..prices
+        };
+        assert_eq!(prices.compute_gas_fee(1), Tokens::new(1));
+        assert_eq!(prices.compute_gas_fee(2), Tokens::new(1));
+        assert_eq!(prices.compute_gas_fee(3), Tokens::new(2));
+        assert_eq!(prices.compute_gas_limit(Tokens::new(1)), 2);
+    }
 }
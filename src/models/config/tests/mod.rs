@@ -2,7 +2,8 @@ use std::num::NonZeroU32;
 
 use super::*;
 use crate::boc::BocRepr;
-use crate::models::ShardStateUnsplit;
+use crate::models::{Lazy, ShardStateUnsplit, VarAddr};
+use crate::num::Uint12;
 use crate::prelude::Boc;
 
 #[test]
@@ -361,3 +362,311 @@ fn serde() {
     // Current config
     check_config(include_bytes!("new_config.boc"));
 }
+
+#[cfg(feature = "json")]
+#[test]
+fn json_dump() {
+    let data = Boc::decode(include_bytes!("new_config.boc")).unwrap();
+    let mut config = data.parse::<BlockchainConfig>().unwrap();
+
+    let unknown = CellBuilder::build_from(0xdeadbeafu32).unwrap();
+    config.set_raw(1000, unknown.clone()).unwrap();
+
+    let json = config.to_json().unwrap();
+    assert!(json.contains('\n'));
+
+    // Params are ordered by their ids
+    let first = json.find("\"2\":").unwrap();
+    let second = json.find("\"10\":").unwrap();
+    assert!(first < second);
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let params = value["params"].as_object().unwrap();
+    assert_eq!(
+        value["address"],
+        serde_json::to_value(config.address).unwrap()
+    );
+    assert_eq!(
+        params["34"],
+        serde_json::to_value(config.get_current_validator_set().unwrap()).unwrap()
+    );
+    assert_eq!(params["1000"], hex::encode(Boc::encode(unknown.as_ref())));
+
+    let json = config
+        .to_json_ext(ConfigJsonOptions {
+            pretty: false,
+            include_unknown: false,
+        })
+        .unwrap();
+    assert!(!json.contains('\n'));
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let params = value["params"].as_object().unwrap();
+    assert!(params.contains_key("34"));
+    assert!(!params.contains_key("1000"));
+}
+
+#[test]
+fn config_address_validation() {
+    let data = Boc::decode(include_bytes!("simple_config.boc")).unwrap();
+    let blockchain_config = data.parse::<BlockchainConfig>().unwrap();
+
+    let account = HashBytes([0x11; 32]);
+    for workchain in [-1, 0] {
+        let addr = IntAddr::with_workchain(workchain, account);
+        assert!(blockchain_config.is_valid_address(&addr).unwrap());
+    }
+
+    // Unknown workchains are not allowed
+    for workchain in [1, 1000, i32::MIN] {
+        let addr = IntAddr::with_workchain(workchain, account);
+        assert!(!blockchain_config.is_valid_address(&addr).unwrap());
+    }
+
+    // Basic format allows only 256-bit account ids
+    let addr = IntAddr::Var(VarAddr {
+        anycast: None,
+        address_len: crate::num::Uint9::new(128),
+        workchain: 0,
+        address: vec![0x11; 16],
+    });
+    assert!(!blockchain_config.is_valid_address(&addr).unwrap());
+}
+
+#[test]
+fn extended_workchain_format() {
+    let format = WorkchainFormatExtended {
+        min_addr_len: Uint12::new(64),
+        max_addr_len: Uint12::new(256),
+        addr_len_step: Uint12::new(32),
+        workchain_type_id: NonZeroU32::new(1).unwrap(),
+    };
+    assert!(format.is_valid());
+
+    for bit_len in [64, 96, 256] {
+        assert!(format.is_valid_addr_len(bit_len));
+    }
+    for bit_len in [0, 63, 65, 100, 288] {
+        assert!(!format.is_valid_addr_len(bit_len));
+    }
+}
+
+#[test]
+fn config_proposals() {
+    let data = Boc::decode(include_bytes!("simple_config.boc")).unwrap();
+    let mut config = data.parse::<BlockchainConfig>().unwrap();
+
+    let setup = config.get_voting_setup().unwrap();
+    let normal = setup.load_setup(false).unwrap();
+    let critical = setup.load_setup(true).unwrap();
+    assert_eq!(normal, setup.normal_params.load().unwrap());
+    assert_eq!(critical, setup.critical_params.load().unwrap());
+
+    assert!(config.set_voting_setup(&setup).unwrap());
+    assert_eq!(config.get_voting_setup().unwrap(), setup);
+
+    let current = config.get_raw(ConfigParam0::ID).unwrap().unwrap();
+    let current = current.cell();
+
+    let proposal = ConfigProposal {
+        param_id: 0,
+        param_value: Some(CellBuilder::build_from(HashBytes([0x11; 32])).unwrap()),
+        if_hash_equal: Some(*current.repr_hash()),
+    };
+    assert!(proposal.matches_current_value(Some(current)));
+    assert!(!proposal.matches_current_value(None));
+    assert!(!proposal.matches_current_value(Some(Cell::empty_cell_ref())));
+
+    let unconditional = ConfigProposal {
+        if_hash_equal: None,
+        ..proposal.clone()
+    };
+    assert!(unconditional.matches_current_value(None));
+    assert_ne!(
+        unconditional.compute_hash().unwrap(),
+        proposal.compute_hash().unwrap()
+    );
+
+    let cell = CellBuilder::build_from(&proposal).unwrap();
+    assert_eq!(cell.parse::<ConfigProposal>().unwrap(), proposal);
+    assert_eq!(*cell.repr_hash(), proposal.compute_hash().unwrap());
+
+    let mut voters = Dict::new();
+    voters.set(3u16, ()).unwrap();
+
+    let status = ConfigProposalStatus {
+        expires: 1000,
+        proposal: Lazy::new(&proposal).unwrap(),
+        is_critical: false,
+        voters,
+        remaining_weight: 100,
+        validator_set_id: HashBytes::ZERO,
+        rounds_remaining: normal.max_total_rounds,
+        wins: 0,
+        losses: 0,
+    };
+    assert_eq!(*status.proposal_hash(), proposal.compute_hash().unwrap());
+    assert!(status.has_voted(3).unwrap());
+    assert!(!status.has_voted(4).unwrap());
+    assert!(!status.is_accepted(&normal));
+    assert!(!status.is_rejected(&normal));
+
+    let cell = CellBuilder::build_from(&status).unwrap();
+    assert_eq!(cell.parse::<ConfigProposalStatus>().unwrap(), status);
+}
+
+#[test]
+fn consensus_config_formats() {
+    // Old catchain config format without flags
+    let mut builder = CellBuilder::new();
+    builder.store_u8(0xc1).unwrap();
+    for value in [250, 250, 1000, 11] {
+        builder.store_u32(value).unwrap();
+    }
+    let old = builder.build().unwrap().parse::<CatchainConfig>().unwrap();
+    assert!(!old.isolate_mc_validators);
+    assert!(!old.shuffle_mc_validators);
+    assert_eq!(old.shard_validators_num, 11);
+
+    // New catchain config format with unknown flags
+    let mut builder = CellBuilder::new();
+    builder.store_u8(0xc2).unwrap();
+    builder.store_u8(0b100).unwrap();
+    for value in [250, 250, 1000, 11] {
+        builder.store_u32(value).unwrap();
+    }
+    let cell = builder.build().unwrap();
+    assert!(matches!(
+        cell.parse::<CatchainConfig>(),
+        Err(Error::InvalidData)
+    ));
+
+    // Old consensus config format with a wide `round_candidates` field
+    let mut builder = CellBuilder::new();
+    builder.store_u8(0xd6).unwrap();
+    for value in [1000, 3000, 2000, 3, 8, 4, 2097152, 2097152] {
+        builder.store_u32(value).unwrap();
+    }
+    let cell = builder.build().unwrap();
+    let mut config = cell.parse::<ConsensusConfig>().unwrap();
+    assert!(!config.new_catchain_ids);
+    assert_eq!(config.round_candidates.get(), 1000);
+    assert_eq!(config.next_candidate_delay_ms, 3000);
+
+    // Values which don't fit into the new format are stored in the old one
+    assert_eq!(CellBuilder::build_from(&config).unwrap(), cell);
+
+    config.new_catchain_ids = true;
+    assert!(matches!(
+        CellBuilder::build_from(&config),
+        Err(Error::IntOverflow)
+    ));
+
+    // Other values are stored in the new format
+    config.round_candidates = NonZeroU32::new(3).unwrap();
+    let cell = CellBuilder::build_from(&config).unwrap();
+    assert_eq!(cell.as_slice().unwrap().load_u8().unwrap(), 0xd7);
+    assert_eq!(cell.parse::<ConsensusConfig>().unwrap(), config);
+}
+
+#[test]
+fn punishment_and_suspended_addresses() {
+    let data = Boc::decode(include_bytes!("simple_config.boc")).unwrap();
+    let mut config = data.parse::<BlockchainConfig>().unwrap();
+
+    let punishment = MisbehaviourPunishmentConfig {
+        default_flat_fine: Tokens::new(101000000000),
+        default_proportional_fine: 1 << 26,
+        severity_flat_mult: 24,
+        severity_proportional_mult: 5,
+        unpunishable_interval: 10,
+        long_interval: 100,
+        long_flat_mult: 24,
+        long_proportional_mult: 5,
+        medium_interval: 100,
+        medium_flat_mult: 24,
+        medium_proportional_mult: 5,
+    };
+    config
+        .set_misbehaviour_punishment_config(&punishment)
+        .unwrap();
+    assert_eq!(
+        config.get_misbehaviour_punishment_config().unwrap(),
+        punishment
+    );
+
+    assert_eq!(config.get_suspended_addresses().unwrap(), None);
+
+    let first = HashBytes([0x11; 32]);
+    let second = HashBytes([0x22; 32]);
+
+    let mut addresses = Dict::new();
+    addresses.set((0, second), ()).unwrap();
+    addresses.set((-1, first), ()).unwrap();
+    addresses.set((0, first), ()).unwrap();
+
+    let list = SuspendedAddressList {
+        addresses,
+        suspended_until: 1000,
+    };
+    config.set_suspended_addresses(&list).unwrap();
+
+    let parsed = config.get_suspended_addresses().unwrap().unwrap();
+    assert_eq!(parsed, list);
+
+    let items = parsed.iter().collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(items, [(0, first), (0, second), (-1, first)]);
+
+    let addr = IntAddr::from((0, first));
+    assert!(parsed.is_suspended(&addr, 999).unwrap());
+    assert!(!parsed.is_suspended(&addr, 1000).unwrap());
+    let addr = IntAddr::from((0, HashBytes([0x33; 32])));
+    assert!(!parsed.is_suspended(&addr, 999).unwrap());
+
+    #[cfg(feature = "serde")]
+    {
+        let json = serde_json::to_string(&parsed).unwrap();
+        let from_json = serde_json::from_str::<SuspendedAddressList>(&json).unwrap();
+        assert_eq!(from_json, parsed);
+    }
+}
+
+#[test]
+fn workchain_description_formats() {
+    let data = Boc::decode(include_bytes!("simple_config.boc")).unwrap();
+    let mut config = data.parse::<BlockchainConfig>().unwrap();
+
+    let mut basechain = config.get_workchain(0).unwrap().unwrap();
+    assert!(basechain.split_merge_timings.is_none());
+    assert!(basechain.is_enabled_at(basechain.enabled_since));
+    assert!(basechain.is_split_allowed(basechain.min_split));
+    assert!(!basechain.is_split_allowed(basechain.max_split));
+    assert!(!basechain.is_merge_allowed(basechain.min_split));
+    assert!(config.get_workchain(123).unwrap().is_none());
+
+    // Old format
+    let cell = CellBuilder::build_from(&basechain).unwrap();
+    assert_eq!(cell.as_slice().unwrap().load_u8().unwrap(), 0xa6);
+    assert_eq!(cell.parse::<WorkchainDescription>().unwrap(), basechain);
+
+    // New format
+    basechain.split_merge_timings = Some(WorkchainSplitMergeTimings {
+        split_merge_delay: 100,
+        split_merge_interval: 100,
+        min_split_merge_interval: 30,
+        max_split_merge_delay: 1000,
+    });
+    let cell = CellBuilder::build_from(&basechain).unwrap();
+    assert_eq!(cell.as_slice().unwrap().load_u8().unwrap(), 0xa7);
+    assert_eq!(cell.parse::<WorkchainDescription>().unwrap(), basechain);
+
+    let mut workchains = config.get_workchains().unwrap();
+    workchains.set(0, &basechain).unwrap();
+    config.set_workchains(&workchains).unwrap();
+    assert_eq!(config.get_workchain(0).unwrap(), Some(basechain.clone()));
+
+    // Invalid split params
+    basechain.actual_min_split = basechain.min_split + 1;
+    assert!(!basechain.is_valid());
+    assert!(CellBuilder::build_from(&basechain).is_err());
+}
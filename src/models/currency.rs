@@ -1,7 +1,8 @@
 //! Currency collection stuff.
 
 use crate::cell::*;
-use crate::dict::{AugDictSkipValue, Dict};
+use crate::dict::Dict;
+use crate::error::Error;
 use crate::num::{Tokens, VarUint248};
 
 /// Amounts collection.
@@ -42,6 +43,30 @@ impl CurrencyCollection {
     pub const fn bit_len(&self) -> u16 {
         self.tokens.unwrap_bit_len() + 1
     }
+
+    /// Checked currency collection addition.
+    /// Computes `self + rhs` for each currency, returning an error if overflow occurred.
+    pub fn checked_add(&self, rhs: &Self) -> Result<Self, Error> {
+        Ok(Self {
+            tokens: match self.tokens.checked_add(rhs.tokens) {
+                Some(tokens) => tokens,
+                None => return Err(Error::IntOverflow),
+            },
+            other: ok!(self.other.checked_add(&rhs.other)),
+        })
+    }
+
+    /// Checked currency collection subtraction.
+    /// Computes `self - rhs` for each currency, returning an error if overflow occurred.
+    pub fn checked_sub(&self, rhs: &Self) -> Result<Self, Error> {
+        Ok(Self {
+            tokens: match self.tokens.checked_sub(rhs.tokens) {
+                Some(tokens) => tokens,
+                None => return Err(Error::IntOverflow),
+            },
+            other: ok!(self.other.checked_sub(&rhs.other)),
+        })
+    }
 }
 
 impl From<Tokens> for CurrencyCollection {
@@ -54,13 +79,6 @@ impl From<Tokens> for CurrencyCollection {
     }
 }
 
-impl<'a> AugDictSkipValue<'a> for CurrencyCollection {
-    #[inline]
-    fn skip_value(slice: &mut CellSlice<'a>) -> bool {
-        Tokens::skip_value(slice) && ExtraCurrencyCollection::skip_value(slice)
-    }
-}
-
 impl ExactSize for CurrencyCollection {
     #[inline]
     fn exact_size(&self) -> CellSliceSize {
@@ -106,6 +124,61 @@ impl ExtraCurrencyCollection {
     pub fn as_dict_mut(&mut self) -> &mut Dict<u32, VarUint248> {
         &mut self.0
     }
+
+    /// Checked extra currency collection addition.
+    /// Computes `self + rhs` for each currency, returning an error if overflow occurred.
+    pub fn checked_add(&self, rhs: &Self) -> Result<Self, Error> {
+        if rhs.is_empty() {
+            return Ok(self.clone());
+        }
+
+        let mut result = self.clone();
+        for entry in rhs.0.iter() {
+            let (currency_id, other) = ok!(entry);
+            let value = match ok!(result.0.get(currency_id)) {
+                Some(value) => match value.checked_add(&other) {
+                    Some(value) => value,
+                    None => return Err(Error::IntOverflow),
+                },
+                None => other,
+            };
+            ok!(result.0.set(currency_id, value));
+        }
+        Ok(result)
+    }
+
+    /// Checked extra currency collection subtraction.
+    /// Computes `self - rhs` for each currency, returning an error if overflow occurred.
+    ///
+    /// Currencies with zero amounts are removed from the result.
+    pub fn checked_sub(&self, rhs: &Self) -> Result<Self, Error> {
+        if rhs.is_empty() {
+            return Ok(self.clone());
+        }
+
+        let mut result = self.clone();
+        for entry in rhs.0.iter() {
+            let (currency_id, other) = ok!(entry);
+            if other.is_zero() {
+                continue;
+            }
+
+            let value = match ok!(result.0.get(currency_id)) {
+                Some(value) => match value.checked_sub(&other) {
+                    Some(value) => value,
+                    None => return Err(Error::IntOverflow),
+                },
+                None => return Err(Error::IntOverflow),
+            };
+
+            if value.is_zero() {
+                ok!(result.0.remove(currency_id));
+            } else {
+                ok!(result.0.set(currency_id, value));
+            }
+        }
+        Ok(result)
+    }
 }
 
 impl From<Dict<u32, VarUint248>> for ExtraCurrencyCollection {
@@ -115,20 +188,50 @@ impl From<Dict<u32, VarUint248>> for ExtraCurrencyCollection {
     }
 }
 
-impl<'a> AugDictSkipValue<'a> for ExtraCurrencyCollection {
-    #[inline]
-    fn skip_value(slice: &mut CellSlice<'a>) -> bool {
-        if let Ok(has_extra) = slice.load_bit() {
-            !has_extra || slice.try_advance(0, 1)
-        } else {
-            false
-        }
-    }
-}
-
 impl ExactSize for ExtraCurrencyCollection {
     #[inline]
     fn exact_size(&self) -> CellSliceSize {
         self.0.exact_size()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_extra(items: &[(u32, u128)]) -> ExtraCurrencyCollection {
+        let mut dict = Dict::new();
+        for (currency_id, amount) in items {
+            dict.set(currency_id, VarUint248::new(*amount)).unwrap();
+        }
+        ExtraCurrencyCollection::from(dict)
+    }
+
+    #[test]
+    fn currency_collection_ops() {
+        let a = CurrencyCollection {
+            tokens: Tokens::new(100),
+            other: make_extra(&[(1, 10), (2, 20)]),
+        };
+        let b = CurrencyCollection {
+            tokens: Tokens::new(30),
+            other: make_extra(&[(1, 10), (2, 5)]),
+        };
+
+        let diff = a.checked_sub(&b).unwrap();
+        assert_eq!(diff.tokens, Tokens::new(70));
+        // Zero amounts are removed
+        assert_eq!(diff.other, make_extra(&[(2, 15)]));
+        assert_eq!(diff.checked_add(&b).unwrap().tokens, a.tokens);
+
+        assert!(b.checked_sub(&a).is_err());
+        assert!(diff.checked_sub(&b).is_err());
+
+        let no_tokens = CurrencyCollection {
+            tokens: Tokens::ZERO,
+            other: make_extra(&[(3, 1)]),
+        };
+        assert!(a.checked_sub(&no_tokens).is_err());
+        assert_eq!(a.checked_sub(&CurrencyCollection::ZERO).unwrap(), a);
+    }
+}
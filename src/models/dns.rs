@@ -0,0 +1,495 @@
+//! DNS record models.
+
+use sha2::Digest;
+
+use crate::cell::*;
+use crate::dict::Dict;
+use crate::error::Error;
+
+use crate::models::message::IntAddr;
+use crate::models::Lazy;
+
+/// Domain records dictionary (`DNS_RecordSet`).
+///
+/// Records are stored by their category hash.
+pub type DnsRecordSet = Dict<HashBytes, Lazy<DnsRecord>>;
+
+/// Computes a hash of the DNS record category name.
+///
+/// NOTE: Zero hash is used to request records of all categories.
+pub fn dns_category_hash(name: &str) -> HashBytes {
+    sha2::Sha256::digest(name).into()
+}
+
+/// Encodes the domain name into the internal DNS representation.
+///
+/// Labels are stored in reverse order, each followed by a zero byte
+/// (e.g. `sub.example.ton` is encoded as `ton\0example\0sub\0`).
+pub fn encode_dns_name(name: &str) -> Result<Vec<u8>, Error> {
+    let name = name.strip_suffix('.').unwrap_or(name);
+    if name.is_empty() || name.len() > MAX_DNS_NAME_LEN {
+        return Err(Error::InvalidData);
+    }
+
+    let mut result = Vec::with_capacity(name.len() + 1);
+    for label in name.rsplit('.') {
+        if label.is_empty() || !label.bytes().all(is_valid_dns_char) {
+            return Err(Error::InvalidData);
+        }
+        result.extend_from_slice(label.as_bytes());
+        result.push(0);
+    }
+    Ok(result)
+}
+
+/// Decodes the domain name from the internal DNS representation.
+///
+/// See [`encode_dns_name`].
+pub fn decode_dns_name(data: &[u8]) -> Result<String, Error> {
+    let data = match data.strip_suffix(&[0]) {
+        Some(data) if !data.is_empty() => data,
+        _ => return Err(Error::InvalidData),
+    };
+
+    let mut labels = Vec::new();
+    for label in data.split(|&b| b == 0) {
+        if label.is_empty() || !label.iter().copied().all(is_valid_dns_char) {
+            return Err(Error::InvalidData);
+        }
+        // SAFETY: label contains only ASCII characters
+        labels.push(unsafe { std::str::from_utf8_unchecked(label) });
+    }
+    labels.reverse();
+    Ok(labels.join("."))
+}
+
+const MAX_DNS_NAME_LEN: usize = 126;
+
+fn is_valid_dns_char(c: u8) -> bool {
+    (0x21..=0x7e).contains(&c) && c != b'.'
+}
+
+/// Standard DNS record category.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum DnsCategory {
+    /// Wallet address (`wallet`).
+    Wallet,
+    /// Next resolver for subdomains (`dns_next_resolver`).
+    NextResolver,
+    /// ADNL address of the site (`site`).
+    Site,
+    /// Storage bag id (`storage`).
+    Storage,
+}
+
+impl DnsCategory {
+    /// Returns the category name.
+    pub const fn name(&self) -> &'static str {
+        match self {
+            Self::Wallet => "wallet",
+            Self::NextResolver => "dns_next_resolver",
+            Self::Site => "site",
+            Self::Storage => "storage",
+        }
+    }
+
+    /// Returns a hash of the category name, which is used as a key in [`DnsRecordSet`].
+    pub fn hash(&self) -> HashBytes {
+        dns_category_hash(self.name())
+    }
+
+    /// Finds a standard category by its hash.
+    pub fn from_hash(hash: &HashBytes) -> Option<Self> {
+        [Self::Wallet, Self::NextResolver, Self::Site, Self::Storage]
+            .into_iter()
+            .find(|category| category.hash() == *hash)
+    }
+}
+
+/// DNS record value.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum DnsRecord {
+    /// Smart contract address (usually stored in the [`DnsCategory::Wallet`] category).
+    SmcAddress {
+        /// Smart contract address.
+        address: IntAddr,
+        /// Optional list of the contract capabilities.
+        capabilities: Option<Vec<SmcCapability>>,
+    },
+    /// Address of the resolver for subdomains
+    /// (usually stored in the [`DnsCategory::NextResolver`] category).
+    NextResolver(IntAddr),
+    /// ADNL address (usually stored in the [`DnsCategory::Site`] category).
+    AdnlAddress {
+        /// ADNL address.
+        address: HashBytes,
+        /// Optional list of the supported protocols.
+        protocols: Option<Vec<DnsProtocol>>,
+    },
+    /// Storage bag id (usually stored in the [`DnsCategory::Storage`] category).
+    StorageAddress(HashBytes),
+}
+
+impl DnsRecord {
+    const TAG_LEN: u16 = 16;
+
+    const TAG_SMC_ADDRESS: u16 = 0x9fd3;
+    const TAG_NEXT_RESOLVER: u16 = 0xba93;
+    const TAG_ADNL_ADDRESS: u16 = 0xad01;
+    const TAG_STORAGE_ADDRESS: u16 = 0x7473;
+}
+
+impl Store for DnsRecord {
+    fn store_into(
+        &self,
+        builder: &mut CellBuilder,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
+        match self {
+            Self::SmcAddress {
+                address,
+                capabilities,
+            } => {
+                ok!(builder.store_u16(Self::TAG_SMC_ADDRESS));
+                ok!(address.store_into(builder, context));
+                store_list(capabilities.as_deref(), builder, context)
+            }
+            Self::NextResolver(address) => {
+                ok!(builder.store_u16(Self::TAG_NEXT_RESOLVER));
+                address.store_into(builder, context)
+            }
+            Self::AdnlAddress { address, protocols } => {
+                ok!(builder.store_u16(Self::TAG_ADNL_ADDRESS));
+                ok!(builder.store_u256(address));
+                store_list(protocols.as_deref(), builder, context)
+            }
+            Self::StorageAddress(bag_id) => {
+                ok!(builder.store_u16(Self::TAG_STORAGE_ADDRESS));
+                builder.store_u256(bag_id)
+            }
+        }
+    }
+}
+
+impl<'a> Load<'a> for DnsRecord {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        Ok(match ok!(slice.load_uint(Self::TAG_LEN)) as u16 {
+            Self::TAG_SMC_ADDRESS => Self::SmcAddress {
+                address: ok!(IntAddr::load_from(slice)),
+                capabilities: ok!(load_list(slice)),
+            },
+            Self::TAG_NEXT_RESOLVER => Self::NextResolver(ok!(IntAddr::load_from(slice))),
+            Self::TAG_ADNL_ADDRESS => Self::AdnlAddress {
+                address: ok!(slice.load_u256()),
+                protocols: ok!(load_list(slice)),
+            },
+            Self::TAG_STORAGE_ADDRESS => Self::StorageAddress(ok!(slice.load_u256())),
+            _ => return Err(Error::InvalidTag),
+        })
+    }
+}
+
+/// Stores `flags:(## 8)` followed by an optional bit-prefixed list.
+fn store_list<T: Store>(
+    items: Option<&[T]>,
+    builder: &mut CellBuilder,
+    context: &mut dyn CellContext,
+) -> Result<(), Error> {
+    let Some(items) = items else {
+        return builder.store_u8(0);
+    };
+
+    ok!(builder.store_u8(1));
+    for item in items {
+        ok!(builder.store_bit_one());
+        ok!(item.store_into(builder, context));
+    }
+    builder.store_bit_zero()
+}
+
+/// Loads `flags:(## 8)` followed by an optional bit-prefixed list.
+fn load_list<'a, T: Load<'a>>(slice: &mut CellSlice<'a>) -> Result<Option<Vec<T>>, Error> {
+    match ok!(slice.load_u8()) {
+        0 => return Ok(None),
+        1 => {}
+        _ => return Err(Error::InvalidData),
+    }
+
+    let mut items = Vec::new();
+    while ok!(slice.load_bit()) {
+        items.push(ok!(T::load_from(slice)));
+    }
+    Ok(Some(items))
+}
+
+/// Smart contract capability.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum SmcCapability {
+    /// Contract has a `seqno` get-method.
+    MethodSeqno,
+    /// Contract has a `get_public_key` get-method.
+    MethodPubkey,
+    /// Contract is a wallet.
+    IsWallet,
+    /// Contract has a human-readable name.
+    Name(String),
+}
+
+impl SmcCapability {
+    const TAG_METHOD_SEQNO: u16 = 0x5371;
+    const TAG_METHOD_PUBKEY: u16 = 0x71f4;
+    const TAG_IS_WALLET: u16 = 0x2177;
+    const TAG_NAME: u8 = 0xff;
+
+    const MAX_CHUNK_LEN: usize = 124;
+}
+
+impl Store for SmcCapability {
+    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        match self {
+            Self::MethodSeqno => builder.store_u16(Self::TAG_METHOD_SEQNO),
+            Self::MethodPubkey => builder.store_u16(Self::TAG_METHOD_PUBKEY),
+            Self::IsWallet => builder.store_u16(Self::TAG_IS_WALLET),
+            Self::Name(name) => {
+                ok!(builder.store_u8(Self::TAG_NAME));
+                if name.is_empty() {
+                    return builder.store_u8(0);
+                }
+
+                // NOTE: The inline chunk is left empty and all data is stored
+                // in a chain of references, so that the name doesn't depend on
+                // the remaining capacity of the parent cell.
+                let chunks = name
+                    .as_bytes()
+                    .chunks(Self::MAX_CHUNK_LEN)
+                    .collect::<Vec<_>>();
+                if chunks.len() >= u8::MAX as usize {
+                    return Err(Error::InvalidData);
+                }
+
+                // Build chunks from the last one, each referencing the next one
+                let mut next = None::<Cell>;
+                for chunk in chunks.iter().rev() {
+                    let mut chunk_builder = CellBuilder::new();
+                    ok!(chunk_builder.store_u8(chunk.len() as u8));
+                    ok!(chunk_builder.store_raw(chunk, ok!(text_chunk_bits(chunk.len()))));
+                    if let Some(next) = next {
+                        ok!(chunk_builder.store_reference(next));
+                    }
+                    next = Some(ok!(chunk_builder.build()));
+                }
+
+                ok!(builder.store_u8(chunks.len() as u8 + 1));
+                ok!(builder.store_u8(0));
+                match next {
+                    Some(next) => builder.store_reference(next),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+impl<'a> Load<'a> for SmcCapability {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        if ok!(slice.get_u8(0)) == Self::TAG_NAME {
+            slice.try_advance(8, 0);
+
+            let chunks = ok!(slice.load_u8());
+            let mut data = Vec::new();
+            if chunks > 0 {
+                ok!(load_text_chunk(slice, &mut data));
+            }
+            if chunks > 1 {
+                // Other chunks are stored in a chain of references
+                let mut cell = ok!(slice.load_reference_cloned());
+                for remaining in (1..chunks).rev() {
+                    let mut chunk = ok!(cell.as_slice());
+                    ok!(load_text_chunk(&mut chunk, &mut data));
+                    if remaining > 1 {
+                        cell = ok!(chunk.load_reference_cloned());
+                    }
+                }
+            }
+
+            return match String::from_utf8(data) {
+                Ok(name) => Ok(Self::Name(name)),
+                Err(_) => Err(Error::InvalidData),
+            };
+        }
+
+        Ok(match ok!(slice.load_u16()) {
+            Self::TAG_METHOD_SEQNO => Self::MethodSeqno,
+            Self::TAG_METHOD_PUBKEY => Self::MethodPubkey,
+            Self::TAG_IS_WALLET => Self::IsWallet,
+            _ => return Err(Error::InvalidTag),
+        })
+    }
+}
+
+fn load_text_chunk(slice: &mut CellSlice<'_>, data: &mut Vec<u8>) -> Result<(), Error> {
+    let len = ok!(slice.load_u8()) as usize;
+    let bits = ok!(text_chunk_bits(len));
+    let offset = data.len();
+    data.resize(offset + len, 0);
+    match slice.load_raw(&mut data[offset..], bits) {
+        Ok(_) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
+
+fn text_chunk_bits(len: usize) -> Result<u16, Error> {
+    match u16::try_from(len).ok().and_then(|len| len.checked_mul(8)) {
+        Some(bits) => Ok(bits),
+        None => Err(Error::IntOverflow),
+    }
+}
+
+/// Protocol supported by the ADNL address.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum DnsProtocol {
+    /// HTTP protocol.
+    Http,
+}
+
+impl DnsProtocol {
+    const TAG_HTTP: u16 = 0x4854;
+}
+
+impl Store for DnsProtocol {
+    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        match self {
+            Self::Http => builder.store_u16(Self::TAG_HTTP),
+        }
+    }
+}
+
+impl<'a> Load<'a> for DnsProtocol {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        match slice.load_u16() {
+            Ok(Self::TAG_HTTP) => Ok(Self::Http),
+            Ok(_) => Err(Error::InvalidTag),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_record(record: DnsRecord) {
+        let cell = CellBuilder::build_from(&record).unwrap();
+        assert_eq!(cell.parse::<DnsRecord>().unwrap(), record);
+    }
+
+    #[test]
+    fn dns_records() {
+        let address = IntAddr::from((0, HashBytes([0x11; 32])));
+
+        check_record(DnsRecord::NextResolver(address.clone()));
+        check_record(DnsRecord::StorageAddress(HashBytes([0x22; 32])));
+        check_record(DnsRecord::AdnlAddress {
+            address: HashBytes([0x33; 32]),
+            protocols: None,
+        });
+        check_record(DnsRecord::AdnlAddress {
+            address: HashBytes([0x33; 32]),
+            protocols: Some(vec![DnsProtocol::Http]),
+        });
+        check_record(DnsRecord::SmcAddress {
+            address: address.clone(),
+            capabilities: None,
+        });
+        check_record(DnsRecord::SmcAddress {
+            address: address.clone(),
+            capabilities: Some(vec![]),
+        });
+        check_record(DnsRecord::SmcAddress {
+            address,
+            capabilities: Some(vec![
+                SmcCapability::IsWallet,
+                SmcCapability::Name("Wallet".to_owned()),
+                SmcCapability::MethodSeqno,
+                SmcCapability::Name(String::new()),
+                SmcCapability::MethodPubkey,
+            ]),
+        });
+
+        // Long names are split into chunks
+        let name = SmcCapability::Name("a".repeat(300));
+        let cell = CellBuilder::build_from(&name).unwrap();
+        assert_eq!(cell.reference_count(), 1);
+        assert_eq!(cell.parse::<SmcCapability>().unwrap(), name);
+
+        // Long names are stored in references, so they fit into records
+        let record = DnsRecord::SmcAddress {
+            address: IntAddr::from((0, HashBytes([0x33; 32]))),
+            capabilities: Some(vec![
+                SmcCapability::IsWallet,
+                SmcCapability::Name("b".repeat(1000)),
+                SmcCapability::Name("c".repeat(124)),
+                SmcCapability::MethodSeqno,
+            ]),
+        };
+        check_record(record);
+
+        // Names which don't fit into the max number of chunks are rejected
+        let name = SmcCapability::Name("a".repeat(124 * 256));
+        assert!(CellBuilder::build_from(&name).is_err());
+
+        // Record set
+        let record = DnsRecord::StorageAddress(HashBytes([0x44; 32]));
+        let mut records = DnsRecordSet::new();
+        records
+            .set(DnsCategory::Storage.hash(), Lazy::new(&record).unwrap())
+            .unwrap();
+
+        let (category, value) = records.iter().next().unwrap().unwrap();
+        assert_eq!(
+            DnsCategory::from_hash(&category),
+            Some(DnsCategory::Storage)
+        );
+        assert_eq!(value.load().unwrap(), record);
+    }
+
+    #[test]
+    fn dns_categories() {
+        assert_eq!(
+            DnsCategory::Wallet.hash().to_string(),
+            "e8d44050873dba865aa7c170ab4cce64d90839a34dcfd6cf71d14e0205443b1b"
+        );
+        for category in [
+            DnsCategory::Wallet,
+            DnsCategory::NextResolver,
+            DnsCategory::Site,
+            DnsCategory::Storage,
+        ] {
+            assert_eq!(DnsCategory::from_hash(&category.hash()), Some(category));
+        }
+        assert_eq!(DnsCategory::from_hash(&HashBytes::ZERO), None);
+    }
+
+    #[test]
+    fn dns_names() {
+        assert_eq!(
+            encode_dns_name("sub.example.ton").unwrap(),
+            b"ton\0example\0sub\0"
+        );
+        assert_eq!(
+            encode_dns_name("example.ever.").unwrap(),
+            b"ever\0example\0"
+        );
+        assert!(encode_dns_name("").is_err());
+        assert!(encode_dns_name("example..ton").is_err());
+        assert!(encode_dns_name("exa mple.ton").is_err());
+
+        assert_eq!(
+            decode_dns_name(b"ton\0example\0sub\0").unwrap(),
+            "sub.example.ton"
+        );
+        assert!(decode_dns_name(b"").is_err());
+        assert!(decode_dns_name(b"ton\0example").is_err());
+        assert!(decode_dns_name(b"ton\0\0example\0").is_err());
+    }
+}
@@ -0,0 +1,833 @@
+//! Message and account filters for block streaming.
+
+use std::ops::RangeInclusive;
+
+use crate::cell::*;
+use crate::error::Error;
+use crate::models::block::{BlockExtra, ShardIdent};
+use crate::models::message::{IntAddr, Message, MessageSummary, MsgKind};
+use crate::num::Tokens;
+
+/// Address prefix predicate.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct AddrPrefix {
+    /// Address workchain.
+    pub workchain: i32,
+    /// Highest bits of the account address.
+    pub prefix: u64,
+    /// The number of significant bits of the prefix.
+    ///
+    /// Values greater than 64 are treated as 64.
+    pub bits: u8,
+}
+
+impl AddrPrefix {
+    /// Creates a new address prefix predicate.
+    ///
+    /// Only the highest `bits` bits of the `prefix` are used.
+    pub const fn new(workchain: i32, prefix: u64, bits: u8) -> Self {
+        let bits = if bits > 64 { 64 } else { bits };
+        Self {
+            workchain,
+            prefix: prefix & Self::mask(bits),
+            bits,
+        }
+    }
+
+    /// Creates a predicate which matches all addresses in the workchain.
+    pub const fn workchain(workchain: i32) -> Self {
+        Self::new(workchain, 0, 0)
+    }
+
+    /// Returns `true` if the specified address matches the prefix.
+    pub fn contains_address(&self, address: &IntAddr) -> bool {
+        self.workchain == address.workchain() && self.contains_prefix(address.prefix())
+    }
+
+    /// Returns `true` if the highest bits of the account address match the prefix.
+    pub const fn contains_prefix(&self, account_prefix: u64) -> bool {
+        (self.prefix ^ account_prefix) & Self::mask(self.bits) == 0
+    }
+
+    /// Returns `true` if some address in the shard could match the prefix.
+    pub const fn intersects_shard(&self, shard: &ShardIdent) -> bool {
+        let shard_bits = shard.prefix_len();
+        let bits = if (self.bits as u16) < shard_bits {
+            self.bits
+        } else {
+            shard_bits as u8
+        };
+        self.workchain == shard.workchain()
+            && (self.prefix ^ shard.prefix()) & Self::mask(bits) == 0
+    }
+
+    const fn mask(bits: u8) -> u64 {
+        if bits == 0 {
+            0
+        } else if bits >= 64 {
+            u64::MAX
+        } else {
+            u64::MAX << (64 - bits as u32)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AddrPrefix {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        #[derive(serde::Deserialize)]
+        struct AddrPrefixRepr {
+            workchain: i32,
+            prefix: u64,
+            bits: u8,
+        }
+
+        let repr = ok!(AddrPrefixRepr::deserialize(deserializer));
+        if repr.bits > 64 {
+            return Err(Error::custom("address prefix can't be longer than 64 bits"));
+        }
+        Ok(Self::new(repr.workchain, repr.prefix, repr.bits))
+    }
+}
+
+/// Message predicate.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum MsgPredicate {
+    /// Message has the specified type.
+    Kind(MsgKind),
+    /// Message source address matches the prefix.
+    Src(AddrPrefix),
+    /// Message destination address matches the prefix.
+    Dst(AddrPrefix),
+    /// Message body starts with the specified 32-bit opcode.
+    Opcode(u32),
+    /// Attached amount in native currency is in the range.
+    Value(RangeInclusive<Tokens>),
+}
+
+/// A set of message filter rules.
+///
+/// A message matches the filter when all predicates of at least
+/// one rule hold. Use [`compile`] to get an efficient matcher.
+///
+/// [`compile`]: MsgFilter::compile
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct MsgFilter {
+    rules: Vec<Vec<MsgPredicate>>,
+}
+
+impl MsgFilter {
+    /// Creates an empty filter which matches nothing.
+    pub const fn new() -> Self {
+        Self { rules: Vec::new() }
+    }
+
+    /// Adds a new rule as a conjunction of the specified predicates.
+    ///
+    /// NOTE: An empty rule matches all messages.
+    pub fn with_rule<I>(mut self, predicates: I) -> Self
+    where
+        I: IntoIterator<Item = MsgPredicate>,
+    {
+        self.rules.push(predicates.into_iter().collect());
+        self
+    }
+
+    /// Returns all rules of this filter.
+    pub fn rules(&self) -> &[Vec<MsgPredicate>] {
+        &self.rules
+    }
+
+    /// Merges predicates of each rule and drops rules which never match.
+    pub fn compile(&self) -> MsgMatcher {
+        let mut rules = Vec::with_capacity(self.rules.len());
+        for predicates in &self.rules {
+            if let Some(rule) = CompiledRule::new(predicates) {
+                rules.push(rule);
+            }
+        }
+
+        let needs_body = rules.iter().any(|rule| rule.opcode.is_some());
+        MsgMatcher { rules, needs_body }
+    }
+}
+
+/// Compiled message filter.
+#[derive(Debug, Clone)]
+pub struct MsgMatcher {
+    rules: Vec<CompiledRule>,
+    needs_body: bool,
+}
+
+impl MsgMatcher {
+    /// Returns `true` if no message can match this filter.
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Checks whether the serialized message matches the filter.
+    ///
+    /// NOTE: Only the message header is parsed unless some rule
+    /// requires the body opcode.
+    pub fn matches_message(&self, message: &DynCell) -> Result<bool, Error> {
+        if self.rules.is_empty() {
+            return Ok(false);
+        }
+
+        let summary = ok!(MessageSummary::from_cell(message));
+
+        let mut opcode = None;
+        for rule in &self.rules {
+            if !rule.matches_header(&summary) {
+                continue;
+            }
+
+            let Some(expected) = rule.opcode else {
+                return Ok(true);
+            };
+
+            // Body is parsed at most once
+            let opcode = match opcode {
+                Some(opcode) => opcode,
+                None => *opcode.insert(ok!(message.parse::<Message>()).body_opcode()),
+            };
+            if opcode == Some(expected) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// Finds all incoming and outgoing messages of the block transactions
+    /// which match the filter.
+    ///
+    /// NOTE: Transaction info and state updates are not parsed.
+    pub fn find_in_block(&self, extra: &BlockExtra) -> Result<Vec<MatchedMessage>, Error> {
+        let mut result = Vec::new();
+        if self.rules.is_empty() {
+            return Ok(result);
+        }
+
+        let account_blocks = ok!(extra.account_blocks.load());
+        for entry in account_blocks.iter() {
+            let (account, _, account_block) = ok!(entry);
+            for entry in account_block.transactions.iter() {
+                let (lt, _, tx) = ok!(entry);
+                let tx_hash = *tx.inner().repr_hash();
+                let tx = ok!(tx.load());
+
+                if let Some(message) = &tx.in_msg {
+                    if ok!(self.matches_message(message.as_ref())) {
+                        result.push(MatchedMessage {
+                            account,
+                            lt,
+                            tx_hash,
+                            incoming: true,
+                            message: message.clone(),
+                        });
+                    }
+                }
+
+                for message in tx.out_msgs.values() {
+                    let message = ok!(message);
+                    if ok!(self.matches_message(message.as_ref())) {
+                        result.push(MatchedMessage {
+                            account,
+                            lt,
+                            tx_hash,
+                            incoming: false,
+                            message,
+                        });
+                    }
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Returns `true` if some rule requires the message body.
+    pub fn needs_body(&self) -> bool {
+        self.needs_body
+    }
+}
+
+/// Message found by [`MsgMatcher::find_in_block`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MatchedMessage {
+    /// Account of the transaction.
+    pub account: HashBytes,
+    /// Logical time of the transaction.
+    pub lt: u64,
+    /// Hash of the transaction.
+    pub tx_hash: HashBytes,
+    /// Whether this message is an incoming message of the transaction.
+    pub incoming: bool,
+    /// Root cell of the message.
+    pub message: Cell,
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    kinds: u8,
+    src: Vec<AddrPrefix>,
+    dst: Vec<AddrPrefix>,
+    opcode: Option<u32>,
+    min_value: Tokens,
+    max_value: Tokens,
+}
+
+impl CompiledRule {
+    const ALL_KINDS: u8 = 0b111;
+
+    fn new(predicates: &[MsgPredicate]) -> Option<Self> {
+        let mut rule = Self {
+            kinds: Self::ALL_KINDS,
+            src: Vec::new(),
+            dst: Vec::new(),
+            opcode: None,
+            min_value: Tokens::ZERO,
+            max_value: Tokens::MAX,
+        };
+
+        for predicate in predicates {
+            match predicate {
+                MsgPredicate::Kind(kind) => rule.kinds &= Self::kind_bit(*kind),
+                MsgPredicate::Src(prefix) => rule.src.push(*prefix),
+                MsgPredicate::Dst(prefix) => rule.dst.push(*prefix),
+                MsgPredicate::Opcode(opcode) => match rule.opcode {
+                    Some(prev) if prev != *opcode => return None,
+                    _ => rule.opcode = Some(*opcode),
+                },
+                MsgPredicate::Value(range) => {
+                    rule.min_value = std::cmp::max(rule.min_value, *range.start());
+                    rule.max_value = std::cmp::min(rule.max_value, *range.end());
+                }
+            }
+        }
+
+        if rule.kinds == 0 || rule.min_value > rule.max_value {
+            return None;
+        }
+        Some(rule)
+    }
+
+    fn matches_header(&self, summary: &MessageSummary) -> bool {
+        fn matches_addr(prefixes: &[AddrPrefix], addr: &Option<IntAddr>) -> bool {
+            match addr {
+                Some(addr) => prefixes.iter().all(|prefix| prefix.contains_address(addr)),
+                None => prefixes.is_empty(),
+            }
+        }
+
+        self.kinds & Self::kind_bit(summary.kind) != 0
+            && (self.min_value..=self.max_value).contains(&summary.value)
+            && matches_addr(&self.src, &summary.src)
+            && matches_addr(&self.dst, &summary.dst)
+    }
+
+    const fn kind_bit(kind: MsgKind) -> u8 {
+        match kind {
+            MsgKind::Int => 0b001,
+            MsgKind::ExtIn => 0b010,
+            MsgKind::ExtOut => 0b100,
+        }
+    }
+}
+
+/// A set of accounts and address prefixes.
+///
+/// Optimized for fast membership tests of large sets of accounts
+/// (e.g. when an indexer scans blocks for subscribed addresses).
+/// Each workchain has a bitmap of the highest address bits of its
+/// accounts, so most misses are rejected without a lookup.
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
+pub struct AccountFilter {
+    prefixes: Vec<AddrPrefix>,
+    workchains: Vec<WorkchainAccounts>,
+}
+
+impl AccountFilter {
+    /// Creates an empty filter which matches nothing.
+    pub const fn new() -> Self {
+        Self {
+            prefixes: Vec::new(),
+            workchains: Vec::new(),
+        }
+    }
+
+    /// Returns a builder for the account filter.
+    pub fn builder() -> AccountFilterBuilder {
+        AccountFilterBuilder::default()
+    }
+
+    /// Returns `true` if the filter matches nothing.
+    pub fn is_empty(&self) -> bool {
+        self.prefixes.is_empty() && self.workchains.is_empty()
+    }
+
+    /// Returns the total number of accounts in the filter.
+    pub fn account_count(&self) -> usize {
+        self.workchains.iter().map(|wc| wc.accounts.len()).sum()
+    }
+
+    /// Returns sorted address prefixes of the filter.
+    pub fn prefixes(&self) -> &[AddrPrefix] {
+        &self.prefixes
+    }
+
+    /// Returns an iterator over all accounts ordered by workchain and address.
+    pub fn accounts(&self) -> impl Iterator<Item = (i32, &HashBytes)> {
+        self.workchains
+            .iter()
+            .flat_map(|wc| wc.accounts.iter().map(move |a| (wc.workchain, a)))
+    }
+
+    /// Returns `true` if the specified address matches the filter.
+    pub fn matches(&self, address: &IntAddr) -> bool {
+        let workchain = address.workchain();
+        if self.matches_prefix(workchain, address.prefix()) {
+            return true;
+        }
+        match address.account_id() {
+            Some(account) => self.contains_account(workchain, &account),
+            None => false,
+        }
+    }
+
+    /// Returns `true` if the specified account matches the filter.
+    pub fn matches_account(&self, workchain: i32, account: &HashBytes) -> bool {
+        let a = &account.0;
+        let prefix = u64::from_be_bytes([a[0], a[1], a[2], a[3], a[4], a[5], a[6], a[7]]);
+        self.matches_prefix(workchain, prefix) || self.contains_account(workchain, account)
+    }
+
+    /// Returns `true` if some address in the shard could match the filter.
+    ///
+    /// Can be used to skip the whole shard block.
+    pub fn intersects_shard(&self, shard: &ShardIdent) -> bool {
+        if self
+            .workchain_prefixes(shard.workchain())
+            .iter()
+            .any(|prefix| prefix.intersects_shard(shard))
+        {
+            return true;
+        }
+
+        let Some(wc) = self.find_workchain(shard.workchain()) else {
+            return false;
+        };
+
+        // Find the first account which is not less than the lowest shard address
+        let prefix = shard.prefix();
+        let mut lowest = HashBytes::ZERO;
+        lowest.0[..8].copy_from_slice(&(prefix & prefix.wrapping_sub(1)).to_be_bytes());
+
+        let index = wc.accounts.partition_point(|account| account < &lowest);
+        matches!(wc.accounts.get(index), Some(account) if shard.contains_account(account))
+    }
+
+    fn matches_prefix(&self, workchain: i32, account_prefix: u64) -> bool {
+        self.workchain_prefixes(workchain)
+            .iter()
+            .any(|prefix| prefix.contains_prefix(account_prefix))
+    }
+
+    fn contains_account(&self, workchain: i32, account: &HashBytes) -> bool {
+        match self.find_workchain(workchain) {
+            Some(wc) => wc.contains(account),
+            None => false,
+        }
+    }
+
+    fn workchain_prefixes(&self, workchain: i32) -> &[AddrPrefix] {
+        let start = self.prefixes.partition_point(|p| p.workchain < workchain);
+        let end = self.prefixes.partition_point(|p| p.workchain <= workchain);
+        &self.prefixes[start..end]
+    }
+
+    fn find_workchain(&self, workchain: i32) -> Option<&WorkchainAccounts> {
+        // NOTE: There are usually only a few workchains
+        self.workchains.iter().find(|wc| wc.workchain == workchain)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AccountFilter {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        AccountFilterRepr {
+            prefixes: self.prefixes.clone(),
+            accounts: self.accounts().map(|(wc, a)| (wc, *a)).collect(),
+        }
+        .serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for AccountFilter {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = ok!(AccountFilterRepr::deserialize(deserializer));
+
+        let mut builder = AccountFilterBuilder::default();
+        for prefix in repr.prefixes {
+            builder.add_prefix(AddrPrefix::new(
+                prefix.workchain,
+                prefix.prefix,
+                prefix.bits,
+            ));
+        }
+        for (workchain, account) in repr.accounts {
+            builder.add_account(workchain, account);
+        }
+        Ok(builder.build())
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct AccountFilterRepr {
+    prefixes: Vec<AddrPrefix>,
+    accounts: Vec<(i32, HashBytes)>,
+}
+
+/// A builder for the [`AccountFilter`].
+#[derive(Debug, Default, Clone)]
+pub struct AccountFilterBuilder {
+    prefixes: Vec<AddrPrefix>,
+    accounts: Vec<(i32, HashBytes)>,
+}
+
+impl AccountFilterBuilder {
+    /// Adds an address prefix.
+    pub fn add_prefix(&mut self, prefix: AddrPrefix) -> &mut Self {
+        // NOTE: Normalize the prefix in case it was constructed manually
+        self.prefixes.push(AddrPrefix::new(
+            prefix.workchain,
+            prefix.prefix,
+            prefix.bits,
+        ));
+        self
+    }
+
+    /// Adds an account.
+    pub fn add_account(&mut self, workchain: i32, account: HashBytes) -> &mut Self {
+        self.accounts.push((workchain, account));
+        self
+    }
+
+    /// Adds an account of the specified address.
+    ///
+    /// Returns `false` if the address has no 256-bit account id.
+    pub fn add_address(&mut self, address: &IntAddr) -> bool {
+        match address.account_id() {
+            Some(account) => {
+                self.accounts.push((address.workchain(), account));
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Sorts all items and builds lookup bitmaps.
+    pub fn build(mut self) -> AccountFilter {
+        self.prefixes.sort_unstable();
+        self.prefixes.dedup();
+
+        self.accounts.sort_unstable();
+        self.accounts.dedup();
+
+        let mut workchains = Vec::<WorkchainAccounts>::new();
+        for (workchain, account) in self.accounts {
+            let wc = match workchains.last_mut() {
+                Some(wc) if wc.workchain == workchain => wc,
+                _ => {
+                    workchains.push(WorkchainAccounts::new(workchain));
+                    workchains.last_mut().unwrap()
+                }
+            };
+            wc.insert_sorted(account);
+        }
+
+        AccountFilter {
+            prefixes: self.prefixes,
+            workchains,
+        }
+    }
+}
+
+impl Extend<IntAddr> for AccountFilterBuilder {
+    fn extend<T: IntoIterator<Item = IntAddr>>(&mut self, iter: T) {
+        for address in iter {
+            self.add_address(&address);
+        }
+    }
+}
+
+impl FromIterator<IntAddr> for AccountFilter {
+    fn from_iter<T: IntoIterator<Item = IntAddr>>(iter: T) -> Self {
+        let mut builder = AccountFilterBuilder::default();
+        builder.extend(iter);
+        builder.build()
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+struct WorkchainAccounts {
+    workchain: i32,
+    bitmap: Box<[u64]>,
+    accounts: Vec<HashBytes>,
+}
+
+impl WorkchainAccounts {
+    /// The number of the highest address bits used as a bitmap index.
+    const BITMAP_BITS: u32 = 16;
+
+    fn new(workchain: i32) -> Self {
+        Self {
+            workchain,
+            bitmap: vec![0; 1 << (Self::BITMAP_BITS - 6)].into_boxed_slice(),
+            accounts: Vec::new(),
+        }
+    }
+
+    fn insert_sorted(&mut self, account: HashBytes) {
+        let index = Self::bitmap_index(&account);
+        self.bitmap[index >> 6] |= 1 << (index & 63);
+        self.accounts.push(account);
+    }
+
+    fn contains(&self, account: &HashBytes) -> bool {
+        let index = Self::bitmap_index(account);
+        self.bitmap[index >> 6] & (1 << (index & 63)) != 0
+            && self.accounts.binary_search(account).is_ok()
+    }
+
+    #[inline]
+    fn bitmap_index(account: &HashBytes) -> usize {
+        u16::from_be_bytes([account.0[0], account.0[1]]) as usize
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boc::Boc;
+    use crate::models::Block;
+
+    #[test]
+    fn addr_prefix() {
+        let addr = IntAddr::from((0, HashBytes([0xab; 32])));
+        assert!(AddrPrefix::workchain(0).contains_address(&addr));
+        assert!(!AddrPrefix::workchain(-1).contains_address(&addr));
+        assert!(AddrPrefix::new(0, 0xa000_0000_0000_0000, 4).contains_address(&addr));
+        assert!(AddrPrefix::new(0, 0xabff_0000_0000_0000, 8).contains_address(&addr));
+        assert!(!AddrPrefix::new(0, 0xac00_0000_0000_0000, 8).contains_address(&addr));
+        assert!(AddrPrefix::new(0, u64::MAX, 0).contains_address(&addr));
+
+        // Too long prefixes are treated as 64-bit prefixes
+        let full = AddrPrefix {
+            workchain: 0,
+            prefix: 0xabab_abab_abab_abab,
+            bits: 100,
+        };
+        assert!(full.contains_address(&addr));
+        assert!(!full.contains_prefix(0xabab_abab_abab_abaa));
+        assert!(full.intersects_shard(&ShardIdent::BASECHAIN));
+        assert_eq!(AddrPrefix::new(0, full.prefix, full.bits).bits, 64);
+
+        let mut builder = AccountFilter::builder();
+        builder.add_prefix(full);
+        let filter = builder.build();
+        assert!(filter.matches_account(0, &HashBytes([0xab; 32])));
+        assert!(filter.intersects_shard(&ShardIdent::BASECHAIN));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn addr_prefix_serde() {
+        let prefix = AddrPrefix::new(0, 0xabff_0000_0000_0000, 8);
+        let json = serde_json::to_string(&prefix).unwrap();
+        assert_eq!(serde_json::from_str::<AddrPrefix>(&json).unwrap(), prefix);
+
+        // Unused bits are cleared
+        let parsed = serde_json::from_str::<AddrPrefix>(
+            r#"{"workchain":0,"prefix":18446744073709551615,"bits":4}"#,
+        )
+        .unwrap();
+        assert_eq!(parsed, AddrPrefix::new(0, 0xf000_0000_0000_0000, 4));
+
+        assert!(
+            serde_json::from_str::<AddrPrefix>(r#"{"workchain":0,"prefix":0,"bits":65}"#).is_err()
+        );
+    }
+
+    #[test]
+    fn account_filter() {
+        let first = HashBytes([0xab; 32]);
+        let mut second = first;
+        second.0[31] = 0;
+        let mut third = HashBytes::ZERO;
+        third.0[0] = 0x40;
+
+        let mut builder = AccountFilter::builder();
+        builder
+            .add_account(0, first)
+            .add_account(0, first)
+            .add_account(-1, third)
+            .add_prefix(AddrPrefix::new(0, 0xc000_0000_0000_0000, 2));
+        assert!(builder.add_address(&IntAddr::from((0, third))));
+        let filter = builder.build();
+        assert_eq!(filter.account_count(), 3);
+        assert_eq!(filter.prefixes().len(), 1);
+
+        // Accounts
+        assert!(filter.matches(&IntAddr::from((0, first))));
+        assert!(filter.matches_account(0, &first));
+        assert!(filter.matches_account(-1, &third));
+        assert!(filter.matches_account(0, &third));
+        assert!(!filter.matches_account(0, &second));
+        assert!(!filter.matches_account(-1, &first));
+        assert!(!filter.matches_account(1, &first));
+
+        // Prefixes
+        assert!(filter.matches_account(0, &HashBytes([0xff; 32])));
+        assert!(filter.matches_account(0, &HashBytes([0xc0; 32])));
+        assert!(!filter.matches_account(-1, &HashBytes([0xff; 32])));
+
+        // Shards
+        let (left, right) = ShardIdent::BASECHAIN.split().unwrap();
+        assert!(filter.intersects_shard(&ShardIdent::BASECHAIN));
+        assert!(filter.intersects_shard(&left));
+        assert!(filter.intersects_shard(&right));
+        let (right_left, right_right) = right.split().unwrap();
+        assert!(filter.intersects_shard(&right_left));
+        assert!(filter.intersects_shard(&right_right));
+        let (left_left, left_right) = left.split().unwrap();
+        assert!(!filter.intersects_shard(&left_left));
+        assert!(filter.intersects_shard(&left_right));
+        assert!(filter.intersects_shard(&ShardIdent::MASTERCHAIN));
+        assert!(!filter.intersects_shard(&ShardIdent::new_full(1)));
+
+        // Collect from addresses
+        let filter = [IntAddr::from((0, first)), IntAddr::from((-1, second))]
+            .into_iter()
+            .collect::<AccountFilter>();
+        assert_eq!(
+            filter.accounts().collect::<Vec<_>>(),
+            [(-1, &second), (0, &first)]
+        );
+        assert!(AccountFilter::new().is_empty());
+        assert!(!AccountFilter::new().matches(&IntAddr::from((0, first))));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn account_filter_serde() {
+        let mut builder = AccountFilter::builder();
+        builder
+            .add_account(0, HashBytes([0x11; 32]))
+            .add_account(-1, HashBytes([0x22; 32]))
+            .add_prefix(AddrPrefix::new(0, 0xf000_0000_0000_0000, 4));
+        let filter = builder.build();
+
+        let json = serde_json::to_string(&filter).unwrap();
+        let parsed = serde_json::from_str::<AccountFilter>(&json).unwrap();
+        assert_eq!(parsed, filter);
+    }
+
+    #[test]
+    fn filter_block_messages() {
+        let block = Boc::decode(include_bytes!("block/tests/simple_shard_block.boc"))
+            .unwrap()
+            .parse::<Block>()
+            .unwrap();
+        let extra = block.load_extra().unwrap();
+
+        // Collect all messages
+        let all = MsgFilter::new().with_rule([]).compile();
+        let messages = all.find_in_block(&extra).unwrap();
+        assert!(!messages.is_empty());
+
+        // Empty filter matches nothing
+        assert!(MsgFilter::new().compile().is_empty());
+        assert!(MsgFilter::new()
+            .compile()
+            .find_in_block(&extra)
+            .unwrap()
+            .is_empty());
+
+        // Conflicting predicates
+        let never = MsgFilter::new()
+            .with_rule([
+                MsgPredicate::Kind(MsgKind::Int),
+                MsgPredicate::Kind(MsgKind::ExtIn),
+            ])
+            .with_rule([MsgPredicate::Opcode(1), MsgPredicate::Opcode(2)])
+            .with_rule([MsgPredicate::Value(Tokens::new(10)..=Tokens::new(1))])
+            .compile();
+        assert!(never.is_empty());
+
+        for matched in &messages {
+            let summary = MessageSummary::from_cell(matched.message.as_ref()).unwrap();
+            let parsed = matched.message.parse::<Message>().unwrap();
+
+            let mut predicates = vec![MsgPredicate::Kind(summary.kind)];
+            if let Some(src) = &summary.src {
+                predicates.push(MsgPredicate::Src(AddrPrefix::new(
+                    src.workchain(),
+                    src.prefix(),
+                    64,
+                )));
+            }
+            if let Some(dst) = &summary.dst {
+                predicates.push(MsgPredicate::Dst(AddrPrefix::new(
+                    dst.workchain(),
+                    dst.prefix(),
+                    64,
+                )));
+            }
+            if let Some(opcode) = parsed.body_opcode() {
+                predicates.push(MsgPredicate::Opcode(opcode));
+            }
+            predicates.push(MsgPredicate::Value(summary.value..=summary.value));
+
+            let matcher = MsgFilter::new().with_rule(predicates.clone()).compile();
+            assert_eq!(matcher.needs_body(), parsed.body_opcode().is_some());
+            assert!(matcher.matches_message(matched.message.as_ref()).unwrap());
+            assert!(matcher
+                .find_in_block(&extra)
+                .unwrap()
+                .iter()
+                .any(|item| item == matched));
+
+            // Value out of range
+            if let Some(value) = summary.value.checked_add(Tokens::new(1)) {
+                let matcher = MsgFilter::new()
+                    .with_rule([
+                        MsgPredicate::Kind(summary.kind),
+                        MsgPredicate::Value(value..=Tokens::MAX),
+                    ])
+                    .compile();
+                assert!(!matcher.matches_message(matched.message.as_ref()).unwrap());
+            }
+        }
+
+        // Kinds are split between rules
+        let internal = MsgFilter::new()
+            .with_rule([MsgPredicate::Kind(MsgKind::Int)])
+            .compile()
+            .find_in_block(&extra)
+            .unwrap();
+        let external = MsgFilter::new()
+            .with_rule([MsgPredicate::Kind(MsgKind::ExtIn)])
+            .with_rule([MsgPredicate::Kind(MsgKind::ExtOut)])
+            .compile()
+            .find_in_block(&extra)
+            .unwrap();
+        assert_eq!(internal.len() + external.len(), messages.len());
+    }
+}
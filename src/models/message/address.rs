@@ -77,6 +77,58 @@ impl IntAddr {
             Self::Var(addr) => addr.bit_len(),
         }
     }
+
+    /// Constructs an address for the specified workchain and account id.
+    ///
+    /// Returns a standard address if the workchain fits into `i8`,
+    /// otherwise returns a variable-length address with 256 bits of data.
+    pub fn with_workchain(workchain: i32, address: HashBytes) -> Self {
+        if let Ok(workchain) = i8::try_from(workchain) {
+            Self::Std(StdAddr::new(workchain, address))
+        } else {
+            Self::Var(VarAddr::new(workchain, address))
+        }
+    }
+
+    /// Returns the length of the account id in bits.
+    pub const fn address_bit_len(&self) -> u16 {
+        match self {
+            Self::Std(_) => 256,
+            Self::Var(addr) => addr.address_len.into_inner(),
+        }
+    }
+
+    /// Returns the first 64 bits of the account id.
+    ///
+    /// NOTE: Short account ids are padded with zeros.
+    pub fn prefix(&self) -> u64 {
+        match self {
+            Self::Std(addr) => addr.prefix(),
+            Self::Var(addr) => addr.prefix(),
+        }
+    }
+
+    /// Returns a 256-bit account id (if the address has it).
+    pub fn account_id(&self) -> Option<HashBytes> {
+        match self {
+            Self::Std(addr) => Some(addr.address),
+            Self::Var(addr) => addr.account_id(),
+        }
+    }
+
+    /// Converts a variable-length address into the standard one if possible.
+    ///
+    /// A conversion is possible if the workchain fits into `i8` and
+    /// the account id is exactly 256 bits long.
+    pub fn normalize(self) -> Self {
+        match self {
+            Self::Var(addr) => match addr.to_std() {
+                Some(addr) => Self::Std(addr),
+                None => Self::Var(addr),
+            },
+            addr => addr,
+        }
+    }
 }
 
 impl From<(i8, HashBytes)> for IntAddr {
@@ -90,8 +142,14 @@ impl FromStr for IntAddr {
     type Err = ParseAddrError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // TODO: impl from_str for VarAddr
-        Ok(Self::Std(ok!(StdAddr::from_str(s))))
+        // NOTE: Addresses which can be represented as standard are parsed as standard
+        match StdAddr::from_str(s) {
+            Ok(addr) => Ok(Self::Std(addr)),
+            Err(ParseAddrError::InvalidWorkchain | ParseAddrError::InvalidAccountId) => {
+                Ok(Self::Var(ok!(VarAddr::from_str(s))))
+            }
+            Err(e) => Err(e),
+        }
     }
 }
 
@@ -99,7 +157,7 @@ impl std::fmt::Display for IntAddr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             IntAddr::Std(addr) => std::fmt::Display::fmt(addr, f),
-            IntAddr::Var(_) => f.write_str("varaddr"), // TODO: impl display
+            IntAddr::Var(addr) => std::fmt::Display::fmt(addr, f),
         }
     }
 }
@@ -226,15 +284,37 @@ impl StdAddr {
         }
         bit_len
     }
+
+    /// Returns the first 64 bits of the account id.
+    pub const fn prefix(&self) -> u64 {
+        let a = &self.address.0;
+        u64::from_be_bytes([a[0], a[1], a[2], a[3], a[4], a[5], a[6], a[7]])
+    }
 }
 
+/// Formats address as `[anycast:]workchain:address`.
+///
+/// Alternate mode (`{:#}`) also prints the anycast prefix length
+/// and the first 64 bits of the account id (used for shard routing).
 impl std::fmt::Display for StdAddr {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if let Some(anycast) = &self.anycast {
             ok!(f.write_fmt(format_args!("{anycast}:")))
         }
 
-        f.write_fmt(format_args!("{}:{}", self.workchain, self.address))
+        ok!(f.write_fmt(format_args!("{}:{}", self.workchain, self.address)));
+
+        if f.alternate() {
+            ok!(f.write_fmt(format_args!(" (prefix {:016x}", self.prefix())));
+            if let Some(anycast) = &self.anycast {
+                ok!(f.write_fmt(format_args!(
+                    ", anycast depth {}",
+                    anycast.depth.into_bit_len()
+                )));
+            }
+            ok!(f.write_str(")"));
+        }
+        Ok(())
     }
 }
 
@@ -267,7 +347,11 @@ impl FromStr for StdAddr {
             return Err(ParseAddrError::Empty);
         }
 
-        let mut result = Self::default();
+        let (anycast, s) = ok!(split_anycast(s));
+        let mut result = Self {
+            anycast,
+            ..Default::default()
+        };
 
         let mut parts = s.split(':');
         match parts.next() {
@@ -294,6 +378,206 @@ impl FromStr for StdAddr {
     }
 }
 
+impl StdAddr {
+    /// Parses a standard address from a string in one of the allowed formats.
+    ///
+    /// Raw addresses can be in `workchain:hex` form or just 64 hex characters
+    /// (with the basechain workchain). Base64 addresses are 36 bytes with flags,
+    /// workchain, account id and CRC16 checksum.
+    ///
+    /// Returns the address and the flags of its representation
+    /// (default flags for raw addresses).
+    pub fn from_str_ext(
+        s: &str,
+        format: StdAddrFormat,
+    ) -> Result<(Self, Base64StdAddrFlags), ParseAddrError> {
+        if s.is_empty() {
+            return Err(ParseAddrError::Empty);
+        }
+
+        if format.allow_raw {
+            if s.contains(':') {
+                return Self::from_str(s).map(|addr| (addr, Base64StdAddrFlags::default()));
+            } else if s.len() == 64 {
+                let mut address = HashBytes::ZERO;
+                return match hex::decode_to_slice(s, &mut address.0) {
+                    Ok(()) => Ok((Self::new(0, address), Base64StdAddrFlags::default())),
+                    Err(_) => Err(ParseAddrError::InvalidAccountId),
+                };
+            }
+        }
+
+        #[cfg(feature = "base64")]
+        if s.len() == 48 {
+            use base64::Engine;
+
+            let url_chars = s.contains(['-', '_']);
+            let std_chars = s.contains(['+', '/']);
+            let base64_url = match (url_chars, std_chars) {
+                (true, false) if format.allow_base64_url => true,
+                (false, true) if format.allow_base64 => false,
+                (false, false) if format.allow_base64 || format.allow_base64_url => {
+                    !format.allow_base64
+                }
+                _ => return Err(ParseAddrError::BadFormat),
+            };
+            let engine = if base64_url {
+                &base64::engine::general_purpose::URL_SAFE
+            } else {
+                &base64::engine::general_purpose::STANDARD
+            };
+
+            let mut buffer = [0u8; 36];
+            if !matches!(engine.decode_slice(s, &mut buffer), Ok(36)) {
+                return Err(ParseAddrError::BadFormat);
+            }
+
+            let crc = crc_16(&buffer[..34]);
+            if buffer[34..] != crc.to_be_bytes() {
+                return Err(ParseAddrError::BadFormat);
+            }
+
+            let bounceable = match buffer[0] & 0x7f {
+                Base64StdAddrFlags::TAG_BOUNCEABLE => true,
+                Base64StdAddrFlags::TAG_NON_BOUNCEABLE => false,
+                _ => return Err(ParseAddrError::BadFormat),
+            };
+
+            let addr = Self::new(buffer[1] as i8, HashBytes::from_slice(&buffer[2..34]));
+            let flags = Base64StdAddrFlags {
+                testnet: buffer[0] & 0x80 != 0,
+                base64_url,
+                bounceable,
+            };
+            return Ok((addr, flags));
+        }
+
+        Err(ParseAddrError::BadFormat)
+    }
+
+    /// Returns an object which will display address as a bounceable or
+    /// non-bounceable base64 string.
+    ///
+    /// NOTE: Anycast info is ignored.
+    #[cfg(feature = "base64")]
+    pub const fn display_base64(&self, bounceable: bool) -> DisplayBase64StdAddr<'_> {
+        self.display_base64_ext(Base64StdAddrFlags {
+            testnet: false,
+            base64_url: false,
+            bounceable,
+        })
+    }
+
+    /// Returns an object which will display address as a bounceable or
+    /// non-bounceable URL-safe base64 string.
+    ///
+    /// NOTE: Anycast info is ignored.
+    #[cfg(feature = "base64")]
+    pub const fn display_base64_url(&self, bounceable: bool) -> DisplayBase64StdAddr<'_> {
+        self.display_base64_ext(Base64StdAddrFlags {
+            testnet: false,
+            base64_url: true,
+            bounceable,
+        })
+    }
+
+    /// Returns an object which will display address as a base64 string
+    /// with the specified flags.
+    ///
+    /// NOTE: Anycast info is ignored.
+    #[cfg(feature = "base64")]
+    pub const fn display_base64_ext(&self, flags: Base64StdAddrFlags) -> DisplayBase64StdAddr<'_> {
+        DisplayBase64StdAddr { addr: self, flags }
+    }
+}
+
+/// Allowed formats for [`StdAddr::from_str_ext`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct StdAddrFormat {
+    /// Allow `workchain:hex` and plain hex addresses.
+    pub allow_raw: bool,
+    /// Allow base64 addresses.
+    pub allow_base64: bool,
+    /// Allow URL-safe base64 addresses.
+    pub allow_base64_url: bool,
+}
+
+impl StdAddrFormat {
+    /// Allows all formats.
+    pub const fn any() -> Self {
+        Self {
+            allow_raw: true,
+            allow_base64: true,
+            allow_base64_url: true,
+        }
+    }
+}
+
+impl Default for StdAddrFormat {
+    #[inline]
+    fn default() -> Self {
+        Self::any()
+    }
+}
+
+/// Flags of the base64 address representation.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub struct Base64StdAddrFlags {
+    /// Address is intended for the testnet.
+    pub testnet: bool,
+    /// Address uses URL-safe base64 alphabet.
+    pub base64_url: bool,
+    /// Messages to this address must be sent with the bounce flag.
+    pub bounceable: bool,
+}
+
+#[cfg(feature = "base64")]
+impl Base64StdAddrFlags {
+    const TAG_BOUNCEABLE: u8 = 0x11;
+    const TAG_NON_BOUNCEABLE: u8 = 0x51;
+}
+
+/// Helper struct to display address as a base64 string.
+#[cfg(feature = "base64")]
+#[derive(Clone, Copy)]
+pub struct DisplayBase64StdAddr<'a> {
+    /// Standard address.
+    pub addr: &'a StdAddr,
+    /// Representation flags.
+    pub flags: Base64StdAddrFlags,
+}
+
+#[cfg(feature = "base64")]
+impl std::fmt::Display for DisplayBase64StdAddr<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        use base64::Engine;
+
+        let mut buffer = [0u8; 36];
+        buffer[0] = (if self.flags.bounceable {
+            Base64StdAddrFlags::TAG_BOUNCEABLE
+        } else {
+            Base64StdAddrFlags::TAG_NON_BOUNCEABLE
+        }) | ((self.flags.testnet as u8) << 7);
+        buffer[1] = self.addr.workchain as u8;
+        buffer[2..34].copy_from_slice(self.addr.address.as_array());
+        let crc = crc_16(&buffer[..34]);
+        buffer[34..].copy_from_slice(&crc.to_be_bytes());
+
+        let engine = if self.flags.base64_url {
+            &base64::engine::general_purpose::URL_SAFE
+        } else {
+            &base64::engine::general_purpose::STANDARD
+        };
+
+        let mut output = [0u8; 48];
+        engine.encode_slice(buffer, &mut output).ok();
+
+        // SAFETY: output is guaranteed to contain only ASCII
+        let output = unsafe { std::str::from_utf8_unchecked(&output) };
+        f.write_str(output)
+    }
+}
+
 impl Store for StdAddr {
     fn store_into(
         &self,
@@ -406,7 +690,10 @@ impl<'de> serde::Deserialize<'de> for StdAddr {
             where
                 E: Error,
             {
-                StdAddr::from_str(v).map_err(E::custom)
+                match StdAddr::from_str_ext(v, StdAddrFormat::any()) {
+                    Ok((addr, _)) => Ok(addr),
+                    Err(e) => Err(E::custom(e)),
+                }
             }
         }
 
@@ -419,6 +706,119 @@ impl<'de> serde::Deserialize<'de> for StdAddr {
     }
 }
 
+/// Serde helper to use [`StdAddr`] in base64 representation.
+///
+/// Serializes the address as a base64 string (URL-safe by default,
+/// non-bounceable by default). Deserializes addresses in any format.
+///
+/// ```ignore
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Wallet {
+///     #[serde(with = "StdAddrBase64Repr::<true, false>")]
+///     address: StdAddr,
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub struct StdAddrBase64Repr<const URL_SAFE: bool = true, const BOUNCEABLE: bool = false>;
+
+#[cfg(feature = "serde")]
+impl<const URL_SAFE: bool, const BOUNCEABLE: bool> StdAddrBase64Repr<URL_SAFE, BOUNCEABLE> {
+    /// Serializes the address as a base64 string.
+    pub fn serialize<S>(addr: &StdAddr, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::Serialize;
+
+        if serializer.is_human_readable() {
+            serializer.collect_str(&addr.display_base64_ext(Base64StdAddrFlags {
+                testnet: false,
+                base64_url: URL_SAFE,
+                bounceable: BOUNCEABLE,
+            }))
+        } else {
+            addr.serialize(serializer)
+        }
+    }
+
+    /// Deserializes the address in any format.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<StdAddr, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        <StdAddr as serde::Deserialize>::deserialize(deserializer)
+    }
+}
+
+/// Serde helper to use [`IntAddr`] in base64 representation.
+///
+/// Serializes standard addresses as base64 strings (URL-safe by default,
+/// non-bounceable by default) and variable addresses as raw strings.
+/// Deserializes addresses in any format.
+///
+/// ```ignore
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Message {
+///     #[serde(with = "IntAddrBase64Repr::<true, false>")]
+///     dst: IntAddr,
+/// }
+/// ```
+#[cfg(feature = "serde")]
+pub struct IntAddrBase64Repr<const URL_SAFE: bool = true, const BOUNCEABLE: bool = false>;
+
+#[cfg(feature = "serde")]
+impl<const URL_SAFE: bool, const BOUNCEABLE: bool> IntAddrBase64Repr<URL_SAFE, BOUNCEABLE> {
+    /// Serializes the address as a base64 string if it is a standard one.
+    pub fn serialize<S>(addr: &IntAddr, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::Serialize;
+
+        match addr {
+            IntAddr::Std(addr) => {
+                StdAddrBase64Repr::<URL_SAFE, BOUNCEABLE>::serialize(addr, serializer)
+            }
+            IntAddr::Var(addr) if serializer.is_human_readable() => serializer.collect_str(addr),
+            IntAddr::Var(_) => addr.serialize(serializer),
+        }
+    }
+
+    /// Deserializes the address in any format.
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<IntAddr, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{Error, Visitor};
+
+        struct IntAddrVisitor;
+
+        impl<'de> Visitor<'de> for IntAddrVisitor {
+            type Value = IntAddr;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("an internal address")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                match StdAddr::from_str_ext(v, StdAddrFormat::any()) {
+                    Ok((addr, _)) => Ok(IntAddr::Std(addr)),
+                    Err(_) => IntAddr::from_str(v).map_err(E::custom),
+                }
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(IntAddrVisitor)
+        } else {
+            <IntAddr as serde::Deserialize>::deserialize(deserializer)
+        }
+    }
+}
+
 /// Variable-length internal address.
 #[derive(Debug, Clone, Hash, Eq, PartialEq, Ord, PartialOrd)]
 pub struct VarAddr {
@@ -443,6 +843,17 @@ impl VarAddr {
     pub const BITS_MAX: u16 =
         2 + 1 + Anycast::BITS_MAX + Uint9::BITS + 32 + Uint9::MAX.into_inner();
 
+    /// Constructs a new variable-length address with a 256-bit account id
+    /// and without anycast info.
+    pub fn new(workchain: i32, address: HashBytes) -> Self {
+        Self {
+            anycast: None,
+            address_len: Uint9::new(256),
+            workchain,
+            address: address.0.to_vec(),
+        }
+    }
+
     /// Returns `true` if this address is for a masterchain block.
     ///
     /// See [`ShardIdent::MASTERCHAIN`]
@@ -459,6 +870,46 @@ impl VarAddr {
         }
         bit_len
     }
+
+    /// Returns the first 64 bits of the account id.
+    ///
+    /// NOTE: Short account ids are padded with zeros.
+    pub fn prefix(&self) -> u64 {
+        let mut prefix = [0u8; 8];
+        let len = std::cmp::min(self.address.len(), 8);
+        prefix[..len].copy_from_slice(&self.address[..len]);
+
+        let bits = self.address_len.into_inner();
+        let prefix = u64::from_be_bytes(prefix);
+        if bits < 64 {
+            prefix & !(u64::MAX >> bits)
+        } else {
+            prefix
+        }
+    }
+
+    /// Returns a 256-bit account id (if the address has it).
+    pub fn account_id(&self) -> Option<HashBytes> {
+        if self.address_len.into_inner() == 256 {
+            if let Ok(address) = <[u8; 32]>::try_from(self.address.as_slice()) {
+                return Some(HashBytes(address));
+            }
+        }
+        None
+    }
+
+    /// Converts this address into the standard one if possible.
+    ///
+    /// A conversion is possible if the workchain fits into `i8` and
+    /// the account id is exactly 256 bits long.
+    pub fn to_std(&self) -> Option<StdAddr> {
+        let workchain = i8::try_from(self.workchain).ok()?;
+        Some(StdAddr {
+            anycast: self.anycast.clone(),
+            workchain,
+            address: self.account_id()?,
+        })
+    }
 }
 
 impl From<VarAddr> for IntAddr {
@@ -468,6 +919,73 @@ impl From<VarAddr> for IntAddr {
     }
 }
 
+impl From<StdAddr> for VarAddr {
+    fn from(value: StdAddr) -> Self {
+        Self {
+            anycast: value.anycast,
+            address_len: Uint9::new(256),
+            workchain: value.workchain as i32,
+            address: value.address.0.to_vec(),
+        }
+    }
+}
+
+impl std::fmt::Display for VarAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(anycast) = &self.anycast {
+            ok!(f.write_fmt(format_args!("{anycast}:")))
+        }
+
+        let bitstring = Bitstring {
+            bytes: &self.address,
+            bit_len: self.address_len.into_inner(),
+        };
+        f.write_fmt(format_args!("{}:{bitstring}", self.workchain))
+    }
+}
+
+impl FromStr for VarAddr {
+    type Err = ParseAddrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Err(ParseAddrError::Empty);
+        }
+
+        let (anycast, s) = ok!(split_anycast(s));
+
+        let mut parts = s.split(':');
+        let workchain = match parts.next() {
+            Some(part) => match part.parse() {
+                Ok(workchain) => workchain,
+                Err(_) => return Err(ParseAddrError::InvalidWorkchain),
+            },
+            None => return Err(ParseAddrError::Empty),
+        };
+
+        let (address, address_len) = match parts.next() {
+            Some(part) => match Bitstring::from_hex_str(part) {
+                Ok((address, address_len)) if address_len <= Uint9::MAX.into_inner() => {
+                    (address, Uint9::new(address_len))
+                }
+                _ => return Err(ParseAddrError::InvalidAccountId),
+            },
+            None => return Err(ParseAddrError::InvalidAccountId),
+        };
+
+        if parts.next().is_none() {
+            Ok(Self {
+                anycast,
+                address_len,
+                workchain,
+                address,
+            })
+        } else {
+            Err(ParseAddrError::UnexpectedPart)
+        }
+    }
+}
+
 impl Store for VarAddr {
     fn store_into(
         &self,
@@ -485,6 +1003,31 @@ impl Store for VarAddr {
     }
 }
 
+impl<'a> Load<'a> for VarAddr {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        if !ok!(slice.load_bit()) || !ok!(slice.load_bit()) {
+            return Err(Error::InvalidTag);
+        }
+
+        let anycast = ok!(Option::<Box<Anycast>>::load_from(slice));
+        let address_len = ok!(Uint9::load_from(slice));
+        let workchain = ok!(slice.load_u32()) as i32;
+        if !slice.has_remaining(address_len.into_inner(), 0) {
+            return Err(Error::CellUnderflow);
+        }
+
+        let mut address = vec![0; (address_len.into_inner() as usize + 7) / 8];
+        ok!(slice.load_raw(&mut address, address_len.into_inner()));
+
+        Ok(Self {
+            anycast,
+            address_len,
+            workchain,
+            address,
+        })
+    }
+}
+
 /// External address.
 ///
 /// ```text
@@ -644,6 +1187,35 @@ impl std::fmt::Display for Anycast {
     }
 }
 
+impl FromStr for Anycast {
+    type Err = ParseAddrError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Ok((rewrite_prefix, bit_len)) = Bitstring::from_hex_str(s) else {
+            return Err(ParseAddrError::BadFormat);
+        };
+        match SplitDepth::from_bit_len(bit_len) {
+            Ok(depth) => Ok(Self {
+                depth,
+                rewrite_prefix,
+            }),
+            Err(_) => Err(ParseAddrError::BadFormat),
+        }
+    }
+}
+
+/// Splits the optional anycast prefix of the `[anycast:]workchain:address` string.
+fn split_anycast(s: &str) -> Result<(Option<Box<Anycast>>, &str), ParseAddrError> {
+    if s.bytes().filter(|&c| c == b':').count() != 2 {
+        return Ok((None, s));
+    }
+
+    match s.split_once(':') {
+        Some((anycast, rest)) => Ok((Some(Box::new(ok!(Anycast::from_str(anycast)))), rest)),
+        None => Ok((None, s)),
+    }
+}
+
 impl Store for Anycast {
     fn store_into(
         &self,
@@ -698,6 +1270,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn var_addr_conversions() {
+        let account = HashBytes([0xab; 32]);
+
+        let addr = IntAddr::with_workchain(0, account);
+        assert!(matches!(addr, IntAddr::Std(_)));
+
+        let addr = IntAddr::with_workchain(1000, account);
+        assert_eq!(addr.workchain(), 1000);
+        assert_eq!(addr.account_id(), Some(account));
+        assert_eq!(addr.prefix(), 0xabababababababab);
+
+        let text = addr.to_string();
+        assert_eq!(text, format!("1000:{account}"));
+        assert_eq!(text.parse::<IntAddr>().unwrap(), addr);
+
+        // Cell representation
+        let cell = CellBuilder::build_from(&addr).unwrap();
+        assert_eq!(cell.parse::<IntAddr>().unwrap(), addr);
+        assert_eq!(IntAddr::Var(cell.parse::<VarAddr>().unwrap()), addr);
+
+        // Normalization
+        let var = VarAddr::from(StdAddr::new(-1, account));
+        assert_eq!(var.to_std(), Some(StdAddr::new(-1, account)));
+        assert_eq!(
+            IntAddr::Var(var).normalize(),
+            IntAddr::Std(StdAddr::new(-1, account))
+        );
+        assert!(matches!(addr.normalize(), IntAddr::Var(_)));
+
+        // Short address
+        let short = VarAddr {
+            anycast: None,
+            address_len: Uint9::new(12),
+            workchain: 0,
+            address: vec![0xab, 0xcd],
+        };
+        assert_eq!(short.prefix(), 0xabc0000000000000);
+        assert_eq!(short.account_id(), None);
+        assert_eq!(short.to_std(), None);
+    }
+
+    #[test]
+    fn shard_contains_address() {
+        let shard = ShardIdent::new_full(1000);
+        let (left, right) = shard.split().unwrap();
+
+        let addr = IntAddr::with_workchain(1000, HashBytes([0x11; 32]));
+        assert!(shard.contains_address(&addr));
+        assert!(left.contains_address(&addr));
+        assert!(!right.contains_address(&addr));
+        assert!(!ShardIdent::BASECHAIN.contains_address(&addr));
+    }
+
     #[test]
     fn anycast_str() {
         // 0 bit
@@ -723,4 +1349,191 @@ mod tests {
         let anycast = Anycast::from_slice(&prefix.as_data_slice()).unwrap();
         assert_eq!(anycast.to_string(), "b00b1e52_");
     }
+
+    #[test]
+    fn addr_str_roundtrip() {
+        let mut prefix = CellBuilder::new();
+        prefix.store_uint(0xb00b1e5, 28).unwrap();
+        prefix.store_zeros(2).unwrap();
+        let anycast = Anycast::from_slice(&prefix.as_data_slice()).unwrap();
+        assert_eq!(anycast.to_string().parse::<Anycast>().unwrap(), anycast);
+
+        let account = HashBytes([0xab; 32]);
+
+        // Std with anycast
+        let std = StdAddr {
+            anycast: Some(Box::new(anycast.clone())),
+            workchain: -1,
+            address: account,
+        };
+        let text = std.to_string();
+        assert_eq!(text, format!("b00b1e52_:-1:{account}"));
+        assert_eq!(text.parse::<StdAddr>().unwrap(), std);
+        assert_eq!(text.parse::<IntAddr>().unwrap(), IntAddr::Std(std));
+
+        // Var with and without anycast
+        for anycast in [None, Some(Box::new(anycast))] {
+            for (workchain, address_len, address) in [
+                (1000, 256, vec![0xab; 32]),
+                (-1000, 12, vec![0xab, 0xc0]),
+                (0, 3, vec![0xa0]),
+                (123456, 300, [[0xcd; 37].as_slice(), &[0xc0]].concat()),
+            ] {
+                let var = VarAddr {
+                    anycast: anycast.clone(),
+                    address_len: Uint9::new(address_len),
+                    workchain,
+                    address,
+                };
+                let text = var.to_string();
+                assert_eq!(text.parse::<VarAddr>().unwrap(), var);
+                assert_eq!(text.parse::<IntAddr>().unwrap(), IntAddr::Var(var));
+            }
+        }
+
+        // Invalid anycast prefix
+        assert!(matches!(
+            "x:0:00".parse::<VarAddr>(),
+            Err(ParseAddrError::BadFormat)
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "base64")]
+    fn std_addr_formats() {
+        let addr = StdAddr::new(
+            0,
+            HashBytes::from_hex_const(
+                "83dfd552e63729b472fcbcc8c45ebcc6691702558b68ec7527e1ba403a0f31a8",
+            ),
+        );
+        let base64 = "EQCD39VS5jcptHL8vMjEXrzGaRcCVYto7HUn4bpAOg8xqB2N";
+        let any = StdAddrFormat::any();
+
+        assert_eq!(addr.display_base64(true).to_string(), base64);
+        assert_eq!(
+            StdAddr::from_str_ext(base64, any).unwrap(),
+            (
+                addr.clone(),
+                Base64StdAddrFlags {
+                    testnet: false,
+                    base64_url: false,
+                    bounceable: true,
+                }
+            )
+        );
+
+        for flags in [
+            Base64StdAddrFlags::default(),
+            Base64StdAddrFlags {
+                testnet: true,
+                base64_url: true,
+                bounceable: false,
+            },
+            Base64StdAddrFlags {
+                testnet: false,
+                base64_url: false,
+                bounceable: true,
+            },
+        ] {
+            let s = addr.display_base64_ext(flags).to_string();
+            let (parsed, parsed_flags) = StdAddr::from_str_ext(&s, any).unwrap();
+            assert_eq!(parsed, addr);
+            assert_eq!(parsed_flags.testnet, flags.testnet);
+            assert_eq!(parsed_flags.bounceable, flags.bounceable);
+        }
+
+        // Raw formats
+        let raw = addr.to_string();
+        assert_eq!(StdAddr::from_str_ext(&raw, any).unwrap().0, addr);
+        assert_eq!(StdAddr::from_str_ext(&raw[2..], any).unwrap().0, addr);
+
+        // Disallowed formats
+        let only_raw = StdAddrFormat {
+            allow_raw: true,
+            allow_base64: false,
+            allow_base64_url: false,
+        };
+        assert!(StdAddr::from_str_ext(base64, only_raw).is_err());
+        let only_base64 = StdAddrFormat {
+            allow_raw: false,
+            ..any
+        };
+        assert!(StdAddr::from_str_ext(&raw, only_base64).is_err());
+
+        // Invalid checksum
+        let invalid = format!("{}AA", &base64[..46]);
+        assert!(StdAddr::from_str_ext(&invalid, any).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn std_addr_serde() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Test {
+            raw: StdAddr,
+            #[serde(with = "StdAddrBase64Repr::<true, true>")]
+            base64: StdAddr,
+        }
+
+        let addr = StdAddr::new(-1, HashBytes([0x33; 32]));
+        let json = serde_json::to_string(&Test {
+            raw: addr.clone(),
+            base64: addr.clone(),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            format!(
+                r#"{{"raw":"{addr}","base64":"{}"}}"#,
+                addr.display_base64_url(true)
+            )
+        );
+
+        // Any format is accepted
+        let json = format!(
+            r#"{{"raw":"{}","base64":"{}"}}"#,
+            addr.display_base64(false),
+            addr
+        );
+        let parsed = serde_json::from_str::<Test>(&json).unwrap();
+        assert_eq!(parsed.raw, addr);
+        assert_eq!(parsed.base64, addr);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn int_addr_serde() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct Test {
+            #[serde(with = "IntAddrBase64Repr::<false, true>")]
+            addr: IntAddr,
+        }
+
+        let std = StdAddr::new(0, HashBytes([0x44; 32]));
+        let json = serde_json::to_string(&Test {
+            addr: IntAddr::Std(std.clone()),
+        })
+        .unwrap();
+        assert_eq!(
+            json,
+            format!(r#"{{"addr":"{}"}}"#, std.display_base64(true))
+        );
+
+        // Any format is accepted
+        for text in [
+            std.to_string(),
+            std.display_base64(true).to_string(),
+            std.display_base64_url(false).to_string(),
+        ] {
+            let parsed = serde_json::from_str::<Test>(&format!(r#"{{"addr":"{text}"}}"#)).unwrap();
+            assert_eq!(parsed.addr, IntAddr::Std(std.clone()));
+        }
+
+        // Variable addresses use the raw format
+        let var = IntAddr::with_workchain(1000, HashBytes([0x55; 32]));
+        let json = serde_json::to_string(&Test { addr: var.clone() }).unwrap();
+        assert_eq!(json, format!(r#"{{"addr":"{var}"}}"#));
+        assert_eq!(serde_json::from_str::<Test>(&json).unwrap().addr, var);
+    }
 }
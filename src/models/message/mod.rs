@@ -5,7 +5,8 @@ use crate::error::Error;
 use crate::num::*;
 
 use crate::models::account::StateInit;
-use crate::models::currency::CurrencyCollection;
+use crate::models::config::SizeLimitsConfig;
+use crate::models::currency::{CurrencyCollection, ExtraCurrencyCollection};
 
 pub use self::address::*;
 
@@ -126,6 +127,137 @@ where
     }
 }
 
+impl<B> BaseMessage<MsgInfo, B> {
+    /// Returns the type of this message.
+    #[inline]
+    pub const fn kind(&self) -> MsgKind {
+        self.info.kind()
+    }
+
+    /// Returns an internal source address of this message (if any).
+    #[inline]
+    pub const fn src(&self) -> Option<&IntAddr> {
+        self.info.src()
+    }
+
+    /// Returns an internal destination address of this message (if any).
+    #[inline]
+    pub const fn dst(&self) -> Option<&IntAddr> {
+        self.info.dst()
+    }
+
+    /// Returns attached amounts (only for internal messages).
+    #[inline]
+    pub const fn value(&self) -> Option<&CurrencyCollection> {
+        self.info.value()
+    }
+}
+
+impl<I> BaseMessage<I, CellSlice<'_>> {
+    /// Returns the first 32 bits of the body (if it has enough data).
+    ///
+    /// NOTE: Body slice is not modified.
+    #[inline]
+    pub fn body_opcode(&self) -> Option<u32> {
+        self.body.peek_u32().ok()
+    }
+}
+
+impl<I> BaseMessage<I, CellSliceParts> {
+    /// Returns the first 32 bits of the body (if it has enough data).
+    pub fn body_opcode(&self) -> Option<u32> {
+        let (cell, range) = &self.body;
+        range.apply(cell).ok()?.peek_u32().ok()
+    }
+}
+
+impl OwnedMessage {
+    /// Checks that the serialized message fits into the size limits
+    /// which are used by nodes to accept messages.
+    ///
+    /// - the number of unique cells and data bits (without the root cell)
+    ///   must not exceed `max_msg_cells` and `max_msg_bits`;
+    /// - for external incoming messages, the depth and the size of the
+    ///   serialized BOC must not exceed `max_ext_msg_depth` and `max_ext_msg_size`.
+    pub fn validate_limits(&self, limits: &SizeLimitsConfig) -> Result<(), MessageLimitsError> {
+        use MessageLimitsError as E;
+
+        let cell = ok!(CellBuilder::build_from(self).map_err(E::InvalidMessage));
+
+        // Root cell is not counted
+        let Some(stats) = cell.compute_unique_stats(limits.max_msg_cells as usize + 1) else {
+            return Err(E::TooManyCells {
+                limit: limits.max_msg_cells,
+            });
+        };
+        let bits = stats.bit_count - cell.bit_len() as u64;
+        if bits > limits.max_msg_bits as u64 {
+            return Err(E::TooManyBits {
+                bits,
+                limit: limits.max_msg_bits,
+            });
+        }
+
+        if let MsgInfo::ExtIn(_) = &self.info {
+            let depth = cell.repr_depth();
+            if depth > limits.max_ext_msg_depth {
+                return Err(E::TooDeep {
+                    depth,
+                    limit: limits.max_ext_msg_depth,
+                });
+            }
+
+            let size = crate::boc::Boc::encode(cell.as_ref()).len();
+            if size > limits.max_ext_msg_size as usize {
+                return Err(E::TooBig {
+                    size,
+                    limit: limits.max_ext_msg_size,
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Error type for message size limits validation.
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum MessageLimitsError {
+    /// Failed to serialize the message.
+    #[error("invalid message")]
+    InvalidMessage(#[source] Error),
+    /// Message contains too many unique cells.
+    #[error("message has more than {limit} cells")]
+    TooManyCells {
+        /// Limit from config.
+        limit: u32,
+    },
+    /// Message contains too many data bits.
+    #[error("message has {bits} bits which exceeds the limit {limit}")]
+    TooManyBits {
+        /// Number of unique data bits (without the root cell).
+        bits: u64,
+        /// Limit from config.
+        limit: u32,
+    },
+    /// External message is too deep.
+    #[error("message depth {depth} exceeds the limit {limit}")]
+    TooDeep {
+        /// Depth of the message cell tree.
+        depth: u16,
+        /// Limit from config.
+        limit: u16,
+    },
+    /// Serialized external message is too big.
+    #[error("message size {size} exceeds the limit {limit}")]
+    TooBig {
+        /// Size of the serialized message in bytes.
+        size: usize,
+        /// Limit from config.
+        limit: u32,
+    },
+}
+
 impl<I: ExactSize, B: ExactSize> BaseMessage<I, B> {
     /// Computes the most optimal layout of the message parts.
     pub fn compute_layout(info: &I, init: Option<&StateInit>, body: &B) -> MessageLayout {
@@ -396,6 +528,10 @@ impl MessageLayout {
 
     /// Computes the most optimal layout of the message parts.
     /// Also returns the number of bits and refs for the root cell.
+    ///
+    /// NOTE: Code, data and libraries of the state init are always stored
+    /// in child cells, so the fallback layout (init and body to cells)
+    /// always fits into the root cell.
     pub const fn compute(
         info_size: CellSliceSize,
         init: Option<&StateInit>,
@@ -612,6 +748,100 @@ impl MsgInfo {
             _ => false,
         }
     }
+
+    /// Returns the type of this message.
+    pub const fn kind(&self) -> MsgKind {
+        match self {
+            Self::Int(_) => MsgKind::Int,
+            Self::ExtIn(_) => MsgKind::ExtIn,
+            Self::ExtOut(_) => MsgKind::ExtOut,
+        }
+    }
+
+    /// Returns an internal source address (if any).
+    ///
+    /// NOTE: External incoming messages have no internal source.
+    pub const fn src(&self) -> Option<&IntAddr> {
+        match self {
+            Self::Int(info) => Some(&info.src),
+            Self::ExtIn(_) => None,
+            Self::ExtOut(info) => Some(&info.src),
+        }
+    }
+
+    /// Returns an internal destination address (if any).
+    ///
+    /// NOTE: External outgoing messages have no internal destination.
+    pub const fn dst(&self) -> Option<&IntAddr> {
+        match self {
+            Self::Int(info) => Some(&info.dst),
+            Self::ExtIn(info) => Some(&info.dst),
+            Self::ExtOut(_) => None,
+        }
+    }
+
+    /// Returns attached amounts (only for internal messages).
+    pub const fn value(&self) -> Option<&CurrencyCollection> {
+        match self {
+            Self::Int(info) => Some(&info.value),
+            _ => None,
+        }
+    }
+}
+
+/// Formats a short message summary with its type and addresses.
+///
+/// Alternate mode (`{:#}`) also prints attached values, fees, flags
+/// and creation time.
+impl std::fmt::Display for MsgInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let verbose = f.alternate();
+        match self {
+            Self::Int(info) => {
+                ok!(f.write_fmt(format_args!(
+                    "int {} -> {}, value {}",
+                    info.src, info.dst, info.value.tokens
+                )));
+                if verbose {
+                    if !info.value.other.is_empty() {
+                        ok!(f.write_str(" + extra"));
+                    }
+                    ok!(f.write_fmt(format_args!(
+                        ", fwd_fee {}, ihr_fee {}, bounce {}, bounced {}, lt {}, at {}",
+                        info.fwd_fee,
+                        info.ihr_fee,
+                        info.bounce,
+                        info.bounced,
+                        info.created_lt,
+                        info.created_at
+                    )));
+                }
+            }
+            Self::ExtIn(info) => {
+                ok!(f.write_fmt(format_args!("ext_in -> {}", info.dst)));
+                if verbose {
+                    if let Some(src) = &info.src {
+                        ok!(f.write_fmt(format_args!(", src {src}")));
+                    }
+                    ok!(f.write_fmt(format_args!(", import_fee {}", info.import_fee)));
+                }
+            }
+            Self::ExtOut(info) => {
+                ok!(f.write_fmt(format_args!("ext_out {} -> ", info.src)));
+                ok!(match &info.dst {
+                    Some(dst) => std::fmt::Display::fmt(dst, f),
+                    None => f.write_str("none"),
+                });
+                if verbose {
+                    ok!(f.write_fmt(format_args!(
+                        ", lt {}, at {}",
+                        info.created_lt, info.created_at
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
 }
 
 impl ExactSize for MsgInfo {
@@ -665,6 +895,133 @@ impl<'a> Load<'a> for MsgInfo {
     }
 }
 
+/// Message type.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MsgKind {
+    /// Internal message.
+    Int,
+    /// External incoming message.
+    ExtIn,
+    /// External outgoing message.
+    ExtOut,
+}
+
+impl MsgKind {
+    /// Returns whether this message is internal.
+    #[inline]
+    pub const fn is_internal(&self) -> bool {
+        matches!(self, Self::Int)
+    }
+
+    /// Returns whether this message is external incoming.
+    #[inline]
+    pub const fn is_external_in(&self) -> bool {
+        matches!(self, Self::ExtIn)
+    }
+
+    /// Returns whether this message is external outgoing.
+    #[inline]
+    pub const fn is_external_out(&self) -> bool {
+        matches!(self, Self::ExtOut)
+    }
+}
+
+impl<'a> Load<'a> for MsgKind {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        Ok(if !ok!(slice.load_bit()) {
+            Self::Int
+        } else if !ok!(slice.load_bit()) {
+            Self::ExtIn
+        } else {
+            Self::ExtOut
+        })
+    }
+}
+
+/// Short message info which is used for fast filtering.
+///
+/// Reads only the message info prefix and skips all fields which
+/// are not needed, so neither state init nor body are parsed.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MessageSummary {
+    /// Message type.
+    pub kind: MsgKind,
+    /// Internal source address (if any).
+    pub src: Option<IntAddr>,
+    /// Internal destination address (if any).
+    pub dst: Option<IntAddr>,
+    /// Attached amount in native currency (zero for external messages).
+    pub value: Tokens,
+    /// Whether to bounce this message back if the destination transaction fails.
+    pub bounce: bool,
+    /// Whether this message is a bounced message from some failed transaction.
+    pub bounced: bool,
+    /// Logical time when the message was created (zero for external incoming messages).
+    pub created_lt: u64,
+}
+
+impl MessageSummary {
+    /// Extracts the summary from the root cell of the message.
+    pub fn from_cell(cell: &DynCell) -> Result<Self, Error> {
+        cell.parse::<Self>()
+    }
+}
+
+impl<'a> Load<'a> for MessageSummary {
+    fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
+        let kind = ok!(MsgKind::load_from(slice));
+        Ok(match kind {
+            MsgKind::Int => {
+                let flags = ok!(slice.load_small_uint(3));
+                let src = ok!(IntAddr::load_from(slice));
+                let dst = ok!(IntAddr::load_from(slice));
+                let value = ok!(Tokens::load_from(slice));
+
+                // Skip extra currencies, ihr fee and fwd fee
+                ok!(slice.skip_value::<ExtraCurrencyCollection>());
+                ok!(slice.skip_value::<Tokens>());
+                ok!(slice.skip_value::<Tokens>());
+
+                Self {
+                    kind,
+                    src: Some(src),
+                    dst: Some(dst),
+                    value,
+                    bounce: flags & 0b010 != 0,
+                    bounced: flags & 0b001 != 0,
+                    created_lt: ok!(slice.load_u64()),
+                }
+            }
+            MsgKind::ExtIn => {
+                ok!(skip_ext_addr(slice));
+                Self {
+                    kind,
+                    src: None,
+                    dst: Some(ok!(IntAddr::load_from(slice))),
+                    value: Tokens::ZERO,
+                    bounce: false,
+                    bounced: false,
+                    created_lt: 0,
+                }
+            }
+            MsgKind::ExtOut => {
+                let src = ok!(IntAddr::load_from(slice));
+                ok!(skip_ext_addr(slice));
+                Self {
+                    kind,
+                    src: Some(src),
+                    dst: None,
+                    value: Tokens::ZERO,
+                    bounce: false,
+                    bounced: false,
+                    created_lt: ok!(slice.load_u64()),
+                }
+            }
+        })
+    }
+}
+
 /// Internal message info.
 #[derive(Debug, Clone, Eq, PartialEq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -1056,6 +1413,18 @@ fn load_ext_addr(slice: &mut CellSlice<'_>) -> Result<Option<ExtAddr>, Error> {
     Ok(Some(ExtAddr { data_bit_len, data }))
 }
 
+fn skip_ext_addr(slice: &mut CellSlice<'_>) -> Result<(), Error> {
+    if ok!(slice.load_bit()) {
+        return Err(Error::InvalidTag);
+    }
+
+    if ok!(slice.load_bit()) {
+        let data_bit_len = ok!(Uint9::load_from(slice));
+        ok!(slice.advance(data_bit_len.into_inner(), 0));
+    }
+    Ok(())
+}
+
 const fn compute_opt_int_addr_bit_len(addr: &Option<IntAddr>) -> u16 {
     match addr {
         Some(src) => src.bit_len(),
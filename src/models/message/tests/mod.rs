@@ -29,6 +29,9 @@ fn check_message(boc: &[u8]) -> Cell {
             .unwrap();
 
         assert_eq!(CellBuilder::build_from(owned).unwrap(), boc);
+
+        let owned = crate::cell::cast_owned::<_, OwnedMessage>(&message).unwrap();
+        assert_eq!(CellBuilder::build_from(owned).unwrap(), boc);
     };
 
     boc
@@ -204,3 +207,353 @@ fn internal_message_with_deploy_special() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn message_layout_with_large_deploy() -> anyhow::Result<()> {
+    use crate::models::account::*;
+
+    // NOTE: Code, data and libraries of the state init are always stored
+    // as references, so only the whole state init is moved to a child cell.
+    let code = Boc::decode_base64("te6ccgEBAQEABQAABv8AAA==")?;
+    let mut libraries = Dict::new();
+    libraries.set(
+        code.repr_hash(),
+        SimpleLib {
+            public: true,
+            root: code.clone(),
+        },
+    )?;
+    let init = StateInit {
+        split_depth: Some(SplitDepth::new(5)?),
+        special: Some(SpecialFlags {
+            tick: true,
+            tock: false,
+        }),
+        code: Some(code.clone()),
+        data: Some(code.clone()),
+        libraries,
+    };
+
+    let mut other = Dict::<u32, VarUint248>::new();
+    other.set(1, VarUint248::new(100))?;
+
+    let mut body = CellBuilder::new();
+    body.store_raw(&[0xaa; 128], 1000)?;
+    for _ in 0..4 {
+        body.store_reference(code.clone())?;
+    }
+    let body = body.build()?;
+
+    let info = MsgInfo::Int(IntMsgInfo {
+        src: "0:b62450b8355ae57d4e1530dda442e17dda60f39cee7cc0a34795566e30630dbf".parse()?,
+        dst: "0:a0b65eadaf741a132467f027eedc971a3d4f0d7ad34cc18edafac9d3c198fd9b".parse()?,
+        value: CurrencyCollection {
+            tokens: Tokens::new(1000000000),
+            other: other.into(),
+        },
+        ..Default::default()
+    });
+
+    let (layout, size) = MessageLayout::compute(
+        info.exact_size(),
+        Some(&init),
+        body.as_slice()?.exact_size(),
+    );
+    assert_eq!(
+        layout,
+        MessageLayout {
+            init_to_cell: true,
+            body_to_cell: true,
+        }
+    );
+    assert_eq!(size.refs, 3);
+
+    let message = serialize_message(Message {
+        info,
+        init: Some(init),
+        body: body.as_slice()?,
+        layout: None,
+    });
+    assert_eq!(message.reference_count(), 3);
+    check_message(Boc::encode(message).as_ref());
+
+    Ok(())
+}
+
+#[test]
+fn message_summary() {
+    for boc in [
+        &include_bytes!("external_message.boc")[..],
+        include_bytes!("external_out_message.boc"),
+        include_bytes!("empty_internal_message.boc"),
+        include_bytes!("internal_message_with_body.boc"),
+        include_bytes!("internal_message_with_deploy.boc"),
+    ] {
+        let cell = Boc::decode(boc).unwrap();
+        let message = cell.parse::<Message>().unwrap();
+        let summary = MessageSummary::from_cell(cell.as_ref()).unwrap();
+
+        assert_eq!(summary.kind, message.kind());
+        assert_eq!(summary.src.as_ref(), message.src());
+        assert_eq!(summary.dst.as_ref(), message.dst());
+        assert_eq!(
+            summary.value,
+            message.value().map(|v| v.tokens).unwrap_or_default()
+        );
+
+        match &message.info {
+            MsgInfo::Int(info) => {
+                assert_eq!(summary.bounce, info.bounce);
+                assert_eq!(summary.created_lt, info.created_lt);
+            }
+            MsgInfo::ExtIn(_) => assert_eq!(summary.created_lt, 0),
+            MsgInfo::ExtOut(info) => assert_eq!(summary.created_lt, info.created_lt),
+        }
+    }
+}
+
+#[test]
+fn strict_msg_info() {
+    for boc in [
+        &include_bytes!("external_message.boc")[..],
+        include_bytes!("external_out_message.boc"),
+        include_bytes!("empty_internal_message.boc"),
+        include_bytes!("internal_message_with_deploy.boc"),
+    ] {
+        let cell = Boc::decode(boc).unwrap();
+        assert_eq!(
+            cell.parse_strict::<MsgInfo>().unwrap(),
+            cell.parse::<MsgInfo>().unwrap()
+        );
+    }
+
+    let make_info = |value_bytes: u8| {
+        let cx = &mut Cell::empty_context();
+        let mut b = CellBuilder::new();
+        b.store_small_uint(0b0100, 4).unwrap();
+        IntAddr::from((0, HashBytes([0x11; 32])))
+            .store_into(&mut b, cx)
+            .unwrap();
+        IntAddr::from((-1, HashBytes([0x22; 32])))
+            .store_into(&mut b, cx)
+            .unwrap();
+        // value:CurrencyCollection
+        b.store_small_uint(value_bytes, 4).unwrap();
+        b.store_uint(123, value_bytes as u16 * 8).unwrap();
+        b.store_bit_zero().unwrap();
+        // ihr_fee:Grams fwd_fee:Grams
+        Tokens::ZERO.store_into(&mut b, cx).unwrap();
+        Tokens::new(10).store_into(&mut b, cx).unwrap();
+        b.store_u64(1000).unwrap();
+        b.store_u32(2000).unwrap();
+        b.build().unwrap()
+    };
+
+    let canonical = make_info(1);
+    let info = canonical.parse_strict::<MsgInfo>().unwrap();
+    assert_eq!(info, canonical.parse::<MsgInfo>().unwrap());
+
+    // Value with a leading zero byte
+    let non_canonical = make_info(2);
+    let MsgInfo::Int(lenient) = non_canonical.parse::<MsgInfo>().unwrap() else {
+        panic!("expected an internal message info");
+    };
+    assert_eq!(lenient.value.tokens, Tokens::new(123));
+    assert_eq!(
+        non_canonical.parse_strict::<MsgInfo>().unwrap_err(),
+        Error::InvalidData
+    );
+
+    // The slice is not modified on error
+    let mut slice = non_canonical.as_slice().unwrap();
+    assert!(slice.load_strict::<MsgInfo>().is_err());
+    assert_eq!(slice.bits_offset(), 0);
+}
+
+#[test]
+fn message_body_opcode() {
+    let mut body = CellBuilder::new();
+    body.store_u32(0xdeadbeef).unwrap();
+    body.store_u64(123).unwrap();
+    let body = body.build().unwrap();
+
+    let info = MsgInfo::ExtIn(ExtInMsgInfo::default());
+    let cell = serialize_message(Message {
+        info: info.clone(),
+        init: None,
+        body: body.as_slice().unwrap(),
+        layout: None,
+    });
+
+    let message = cell.parse::<Message>().unwrap();
+    assert_eq!(message.body_opcode(), Some(0xdeadbeef));
+    assert_eq!(message.body.remaining_bits(), 96);
+
+    let owned = cell.parse::<OwnedMessage>().unwrap();
+    assert_eq!(owned.body_opcode(), Some(0xdeadbeef));
+
+    let cell = serialize_message(Message {
+        info,
+        init: None,
+        body: Default::default(),
+        layout: None,
+    });
+    let message = cell.parse::<Message>().unwrap();
+    assert_eq!(message.body_opcode(), None);
+}
+
+#[test]
+fn lazy_messages_as_keys() {
+    use std::collections::{BTreeMap, HashSet};
+
+    let messages = [
+        &include_bytes!("external_message.boc")[..],
+        include_bytes!("external_out_message.boc"),
+        include_bytes!("empty_internal_message.boc"),
+        include_bytes!("internal_message_with_body.boc"),
+    ]
+    .into_iter()
+    .map(|boc| Lazy::<OwnedMessage>::from_raw(Boc::decode(boc).unwrap()))
+    .collect::<Vec<_>>();
+
+    let set = messages.iter().cloned().collect::<HashSet<_>>();
+    assert_eq!(set.len(), messages.len());
+
+    let map = messages
+        .iter()
+        .cloned()
+        .enumerate()
+        .map(|(i, msg)| (msg, i))
+        .collect::<BTreeMap<_, _>>();
+    assert_eq!(map.len(), messages.len());
+
+    let first = map.keys().next().unwrap();
+    assert!(messages
+        .iter()
+        .all(|msg| first.inner().repr_hash() <= msg.inner().repr_hash()));
+
+    for (i, msg) in messages.iter().enumerate() {
+        let copy = Lazy::<OwnedMessage>::from_raw(msg.inner().clone());
+        assert!(set.contains(&copy));
+        assert_eq!(map.get(&copy), Some(&i));
+    }
+}
+
+#[test]
+fn message_size_limits() {
+    let cell = Boc::decode(include_bytes!("external_message.boc")).unwrap();
+    let message = cell.parse::<OwnedMessage>().unwrap();
+    let stats = cell.compute_unique_stats(usize::MAX).unwrap();
+    let size = Boc::encode(cell.as_ref()).len();
+
+    let limits = SizeLimitsConfig {
+        max_msg_bits: 1 << 21,
+        max_msg_cells: 1 << 13,
+        max_library_cells: 1000,
+        max_vm_data_depth: 512,
+        max_ext_msg_size: 65535,
+        max_ext_msg_depth: 512,
+        acc_state_limits: None,
+    };
+    message.validate_limits(&limits).unwrap();
+
+    // Both layouts are parsed
+    for acc_state_limits in [
+        None,
+        Some(crate::models::AccountStateLimits {
+            max_cells: 1 << 16,
+            max_bits: 1 << 24,
+        }),
+    ] {
+        let limits = SizeLimitsConfig {
+            acc_state_limits,
+            ..limits
+        };
+        let cell = CellBuilder::build_from(limits).unwrap();
+        assert_eq!(cell.parse::<SizeLimitsConfig>().unwrap(), limits);
+    }
+
+    // Exact limits are allowed
+    let exact = SizeLimitsConfig {
+        max_msg_bits: (stats.bit_count - cell.bit_len() as u64) as u32,
+        max_msg_cells: stats.cell_count as u32 - 1,
+        max_ext_msg_size: size as u32,
+        max_ext_msg_depth: cell.repr_depth(),
+        ..limits
+    };
+    message.validate_limits(&exact).unwrap();
+
+    let check = |f: &dyn Fn(&mut SizeLimitsConfig)| {
+        let mut limits = exact;
+        f(&mut limits);
+        message.validate_limits(&limits).unwrap_err()
+    };
+
+    assert!(matches!(
+        check(&|l| l.max_msg_cells -= 1),
+        MessageLimitsError::TooManyCells { .. }
+    ));
+    assert!(matches!(
+        check(&|l| l.max_msg_bits -= 1),
+        MessageLimitsError::TooManyBits { .. }
+    ));
+    assert!(matches!(
+        check(&|l| l.max_ext_msg_depth -= 1),
+        MessageLimitsError::TooDeep { .. }
+    ));
+    assert!(matches!(
+        check(&|l| l.max_ext_msg_size -= 1),
+        MessageLimitsError::TooBig { .. }
+    ));
+}
+
+#[test]
+fn message_info_display() {
+    let addr = StdAddr::new(0, HashBytes([0x11; 32]));
+    assert_eq!(
+        format!("{addr:#}"),
+        format!("{addr} (prefix 1111111111111111)")
+    );
+
+    let info = MsgInfo::Int(IntMsgInfo {
+        src: addr.clone().into(),
+        dst: StdAddr::new(-1, HashBytes([0x22; 32])).into(),
+        value: CurrencyCollection::new(1_500_000_000),
+        fwd_fee: Tokens::new(1000),
+        created_lt: 123,
+        created_at: 456,
+        ..Default::default()
+    });
+    assert_eq!(
+        info.to_string(),
+        format!(
+            "int 0:{} -> -1:{}, value 1500000000",
+            "11".repeat(32),
+            "22".repeat(32)
+        )
+    );
+    assert!(format!("{info:#}")
+        .ends_with(", fwd_fee 1000, ihr_fee 0, bounce false, bounced false, lt 123, at 456"));
+
+    let info = MsgInfo::ExtIn(ExtInMsgInfo {
+        dst: addr.clone().into(),
+        ..Default::default()
+    });
+    assert_eq!(info.to_string(), format!("ext_in -> {addr}"));
+    assert_eq!(
+        format!("{info:#}"),
+        format!("ext_in -> {addr}, import_fee 0")
+    );
+
+    let info = MsgInfo::ExtOut(ExtOutMsgInfo {
+        src: addr.clone().into(),
+        dst: None,
+        created_lt: 1,
+        created_at: 2,
+    });
+    assert_eq!(info.to_string(), format!("ext_out {addr} -> none"));
+    assert_eq!(
+        format!("{info:#}"),
+        format!("ext_out {addr} -> none, lt 1, at 2")
+    );
+}
@@ -13,20 +13,28 @@ pub use account::*;
 pub use block::*;
 pub use config::*;
 pub use currency::*;
+pub use dns::*;
+pub use filter::*;
 pub use global_version::*;
 pub use message::*;
 pub use shard::*;
+pub use time::*;
 pub use transaction::*;
+pub use validate::*;
 pub use vm::*;
 
 pub mod account;
 pub mod block;
 pub mod config;
 pub mod currency;
+pub mod dns;
+pub mod filter;
 pub mod global_version;
 pub mod message;
 pub mod shard;
+pub mod time;
 pub mod transaction;
+pub mod validate;
 pub mod vm;
 
 #[cfg(feature = "sync")]
@@ -69,6 +77,27 @@ impl<T> PartialEq for Lazy<T> {
     }
 }
 
+impl<T> Ord for Lazy<T> {
+    #[inline]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.cell.as_ref().cmp(other.cell.as_ref())
+    }
+}
+
+impl<T> PartialOrd for Lazy<T> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T> std::hash::Hash for Lazy<T> {
+    #[inline]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::hash::Hash::hash(self.cell.as_ref(), state)
+    }
+}
+
 impl<T> Clone for Lazy<T> {
     #[inline]
     fn clone(&self) -> Self {
@@ -192,3 +221,21 @@ where
         }
     }
 }
+
+/// Serialization layout of a model which has several historical variants.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FormatVersion {
+    /// The original layout.
+    V1,
+    /// The extended layout.
+    V2,
+}
+
+impl FormatVersion {
+    /// Returns `true` if this is the original layout.
+    #[inline]
+    pub const fn is_legacy(&self) -> bool {
+        matches!(self, Self::V1)
+    }
+}
@@ -3,19 +3,25 @@
 use crate::cell::*;
 use crate::dict::Dict;
 use crate::error::*;
+use crate::merkle::MerkleProof;
 
 use crate::models::block::{BlockRef, ShardIdent};
 use crate::models::currency::CurrencyCollection;
+use crate::models::message::StdAddr;
 use crate::models::Lazy;
 
+pub use self::out_msg_queue::*;
 pub use self::shard_accounts::*;
 pub use self::shard_extra::*;
+pub use self::zerostate::*;
 
-#[cfg(feature = "venom")]
 use super::ShardBlockRefs;
 
+mod out_msg_queue;
 mod shard_accounts;
 mod shard_extra;
+mod split_merge;
+mod zerostate;
 
 #[cfg(test)]
 mod tests;
@@ -73,8 +79,9 @@ pub struct ShardStateUnsplit {
     /// Unix timestamp when the block was created.
     pub gen_utime: u32,
     /// Milliseconds part of the timestamp when the block was created.
-    #[cfg(feature = "venom")]
-    pub gen_utime_ms: u16,
+    ///
+    /// NOTE: Only used in networks with fast finality.
+    pub gen_utime_ms: Option<u16>,
     /// Logical time when the state was created.
     pub gen_lt: u64,
     /// Minimal referenced seqno of the masterchain block.
@@ -100,13 +107,13 @@ pub struct ShardStateUnsplit {
     /// Shard state additional info.
     pub custom: Option<Lazy<McStateExtra>>,
     /// References to the latest known blocks from all shards.
-    #[cfg(feature = "venom")]
+    ///
+    /// NOTE: Only used in networks with fast finality (for non-masterchain states).
     pub shard_block_refs: Option<ShardBlockRefs>,
 }
 
 impl ShardStateUnsplit {
     const TAG_V1: u32 = 0x9023afe2;
-    #[cfg(feature = "venom")]
     const TAG_V2: u32 = 0x9023aeee;
 
     /// Tries to load shard accounts dictionary.
@@ -114,6 +121,17 @@ impl ShardStateUnsplit {
         self.accounts.load()
     }
 
+    /// Tries to load outbound messages queue info.
+    pub fn load_out_msg_queue_info(&self) -> Result<OutMsgQueueInfo, Error> {
+        self.out_msg_queue_info.parse::<OutMsgQueueInfo>()
+    }
+
+    /// Tries to store outbound messages queue info.
+    pub fn set_out_msg_queue_info(&mut self, info: &OutMsgQueueInfo) -> Result<(), Error> {
+        self.out_msg_queue_info = ok!(CellBuilder::build_from(info));
+        Ok(())
+    }
+
     /// Tries to load additional masterchain data.
     pub fn load_custom(&self) -> Result<Option<McStateExtra>, Error> {
         match &self.custom {
@@ -124,6 +142,149 @@ impl ShardStateUnsplit {
             None => Ok(None),
         }
     }
+
+    /// Builds a pruned state proof which contains only the specified accounts.
+    ///
+    /// The proof also includes all state libraries referenced by the accounts
+    /// and the blockchain config (for masterchain states). Accounts which are
+    /// not present in the state are skipped (the proof still contains
+    /// the dictionary path to prove their absence).
+    pub fn serialize_subset(&self, accounts: &[StdAddr]) -> Result<MerkleProof, Error> {
+        let root = ok!(CellBuilder::build_from(self));
+
+        let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+        let mut subtrees = Vec::new();
+
+        {
+            let state = ok!(usage_tree.track(&root).parse::<ShardStateUnsplit>());
+            let shard_accounts = ok!(state.load_accounts());
+
+            let mut lib_hashes = Vec::new();
+            for address in accounts {
+                if address.workchain as i32 != self.shard_ident.workchain() {
+                    continue;
+                }
+
+                if let Some(account) = ok!(shard_accounts.get(address.address)) {
+                    let cell = account.account.into_inner();
+                    collect_library_refs(cell.as_ref(), &mut lib_hashes);
+                    subtrees.push(cell);
+                }
+            }
+
+            for hash in lib_hashes {
+                if let Some(lib) = ok!(state.libraries.get(hash)) {
+                    subtrees.push(lib.lib);
+                }
+            }
+
+            if let Some(custom) = &state.custom {
+                let custom = ok!(custom.load());
+                if let Some(params) = custom.config.params.as_dict().root() {
+                    subtrees.push(params.clone());
+                }
+            }
+        }
+
+        let mut usage_tree = usage_tree.with_subtrees();
+        for cell in &subtrees {
+            usage_tree.add_subtree(cell.as_ref());
+        }
+
+        MerkleProof::create(root.as_ref(), usage_tree).build()
+    }
+
+    /// Adds a publisher of the public library.
+    ///
+    /// Creates a new library entry if it didn't exist.
+    /// Returns `false` if the account was already a publisher.
+    ///
+    /// Fails for non-masterchain states.
+    pub fn add_library_publisher(
+        &mut self,
+        lib: Cell,
+        publisher: &HashBytes,
+    ) -> Result<bool, Error> {
+        if !self.shard_ident.is_masterchain() {
+            return Err(Error::InvalidData);
+        }
+
+        let hash = *lib.repr_hash();
+        let descr = match ok!(self.libraries.get(hash)) {
+            Some(mut descr) => {
+                if !ok!(descr.add_publisher(publisher)) {
+                    return Ok(false);
+                }
+                descr
+            }
+            None => ok!(LibDescr::new(lib, publisher)),
+        };
+        self.libraries.set(hash, descr)
+    }
+
+    /// Removes a publisher of the public library.
+    ///
+    /// The library entry is removed when it has no publishers left.
+    /// Returns `false` if the account was not a publisher.
+    pub fn remove_library_publisher(
+        &mut self,
+        lib_hash: &HashBytes,
+        publisher: &HashBytes,
+    ) -> Result<bool, Error> {
+        let mut descr = match ok!(self.libraries.get(lib_hash)) {
+            Some(descr) => descr,
+            None => return Ok(false),
+        };
+        if !ok!(descr.remove_publisher(publisher)) {
+            return Ok(false);
+        }
+
+        if descr.has_publishers() {
+            ok!(self.libraries.set(lib_hash, descr));
+        } else {
+            ok!(self.libraries.remove(lib_hash));
+        }
+        Ok(true)
+    }
+
+    /// Checks that public libraries are present only in masterchain states
+    /// and that all entries are stored under the hash of the library code.
+    pub fn validate_libraries(&self) -> Result<(), Error> {
+        if self.libraries.is_empty() {
+            return Ok(());
+        }
+        if !self.shard_ident.is_masterchain() {
+            return Err(Error::InvalidData);
+        }
+
+        for entry in self.libraries.iter() {
+            let (hash, descr) = ok!(entry);
+            if descr.lib.repr_hash() != &hash || !descr.has_publishers() {
+                return Err(Error::InvalidData);
+            }
+        }
+        Ok(())
+    }
+}
+
+fn collect_library_refs(root: &DynCell, lib_hashes: &mut Vec<HashBytes>) {
+    let mut visited = ahash::HashSet::default();
+    let mut stack = vec![root];
+    while let Some(cell) = stack.pop() {
+        if !visited.insert(cell.repr_hash()) {
+            continue;
+        }
+
+        if cell.cell_type() == CellType::LibraryReference {
+            let data = cell.data();
+            if data.len() == 33 {
+                lib_hashes.push(HashBytes(data[1..].try_into().unwrap()));
+            }
+            continue;
+        }
+
+        stack.extend(cell.references());
+    }
 }
 
 impl Store for ShardStateUnsplit {
@@ -143,16 +304,22 @@ impl Store for ShardStateUnsplit {
             ok!(builder.build_ext(context))
         };
 
-        #[cfg(not(feature = "venom"))]
-        ok!(builder.store_u32(Self::TAG_V1));
-        #[cfg(feature = "venom")]
-        ok!(builder.store_u32(Self::TAG_V2));
+        let fast_finality = self.gen_utime_ms.is_some();
+
+        ok!(builder.store_u32(if fast_finality {
+            Self::TAG_V2
+        } else {
+            Self::TAG_V1
+        }));
 
         ok!(builder.store_u32(self.global_id as u32));
         ok!(self.shard_ident.store_into(builder, context));
         ok!(builder.store_u32(self.seqno));
         ok!(builder.store_u32(self.vert_seqno));
         ok!(builder.store_u32(self.gen_utime));
+        if let Some(gen_utime_ms) = self.gen_utime_ms {
+            ok!(builder.store_u16(gen_utime_ms));
+        }
         ok!(builder.store_u64(self.gen_lt));
         ok!(builder.store_u32(self.min_ref_mc_seqno));
         ok!(builder.store_reference(self.out_msg_queue_info.clone()));
@@ -160,19 +327,17 @@ impl Store for ShardStateUnsplit {
         ok!(builder.store_reference(self.accounts.cell.clone()));
         ok!(builder.store_reference(child_cell));
 
-        #[cfg(not(feature = "venom"))]
-        ok!(self.custom.store_into(builder, context));
-
-        #[cfg(feature = "venom")]
-        if self.custom.is_some() && self.shard_block_refs.is_some() {
-            return Err(Error::InvalidData);
-        } else if let Some(refs) = &self.shard_block_refs {
-            ok!(refs.store_into(builder, context));
+        if fast_finality && !self.shard_ident.is_masterchain() {
+            // Shard states in fast finality mode have block refs instead of extra
+            match (&self.custom, &self.shard_block_refs) {
+                (None, Some(refs)) => refs.store_into(builder, context),
+                _ => Err(Error::InvalidData),
+            }
+        } else if self.shard_block_refs.is_some() {
+            Err(Error::InvalidData)
         } else {
-            ok!(self.custom.store_into(builder, context));
+            self.custom.store_into(builder, context)
         }
-
-        Ok(())
     }
 }
 
@@ -180,15 +345,11 @@ impl<'a> Load<'a> for ShardStateUnsplit {
     fn load_from(slice: &mut CellSlice<'a>) -> Result<Self, Error> {
         let fast_finality = match slice.load_u32() {
             Ok(Self::TAG_V1) => false,
-            #[cfg(feature = "venom")]
             Ok(Self::TAG_V2) => true,
             Ok(_) => return Err(Error::InvalidTag),
             Err(e) => return Err(e),
         };
 
-        #[cfg(not(feature = "venom"))]
-        let _ = fast_finality;
-
         let out_msg_queue_info = ok!(slice.load_reference_cloned());
         let accounts = ok!(Lazy::load_from(slice));
 
@@ -196,6 +357,7 @@ impl<'a> Load<'a> for ShardStateUnsplit {
 
         let global_id = ok!(slice.load_u32()) as i32;
         let shard_ident = ok!(ShardIdent::load_from(slice));
+        let with_block_refs = fast_finality && !shard_ident.is_masterchain();
 
         Ok(Self {
             global_id,
@@ -203,11 +365,10 @@ impl<'a> Load<'a> for ShardStateUnsplit {
             seqno: ok!(slice.load_u32()),
             vert_seqno: ok!(slice.load_u32()),
             gen_utime: ok!(slice.load_u32()),
-            #[cfg(feature = "venom")]
             gen_utime_ms: if fast_finality {
-                ok!(slice.load_u16())
+                Some(ok!(slice.load_u16()))
             } else {
-                0
+                None
             },
             gen_lt: ok!(slice.load_u64()),
             min_ref_mc_seqno: ok!(slice.load_u32()),
@@ -220,19 +381,15 @@ impl<'a> Load<'a> for ShardStateUnsplit {
             total_validator_fees: ok!(CurrencyCollection::load_from(child_slice)),
             libraries: ok!(Dict::load_from(child_slice)),
             master_ref: ok!(Option::<BlockRef>::load_from(child_slice)),
-            #[allow(unused_labels)]
-            custom: 'custom: {
-                #[cfg(feature = "venom")]
-                if !shard_ident.is_masterchain() {
-                    break 'custom None;
-                }
-                ok!(Option::<Lazy<McStateExtra>>::load_from(slice))
-            },
-            #[cfg(feature = "venom")]
-            shard_block_refs: if shard_ident.is_masterchain() {
+            custom: if with_block_refs {
                 None
             } else {
+                ok!(Option::<Lazy<McStateExtra>>::load_from(slice))
+            },
+            shard_block_refs: if with_block_refs {
                 Some(ok!(ShardBlockRefs::load_from(slice)))
+            } else {
+                None
             },
         })
     }
@@ -257,12 +414,46 @@ pub struct LibDescr {
     pub publishers: Dict<HashBytes, ()>,
 }
 
+impl LibDescr {
+    /// Creates a library description with a single publisher.
+    pub fn new(lib: Cell, publisher: &HashBytes) -> Result<Self, Error> {
+        let mut publishers = Dict::new();
+        ok!(publishers.set(publisher, ()));
+        Ok(Self { lib, publishers })
+    }
+
+    /// Adds the account to the library publishers.
+    ///
+    /// Returns `false` if the account was already a publisher.
+    pub fn add_publisher(&mut self, publisher: &HashBytes) -> Result<bool, Error> {
+        self.publishers.add(publisher, ())
+    }
+
+    /// Removes the account from the library publishers.
+    ///
+    /// Returns `false` if the account was not a publisher.
+    pub fn remove_publisher(&mut self, publisher: &HashBytes) -> Result<bool, Error> {
+        match self.publishers.remove(publisher) {
+            Ok(removed) => Ok(removed.is_some()),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Returns `true` if the library has at least one publisher.
+    ///
+    /// Entries without publishers can't be stored and must be removed.
+    pub fn has_publishers(&self) -> bool {
+        !self.publishers.is_empty()
+    }
+}
+
 impl Store for LibDescr {
     fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
         ok!(builder.store_small_uint(0, 2));
         ok!(builder.store_reference(self.lib.clone()));
+        // NOTE: publishers are stored as a non-empty `Hashmap` (inline)
         match self.publishers.root() {
-            Some(root) => builder.store_reference(root.clone()),
+            Some(root) => builder.store_slice(ok!(root.as_slice())),
             None => Err(Error::InvalidData),
         }
     }
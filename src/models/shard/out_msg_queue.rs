@@ -0,0 +1,244 @@
+use crate::cell::*;
+use crate::dict::{AugDict, Dict, DictKey};
+use crate::error::*;
+
+use crate::models::message::{IntAddr, IntMsgInfo, MsgInfo};
+
+/// Outbound messages queue info.
+///
+/// ```text
+/// _ out_queue:OutMsgQueue proc_info:ProcessedInfo
+///   ihr_pending:IhrPendingInfo = OutMsgQueueInfo;
+/// ```
+#[derive(Debug, Default, Clone, Eq, PartialEq, Store, Load)]
+pub struct OutMsgQueueInfo {
+    /// Enqueued outbound messages.
+    pub out_queue: OutMsgQueue,
+    /// Info about the processed inbound messages from other shards.
+    pub proc_info: ProcessedInfo,
+    /// Pending IHR messages.
+    pub ihr_pending: IhrPendingInfo,
+}
+
+/// Outbound messages queue with the minimal enqueued lt as an augmentation.
+///
+/// ```text
+/// _ (HashmapAugE 352 EnqueuedMsg uint64) = OutMsgQueue;
+/// ```
+pub type OutMsgQueue = AugDict<OutMsgQueueKey, u64, EnqueuedMsg>;
+
+/// Outbound messages queue key.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct OutMsgQueueKey {
+    /// Destination workchain.
+    pub workchain: i32,
+    /// Destination address prefix.
+    pub prefix: u64,
+    /// Message hash.
+    pub hash: HashBytes,
+}
+
+impl DictKey for OutMsgQueueKey {
+    const BITS: u16 = 32 + 64 + 256;
+
+    #[inline]
+    fn from_raw_data(raw_data: &[u8; 128]) -> Option<Self> {
+        Some(Self {
+            workchain: i32::from_be_bytes(raw_data[0..4].try_into().unwrap()),
+            prefix: u64::from_be_bytes(raw_data[4..12].try_into().unwrap()),
+            hash: HashBytes(raw_data[12..44].try_into().unwrap()),
+        })
+    }
+}
+
+impl Store for OutMsgQueueKey {
+    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        ok!(builder.store_u32(self.workchain as u32));
+        ok!(builder.store_u64(self.prefix));
+        builder.store_u256(&self.hash)
+    }
+}
+
+/// Message in the outbound queue.
+///
+/// ```text
+/// _ enqueued_lt:uint64 out_msg:^MsgEnvelope = EnqueuedMsg;
+/// ```
+#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+pub struct EnqueuedMsg {
+    /// Logical time when the message was enqueued.
+    pub enqueued_lt: u64,
+    /// Message envelope.
+    pub out_msg: Cell,
+}
+
+impl EnqueuedMsg {
+    /// Loads the source address of the message from its envelope.
+    pub fn load_src(&self) -> Result<IntAddr, Error> {
+        match self.load_info() {
+            Ok(info) => Ok(info.src),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Loads the current position of the message (workchain and address prefix)
+    /// from the intermediate address of its envelope.
+    ///
+    /// NOTE: Transit messages are routed by this address,
+    /// so it can differ from both source and destination addresses.
+    ///
+    /// ```text
+    /// interm_addr_regular$0 use_dest_bits:(#<= 96) = IntermediateAddress;
+    /// interm_addr_simple$10 workchain_id:int8 addr_pfx:uint64 = IntermediateAddress;
+    /// interm_addr_ext$11 workchain_id:int32 addr_pfx:uint64 = IntermediateAddress;
+    /// ```
+    pub fn load_cur_addr(&self) -> Result<(i32, u64), Error> {
+        let mut envelope = ok!(self.out_msg.as_slice());
+        // msg_envelope#4 or msg_envelope_v2#5
+        if !matches!(ok!(envelope.load_small_uint(4)), 4 | 5) {
+            return Err(Error::InvalidTag);
+        }
+
+        if ok!(envelope.load_bit()) {
+            let workchain = if ok!(envelope.load_bit()) {
+                ok!(envelope.load_u32()) as i32
+            } else {
+                ok!(envelope.load_u8()) as i8 as i32
+            };
+            return Ok((workchain, ok!(envelope.load_u64())));
+        }
+
+        let use_dest_bits = ok!(envelope.load_small_uint(7)) as u32;
+        if use_dest_bits > 96 {
+            return Err(Error::InvalidData);
+        }
+
+        // Take the first `use_dest_bits` of the destination
+        // and the remaining bits of the source (`workchain ++ prefix`)
+        let info = ok!(self.load_info());
+        let (src_wc, src_prefix) = (info.src.workchain(), info.src.prefix());
+        let (dst_wc, dst_prefix) = (info.dst.workchain(), info.dst.prefix());
+        Ok(if use_dest_bits <= 32 {
+            let mask = !u32::MAX.checked_shr(use_dest_bits).unwrap_or_default();
+            let workchain = (dst_wc as u32 & mask) | (src_wc as u32 & !mask);
+            (workchain as i32, src_prefix)
+        } else {
+            let mask = !u64::MAX.checked_shr(use_dest_bits - 32).unwrap_or_default();
+            (dst_wc, (dst_prefix & mask) | (src_prefix & !mask))
+        })
+    }
+
+    fn load_info(&self) -> Result<IntMsgInfo, Error> {
+        // NOTE: message is the only reference of the envelope
+        let msg = match self.out_msg.as_ref().reference(0) {
+            Some(msg) => msg,
+            None => return Err(Error::CellUnderflow),
+        };
+        match MsgInfo::load_from(&mut ok!(msg.as_slice())) {
+            Ok(MsgInfo::Int(info)) => Ok(info),
+            Ok(_) => Err(Error::InvalidData),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+/// Processed inbound messages info for each neighbour shard.
+///
+/// ```text
+/// _ (HashmapE 96 ProcessedUpto) = ProcessedInfo;
+/// ```
+pub type ProcessedInfo = Dict<ProcessedUptoKey, ProcessedUpto>;
+
+/// Processed info key.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct ProcessedUptoKey {
+    /// Shard prefix with tag.
+    pub shard: u64,
+    /// Masterchain block seqno.
+    pub mc_seqno: u32,
+}
+
+impl DictKey for ProcessedUptoKey {
+    const BITS: u16 = 64 + 32;
+
+    #[inline]
+    fn from_raw_data(raw_data: &[u8; 128]) -> Option<Self> {
+        Some(Self {
+            shard: u64::from_be_bytes(raw_data[0..8].try_into().unwrap()),
+            mc_seqno: u32::from_be_bytes(raw_data[8..12].try_into().unwrap()),
+        })
+    }
+
+    #[inline]
+    fn write_raw_key(&self, buffer: &mut [u8; 32]) -> bool {
+        buffer[0..8].copy_from_slice(&self.shard.to_be_bytes());
+        buffer[8..12].copy_from_slice(&self.mc_seqno.to_be_bytes());
+        true
+    }
+}
+
+impl Store for ProcessedUptoKey {
+    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        ok!(builder.store_u64(self.shard));
+        builder.store_u32(self.mc_seqno)
+    }
+}
+
+/// The last processed inbound message.
+///
+/// ```text
+/// processed_upto$_ last_msg_lt:uint64 last_msg_hash:bits256 = ProcessedUpto;
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Store, Load)]
+pub struct ProcessedUpto {
+    /// Logical time of the last processed message.
+    pub last_msg_lt: u64,
+    /// Hash of the last processed message.
+    pub last_msg_hash: HashBytes,
+}
+
+/// Pending IHR messages.
+///
+/// ```text
+/// _ (HashmapE 320 IhrPendingSince) = IhrPendingInfo;
+/// ```
+pub type IhrPendingInfo = Dict<IhrPendingKey, IhrPendingSince>;
+
+/// Pending IHR message key.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub struct IhrPendingKey {
+    /// Address prefix.
+    pub prefix: u64,
+    /// Message hash.
+    pub hash: HashBytes,
+}
+
+impl DictKey for IhrPendingKey {
+    const BITS: u16 = 64 + 256;
+
+    #[inline]
+    fn from_raw_data(raw_data: &[u8; 128]) -> Option<Self> {
+        Some(Self {
+            prefix: u64::from_be_bytes(raw_data[0..8].try_into().unwrap()),
+            hash: HashBytes(raw_data[8..40].try_into().unwrap()),
+        })
+    }
+}
+
+impl Store for IhrPendingKey {
+    fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
+        ok!(builder.store_u64(self.prefix));
+        builder.store_u256(&self.hash)
+    }
+}
+
+/// Pending IHR message info.
+///
+/// ```text
+/// ihr_pending$_ import_lt:uint64 = IhrPendingSince;
+/// ```
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Store, Load)]
+pub struct IhrPendingSince {
+    /// Logical time when the message was imported.
+    pub import_lt: u64,
+}
@@ -1,17 +1,33 @@
 use std::borrow::Borrow;
 
 use crate::cell::*;
-use crate::dict::{self, AugDict, AugDictSkipValue};
+use crate::dict::{self, AugDict};
 use crate::error::*;
 
 use crate::models::currency::CurrencyCollection;
-use crate::models::ShardAccount;
+use crate::models::{ShardAccount, ShardIdent};
 
 /// A dictionary of account states.
-#[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
+#[derive(Debug, Default, Clone, Eq, PartialEq, Store, Load)]
 pub struct ShardAccounts(AugDict<HashBytes, DepthBalanceInfo, ShardAccount>);
 
 impl ShardAccounts {
+    /// Creates an empty shard accounts dictionary.
+    pub fn new() -> Self {
+        Self(AugDict::new())
+    }
+
+    /// Returns `true` if the dictionary contains no accounts.
+    pub const fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the total balance info of all accounts.
+    #[inline]
+    pub const fn root_extra(&self) -> &DepthBalanceInfo {
+        self.0.root_extra()
+    }
+
     /// Returns the account state corresponding to the key.
     pub fn get<'a: 'b, 'b, Q>(&'a self, key: Q) -> Result<Option<ShardAccount>, Error>
     where
@@ -20,7 +36,7 @@ impl ShardAccounts {
         fn get_impl(dict: &ShardAccounts, key: &HashBytes) -> Result<Option<ShardAccount>, Error> {
             match dict.get_raw(key) {
                 Ok(Some(mut value)) => {
-                    if DepthBalanceInfo::skip_value(&mut value) {
+                    if value.skip_value::<DepthBalanceInfo>().is_ok() {
                         match ShardAccount::load_from(&mut value) {
                             Ok(value) => Ok(Some(value)),
                             Err(e) => Err(e),
@@ -37,6 +53,13 @@ impl ShardAccounts {
         get_impl(self, key.borrow())
     }
 
+    /// Returns the underlying dictionary.
+    #[cfg(all(feature = "models", feature = "rand"))]
+    #[inline]
+    pub(crate) fn dict(&self) -> &dict::Dict<HashBytes, (DepthBalanceInfo, ShardAccount)> {
+        self.0.dict()
+    }
+
     /// Returns the raw value (with augmentation) corresponding to the key.
     pub fn get_raw<'a: 'b, 'b, Q>(&'a self, key: Q) -> Result<Option<CellSlice<'a>>, Error>
     where
@@ -53,6 +76,47 @@ impl ShardAccounts {
         self.0.dict().contains_key(key)
     }
 
+    /// Sets the account state and its balance info for the specified account id.
+    pub fn set<Q, E, T>(&mut self, key: Q, info: E, account: T) -> Result<bool, Error>
+    where
+        Q: Borrow<HashBytes>,
+        E: Borrow<DepthBalanceInfo>,
+        T: Borrow<ShardAccount>,
+    {
+        self.0.set(key, info, account, depth_balance_comp)
+    }
+
+    /// Removes the account state for the specified account id.
+    /// Returns an optional removed balance info and account state.
+    pub fn remove<Q>(&mut self, key: Q) -> Result<Option<(DepthBalanceInfo, ShardAccount)>, Error>
+    where
+        Q: Borrow<HashBytes>,
+    {
+        self.0.remove(key, depth_balance_comp)
+    }
+
+    /// Splits the accounts between the left and right child shards of the specified shard.
+    ///
+    /// NOTE: All accounts must belong to the specified shard,
+    /// otherwise [`Error::InvalidData`] is returned.
+    pub fn split_by_shard(&self, shard: &ShardIdent) -> Result<(Self, Self), Error> {
+        let prefix_len = shard.prefix_len();
+        let prefix = shard.prefix().checked_shr(64 - prefix_len as u32);
+
+        let mut key_prefix = CellBuilder::new();
+        ok!(key_prefix.store_uint(prefix.unwrap_or_default(), prefix_len));
+
+        let (left, right) = ok!(self.0.split_by_prefix(&key_prefix.as_data_slice()));
+        Ok((Self(left), Self(right)))
+    }
+
+    /// Merges the accounts with the accounts of another shard.
+    ///
+    /// Returns [`Error::InvalidData`] if some account is present in both dictionaries.
+    pub fn merge(&mut self, other: &Self) -> Result<(), Error> {
+        self.0.merge(&other.0, depth_balance_comp)
+    }
+
     /// Gets an iterator over the entries of the shard accounts (without augmentation),
     /// sorted by account id. The iterator element is `Result<(HashBytes, ShardAccount)>`.
     ///
@@ -68,10 +132,21 @@ impl ShardAccounts {
     pub fn raw_iter(&self) -> ShardAccountsRawIter<'_> {
         ShardAccountsRawIter::new(self.0.dict().root())
     }
+
+    /// Gets an iterator over the changed entries between two shard accounts,
+    /// sorted by account id. The iterator element is `Result<(HashBytes, ShardAccountDiff)>`.
+    ///
+    /// `self` is treated as an old state and `other` as a new one.
+    /// Unchanged accounts are skipped.
+    ///
+    /// If any dict is invalid, finishes after the first invalid element, returning an error.
+    pub fn diff_iter<'a>(&'a self, other: &'a Self) -> ShardAccountsDiffIter<'a> {
+        ShardAccountsDiffIter::new(self.0.dict().root(), other.0.dict().root())
+    }
 }
 
 /// Intermediate balance info.
-#[derive(Debug, Clone, Eq, PartialEq)]
+#[derive(Debug, Default, Clone, Eq, PartialEq)]
 pub struct DepthBalanceInfo {
     /// Depth for which the balance was calculated.
     pub split_depth: u8,
@@ -117,10 +192,21 @@ impl<'a> Load<'a> for DepthBalanceInfo {
     }
 }
 
-impl<'a> AugDictSkipValue<'a> for DepthBalanceInfo {
-    fn skip_value(slice: &mut CellSlice<'a>) -> bool {
-        slice.try_advance(5, 0) && CurrencyCollection::skip_value(slice)
+/// Combines the balance info of two subtrees
+/// (sums balances and takes the max split depth).
+fn depth_balance_comp(
+    left: &mut CellSlice<'_>,
+    right: &mut CellSlice<'_>,
+    builder: &mut CellBuilder,
+    context: &mut dyn CellContext,
+) -> Result<(), Error> {
+    let left = ok!(DepthBalanceInfo::load_from(left));
+    let right = ok!(DepthBalanceInfo::load_from(right));
+    DepthBalanceInfo {
+        split_depth: std::cmp::max(left.split_depth, right.split_depth),
+        balance: ok!(left.balance.checked_add(&right.balance)),
     }
+    .store_into(builder, context)
 }
 
 /// An iterator over the entries of a [`ShardAccounts`] (without augmentation).
@@ -148,7 +234,7 @@ impl<'a> Iterator for ShardAccountsIter<'a> {
     fn next(&mut self) -> Option<Self::Item> {
         Some(match self.inner.next()? {
             Ok((key, mut value)) => {
-                let e = if DepthBalanceInfo::skip_value(&mut value) {
+                let e = if value.skip_value::<DepthBalanceInfo>().is_ok() {
                     match ShardAccount::load_from(&mut value) {
                         Ok(value) => {
                             return Some(Ok((
@@ -198,3 +284,237 @@ impl<'a> Iterator for ShardAccountsRawIter<'a> {
         })
     }
 }
+
+/// Account change between two [`ShardAccounts`].
+///
+/// See [`ShardAccounts::diff_iter`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ShardAccountDiff {
+    /// Account is present only in the new state.
+    Added(ShardAccount),
+    /// Account is present only in the old state.
+    Removed(ShardAccount),
+    /// Account is present in both states but differs.
+    Changed {
+        /// Account entry from the old state.
+        old: ShardAccount,
+        /// Account entry from the new state.
+        new: ShardAccount,
+    },
+}
+
+/// An iterator over the changed entries between two [`ShardAccounts`].
+///
+/// Subtrees with the same representation hash in both dictionaries
+/// are skipped without being visited.
+///
+/// This struct is created by the [`diff_iter`] method on [`ShardAccounts`].
+/// See its documentation for more.
+///
+/// [`diff_iter`]: ShardAccounts::diff_iter
+#[derive(Clone)]
+pub struct ShardAccountsDiffIter<'a> {
+    segments: Vec<DiffSegment<'a>>,
+    error: Option<Error>,
+}
+
+impl<'a> ShardAccountsDiffIter<'a> {
+    fn new(old: &'a Option<Cell>, new: &'a Option<Cell>) -> Self {
+        fn load_root(root: Option<&DynCell>) -> Result<Option<DiffNode<'_>>, Error> {
+            match root {
+                Some(cell) => DiffNode::load(cell, 256).map(Some),
+                None => Ok(None),
+            }
+        }
+
+        let (old, new) = (old.as_deref(), new.as_deref());
+        if let (Some(old), Some(new)) = (old, new) {
+            if old.repr_hash() == new.repr_hash() {
+                return Self {
+                    segments: Vec::new(),
+                    error: None,
+                };
+            }
+        }
+
+        let root = match (load_root(old), load_root(new)) {
+            (Ok(old), Ok(new)) => DiffSegment {
+                key: CellBuilder::new(),
+                remaining_bit_len: 256,
+                old,
+                new,
+            },
+            (Err(e), _) | (_, Err(e)) => {
+                return Self {
+                    segments: Vec::new(),
+                    error: Some(e),
+                }
+            }
+        };
+
+        Self {
+            segments: vec![root],
+            error: None,
+        }
+    }
+
+    #[inline]
+    fn finish(&mut self, err: Error) -> Error {
+        self.segments.clear();
+        err
+    }
+}
+
+impl<'a> Iterator for ShardAccountsDiffIter<'a> {
+    type Item = Result<(HashBytes, ShardAccountDiff), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        fn load_value(value: Option<CellSlice<'_>>) -> Result<Option<ShardAccount>, Error> {
+            let Some(mut value) = value else {
+                return Ok(None);
+            };
+            ok!(value.skip_value::<DepthBalanceInfo>());
+            ShardAccount::load_from(&mut value).map(Some)
+        }
+
+        fn next_impl<'a>(
+            segments: &mut Vec<DiffSegment<'a>>,
+        ) -> Result<Option<(HashBytes, ShardAccountDiff)>, Error> {
+            while let Some(segment) = segments.pop() {
+                // Find the longest label part which is the same for both sides
+                let label = match (&segment.old, &segment.new) {
+                    (Some(old), Some(new)) => old.label.longest_common_data_prefix(&new.label),
+                    (Some(node), None) | (None, Some(node)) => node.label,
+                    (None, None) => continue,
+                };
+                let prefix_len = label.remaining_bits();
+
+                let mut key = segment.key;
+                ok!(key.store_slice_data(label));
+
+                // Compare values if there are no remaining bits to read
+                let Some(remaining_bit_len) = segment.remaining_bit_len.checked_sub(prefix_len)
+                else {
+                    return Err(Error::CellUnderflow);
+                };
+                if remaining_bit_len == 0 {
+                    let diff = match (
+                        ok!(load_value(segment.old.map(|node| node.data))),
+                        ok!(load_value(segment.new.map(|node| node.data))),
+                    ) {
+                        (Some(old), Some(new)) if old == new => continue,
+                        (Some(old), Some(new)) => ShardAccountDiff::Changed { old, new },
+                        (Some(old), None) => ShardAccountDiff::Removed(old),
+                        (None, Some(new)) => ShardAccountDiff::Added(new),
+                        (None, None) => continue,
+                    };
+
+                    let key = HashBytes(key.raw_data()[..32].try_into().unwrap());
+                    return Ok(Some((key, diff)));
+                }
+
+                // Push branches in reverse order to visit the left one first
+                for bit in [true, false] {
+                    let old = ok!(DiffChild::new(segment.old.as_ref(), prefix_len, bit));
+                    let new = ok!(DiffChild::new(segment.new.as_ref(), prefix_len, bit));
+
+                    // Skip the same subtrees
+                    if let (Some(DiffChild::Cell(old)), Some(DiffChild::Cell(new))) = (&old, &new) {
+                        if old.repr_hash() == new.repr_hash() {
+                            continue;
+                        }
+                    }
+
+                    let mut key = key.clone();
+                    ok!(key.store_bit(bit));
+
+                    let remaining_bit_len = remaining_bit_len - 1;
+                    segments.push(DiffSegment {
+                        key,
+                        remaining_bit_len,
+                        old: ok!(DiffChild::load(old, remaining_bit_len)),
+                        new: ok!(DiffChild::load(new, remaining_bit_len)),
+                    });
+                }
+            }
+
+            Ok(None)
+        }
+
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+
+        match next_impl(&mut self.segments) {
+            Ok(res) => res.map(Ok),
+            Err(e) => Some(Err(self.finish(e))),
+        }
+    }
+}
+
+#[derive(Clone)]
+struct DiffSegment<'a> {
+    key: CellBuilder,
+    remaining_bit_len: u16,
+    old: Option<DiffNode<'a>>,
+    new: Option<DiffNode<'a>>,
+}
+
+#[derive(Clone, Copy)]
+struct DiffNode<'a> {
+    /// Unprocessed part of the node label.
+    label: CellSlice<'a>,
+    /// Node data after the label.
+    data: CellSlice<'a>,
+}
+
+impl<'a> DiffNode<'a> {
+    fn load(cell: &'a DynCell, key_bit_len: u16) -> Result<Self, Error> {
+        let mut data = ok!(cell.as_slice());
+        let label = ok!(dict::read_label(&mut data, key_bit_len));
+        if label.remaining_bits() > key_bit_len {
+            return Err(Error::CellUnderflow);
+        }
+        Ok(Self { label, data })
+    }
+}
+
+enum DiffChild<'a> {
+    /// A child of the fork node.
+    Cell(&'a DynCell),
+    /// The same node with the rest of the label.
+    Node(DiffNode<'a>),
+}
+
+impl<'a> DiffChild<'a> {
+    /// Returns the subtree of the node for the next key bit
+    /// after the `prefix_len` bits of its label.
+    fn new(node: Option<&DiffNode<'a>>, prefix_len: u16, bit: bool) -> Result<Option<Self>, Error> {
+        let Some(node) = node else {
+            return Ok(None);
+        };
+
+        if prefix_len == node.label.remaining_bits() {
+            node.data
+                .get_reference(bit as u8)
+                .map(|cell| Some(Self::Cell(cell)))
+        } else if ok!(node.label.get_bit(prefix_len)) == bit {
+            let mut label = node.label;
+            ok!(label.advance(prefix_len + 1, 0));
+            Ok(Some(Self::Node(DiffNode {
+                label,
+                data: node.data,
+            })))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn load(child: Option<Self>, key_bit_len: u16) -> Result<Option<DiffNode<'a>>, Error> {
+        match child {
+            Some(Self::Cell(cell)) => DiffNode::load(cell, key_bit_len).map(Some),
+            Some(Self::Node(node)) => Ok(Some(node)),
+            None => Ok(None),
+        }
+    }
+}
@@ -34,6 +34,24 @@ pub struct McStateExtra {
 impl McStateExtra {
     const TAG: u16 = 0xcc26;
     const BLOCK_STATS_TAG: u8 = 0x17;
+
+    /// Returns block production statistics for the specified validator.
+    pub fn get_creator_stats(&self, public_key: &HashBytes) -> Result<Option<CreatorStats>, Error> {
+        match &self.block_create_stats {
+            Some(stats) => stats.get(public_key),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns a reference to the latest key block with seqno less than
+    /// or equal to the specified one.
+    ///
+    /// Uses the [`prev_blocks`] dictionary.
+    ///
+    /// [`prev_blocks`]: Self::prev_blocks
+    pub fn find_prev_key_block(&self, seqno: u32) -> Result<Option<BlockRef>, Error> {
+        self.prev_blocks.find_prev_key_block(seqno)
+    }
 }
 
 impl Store for McStateExtra {
@@ -138,9 +156,102 @@ pub struct ValidatorBaseInfo {
 }
 
 /// A dictionary with old masterchain blocks by seqno.
-#[derive(Debug, Clone, Store, Load)]
+#[derive(Debug, Default, Clone, Store, Load)]
 pub struct OldMcBlocksInfo(AugDict<u32, KeyMaxLt, KeyBlockRef>);
 
+impl OldMcBlocksInfo {
+    /// Returns the underlying dictionary.
+    #[inline]
+    pub const fn as_dict(&self) -> &AugDict<u32, KeyMaxLt, KeyBlockRef> {
+        &self.0
+    }
+
+    /// Returns `true` if there is at least one key block in the dictionary.
+    pub fn has_key_blocks(&self) -> bool {
+        self.0.root_extra().has_key_block
+    }
+
+    /// Returns a reference to the masterchain block with the specified seqno.
+    pub fn get(&self, seqno: u32) -> Result<Option<KeyBlockRef>, Error> {
+        match self.0.get(seqno) {
+            Ok(Some((_, block))) => Ok(Some(block)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Gets an iterator over the key blocks in ascending seqno order.
+    pub fn iter_key_blocks(&self) -> impl Iterator<Item = Result<BlockRef, Error>> + '_ {
+        self.0.iter().filter_map(|entry| match entry {
+            Ok((_, _, block)) if block.is_key_block => Some(Ok(block.block_ref)),
+            Ok(_) => None,
+            Err(e) => Some(Err(e)),
+        })
+    }
+
+    /// Returns a reference to the latest key block with seqno less than
+    /// or equal to the specified one.
+    ///
+    /// Subtrees without key blocks (according to [`KeyMaxLt`])
+    /// or with only greater seqno are skipped.
+    pub fn find_prev_key_block(&self, seqno: u32) -> Result<Option<BlockRef>, Error> {
+        match self.0.dict().root() {
+            Some(root) => find_prev_key_block(root.as_ref(), 0, 0, seqno),
+            None => Ok(None),
+        }
+    }
+}
+
+fn find_prev_key_block(
+    cell: &DynCell,
+    mut prefix: u32,
+    mut prefix_len: u16,
+    seqno: u32,
+) -> Result<Option<BlockRef>, Error> {
+    let mut slice = ok!(cell.as_slice());
+
+    let mut label = ok!(crate::dict::read_label(&mut slice, 32 - prefix_len));
+    let label_len = label.remaining_bits();
+    if label_len > 0 {
+        let label = ok!(label.load_uint(label_len));
+        prefix = (((prefix as u64) << label_len) | label) as u32;
+        prefix_len += label_len;
+    }
+
+    // Skip subtrees with only greater keys
+    let rem = 32 - prefix_len;
+    let min_seqno = if rem == 32 { 0 } else { prefix << rem };
+    if min_seqno > seqno {
+        return Ok(None);
+    }
+
+    // Skip subtrees without key blocks
+    let extra = ok!(KeyMaxLt::load_from(&mut slice));
+    if !extra.has_key_block {
+        return Ok(None);
+    }
+
+    if rem == 0 {
+        let block = ok!(KeyBlockRef::load_from(&mut slice));
+        return Ok(block.is_key_block.then_some(block.block_ref));
+    }
+
+    // Search the right subtree first for the greatest seqno
+    for bit in [1, 0] {
+        let child = ok!(slice.get_reference(bit as u8));
+        let found = ok!(find_prev_key_block(
+            child,
+            (prefix << 1) | bit,
+            prefix_len + 1,
+            seqno
+        ));
+        if found.is_some() {
+            return Ok(found);
+        }
+    }
+    Ok(None)
+}
+
 /// Entry value for the [`OldMcBlocksInfo`] dictionary.
 #[derive(Debug, Clone, Eq, PartialEq, Store, Load)]
 pub struct KeyBlockRef {
@@ -151,7 +262,7 @@ pub struct KeyBlockRef {
 }
 
 /// Value augmentation for the [`OldMcBlocksInfo`] dictionary.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Store, Load)]
+#[derive(Debug, Default, Copy, Clone, Eq, PartialEq, Store, Load)]
 pub struct KeyMaxLt {
     /// Has key block in a subtree.
     pub has_key_block: bool,
@@ -169,6 +280,13 @@ pub struct CreatorStats {
     pub shard_blocks: BlockCounters,
 }
 
+impl CreatorStats {
+    /// Returns the total number of produced blocks in all workchains.
+    pub const fn total_blocks(&self) -> u64 {
+        self.mc_blocks.total.saturating_add(self.shard_blocks.total)
+    }
+}
+
 /// Block counters with absolute value and rates.
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Store, Load)]
 pub struct BlockCounters {
@@ -0,0 +1,368 @@
+use crate::cell::*;
+use crate::dict::{Dict, DictKey};
+use crate::error::*;
+
+use crate::models::block::{BlockRef, ShardIdent};
+
+use super::{OutMsgQueue, OutMsgQueueInfo, ShardAccounts, ShardStateUnsplit};
+
+impl ShardStateUnsplit {
+    /// Splits the state into the left and right child states.
+    ///
+    /// Accounts are distributed by the account id prefix and outbound
+    /// messages are distributed by their current (intermediate) address
+    /// prefix, so transit messages stay on their route. Total balances
+    /// are recomputed, processed info is copied into both states and
+    /// validator fees are assigned to the left state.
+    ///
+    /// Returns an error for masterchain states and shards which can't be split.
+    pub fn split(&self) -> Result<(Self, Self), Error> {
+        if self.shard_ident.is_masterchain() {
+            return Err(Error::InvalidData);
+        }
+        let Some((left_shard, right_shard)) = self.shard_ident.split() else {
+            return Err(Error::InvalidData);
+        };
+
+        // Split accounts
+        let accounts = ok!(self.load_accounts());
+        let (left_accounts, right_accounts) = ok!(accounts.split_by_shard(&self.shard_ident));
+
+        // Split outbound messages queue by the current message position
+        let queue_info = ok!(self.load_out_msg_queue_info());
+        let mut left_queue = OutMsgQueue::new();
+        let mut right_queue = OutMsgQueue::new();
+        for entry in queue_info.out_queue.iter() {
+            let (key, lt, msg) = ok!(entry);
+            let (_, cur_prefix) = ok!(msg.load_cur_addr());
+            let target = if left_shard.contains_prefix(cur_prefix) {
+                &mut left_queue
+            } else {
+                &mut right_queue
+            };
+            ok!(target.set(key, lt, msg, min_lt_comp));
+        }
+
+        let make_child = |shard_ident: ShardIdent, accounts: ShardAccounts, out_queue| {
+            let out_msg_queue_info = ok!(CellBuilder::build_from(OutMsgQueueInfo {
+                out_queue,
+                proc_info: queue_info.proc_info.clone(),
+                ihr_pending: queue_info.ihr_pending.clone(),
+            }));
+
+            Ok::<_, Error>(Self {
+                shard_ident,
+                out_msg_queue_info,
+                before_split: false,
+                total_balance: accounts.root_extra().balance.clone(),
+                accounts: ok!(crate::models::Lazy::new(&accounts)),
+                overload_history: 0,
+                underload_history: 0,
+                custom: None,
+                ..self.clone()
+            })
+        };
+
+        let left = ok!(make_child(left_shard, left_accounts, left_queue));
+        let mut right = ok!(make_child(right_shard, right_accounts, right_queue));
+        right.total_validator_fees = Default::default();
+
+        Ok((left, right))
+    }
+
+    /// Merges the left and right sibling states into the parent state.
+    ///
+    /// Accounts and outbound messages are combined, total balances are
+    /// recomputed and all block related info is taken from the latest state.
+    ///
+    /// Returns an error if the states are not the left and right children
+    /// of the same shard, or if their processed info entries conflict.
+    pub fn merge(left: &Self, right: &Self) -> Result<Self, Error> {
+        let Some(shard_ident) = left.shard_ident.merge() else {
+            return Err(Error::InvalidData);
+        };
+        if left.global_id != right.global_id
+            || !left.shard_ident.is_left_child()
+            || !right.shard_ident.is_right_child()
+            || right.shard_ident.merge() != Some(shard_ident)
+        {
+            return Err(Error::InvalidData);
+        }
+
+        // Merge accounts
+        let mut accounts = ok!(left.load_accounts());
+        ok!(accounts.merge(&ok!(right.load_accounts())));
+
+        // Merge outbound messages queue
+        let mut queue_info = ok!(left.load_out_msg_queue_info());
+        let right_queue_info = ok!(right.load_out_msg_queue_info());
+        for entry in right_queue_info.out_queue.iter() {
+            let (key, lt, msg) = ok!(entry);
+            ok!(queue_info.out_queue.set(key, lt, msg, min_lt_comp));
+        }
+        ok!(merge_dicts(
+            &mut queue_info.proc_info,
+            &right_queue_info.proc_info
+        ));
+        ok!(merge_dicts(
+            &mut queue_info.ihr_pending,
+            &right_queue_info.ihr_pending
+        ));
+
+        let latest = if left.gen_lt >= right.gen_lt {
+            left
+        } else {
+            right
+        };
+
+        let master_ref = match (&left.master_ref, &right.master_ref) {
+            (Some(left), Some(right)) => Some(newest_block_ref(left, right).clone()),
+            (master_ref, None) | (None, master_ref) => master_ref.clone(),
+        };
+
+        Ok(Self {
+            global_id: left.global_id,
+            shard_ident,
+            seqno: std::cmp::max(left.seqno, right.seqno),
+            vert_seqno: std::cmp::max(left.vert_seqno, right.vert_seqno),
+            gen_utime: latest.gen_utime,
+            gen_utime_ms: latest.gen_utime_ms,
+            gen_lt: latest.gen_lt,
+            min_ref_mc_seqno: std::cmp::min(left.min_ref_mc_seqno, right.min_ref_mc_seqno),
+            out_msg_queue_info: ok!(CellBuilder::build_from(queue_info)),
+            before_split: false,
+            total_balance: accounts.root_extra().balance.clone(),
+            accounts: ok!(crate::models::Lazy::new(&accounts)),
+            overload_history: 0,
+            underload_history: 0,
+            total_validator_fees: ok!(left
+                .total_validator_fees
+                .checked_add(&right.total_validator_fees)),
+            libraries: left.libraries.clone(),
+            master_ref,
+            custom: None,
+            shard_block_refs: latest.shard_block_refs.clone(),
+        })
+    }
+}
+
+fn newest_block_ref<'a>(left: &'a BlockRef, right: &'a BlockRef) -> &'a BlockRef {
+    if left.seqno >= right.seqno {
+        left
+    } else {
+        right
+    }
+}
+
+fn merge_dicts<K, V>(left: &mut Dict<K, V>, right: &Dict<K, V>) -> Result<(), Error>
+where
+    K: Store + DictKey,
+    V: Store + PartialEq + for<'a> Load<'a>,
+{
+    for entry in right.iter() {
+        let (key, value) = ok!(entry);
+        match ok!(left.get(&key)) {
+            // NOTE: Entries are copied into both states on split
+            Some(existing) if existing == value => {}
+            Some(_) => return Err(Error::InvalidData),
+            None => {
+                ok!(left.add(key, value));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Computes the augmentation of the outbound messages queue (the minimal enqueued lt).
+fn min_lt_comp(
+    left: &mut CellSlice<'_>,
+    right: &mut CellSlice<'_>,
+    builder: &mut CellBuilder,
+    _: &mut dyn CellContext,
+) -> Result<(), Error> {
+    let left = ok!(left.load_u64());
+    let right = ok!(right.load_u64());
+    builder.store_u64(std::cmp::min(left, right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::boc::Boc;
+    use crate::models::{
+        CurrencyCollection, DepthBalanceInfo, EnqueuedMsg, IntMsgInfo, Lazy, MsgInfo,
+        OptionalAccount, OutMsgQueueKey, ProcessedUpto, ProcessedUptoKey, ShardAccount, StdAddr,
+    };
+    use crate::num::Tokens;
+
+    fn make_envelope(src: HashBytes, dst: HashBytes, use_dest_bits: u8) -> Cell {
+        let mut msg = CellBuilder::new();
+        MsgInfo::Int(IntMsgInfo {
+            src: StdAddr::new(0, src).into(),
+            dst: StdAddr::new(0, dst).into(),
+            ..Default::default()
+        })
+        .store_into(&mut msg, &mut Cell::empty_context())
+        .unwrap();
+        // No state init, inline body
+        msg.store_zeros(2).unwrap();
+
+        let mut envelope = CellBuilder::new();
+        envelope.store_small_uint(4, 4).unwrap();
+        // Regular intermediate addresses
+        envelope.store_u8(use_dest_bits).unwrap();
+        envelope.store_u8(use_dest_bits).unwrap();
+        envelope.store_zeros(4).unwrap();
+        envelope.store_reference(msg.build().unwrap()).unwrap();
+        envelope.build().unwrap()
+    }
+
+    #[test]
+    fn split_merge_state() {
+        let root = Boc::decode(include_bytes!("tests/new_zerostate.boc")).unwrap();
+        let mut state = root.parse::<ShardStateUnsplit>().unwrap();
+
+        // Masterchain can't be split
+        assert!(state.split().is_err());
+
+        let mut accounts = ShardAccounts::new();
+        for (i, byte) in [0x11, 0x22, 0x88, 0x99].into_iter().enumerate() {
+            let info = DepthBalanceInfo {
+                split_depth: 0,
+                balance: CurrencyCollection::new(i as u128 + 1),
+            };
+            let account = ShardAccount {
+                account: Lazy::new(&OptionalAccount::EMPTY).unwrap(),
+                last_trans_hash: HashBytes([byte; 32]),
+                last_trans_lt: i as u64,
+            };
+            accounts.set(HashBytes([byte; 32]), info, account).unwrap();
+        }
+
+        let mut out_queue = OutMsgQueue::new();
+        // NOTE: The last message is a transit message which is already
+        // routed to the destination, but its source is in the right shard
+        for (i, (src, dst, use_dest_bits)) in [
+            (0x11, 0x99, 0),
+            (0x99, 0x22, 0),
+            (0x22, 0x22, 0),
+            (0x99, 0x22, 96),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let i = i as u8;
+            let key = OutMsgQueueKey {
+                workchain: 0,
+                prefix: u64::from_be_bytes([i; 8]),
+                hash: HashBytes([i; 32]),
+            };
+            let msg = EnqueuedMsg {
+                enqueued_lt: 100 + i as u64,
+                out_msg: make_envelope(HashBytes([src; 32]), HashBytes([dst; 32]), use_dest_bits),
+            };
+            out_queue
+                .set(key, msg.enqueued_lt, msg, min_lt_comp)
+                .unwrap();
+        }
+
+        state.shard_ident = ShardIdent::BASECHAIN;
+        state.custom = None;
+        state.total_balance = Tokens::new(10).into();
+        state.total_validator_fees = Tokens::new(5).into();
+        state.accounts = Lazy::new(&accounts).unwrap();
+        state
+            .set_out_msg_queue_info(&OutMsgQueueInfo {
+                out_queue,
+                ..Default::default()
+            })
+            .unwrap();
+
+        // Split
+        let (left, right) = state.split().unwrap();
+        assert_eq!(
+            (left.shard_ident, right.shard_ident),
+            ShardIdent::BASECHAIN.split().unwrap()
+        );
+
+        let left_accounts = left.load_accounts().unwrap();
+        let right_accounts = right.load_accounts().unwrap();
+        assert_eq!(left_accounts.iter().count(), 2);
+        assert_eq!(right_accounts.iter().count(), 2);
+        assert_eq!(left.total_balance, Tokens::new(3).into());
+        assert_eq!(right.total_balance, Tokens::new(7).into());
+        for (shard, accounts) in [(&left, &left_accounts), (&right, &right_accounts)] {
+            for entry in accounts.iter() {
+                let (account, _) = entry.unwrap();
+                assert!(shard.shard_ident.contains_account(&account));
+            }
+        }
+        assert_eq!(left.total_validator_fees, Tokens::new(5).into());
+        assert_eq!(right.total_validator_fees, CurrencyCollection::ZERO);
+
+        let queue_len = |state: &ShardStateUnsplit| {
+            let info = state.load_out_msg_queue_info().unwrap();
+            (info.out_queue.iter().count(), *info.out_queue.root_extra())
+        };
+        assert_eq!(queue_len(&left), (3, 100));
+        assert_eq!(queue_len(&right), (1, 101));
+
+        // Merge
+        let merged = ShardStateUnsplit::merge(&left, &right).unwrap();
+        assert_eq!(merged.shard_ident, ShardIdent::BASECHAIN);
+        assert_eq!(merged.accounts, state.accounts);
+        assert_eq!(merged.out_msg_queue_info, state.out_msg_queue_info);
+        assert_eq!(merged.total_balance, state.total_balance);
+        assert_eq!(merged.total_validator_fees, state.total_validator_fees);
+
+        assert!(ShardStateUnsplit::merge(&right, &left).is_err());
+        assert!(ShardStateUnsplit::merge(&left, &left).is_err());
+
+        // Conflicting processed info
+        let mut conflicting = right.clone();
+        let mut queue_info = conflicting.load_out_msg_queue_info().unwrap();
+        let key = ProcessedUptoKey {
+            shard: ShardIdent::BASECHAIN.prefix(),
+            mc_seqno: 1,
+        };
+        queue_info
+            .proc_info
+            .set(
+                key,
+                ProcessedUpto {
+                    last_msg_lt: 1,
+                    last_msg_hash: HashBytes::ZERO,
+                },
+            )
+            .unwrap();
+        conflicting.set_out_msg_queue_info(&queue_info).unwrap();
+        let merged = ShardStateUnsplit::merge(&left, &conflicting).unwrap();
+
+        let mut left = left;
+        queue_info
+            .proc_info
+            .set(
+                key,
+                ProcessedUpto {
+                    last_msg_lt: 2,
+                    last_msg_hash: HashBytes::ZERO,
+                },
+            )
+            .unwrap();
+        left.set_out_msg_queue_info(&queue_info).unwrap();
+        assert_eq!(
+            ShardStateUnsplit::merge(&left, &conflicting).unwrap_err(),
+            Error::InvalidData
+        );
+        assert_eq!(
+            merged
+                .load_out_msg_queue_info()
+                .unwrap()
+                .proc_info
+                .get(key)
+                .unwrap()
+                .map(|v| v.last_msg_lt),
+            Some(1)
+        );
+    }
+}
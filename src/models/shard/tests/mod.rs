@@ -1,5 +1,6 @@
 use super::*;
-use crate::models::Block;
+use crate::dict::AugDict;
+use crate::models::{Account, AccountState, Block, IntAddr, OptionalAccount, ShardAccount};
 use crate::prelude::Boc;
 
 fn check_master_state(cell: Cell) {
@@ -17,6 +18,10 @@ fn check_master_state(cell: Cell) {
         let (id, shard_state) = entry.unwrap();
         let account = shard_state.load_account().unwrap();
         println!("{id}: {account:#?}");
+        assert_eq!(
+            shard_state.code_hash().unwrap(),
+            account.as_ref().and_then(Account::code_hash)
+        );
     }
 
     for (i, entry) in data.libraries.iter().enumerate() {
@@ -31,6 +36,12 @@ fn check_master_state(cell: Cell) {
     let _elector = shard_accounts.get([0x33; 32]).unwrap().unwrap();
     assert!(shard_accounts.contains_account([0x55; 32]).unwrap());
 
+    let queue_info = data.load_out_msg_queue_info().unwrap();
+    assert_eq!(
+        CellBuilder::build_from(&queue_info).unwrap(),
+        data.out_msg_queue_info
+    );
+
     let custom = data.load_custom().unwrap().unwrap();
     println!("custom: {custom:#?}");
     assert_eq!(
@@ -58,3 +69,547 @@ fn new_zerostate() {
     let new_state = state_update.apply(&zerostate).unwrap();
     check_master_state(new_state);
 }
+
+#[test]
+fn out_msg_queue_info() {
+    let root = Boc::decode(include_bytes!("new_zerostate.boc")).unwrap();
+    let mut state = root.parse::<ShardStateUnsplit>().unwrap();
+
+    let mut queue_info = state.load_out_msg_queue_info().unwrap();
+    assert!(queue_info.out_queue.is_empty());
+    assert!(queue_info.proc_info.is_empty());
+    assert!(queue_info.ihr_pending.is_empty());
+
+    let key = ProcessedUptoKey {
+        shard: ShardIdent::BASECHAIN.prefix(),
+        mc_seqno: 123,
+    };
+    let processed = ProcessedUpto {
+        last_msg_lt: 1000,
+        last_msg_hash: HashBytes([0x11; 32]),
+    };
+    queue_info.proc_info.set(key, processed).unwrap();
+
+    let key = IhrPendingKey {
+        prefix: 0x8000000000000000,
+        hash: HashBytes([0x22; 32]),
+    };
+    let pending = IhrPendingSince { import_lt: 2000 };
+    queue_info.ihr_pending.set(key, pending).unwrap();
+
+    state.set_out_msg_queue_info(&queue_info).unwrap();
+    let parsed = state.load_out_msg_queue_info().unwrap();
+    assert_eq!(parsed, queue_info);
+
+    let (key, value) = parsed.proc_info.iter().next().unwrap().unwrap();
+    assert_eq!(key.mc_seqno, 123);
+    assert_eq!(value, processed);
+    let (key, value) = parsed.ihr_pending.iter().next().unwrap().unwrap();
+    assert_eq!(key.hash, HashBytes([0x22; 32]));
+    assert_eq!(value, pending);
+
+    // Unchanged state is preserved
+    let mut state = root.parse::<ShardStateUnsplit>().unwrap();
+    let queue_info = state.load_out_msg_queue_info().unwrap();
+    state.set_out_msg_queue_info(&queue_info).unwrap();
+    assert_eq!(CellBuilder::build_from(&state).unwrap(), root);
+}
+
+#[test]
+fn shard_accounts_diff() {
+    let zerostate = Boc::decode(include_bytes!("new_zerostate.boc")).unwrap();
+    let block = Boc::decode(include_bytes!("first_block.boc")).unwrap();
+    let block = block.parse::<Block>().unwrap();
+    let new_state = block
+        .state_update
+        .load()
+        .unwrap()
+        .apply(&zerostate)
+        .unwrap();
+
+    let old = zerostate.parse::<ShardStateUnsplit>().unwrap();
+    let old = old.load_accounts().unwrap();
+    let new = new_state.parse::<ShardStateUnsplit>().unwrap();
+    let new = new.load_accounts().unwrap();
+
+    assert_eq!(old.diff_iter(&old).count(), 0);
+
+    let mut changed = 0;
+    for entry in old.diff_iter(&new) {
+        let (id, diff) = entry.unwrap();
+        match diff {
+            ShardAccountDiff::Added(account) => {
+                assert!(!old.contains_account(id).unwrap());
+                assert_eq!(new.get(id).unwrap(), Some(account));
+            }
+            ShardAccountDiff::Removed(account) => {
+                assert_eq!(old.get(id).unwrap(), Some(account));
+                assert!(!new.contains_account(id).unwrap());
+            }
+            ShardAccountDiff::Changed {
+                old: prev,
+                new: next,
+            } => {
+                assert_ne!(prev, next);
+                assert_eq!(old.get(id).unwrap(), Some(prev));
+                assert_eq!(new.get(id).unwrap(), Some(next));
+            }
+        }
+        changed += 1;
+    }
+    assert!(changed > 0);
+
+    let expected = new
+        .iter()
+        .filter(|entry| {
+            let (id, account) = entry.as_ref().unwrap();
+            old.get(id).unwrap().as_ref() != Some(account)
+        })
+        .count();
+    assert_eq!(changed, expected);
+}
+
+#[test]
+fn shard_accounts_diff_skips_same_subtrees() {
+    fn make_account(lt: u64) -> ShardAccount {
+        ShardAccount {
+            account: Lazy::new(&OptionalAccount::EMPTY).unwrap(),
+            last_trans_hash: HashBytes::ZERO,
+            last_trans_lt: lt,
+        }
+    }
+
+    fn make_key(i: u8) -> HashBytes {
+        let mut key = HashBytes([i.wrapping_mul(37); 32]);
+        key.0[31] = i;
+        key
+    }
+
+    fn collect(accounts: &ShardAccounts) -> std::collections::BTreeMap<HashBytes, ShardAccount> {
+        accounts.iter().map(Result::unwrap).collect()
+    }
+
+    let mut old = ShardAccounts::new();
+    for i in 0..100 {
+        old.set(make_key(i), DepthBalanceInfo::default(), make_account(0))
+            .unwrap();
+    }
+
+    let mut new = old.clone();
+    for i in [1, 10, 55] {
+        new.set(make_key(i), DepthBalanceInfo::default(), make_account(1))
+            .unwrap();
+    }
+    for i in [3, 42] {
+        new.remove(make_key(i)).unwrap();
+    }
+    for i in [100, 200] {
+        new.set(make_key(i), DepthBalanceInfo::default(), make_account(0))
+            .unwrap();
+    }
+    // Key with a label which diverges from the existing one in the middle
+    let mut key = make_key(7);
+    key.0[16] ^= 0x10;
+    new.set(key, DepthBalanceInfo::default(), make_account(0))
+        .unwrap();
+
+    // Compare with the full diff
+    let (old_items, new_items) = (collect(&old), collect(&new));
+    let mut expected = Vec::new();
+    for (id, account) in &old_items {
+        match new_items.get(id) {
+            None => expected.push((*id, ShardAccountDiff::Removed(account.clone()))),
+            Some(next) if next != account => expected.push((
+                *id,
+                ShardAccountDiff::Changed {
+                    old: account.clone(),
+                    new: next.clone(),
+                },
+            )),
+            Some(_) => {}
+        }
+    }
+    for (id, account) in &new_items {
+        if !old_items.contains_key(id) {
+            expected.push((*id, ShardAccountDiff::Added(account.clone())));
+        }
+    }
+    expected.sort_by_key(|(id, _)| *id);
+
+    let diff = old.diff_iter(&new).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(diff, expected);
+    assert_eq!(diff.len(), 8);
+
+    let diff = ShardAccounts::new()
+        .diff_iter(&new)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(diff.len(), new_items.len());
+    assert!(diff
+        .iter()
+        .all(|(_, diff)| matches!(diff, ShardAccountDiff::Added(_))));
+
+    // Unchanged subtrees are not visited
+    let mut old = ShardAccounts::new();
+    let mut new = ShardAccounts::new();
+    for byte in [0x11, 0x22, 0xaa, 0xbb] {
+        old.set(
+            HashBytes([byte; 32]),
+            DepthBalanceInfo::default(),
+            make_account(0),
+        )
+        .unwrap();
+        let lt = (byte == 0x11) as u64;
+        new.set(
+            HashBytes([byte; 32]),
+            DepthBalanceInfo::default(),
+            make_account(lt),
+        )
+        .unwrap();
+    }
+
+    let usage_tree = UsageTree::new(UsageTreeMode::OnDataAccess);
+    let new_cell = usage_tree.track(&CellBuilder::build_from(&new).unwrap());
+    let root = new_cell.reference(0).unwrap();
+    let unchanged = root.reference(1).unwrap();
+
+    let new = new_cell.parse::<ShardAccounts>().unwrap();
+    let diff = old.diff_iter(&new).collect::<Result<Vec<_>, _>>().unwrap();
+    assert_eq!(diff.len(), 1);
+    assert_eq!(diff[0].0, HashBytes([0x11; 32]));
+    assert!(usage_tree.contains(root.repr_hash()));
+    assert!(!usage_tree.contains(unchanged.repr_hash()));
+}
+
+#[test]
+fn shard_accounts_split_depth() {
+    let account = ShardAccount {
+        account: Lazy::new(&OptionalAccount::EMPTY).unwrap(),
+        last_trans_hash: HashBytes::ZERO,
+        last_trans_lt: 0,
+    };
+
+    let mut accounts = ShardAccounts::new();
+    for (byte, split_depth) in [(0x11, 3), (0x22, 0), (0xaa, 5), (0xbb, 2)] {
+        let info = DepthBalanceInfo {
+            split_depth,
+            balance: CurrencyCollection::new(10),
+        };
+        accounts
+            .set(HashBytes([byte; 32]), info, account.clone())
+            .unwrap();
+    }
+
+    // Fork extra has the max split depth of its children
+    let root = accounts.root_extra();
+    assert_eq!(root.split_depth, 5);
+    assert_eq!(root.balance, CurrencyCollection::new(40));
+
+    accounts.remove(HashBytes([0xaa; 32])).unwrap();
+    let root = accounts.root_extra();
+    assert_eq!(root.split_depth, 3);
+    assert_eq!(root.balance, CurrencyCollection::new(30));
+}
+
+#[test]
+fn mc_state_extra_queries() {
+    let zerostate = Boc::decode(include_bytes!("new_zerostate.boc")).unwrap();
+
+    let block = Boc::decode(include_bytes!("first_block.boc")).unwrap();
+    let block = block.parse::<Block>().unwrap();
+    let state_update = block.state_update.load().unwrap();
+
+    let state = state_update.apply(&zerostate).unwrap();
+    let state = state.parse::<ShardStateUnsplit>().unwrap();
+    let extra = state.load_custom().unwrap().unwrap();
+
+    // Creator stats
+    let stats = extra.block_create_stats.as_ref().unwrap();
+    let mut total = 0;
+    for entry in stats.iter() {
+        let (key, value) = entry.unwrap();
+        assert_eq!(extra.get_creator_stats(&key).unwrap(), Some(value.clone()));
+        total += value.total_blocks();
+    }
+    assert!(total > 0);
+    assert_eq!(
+        extra.get_creator_stats(&HashBytes([0xaa; 32])).unwrap(),
+        None
+    );
+
+    // Previous blocks
+    let zerostate_ref = extra.prev_blocks.get(0).unwrap().unwrap();
+    assert!(zerostate_ref.is_key_block);
+    assert!(extra.prev_blocks.has_key_blocks());
+    assert_eq!(extra.prev_blocks.get(100).unwrap(), None);
+
+    let key_blocks = extra
+        .prev_blocks
+        .iter_key_blocks()
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(key_blocks.len(), 1);
+    assert_eq!(key_blocks[0], zerostate_ref.block_ref);
+
+    assert_eq!(
+        extra.find_prev_key_block(100).unwrap(),
+        Some(zerostate_ref.block_ref)
+    );
+}
+
+#[test]
+fn find_prev_key_block() {
+    fn key_max_lt_comp(
+        left: &mut CellSlice<'_>,
+        right: &mut CellSlice<'_>,
+        builder: &mut CellBuilder,
+        context: &mut dyn CellContext,
+    ) -> Result<(), Error> {
+        let left = KeyMaxLt::load_from(left)?;
+        let right = KeyMaxLt::load_from(right)?;
+        KeyMaxLt {
+            has_key_block: left.has_key_block || right.has_key_block,
+            max_end_lt: left.max_end_lt.max(right.max_end_lt),
+        }
+        .store_into(builder, context)
+    }
+
+    let is_key_block = |seqno: u32| seqno % 37 == 5 || seqno == 1000;
+
+    let mut dict = AugDict::<u32, KeyMaxLt, KeyBlockRef>::new();
+    for seqno in (0..1200).step_by(3) {
+        let block = KeyBlockRef {
+            is_key_block: is_key_block(seqno),
+            block_ref: BlockRef {
+                end_lt: seqno as u64 * 10,
+                seqno,
+                root_hash: HashBytes::ZERO,
+                file_hash: HashBytes::ZERO,
+            },
+        };
+        let extra = KeyMaxLt {
+            has_key_block: block.is_key_block,
+            max_end_lt: block.block_ref.end_lt,
+        };
+        dict.set(seqno, extra, block, key_max_lt_comp).unwrap();
+    }
+    let prev_blocks = CellBuilder::build_from(&dict)
+        .unwrap()
+        .parse::<OldMcBlocksInfo>()
+        .unwrap();
+
+    for seqno in (0..1300).chain([u32::MAX - 1, u32::MAX]) {
+        let expected = (0..=seqno.min(1199))
+            .rev()
+            .find(|&s| s % 3 == 0 && is_key_block(s));
+        let found = prev_blocks.find_prev_key_block(seqno).unwrap();
+        assert_eq!(found.map(|block| block.seqno), expected, "seqno: {seqno}");
+    }
+
+    assert_eq!(
+        OldMcBlocksInfo::default().find_prev_key_block(100).unwrap(),
+        None
+    );
+
+    // Single entry with the full key label
+    let seqno = u32::MAX - 1;
+    let block = KeyBlockRef {
+        is_key_block: true,
+        block_ref: BlockRef {
+            end_lt: 0,
+            seqno,
+            root_hash: HashBytes::ZERO,
+            file_hash: HashBytes::ZERO,
+        },
+    };
+    let extra = KeyMaxLt {
+        has_key_block: true,
+        max_end_lt: 0,
+    };
+    let mut dict = AugDict::<u32, KeyMaxLt, KeyBlockRef>::new();
+    dict.set(seqno, extra, block, key_max_lt_comp).unwrap();
+    let prev_blocks = CellBuilder::build_from(&dict)
+        .unwrap()
+        .parse::<OldMcBlocksInfo>()
+        .unwrap();
+    assert_eq!(prev_blocks.find_prev_key_block(seqno - 1).unwrap(), None);
+    for seqno in [seqno, u32::MAX] {
+        let found = prev_blocks.find_prev_key_block(seqno).unwrap();
+        assert_eq!(found.map(|block| block.seqno), Some(u32::MAX - 1));
+    }
+}
+
+#[test]
+fn serialize_state_subset() {
+    let root = Boc::decode(include_bytes!("new_zerostate.boc")).unwrap();
+    let state = root.parse::<ShardStateUnsplit>().unwrap();
+
+    let elector = StdAddr::new(-1, HashBytes([0x33; 32]));
+    let config = StdAddr::new(-1, HashBytes([0x55; 32]));
+    let unknown = StdAddr::new(-1, HashBytes([0x01; 32]));
+
+    let proof = state
+        .serialize_subset(&[elector.clone(), unknown.clone()])
+        .unwrap();
+    assert_eq!(proof.hash, *root.repr_hash());
+
+    let virtual_root = Cell::virtualize(proof.cell.clone());
+    assert_eq!(virtual_root.repr_hash(), root.repr_hash());
+
+    let pruned = virtual_root.parse::<ShardStateUnsplit>().unwrap();
+    let accounts = pruned.load_accounts().unwrap();
+
+    // Requested accounts are fully included
+    let account = accounts.get(elector.address).unwrap().unwrap();
+    let account = account.load_account().unwrap().unwrap();
+    let original = state
+        .load_accounts()
+        .unwrap()
+        .get(elector.address)
+        .unwrap()
+        .unwrap()
+        .load_account()
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.balance, original.balance);
+    if let AccountState::Active(init) = &account.state {
+        let code = init.code.as_ref().unwrap();
+        assert!(code.compute_unique_stats(usize::MAX).is_some());
+    }
+
+    // Missing accounts are proven to be absent
+    assert!(accounts.get(unknown.address).unwrap().is_none());
+
+    // Other accounts are pruned
+    assert!(accounts.get(config.address).is_err());
+
+    // Config is included for the masterchain state
+    let custom = pruned.load_custom().unwrap().unwrap();
+    custom.config.get_global_version().unwrap();
+    custom.config.get_current_validator_set().unwrap();
+
+    // Proof is smaller than the whole state
+    let full_stats = root.compute_unique_stats(usize::MAX).unwrap();
+    let proof_stats = proof.cell.compute_unique_stats(usize::MAX).unwrap();
+    assert!(proof_stats.cell_count < full_stats.cell_count);
+}
+
+#[test]
+fn library_publishers() {
+    let root = Boc::decode(include_bytes!("new_zerostate.boc")).unwrap();
+    let mut state = root.parse::<ShardStateUnsplit>().unwrap();
+    state.validate_libraries().unwrap();
+
+    let lib = CellBuilder::build_from(0xdeafbeafu32).unwrap();
+    let lib_hash = *lib.repr_hash();
+    let first = HashBytes([0x11; 32]);
+    let second = HashBytes([0x22; 32]);
+
+    assert!(state.add_library_publisher(lib.clone(), &first).unwrap());
+    assert!(!state.add_library_publisher(lib.clone(), &first).unwrap());
+    assert!(state.add_library_publisher(lib.clone(), &second).unwrap());
+    state.validate_libraries().unwrap();
+
+    let descr = state.libraries.get(lib_hash).unwrap().unwrap();
+    assert_eq!(descr.lib, lib);
+    assert_eq!(descr.publishers.keys().count(), 2);
+
+    assert!(state.remove_library_publisher(&lib_hash, &first).unwrap());
+    assert!(!state.remove_library_publisher(&lib_hash, &first).unwrap());
+    assert!(state.libraries.contains_key(lib_hash).unwrap());
+
+    // Empty entries are pruned
+    assert!(state.remove_library_publisher(&lib_hash, &second).unwrap());
+    assert!(!state.libraries.contains_key(lib_hash).unwrap());
+    state.validate_libraries().unwrap();
+
+    // Entries must be stored under the library hash
+    let descr = LibDescr::new(lib.clone(), &first).unwrap();
+    state.libraries.set(HashBytes::ZERO, descr).unwrap();
+    assert!(state.validate_libraries().is_err());
+    state.libraries.remove(HashBytes::ZERO).unwrap();
+
+    // Libraries are allowed only in masterchain
+    state.shard_ident = ShardIdent::BASECHAIN;
+    assert!(state.add_library_publisher(lib.clone(), &first).is_err());
+
+    state
+        .libraries
+        .set(lib_hash, LibDescr::new(lib, &first).unwrap())
+        .unwrap();
+    assert!(state.validate_libraries().is_err());
+}
+
+#[test]
+fn zerostate_builder() {
+    const BOC: &[u8] = include_bytes!("new_zerostate.boc");
+    let cell = Boc::decode(BOC).unwrap();
+    let original = cell.parse::<ShardStateUnsplit>().unwrap();
+    assert!(original.is_zerostate());
+
+    // Zerostate id
+    let id = compute_zerostate_id(&cell).unwrap();
+    assert_eq!(id, original.compute_zerostate_id().unwrap());
+    assert_eq!(id.shard, ShardIdent::MASTERCHAIN);
+    assert_eq!(id.seqno, 0);
+    assert_eq!(&id.root_hash, cell.repr_hash());
+    assert_eq!(id.file_hash, Boc::compute_file_hash(BOC));
+
+    // Rebuild the same zerostate
+    let custom = original.load_custom().unwrap().unwrap();
+    let accounts = original
+        .load_accounts()
+        .unwrap()
+        .iter()
+        .map(|entry| entry.unwrap().1.load_account().unwrap().unwrap())
+        .collect::<Vec<_>>();
+
+    let builder = ZerostateBuilder::new(original.global_id, custom.config.clone())
+        .with_gen_utime(original.gen_utime);
+    let state = builder
+        .clone()
+        .with_accounts(accounts.clone())
+        .build()
+        .unwrap();
+    assert_eq!(state, original);
+    assert_eq!(state.compute_zerostate_id().unwrap(), id);
+
+    // Workchain zerostate
+    let wc_state = builder.build_workchain_zerostate(0).unwrap();
+    assert_eq!(wc_state.shard_ident, ShardIdent::BASECHAIN);
+    assert!(wc_state.custom.is_none());
+    assert!(wc_state.load_accounts().unwrap().is_empty());
+    let wc_id = wc_state.compute_zerostate_id().unwrap();
+    assert_eq!(wc_id.shard, ShardIdent::BASECHAIN);
+    assert!(builder.build_workchain_zerostate(123).is_err());
+    assert!(builder.build_workchain_zerostate(-1).is_err());
+
+    // Mandatory accounts
+    let elector = custom.config.get_elector_address().unwrap();
+    let without_elector = accounts.iter().filter(
+        |account| !matches!(&account.address, IntAddr::Std(addr) if addr.address == elector),
+    );
+    let err = builder
+        .clone()
+        .with_accounts(without_elector.cloned())
+        .build();
+    assert!(err.is_err());
+
+    // Duplicate accounts
+    let err = builder
+        .clone()
+        .with_accounts(accounts.clone())
+        .with_account(accounts[0].clone())
+        .build();
+    assert!(err.is_err());
+
+    // Mandatory config params
+    let mut builder = builder.with_accounts(accounts);
+    builder.config_mut().remove(34).unwrap();
+    assert!(builder.clone().build().is_err());
+
+    // Non-zerostate
+    let mut state = state;
+    state.seqno = 1;
+    assert!(state.compute_zerostate_id().is_err());
+}
@@ -0,0 +1,233 @@
+use crate::boc::Boc;
+use crate::cell::*;
+use crate::dict::Dict;
+use crate::error::*;
+
+use crate::models::account::{Account, OptionalAccount, ShardAccount};
+use crate::models::block::{BlockId, ShardHashes, ShardIdent};
+use crate::models::config::BlockchainConfig;
+use crate::models::currency::CurrencyCollection;
+use crate::models::message::IntAddr;
+use crate::models::Lazy;
+
+use super::{
+    DepthBalanceInfo, McStateExtra, OutMsgQueueInfo, ShardAccounts, ShardStateUnsplit,
+    ValidatorInfo,
+};
+
+impl ShardStateUnsplit {
+    /// Returns `true` if this is an initial state of the shard.
+    #[inline]
+    pub const fn is_zerostate(&self) -> bool {
+        self.seqno == 0
+    }
+
+    /// Serializes the zerostate and computes its id.
+    ///
+    /// Returns an error if the state is not a zerostate.
+    pub fn compute_zerostate_id(&self) -> Result<BlockId, Error> {
+        if !self.is_zerostate() {
+            return Err(Error::InvalidData);
+        }
+        let root = ok!(CellBuilder::build_from(self));
+        Ok(make_zerostate_id(self.shard_ident, &root))
+    }
+}
+
+/// Computes the id of the serialized zerostate.
+///
+/// The root hash is the hash of the root cell and the file hash
+/// is the hash of its BOC representation (as encoded by [`Boc::encode`]).
+pub fn compute_zerostate_id(root: &Cell) -> Result<BlockId, Error> {
+    let state = ok!(root.parse::<ShardStateUnsplit>());
+    if !state.is_zerostate() {
+        return Err(Error::InvalidData);
+    }
+    Ok(make_zerostate_id(state.shard_ident, root))
+}
+
+fn make_zerostate_id(shard: ShardIdent, root: &Cell) -> BlockId {
+    BlockId {
+        shard,
+        seqno: 0,
+        root_hash: *root.repr_hash(),
+        file_hash: Boc::encode_with_file_hash(root.as_ref()).1,
+    }
+}
+
+/// Network zerostate builder.
+///
+/// Builds the initial masterchain state with the specified config
+/// and accounts. The config must contain the current validator set
+/// and all mandatory params, and accounts must contain at least
+/// the config and elector contracts.
+#[derive(Debug, Clone)]
+pub struct ZerostateBuilder {
+    global_id: i32,
+    gen_utime: u32,
+    config: BlockchainConfig,
+    accounts: Vec<Account>,
+}
+
+impl ZerostateBuilder {
+    /// Creates a builder for the network with the specified config.
+    pub fn new(global_id: i32, config: BlockchainConfig) -> Self {
+        Self {
+            global_id,
+            gen_utime: 0,
+            config,
+            accounts: Vec::new(),
+        }
+    }
+
+    /// Sets the creation time of the zerostate.
+    pub fn with_gen_utime(mut self, gen_utime: u32) -> Self {
+        self.gen_utime = gen_utime;
+        self
+    }
+
+    /// Adds a masterchain account.
+    ///
+    /// NOTE: Account storage stats are used as is.
+    pub fn with_account(mut self, account: Account) -> Self {
+        self.accounts.push(account);
+        self
+    }
+
+    /// Adds all masterchain accounts from the iterator.
+    pub fn with_accounts<I>(mut self, accounts: I) -> Self
+    where
+        I: IntoIterator<Item = Account>,
+    {
+        self.accounts.extend(accounts);
+        self
+    }
+
+    /// Returns the network config.
+    pub fn config(&self) -> &BlockchainConfig {
+        &self.config
+    }
+
+    /// Returns the network config for modification
+    /// (e.g. to set workchain zerostate hashes).
+    pub fn config_mut(&mut self) -> &mut BlockchainConfig {
+        &mut self.config
+    }
+
+    /// Builds an empty zerostate for the specified workchain.
+    pub fn build_workchain_zerostate(&self, workchain: i32) -> Result<ShardStateUnsplit, Error> {
+        let shard_ident = match ShardIdent::new_full(workchain) {
+            shard_ident if !shard_ident.is_masterchain() => shard_ident,
+            _ => return Err(Error::InvalidData),
+        };
+        if ok!(self.config.get_workchain(workchain)).is_none() {
+            return Err(Error::InvalidData);
+        }
+
+        self.make_state(shard_ident, ShardAccounts::new(), 0, None)
+    }
+
+    /// Builds the masterchain zerostate.
+    pub fn build(self) -> Result<ShardStateUnsplit, Error> {
+        let config = &self.config;
+
+        // Check config params
+        for entry in ok!(config.get_mandatory_params()).keys() {
+            if !ok!(config.contains_raw(ok!(entry))) {
+                return Err(Error::InvalidData);
+            }
+        }
+        let validator_set = ok!(config.get_current_validator_set());
+        let catchain_config = ok!(config.get_catchain_config());
+
+        // Collect accounts
+        let mut accounts = ShardAccounts::new();
+        let mut gen_lt = 0;
+        for account in &self.accounts {
+            let address = match &account.address {
+                IntAddr::Std(addr) if addr.is_masterchain() => addr.address,
+                _ => return Err(Error::InvalidData),
+            };
+            gen_lt = std::cmp::max(gen_lt, account.last_trans_lt);
+
+            let info = DepthBalanceInfo {
+                split_depth: 0,
+                balance: account.balance.clone(),
+            };
+            let shard_account = ShardAccount {
+                account: ok!(Lazy::new(&OptionalAccount(Some(account.clone())))),
+                last_trans_hash: HashBytes::ZERO,
+                last_trans_lt: account.last_trans_lt,
+            };
+            if ok!(accounts.contains_account(address)) {
+                // Duplicate account
+                return Err(Error::InvalidData);
+            }
+            ok!(accounts.set(address, info, shard_account));
+        }
+
+        // Check mandatory accounts
+        for address in [config.address, ok!(config.get_elector_address())] {
+            if !ok!(accounts.contains_account(address)) {
+                return Err(Error::InvalidData);
+            }
+        }
+
+        let Some((_, validator_list_hash_short)) =
+            validator_set.compute_subset(ShardIdent::MASTERCHAIN, &catchain_config, 0)
+        else {
+            return Err(Error::InvalidData);
+        };
+
+        let custom = McStateExtra {
+            shards: ok!(ShardHashes::from_shards(std::iter::empty())),
+            config: self.config.clone(),
+            validator_info: ValidatorInfo {
+                validator_list_hash_short,
+                catchain_seqno: 0,
+                nx_cc_updated: true,
+            },
+            prev_blocks: Default::default(),
+            after_key_block: true,
+            last_key_block: None,
+            block_create_stats: None,
+            global_balance: accounts.root_extra().balance.clone(),
+            copyleft_rewards: Dict::new(),
+        };
+
+        self.make_state(ShardIdent::MASTERCHAIN, accounts, gen_lt, Some(custom))
+    }
+
+    fn make_state(
+        &self,
+        shard_ident: ShardIdent,
+        accounts: ShardAccounts,
+        gen_lt: u64,
+        custom: Option<McStateExtra>,
+    ) -> Result<ShardStateUnsplit, Error> {
+        Ok(ShardStateUnsplit {
+            global_id: self.global_id,
+            shard_ident,
+            seqno: 0,
+            vert_seqno: 0,
+            gen_utime: self.gen_utime,
+            gen_utime_ms: None,
+            gen_lt,
+            min_ref_mc_seqno: u32::MAX,
+            out_msg_queue_info: ok!(CellBuilder::build_from(OutMsgQueueInfo::default())),
+            before_split: false,
+            total_balance: accounts.root_extra().balance.clone(),
+            accounts: ok!(Lazy::new(&accounts)),
+            overload_history: 0,
+            underload_history: 0,
+            total_validator_fees: CurrencyCollection::ZERO,
+            libraries: Dict::new(),
+            master_ref: None,
+            custom: match custom {
+                Some(custom) => Some(ok!(Lazy::new(&custom))),
+                None => None,
+            },
+            shard_block_refs: None,
+        })
+    }
+}
@@ -0,0 +1,87 @@
+//! Logical time and unix time helpers.
+
+/// Alignment of the block logical time.
+///
+/// Collators start each block from the next multiple of this value
+/// after the logical time of the previous state.
+pub const LT_ALIGN: u64 = 1_000_000;
+
+/// Max allowed difference (in seconds) between the block generation
+/// time and the local time of the node which validates it.
+pub const MAX_GEN_UTIME_DRIFT: u32 = 15;
+
+/// Returns the smallest multiple of [`LT_ALIGN`] which is strictly
+/// greater than the specified logical time.
+///
+/// NOTE: Returns [`u64::MAX`] in case of overflow.
+pub const fn lt_align(lt: u64) -> u64 {
+    match (lt / LT_ALIGN + 1).checked_mul(LT_ALIGN) {
+        Some(lt) => lt,
+        None => u64::MAX,
+    }
+}
+
+/// Returns `true` if the logical time is a multiple of [`LT_ALIGN`].
+pub const fn is_lt_aligned(lt: u64) -> bool {
+    lt % LT_ALIGN == 0
+}
+
+/// Computes the start logical time of the next block from the logical
+/// times of the previous state and the referenced masterchain state.
+pub const fn next_block_start_lt(prev_lt: u64, mc_lt: u64) -> u64 {
+    lt_align(if prev_lt > mc_lt { prev_lt } else { mc_lt })
+}
+
+/// Returns `true` if the logical time is within the block
+/// logical time window (`start_lt..end_lt`).
+pub const fn is_lt_in_block(lt: u64, start_lt: u64, end_lt: u64) -> bool {
+    start_lt <= lt && lt < end_lt
+}
+
+/// Returns the absolute difference between two unix timestamps.
+pub const fn utime_drift(a: u32, b: u32) -> u32 {
+    a.abs_diff(b)
+}
+
+/// Returns `true` if the creation time differs from the reference time
+/// by no more than `max_drift` seconds.
+pub const fn check_utime_drift(created_at: u32, now: u32, max_drift: u32) -> bool {
+    utime_drift(created_at, now) <= max_drift
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lt_helpers() {
+        assert_eq!(lt_align(0), LT_ALIGN);
+        assert_eq!(lt_align(1), LT_ALIGN);
+        assert_eq!(lt_align(LT_ALIGN - 1), LT_ALIGN);
+        assert_eq!(lt_align(LT_ALIGN), 2 * LT_ALIGN);
+        assert_eq!(lt_align(u64::MAX), u64::MAX);
+        assert!(is_lt_aligned(lt_align(123456789)));
+        assert!(!is_lt_aligned(123456789));
+
+        assert_eq!(next_block_start_lt(1_500_000, 2_000_001), 3_000_000);
+        assert_eq!(next_block_start_lt(2_999_999, 1_000_000), 3_000_000);
+
+        assert!(is_lt_in_block(10, 10, 20));
+        assert!(is_lt_in_block(19, 10, 20));
+        assert!(!is_lt_in_block(20, 10, 20));
+        assert!(!is_lt_in_block(9, 10, 20));
+        assert!(!is_lt_in_block(10, 10, 10));
+    }
+
+    #[test]
+    fn utime_helpers() {
+        assert_eq!(utime_drift(100, 90), 10);
+        assert_eq!(utime_drift(90, 100), 10);
+        assert_eq!(utime_drift(0, u32::MAX), u32::MAX);
+
+        assert!(check_utime_drift(100, 100, 0));
+        assert!(check_utime_drift(115, 100, MAX_GEN_UTIME_DRIFT));
+        assert!(check_utime_drift(85, 100, MAX_GEN_UTIME_DRIFT));
+        assert!(!check_utime_drift(116, 100, MAX_GEN_UTIME_DRIFT));
+    }
+}
@@ -11,8 +11,10 @@ use crate::models::message::Message;
 use crate::models::Lazy;
 
 pub use self::phases::*;
+pub use self::tree::*;
 
 mod phases;
+mod tree;
 
 #[cfg(test)]
 mod tests;
@@ -208,7 +210,37 @@ impl<'a> Iterator for TxOutMsgIter<'a> {
 }
 
 impl Transaction {
-    const TAG: u8 = 0b0111;
+    pub(crate) const TAG: u8 = 0b0111;
+}
+
+/// Formats a short transaction summary with its account, lt and status change.
+///
+/// Alternate mode (`{:#}`) also prints the execution time, the previous
+/// transaction and the incoming message hash.
+impl std::fmt::Display for Transaction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        ok!(f.write_fmt(format_args!(
+            "tx {}:{}, {:?} -> {:?}, out_msgs {}, fees {}",
+            self.account,
+            self.lt,
+            self.orig_status,
+            self.end_status,
+            self.out_msg_count,
+            self.total_fees.tokens,
+        )));
+
+        if f.alternate() {
+            ok!(f.write_fmt(format_args!(
+                ", now {}, prev {}:{}, in_msg ",
+                self.now, self.prev_trans_lt, self.prev_trans_hash
+            )));
+            ok!(match &self.in_msg {
+                Some(in_msg) => std::fmt::Display::fmt(in_msg.repr_hash(), f),
+                None => f.write_str("none"),
+            });
+        }
+        Ok(())
+    }
 }
 
 impl Store for Transaction {
@@ -17,6 +17,10 @@ fn check_tx(boc: &[u8]) -> Cell {
 
     println!("tx: {tx:#?}");
 
+    let summary = tx.to_string();
+    assert!(summary.starts_with(&format!("tx {}:{}, ", tx.account, tx.lt)));
+    assert!(format!("{tx:#}").starts_with(&summary));
+
     let in_msg = tx.load_in_msg().unwrap();
     println!("In message: {in_msg:?}");
 
@@ -86,3 +90,98 @@ fn tick_tx() {
 fn tock_tx() {
     check_tx(include_bytes!("tock_tx.boc"));
 }
+
+#[test]
+fn tx_tree() {
+    let parent = Boc::decode(include_bytes!("ordinary_tx_with_outgoing.boc")).unwrap();
+    let template = Boc::decode(include_bytes!("ordinary_tx_without_outgoing.boc"))
+        .unwrap()
+        .parse::<Transaction>()
+        .unwrap();
+    let unrelated = Boc::decode(include_bytes!("tick_tx.boc")).unwrap();
+
+    let parent_tx = parent.parse::<Transaction>().unwrap();
+    let out_msgs = parent_tx
+        .out_msgs
+        .values()
+        .map(|cell| cell.unwrap())
+        .collect::<Vec<_>>();
+    assert!(!out_msgs.is_empty());
+
+    // Process only the first outgoing message
+    let child = CellBuilder::build_from(Transaction {
+        lt: parent_tx.lt + 10,
+        in_msg: Some(out_msgs[0].clone()),
+        ..template
+    })
+    .unwrap();
+
+    let mut builder = TxTree::builder();
+    builder
+        .add_transactions([child.clone(), unrelated.clone(), parent.clone()])
+        .unwrap();
+    assert!(!builder.add_transaction(parent.clone()).unwrap());
+
+    let tree = builder.build();
+    assert_eq!(tree.len(), 3);
+    assert_eq!(tree.pending_messages().len(), out_msgs.len() - 1);
+
+    let roots = tree.roots().map(|item| *item.hash()).collect::<Vec<_>>();
+    assert_eq!(roots.len(), 2);
+    assert!(roots.contains(parent.repr_hash()));
+    assert!(roots.contains(unrelated.repr_hash()));
+
+    let child_item = tree.get(child.repr_hash()).unwrap();
+    assert_eq!(child_item.depth().unwrap(), 1);
+    assert_eq!(child_item.parent().unwrap().hash(), parent.repr_hash());
+    assert_eq!(child_item.root().unwrap().hash(), parent.repr_hash());
+    assert_eq!(child_item.transaction().lt, parent_tx.lt + 10);
+
+    let parent_item = tree.get(parent.repr_hash()).unwrap();
+    assert_eq!(parent_item.depth().unwrap(), 0);
+    assert_eq!(parent_item.cell(), &parent);
+    let subtree = parent_item
+        .iter_subtree()
+        .map(|item| *item.unwrap().hash())
+        .collect::<Vec<_>>();
+    assert_eq!(subtree, [*parent.repr_hash(), *child.repr_hash()]);
+
+    let unrelated_item = tree.get(unrelated.repr_hash()).unwrap();
+    assert_eq!(unrelated_item.children().count(), 0);
+    assert!(tree.get(&HashBytes::ZERO).is_none());
+}
+
+#[test]
+fn tx_tree_cycle() {
+    let parent = Boc::decode(include_bytes!("ordinary_tx_with_outgoing.boc"))
+        .unwrap()
+        .parse::<Transaction>()
+        .unwrap();
+    let msg = parent.out_msgs.values().next().unwrap().unwrap();
+
+    // Transaction which processes its own outgoing message
+    let mut out_msgs = Dict::new();
+    out_msgs.set(Uint15::new(0), msg.clone()).unwrap();
+    let tx = CellBuilder::build_from(Transaction {
+        in_msg: Some(msg),
+        out_msg_count: Uint15::new(1),
+        out_msgs,
+        ..parent
+    })
+    .unwrap();
+
+    let mut builder = TxTree::builder();
+    builder.add_transaction(tx.clone()).unwrap();
+    let tree = builder.build();
+    assert_eq!(tree.roots().count(), 0);
+
+    let item = tree.get(tx.repr_hash()).unwrap();
+    assert_eq!(item.parent().unwrap().hash(), tx.repr_hash());
+    assert!(item.root().is_err());
+    assert!(item.depth().is_err());
+
+    let subtree = item.iter_subtree().collect::<Vec<_>>();
+    assert_eq!(subtree.len(), 2);
+    assert!(subtree[0].is_ok());
+    assert!(subtree[1].is_err());
+}
@@ -0,0 +1,294 @@
+use crate::cell::*;
+use crate::error::Error;
+use crate::models::message::{MessageSummary, MsgKind};
+
+use super::Transaction;
+
+/// A builder for the [`TxTree`].
+///
+/// Collects transactions (e.g. from one or several blocks) and links
+/// them by the hashes of their incoming and outgoing messages.
+#[derive(Default)]
+pub struct TxTreeBuilder {
+    nodes: Vec<TxTreeNode>,
+    by_hash: ahash::HashMap<HashBytes, usize>,
+}
+
+impl TxTreeBuilder {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a serialized transaction.
+    ///
+    /// Returns `false` if the transaction was already added.
+    pub fn add_transaction(&mut self, cell: Cell) -> Result<bool, Error> {
+        let hash = *cell.repr_hash();
+        if self.by_hash.contains_key(&hash) {
+            return Ok(false);
+        }
+
+        let transaction = ok!(cell.parse::<Transaction>());
+
+        let in_msg = transaction.in_msg.as_ref().map(|cell| *cell.repr_hash());
+
+        let mut out_msgs = Vec::with_capacity(transaction.out_msg_count.into_inner() as usize);
+        for entry in transaction.out_msgs.values() {
+            let msg = ok!(entry);
+            // Only internal messages can produce new transactions
+            if ok!(MessageSummary::from_cell(msg.as_ref())).kind == MsgKind::Int {
+                out_msgs.push(*msg.repr_hash());
+            }
+        }
+
+        self.by_hash.insert(hash, self.nodes.len());
+        self.nodes.push(TxTreeNode {
+            hash,
+            cell,
+            transaction,
+            in_msg,
+            out_msgs,
+            parent: None,
+            children: Vec::new(),
+        });
+        Ok(true)
+    }
+
+    /// Adds all serialized transactions from the iterator.
+    pub fn add_transactions<I>(&mut self, cells: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = Cell>,
+    {
+        for cell in cells {
+            ok!(self.add_transaction(cell));
+        }
+        Ok(())
+    }
+
+    /// Links all added transactions into traces.
+    pub fn build(self) -> TxTree {
+        let mut nodes = self.nodes;
+
+        // Index all incoming messages
+        let mut by_in_msg =
+            ahash::HashMap::with_capacity_and_hasher(nodes.len(), Default::default());
+        for (index, node) in nodes.iter().enumerate() {
+            if let Some(in_msg) = node.in_msg {
+                by_in_msg.insert(in_msg, index);
+            }
+        }
+
+        // Link transactions through their outgoing messages
+        let mut pending_messages = Vec::new();
+        for index in 0..nodes.len() {
+            let mut children = Vec::new();
+            for msg_hash in &nodes[index].out_msgs {
+                match by_in_msg.get(msg_hash) {
+                    Some(&child) => children.push(child),
+                    None => pending_messages.push(*msg_hash),
+                }
+            }
+
+            for child in &children {
+                nodes[*child].parent = Some(index);
+            }
+            nodes[index].children = children;
+        }
+
+        let mut roots = nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.parent.is_none())
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+        roots.sort_by_key(|index| (nodes[*index].transaction.lt, nodes[*index].hash));
+
+        TxTree {
+            nodes,
+            roots,
+            by_hash: self.by_hash,
+            pending_messages,
+        }
+    }
+}
+
+/// Transactions linked into traces by their messages.
+///
+/// Each transaction is a child of the transaction which produced
+/// its incoming message. Transactions without a known parent are roots.
+pub struct TxTree {
+    nodes: Vec<TxTreeNode>,
+    roots: Vec<usize>,
+    by_hash: ahash::HashMap<HashBytes, usize>,
+    pending_messages: Vec<HashBytes>,
+}
+
+impl TxTree {
+    /// Returns a builder for the transaction tree.
+    pub fn builder() -> TxTreeBuilder {
+        TxTreeBuilder::new()
+    }
+
+    /// Returns the number of transactions in the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns `true` if the tree contains no transactions.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Returns trace roots ordered by their logical time.
+    pub fn roots(&self) -> impl Iterator<Item = TxTreeItem<'_>> {
+        self.roots.iter().map(|index| self.item(*index))
+    }
+
+    /// Returns a transaction by its hash.
+    pub fn get(&self, hash: &HashBytes) -> Option<TxTreeItem<'_>> {
+        let index = *self.by_hash.get(hash)?;
+        Some(self.item(index))
+    }
+
+    /// Returns hashes of internal outgoing messages which
+    /// were not processed by any transaction in the tree.
+    pub fn pending_messages(&self) -> &[HashBytes] {
+        &self.pending_messages
+    }
+
+    fn item(&self, index: usize) -> TxTreeItem<'_> {
+        TxTreeItem { tree: self, index }
+    }
+}
+
+/// Transaction in the [`TxTree`].
+#[derive(Clone, Copy)]
+pub struct TxTreeItem<'a> {
+    tree: &'a TxTree,
+    index: usize,
+}
+
+impl<'a> TxTreeItem<'a> {
+    /// Returns the hash of the transaction.
+    pub fn hash(&self) -> &'a HashBytes {
+        &self.node().hash
+    }
+
+    /// Returns the root cell of the transaction.
+    pub fn cell(&self) -> &'a Cell {
+        &self.node().cell
+    }
+
+    /// Returns the parsed transaction.
+    pub fn transaction(&self) -> &'a Transaction {
+        &self.node().transaction
+    }
+
+    /// Returns the transaction which produced the incoming message.
+    pub fn parent(&self) -> Option<TxTreeItem<'a>> {
+        let index = self.node().parent?;
+        Some(self.tree.item(index))
+    }
+
+    /// Returns transactions which processed outgoing messages,
+    /// in the order of outgoing messages.
+    pub fn children(&self) -> impl Iterator<Item = TxTreeItem<'a>> {
+        let tree = self.tree;
+        self.node().children.iter().map(|index| tree.item(*index))
+    }
+
+    /// Returns the root of the trace.
+    ///
+    /// Returns an error if the transaction is a part of a cycle.
+    pub fn root(&self) -> Result<TxTreeItem<'a>, Error> {
+        let mut item = *self;
+        for _ in 0..self.tree.len() {
+            match item.parent() {
+                Some(parent) => item = parent,
+                None => return Ok(item),
+            }
+        }
+        Err(Error::InvalidData)
+    }
+
+    /// Returns the number of ancestors of the transaction.
+    ///
+    /// Returns an error if the transaction is a part of a cycle.
+    pub fn depth(&self) -> Result<usize, Error> {
+        let mut item = *self;
+        for depth in 0..self.tree.len() {
+            match item.parent() {
+                Some(parent) => item = parent,
+                None => return Ok(depth),
+            }
+        }
+        Err(Error::InvalidData)
+    }
+
+    /// Returns an iterator over this transaction and all its
+    /// descendants in depth-first order.
+    ///
+    /// The iterator yields an error if the subtree contains a cycle.
+    pub fn iter_subtree(&self) -> TxSubtreeIter<'a> {
+        TxSubtreeIter {
+            tree: self.tree,
+            stack: vec![self.index],
+            remaining: self.tree.len(),
+        }
+    }
+
+    fn node(&self) -> &'a TxTreeNode {
+        &self.tree.nodes[self.index]
+    }
+}
+
+impl std::fmt::Debug for TxTreeItem<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TxTreeItem")
+            .field("hash", self.hash())
+            .field("account", &self.transaction().account)
+            .field("lt", &self.transaction().lt)
+            .finish()
+    }
+}
+
+/// An iterator over a transaction subtree in depth-first order.
+///
+/// This struct is created by the [`iter_subtree`] method on [`TxTreeItem`].
+///
+/// [`iter_subtree`]: TxTreeItem::iter_subtree
+pub struct TxSubtreeIter<'a> {
+    tree: &'a TxTree,
+    stack: Vec<usize>,
+    remaining: usize,
+}
+
+impl<'a> Iterator for TxSubtreeIter<'a> {
+    type Item = Result<TxTreeItem<'a>, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.stack.pop()?;
+
+        // A tree can't have more items than transactions
+        if self.remaining == 0 {
+            self.stack.clear();
+            return Some(Err(Error::InvalidData));
+        }
+        self.remaining -= 1;
+
+        let children = &self.tree.nodes[index].children;
+        self.stack.extend(children.iter().rev());
+        Some(Ok(self.tree.item(index)))
+    }
+}
+
+struct TxTreeNode {
+    hash: HashBytes,
+    cell: Cell,
+    transaction: Transaction,
+    in_msg: Option<HashBytes>,
+    out_msgs: Vec<HashBytes>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+}
@@ -0,0 +1,309 @@
+//! Structural block validation.
+
+use crate::boc::Boc;
+use crate::cell::*;
+use crate::error::Error;
+use crate::models::block::{AccountBlock, Block, BlockInfo, ValueFlow};
+use crate::models::config::BlockchainConfig;
+
+/// Error type for block validation.
+#[derive(Debug, Clone, Eq, PartialEq, thiserror::Error)]
+pub enum BlockValidationError {
+    /// Failed to parse some part of the block.
+    #[error("invalid {0}")]
+    InvalidStructure(&'static str, #[source] Error),
+    /// Failed to get some param from the blockchain config.
+    #[error("invalid config param {0}")]
+    InvalidConfig(u32, #[source] Error),
+    /// Block workchain is not described in the blockchain config.
+    #[error("unknown workchain {0}")]
+    UnknownWorkchain(i32),
+    /// Block logical time range is empty.
+    #[error("invalid lt range {start_lt}..{end_lt}")]
+    InvalidLtRange {
+        /// Logical time range start.
+        start_lt: u64,
+        /// Logical time range end.
+        end_lt: u64,
+    },
+    /// Block logical time range exceeds the limit from config.
+    #[error("lt delta {delta} exceeds the limit {limit}")]
+    LtDeltaTooBig {
+        /// Logical time delta.
+        delta: u64,
+        /// Hard limit from config.
+        limit: u32,
+    },
+    /// Serialized block exceeds the limit from config.
+    #[error("block size {size} exceeds the limit {limit}")]
+    BlockTooBig {
+        /// Size of the serialized block in bytes.
+        size: usize,
+        /// Hard limit from config.
+        limit: u32,
+    },
+    /// Value flow inputs don't match its outputs.
+    #[error("unbalanced value flow")]
+    UnbalancedValueFlow,
+    /// Account block is outside of the block shard or doesn't match its key.
+    #[error("invalid account block {0}")]
+    InvalidAccountBlock(HashBytes),
+    /// Transaction doesn't match the account block or the block info.
+    #[error("invalid transaction {lt} of account {account}")]
+    InvalidTransaction {
+        /// Account id.
+        account: HashBytes,
+        /// Transaction logical time.
+        lt: u64,
+    },
+}
+
+/// Performs structural checks of the block:
+///
+/// - all block parts are parsed with valid tags;
+/// - logical time range is not empty and is within the config limits;
+/// - serialized block size is within the config limits;
+/// - value flow is balanced;
+/// - all accounts belong to the block shard;
+/// - all transactions belong to their accounts and the block logical time range,
+///   and were executed at the block creation time.
+///
+/// NOTE: This is not a full block validation. Signatures, messages and
+/// the state update are not checked against the previous state.
+pub fn validate_block(
+    block: &Block,
+    config: &BlockchainConfig,
+) -> Result<(), BlockValidationError> {
+    use BlockValidationError as E;
+
+    let info = ok!(block
+        .load_info()
+        .map_err(|e| E::InvalidStructure("block info", e)));
+    let value_flow = ok!(block
+        .load_value_flow()
+        .map_err(|e| E::InvalidStructure("value flow", e)));
+    ok!(block
+        .load_state_update()
+        .map_err(|e| E::InvalidStructure("state update", e)));
+    let extra = ok!(block
+        .load_extra()
+        .map_err(|e| E::InvalidStructure("block extra", e)));
+    ok!(extra
+        .load_custom()
+        .map_err(|e| E::InvalidStructure("masterchain block extra", e)));
+    ok!(info
+        .load_prev_ref()
+        .map_err(|e| E::InvalidStructure("previous block reference", e)));
+
+    // Check shard
+    let is_masterchain = info.shard.is_masterchain();
+    if !is_masterchain {
+        let workchain = info.shard.workchain();
+        match config.get_workchain(workchain) {
+            Ok(Some(_)) => {}
+            Ok(None) => return Err(E::UnknownWorkchain(workchain)),
+            Err(e) => return Err(E::InvalidConfig(12, e)),
+        }
+    }
+
+    // Check limits
+    let limits = match config.get_block_limits(is_masterchain) {
+        Ok(limits) => limits,
+        Err(e) => return Err(E::InvalidConfig(if is_masterchain { 22 } else { 23 }, e)),
+    };
+
+    if info.start_lt >= info.end_lt {
+        return Err(E::InvalidLtRange {
+            start_lt: info.start_lt,
+            end_lt: info.end_lt,
+        });
+    }
+
+    let delta = info.end_lt - info.start_lt;
+    if delta > limits.lt_delta.hard_limit as u64 {
+        return Err(E::LtDeltaTooBig {
+            delta,
+            limit: limits.lt_delta.hard_limit,
+        });
+    }
+
+    let size = match CellBuilder::build_from(block) {
+        Ok(cell) => Boc::encode(cell.as_ref()).len(),
+        Err(e) => return Err(E::InvalidStructure("block", e)),
+    };
+    if size > limits.bytes.hard_limit as usize {
+        return Err(E::BlockTooBig {
+            size,
+            limit: limits.bytes.hard_limit,
+        });
+    }
+
+    // Check value flow
+    match is_value_flow_balanced(&value_flow) {
+        Ok(true) => {}
+        Ok(false) => return Err(E::UnbalancedValueFlow),
+        Err(e) => return Err(E::InvalidStructure("value flow", e)),
+    }
+
+    // Check account blocks
+    let account_blocks = ok!(extra
+        .account_blocks
+        .load()
+        .map_err(|e| E::InvalidStructure("account blocks", e)));
+    for entry in account_blocks.iter() {
+        let (account, _, account_block) =
+            ok!(entry.map_err(|e| E::InvalidStructure("account blocks", e)));
+        if account_block.account != account || !info.shard.contains_account(&account) {
+            return Err(E::InvalidAccountBlock(account));
+        }
+        ok!(validate_transactions(&info, &account, &account_block));
+    }
+
+    Ok(())
+}
+
+fn validate_transactions(
+    info: &BlockInfo,
+    account: &HashBytes,
+    account_block: &AccountBlock,
+) -> Result<(), BlockValidationError> {
+    use BlockValidationError as E;
+
+    let mut prev_lt = None;
+    for entry in account_block.transactions.iter() {
+        let (lt, _, tx) = ok!(entry.map_err(|e| E::InvalidStructure("transactions", e)));
+        let tx = ok!(tx.load().map_err(|e| E::InvalidStructure("transaction", e)));
+
+        let is_valid = tx.lt == lt
+            && tx.account == *account
+            && tx.now == info.gen_utime
+            && tx.prev_trans_lt < lt
+            && info.contains_lt(lt)
+            && !matches!(prev_lt, Some(prev_lt) if tx.prev_trans_lt < prev_lt);
+        if !is_valid {
+            return Err(E::InvalidTransaction {
+                account: *account,
+                lt,
+            });
+        }
+        prev_lt = Some(lt);
+    }
+
+    Ok(())
+}
+
+/// Checks that `from_prev_block + imported + fees_imported + created + minted + recovered`
+/// equals to `to_next_block + exported + fees_collected`.
+fn is_value_flow_balanced(value_flow: &ValueFlow) -> Result<bool, Error> {
+    let mut input = value_flow.from_prev_block.clone();
+    for item in [
+        &value_flow.imported,
+        &value_flow.fees_imported,
+        &value_flow.created,
+        &value_flow.minted,
+        &value_flow.recovered,
+    ] {
+        input = ok!(input.checked_add(item));
+    }
+
+    let mut output = value_flow.to_next_block.clone();
+    for item in [&value_flow.exported, &value_flow.fees_collected] {
+        output = ok!(output.checked_add(item));
+    }
+
+    Ok(input == output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{Lazy, ShardIdent};
+
+    fn parse_block(boc: &[u8]) -> Block {
+        Boc::decode(boc).unwrap().parse::<Block>().unwrap()
+    }
+
+    #[test]
+    fn validate_blocks() {
+        let key_block = parse_block(include_bytes!("block/tests/mc_key_block.boc"));
+        let config = key_block
+            .load_extra()
+            .unwrap()
+            .load_custom()
+            .unwrap()
+            .unwrap()
+            .config
+            .unwrap();
+
+        for boc in [
+            &include_bytes!("block/tests/mc_key_block.boc")[..],
+            include_bytes!("block/tests/mc_simple_block.boc"),
+            include_bytes!("block/tests/mc_block_with_shards.boc"),
+            include_bytes!("block/tests/empty_shard_block.boc"),
+            include_bytes!("block/tests/simple_shard_block.boc"),
+        ] {
+            validate_block(&parse_block(boc), &config).unwrap();
+        }
+
+        let block = parse_block(include_bytes!("block/tests/simple_shard_block.boc"));
+        let with_info = |f: &dyn Fn(&mut BlockInfo)| {
+            let mut info = block.load_info().unwrap();
+            f(&mut info);
+            Block {
+                info: Lazy::new(&info).unwrap(),
+                ..block.clone()
+            }
+        };
+
+        // Invalid lt range
+        let invalid = with_info(&|info| info.end_lt = info.start_lt);
+        assert!(matches!(
+            validate_block(&invalid, &config),
+            Err(BlockValidationError::InvalidLtRange { .. })
+        ));
+
+        let invalid = with_info(&|info| info.end_lt = info.start_lt + 100_000_000);
+        assert!(matches!(
+            validate_block(&invalid, &config),
+            Err(BlockValidationError::LtDeltaTooBig { .. })
+        ));
+
+        // Transactions must be executed at the block creation time
+        let invalid = with_info(&|info| info.gen_utime += 1);
+        assert!(matches!(
+            validate_block(&invalid, &config),
+            Err(BlockValidationError::InvalidTransaction { .. })
+        ));
+
+        // Unknown workchain
+        let invalid = with_info(&|info| info.shard = ShardIdent::new_full(123));
+        assert_eq!(
+            validate_block(&invalid, &config),
+            Err(BlockValidationError::UnknownWorkchain(123))
+        );
+
+        // Unbalanced value flow
+        let mut value_flow = block.load_value_flow().unwrap();
+        value_flow.to_next_block.tokens += 1;
+        let invalid = Block {
+            value_flow: Lazy::new(&value_flow).unwrap(),
+            ..block.clone()
+        };
+        assert_eq!(
+            validate_block(&invalid, &config),
+            Err(BlockValidationError::UnbalancedValueFlow)
+        );
+
+        // Size limits
+        let mut limits = config.get_block_limits(false).unwrap();
+        limits.bytes.underload = 100;
+        limits.bytes.soft_limit = 100;
+        limits.bytes.hard_limit = 100;
+        let mut config = config;
+        config.set_block_limits(false, &limits).unwrap();
+        assert!(matches!(
+            validate_block(&block, &config),
+            Err(BlockValidationError::BlockTooBig { .. })
+        ));
+    }
+}
@@ -54,6 +54,9 @@ bitflags! {
         /// Any errors arising while processing this message during
         /// the action phase should be ignored.
         const IGNORE_ERROR = 2;
+        /// Bounce the transaction if any errors arise while processing
+        /// this message during the action phase.
+        const BOUNCE_ON_ERROR = 16;
         /// The current account must be destroyed if its resulting balance is zero.
         const DELETE_IF_EMPTY = 32;
         /// Message will carry all the remaining value of the inbound message
@@ -66,6 +69,20 @@ bitflags! {
     }
 }
 
+impl SendMsgFlags {
+    /// Returns `true` if the mode contains only known flags
+    /// and doesn't combine mutually exclusive ones.
+    ///
+    /// NOTE: Invalid modes are still parsed as is, but such
+    /// actions fail during the action phase.
+    pub const fn is_valid(&self) -> bool {
+        let bits = self.bits();
+        bits & !Self::all().bits() == 0
+            && bits & (Self::WITH_REMAINING_BALANCE.bits() | Self::ALL_BALANCE.bits())
+                != Self::WITH_REMAINING_BALANCE.bits() | Self::ALL_BALANCE.bits()
+    }
+}
+
 impl Store for SendMsgFlags {
     fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
         builder.store_u8(self.bits())
@@ -135,6 +152,7 @@ impl TryFrom<u8> for ChangeLibraryMode {
 }
 
 /// Library reference.
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum LibRef {
     /// Hash of the root cell of the library code.
     Hash(HashBytes),
@@ -143,6 +161,7 @@ pub enum LibRef {
 }
 
 /// Output action.
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub enum OutAction {
     /// Sends a raw message contained in cell.
     SendMsg {
@@ -187,6 +206,35 @@ impl OutAction {
     const TAG_RESERVE: u32 = 0x36e6b809;
     const TAG_CHANGE_LIB: u32 = 0x26fa1dd4;
     const TAG_COPYLEFT: u32 = 0x24486f7a;
+
+    /// Parses the output actions list (e.g. contents of the `c5` register).
+    ///
+    /// Returns actions in the order in which they were added.
+    pub fn parse_list(root: &Cell) -> Result<Vec<Self>, Error> {
+        let mut actions = Vec::new();
+        for action in OutActionsRevIter::new(ok!(root.as_slice())) {
+            actions.push(ok!(action));
+        }
+        actions.reverse();
+        Ok(actions)
+    }
+
+    /// Builds the output actions list from the actions in the order
+    /// in which they should be executed.
+    pub fn build_list<'a, I>(actions: I) -> Result<Cell, Error>
+    where
+        I: IntoIterator<Item = &'a Self>,
+    {
+        let context = &mut Cell::empty_context();
+        let mut list = Cell::empty_cell();
+        for action in actions {
+            let mut builder = CellBuilder::new();
+            ok!(builder.store_reference(list));
+            ok!(action.store_into(&mut builder, context));
+            list = ok!(builder.build_ext(context));
+        }
+        Ok(list)
+    }
 }
 
 impl Store for OutAction {
@@ -267,3 +315,70 @@ impl<'a> Load<'a> for OutAction {
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn out_actions_list() {
+        let out_msg = Lazy::from_raw(CellBuilder::build_from(0xdeafbeafu32).unwrap());
+
+        let actions = vec![
+            OutAction::ReserveCurrency {
+                mode: ReserveCurrencyFlags::ALL_BUT,
+                value: CurrencyCollection::new(100),
+            },
+            OutAction::SendMsg {
+                mode: SendMsgFlags::PAY_FEE_SEPARATELY | SendMsgFlags::IGNORE_ERROR,
+                out_msg,
+            },
+            OutAction::SetCode {
+                new_code: Cell::empty_cell(),
+            },
+            OutAction::ChangeLibrary {
+                mode: ChangeLibraryMode::AddPublic,
+                lib: LibRef::Hash(HashBytes([0x22; 32])),
+            },
+            OutAction::ChangeLibrary {
+                mode: ChangeLibraryMode::Remove,
+                lib: LibRef::Cell(Cell::empty_cell()),
+            },
+        ];
+
+        let list = OutAction::build_list(&actions).unwrap();
+        assert_eq!(OutAction::parse_list(&list).unwrap(), actions);
+
+        // Last action is at the head of the list
+        let mut iter = OutActionsRevIter::new(list.as_slice().unwrap());
+        assert_eq!(iter.next().unwrap().unwrap(), actions[4]);
+
+        // Invalid modes are parsed as is
+        let actions = [OutAction::SendMsg {
+            mode: SendMsgFlags::from_bits_retain(0xc4),
+            out_msg: Lazy::from_raw(Cell::empty_cell()),
+        }];
+        let list = OutAction::build_list(&actions).unwrap();
+        assert_eq!(OutAction::parse_list(&list).unwrap(), actions);
+
+        let empty = OutAction::build_list(&[]).unwrap();
+        assert_eq!(empty, Cell::empty_cell());
+        assert!(OutAction::parse_list(&empty).unwrap().is_empty());
+    }
+
+    #[test]
+    fn send_msg_flags() {
+        for mode in [0, 1, 2, 3, 16, 32, 64, 128, 64 | 2 | 1, 128 | 32 | 16 | 2] {
+            assert!(
+                SendMsgFlags::from_bits_retain(mode).is_valid(),
+                "mode: {mode}"
+            );
+        }
+        for mode in [4, 8, 64 | 128, 128 | 64 | 2, 0xff] {
+            assert!(
+                !SendMsgFlags::from_bits_retain(mode).is_valid(),
+                "mode: {mode}"
+            );
+        }
+    }
+}
@@ -52,6 +52,16 @@ macro_rules! impl_serde {
     };
 }
 
+macro_rules! impl_display {
+    ($ident:ident) => {
+        impl std::fmt::Display for $ident {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+    };
+}
+
 macro_rules! impl_ops {
     ($ident:ident, $inner:ty) => {
         impl From<$ident> for $inner {
@@ -107,12 +117,6 @@ macro_rules! impl_ops {
             }
         }
 
-        impl std::fmt::Display for $ident {
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                self.0.fmt(f)
-            }
-        }
-
         impl std::fmt::Binary for $ident {
             fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                 std::fmt::Binary::fmt(&self.0, f)
@@ -418,6 +422,33 @@ macro_rules! impl_var_uints {
                     _ => None,
                 }
             }
+
+            /// Saturating integer addition. Computes `self + rhs`,
+            /// saturating at the numeric bounds instead of overflowing.
+            #[inline]
+            pub const fn saturating_add(self, rhs: Self) -> Self {
+                match self.0.checked_add(rhs.0) {
+                    Some(value) if value <= Self::MAX.0 => $ident(value),
+                    _ => Self::MAX,
+                }
+            }
+
+            /// Saturating integer subtraction. Computes `self - rhs`,
+            /// saturating at the numeric bounds instead of overflowing.
+            #[inline]
+            pub const fn saturating_sub(self, rhs: Self) -> Self {
+                $ident(self.0.saturating_sub(rhs.0))
+            }
+
+            /// Saturating integer multiplication. Computes `self * rhs`,
+            /// saturating at the numeric bounds instead of overflowing.
+            #[inline]
+            pub const fn saturating_mul(self, rhs: Self) -> Self {
+                match self.0.checked_mul(rhs.0) {
+                    Some(value) if value <= Self::MAX.0 => $ident(value),
+                    _ => Self::MAX,
+                }
+            }
         }
 
         impl ExactSize for $ident {
@@ -454,6 +485,9 @@ impl_var_uints! {
 impl_serde!(VarUint24, u32);
 impl_serde!(VarUint56, u64);
 
+impl_display!(VarUint24);
+impl_display!(VarUint56);
+
 #[cfg(feature = "serde")]
 impl serde::Serialize for Tokens {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
@@ -515,6 +549,115 @@ impl<'de> serde::Deserialize<'de> for Tokens {
     }
 }
 
+impl Tokens {
+    /// The number of decimal places of the native currency.
+    pub const DECIMALS: u32 = 9;
+
+    /// Computes `self * mul / div` rounded down, returning `None`
+    /// if `div == 0` or the result doesn't fit into tokens.
+    ///
+    /// The intermediate product is not truncated, so it can exceed 128 bits.
+    pub const fn mul_div_floor(self, mul: u128, div: u128) -> Option<Self> {
+        match mul_div(self.0, mul, div) {
+            Some((value, _)) if value <= Self::MAX.0 => Some(Self(value)),
+            _ => None,
+        }
+    }
+
+    /// Computes `self * mul / div` rounded up, returning `None`
+    /// if `div == 0` or the result doesn't fit into tokens.
+    ///
+    /// The intermediate product is not truncated, so it can exceed 128 bits.
+    pub const fn mul_div_ceil(self, mul: u128, div: u128) -> Option<Self> {
+        match mul_div(self.0, mul, div) {
+            Some((value, has_rem)) if value + (has_rem as u128) <= Self::MAX.0 => {
+                Some(Self(value + has_rem as u128))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Computes `a * b / d` with a 256-bit intermediate product.
+///
+/// Returns the quotient and whether the remainder is non-zero,
+/// or `None` if `d == 0` or the quotient doesn't fit into `u128`.
+const fn mul_div(a: u128, b: u128, d: u128) -> Option<(u128, bool)> {
+    if d == 0 {
+        return None;
+    }
+
+    if let Some(product) = a.checked_mul(b) {
+        return Some((product / d, product % d != 0));
+    }
+
+    // Compute the full 256-bit product as `hi * 2^128 + lo`
+    const HALF: u32 = 64;
+    const LOW_MASK: u128 = u64::MAX as u128;
+
+    let (a_hi, a_lo) = (a >> HALF, a & LOW_MASK);
+    let (b_hi, b_lo) = (b >> HALF, b & LOW_MASK);
+
+    let lo_lo = a_lo * b_lo;
+    let hi_lo = a_hi * b_lo;
+    let lo_hi = a_lo * b_hi;
+    let hi_hi = a_hi * b_hi;
+
+    let mid = (lo_lo >> HALF) + (hi_lo & LOW_MASK) + (lo_hi & LOW_MASK);
+    let lo = (lo_lo & LOW_MASK) | (mid << HALF);
+    let hi = hi_hi + (hi_lo >> HALF) + (lo_hi >> HALF) + (mid >> HALF);
+
+    // The quotient fits into `u128` only if `hi < d`
+    if hi >= d {
+        return None;
+    }
+
+    // Long division of `hi:lo` by `d`
+    let mut rem = hi;
+    let mut quotient = 0u128;
+    let mut i = 128;
+    while i > 0 {
+        i -= 1;
+        let carry = rem >> 127;
+        rem = (rem << 1) | ((lo >> i) & 1);
+        quotient <<= 1;
+        if carry != 0 || rem >= d {
+            rem = rem.wrapping_sub(d);
+            quotient |= 1;
+        }
+    }
+
+    Some((quotient, rem != 0))
+}
+
+/// Formats tokens as a raw integer amount.
+///
+/// Alternate mode (`{:#}`) formats tokens as a decimal number with
+/// [`Tokens::DECIMALS`] decimal places and trailing zeros removed
+/// (e.g. `1.5` for `1500000000`).
+impl std::fmt::Display for Tokens {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if !f.alternate() {
+            return self.0.fmt(f);
+        }
+
+        const DENOM: u128 = 10u128.pow(Tokens::DECIMALS);
+
+        let int = self.0 / DENOM;
+        let mut frac = self.0 % DENOM;
+        if frac == 0 {
+            return f.write_fmt(format_args!("{int}"));
+        }
+
+        let mut width = Tokens::DECIMALS as usize;
+        while frac % 10 == 0 {
+            frac /= 10;
+            width -= 1;
+        }
+        f.write_fmt(format_args!("{int}.{frac:0width$}"))
+    }
+}
+
 impl Store for VarUint24 {
     fn store_into(&self, builder: &mut CellBuilder, _: &mut dyn CellContext) -> Result<(), Error> {
         let bytes = (4 - self.0.leading_zeros() / 8) as u8;
@@ -735,6 +878,10 @@ impl_serde!(Uint9, u16);
 impl_serde!(Uint12, u16);
 impl_serde!(Uint15, u16);
 
+impl_display!(Uint9);
+impl_display!(Uint12);
+impl_display!(Uint15);
+
 /// Account split depth. Fixed-length 5-bit integer of range `1..=30`
 #[derive(Debug, Clone, Copy, Hash, Eq, PartialEq, Ord, PartialOrd)]
 #[repr(transparent)]
@@ -1026,6 +1173,74 @@ mod tests {
         impl_operation_tests!(Tokens, check_max_div);
     }
 
+    #[test]
+    fn tokens_saturating_and_mul_div() {
+        let max = Tokens::MAX;
+        assert_eq!(max.saturating_add(Tokens::ONE), max);
+        assert_eq!(Tokens::new(1).saturating_sub(Tokens::new(2)), Tokens::ZERO);
+        assert_eq!(max.saturating_mul(Tokens::new(2)), max);
+        assert_eq!(
+            Tokens::new(3).saturating_mul(Tokens::new(4)),
+            Tokens::new(12)
+        );
+        assert_eq!(
+            VarUint24::MAX.saturating_add(VarUint24::ONE),
+            VarUint24::MAX
+        );
+
+        let value = Tokens::new(1_000_000_007);
+        assert_eq!(value.mul_div_floor(3, 7), Some(Tokens::new(428_571_431)));
+        assert_eq!(value.mul_div_ceil(3, 7), Some(Tokens::new(428_571_432)));
+        assert_eq!(value.mul_div_ceil(7, 7), Some(value));
+        assert_eq!(value.mul_div_floor(1, 0), None);
+
+        // 256-bit intermediate product
+        let value = Tokens::new(1 << 100);
+        let mul = 3u128 << 100;
+        assert_eq!(
+            value.mul_div_floor(mul, 1 << 101),
+            Some(Tokens::new(3 << 99))
+        );
+        assert_eq!(
+            Tokens::new((1 << 100) + 1).mul_div_ceil(mul, 1 << 101),
+            Some(Tokens::new((3 << 99) + 2))
+        );
+        assert_eq!(
+            Tokens::new((1 << 100) + 1).mul_div_floor(mul, 1 << 101),
+            Some(Tokens::new((3 << 99) + 1))
+        );
+        let value = Tokens::new(0xabcdef0123456789abcdef01234567);
+        let (mul, div) = (
+            0xfedcba9876543210fedcba98765432,
+            0x123456789abcdef0123456789abcdef1,
+        );
+        let expected = 0x96543120fedcbb2606535020fedd3;
+        assert_eq!(value.mul_div_floor(mul, div), Some(Tokens::new(expected)));
+        assert_eq!(
+            value.mul_div_ceil(mul, div),
+            Some(Tokens::new(expected + 1))
+        );
+        assert_eq!(max.mul_div_floor(u128::MAX, u128::MAX), Some(max));
+        assert_eq!(max.mul_div_floor(u128::MAX, u128::MAX - 1), Some(max));
+        assert_eq!(max.mul_div_ceil(u128::MAX, u128::MAX - 1), None);
+        assert_eq!(value.mul_div_floor(mul, 1), None);
+    }
+
+    #[test]
+    fn tokens_display() {
+        for (value, raw, decimal) in [
+            (0, "0", "0"),
+            (1, "1", "0.000000001"),
+            (1_500_000_000, "1500000000", "1.5"),
+            (10_000_000_000, "10000000000", "10"),
+            (123_456_789_012, "123456789012", "123.456789012"),
+        ] {
+            let tokens = Tokens::new(value);
+            assert_eq!(format!("{tokens}"), raw);
+            assert_eq!(format!("{tokens:#}"), decimal);
+        }
+    }
+
     #[test]
     fn var_uint24_serialization() {
         impl_serialization_tests!(VarUint24, 32);
@@ -0,0 +1,247 @@
+//! Reference test vectors.
+//!
+//! Known blocks, states, messages and transactions with their expected
+//! representation hashes. These are the same fixtures which are used
+//! by this crate tests, so other implementations can use them
+//! for conformance checks.
+
+use std::marker::PhantomData;
+
+use crate::boc::Boc;
+use crate::cell::*;
+use crate::error::Error;
+use crate::models::{Block, OwnedMessage, ShardStateUnsplit, Transaction};
+
+/// Serialized reference object with its expected hash.
+pub struct TestVector<T> {
+    /// Fixture name.
+    pub name: &'static str,
+    /// BOC encoded root cell.
+    pub boc: &'static [u8],
+    /// Expected representation hash of the root cell.
+    pub repr_hash: HashBytes,
+    _ty: PhantomData<fn() -> T>,
+}
+
+impl<T> TestVector<T> {
+    /// Decodes the root cell and checks its representation hash.
+    pub fn decode(&self) -> Result<Cell, TestVectorError> {
+        let cell = ok!(Boc::decode(self.boc).map_err(TestVectorError::InvalidBoc));
+        if cell.repr_hash() != &self.repr_hash {
+            return Err(TestVectorError::HashMismatch);
+        }
+        Ok(cell)
+    }
+
+    /// Computes the hash of the BOC bytes.
+    pub fn file_hash(&self) -> HashBytes {
+        Boc::compute_file_hash(self.boc)
+    }
+}
+
+impl<T: for<'a> Load<'a>> TestVector<T> {
+    /// Decodes the root cell, checks its hash and parses the object.
+    pub fn load(&self) -> Result<T, TestVectorError> {
+        let cell = ok!(self.decode());
+        match cell.parse::<T>() {
+            Ok(value) => Ok(value),
+            Err(e) => Err(TestVectorError::InvalidData(e)),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for TestVector<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TestVector")
+            .field("name", &self.name)
+            .field("repr_hash", &self.repr_hash)
+            .finish()
+    }
+}
+
+/// Error type for test vector loading.
+#[derive(Debug, Clone, thiserror::Error)]
+pub enum TestVectorError {
+    /// Failed to decode BOC.
+    #[error("invalid BOC")]
+    InvalidBoc(#[source] crate::boc::de::Error),
+    /// Root cell hash doesn't match the expected one.
+    #[error("root cell hash mismatch")]
+    HashMismatch,
+    /// Failed to parse the object.
+    #[error("invalid data")]
+    InvalidData(#[source] Error),
+}
+
+macro_rules! test_vector {
+    ($name:literal, $path:literal, $hash:literal) => {
+        TestVector {
+            name: $name,
+            boc: include_bytes!($path),
+            repr_hash: HashBytes::from_hex_const($hash),
+            _ty: PhantomData,
+        }
+    };
+}
+
+/// Reference blocks.
+pub static BLOCKS: &[TestVector<Block>] = &[
+    test_vector!(
+        "empty_shard_block",
+        "models/block/tests/empty_shard_block.boc",
+        "86329d5ef45c817b2f1047e2e7c81da61bf8db4ca3f46977391ceb995b6f43f9"
+    ),
+    test_vector!(
+        "simple_shard_block",
+        "models/block/tests/simple_shard_block.boc",
+        "23235afb442f41eb5615ae978901972f6fb916cbf97c5686003d686b0460e9c6"
+    ),
+    test_vector!(
+        "mc_simple_block",
+        "models/block/tests/mc_simple_block.boc",
+        "6cea3d856d004fe6640eaa2d406a83ffec95ce1a10c744253fa1660acbc3072a"
+    ),
+    test_vector!(
+        "mc_block_with_shards",
+        "models/block/tests/mc_block_with_shards.boc",
+        "33b8df6be55259ad6a0535cc560f353f72576d242b296a42a900017b46a61d37"
+    ),
+    test_vector!(
+        "mc_key_block",
+        "models/block/tests/mc_key_block.boc",
+        "eb7d8ea6445300bd8b64f78981149ecc08d6cd6be83fc15e78ed851aa53754b9"
+    ),
+    test_vector!(
+        "first_block",
+        "models/shard/tests/first_block.boc",
+        "169755a1d05f3892b56a8b6d85300e22b1f2ff57cf911de7ad1d91372d7eafa8"
+    ),
+];
+
+/// Reference shard states.
+pub static STATES: &[TestVector<ShardStateUnsplit>] = &[
+    test_vector!(
+        "everscale_zerostate",
+        "models/shard/tests/everscale_zerostate.boc",
+        "58ffca1a178daff705de54216e5433c9bd2e7d850070d334d38997847ab9e845"
+    ),
+    test_vector!(
+        "new_zerostate",
+        "models/shard/tests/new_zerostate.boc",
+        "c8a403b3fcc516d6c4ecb2c17a890a600d98b531d23637504464253c56b0b1d7"
+    ),
+];
+
+/// Reference messages.
+pub static MESSAGES: &[TestVector<OwnedMessage>] = &[
+    test_vector!(
+        "external_message",
+        "models/message/tests/external_message.boc",
+        "c261afa23ccffbb8cdf2fe1be9f8b5e3ad166f1a61f29946acd8b8f770d70608"
+    ),
+    test_vector!(
+        "external_out_message",
+        "models/message/tests/external_out_message.boc",
+        "93b16c46c023c1ae30cf544f9ebe2a72f2b53d34fc9b3d3dee36e92316fb4300"
+    ),
+    test_vector!(
+        "empty_internal_message",
+        "models/message/tests/empty_internal_message.boc",
+        "d17ba50d54a271b88433043c79c0126d4a54c25e1eba68e2d5df09fb7339b476"
+    ),
+    test_vector!(
+        "internal_message_with_body",
+        "models/message/tests/internal_message_with_body.boc",
+        "add5d55da58fd0d7fa2ff2c133a4f751c56e6c4a7f8dd06509ea62e5fc1b8992"
+    ),
+    test_vector!(
+        "internal_message_with_deploy",
+        "models/message/tests/internal_message_with_deploy.boc",
+        "122779600185e79c827dc58ad7c28fceff621ed355019a2123e38a1f1c49d42b"
+    ),
+    test_vector!(
+        "internal_message_with_deploy_special",
+        "models/message/tests/internal_message_with_deploy_special.boc",
+        "5f8538790d1ccc4ff3754ebf2534d49f88aeacef1dc01e3e43c2913ba50cf662"
+    ),
+];
+
+/// Reference transactions.
+pub static TRANSACTIONS: &[TestVector<Transaction>] = &[
+    test_vector!(
+        "ordinary_tx_without_outgoing",
+        "models/transaction/tests/ordinary_tx_without_outgoing.boc",
+        "7f44d69ea8c64c24c91b4116d56cac9329500098b43474e1c6e28ff03bbb552f"
+    ),
+    test_vector!(
+        "ordinary_tx_with_outgoing",
+        "models/transaction/tests/ordinary_tx_with_outgoing.boc",
+        "359eb4aab97eb9b764669630e0a1c6b7f8123ee9fcd3006993f6a99c5557be69"
+    ),
+    test_vector!(
+        "ordinary_tx_with_external",
+        "models/transaction/tests/ordinary_tx_with_external.boc",
+        "79fc044cd4951ed8dbc349574d8367fb65820c11629a2ddf8531eacf0b1a4038"
+    ),
+    test_vector!(
+        "ordinary_tx_recursive",
+        "models/transaction/tests/ordinary_tx_recursive.boc",
+        "ab4fd289802ad25b8ed7d8d649731a9d658b061adfb2708bdb7c1fa3b2f09136"
+    ),
+    test_vector!(
+        "ordinary_tx_bounce_no_funds",
+        "models/transaction/tests/ordinary_tx_bounce_no_funds.boc",
+        "056f26f10b442441290e0df11a6bc73177fb64dcda20cea2fa8ad1d477fb92d7"
+    ),
+    test_vector!(
+        "ordinary_tx_bounce_no_state",
+        "models/transaction/tests/ordinary_tx_bounce_no_state.boc",
+        "bcb1de64a5762061b79a7f86c3ac807cb09d54e8ada3c10093fa1b457fad51c7"
+    ),
+    test_vector!(
+        "tick_tx",
+        "models/transaction/tests/tick_tx.boc",
+        "f46ffa33be8d038e6a558ed1b2ab0dadd029cb8b89f3fe93b242bf95395595aa"
+    ),
+    test_vector!(
+        "tock_tx",
+        "models/transaction/tests/tock_tx.boc",
+        "35dd78a3c52b0db5fc5aefb6a377ccf13894d3290a552c3ae6f30809e3491531"
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_vectors<T>(vectors: &[TestVector<T>])
+    where
+        T: Store + for<'a> Load<'a>,
+    {
+        for vector in vectors {
+            let value = vector.load().unwrap();
+            let cell = CellBuilder::build_from(value).unwrap();
+            assert_eq!(cell.repr_hash(), &vector.repr_hash, "{}", vector.name);
+        }
+    }
+
+    #[test]
+    fn reference_test_vectors() {
+        check_vectors(BLOCKS);
+        check_vectors(STATES);
+        check_vectors(MESSAGES);
+        check_vectors(TRANSACTIONS);
+
+        // Corrupted vector is rejected
+        let vector = TestVector::<Block> {
+            name: "invalid",
+            boc: BLOCKS[0].boc,
+            repr_hash: HashBytes::ZERO,
+            _ty: PhantomData,
+        };
+        assert!(matches!(
+            vector.decode(),
+            Err(TestVectorError::HashMismatch)
+        ));
+    }
+}
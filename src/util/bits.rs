@@ -0,0 +1,358 @@
+//! Bit manipulation helpers for cell data.
+//!
+//! All functions treat byte slices as big-endian bit strings,
+//! i.e. the first bit is the highest bit of the first byte.
+
+/// Writes the highest `bits` bits of `src` into `dst` starting from
+/// the bit `dst_offset`.
+///
+/// Bits of the first affected byte before the offset are preserved,
+/// and bits of the last affected byte after the written ones are zeroed.
+///
+/// # Panics
+///
+/// Panics if `src` contains less than `bits` bits or if `dst` contains
+/// less than `dst_offset + bits` bits.
+pub fn write_bits(dst: &mut [u8], dst_offset: u16, src: &[u8], bits: u16) {
+    assert!(src.len() * 8 >= bits as usize);
+    assert!(dst.len() * 8 >= dst_offset as usize + bits as usize);
+
+    // SAFETY: bounds are checked above
+    unsafe { write_bits_unchecked(dst, dst_offset, src, bits) }
+}
+
+/// Unchecked version of [`write_bits`].
+///
+/// # Safety
+///
+/// `src` must contain at least `bits` bits and `dst` must contain
+/// at least `dst_offset + bits` bits.
+pub(crate) unsafe fn write_bits_unchecked(dst: &mut [u8], dst_offset: u16, src: &[u8], bits: u16) {
+    debug_assert!(src.len() * 8 >= bits as usize);
+    debug_assert!(dst.len() * 8 >= dst_offset as usize + bits as usize);
+
+    if bits == 0 {
+        return;
+    }
+
+    let q = (dst_offset / 8) as usize;
+    let r = dst_offset % 8;
+    // SAFETY: all reads and writes are in bounds, as guaranteed by the caller
+    unsafe {
+        let mut data_ptr = dst.as_mut_ptr().add(q);
+        let mut value_ptr = src.as_ptr();
+
+        if r == 0 {
+            let byte_len = ((bits + 7) / 8) as usize;
+            std::ptr::copy_nonoverlapping(value_ptr, data_ptr, byte_len);
+
+            let bits_r = bits % 8;
+            if bits_r != 0 {
+                *data_ptr.add(byte_len - 1) &= 0xff << (8 - bits_r);
+            }
+        } else {
+            let byte_len = ((bits + r + 7) / 8) as usize - 1;
+            let value_len = ((bits + 7) / 8) as usize;
+
+            // Clear the remaining bits of the first byte
+            *data_ptr &= 0xff << (8 - r);
+
+            let shift = 8 - r;
+            for _ in 0..byte_len {
+                *data_ptr |= *value_ptr >> r;
+                data_ptr = data_ptr.add(1);
+                *data_ptr = *value_ptr << shift;
+                value_ptr = value_ptr.add(1);
+            }
+            if byte_len < value_len {
+                *data_ptr |= *value_ptr >> r;
+            }
+
+            let bits_r = (r + bits) % 8;
+            if bits_r != 0 {
+                *data_ptr &= 0xff << (8 - bits_r);
+            }
+        }
+    }
+}
+
+/// Reads `bits` bits of `src` starting from the bit `src_offset`
+/// into the beginning of `dst`.
+///
+/// Bits of the last affected byte of `dst` after the read ones are zeroed.
+///
+/// # Panics
+///
+/// Panics if `src` contains less than `src_offset + bits` bits or
+/// if `dst` contains less than `bits` bits.
+pub fn read_bits(src: &[u8], src_offset: u16, dst: &mut [u8], bits: u16) {
+    assert!(src.len() * 8 >= src_offset as usize + bits as usize);
+    assert!(dst.len() * 8 >= bits as usize);
+
+    // SAFETY: bounds are checked above
+    unsafe { read_bits_unchecked(src, src_offset, dst, bits) }
+}
+
+/// Unchecked version of [`read_bits`].
+///
+/// # Safety
+///
+/// `src` must contain at least `src_offset + bits` bits and
+/// `dst` must contain at least `bits` bits.
+pub(crate) unsafe fn read_bits_unchecked(src: &[u8], src_offset: u16, dst: &mut [u8], bits: u16) {
+    debug_assert!(src.len() * 8 >= src_offset as usize + bits as usize);
+    debug_assert!(dst.len() * 8 >= bits as usize);
+
+    if bits == 0 {
+        return;
+    }
+
+    let q = (src_offset / 8) as usize;
+    let r = src_offset % 8;
+    let target_len = ((bits + 7) / 8) as usize;
+
+    // SAFETY: all reads and writes are in bounds, as guaranteed by the caller
+    unsafe {
+        let mut data_ptr = src.as_ptr().add(q);
+        let target_ptr = dst.as_mut_ptr();
+
+        if r == 0 {
+            std::ptr::copy_nonoverlapping(data_ptr, target_ptr, target_len);
+        } else {
+            let byte_len = ((bits + r + 7) / 8) as usize - 1;
+
+            let shift = 8 - r;
+            for i in 0..byte_len {
+                let target = target_ptr.add(i);
+                *target = *data_ptr << r;
+                data_ptr = data_ptr.add(1);
+                *target |= *data_ptr >> shift;
+            }
+            if byte_len < target_len {
+                *target_ptr.add(byte_len) = *data_ptr << r;
+            }
+        }
+
+        let bits_r = bits % 8;
+        if bits_r != 0 {
+            *target_ptr.add(target_len - 1) &= 0xff << (8 - bits_r);
+        }
+    }
+}
+
+/// Returns the length of the longest common prefix of two bit strings,
+/// comparing at most `bits` bits starting from the specified offsets.
+///
+/// # Panics
+///
+/// Panics if `a` contains less than `a_offset + bits` bits or
+/// if `b` contains less than `b_offset + bits` bits.
+pub fn common_prefix_len(a: &[u8], a_offset: u16, b: &[u8], b_offset: u16, bits: u16) -> u16 {
+    assert!(a.len() * 8 >= a_offset as usize + bits as usize);
+    assert!(b.len() * 8 >= b_offset as usize + bits as usize);
+
+    // SAFETY: bounds are checked above
+    unsafe { common_prefix_len_unchecked(a, a_offset, b, b_offset, bits) }
+}
+
+/// Unchecked version of [`common_prefix_len`].
+///
+/// # Safety
+///
+/// `a` must contain at least `a_offset + bits` bits and
+/// `b` must contain at least `b_offset + bits` bits.
+pub(crate) unsafe fn common_prefix_len_unchecked(
+    a: &[u8],
+    a_offset: u16,
+    b: &[u8],
+    b_offset: u16,
+    bits: u16,
+) -> u16 {
+    debug_assert!(a.len() * 8 >= a_offset as usize + bits as usize);
+    debug_assert!(b.len() * 8 >= b_offset as usize + bits as usize);
+
+    if bits == 0 {
+        return 0;
+    }
+
+    // Compute shifts and data offsets
+    let a_r = a_offset % 8;
+    let a_q = (a_offset / 8) as usize;
+    let b_r = b_offset % 8;
+    let b_q = (b_offset / 8) as usize;
+
+    // Compute remaining bytes to check
+    let a_bytes = (((a_r + bits) + 7) / 8) as usize;
+    let b_bytes = (((b_r + bits) + 7) / 8) as usize;
+
+    let aligned_bytes = std::cmp::min(a_bytes, b_bytes);
+
+    let mut prefix_len: u16 = 0;
+
+    // SAFETY: all reads are in bounds, as guaranteed by the caller
+    unsafe {
+        let a_ptr = a.as_ptr().add(a_q);
+        let b_ptr = b.as_ptr().add(b_q);
+
+        // Get first bytes aligned to the left
+        let mut a_byte = *a_ptr << a_r;
+        let mut b_byte = *b_ptr << b_r;
+
+        // For all aligned bytes except the first
+        for i in 1..aligned_bytes {
+            // Concat previous bits with current bits
+            // NOTE: shift as `u16` to allow overflow
+            let next_a_byte = *a_ptr.add(i);
+            a_byte |= ((next_a_byte as u16) >> (8 - a_r)) as u8;
+            let next_b_byte = *b_ptr.add(i);
+            b_byte |= ((next_b_byte as u16) >> (8 - b_r)) as u8;
+
+            // XOR bytes to check equality
+            match a_byte ^ b_byte {
+                // All bits are equal, update current bytes and move forward
+                0 => {
+                    prefix_len += 8;
+                    a_byte = next_a_byte << a_r;
+                    b_byte = next_b_byte << b_r;
+                }
+                // Some bits are not equal
+                x => {
+                    // Number of leading zeros is the number of equal bits
+                    return std::cmp::min(prefix_len + x.leading_zeros() as u16, bits);
+                }
+            }
+        }
+
+        // Concat remaining bits
+        if a_r > 0 && aligned_bytes < a_bytes {
+            a_byte |= *a_ptr.add(aligned_bytes) >> (8 - a_r);
+        }
+        if b_r > 0 && aligned_bytes < b_bytes {
+            b_byte |= *b_ptr.add(aligned_bytes) >> (8 - b_r);
+        }
+
+        // Apply last byte mask
+        let last_byte_mask = 0xff << ((8 - bits % 8) % 8);
+        a_byte &= last_byte_mask;
+        b_byte &= last_byte_mask;
+
+        // Count the number of remaining equal bits
+        prefix_len += (a_byte ^ b_byte).leading_zeros() as u16;
+    }
+
+    // Return the longest prefix (without equal bits from the last byte mask)
+    std::cmp::min(prefix_len, bits)
+}
+
+/// Reverses the order of the first `bits` bits of `data`.
+///
+/// Bits of the last affected byte after the reversed ones are zeroed.
+///
+/// # Panics
+///
+/// Panics if `data` contains less than `bits` bits.
+pub fn reverse_bits(data: &mut [u8], bits: u16) {
+    assert!(data.len() * 8 >= bits as usize);
+
+    let byte_len = ((bits + 7) / 8) as usize;
+    let data = &mut data[..byte_len];
+
+    // Reverse the whole bytes and then align the result to the left
+    data.reverse();
+    for byte in data.iter_mut() {
+        *byte = byte.reverse_bits();
+    }
+
+    // Shift left by the number of unused bits
+    let shift = (byte_len * 8 - bits as usize) as u32;
+    if shift != 0 {
+        for i in 0..byte_len {
+            let next = data.get(i + 1).copied().unwrap_or_default();
+            data[i] = (data[i] << shift) | (next >> (8 - shift));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn get_bit(data: &[u8], i: usize) -> bool {
+        data[i / 8] & (0x80 >> (i % 8)) != 0
+    }
+
+    #[test]
+    fn bits_roundtrip() {
+        let src = [0xde, 0xad, 0xbe, 0xef, 0x12, 0x34];
+        for dst_offset in 0..16u16 {
+            for bits in 0..=40u16 {
+                let mut dst = [0xffu8; 8];
+                write_bits(&mut dst, dst_offset, &src, bits);
+
+                // Preceding bits are untouched
+                for i in 0..dst_offset as usize {
+                    assert!(get_bit(&dst, i));
+                }
+                // Data is written
+                for i in 0..bits as usize {
+                    assert_eq!(get_bit(&dst, dst_offset as usize + i), get_bit(&src, i));
+                }
+                // The rest of the last byte is zeroed
+                let end = (dst_offset + bits) as usize;
+                if bits > 0 {
+                    for i in end..(end + 7) / 8 * 8 {
+                        assert!(!get_bit(&dst, i));
+                    }
+                }
+
+                let mut read = [0xffu8; 6];
+                read_bits(&dst, dst_offset, &mut read, bits);
+                for i in 0..bits as usize {
+                    assert_eq!(get_bit(&read, i), get_bit(&src, i));
+                }
+                for i in bits as usize..(bits as usize + 7) / 8 * 8 {
+                    assert!(!get_bit(&read, i));
+                }
+
+                assert_eq!(common_prefix_len(&dst, dst_offset, &src, 0, bits), bits);
+            }
+        }
+    }
+
+    #[test]
+    fn bits_prefix() {
+        let a = [0b1010_1100, 0xff];
+        let b = [0b0101_0110, 0x00];
+        assert_eq!(common_prefix_len(&a, 0, &b, 0, 16), 0);
+        assert_eq!(common_prefix_len(&a, 0, &b, 1, 15), 8);
+        assert_eq!(common_prefix_len(&a, 2, &b, 3, 13), 6);
+        assert_eq!(common_prefix_len(&a, 8, &a, 8, 8), 8);
+        assert_eq!(common_prefix_len(&a, 0, &b, 0, 0), 0);
+    }
+
+    #[test]
+    fn bits_reverse() {
+        let mut data = [0b1100_1010, 0b1000_0000];
+        reverse_bits(&mut data, 9);
+        assert_eq!(data, [0b1010_1001, 0b1000_0000]);
+
+        let mut data = [0x12, 0x34];
+        reverse_bits(&mut data, 16);
+        assert_eq!(data, [0x2c, 0x48]);
+
+        let mut data = [0xf0];
+        reverse_bits(&mut data, 3);
+        assert_eq!(data, [0xe0]);
+
+        let mut data = [0xab];
+        reverse_bits(&mut data, 0);
+        assert_eq!(data, [0xab]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bits_out_of_bounds() {
+        let mut dst = [0u8; 2];
+        write_bits(&mut dst, 9, &[0xff], 8);
+    }
+}
@@ -4,6 +4,8 @@ use std::mem::MaybeUninit;
 
 use crate::error::Error;
 
+pub mod bits;
+
 /// Brings [unlikely](core::intrinsics::unlikely) to stable rust.
 #[inline(always)]
 pub(crate) const fn unlikely(b: bool) -> bool {
@@ -94,6 +96,23 @@ pub(crate) fn decode_base64_slice<T: AsRef<[u8]>>(
     decode_base64_slice_impl(data.as_ref(), target)
 }
 
+/// Computes CRC16 (XMODEM) checksum of the data.
+#[cfg(all(feature = "models", feature = "base64"))]
+pub(crate) fn crc_16(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for &byte in data {
+        crc ^= (byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
 /// Small on-stack vector of max length N.
 pub struct ArrayVec<T, const N: usize> {
     inner: [MaybeUninit<T>; N],
@@ -130,6 +149,21 @@ impl<T, const N: usize> ArrayVec<T, N> {
         self.len += 1;
     }
 
+    /// Shortens the vector, keeping the first `len` elements and dropping the rest.
+    ///
+    /// Does nothing if `len` is greater or equal to the vector’s current length.
+    pub fn truncate(&mut self, len: usize) {
+        while self.len as usize > len {
+            self.len -= 1;
+            // SAFETY: {len} elements were initialized, the item is no longer tracked
+            unsafe {
+                self.inner
+                    .get_unchecked_mut(self.len as usize)
+                    .assume_init_drop()
+            };
+        }
+    }
+
     /// Returns a reference to an element.
     pub const fn get(&self, n: u8) -> Option<&T> {
         if n < self.len {